@@ -1,14 +1,17 @@
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
 use skepa_db_core::Database;
 use skepa_db_core::config::DbConfig;
+use skepa_db_core::error::{DbError, DbErrorKind};
 use skepa_db_core::execution_stats::ExecutionStats;
+use skepa_db_core::parser::command::Command;
 use skepa_db_core::parser::parser::parse;
 use skepa_db_core::query_result::QueryResult;
+use skepa_db_core::settings::OutputFormat;
 use skepa_db_core::storage::Schema;
 use skepa_db_core::types::Row;
-use skepa_db_core::types::value::value_to_string;
+use skepa_db_core::types::value::{FormatOptions, Value, format_value};
 use std::env;
 use std::io::{self, Write};
 use std::path::PathBuf;
@@ -17,6 +20,7 @@ use std::path::PathBuf;
 enum CommandMode {
     Shell,
     Execute { sql: String },
+    Script { path: PathBuf },
 }
 
 #[derive(Debug, Clone)]
@@ -24,6 +28,25 @@ struct CliConfig {
     mode: CommandMode,
     db_path: PathBuf,
     remote_url: Option<String>,
+    json_errors: bool,
+    format: FormatOptions,
+}
+
+/// One statement from a script file and the 1-based line it starts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScriptStatement {
+    line: usize,
+    sql: String,
+}
+
+/// A failed script statement, as written to stderr.
+#[derive(Debug, Serialize)]
+struct ScriptError<'a> {
+    statement: usize,
+    line: usize,
+    kind: DbErrorKind,
+    message: String,
+    sql: &'a str,
 }
 
 #[derive(Debug, Serialize)]
@@ -53,6 +76,7 @@ struct ErrorBody {
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 enum ApiQueryResult {
     Select {
         schema: Schema,
@@ -82,7 +106,7 @@ impl From<ApiQueryResult> for QueryResult {
                 rows,
                 stats,
             } => Self::Select {
-                schema,
+                schema: Box::new(schema),
                 rows,
                 stats,
             },
@@ -103,7 +127,7 @@ impl From<ApiQueryResult> for QueryResult {
     }
 }
 
-fn render_query_result(result: &QueryResult) -> String {
+fn render_query_result(result: &QueryResult, format: &FormatOptions) -> String {
     match result {
         QueryResult::Select { schema, rows, .. } => {
             let header = schema
@@ -121,7 +145,7 @@ fn render_query_result(result: &QueryResult) -> String {
                 .iter()
                 .map(|row| {
                     row.iter()
-                        .map(value_to_string)
+                        .map(|value| format_value(value, format))
                         .collect::<Vec<_>>()
                         .join("\t")
                 })
@@ -140,12 +164,24 @@ fn print_help() {
     println!("Commands:");
     println!("  skepa_db_cli shell [--db-path <path>] [--remote <url>]");
     println!("  skepa_db_cli execute <sql> [--db-path <path>] [--remote <url>]");
+    println!(
+        "  skepa_db_cli --file <script.sql> [--json-errors] [--db-path <path>] [--remote <url>]"
+    );
+    println!("  --decimal-places <n>  -> round decimal output to n places (display only)");
     println!("  parse <cmd>   -> show parsed Command (debug) in shell mode");
+    println!("  .plan <cmd>   -> show resolved columns, bindings and access path (embedded shell)");
+    println!("  .import <table> <file.csv> -> bulk load CSV rows into a table (embedded shell)");
+    println!("  .read <script.sql>         -> run a ';'-separated script");
+    println!("  .complete <partial sql>    -> list completions for the last word (embedded shell)");
     println!("  begin | commit | rollback");
     println!(
-        "  create table <table> (<col> <type> [primary key|unique|not null], ..., [primary key(<col,...>)], [unique(<col,...>)], [foreign key(<col,...>) references <table>(<col,...>) [on delete restrict|cascade|set null|no action] [on update restrict|cascade|set null|no action]])"
+        "  set <output_format|defer_constraints|max_rows|strict_types> = <value> | show <setting>"
     );
-    println!("  alter table <table> add unique(<col,...>)");
+    println!(
+        "  create table <table> (<col> <type> [primary key|unique [nulls not distinct]|not null], ..., [primary key(<col,...>)], [unique(<col,...>) [nulls not distinct]], [foreign key(<col,...>) references <table>(<col,...>) [on delete restrict|cascade|set null|no action] [on update restrict|cascade|set null|no action]])"
+    );
+    println!("  create table <table> as select ...");
+    println!("  alter table <table> add unique(<col,...>) [nulls not distinct]");
     println!("  alter table <table> drop unique(<col,...>)");
     println!(
         "  alter table <table> add foreign key(<col,...>) references <table>(<col,...>) [on delete ...] [on update ...]"
@@ -153,9 +189,11 @@ fn print_help() {
     println!("  alter table <table> drop foreign key(<col,...>) references <table>(<col,...>)");
     println!("  alter table <table> alter column <col> set not null");
     println!("  alter table <table> alter column <col> drop not null");
-    println!("  create index on <table> (<col,...>)");
-    println!("  drop index on <table> (<col,...>)");
+    println!("  alter table <table> add column <col> <type> [first | after <col>]");
+    println!("  create index [<name>] on <table> (<col,...>)");
+    println!("  drop index <name> | drop index on <table> (<col,...>)");
     println!("  insert into <table> values (<v1>, <v2>, ...)");
+    println!("  insert into <table> [(<col,...>)] select ...");
     println!(
         "  update <table> set <col> = <value> [, <col> = <value> ...] where <column> <op> <value>"
     );
@@ -163,7 +201,25 @@ fn print_help() {
     println!(
         "  select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>]"
     );
+    println!("  select ... intersect [all] select ... | select ... except [all] select ...");
+    println!(
+        "  select ... from <table> [<alias>] where [not] exists (select ... from <t> [<a>] where <col> = <alias>.<col> ...)"
+    );
     println!("  describe <table>");
+    println!("  show indexes from|on <table>");
+    println!("  create snapshot <name> | drop snapshot <name> | list snapshots");
+    println!("  select ... from <table> as of <snapshot>");
+    println!("  attach \"<path>\" as <alias> | detach <alias>");
+    println!("  comment on table <table> is <text> | comment on column <table>.<col> is <text>");
+    println!(
+        "  create trigger <name> after insert on <table> begin insert into <table> values (...) end"
+    );
+    println!("  drop trigger <name>");
+    println!("  create [materialized] view <name> as select ...");
+    println!("  refresh materialized view <name>");
+    println!("  drop [materialized] view <name>");
+    println!("  show tables [with stats]");
+    println!("  checkpoint | show wal");
     println!("  where ops: =|eq|!=|neq|>|gt|<|lt|>=|gte|<=|lte|like");
     println!("  like uses '*' and '?' wildcards, e.g. \"ra*\", \"*ir\", \"*av*\", \"r?m\"");
     println!("  exit|quit     -> quit");
@@ -177,6 +233,8 @@ where
     let mut db_path = PathBuf::from("./mydb");
     let mut remote_url = None;
     let mut mode = None;
+    let mut json_errors = false;
+    let mut format = FormatOptions::default();
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -188,6 +246,27 @@ where
                 let value = args.next().context("missing value for --remote")?;
                 remote_url = Some(value);
             }
+            "--json-errors" => json_errors = true,
+            "--decimal-places" => {
+                let value = args.next().context("missing value for --decimal-places")?;
+                let places = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|n| *n <= 28)
+                    .with_context(|| {
+                        format!("--decimal-places expects a number from 0 to 28, got '{value}'")
+                    })?;
+                format.decimal_places = Some(places);
+            }
+            "--file" => {
+                if mode.is_some() {
+                    bail!("command already specified");
+                }
+                let value = args.next().context("missing value for --file")?;
+                mode = Some(CommandMode::Script {
+                    path: PathBuf::from(value),
+                });
+            }
             "shell" => {
                 if mode.is_some() {
                     bail!("command already specified");
@@ -200,7 +279,10 @@ where
                 }
                 let mut sql_parts = Vec::new();
                 while let Some(next) = args.peek() {
-                    if next == "--db-path" || next == "--remote" {
+                    if matches!(
+                        next.as_str(),
+                        "--db-path" | "--remote" | "--json-errors" | "--decimal-places"
+                    ) {
                         break;
                     }
                     sql_parts.push(args.next().expect("peeked argument should exist"));
@@ -235,6 +317,8 @@ where
         mode: mode.unwrap_or(CommandMode::Shell),
         db_path,
         remote_url,
+        json_errors,
+        format,
     })
 }
 
@@ -279,8 +363,9 @@ fn execute_remote(client: &Client, remote_url: &str, sql: &str) -> Result<QueryR
 }
 
 fn run_embedded_shell(config: &CliConfig) -> Result<()> {
-    let mut db = Database::open(DbConfig::new(config.db_path.clone()))
-        .with_context(|| format!("failed to open database at {}", config.db_path.display()))?;
+    let mut db =
+        Database::open(DbConfig::new(config.db_path.clone()).with_annotate_staged(true))
+            .with_context(|| format!("failed to open database at {}", config.db_path.display()))?;
 
     println!("skepa_db_cli (type 'help' or 'exit')");
 
@@ -319,8 +404,42 @@ fn run_embedded_shell(config: &CliConfig) -> Result<()> {
             continue;
         }
 
+        if let Some(rest) = input.strip_prefix(".plan ") {
+            match db.describe_statement(rest) {
+                Ok(plan) => print!("{plan}"),
+                Err(error) => eprintln!("{error}"),
+            }
+            continue;
+        }
+
+        if let Some(rest) = input.strip_prefix(".complete") {
+            for candidate in complete(&db, rest.trim_start()) {
+                println!("{candidate}");
+            }
+            continue;
+        }
+
+        if let Some(rest) = input.strip_prefix(".import ") {
+            match import_csv(&mut db, rest) {
+                Ok(message) => println!("{message}"),
+                Err(error) => eprintln!("{error:#}"),
+            }
+            continue;
+        }
+
+        if let Some(path) = input.strip_prefix(".read ") {
+            match read_script(path.trim(), config, |sql| execute_embedded(&mut db, sql)) {
+                Ok(message) => println!("{message}"),
+                Err(error) => eprintln!("{error:#}"),
+            }
+            continue;
+        }
+
         match execute_embedded(&mut db, input) {
-            Ok(result) => println!("{}", render_query_result(&result)),
+            Ok(result) if db.settings().output_format == OutputFormat::Json => {
+                println!("{}", db.render(&result))
+            }
+            Ok(result) => println!("{}", render_query_result(&result, &config.format)),
             Err(error) => eprintln!("{error}"),
         }
     }
@@ -328,7 +447,7 @@ fn run_embedded_shell(config: &CliConfig) -> Result<()> {
     Ok(())
 }
 
-fn run_remote_shell(_config: &CliConfig, remote_url: &str) -> Result<()> {
+fn run_remote_shell(config: &CliConfig, remote_url: &str) -> Result<()> {
     let client = Client::new();
     println!("skepa_db_cli remote shell ({remote_url}) (type 'help' or 'exit')");
 
@@ -367,8 +486,37 @@ fn run_remote_shell(_config: &CliConfig, remote_url: &str) -> Result<()> {
             continue;
         }
 
+        if input.starts_with(".plan ") {
+            eprintln!(".plan needs the local catalog and is only available in the embedded shell");
+            continue;
+        }
+
+        if input.starts_with(".complete") {
+            eprintln!(
+                ".complete needs the local catalog and is only available in the embedded shell"
+            );
+            continue;
+        }
+
+        if input.starts_with(".import ") {
+            eprintln!(
+                ".import loads through the local database and is only available in the embedded shell"
+            );
+            continue;
+        }
+
+        if let Some(path) = input.strip_prefix(".read ") {
+            match read_script(path.trim(), config, |sql| {
+                execute_remote(&client, remote_url, sql)
+            }) {
+                Ok(message) => println!("{message}"),
+                Err(error) => eprintln!("{error:#}"),
+            }
+            continue;
+        }
+
         match execute_remote(&client, remote_url, input) {
-            Ok(result) => println!("{}", render_query_result(&result)),
+            Ok(result) => println!("{}", render_query_result(&result, &config.format)),
             Err(error) => eprintln!("{error}"),
         }
     }
@@ -379,33 +527,382 @@ fn run_remote_shell(_config: &CliConfig, remote_url: &str) -> Result<()> {
 fn run_embedded_execute(config: &CliConfig, sql: &str) -> Result<()> {
     let mut db = Database::open(DbConfig::new(config.db_path.clone()))
         .with_context(|| format!("failed to open database at {}", config.db_path.display()))?;
+    if matches!(parse(sql), Ok(Command::Select { .. })) {
+        let stdout = io::stdout();
+        let mut out = io::BufWriter::new(stdout.lock());
+        stream_select_rows(&mut db, sql, &config.format, &mut out)?;
+        out.flush()?;
+        return Ok(());
+    }
     let result = execute_embedded(&mut db, sql)?;
-    println!("{}", render_query_result(&result));
+    println!("{}", render_query_result(&result, &config.format));
+    Ok(())
+}
+
+/// Writes a SELECT to `out` in the same layout as `render_query_result`,
+/// one row at a time, so large results are never held in memory.
+fn stream_select_rows(
+    db: &mut Database,
+    sql: &str,
+    format: &FormatOptions,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let header = |schema: &Schema| {
+        schema
+            .columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect::<Vec<_>>()
+            .join("\t")
+    };
+    let mut wrote_header = false;
+    let schema = db.query_stream(sql, &mut |schema, row| {
+        if !wrote_header {
+            writeln!(out, "{}", header(schema)).map_err(|e| e.to_string())?;
+            wrote_header = true;
+        }
+        let line = row
+            .iter()
+            .map(|value| format_value(value, format))
+            .collect::<Vec<_>>()
+            .join("\t");
+        writeln!(out, "{line}").map_err(|e| e.to_string())
+    })?;
+    if !wrote_header {
+        writeln!(out, "{}", header(&schema))?;
+    }
     Ok(())
 }
 
-fn run_remote_execute(remote_url: &str, sql: &str) -> Result<()> {
+fn run_remote_execute(config: &CliConfig, remote_url: &str, sql: &str) -> Result<()> {
     let client = Client::new();
     let result = execute_remote(&client, remote_url, sql)?;
-    println!("{}", render_query_result(&result));
+    println!("{}", render_query_result(&result, &config.format));
     Ok(())
 }
 
+/// Splits a script into statements on `;` outside double quotes. Blank
+/// statements and whole-line `--` comments are dropped.
+fn split_script(text: &str) -> Vec<ScriptStatement> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut start_line = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (idx, line) in text.lines().enumerate() {
+        if !in_quotes && line.trim_start().starts_with("--") {
+            continue;
+        }
+        for ch in line.chars() {
+            if in_quotes {
+                current.push(ch);
+                if escaped {
+                    escaped = false;
+                } else if ch == '\\' {
+                    escaped = true;
+                } else if ch == '"' {
+                    in_quotes = false;
+                }
+                continue;
+            }
+            match ch {
+                ';' => {
+                    let sql = current.trim();
+                    if !sql.is_empty() {
+                        out.push(ScriptStatement {
+                            line: start_line,
+                            sql: sql.to_string(),
+                        });
+                    }
+                    current.clear();
+                }
+                _ => {
+                    if current.trim().is_empty() && !ch.is_whitespace() {
+                        start_line = idx + 1;
+                    }
+                    if ch == '"' {
+                        in_quotes = true;
+                    }
+                    current.push(ch);
+                }
+            }
+        }
+        current.push('\n');
+    }
+
+    let sql = current.trim();
+    if !sql.is_empty() {
+        out.push(ScriptStatement {
+            line: start_line,
+            sql: sql.to_string(),
+        });
+    }
+    out
+}
+
+/// Runs every statement, printing results to stdout and failures to
+/// stderr, and returns how many statements failed.
+fn run_script<F>(
+    statements: &[ScriptStatement],
+    json_errors: bool,
+    format: &FormatOptions,
+    mut execute: F,
+) -> usize
+where
+    F: FnMut(&str) -> Result<QueryResult>,
+{
+    let mut failures = 0;
+    for (idx, stmt) in statements.iter().enumerate() {
+        match execute(&stmt.sql) {
+            Ok(result) => println!("{}", render_query_result(&result, format)),
+            Err(error) => {
+                failures += 1;
+                let kind = match error.downcast_ref::<DbError>() {
+                    Some(db_error) => db_error.kind(),
+                    None => DbError::from(error.to_string()).kind(),
+                };
+                let report = ScriptError {
+                    statement: idx + 1,
+                    line: stmt.line,
+                    kind,
+                    message: error.to_string(),
+                    sql: &stmt.sql,
+                };
+                if json_errors {
+                    eprintln!(
+                        "{}",
+                        serde_json::to_string(&report).expect("script error should serialize")
+                    );
+                } else {
+                    eprintln!(
+                        "error at line {} (statement {}): {}\n  {}",
+                        report.line, report.statement, report.message, report.sql
+                    );
+                }
+            }
+        }
+    }
+    failures
+}
+
+/// Completions for the word at the end of `line`: `table.column` names once
+/// the word has a dot; table names right after `from`, `into`, `update` or
+/// `join`; column names of the tables named so
+/// far (plus keywords) while inside a select list, `where`, `set`, `on`,
+/// `by` or `returning`; keywords everywhere else. Matching ignores case.
+fn complete(db: &Database, line: &str) -> Vec<String> {
+    let is_separator =
+        |c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')' | '=' | '<' | '>');
+    let partial_start = line.rfind(is_separator).map_or(0, |i| i + 1);
+    let partial = &line[partial_start..];
+    let words: Vec<String> = line[..partial_start]
+        .split(is_separator)
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let keywords = db.keyword_list().iter().map(|k| k.to_string());
+    let candidates: Vec<String> = match words.last().map(String::as_str) {
+        _ if partial.contains('.') => db
+            .all_identifiers()
+            .into_iter()
+            .filter(|id| id.contains('.'))
+            .collect(),
+        Some("from" | "into" | "update" | "join") => db.table_names(),
+        _ => {
+            let clause = words
+                .iter()
+                .rev()
+                .find(|w| db.keyword_list().contains(&w.as_str()))
+                .map(String::as_str);
+            let mut out = Vec::new();
+            if matches!(
+                clause,
+                Some("select" | "where" | "set" | "on" | "and" | "or" | "by" | "returning")
+            ) {
+                for table in db.table_names() {
+                    if words.iter().any(|w| w.eq_ignore_ascii_case(&table)) {
+                        out.extend(db.column_names(&table).unwrap_or_default());
+                    }
+                }
+            }
+            out.extend(keywords);
+            out
+        }
+    };
+
+    let prefix = partial.to_lowercase();
+    let mut seen = std::collections::HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|c| c.to_lowercase().starts_with(&prefix) && seen.insert(c.clone()))
+        .collect()
+}
+
+/// Backs the shell's `.read <path>`: runs the script like `--file` does and
+/// summarizes how many statements failed.
+fn read_script<F>(path: &str, config: &CliConfig, execute: F) -> Result<String>
+where
+    F: FnMut(&str) -> Result<QueryResult>,
+{
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("failed to read script {path}"))?;
+    let statements = split_script(&text);
+    let failures = run_script(&statements, config.json_errors, &config.format, execute);
+    Ok(format!(
+        "ran {} statement(s) from {path}, {failures} failed",
+        statements.len()
+    ))
+}
+
+/// Backs the shell's `.import <table> <file.csv>`: loads every CSV record in
+/// one `bulk_load` batch, so a bad row leaves the table unchanged. A first
+/// line naming the table's columns is treated as a header and skipped.
+fn import_csv(db: &mut Database, args: &str) -> Result<String> {
+    let mut parts = args.split_whitespace();
+    let (Some(table), Some(path), None) = (parts.next(), parts.next(), parts.next()) else {
+        bail!("Usage: .import <table> <file.csv>");
+    };
+    let text = std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?;
+    let columns: Vec<String> = db
+        .describe_statement(&format!("describe {table}"))?
+        .columns
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+
+    let mut rows: Vec<Row> = Vec::new();
+    let mut line_numbers: Vec<usize> = Vec::new();
+    let mut first_record = true;
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = parse_csv_record(line).map_err(|e| anyhow!("line {}: {e}", idx + 1))?;
+        let is_header = first_record
+            && fields
+                .iter()
+                .map(|f| f.as_deref().unwrap_or(""))
+                .eq(columns.iter().map(String::as_str));
+        first_record = false;
+        if is_header {
+            continue;
+        }
+        rows.push(
+            fields
+                .into_iter()
+                .map(|f| f.map_or(Value::Null, Value::Text))
+                .collect(),
+        );
+        line_numbers.push(idx + 1);
+    }
+
+    let result = db.bulk_load(table, rows).map_err(|error| {
+        let message = error.to_string();
+        // Per-row errors read "Bulk row <n>: ..."; point at the file line instead.
+        let located = message
+            .strip_prefix("Bulk row ")
+            .and_then(|rest| rest.split_once(": "))
+            .and_then(|(n, detail)| {
+                let line = line_numbers.get(n.parse::<usize>().ok()?.checked_sub(1)?)?;
+                Some(format!("{path} line {line}: {detail}"))
+            });
+        anyhow!(located.unwrap_or(message))
+    })?;
+    Ok(format!(
+        "imported {} row(s) into {table} from {path}",
+        result.rows_affected().unwrap_or(0)
+    ))
+}
+
+/// Splits one CSV line into fields. A field may be double-quoted, with `""`
+/// for a literal quote; an empty unquoted field is `None` and loads as NULL.
+fn parse_csv_record(line: &str) -> Result<Vec<Option<String>>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let field = if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        value.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => return Err("unclosed quoted field".to_string()),
+                }
+            }
+            if let Some(&c) = chars.peek().filter(|&&c| c != ',') {
+                return Err(format!("unexpected '{c}' after a quoted field"));
+            }
+            Some(value)
+        } else {
+            let mut value = String::new();
+            while let Some(c) = chars.next_if(|&c| c != ',') {
+                value.push(c);
+            }
+            (!value.is_empty()).then_some(value)
+        };
+        fields.push(field);
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+fn run_script_file(config: &CliConfig, path: &PathBuf) -> Result<bool> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read script {}", path.display()))?;
+    let statements = split_script(&text);
+    let failures = match &config.remote_url {
+        Some(remote_url) => {
+            let client = Client::new();
+            run_script(&statements, config.json_errors, &config.format, |sql| {
+                execute_remote(&client, remote_url, sql)
+            })
+        }
+        None => {
+            let mut db =
+                Database::open(DbConfig::new(config.db_path.clone())).with_context(|| {
+                    format!("failed to open database at {}", config.db_path.display())
+                })?;
+            run_script(&statements, config.json_errors, &config.format, |sql| {
+                execute_embedded(&mut db, sql)
+            })
+        }
+    };
+    Ok(failures == 0)
+}
+
 fn main() {
-    if let Err(error) = run() {
-        eprintln!("{error}");
-        std::process::exit(1);
+    match run() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
     }
 }
 
-fn run() -> Result<()> {
+/// Returns `Ok(false)` when a script ran to the end but a statement failed.
+fn run() -> Result<bool> {
     let config = parse_cli_config()?;
 
     match (&config.mode, &config.remote_url) {
-        (CommandMode::Shell, Some(remote_url)) => run_remote_shell(&config, remote_url),
-        (CommandMode::Execute { sql }, Some(remote_url)) => run_remote_execute(remote_url, sql),
-        (CommandMode::Shell, None) => run_embedded_shell(&config),
-        (CommandMode::Execute { sql }, None) => run_embedded_execute(&config, sql),
+        (CommandMode::Script { path }, _) => run_script_file(&config, path),
+        (CommandMode::Shell, Some(remote_url)) => {
+            run_remote_shell(&config, remote_url).map(|()| true)
+        }
+        (CommandMode::Execute { sql }, Some(remote_url)) => {
+            run_remote_execute(&config, remote_url, sql).map(|()| true)
+        }
+        (CommandMode::Shell, None) => run_embedded_shell(&config).map(|()| true),
+        (CommandMode::Execute { sql }, None) => run_embedded_execute(&config, sql).map(|()| true),
     }
 }
 
@@ -428,7 +925,7 @@ mod tests {
 
         match config.mode {
             CommandMode::Execute { sql } => assert_eq!(sql, "select * from users"),
-            CommandMode::Shell => panic!("expected execute mode"),
+            _ => panic!("expected execute mode"),
         }
         assert_eq!(config.remote_url.as_deref(), Some("http://127.0.0.1:8080"));
     }
@@ -441,6 +938,227 @@ mod tests {
         assert!(config.remote_url.is_none());
     }
 
+    #[test]
+    fn parse_cli_args_supports_script_mode_with_json_errors() {
+        let config = parse_cli_args([
+            "--file".to_string(),
+            "migration.sql".to_string(),
+            "--json-errors".to_string(),
+        ])
+        .expect("args should parse");
+
+        match config.mode {
+            CommandMode::Script { path } => assert_eq!(path, PathBuf::from("migration.sql")),
+            _ => panic!("expected script mode"),
+        }
+        assert!(config.json_errors);
+    }
+
+    #[test]
+    fn decimal_places_rounds_rendered_decimals_only() {
+        let config = parse_cli_args([
+            "--decimal-places".to_string(),
+            "2".to_string(),
+            "execute".to_string(),
+            "select avg(v) from t".to_string(),
+        ])
+        .expect("args should parse");
+        assert_eq!(config.format.decimal_places, Some(2));
+        assert!(parse_cli_args(["--decimal-places".to_string(), "x".to_string()]).is_err());
+
+        let path =
+            std::env::temp_dir().join(format!("skepa_db_cli_decimals_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let mut db = Database::open(DbConfig::new(path.clone())).expect("db should open");
+        db.execute("create table t (v decimal(10,3))")
+            .expect("create");
+        db.execute("insert into t values (1.005)").expect("insert");
+        db.execute("insert into t values (20)").expect("insert");
+        let result = db.execute("select v from t").expect("select");
+        assert_eq!(
+            render_query_result(&result, &config.format),
+            "v\n1.01\n20.00"
+        );
+        assert_eq!(
+            render_query_result(&result, &FormatOptions::default()),
+            "v\n1.005\n20.000"
+        );
+
+        let _ = std::fs::remove_dir_all(&path);
+    }
+
+    #[test]
+    fn stream_select_rows_matches_rendered_result() {
+        let path = std::env::temp_dir().join(format!("skepa_db_cli_stream_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let mut db = Database::open(DbConfig::new(path.clone())).expect("db should open");
+        db.execute("create table t (id int, name text)")
+            .expect("create");
+        db.execute("insert into t values (1, \"a\")")
+            .expect("insert");
+        db.execute("insert into t values (2, \"b\")")
+            .expect("insert");
+
+        for sql in ["select * from t", "select name from t where id = 5"] {
+            let result = db.execute(sql).expect("select");
+            let mut out = Vec::new();
+            stream_select_rows(&mut db, sql, &FormatOptions::default(), &mut out).expect("stream");
+            assert_eq!(
+                String::from_utf8(out).expect("utf8"),
+                format!(
+                    "{}\n",
+                    render_query_result(&result, &FormatOptions::default())
+                )
+            );
+        }
+
+        let _ = std::fs::remove_dir_all(&path);
+    }
+
+    #[test]
+    fn split_script_tracks_start_lines_and_ignores_quoted_semicolons() {
+        let script = "-- setup\ncreate table t (id int, s text);\n\ninsert into t\n  values (1, \"a;b\");\ninsert into t values (2, \"say \\\"hi\\\";\")";
+        let statements = split_script(script);
+        assert_eq!(
+            statements,
+            vec![
+                ScriptStatement {
+                    line: 2,
+                    sql: "create table t (id int, s text)".to_string(),
+                },
+                ScriptStatement {
+                    line: 4,
+                    sql: "insert into t\n  values (1, \"a;b\")".to_string(),
+                },
+                ScriptStatement {
+                    line: 6,
+                    sql: "insert into t values (2, \"say \\\"hi\\\";\")".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn run_script_continues_past_failures_and_counts_them() {
+        let path = std::env::temp_dir().join(format!("skepa_db_cli_script_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let mut db = Database::open(DbConfig::new(path.clone())).expect("db should open");
+
+        let statements = split_script(
+            "create table t (id int primary key);\ninsert into t values (1);\ninsert into t values (1);\nselect * from nope;\ninsert into t values (2);",
+        );
+        let failures = run_script(&statements, true, &FormatOptions::default(), |sql| {
+            execute_embedded(&mut db, sql)
+        });
+        assert_eq!(failures, 2);
+        let result = db.execute("select * from t").expect("select should run");
+        match result {
+            QueryResult::Select { rows, .. } => assert_eq!(rows.len(), 2),
+            _ => panic!("expected select result"),
+        }
+
+        let _ = std::fs::remove_dir_all(&path);
+    }
+
+    #[test]
+    fn complete_offers_keywords_tables_and_columns_by_position() {
+        let path =
+            std::env::temp_dir().join(format!("skepa_db_cli_complete_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let mut db = Database::open(DbConfig::new(path.clone())).expect("db should open");
+        db.execute("create table users (id int, name text)")
+            .expect("create should run");
+        db.execute("create table orders (id int, user_id int)")
+            .expect("create should run");
+
+        assert_eq!(complete(&db, "sel"), vec!["select"]);
+        assert_eq!(complete(&db, "select * from u"), vec!["users"]);
+        assert_eq!(complete(&db, "insert into "), vec!["orders", "users"]);
+        assert_eq!(complete(&db, "select * from users where na"), vec!["name"]);
+        assert_eq!(
+            complete(&db, "select * from users join orders on users.id = u"),
+            vec!["user_id", "unique", "update", "using", "uuid"]
+        );
+        assert_eq!(complete(&db, "update users set id = 1 wh"), vec!["where"]);
+        assert_eq!(
+            complete(&db, "select users.id, o"),
+            vec!["of", "offset", "on", "or", "order"]
+        );
+        assert_eq!(
+            complete(&db, "select * from users join orders on orders.u"),
+            vec!["orders.user_id"]
+        );
+
+        let _ = std::fs::remove_dir_all(&path);
+    }
+
+    #[test]
+    fn parse_csv_record_handles_quotes_and_empty_fields() {
+        assert_eq!(
+            parse_csv_record(r#"1,"ram, ""jr""",,"""#).unwrap(),
+            vec![
+                Some("1".to_string()),
+                Some("ram, \"jr\"".to_string()),
+                None,
+                Some(String::new()),
+            ]
+        );
+        assert!(parse_csv_record(r#""open,1"#).is_err());
+        assert!(parse_csv_record(r#""a"b,1"#).is_err());
+    }
+
+    #[test]
+    fn import_csv_skips_header_and_reports_failing_line() {
+        let dir = std::env::temp_dir().join(format!("skepa_db_cli_import_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut db = Database::open(DbConfig::new(dir.join("db"))).expect("db should open");
+        db.execute("create table users (id int, name text)")
+            .expect("create should run");
+
+        let good = dir.join("good.csv");
+        std::fs::write(&good, "id,name\n1,ram\n\n2,\n").expect("csv should write");
+        let message = import_csv(&mut db, &format!("users {}", good.display())).unwrap();
+        assert!(
+            message.starts_with("imported 2 row(s) into users"),
+            "{message}"
+        );
+
+        let bad = dir.join("bad.csv");
+        std::fs::write(&bad, "3,sita\nfour,hari\n").expect("csv should write");
+        let error = import_csv(&mut db, &format!("users {}", bad.display())).unwrap_err();
+        assert!(error.to_string().contains("line 2:"), "{error}");
+
+        match db
+            .execute("select * from users")
+            .expect("select should run")
+        {
+            QueryResult::Select { rows, .. } => {
+                assert_eq!(rows.len(), 2);
+                assert_eq!(rows[1][1], Value::Null);
+            }
+            _ => panic!("expected select result"),
+        }
+        assert!(import_csv(&mut db, "users").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn script_error_serializes_kind_and_position() {
+        let report = ScriptError {
+            statement: 3,
+            line: 7,
+            kind: DbError::from("PRIMARY KEY constraint violation on column(s) id").kind(),
+            message: "PRIMARY KEY constraint violation on column(s) id".to_string(),
+            sql: "insert into t values (1)",
+        };
+        let json = serde_json::to_value(&report).expect("should serialize");
+        assert_eq!(json["statement"], 3);
+        assert_eq!(json["line"], 7);
+        assert_eq!(json["kind"], "unique_violation");
+        assert_eq!(json["sql"], "insert into t values (1)");
+    }
+
     fn spawn_test_server(
         response_body: String,
         status_line: &str,
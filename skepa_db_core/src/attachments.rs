@@ -0,0 +1,125 @@
+use super::*;
+
+impl Database {
+    /// Makes the database directory at `path` readable from this handle as
+    /// `<alias>.<table>`. Attached tables can be selected, joined with local
+    /// ones, described and listed with `show indexes`; every write to them
+    /// is rejected. Each statement rereads the attached tables from disk.
+    pub fn attach(&mut self, path: impl Into<PathBuf>, alias: &str) -> DbResult<()> {
+        let valid =
+            !alias.is_empty() && alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(DbError::from(format!(
+                "Invalid attachment alias '{alias}': use letters, digits and '_'"
+            )));
+        }
+        if self.attachments.contains_key(alias) {
+            return Err(DbError::from(format!(
+                "Database '{alias}' is already attached"
+            )));
+        }
+        let path = path.into();
+        if !path.join("catalog.json").is_file() {
+            return Err(DbError::from(format!(
+                "No database found at '{}'",
+                path.display()
+            )));
+        }
+        // Surfaces a malformed catalog now rather than on first use.
+        Self::load_catalog(&path)?;
+        self.attachments.insert(alias.to_string(), path);
+        Ok(())
+    }
+
+    /// Forgets an attachment made by [`Database::attach`].
+    pub fn detach(&mut self, alias: &str) -> DbResult<()> {
+        self.attachments
+            .remove(alias)
+            .map(|_| ())
+            .ok_or_else(|| DbError::from(format!("Database '{alias}' is not attached")))
+    }
+
+    /// Attached aliases with their directories, sorted by alias.
+    pub fn attached_databases(&self) -> Vec<(String, PathBuf)> {
+        self.attachments
+            .iter()
+            .map(|(alias, path)| (alias.clone(), path.clone()))
+            .collect()
+    }
+
+    /// Splits `<alias>.<table>` when `alias` is attached.
+    pub(super) fn split_attached<'a>(&self, name: &'a str) -> Option<(&'a str, &'a str)> {
+        let (alias, table) = name.split_once('.')?;
+        self.attachments
+            .contains_key(alias)
+            .then_some((alias, table))
+    }
+
+    /// Whether `cmd` names a table of an attached database.
+    pub(super) fn names_attached_table(&self, cmd: &Command) -> bool {
+        restricted::referenced_tables(cmd)
+            .iter()
+            .any(|t| self.split_attached(t).is_some())
+    }
+
+    /// Runs a statement naming an attached table against
+    /// [`Database::attached_catalog`] and a storage holding only the tables
+    /// it names. SELECT (without `for update`), INTERSECT/EXCEPT, DESCRIBE
+    /// and SHOW INDEXES are allowed; anything else is a write and fails.
+    pub(super) fn execute_attached(&self, cmd: Command) -> Result<QueryResult, String> {
+        let tables: Vec<String> = restricted::referenced_tables(&cmd)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let read = match &cmd {
+            Command::Select { for_update, .. } => !for_update,
+            Command::SetOp { .. } | Command::Describe { .. } | Command::ShowIndexes { .. } => true,
+            _ => false,
+        };
+        if !read {
+            let alias = tables
+                .iter()
+                .find_map(|t| self.split_attached(t))
+                .map_or("", |(alias, _)| alias);
+            return Err(format!("Attached database '{alias}' is read-only"));
+        }
+        let catalog = self.attached_catalog(&tables)?;
+        let mut storage = DiskStorage::new(self.path.clone())?;
+        for name in &tables {
+            match self.split_attached(name) {
+                Some((alias, table)) => {
+                    let mut other = DiskStorage::new(self.attachments[alias].clone())?;
+                    other.bootstrap_table(table, catalog.schema(name)?)?;
+                    storage.restore_table(name, other.snapshot_table(table));
+                }
+                None if self.catalog.exists(name) => {
+                    storage.restore_table(name, self.storage.snapshot_table(name));
+                }
+                None => {}
+            }
+        }
+        engine::execute_read(
+            self.read_command(cmd, &catalog),
+            &catalog,
+            &storage,
+            self.max_join_rows,
+        )
+    }
+
+    /// The live catalog plus, under their `<alias>.<table>` names, the
+    /// attached tables among `tables`, read from the attached catalogs.
+    pub(super) fn attached_catalog(&self, tables: &[String]) -> Result<Catalog, String> {
+        let mut catalog = self.catalog.clone();
+        for name in tables {
+            let Some((alias, table)) = self.split_attached(name) else {
+                continue;
+            };
+            let other = Self::load_catalog(&self.attachments[alias]).map_err(|e| e.to_string())?;
+            let schema = other
+                .schema(table)
+                .map_err(|_| format!("Table '{name}' does not exist"))?;
+            catalog.insert_schema(name.clone(), schema.clone());
+        }
+        Ok(catalog)
+    }
+}
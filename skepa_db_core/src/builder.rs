@@ -0,0 +1,178 @@
+use crate::Database;
+use crate::bulk::sql_literal;
+use crate::error::DbResult;
+use crate::parser::command::{ColumnDef, Command, ForeignKeyAction, TableConstraintDef};
+use crate::query_result::QueryResult;
+use crate::types::datatype::DataType;
+use crate::types::value::{Value, value_to_string};
+
+impl Database {
+    /// Starts a `create table` definition for `table`; nothing happens
+    /// until [`TableBuilder::execute`].
+    pub fn create_table(&mut self, table: impl Into<String>) -> TableBuilder<'_> {
+        TableBuilder {
+            db: self,
+            table: table.into(),
+            columns: Vec::new(),
+            table_constraints: Vec::new(),
+            if_not_exists: false,
+            partition_by: None,
+        }
+    }
+
+    /// Starts an insert into `table`; [`InsertBuilder::values`] runs it.
+    pub fn insert(&mut self, table: impl Into<String>) -> InsertBuilder<'_> {
+        InsertBuilder {
+            db: self,
+            table: table.into(),
+        }
+    }
+}
+
+/// A table definition built column by column. Runs through the same
+/// validation, catalog update and persistence as `create table`.
+#[must_use = "a table builder does nothing until `execute` is called"]
+pub struct TableBuilder<'db> {
+    db: &'db mut Database,
+    table: String,
+    columns: Vec<ColumnDef>,
+    table_constraints: Vec<TableConstraintDef>,
+    if_not_exists: bool,
+    partition_by: Option<String>,
+}
+
+impl TableBuilder<'_> {
+    /// Adds a nullable column with no DEFAULT.
+    pub fn column(mut self, name: impl Into<String>, dtype: DataType) -> Self {
+        self.columns.push(ColumnDef {
+            name: name.into(),
+            dtype,
+            primary_key: false,
+            unique: false,
+            not_null: false,
+            default: None,
+            json_shape: None,
+        });
+        self
+    }
+
+    /// Marks the most recently added column NOT NULL.
+    pub fn not_null(mut self) -> Self {
+        if let Some(col) = self.columns.last_mut() {
+            col.not_null = true;
+        }
+        self
+    }
+
+    /// Gives the most recently added column a DEFAULT, converted as the
+    /// same literal would be in `default <value>`.
+    pub fn default(mut self, value: Value) -> Self {
+        if let Some(col) = self.columns.last_mut() {
+            col.default = Some(value_token(&value));
+        }
+        self
+    }
+
+    /// `primary key(<cols>)`.
+    pub fn primary_key<S: Into<String>>(mut self, cols: impl IntoIterator<Item = S>) -> Self {
+        self.table_constraints
+            .push(TableConstraintDef::PrimaryKey(names(cols)));
+        self
+    }
+
+    /// `unique(<cols>)`.
+    pub fn unique<S: Into<String>>(mut self, cols: impl IntoIterator<Item = S>) -> Self {
+        self.table_constraints.push(TableConstraintDef::Unique {
+            columns: names(cols),
+            nulls_not_distinct: false,
+        });
+        self
+    }
+
+    /// `foreign key(<cols>) references <ref_table>(<ref_cols>) on delete
+    /// <on_delete>`. ON UPDATE is RESTRICT, as when it is left out in SQL.
+    pub fn foreign_key<S: Into<String>, R: Into<String>>(
+        mut self,
+        cols: impl IntoIterator<Item = S>,
+        ref_table: impl Into<String>,
+        ref_cols: impl IntoIterator<Item = R>,
+        on_delete: ForeignKeyAction,
+    ) -> Self {
+        self.table_constraints.push(TableConstraintDef::ForeignKey {
+            columns: names(cols),
+            ref_table: ref_table.into(),
+            ref_columns: names(ref_cols),
+            on_delete,
+            on_update: ForeignKeyAction::Restrict,
+        });
+        self
+    }
+
+    /// `create table if not exists`.
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_not_exists = true;
+        self
+    }
+
+    /// `partition by (<col>)`.
+    pub fn partition_by(mut self, column: impl Into<String>) -> Self {
+        self.partition_by = Some(column.into());
+        self
+    }
+
+    /// Creates the table. Fails, leaving nothing behind, for the same
+    /// reasons the equivalent `create table` statement would.
+    pub fn execute(self) -> DbResult<QueryResult> {
+        let cmd = Command::Create {
+            table: self.table,
+            columns: self.columns,
+            table_constraints: self.table_constraints,
+            if_not_exists: self.if_not_exists,
+            partition_by: self.partition_by,
+        };
+        // Schema changes are persisted from the catalog; the source text is
+        // only ever logged for row writes.
+        self.db.execute_parsed("", cmd)
+    }
+}
+
+/// A single-row insert whose values are bound as [`Value`]s.
+#[must_use = "an insert builder does nothing until `values` is called"]
+pub struct InsertBuilder<'db> {
+    db: &'db mut Database,
+    table: String,
+}
+
+impl InsertBuilder<'_> {
+    /// Inserts one row. Values are converted to the column types as insert
+    /// literals are, and trailing columns left out take their DEFAULT. The
+    /// row is logged as the equivalent `insert` statement, so recovery and
+    /// transaction commit replay it exactly.
+    pub fn values(self, values: impl IntoIterator<Item = Value>) -> DbResult<QueryResult> {
+        let values: Vec<Value> = values.into_iter().collect();
+        let literals: Vec<String> = values.iter().map(sql_literal).collect();
+        let input = format!(
+            "insert into `{}` values ({})",
+            self.table,
+            literals.join(", ")
+        );
+        let cmd = Command::Insert {
+            table: self.table,
+            values: values.iter().map(value_token).collect(),
+            returning: None,
+        };
+        self.db.execute_parsed(&input, cmd)
+    }
+}
+
+fn names<S: Into<String>>(cols: impl IntoIterator<Item = S>) -> Vec<String> {
+    cols.into_iter().map(Into::into).collect()
+}
+
+/// The token the parser produces for `v` written as a literal.
+fn value_token(v: &Value) -> String {
+    match v {
+        Value::Null => "null".to_string(),
+        other => value_to_string(other),
+    }
+}
@@ -0,0 +1,113 @@
+use super::*;
+
+impl Database {
+    /// Appends `rows` to `table` as one batch.
+    ///
+    /// Values are converted as INSERT literals would be and every constraint
+    /// is checked across the whole batch before anything is stored; if any
+    /// row fails, the table is left unchanged. Indexes are rebuilt once, the
+    /// batch is logged as a single WAL group and the table is persisted once.
+    /// Rows must supply every column; DEFAULTs are not applied. Tables with
+    /// triggers are refused, since their triggers would not run, and so are
+    /// views.
+    pub fn bulk_load(
+        &mut self,
+        table: &str,
+        rows: impl IntoIterator<Item = Row>,
+    ) -> DbResult<QueryResult> {
+        if self.current_tx.is_some() {
+            return Err(DbError::from(
+                "bulk_load cannot run inside an active transaction".to_string(),
+            ));
+        }
+        let write_lock = self.write_guard().map_err(DbError::from)?;
+        self.refresh_if_stale(write_lock.is_some())
+            .map_err(DbError::from)?;
+        if self.catalog.view(table).is_some() {
+            return Err(DbError::from(format!("Cannot modify view '{table}'")));
+        }
+        if self
+            .catalog
+            .schema(table)
+            .is_ok_and(|schema| !schema.triggers.is_empty())
+        {
+            return Err(DbError::from(format!(
+                "bulk_load cannot load table '{table}': it has triggers, use INSERT instead"
+            )));
+        }
+        let prepared = engine::prepare_bulk_rows(
+            table,
+            rows.into_iter().collect(),
+            &self.catalog,
+            &self.storage,
+        )
+        .map_err(DbError::from)?;
+        if let Some(max) = self.max_json_bytes {
+            let schema = self.catalog.schema(table).map_err(DbError::from)?;
+            for row in &prepared {
+                Self::check_bulk_json_size(schema, row, max)?;
+            }
+        }
+
+        let count = prepared.len();
+        let txid = self.alloc_txid();
+        let mut wal: Vec<String> = Vec::with_capacity(count + 2);
+        wal.push(format!("BEGIN {txid}"));
+        for row in &prepared {
+            let literals: Vec<String> = row.iter().map(sql_literal).collect();
+            wal.push(crate::recovery::wal_op_line(
+                txid,
+                &format!("insert into {table} values ({})", literals.join(", ")),
+            ));
+        }
+        wal.push(format!("COMMIT {txid}"));
+
+        let base = self.change_base(table);
+        let schema = self.catalog.schema(table).map_err(DbError::from)?;
+        for row in prepared {
+            self.storage.insert_row(table, row).map_err(DbError::from)?;
+        }
+        self.storage
+            .rebuild_indexes(table, schema)
+            .map_err(DbError::from)?;
+
+        self.append_wal_group(&wal).map_err(DbError::from)?;
+        self.dirty_tables.insert(table.to_string());
+        self.checkpoint_after_commit().map_err(DbError::from)?;
+        self.mark_disk_seen();
+        drop(write_lock);
+        let events = self.changes_since(base.iter().map(|(t, s)| (t, s)));
+        self.fire_changes(&events);
+
+        Ok(QueryResult::mutation(
+            format!("inserted {count} row(s) into {table}"),
+            count,
+        ))
+    }
+
+    pub(super) fn check_bulk_json_size(schema: &Schema, row: &Row, max: usize) -> DbResult<()> {
+        for (col, value) in schema.columns.iter().zip(row) {
+            if let types::value::Value::Json(j) = value {
+                let len = j.to_string().len();
+                if len > max {
+                    return Err(DbError::from(format!(
+                        "JSON value for column '{}' is {len} bytes, exceeding the {max}-byte limit",
+                        col.name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a stored value as a quoted INSERT token for WAL replay.
+pub(crate) fn sql_literal(v: &types::value::Value) -> String {
+    if matches!(v, types::value::Value::Null) {
+        return "null".to_string();
+    }
+    let text = types::value::value_to_string(v)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    format!("\"{text}\"")
+}
@@ -0,0 +1,28 @@
+use crate::database_view::DatabaseView;
+use crate::types::{Row, RowId};
+
+/// Kind of row change reported by [`ChangeEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// One committed row change, passed to callbacks registered with
+/// `Database::on_change`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub table: String,
+    pub op: ChangeOp,
+    /// The changed row's id, as yielded by `Database::scan_table`.
+    pub row_id: RowId,
+    /// The row before the change; `None` for inserts.
+    pub old: Option<Row>,
+    /// The row after the change; `None` for deletes.
+    pub new: Option<Row>,
+}
+
+/// A callback passed to `Database::on_change`. The [`DatabaseView`] reads
+/// the database as it stands after the change.
+pub type ChangeHook = Box<dyn FnMut(&ChangeEvent, &DatabaseView<'_>) + Send>;
@@ -0,0 +1,93 @@
+use super::*;
+use change_event::{ChangeEvent, ChangeHook};
+use database_view::DatabaseView;
+use types::RowId;
+
+/// Callbacks registered with [`Database::on_change`].
+#[derive(Default)]
+pub(crate) struct ChangeHooks(Vec<ChangeHook>);
+
+impl std::fmt::Debug for ChangeHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ChangeHooks({} registered)", self.0.len())
+    }
+}
+
+impl Database {
+    /// Registers `hook` to run once per row changed by each committed
+    /// INSERT, UPDATE or DELETE, including rows changed by foreign-key
+    /// actions and by [`Database::bulk_load`]. Auto-commit statements report
+    /// their rows after the WAL write; transactions report all of theirs at
+    /// `commit`, and rolled back work reports nothing. Changes made by other
+    /// handles on the same directory are not reported. With no hook
+    /// registered nothing is tracked. Each hook also gets a [`DatabaseView`]
+    /// for running SELECTs over the data as committed.
+    pub fn on_change(&mut self, hook: ChangeHook) {
+        self.change_hooks.0.push(hook);
+    }
+
+    /// Every row of `table` with its [`RowId`], in storage order, as this
+    /// handle currently sees it (including writes of its open transaction).
+    /// The ids are the ones [`ChangeEvent::row_id`] reports, so an external
+    /// index can be built from this scan and then kept current from
+    /// [`Database::on_change`]. Rows the open transaction inserted may get
+    /// other ids at commit; see [`RowId`].
+    pub fn scan_table(&self, table: &str) -> DbResult<impl Iterator<Item = (RowId, &Row)>> {
+        self.catalog.schema(table).map_err(DbError::from)?;
+        let rows = self.storage.scan(table).map_err(DbError::from)?;
+        Ok(self.storage.row_ids(table).iter().copied().zip(rows))
+    }
+
+    pub(super) fn has_change_hooks(&self) -> bool {
+        !self.change_hooks.0.is_empty()
+    }
+
+    /// Copies of `table` and every table a foreign-key action or trigger on
+    /// it can reach, to diff against after the statement runs. Empty when no hook
+    /// is registered.
+    pub(super) fn change_base(&self, table: &str) -> Vec<(String, storage::disk::TableSnapshot)> {
+        if !self.has_change_hooks() {
+            return Vec::new();
+        }
+        self.tables_written_by(table)
+            .into_iter()
+            .map(|name| {
+                let snapshot = self.storage.snapshot_table(&name);
+                (name, snapshot)
+            })
+            .collect()
+    }
+
+    /// Row changes from each `(table, snapshot)` to the table now.
+    pub(super) fn changes_since<'a>(
+        &self,
+        bases: impl IntoIterator<Item = (&'a String, &'a storage::disk::TableSnapshot)>,
+    ) -> Vec<ChangeEvent> {
+        let mut bases: Vec<_> = bases.into_iter().collect();
+        bases.sort_by_key(|(table, _)| *table);
+        bases
+            .into_iter()
+            .flat_map(|(table, base)| self.storage.changes_since(table, base))
+            .collect()
+    }
+
+    /// Runs the hooks for `events`. Called once the statement or commit has
+    /// finished writing; the hooks get a read-only view, so a SELECT they run
+    /// sees the reported changes and cannot start another write.
+    pub(super) fn fire_changes(&mut self, events: &[ChangeEvent]) {
+        if events.is_empty() {
+            return;
+        }
+        let view = DatabaseView::new(
+            &self.catalog,
+            &self.storage,
+            self.max_join_rows,
+            self.settings.strict_types,
+        );
+        for event in events {
+            for hook in &mut self.change_hooks.0 {
+                hook(event, &view);
+            }
+        }
+    }
+}
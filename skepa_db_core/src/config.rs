@@ -1,12 +1,140 @@
 use std::path::PathBuf;
 
+/// How commit decides that another handle's writes conflict with a
+/// transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictDetection {
+    /// Any change to a table the transaction wrote aborts the commit.
+    #[default]
+    Table,
+    /// Only changes to the rows the transaction updated, deleted or locked
+    /// with `for update` abort it; its writes are replayed by row id on top
+    /// of whatever else was committed.
+    Row,
+}
+
+/// When a committed write rewrites the table files and truncates the WAL.
+/// Until then the commit is durable in the WAL alone and replayed on open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoCheckpoint {
+    /// After every commit.
+    #[default]
+    EveryCommit,
+    /// After a commit that leaves the WAL at least this many bytes long.
+    WalBytes(u64),
+    /// Only on `checkpoint`, schema changes and the next open.
+    Manual,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DbConfig {
     pub path: PathBuf,
+    /// Seed for `gen_uuid()`. `None` uses the OS random source.
+    pub uuid_seed: Option<u64>,
+    /// Largest accepted json value, measured as stored text. `None` is unlimited.
+    pub max_json_bytes: Option<usize>,
+    /// Most rows a JOIN may produce before the query fails. `None` is unlimited.
+    pub max_join_rows: Option<usize>,
+    /// Prefix INSERT/UPDATE/DELETE messages with `staged: ` inside a transaction.
+    pub annotate_staged: bool,
+    /// Keep the WAL file open for the life of the handle instead of opening
+    /// it for every write. Assumes no other handle writes the same directory.
+    pub keep_wal_open: bool,
+    /// Table names `create table` rejects. `None` uses
+    /// [`crate::storage::catalog::DEFAULT_RESERVED_TABLE_NAMES`].
+    pub reserved_table_names: Option<Vec<String>>,
+    /// Recreate a table's missing `.rows` file as empty on open instead of
+    /// failing. The table's rows are lost; off by default.
+    pub recreate_missing_table_files: bool,
+    /// Granularity of the commit-time conflict check; table-level by default.
+    pub conflict_detection: ConflictDetection,
+    /// Most INSERT/UPDATE/DELETE statements one transaction may stage.
+    /// `None` is unlimited.
+    pub tx_max_staged_statements: Option<usize>,
+    /// Most bytes of statement text one transaction may stage. `None` is
+    /// unlimited.
+    pub tx_max_staged_bytes: Option<usize>,
+    /// When commits checkpoint; after every one by default. Anything else
+    /// needs the directory to itself: opening fails while another handle
+    /// has it open, and other handles fail to open until this one is
+    /// dropped.
+    pub auto_checkpoint: AutoCheckpoint,
 }
 
 impl DbConfig {
     pub fn new(path: impl Into<PathBuf>) -> Self {
-        Self { path: path.into() }
+        Self {
+            path: path.into(),
+            uuid_seed: None,
+            max_json_bytes: None,
+            max_join_rows: None,
+            annotate_staged: false,
+            keep_wal_open: false,
+            reserved_table_names: None,
+            recreate_missing_table_files: false,
+            conflict_detection: ConflictDetection::Table,
+            tx_max_staged_statements: None,
+            tx_max_staged_bytes: None,
+            auto_checkpoint: AutoCheckpoint::EveryCommit,
+        }
+    }
+
+    pub fn with_uuid_seed(mut self, seed: u64) -> Self {
+        self.uuid_seed = Some(seed);
+        self
+    }
+
+    pub fn with_max_json_bytes(mut self, max: usize) -> Self {
+        self.max_json_bytes = Some(max);
+        self
+    }
+
+    pub fn with_max_join_rows(mut self, max: usize) -> Self {
+        self.max_join_rows = Some(max);
+        self
+    }
+
+    pub fn with_annotate_staged(mut self, annotate: bool) -> Self {
+        self.annotate_staged = annotate;
+        self
+    }
+
+    pub fn with_keep_wal_open(mut self, keep: bool) -> Self {
+        self.keep_wal_open = keep;
+        self
+    }
+
+    pub fn with_reserved_table_names<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.reserved_table_names = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_recreate_missing_table_files(mut self, recreate: bool) -> Self {
+        self.recreate_missing_table_files = recreate;
+        self
+    }
+
+    pub fn with_conflict_detection(mut self, conflict: ConflictDetection) -> Self {
+        self.conflict_detection = conflict;
+        self
+    }
+
+    pub fn with_tx_max_staged_statements(mut self, max: usize) -> Self {
+        self.tx_max_staged_statements = Some(max);
+        self
+    }
+
+    pub fn with_tx_max_staged_bytes(mut self, max: usize) -> Self {
+        self.tx_max_staged_bytes = Some(max);
+        self
+    }
+
+    pub fn with_auto_checkpoint(mut self, auto_checkpoint: AutoCheckpoint) -> Self {
+        self.auto_checkpoint = auto_checkpoint;
+        self
     }
 }
@@ -0,0 +1,77 @@
+use crate::engine;
+use crate::error::{DbError, DbResult};
+use crate::parser::command::Command;
+use crate::parser::parser;
+use crate::query_result::QueryResult;
+use crate::restricted;
+use crate::storage::{Catalog, DiskStorage};
+
+/// Read-only access to a `Database` handle, passed to callbacks registered
+/// with `Database::on_change`. Hooks run once the statement (or commit) has
+/// finished writing, so queries see the changes being reported.
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseView<'a> {
+    catalog: &'a Catalog,
+    storage: &'a DiskStorage,
+    max_join_rows: Option<usize>,
+    strict_types: bool,
+}
+
+impl<'a> DatabaseView<'a> {
+    pub(crate) fn new(
+        catalog: &'a Catalog,
+        storage: &'a DiskStorage,
+        max_join_rows: Option<usize>,
+        strict_types: bool,
+    ) -> Self {
+        Self {
+            catalog,
+            storage,
+            max_join_rows,
+            strict_types,
+        }
+    }
+
+    /// Runs a read statement: SELECT (without `for update` or AS OF),
+    /// INTERSECT/EXCEPT, DESCRIBE or SHOW INDEXES, over base tables of this
+    /// handle. Anything else, including reads of views and attached
+    /// databases, fails without touching the database.
+    pub fn query(&self, sql: &str) -> DbResult<QueryResult> {
+        let cmd = parser::parse(sql).map_err(DbError::from)?;
+        match &cmd {
+            Command::Select {
+                for_update: true, ..
+            } => {
+                return Err(DbError::from(
+                    "DatabaseView::query cannot select FOR UPDATE",
+                ));
+            }
+            Command::Select { as_of: Some(_), .. } => {
+                return Err(DbError::from(
+                    "DatabaseView::query cannot read AS OF a snapshot",
+                ));
+            }
+            _ if !engine::is_read_command(&cmd) => {
+                return Err(DbError::from(
+                    "DatabaseView::query only runs read statements",
+                ));
+            }
+            _ => {}
+        }
+        if let Some(view) = restricted::referenced_tables(&cmd)
+            .into_iter()
+            .find(|t| self.catalog.view(t).is_some())
+        {
+            return Err(DbError::from(format!(
+                "DatabaseView::query cannot read view '{view}'"
+            )));
+        }
+        let cmd = if self.strict_types {
+            cmd
+        } else {
+            engine::relax_where_literals(cmd, self.catalog)
+        };
+        engine::execute_read(cmd, self.catalog, self.storage, self.max_join_rows)
+            .map_err(DbError::from)
+    }
+}
@@ -1,14 +1,21 @@
 use crate::execution_stats::ExecutionStats;
 use crate::parser::command::{
-    AlterAction, Assignment, ColumnDef, Command, CompareOp, ForeignKeyAction, JoinClause, JoinType,
-    LogicalOp, OrderBy, TableConstraintDef, WhereClause,
+    AlterAction, Assignment, ColumnDef, ColumnPosition, Command, CommentTarget, CompareOp,
+    ForeignKeyAction, IndexKind, JoinClause, JoinType, LogicalOp, OrderBy, Predicate, SetOpKind,
+    Subquery, TableConstraintDef, TriggerValue, WhereClause,
 };
 use crate::query_result::QueryResult;
-use crate::storage::schema::ForeignKeyDef;
+use crate::statement_plan::{
+    AccessPath, AliasBinding, BindingTarget, ColumnBinding, PlanColumn, StatementPlan,
+};
+use crate::storage::schema::{ForeignKeyDef, TriggerDef};
 use crate::storage::{Catalog, Column, Schema, StorageEngine};
-use crate::types::Row;
-use crate::types::datatype::DataType;
-use crate::types::value::{Value, parse_value, value_to_string};
+use crate::types::datatype::{DataType, datatype_name, parse_datatype};
+use crate::types::uuid_gen::{UuidGenerator, is_gen_uuid_call};
+use crate::types::value::{
+    Value, convert_value, fit_decimal, parse_value, push_value_key, value_key, value_to_string,
+};
+use crate::types::{Row, RowId};
 use rust_decimal::Decimal;
 use std::cmp::Ordering;
 
@@ -18,7 +25,11 @@ include!("execute/foreign_keys.rs");
 include!("execute/dml.rs");
 include!("execute/describe.rs");
 include!("execute/select.rs");
+include!("execute/set_ops.rs");
 include!("execute/mutations.rs");
 include!("execute/filter_project.rs");
 include!("execute/constraints.rs");
 include!("execute/referential.rs");
+include!("execute/plan.rs");
+include!("execute/bulk.rs");
+include!("execute/stream.rs");
@@ -0,0 +1,114 @@
+/// Checks a bulk-load batch against `table` and returns the rows coerced to
+/// the column types, without storing anything.
+///
+/// Each value is converted the same way an INSERT literal would be, so a
+/// `Value::Text("2024-01-31")` lands in a date column as a date. PRIMARY KEY,
+/// UNIQUE and FOREIGN KEY checks run once over the whole batch with hash
+/// sets instead of once per row, and any failure rejects the entire batch.
+pub fn prepare_bulk_rows(
+    table: &str,
+    rows: Vec<Row>,
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+) -> Result<Vec<Row>, String> {
+    let schema = catalog.schema(table)?;
+
+    let mut prepared: Vec<Row> = Vec::with_capacity(rows.len());
+    for (n, row) in rows.into_iter().enumerate() {
+        if row.len() != schema.column_count() {
+            return Err(format!(
+                "Bulk row {}: {}",
+                n + 1,
+                insert_value_count_error(schema, row.len())
+            ));
+        }
+        let mut out: Row = Vec::with_capacity(row.len());
+        for (col, value) in schema.columns.iter().zip(row) {
+            let token = value_to_string(&value);
+            if token.contains(['\n', '\r']) {
+                return Err(format!(
+                    "Bulk row {}: value for column '{}' contains a line break",
+                    n + 1,
+                    col.name
+                ));
+            }
+            let parsed =
+                parse_value(&col.dtype, &token).map_err(|e| format!("Bulk row {}: {e}", n + 1))?;
+            if col.not_null && parsed == Value::Null {
+                return Err(format!("Bulk row {}: Column '{}' is NOT NULL", n + 1, col.name));
+            }
+            col.check_json_shape(&parsed)
+                .map_err(|e| format!("Bulk row {}: {e}", n + 1))?;
+            out.push(parsed);
+        }
+        prepared.push(out);
+    }
+
+    let existing = storage.scan(table)?;
+    for (kind, idxs, cols) in unique_constraint_groups(schema)? {
+        let nulls_equal = schema.unique_nulls_not_distinct(&cols);
+        let mut seen: std::collections::HashSet<Vec<u8>> =
+            std::collections::HashSet::with_capacity(existing.len() + prepared.len());
+        for row in existing.iter().chain(prepared.iter()) {
+            let key = if nulls_equal {
+                Some(idxs.iter().fold(Vec::new(), |mut key, i| {
+                    push_value_key(&mut key, row.get(*i).unwrap_or(&Value::Null));
+                    key
+                }))
+            } else {
+                bulk_key(row, &idxs)
+            };
+            let Some(key) = key else {
+                continue;
+            };
+            if !seen.insert(key) {
+                return Err(format!(
+                    "{} constraint violation on column(s) {}",
+                    kind,
+                    cols.join(",")
+                ));
+            }
+        }
+    }
+
+    for fk in &schema.foreign_keys {
+        let parent_schema = catalog.schema(&fk.ref_table)?;
+        let child_idxs = resolve_cols_to_idxs(schema, &fk.columns)?;
+        let parent_idxs = resolve_cols_to_idxs(parent_schema, &fk.ref_columns)?;
+        let mut parents: std::collections::HashSet<Vec<u8>> = storage
+            .scan(&fk.ref_table)?
+            .iter()
+            .filter_map(|r| bulk_key(r, &parent_idxs))
+            .collect();
+        if fk.ref_table == table {
+            parents.extend(prepared.iter().filter_map(|r| bulk_key(r, &parent_idxs)));
+        }
+        for row in &prepared {
+            if let Some(key) = bulk_key(row, &child_idxs)
+                && !parents.contains(&key)
+            {
+                return Err(format!(
+                    "FOREIGN KEY violation on ({}) references {}({})",
+                    fk.columns.join(","),
+                    fk.ref_table,
+                    fk.ref_columns.join(",")
+                ));
+            }
+        }
+    }
+
+    Ok(prepared)
+}
+
+/// Concatenated [`value_key`]s for hash-set checks; `None` when any part is
+/// NULL, since NULLs never conflict and never need a parent.
+fn bulk_key(row: &Row, idxs: &[usize]) -> Option<Vec<u8>> {
+    let mut key = Vec::new();
+    for i in idxs {
+        match row.get(*i) {
+            None | Some(Value::Null) => return None,
+            Some(v) => push_value_key(&mut key, v),
+        }
+    }
+    Some(key)
+}
@@ -5,12 +5,14 @@ fn validate_unique_constraints(
     skip_idx: Option<usize>,
 ) -> Result<(), String> {
     for (kind, idxs, cols) in unique_constraint_groups(schema)? {
+        let nulls_equal = schema.unique_nulls_not_distinct(&cols);
         for (row_idx, existing) in rows.iter().enumerate() {
             if skip_idx == Some(row_idx) {
                 continue;
             }
-            if idxs.iter().any(|i| matches!(candidate.get(*i), Some(Value::Null)))
-                || idxs.iter().any(|i| matches!(existing.get(*i), Some(Value::Null)))
+            if !nulls_equal
+                && (idxs.iter().any(|i| matches!(candidate.get(*i), Some(Value::Null)))
+                    || idxs.iter().any(|i| matches!(existing.get(*i), Some(Value::Null))))
             {
                 continue;
             }
@@ -34,6 +36,54 @@ fn validate_all_unique_constraints(schema: &Schema, rows: &[Row]) -> Result<(),
     Ok(())
 }
 
+/// Re-checks every PRIMARY KEY and UNIQUE constraint of `table` over its
+/// stored rows, as needed after rows are merged in from outside a statement.
+pub fn validate_table_unique_constraints(
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+    table: &str,
+) -> Result<(), String> {
+    validate_all_unique_constraints(catalog.schema(table)?, storage.scan(table)?)
+}
+
+/// Rejects INSERT/UPDATE json values whose canonical text exceeds `max_bytes`.
+/// Runs ahead of execution so WAL replay never re-checks committed data.
+pub fn validate_json_size(cmd: &Command, catalog: &Catalog, max_bytes: usize) -> Result<(), String> {
+    let (table, targets): (&String, Vec<(Option<&str>, &str)>) = match cmd {
+        Command::Insert { table, values, .. } => (table, values.iter().map(|v| (None, v.as_str())).collect()),
+        Command::Update {
+            table, assignments, ..
+        } => (
+            table,
+            assignments
+                .iter()
+                .map(|a| (Some(a.column.as_str()), a.value.as_str()))
+                .collect(),
+        ),
+        _ => return Ok(()),
+    };
+    let schema = catalog.schema(table)?;
+    for (pos, (name, token)) in targets.into_iter().enumerate() {
+        let col = match name {
+            Some(name) => schema.columns.iter().find(|c| c.name == name),
+            None => schema.columns.get(pos),
+        };
+        let Some(col) = col.filter(|c| c.dtype == DataType::Json) else {
+            continue;
+        };
+        if let Ok(Value::Json(j)) = parse_value(&col.dtype, token) {
+            let len = j.to_string().len();
+            if len > max_bytes {
+                return Err(format!(
+                    "JSON value for column '{}' is {len} bytes, exceeding the {max_bytes}-byte limit",
+                    col.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 fn validate_not_null_columns(schema: &Schema, rows: &[Row]) -> Result<(), String> {
     for row in rows {
         for (idx, col) in schema.columns.iter().enumerate() {
@@ -99,37 +149,168 @@ fn resolve_cols(schema: &Schema, names: &[String]) -> Result<(Vec<usize>, Vec<St
     Ok((idxs, names.to_vec()))
 }
 
-fn matches_where(cell: &Value, dtype: &DataType, op: &CompareOp, rhs_token: &str) -> Result<bool, String> {
-    match op {
-        CompareOp::IsNull => Ok(matches!(cell, Value::Null)),
-        CompareOp::IsNotNull => Ok(!matches!(cell, Value::Null)),
-        CompareOp::In => {
-            let items: Vec<&str> = rhs_token
-                .split('\u{1F}')
-                .filter(|s| !s.is_empty())
-                .collect();
-            if items.is_empty() {
-                return Err("IN list cannot be empty".to_string());
+/// Parses a literal a `clause` (WHERE, HAVING, SET) compares or assigns to
+/// `column`, naming both when it is not a value of the column's type.
+fn parse_literal(clause: &str, column: &str, dtype: &DataType, token: &str) -> Result<Value, String> {
+    parse_value(dtype, token).map_err(|_| invalid_literal(clause, column, dtype, token))
+}
+
+fn invalid_literal(clause: &str, column: &str, dtype: &DataType, token: &str) -> String {
+    format!(
+        "{clause}: value '{token}' is not valid for column '{column}' ({})",
+        datatype_name(dtype)
+    )
+}
+
+/// Checks every literal in `clause` against its column's type, so a
+/// mismatch fails before any index lookup or row scan. Columns must already
+/// be validated.
+fn validate_where_literals(schema: &Schema, clause: &WhereClause, clause_name: &str) -> Result<(), String> {
+    match clause {
+        WhereClause::Predicate(p) => {
+            let col_idx = resolve_column_index(schema, &p.column, clause_name)?;
+            predicate_literals(&schema.columns[col_idx].dtype, p, clause_name).map(|_| ())
+        }
+        WhereClause::Binary { left, right, .. } => {
+            validate_where_literals(schema, left, clause_name)?;
+            validate_where_literals(schema, right, clause_name)
+        }
+        // Checked against the subquery's table when it is compiled.
+        WhereClause::Exists { .. } => Ok(()),
+    }
+}
+
+/// `cmd` with every WHERE literal that is not a value of its column's type
+/// turned into a comparison matching no row, for reads with the
+/// `strict_types` setting off. A comparison with such a literal becomes an
+/// empty IN list, and such IN items are dropped. SELECTs (also inside
+/// INTERSECT/EXCEPT and EXISTS/IN subqueries) are rewritten; other commands
+/// and names that do not resolve are left for the usual checks to reject.
+pub fn relax_where_literals(cmd: Command, catalog: &Catalog) -> Command {
+    match cmd {
+        Command::Select {
+            table,
+            distinct,
+            join,
+            columns,
+            filter: Some(filter),
+            group_by,
+            having,
+            order_by,
+            limit,
+            offset,
+            for_update,
+            as_of,
+        } => {
+            let schema = match &join {
+                Some(join) => resolve_join_schema(catalog, &table, join).map(|(schema, ..)| schema),
+                None => catalog.schema(&table).cloned(),
+            };
+            let filter = match schema {
+                Ok(schema) => relax_where_clause(&schema, filter, catalog),
+                Err(_) => filter,
+            };
+            Command::Select {
+                table,
+                distinct,
+                join,
+                columns,
+                filter: Some(filter),
+                group_by,
+                having,
+                order_by,
+                limit,
+                offset,
+                for_update,
+                as_of,
             }
-            for tok in items {
-                let rhs = parse_value(dtype, tok)?;
-                if cell == &rhs {
-                    return Ok(true);
+        }
+        Command::SetOp {
+            kind,
+            left,
+            right,
+            all,
+        } => Command::SetOp {
+            kind,
+            left: Box::new(relax_where_literals(*left, catalog)),
+            right: Box::new(relax_where_literals(*right, catalog)),
+            all,
+        },
+        cmd => cmd,
+    }
+}
+
+fn relax_where_clause(schema: &Schema, clause: WhereClause, catalog: &Catalog) -> WhereClause {
+    match clause {
+        WhereClause::Predicate(mut p) => {
+            let Ok(col_idx) = resolve_column_index(schema, &p.column, "WHERE") else {
+                return WhereClause::Predicate(p);
+            };
+            let dtype = &schema.columns[col_idx].dtype;
+            match p.op {
+                CompareOp::IsNull | CompareOp::IsNotNull | CompareOp::Like => {}
+                CompareOp::In => p.values.retain(|tok| parse_value(dtype, tok).is_ok()),
+                _ if parse_value(dtype, &p.value).is_err() => {
+                    p.op = CompareOp::In;
+                    p.value.clear();
+                    p.values.clear();
                 }
+                _ => {}
             }
-            Ok(false)
+            WhereClause::Predicate(p)
         }
-        CompareOp::Eq => {
-            let rhs = parse_value(dtype, rhs_token)?;
-            Ok(cell == &rhs)
-        }
-        CompareOp::NotEq => {
-            let rhs = parse_value(dtype, rhs_token)?;
-            Ok(cell != &rhs)
+        WhereClause::Binary { left, op, right } => WhereClause::Binary {
+            left: Box::new(relax_where_clause(schema, *left, catalog)),
+            op,
+            right: Box::new(relax_where_clause(schema, *right, catalog)),
+        },
+        WhereClause::Exists {
+            mut subquery,
+            negated,
+        } => {
+            if let Ok(inner) = catalog.schema(&subquery.table) {
+                subquery.filter = subquery
+                    .filter
+                    .map(|filter| Box::new(relax_where_clause(inner, *filter, catalog)));
+            }
+            WhereClause::Exists { subquery, negated }
         }
+    }
+}
+
+/// The parsed right-hand side of `predicate`: the one comparison value, or
+/// every IN item. IS [NOT] NULL and LIKE take none.
+fn predicate_literals(dtype: &DataType, predicate: &Predicate, clause: &str) -> Result<Vec<Value>, String> {
+    let column = predicate.column.as_str();
+    match predicate.op {
+        CompareOp::IsNull | CompareOp::IsNotNull | CompareOp::Like => Ok(Vec::new()),
+        CompareOp::In => predicate
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, tok)| {
+                parse_value(dtype, tok).map_err(|_| {
+                    invalid_literal(&format!("{clause} IN item {}", i + 1), column, dtype, tok)
+                })
+            })
+            .collect(),
+        _ => parse_literal(clause, column, dtype, &predicate.value).map(|v| vec![v]),
+    }
+}
+
+/// Evaluates `predicate` against `cell`. `rhs` is the predicate's parsed
+/// comparison value; IN lists are evaluated by the caller.
+fn matches_where(cell: &Value, dtype: &DataType, predicate: &Predicate, rhs: &Value) -> Result<bool, String> {
+    let op = &predicate.op;
+    let rhs_token = predicate.value.as_str();
+    match op {
+        CompareOp::IsNull => Ok(matches!(cell, Value::Null)),
+        CompareOp::IsNotNull => Ok(!matches!(cell, Value::Null)),
+        CompareOp::In => Err("Internal error: IN is evaluated from its compiled set".to_string()),
+        CompareOp::Eq => Ok(cell == rhs),
+        CompareOp::NotEq => Ok(cell != rhs),
         CompareOp::Gt | CompareOp::Lt | CompareOp::Gte | CompareOp::Lte => {
-            let rhs = parse_value(dtype, rhs_token)?;
-            let ord = compare_order(cell, &rhs, dtype)?;
+            let ord = compare_order(cell, rhs, dtype)?;
             Ok(match op {
                 CompareOp::Gt => ord == Ordering::Greater,
                 CompareOp::Lt => ord == Ordering::Less,
@@ -1,53 +1,162 @@
 fn handle_create_index(
     table: String,
     columns: Vec<String>,
+    if_not_exists: bool,
+    kind: IndexKind,
+    name: Option<String>,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
 ) -> Result<QueryResult, String> {
-    catalog.add_secondary_index(&table, columns.clone())?;
+    let suffix = index_kind_suffix(kind);
+    let label = index_label(name.as_deref());
+    let schema = catalog.schema(&table)?;
+    let exists = match kind {
+        IndexKind::Standard => schema.secondary_indexes.contains(&columns),
+        IndexKind::Trigram => columns.len() == 1 && schema.trigram_indexes.contains(&columns[0]),
+    };
+    let name_taken = name.as_deref().is_some_and(|n| catalog.named_index(n).is_some());
+    if if_not_exists && (exists || name_taken) {
+        return Ok(QueryResult::schema_change(format!(
+            "index{} on {}({}){} already exists, skipped",
+            label,
+            table,
+            columns.join(","),
+            suffix
+        )));
+    }
+    if let Some(name) = name.as_deref().filter(|_| name_taken) {
+        return Err(format!("index {} already exists", name));
+    }
+    match kind {
+        IndexKind::Standard => catalog.add_secondary_index(&table, columns.clone())?,
+        IndexKind::Trigram => catalog.add_trigram_index(&table, columns.clone())?,
+    }
+    if let Some(name) = &name {
+        catalog.name_index(&table, name, kind, columns.clone())?;
+    }
     let schema = catalog.schema(&table)?;
     storage.rebuild_indexes(&table, schema)?;
     Ok(QueryResult::schema_change(format!(
-        "created index on {}({})",
+        "created index{} on {}({}){}",
+        label,
         table,
-        columns.join(",")
+        columns.join(","),
+        suffix
     )))
 }
 
 fn handle_drop_index(
     table: String,
     columns: Vec<String>,
+    kind: IndexKind,
+    name: Option<String>,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
 ) -> Result<QueryResult, String> {
-    catalog.drop_secondary_index(&table, &columns)?;
+    match kind {
+        IndexKind::Standard => catalog.drop_secondary_index(&table, &columns)?,
+        IndexKind::Trigram => catalog.drop_trigram_index(&table, &columns)?,
+    }
     let schema = catalog.schema(&table)?;
     storage.rebuild_indexes(&table, schema)?;
     Ok(QueryResult::schema_change(format!(
-        "dropped index on {}({})",
+        "dropped index{} on {}({}){}",
+        index_label(name.as_deref()),
         table,
-        columns.join(",")
+        columns.join(","),
+        index_kind_suffix(kind)
     )))
 }
 
+/// Fills in the table, columns and kind of a `drop index <name>` from the
+/// catalog, so the statement can be checked and persisted like a drop by
+/// columns. Every other command is returned unchanged.
+pub fn resolve_index_name(cmd: Command, catalog: &Catalog) -> Result<Command, String> {
+    match cmd {
+        Command::DropIndex {
+            table,
+            name: Some(name),
+            ..
+        } if table.is_empty() => {
+            let (table, index) = catalog
+                .named_index(&name)
+                .ok_or_else(|| format!("index {} does not exist", name))?;
+            Ok(Command::DropIndex {
+                table: table.to_string(),
+                columns: index.columns.clone(),
+                kind: index.kind,
+                name: Some(name),
+            })
+        }
+        other => Ok(other),
+    }
+}
+
+/// Column definitions for `create table <table> as select ...`, one plain
+/// column (no constraints or default) per SELECT output column. A join's
+/// `<table>.<column>` names lose their qualifier; a name that still is no
+/// column name, such as `count(*)`, needs an alias.
+pub fn create_table_as_columns(source: &[(String, DataType)]) -> Result<Vec<ColumnDef>, String> {
+    let mut columns: Vec<ColumnDef> = Vec::with_capacity(source.len());
+    for (name, dtype) in source {
+        let name = name.rsplit('.').next().unwrap_or(name);
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!(
+                "CREATE TABLE ... AS: output column '{name}' is not a column name; give it one with AS"
+            ));
+        }
+        if columns.iter().any(|c| c.name == name) {
+            return Err(format!(
+                "CREATE TABLE ... AS: the SELECT returns more than one column named '{name}'; rename them with AS"
+            ));
+        }
+        columns.push(ColumnDef {
+            name: name.to_string(),
+            dtype: dtype.clone(),
+            primary_key: false,
+            unique: false,
+            not_null: false,
+            default: None,
+            json_shape: None,
+        });
+    }
+    Ok(columns)
+}
+
+fn index_label(name: Option<&str>) -> String {
+    name.map(|n| format!(" {n}")).unwrap_or_default()
+}
+
+fn index_kind_suffix(kind: IndexKind) -> &'static str {
+    match kind {
+        IndexKind::Standard => "",
+        IndexKind::Trigram => " using trigram",
+    }
+}
+
 fn handle_alter(
     table: String,
     action: AlterAction,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
+    uuid_gen: &mut UuidGenerator,
 ) -> Result<QueryResult, String> {
     let before = catalog.clone();
     let result = match action {
-        AlterAction::AddUnique(cols) => (|| -> Result<QueryResult, String> {
-            catalog.add_unique_constraint(&table, cols.clone())?;
+        AlterAction::AddUnique {
+            columns: cols,
+            nulls_not_distinct,
+        } => (|| -> Result<QueryResult, String> {
+            catalog.add_unique_constraint(&table, cols.clone(), nulls_not_distinct)?;
             let schema = catalog.schema(&table)?;
             let rows = storage.scan(&table)?;
             validate_all_unique_constraints(schema, rows)?;
             storage.rebuild_indexes(&table, schema)?;
             Ok(QueryResult::schema_change(format!(
-                "altered table {}: added unique({})",
+                "altered table {}: added unique({}){}",
                 table,
-                cols.join(",")
+                cols.join(","),
+                if nulls_not_distinct { " nulls not distinct" } else { "" }
             )))
         })(),
         AlterAction::DropUnique(cols) => (|| -> Result<QueryResult, String> {
@@ -119,9 +228,113 @@ fn handle_alter(
                 table, col
             )))
         })(),
+        AlterAction::AddColumn { column, position } => (|| -> Result<QueryResult, String> {
+            let name = column.name.clone();
+            let idx = catalog.add_column(&table, column, &position)?;
+            let schema = catalog.schema(&table)?;
+            let col = &schema.columns[idx];
+            // Every existing row gets the DEFAULT (or null) at the new
+            // position; the rewrite is built and checked before storage changes.
+            let mut rows = storage.scan(&table)?.to_vec();
+            for row in &mut rows {
+                let value = match &col.default {
+                    Some(token) if col.dtype == DataType::Uuid && is_gen_uuid_call(token) => {
+                        Value::Uuid(uuid_gen.next_v4())
+                    }
+                    Some(token) => parse_value(&col.dtype, token)?,
+                    None => Value::Null,
+                };
+                row.insert(idx, value);
+            }
+            validate_not_null_columns(schema, &rows)?;
+            validate_all_unique_constraints(schema, &rows)?;
+            *storage.scan_mut(&table)? = rows;
+            storage.rebuild_indexes(&table, schema)?;
+            let placement = match &position {
+                ColumnPosition::Last => String::new(),
+                ColumnPosition::First => " first".to_string(),
+                ColumnPosition::After(c) => format!(" after {c}"),
+            };
+            Ok(QueryResult::schema_change(format!(
+                "altered table {}: added column {} {}{}",
+                table,
+                name,
+                datatype_name(&schema.columns[idx].dtype),
+                placement
+            )))
+        })(),
     };
     if result.is_err() {
         *catalog = before;
     }
     result
 }
+
+fn handle_create_trigger(
+    name: String,
+    table: String,
+    target: String,
+    values: Vec<TriggerValue>,
+    catalog: &mut Catalog,
+) -> Result<QueryResult, String> {
+    let schema = catalog.schema(&table)?;
+    for value in &values {
+        if let TriggerValue::New(col) = value
+            && !schema.columns.iter().any(|c| &c.name == col)
+        {
+            return Err(format!("Unknown column 'new.{col}' in trigger {name}"));
+        }
+    }
+    let target_schema = catalog.schema(&target)?;
+    if values.len() > target_schema.column_count() {
+        return Err(format!(
+            "Trigger {name} inserts {} value(s) but table {target} has {} column(s)",
+            values.len(),
+            target_schema.column_count()
+        ));
+    }
+    catalog.create_trigger(
+        &table,
+        TriggerDef {
+            name: name.clone(),
+            target,
+            values,
+        },
+    )?;
+    Ok(QueryResult::schema_change(format!(
+        "created trigger {} on {}",
+        name, table
+    )))
+}
+
+fn handle_drop_trigger(name: String, catalog: &mut Catalog) -> Result<QueryResult, String> {
+    let table = catalog.drop_trigger(&name)?;
+    Ok(QueryResult::schema_change(format!(
+        "dropped trigger {} on {}",
+        name, table
+    )))
+}
+
+fn handle_comment(
+    target: CommentTarget,
+    text: Option<String>,
+    catalog: &mut Catalog,
+) -> Result<QueryResult, String> {
+    let verb = if text.is_some() { "set" } else { "removed" };
+    match target {
+        CommentTarget::Table(table) => {
+            catalog.set_table_comment(&table, text)?;
+            Ok(QueryResult::schema_change(format!(
+                "{} comment on table {}",
+                verb, table
+            )))
+        }
+        CommentTarget::Column { table, column } => {
+            catalog.set_column_comment(&table, &column, text)?;
+            Ok(QueryResult::schema_change(format!(
+                "{} comment on column {}.{}",
+                verb, table, column
+            )))
+        }
+    }
+}
@@ -8,6 +8,7 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
             unique: false,
             not_null: true,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "type".to_string(),
@@ -16,6 +17,7 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
             unique: false,
             not_null: true,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "primary_key".to_string(),
@@ -24,6 +26,7 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
             unique: false,
             not_null: true,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "unique".to_string(),
@@ -32,6 +35,7 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
             unique: false,
             not_null: true,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "not_null".to_string(),
@@ -40,6 +44,7 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
             unique: false,
             not_null: true,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "default".to_string(),
@@ -48,6 +53,7 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "indexes".to_string(),
@@ -56,6 +62,25 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
+        },
+        Column {
+            name: "comment".to_string(),
+            dtype: DataType::Text,
+            primary_key: false,
+            unique: false,
+            not_null: false,
+            default: None,
+            json_shape: None,
+        },
+        Column {
+            name: "table_comment".to_string(),
+            dtype: DataType::Text,
+            primary_key: false,
+            unique: false,
+            not_null: false,
+            default: None,
+            json_shape: None,
         },
     ]);
 
@@ -63,7 +88,7 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
     for column in &table_schema.columns {
         rows.push(vec![
             Value::Text(column.name.clone()),
-            Value::Text(datatype_to_string(&column.dtype)),
+            Value::Text(datatype_name(&column.dtype)),
             Value::Bool(table_schema.primary_key.iter().any(|name| name == &column.name)),
             Value::Bool(column.unique || column_is_in_unique_constraint(table_schema, &column.name)),
             Value::Bool(column.not_null),
@@ -73,6 +98,16 @@ fn handle_describe(table: String, catalog: &Catalog) -> Result<QueryResult, Stri
                 .map(|value| Value::Text(value.clone()))
                 .unwrap_or(Value::Null),
             Value::Text(indexes_for_column(table_schema, &column.name).join(",")),
+            table_schema
+                .column_comments
+                .get(&column.name)
+                .map(|text| Value::Text(text.clone()))
+                .unwrap_or(Value::Null),
+            table_schema
+                .comment
+                .as_ref()
+                .map(|text| Value::Text(text.clone()))
+                .unwrap_or(Value::Null),
         ]);
     }
 
@@ -92,21 +127,41 @@ fn indexes_for_column(schema: &Schema, column_name: &str) -> Vec<String> {
         .iter()
         .filter(|columns| columns.iter().any(|column| column == column_name))
         .map(|columns| columns.join("+"))
+        .chain(
+            schema
+                .trigram_indexes
+                .iter()
+                .filter(|column| *column == column_name)
+                .map(|column| format!("{column} using trigram")),
+        )
         .collect()
 }
 
-fn datatype_to_string(dtype: &DataType) -> String {
-    match dtype {
-        DataType::Bool => "bool".to_string(),
-        DataType::Int => "int".to_string(),
-        DataType::BigInt => "bigint".to_string(),
-        DataType::Decimal { precision, scale } => format!("decimal({precision},{scale})"),
-        DataType::VarChar(size) => format!("varchar({size})"),
-        DataType::Text => "text".to_string(),
-        DataType::Date => "date".to_string(),
-        DataType::Timestamp => "timestamp".to_string(),
-        DataType::Uuid => "uuid".to_string(),
-        DataType::Json => "json".to_string(),
-        DataType::Blob => "blob".to_string(),
-    }
+fn handle_show_indexes(table: String, catalog: &Catalog) -> Result<QueryResult, String> {
+    let indexes = catalog.indexes(&table)?;
+    let out_schema = Schema::new(
+        ["kind", "columns", "name"]
+            .into_iter()
+            .map(|name| Column {
+                name: name.to_string(),
+                dtype: DataType::Text,
+                primary_key: false,
+                unique: false,
+                not_null: name != "name",
+                default: None,
+                json_shape: None,
+            })
+            .collect(),
+    );
+    let rows = indexes
+        .into_iter()
+        .map(|index| {
+            vec![
+                Value::Text(index.source.as_str().to_string()),
+                Value::Text(index.columns.join(",")),
+                index.name.map(Value::Text).unwrap_or(Value::Null),
+            ]
+        })
+        .collect();
+    Ok(QueryResult::select(out_schema, rows))
 }
@@ -1,27 +1,115 @@
+/// Runs any statement the engine executes. Reads go through
+/// [`execute_read`]; everything else may change `catalog` and `storage`.
 pub fn execute_command(
     cmd: Command,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
+    uuid_gen: &mut UuidGenerator,
+    max_join_rows: Option<usize>,
+    defer_foreign_keys: bool,
 ) -> Result<QueryResult, String> {
     match cmd {
         Command::Create {
             table,
             columns,
             table_constraints,
-        } => handle_create(table, columns, table_constraints, catalog, storage),
-        Command::CreateIndex { table, columns } => {
-            handle_create_index(table, columns, catalog, storage)
+            if_not_exists,
+            partition_by,
+        } => handle_create(
+            table,
+            columns,
+            table_constraints,
+            if_not_exists,
+            partition_by,
+            catalog,
+            storage,
+        ),
+        Command::CreateIndex {
+            table,
+            columns,
+            if_not_exists,
+            kind,
+            name,
+        } => handle_create_index(table, columns, if_not_exists, kind, name, catalog, storage),
+        Command::DropIndex {
+            table,
+            columns,
+            kind,
+            name,
+        } => handle_drop_index(table, columns, kind, name, catalog, storage),
+        Command::Alter { table, action } => {
+            handle_alter(table, action, catalog, storage, uuid_gen)
         }
-        Command::DropIndex { table, columns } => handle_drop_index(table, columns, catalog, storage),
-        Command::Alter { table, action } => handle_alter(table, action, catalog, storage),
-        Command::Insert { table, values } => handle_insert(table, values, catalog, storage),
+        Command::Insert {
+            table,
+            values,
+            returning,
+        } => handle_insert(table, values, returning, catalog, storage, uuid_gen, defer_foreign_keys),
         Command::Update {
             table,
             assignments,
             filter,
-        } => handle_update(table, assignments, filter, catalog, storage),
-        Command::Delete { table, filter } => handle_delete(table, filter, catalog, storage),
+            returning,
+        } => handle_update(table, assignments, filter, returning, catalog, storage, defer_foreign_keys),
+        Command::Delete {
+            table,
+            filter,
+            returning,
+        } => handle_delete(table, filter, returning, catalog, storage),
+        Command::Comment { target, text } => handle_comment(target, text, catalog),
+        Command::CreateTrigger {
+            name,
+            table,
+            target,
+            values,
+        } => handle_create_trigger(name, table, target, values, catalog),
+        Command::DropTrigger { name } => handle_drop_trigger(name, catalog),
+        cmd @ (Command::Describe { .. }
+        | Command::ShowIndexes { .. }
+        | Command::Select { .. }
+        | Command::SetOp { .. }) => {
+            execute_read(cmd, catalog, storage, max_join_rows)
+        }
+        Command::Begin
+        | Command::Commit
+        | Command::Rollback
+        | Command::ShowTransaction
+        | Command::Set { .. }
+        | Command::Show { .. }
+        | Command::CreateSnapshot { .. }
+        | Command::DropSnapshot { .. }
+        | Command::ListSnapshots
+        | Command::Attach { .. }
+        | Command::Detach { .. }
+        | Command::InsertSelect { .. }
+        | Command::CreateTableAs { .. }
+        | Command::CreateView { .. }
+        | Command::DropView { .. }
+        | Command::RefreshMaterializedView { .. }
+        | Command::ShowTables { .. }
+        | Command::Checkpoint
+        | Command::ShowWal => Err(
+            "Transaction control, session settings, snapshots, attachments, views, checkpoints, INSERT ... SELECT and CREATE TABLE ... AS are handled by Database"
+                .to_string(),
+        ),
+    }
+}
+
+/// Runs a statement that only reads: SELECT (alone or combined by
+/// INTERSECT/EXCEPT), DESCRIBE or SHOW INDEXES.
+/// Takes shared borrows, so a read can never change the catalog or rows.
+pub fn execute_read(
+    cmd: Command,
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+    max_join_rows: Option<usize>,
+) -> Result<QueryResult, String> {
+    match cmd {
         Command::Describe { table } => handle_describe(table, catalog),
+        Command::ShowIndexes { table } => handle_show_indexes(table, catalog),
+        Command::Select { as_of: Some(_), .. } => {
+            Err("AS OF snapshot reads are handled by Database".to_string())
+        }
         Command::Select {
             table,
             distinct,
@@ -33,9 +121,25 @@ pub fn execute_command(
             order_by,
             limit,
             offset,
-        } => handle_select(table, distinct, join, columns, filter, group_by, having, order_by, limit, offset, catalog, storage),
-        Command::Begin | Command::Commit | Command::Rollback => {
-            Err("Transaction control is handled by Database".to_string())
-        }
+            ..
+        } => handle_select(table, distinct, join, columns, filter, group_by, having, order_by, limit, offset, catalog, storage, max_join_rows),
+        Command::SetOp {
+            kind,
+            left,
+            right,
+            all,
+        } => handle_set_op(kind, *left, *right, all, catalog, storage, max_join_rows),
+        _ => Err("Only SELECT, DESCRIBE and SHOW INDEXES are read-only statements".to_string()),
     }
 }
+
+/// Whether [`execute_read`] runs `cmd`.
+pub fn is_read_command(cmd: &Command) -> bool {
+    matches!(
+        cmd,
+        Command::Describe { .. }
+            | Command::ShowIndexes { .. }
+            | Command::Select { .. }
+            | Command::SetOp { .. }
+    )
+}
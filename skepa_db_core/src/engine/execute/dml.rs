@@ -2,10 +2,35 @@ fn handle_create(
     table: String,
     columns: Vec<ColumnDef>,
     table_constraints: Vec<TableConstraintDef>,
+    if_not_exists: bool,
+    partition_by: Option<String>,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
 ) -> Result<QueryResult, String> {
+    if if_not_exists && catalog.exists(&table) {
+        // Only names and types are compared; constraints are not.
+        let existing = catalog.schema(&table)?;
+        let same_shape = existing.columns.len() == columns.len()
+            && existing
+                .columns
+                .iter()
+                .zip(&columns)
+                .all(|(have, want)| have.name == want.name && have.dtype == want.dtype);
+        let note = if same_shape { "" } else { " (existing columns differ from this definition)" };
+        return Ok(QueryResult::schema_change(format!(
+            "table {} already exists, skipped{}",
+            table, note
+        )));
+    }
+    if let Some(col) = &partition_by
+        && !columns.iter().any(|c| &c.name == col)
+    {
+        return Err(format!("PARTITION BY references unknown column '{col}'"));
+    }
     catalog.create_table(table.clone(), columns, table_constraints)?;
+    if let Some(col) = &partition_by {
+        catalog.set_partition_by(&table, col)?;
+    }
     storage.create_table(&table)?;
     Ok(QueryResult::schema_change(format!("created table {}", table)))
 }
@@ -13,24 +38,51 @@ fn handle_create(
 fn handle_insert(
     table: String,
     values: Vec<String>,
+    returning: Option<Vec<String>>,
+    catalog: &mut Catalog,
+    storage: &mut dyn StorageEngine,
+    uuid_gen: &mut UuidGenerator,
+    defer_foreign_keys: bool,
+) -> Result<QueryResult, String> {
+    let values = values.into_iter().map(InsertValue::Token).collect();
+    insert_with_triggers(table, values, returning, catalog, storage, uuid_gen, defer_foreign_keys, 0)
+}
+
+/// One value of an INSERT run by the engine: a literal token from the
+/// statement, or a column of the row that fired a trigger.
+enum InsertValue {
+    Token(String),
+    Typed(Value),
+}
+
+/// How many trigger-fired INSERTs may nest before the statement fails.
+const MAX_TRIGGER_DEPTH: usize = 16;
+
+/// Inserts one row, then runs the table's triggers; `depth` counts the
+/// triggers that led here. If a trigger fails, the rows already written are
+/// left in place: the caller restores every table the statement can reach.
+#[allow(clippy::too_many_arguments)]
+fn insert_with_triggers(
+    table: String,
+    values: Vec<InsertValue>,
+    returning: Option<Vec<String>>,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
+    uuid_gen: &mut UuidGenerator,
+    defer_foreign_keys: bool,
+    depth: usize,
 ) -> Result<QueryResult, String> {
     let schema = catalog.schema(&table)?;
+    validate_returning(schema, returning.as_ref())?;
 
     if values.len() > schema.column_count() {
-        return Err(format!(
-            "Expected {} values but got {}",
-            schema.column_count(),
-            values.len()
-        ));
+        return Err(insert_value_count_error(schema, values.len()));
     }
     for col in schema.columns.iter().skip(values.len()) {
         if col.default.is_none() {
             return Err(format!(
-                "Expected {} values but got {}. Missing column '{}' has no DEFAULT",
-                schema.column_count(),
-                values.len(),
+                "{}. Missing column '{}' has no DEFAULT",
+                insert_value_count_error(schema, values.len()),
                 col.name
             ));
         }
@@ -38,14 +90,21 @@ fn handle_insert(
 
     let mut row: Row = Vec::new();
     for (i, col) in schema.columns.iter().enumerate() {
-        let token = values
-            .get(i)
-            .or(col.default.as_ref())
-            .ok_or_else(|| format!("Missing value for column '{}'", col.name))?;
-        if col.not_null && token.eq_ignore_ascii_case("null") {
+        let value = match values.get(i) {
+            Some(InsertValue::Typed(v)) => convert_value(&col.dtype, v)?,
+            Some(InsertValue::Token(token)) => insert_token_value(col, token, uuid_gen)?,
+            None => {
+                let token = col
+                    .default
+                    .as_ref()
+                    .ok_or_else(|| format!("Missing value for column '{}'", col.name))?;
+                insert_token_value(col, token, uuid_gen)?
+            }
+        };
+        if col.not_null && value == Value::Null {
             return Err(format!("Column '{}' is NOT NULL", col.name));
         }
-        let value = parse_value(&col.dtype, token)?;
+        col.check_json_shape(&value)?;
         row.push(value);
     }
 
@@ -69,13 +128,206 @@ fn handle_insert(
     }
 
     validate_unique_constraints(schema, rows, &row, None)?;
-    validate_outgoing_foreign_keys(catalog, storage, schema, &row)?;
+    if !defer_foreign_keys {
+        validate_outgoing_foreign_keys(catalog, storage, schema, &row)?;
+    }
+
+    let triggered = trigger_inserts(schema, &row)?;
+    if !triggered.is_empty() && depth >= MAX_TRIGGER_DEPTH {
+        return Err(format!(
+            "Triggers nest more than {MAX_TRIGGER_DEPTH} levels deep"
+        ));
+    }
 
+    let written = returning.is_some().then(|| vec![row.clone()]);
     storage.insert_row(&table, row)?;
     storage.rebuild_indexes(&table, schema)?;
-    Ok(QueryResult::mutation(
+    let result = mutation_result(
+        schema,
         format!("inserted 1 row into {}", table),
         1,
-    ))
+        written,
+        returning.as_ref(),
+    )?;
+    for (name, target, values) in triggered {
+        if let Err(e) = insert_with_triggers(
+            target,
+            values,
+            None,
+            catalog,
+            storage,
+            uuid_gen,
+            defer_foreign_keys,
+            depth + 1,
+        ) {
+            return Err(format!("Trigger '{name}' failed: {e}"));
+        }
+    }
+    Ok(result)
+}
+
+/// The value an INSERT token stores in `col`, calling `gen_uuid()` for a
+/// uuid column.
+fn insert_token_value(col: &Column, token: &str, uuid_gen: &mut UuidGenerator) -> Result<Value, String> {
+    if col.dtype == DataType::Uuid && is_gen_uuid_call(token) {
+        Ok(Value::Uuid(uuid_gen.next_v4()))
+    } else {
+        parse_value(&col.dtype, token)
+    }
 }
 
+/// The `(trigger, target table, INSERT values)` each of `schema`'s
+/// triggers runs for the newly inserted `row`. `new.<col>` values are
+/// passed typed, so they are stored as the row holds them.
+fn trigger_inserts(
+    schema: &Schema,
+    row: &Row,
+) -> Result<Vec<(String, String, Vec<InsertValue>)>, String> {
+    schema
+        .triggers
+        .iter()
+        .map(|trigger| {
+            let values = trigger
+                .values
+                .iter()
+                .map(|value| match value {
+                    TriggerValue::Literal(token) => Ok(InsertValue::Token(token.clone())),
+                    TriggerValue::New(col) => {
+                        let idx = schema
+                            .columns
+                            .iter()
+                            .position(|c| &c.name == col)
+                            .ok_or_else(|| {
+                                format!(
+                                    "Trigger '{}' references unknown column 'new.{col}'",
+                                    trigger.name
+                                )
+                            })?;
+                        Ok(InsertValue::Typed(row[idx].clone()))
+                    }
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok((trigger.name.clone(), trigger.target.clone(), values))
+        })
+        .collect()
+}
+
+/// Rejects RETURNING columns `schema` cannot resolve, before any row changes.
+fn validate_returning(schema: &Schema, returning: Option<&Vec<String>>) -> Result<(), String> {
+    if returning.is_some() {
+        resolve_projection(schema, returning)?;
+    }
+    Ok(())
+}
+
+/// With RETURNING, answers with `rows` projected like a SELECT list (the
+/// affected count stays in the stats); otherwise with the plain message.
+fn mutation_result(
+    schema: &Schema,
+    message: String,
+    rows_affected: usize,
+    rows: Option<Vec<Row>>,
+    returning: Option<&Vec<String>>,
+) -> Result<QueryResult, String> {
+    match (returning, rows) {
+        (Some(columns), Some(rows)) => {
+            let (out_schema, out_rows) = project_rows(schema, &rows, Some(columns))?;
+            Ok(QueryResult::returning(out_schema, out_rows, rows_affected))
+        }
+        _ => Ok(QueryResult::mutation(message, rows_affected)),
+    }
+}
+
+
+/// For each column of `schema`, the column of an INSERT ... SELECT result
+/// that fills it, if any. `columns` lists the target columns the result's
+/// columns fill, in order; without it, every column of the table. Each
+/// result column must be of a type its target accepts, as in
+/// INTERSECT/EXCEPT.
+fn insert_select_sources(
+    schema: &Schema,
+    columns: Option<&[String]>,
+    source: &[(String, DataType)],
+) -> Result<Vec<Option<usize>>, String> {
+    let targets: Vec<usize> = match columns {
+        None => (0..schema.column_count()).collect(),
+        Some(columns) => {
+            let mut targets = Vec::with_capacity(columns.len());
+            for c in columns {
+                let idx = resolve_column_index(schema, c, "INSERT")?;
+                if targets.contains(&idx) {
+                    return Err(format!("Column '{c}' is listed more than once in INSERT"));
+                }
+                targets.push(idx);
+            }
+            targets
+        }
+    };
+    if source.len() != targets.len() {
+        return Err(format!(
+            "INSERT ... SELECT: SELECT returns {} column(s) but {} are inserted",
+            source.len(),
+            targets.len()
+        ));
+    }
+    let mut sources = vec![None; schema.column_count()];
+    for (i, ((name, dtype), &target)) in source.iter().zip(&targets).enumerate() {
+        let col = &schema.columns[target];
+        if !column_types_combine(dtype, &col.dtype) {
+            return Err(format!(
+                "INSERT ... SELECT: column {} '{name}' ({}) cannot be inserted into column '{}' ({})",
+                i + 1,
+                datatype_name(dtype),
+                col.name,
+                datatype_name(&col.dtype)
+            ));
+        }
+        sources[target] = Some(i);
+    }
+    Ok(sources)
+}
+
+/// The row of `table` each row of an INSERT ... SELECT result becomes,
+/// checked by `insert_select_sources`, with every value converted to its
+/// column's type. Columns the SELECT does not fill take their DEFAULT, or
+/// null.
+pub fn insert_select_rows(
+    table: &str,
+    columns: Option<&[String]>,
+    source: &Schema,
+    rows: Vec<Row>,
+    catalog: &Catalog,
+    uuid_gen: &mut UuidGenerator,
+) -> Result<Vec<Row>, String> {
+    let schema = catalog.schema(table)?;
+    let source: Vec<(String, DataType)> = source
+        .columns
+        .iter()
+        .map(|c| (c.name.clone(), c.dtype.clone()))
+        .collect();
+    let sources = insert_select_sources(schema, columns, &source)?;
+    rows.into_iter()
+        .map(|row| {
+            schema
+                .columns
+                .iter()
+                .zip(&sources)
+                .map(|(col, source)| match (source, &col.default) {
+                    (Some(i), _) => convert_value(&col.dtype, &row[*i]),
+                    (None, Some(token)) => insert_token_value(col, token, uuid_gen),
+                    (None, None) => Ok(Value::Null),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn insert_value_count_error(schema: &Schema, got: usize) -> String {
+    let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+    format!(
+        "Expected {} values ({}) but got {}",
+        schema.column_count(),
+        names.join(", "),
+        got
+    )
+}
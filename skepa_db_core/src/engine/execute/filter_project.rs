@@ -3,37 +3,35 @@ fn project_rows(
     rows: &[Row],
     columns: Option<&Vec<String>>,
 ) -> Result<(Schema, Vec<Row>), String> {
-    let Some(requested_columns) = columns else {
+    let Some((projected_schema, exprs)) = resolve_projection(schema, columns)? else {
         return Ok((schema.clone(), rows.to_vec()));
     };
+    let projected_rows: Vec<Row> = rows.iter().map(|row| project_row(&exprs, row)).collect();
+    Ok((projected_schema, projected_rows))
+}
 
-    if requested_columns.is_empty() {
-        return Ok((schema.clone(), rows.to_vec()));
-    }
+/// Resolves a SELECT list against `schema`; `None` means every column as is.
+fn resolve_projection(
+    schema: &Schema,
+    columns: Option<&Vec<String>>,
+) -> Result<Option<(Schema, Vec<ScalarExpr>)>, String> {
+    let Some(requested_columns) = columns.filter(|c| !c.is_empty()) else {
+        return Ok(None);
+    };
 
-    let mut selected: Vec<(usize, Column)> = Vec::new();
+    let mut exprs: Vec<ScalarExpr> = Vec::new();
+    let mut out_columns: Vec<Column> = Vec::new();
     for name in requested_columns {
         let (expr, alias) = split_select_alias(name);
-        let idx = resolve_column_index(schema, &expr, "SELECT list")?;
-        let mut out_col = schema.columns[idx].clone();
-        if let Some(a) = alias {
-            out_col.name = a;
-        }
-        selected.push((idx, out_col));
+        let resolved = resolve_scalar_expr(schema, &expr, "SELECT list")?;
+        out_columns.push(resolved.output_column(schema, &expr, alias));
+        exprs.push(resolved);
     }
+    Ok(Some((Schema::new(out_columns), exprs)))
+}
 
-    let projected_schema = Schema::new(selected.iter().map(|(_, c)| c.clone()).collect());
-    let projected_rows: Vec<Row> = rows
-        .iter()
-        .map(|row| {
-            selected
-                .iter()
-                .map(|(idx, _)| row[*idx].clone())
-                .collect::<Row>()
-        })
-        .collect();
-
-    Ok((projected_schema, projected_rows))
+fn project_row(exprs: &[ScalarExpr], row: &Row) -> Row {
+    exprs.iter().map(|expr| expr.eval(row)).collect()
 }
 
 fn split_select_alias(token: &str) -> (String, Option<String>) {
@@ -48,16 +46,45 @@ fn split_select_alias(token: &str) -> (String, Option<String>) {
     (token.trim().to_string(), None)
 }
 
+/// Keeps the rows matching `compiled` (every row when `None`) in order,
+/// stopping once `cap` rows are kept.
+/// Returns the kept rows, each with its key, and how many rows were visited.
+fn scan_rows<'a, K>(
+    rows: impl IntoIterator<Item = (K, &'a Row)>,
+    compiled: Option<&CompiledWhere>,
+    cap: Option<usize>,
+) -> Result<(Vec<(K, Row)>, usize), String> {
+    let cap = cap.unwrap_or(usize::MAX);
+    let mut kept: Vec<(K, Row)> = Vec::new();
+    let mut visited = 0usize;
+    for (key, row) in rows {
+        if kept.len() >= cap {
+            break;
+        }
+        visited += 1;
+        if let Some(compiled) = compiled
+            && !compiled.eval(row)?
+        {
+            continue;
+        }
+        kept.push((key, row.clone()));
+    }
+    Ok((kept, visited))
+}
+
+/// Keeps the rows matching `where_clause`; `clause_name` names it in errors.
 fn filter_rows(
     schema: &crate::storage::Schema,
     rows: &[Row],
     where_clause: &WhereClause,
+    clause_name: &str,
 ) -> Result<Vec<Row>, String> {
-    validate_where_columns(schema, where_clause)?;
+    validate_where_columns(schema, where_clause, &[])?;
+    let compiled = CompiledWhere::compile(schema, where_clause, clause_name)?;
     let mut filtered: Vec<Row> = Vec::new();
 
     for row in rows {
-        if eval_where_row(row, schema, where_clause)? {
+        if compiled.eval(row)? {
             filtered.push(row.clone());
         }
     }
@@ -65,35 +92,254 @@ fn filter_rows(
     Ok(filtered)
 }
 
-fn validate_where_columns(schema: &Schema, clause: &WhereClause) -> Result<(), String> {
+/// `aliases` are the SELECT list's `as` names. WHERE never resolves them,
+/// but naming one gets a hint instead of a bare unknown-column error.
+fn validate_where_columns(schema: &Schema, clause: &WhereClause, aliases: &[String]) -> Result<(), String> {
     match clause {
-        WhereClause::Predicate(p) => {
-            let _ = resolve_column_index(schema, &p.column, "WHERE")?;
-            Ok(())
-        }
+        WhereClause::Predicate(p) => match resolve_column_index(schema, &p.column, "WHERE") {
+            Ok(_) => Ok(()),
+            Err(_) if aliases.iter().any(|a| a == &p.column) => Err(format!(
+                "cannot reference select alias '{}' in WHERE; use the full expression or HAVING",
+                p.column
+            )),
+            Err(e) => Err(e),
+        },
         WhereClause::Binary { left, right, .. } => {
-            validate_where_columns(schema, left)?;
-            validate_where_columns(schema, right)
+            validate_where_columns(schema, left, aliases)?;
+            validate_where_columns(schema, right, aliases)
         }
+        // The subquery's own columns are checked when it is compiled.
+        WhereClause::Exists { subquery, .. } => subquery
+            .correlation
+            .iter()
+            .try_for_each(|(_, outer)| resolve_column_index(schema, outer, "WHERE").map(|_| ())),
     }
 }
 
-fn eval_where_row(row: &Row, schema: &Schema, clause: &WhereClause) -> Result<bool, String> {
+/// Where an EXISTS subquery finds its table. A WHERE compiled without one
+/// rejects EXISTS.
+#[derive(Clone, Copy)]
+struct SubqueryScope<'s> {
+    catalog: &'s Catalog,
+    storage: &'s dyn StorageEngine,
+}
+
+fn contains_exists(clause: &WhereClause) -> bool {
     match clause {
-        WhereClause::Predicate(p) => {
-            let col_idx = resolve_column_index(schema, &p.column, "WHERE")?;
-            let col_dtype = &schema.columns[col_idx].dtype;
-            row_matches(row, col_idx, &p.column, col_dtype, &p.op, &p.value)
+        WhereClause::Predicate(_) => false,
+        WhereClause::Binary { left, right, .. } => contains_exists(left) || contains_exists(right),
+        WhereClause::Exists { .. } => true,
+    }
+}
+
+/// A WHERE/HAVING tree with columns resolved once and IN lists parsed into
+/// a set, so evaluating it per row does no name lookups or re-parsing.
+enum CompiledWhere<'a> {
+    Predicate {
+        col_idx: usize,
+        dtype: &'a DataType,
+        predicate: &'a Predicate,
+        /// The comparison value; `Null` for operators that take none.
+        rhs: Value,
+        in_set: Option<InSet>,
+    },
+    Binary {
+        left: Box<CompiledWhere<'a>>,
+        op: LogicalOp,
+        right: Box<CompiledWhere<'a>>,
+    },
+    /// The subquery runs once, at compile time: its matching rows are
+    /// reduced to their correlation keys, so each outer row is one lookup.
+    Exists {
+        /// Outer columns in correlation order; empty when uncorrelated.
+        outer_cols: Vec<usize>,
+        keys: std::collections::HashSet<Vec<u8>>,
+        /// Whether any subquery row passed its WHERE.
+        any: bool,
+        negated: bool,
+    },
+}
+
+/// Parsed IN list keyed by [`value_key`]; rows hold values already typed to
+/// the column, so equal values share a key.
+struct InSet {
+    keys: std::collections::HashSet<Vec<u8>>,
+    has_null: bool,
+}
+
+impl<'a> CompiledWhere<'a> {
+    /// `clause_name` (WHERE or HAVING) prefixes the error for a literal
+    /// that is not a value of its column's type.
+    fn compile(schema: &'a Schema, clause: &'a WhereClause, clause_name: &str) -> Result<Self, String> {
+        Self::compile_in(schema, clause, clause_name, None)
+    }
+
+    /// [`CompiledWhere::compile`], running any EXISTS subquery against
+    /// `scope`.
+    fn compile_in(
+        schema: &'a Schema,
+        clause: &'a WhereClause,
+        clause_name: &str,
+        scope: Option<SubqueryScope>,
+    ) -> Result<Self, String> {
+        match clause {
+            WhereClause::Predicate(p) => {
+                let col_idx = resolve_column_index(schema, &p.column, clause_name)?;
+                let dtype = &schema.columns[col_idx].dtype;
+                let literals = predicate_literals(dtype, p, clause_name)?;
+                let (rhs, in_set) = if p.op == CompareOp::In {
+                    let mut set = InSet {
+                        keys: std::collections::HashSet::with_capacity(literals.len()),
+                        has_null: false,
+                    };
+                    for v in literals {
+                        match v {
+                            Value::Null => set.has_null = true,
+                            v => {
+                                set.keys.insert(value_key(&v));
+                            }
+                        }
+                    }
+                    (Value::Null, Some(set))
+                } else {
+                    (literals.into_iter().next().unwrap_or(Value::Null), None)
+                };
+                Ok(CompiledWhere::Predicate {
+                    col_idx,
+                    dtype,
+                    predicate: p,
+                    rhs,
+                    in_set,
+                })
+            }
+            WhereClause::Binary { left, op, right } => Ok(CompiledWhere::Binary {
+                left: Box::new(Self::compile_in(schema, left, clause_name, scope)?),
+                op: op.clone(),
+                right: Box::new(Self::compile_in(schema, right, clause_name, scope)?),
+            }),
+            WhereClause::Exists { subquery, negated } => {
+                let scope = scope.ok_or_else(|| {
+                    format!("{clause_name}: subqueries are only supported in the WHERE clause of SELECT, UPDATE and DELETE")
+                })?;
+                compile_exists(schema, subquery, *negated, scope)
+            }
+        }
+    }
+
+    fn eval(&self, row: &Row) -> Result<bool, String> {
+        match self {
+            CompiledWhere::Predicate {
+                col_idx,
+                dtype,
+                predicate,
+                rhs,
+                in_set,
+            } => {
+                let Some(set) = in_set else {
+                    return row_matches(row, *col_idx, dtype, predicate, rhs);
+                };
+                let cell = row.get(*col_idx).ok_or_else(|| {
+                    format!("Row is missing value for column '{}'", predicate.column)
+                })?;
+                Ok(match cell {
+                    Value::Null => set.has_null,
+                    v => set.keys.contains(&value_key(v)),
+                })
+            }
+            CompiledWhere::Binary { left, op, right } => {
+                let lhs = left.eval(row)?;
+                let rhs = right.eval(row)?;
+                Ok(match op {
+                    LogicalOp::And => lhs && rhs,
+                    LogicalOp::Or => lhs || rhs,
+                })
+            }
+            CompiledWhere::Exists {
+                outer_cols,
+                keys,
+                any,
+                negated,
+            } => {
+                let found = if outer_cols.is_empty() {
+                    *any
+                } else {
+                    correlation_key(row, outer_cols).is_some_and(|key| keys.contains(&key))
+                };
+                Ok(found != *negated)
+            }
+        }
+    }
+}
+
+/// Runs `subquery` once and keeps the correlation keys of its matching
+/// rows. Correlated columns pair up like JOIN keys: int with bigint, or
+/// otherwise the same type.
+fn compile_exists<'a>(
+    outer_schema: &Schema,
+    subquery: &Subquery,
+    negated: bool,
+    scope: SubqueryScope,
+) -> Result<CompiledWhere<'a>, String> {
+    let schema = scope.catalog.schema(&subquery.table)?;
+    let filter = match subquery.filter.as_deref() {
+        Some(clause) => {
+            validate_where_columns(schema, clause, &[])?;
+            Some(CompiledWhere::compile(schema, clause, "WHERE")?)
+        }
+        None => None,
+    };
+    let mut inner_cols: Vec<usize> = Vec::new();
+    let mut outer_cols: Vec<usize> = Vec::new();
+    for (inner, outer) in &subquery.correlation {
+        let i = resolve_column_index(schema, inner, "WHERE")?;
+        let o = resolve_column_index(outer_schema, outer, "WHERE")?;
+        let (inner_type, outer_type) = (&schema.columns[i].dtype, &outer_schema.columns[o].dtype);
+        if !join_key_types_compatible(inner_type, outer_type) {
+            return Err(format!(
+                "EXISTS cannot compare '{}.{inner}' ({}) with '{outer}' ({})",
+                subquery.table,
+                datatype_name(inner_type),
+                datatype_name(outer_type)
+            ));
+        }
+        inner_cols.push(i);
+        outer_cols.push(o);
+    }
+    let mut keys = std::collections::HashSet::new();
+    let mut any = false;
+    for row in scope.storage.scan(&subquery.table)? {
+        if let Some(filter) = &filter
+            && !filter.eval(row)?
+        {
+            continue;
         }
-        WhereClause::Binary { left, op, right } => {
-            let lhs = eval_where_row(row, schema, left)?;
-            let rhs = eval_where_row(row, schema, right)?;
-            Ok(match op {
-                LogicalOp::And => lhs && rhs,
-                LogicalOp::Or => lhs || rhs,
-            })
+        any = true;
+        if inner_cols.is_empty() {
+            break;
+        }
+        if let Some(key) = correlation_key(row, &inner_cols) {
+            keys.insert(key);
+        }
+    }
+    Ok(CompiledWhere::Exists {
+        outer_cols,
+        keys,
+        any,
+        negated,
+    })
+}
+
+/// The values at `cols` as one key; `None` when any is NULL, since NULL
+/// equals nothing.
+fn correlation_key(row: &Row, cols: &[usize]) -> Option<Vec<u8>> {
+    let mut key = Vec::new();
+    for &idx in cols {
+        match row.get(idx)? {
+            Value::Null => return None,
+            v => push_value_key(&mut key, v),
         }
     }
+    Some(key)
 }
 
 fn simple_eq_filter(clause: &WhereClause) -> Option<(String, String)> {
@@ -106,14 +352,13 @@ fn simple_eq_filter(clause: &WhereClause) -> Option<(String, String)> {
 fn row_matches(
     row: &Row,
     col_idx: usize,
-    col_name: &str,
     col_dtype: &DataType,
-    op: &CompareOp,
-    rhs_token: &str,
+    predicate: &Predicate,
+    rhs: &Value,
 ) -> Result<bool, String> {
     let cell = row
         .get(col_idx)
-        .ok_or_else(|| format!("Row is missing value for column '{}'", col_name))?;
-    matches_where(cell, col_dtype, op, rhs_token)
+        .ok_or_else(|| format!("Row is missing value for column '{}'", predicate.column))?;
+    matches_where(cell, col_dtype, predicate, rhs)
 }
 
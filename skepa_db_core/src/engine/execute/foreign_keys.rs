@@ -1,3 +1,20 @@
+/// Checks every foreign key of every table against its parent rows. Runs at
+/// commit for transactions that skipped per-statement checks under
+/// `defer_constraints`.
+pub fn validate_deferred_foreign_keys(
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+) -> Result<(), String> {
+    for (table, schema) in catalog.snapshot_tables() {
+        if schema.foreign_keys.is_empty() {
+            continue;
+        }
+        let rows = storage.scan(&table)?;
+        validate_all_foreign_keys(catalog, storage, &schema, rows)?;
+    }
+    Ok(())
+}
+
 pub fn validate_no_action_constraints(
     catalog: &Catalog,
     storage: &dyn StorageEngine,
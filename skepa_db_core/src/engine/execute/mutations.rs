@@ -2,28 +2,59 @@ fn handle_update(
     table: String,
     assignments: Vec<Assignment>,
     filter: WhereClause,
+    returning: Option<Vec<String>>,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
+    defer_foreign_keys: bool,
 ) -> Result<QueryResult, String> {
     let schema = catalog.schema(&table)?;
+    validate_returning(schema, returning.as_ref())?;
 
-    let mut compiled: Vec<(usize, Value)> = Vec::new();
+    let mut compiled: Vec<(usize, AssignSource)> = Vec::new();
     for a in assignments {
         let idx = schema
             .columns
             .iter()
             .position(|c| c.name == a.column)
             .ok_or_else(|| format!("Unknown column '{}' in UPDATE", a.column))?;
+        if !a.quoted {
+            // A declared column name wins over an unquoted literal; a
+            // `<table>.<col>` prefix names the column explicitly.
+            let qualified = a
+                .value
+                .strip_prefix(table.as_str())
+                .and_then(|rest| rest.strip_prefix('.'));
+            let source = qualified.unwrap_or(&a.value);
+            match schema.columns.iter().position(|c| c.name == source) {
+                Some(source_col) => {
+                    compiled.push((idx, AssignSource::Column(source_col)));
+                    continue;
+                }
+                None if qualified.is_some() => {
+                    return Err(format!("Unknown column '{}' in UPDATE", a.value));
+                }
+                None => {}
+            }
+        }
         if schema.columns[idx].not_null && a.value.eq_ignore_ascii_case("null") {
             return Err(format!("Column '{}' is NOT NULL", schema.columns[idx].name));
         }
         let dtype = &schema.columns[idx].dtype;
-        let parsed = parse_value(dtype, &a.value)?;
-        compiled.push((idx, parsed));
+        let parsed = parse_literal("SET", &schema.columns[idx].name, dtype, &a.value)?;
+        schema.columns[idx].check_json_shape(&parsed)?;
+        compiled.push((idx, AssignSource::Literal(parsed)));
     }
 
-    validate_where_columns(schema, &filter)?;
-    let targeted_row_indices = if simple_eq_filter(&filter).is_some()
+    validate_where_columns(schema, &filter, &[])?;
+    // A subquery runs here, once, before any row changes.
+    let scope = SubqueryScope {
+        catalog,
+        storage: &*storage,
+    };
+    let where_filter = CompiledWhere::compile_in(schema, &filter, "WHERE", Some(scope))?;
+    let targeted_row_indices = if !storage.indexes_current(&table) {
+        None
+    } else if simple_eq_filter(&filter).is_some()
         && schema.primary_key.len() == 1
         && schema.primary_key.first().is_some_and(|pk| pk == &simple_eq_filter(&filter).expect("eq").0)
     {
@@ -41,11 +72,16 @@ fn handle_update(
     } else {
         None
     };
+    let targeted_row_indices = match targeted_row_indices {
+        Some(rows) => Some(rows),
+        None => partition_candidates(&table, schema, &filter, storage)?,
+    };
 
-    let (updated, new_rows, old_indices, old_rows) = {
+    let (updated, new_rows, old_indices, old_rows, updated_indices) = {
         let rows = storage.scan(&table)?;
         let old_rows = rows.to_vec();
         let mut updated = 0usize;
+        let mut updated_indices: Vec<usize> = Vec::new();
         let mut new_rows = rows.to_vec();
         let old_indices: Vec<usize> = (0..rows.len()).collect();
 
@@ -55,53 +91,113 @@ fn handle_update(
                     continue;
                 }
                 let row = &mut new_rows[i];
-                if eval_where_row(row, schema, &filter)? {
-                    for (idx, new_value) in &compiled {
-                        if let Some(slot) = row.get_mut(*idx) {
-                            *slot = new_value.clone();
-                        }
-                    }
+                if where_filter.eval(row)? {
+                    apply_assignments(schema, &compiled, row)?;
                     updated += 1;
+                    updated_indices.push(i);
                 }
             }
         } else {
-            for row in new_rows.iter_mut() {
-                if eval_where_row(row, schema, &filter)? {
-                    for (idx, new_value) in &compiled {
-                        if let Some(slot) = row.get_mut(*idx) {
-                            *slot = new_value.clone();
-                        }
-                    }
+            for (i, row) in new_rows.iter_mut().enumerate() {
+                if where_filter.eval(row)? {
+                    apply_assignments(schema, &compiled, row)?;
                     updated += 1;
+                    updated_indices.push(i);
                 }
             }
         }
 
         validate_all_unique_constraints(schema, &new_rows)?;
-        validate_all_foreign_keys(catalog, storage, schema, &new_rows)?;
+        if !defer_foreign_keys {
+            validate_all_foreign_keys(catalog, storage, schema, &new_rows)?;
+        }
         validate_restrict_on_parent_update(catalog, storage, &table, schema, &old_rows, &new_rows)?;
-        (updated, new_rows, old_indices, old_rows)
+        (updated, new_rows, old_indices, old_rows, updated_indices)
     };
+    let written = returning
+        .is_some()
+        .then(|| updated_indices.iter().map(|&i| new_rows[i].clone()).collect());
     storage.replace_rows_with_alignment(&table, new_rows, old_indices)?;
     let post_parent_rows = storage.scan(&table)?.to_vec();
-    apply_on_update_cascade(catalog, storage, &table, schema, &old_rows, &post_parent_rows)?;
+    let mut cascaded = CascadeCounts::default();
+    apply_on_update_cascade(catalog, storage, &table, schema, &old_rows, &post_parent_rows, &mut cascaded)?;
     storage.rebuild_indexes(&table, schema)?;
 
-    Ok(QueryResult::mutation(
-        format!("updated {} row(s) in {}", updated, table),
+    mutation_result(
+        schema,
+        format!("updated {} row(s) in {}{}", updated, table, cascaded.message_suffix()),
         updated,
-    ))
+        written,
+        returning.as_ref(),
+    )
+}
+
+/// The right-hand side of one `set col = ...` assignment.
+enum AssignSource {
+    Literal(Value),
+    /// Another column of the same row, by index.
+    Column(usize),
+}
+
+/// Applies every assignment to `row` at once: column sources are read from
+/// the row as it was before any of them, so `set a = b, b = a` swaps. A
+/// column of another type converts as if its text had been written.
+fn apply_assignments(
+    schema: &Schema,
+    compiled: &[(usize, AssignSource)],
+    row: &mut Row,
+) -> Result<(), String> {
+    let old = row.clone();
+    for (idx, source) in compiled {
+        let target = &schema.columns[*idx];
+        let value = match source {
+            AssignSource::Literal(v) => v.clone(),
+            AssignSource::Column(src) => {
+                let v = old.get(*src).cloned().unwrap_or(Value::Null);
+                let source = &schema.columns[*src];
+                let v = if v == Value::Null || source.dtype == target.dtype {
+                    v
+                } else {
+                    parse_value(&target.dtype, &value_to_string(&v)).map_err(|e| {
+                        format!(
+                            "Cannot assign column '{}' to '{}': {}",
+                            source.name, target.name, e
+                        )
+                    })?
+                };
+                if target.not_null && v == Value::Null {
+                    return Err(format!("Column '{}' is NOT NULL", target.name));
+                }
+                target.check_json_shape(&v)?;
+                v
+            }
+        };
+        if let Some(slot) = row.get_mut(*idx) {
+            *slot = value;
+        }
+    }
+    Ok(())
 }
 
 fn handle_delete(
     table: String,
     filter: WhereClause,
+    returning: Option<Vec<String>>,
     catalog: &mut Catalog,
     storage: &mut dyn StorageEngine,
 ) -> Result<QueryResult, String> {
     let schema = catalog.schema(&table)?;
-    validate_where_columns(schema, &filter)?;
-    let targeted_row_indices = if simple_eq_filter(&filter).is_some()
+    validate_returning(schema, returning.as_ref())?;
+    validate_where_columns(schema, &filter, &[])?;
+    // A subquery runs here, once, before any row changes.
+    let scope = SubqueryScope {
+        catalog,
+        storage: &*storage,
+    };
+    let where_filter = CompiledWhere::compile_in(schema, &filter, "WHERE", Some(scope))?;
+    let targeted_row_indices = if !storage.indexes_current(&table) {
+        None
+    } else if simple_eq_filter(&filter).is_some()
         && schema.primary_key.len() == 1
         && schema.primary_key.first().is_some_and(|pk| pk == &simple_eq_filter(&filter).expect("eq").0)
     {
@@ -119,6 +215,10 @@ fn handle_delete(
     } else {
         None
     };
+    let targeted_row_indices = match targeted_row_indices {
+        Some(rows) => Some(rows),
+        None => partition_candidates(&table, schema, &filter, storage)?,
+    };
 
     let (deleted, kept_rows, kept_old_indices, deleted_rows) = {
         let rows = storage.scan(&table)?;
@@ -135,7 +235,7 @@ fn handle_delete(
                     kept_old_indices.push(idx);
                     continue;
                 }
-                let should_delete = eval_where_row(row, schema, &filter)?;
+                let should_delete = where_filter.eval(row)?;
                 if should_delete {
                     validate_restrict_on_parent_delete(catalog, storage, &table, schema, row)?;
                     deleted += 1;
@@ -148,7 +248,7 @@ fn handle_delete(
         } else {
             let mut keep_flags: Vec<bool> = Vec::with_capacity(rows.len());
             for row in rows.iter() {
-                let should_delete = eval_where_row(row, schema, &filter)?;
+                let should_delete = where_filter.eval(row)?;
                 keep_flags.push(!should_delete);
             }
 
@@ -166,12 +266,16 @@ fn handle_delete(
         (deleted, kept_rows, kept_old_indices, deleted_rows)
     };
     storage.replace_rows_with_alignment(&table, kept_rows, kept_old_indices)?;
-    apply_on_delete_cascade(catalog, storage, &table, schema, &deleted_rows)?;
+    let mut cascaded = CascadeCounts::default();
+    apply_on_delete_cascade(catalog, storage, &table, schema, &deleted_rows, &mut cascaded)?;
     storage.rebuild_indexes(&table, schema)?;
 
-    Ok(QueryResult::mutation(
-        format!("deleted {} row(s) from {}", deleted, table),
+    mutation_result(
+        schema,
+        format!("deleted {} row(s) from {}{}", deleted, table, cascaded.message_suffix()),
         deleted,
-    ))
+        returning.is_some().then_some(deleted_rows),
+        returning.as_ref(),
+    )
 }
 
@@ -0,0 +1,540 @@
+/// Resolves a parsed command against the catalog without executing it.
+/// Name resolution reuses the executor's helpers, so "Unknown column" and
+/// "Ambiguous column" errors match what execution would report.
+pub fn describe_command(cmd: Command, catalog: &Catalog) -> Result<StatementPlan, String> {
+    match cmd {
+        Command::Select {
+            table,
+            distinct,
+            join,
+            columns,
+            filter,
+            group_by,
+            having,
+            order_by,
+            ..
+        } => plan_select(
+            table, distinct, join, columns, filter, group_by, having, order_by, catalog,
+        ),
+        Command::SetOp {
+            kind,
+            left,
+            right,
+            all,
+        } => plan_set_op(kind, *left, *right, all, catalog),
+        Command::Insert {
+            table,
+            values,
+            returning,
+        } => {
+            let mut plan = plan_insert(table.clone(), values, catalog)?;
+            plan_returning(&table, returning, catalog, &mut plan)?;
+            Ok(plan)
+        }
+        Command::InsertSelect {
+            table,
+            columns,
+            query,
+        } => plan_insert_select(table, columns, *query, catalog),
+        Command::Update {
+            table,
+            assignments,
+            filter,
+            returning,
+        } => {
+            let schema = catalog.schema(&table)?;
+            let mut plan = table_plan("update", &table, schema);
+            for a in &assignments {
+                let idx = schema
+                    .columns
+                    .iter()
+                    .position(|c| c.name == a.column)
+                    .ok_or_else(|| format!("Unknown column '{}' in UPDATE", a.column))?;
+                plan.bindings.push(input_binding("SET", &a.column, idx));
+            }
+            bind_where_columns(schema, &filter, "WHERE", BindingTarget::Input, &mut plan.bindings)?;
+            plan.access = Some(filter_access_path(schema, Some(&filter), false));
+            plan_returning(&table, returning, catalog, &mut plan)?;
+            Ok(plan)
+        }
+        Command::Delete {
+            table,
+            filter,
+            returning,
+        } => {
+            let schema = catalog.schema(&table)?;
+            let mut plan = table_plan("delete", &table, schema);
+            bind_where_columns(schema, &filter, "WHERE", BindingTarget::Input, &mut plan.bindings)?;
+            plan.access = Some(filter_access_path(schema, Some(&filter), false));
+            plan_returning(&table, returning, catalog, &mut plan)?;
+            Ok(plan)
+        }
+        Command::Describe { table } => Ok(table_plan("describe", &table, catalog.schema(&table)?)),
+        Command::ShowIndexes { table } => Ok(table_plan("show indexes", &table, catalog.schema(&table)?)),
+        Command::Create { table, columns, .. } => Ok(StatementPlan {
+            statement: "create table".to_string(),
+            tables: vec![table],
+            access: None,
+            columns: plan_column_defs(&columns),
+            bindings: Vec::new(),
+            aliases: Vec::new(),
+            output: Vec::new(),
+        }),
+        Command::CreateTableAs { table, query } => plan_create_table_as(table, *query, catalog),
+        Command::CreateIndex { table, columns, .. } => plan_index("create index", table, columns, catalog),
+        Command::DropIndex { table, columns, .. } => plan_index("drop index", table, columns, catalog),
+        Command::Comment { target, .. } => {
+            let (table, column) = match target {
+                CommentTarget::Table(table) => (table, None),
+                CommentTarget::Column { table, column } => (table, Some(column)),
+            };
+            let schema = catalog.schema(&table)?;
+            let mut plan = table_plan("comment", &table, schema);
+            if let Some(column) = column {
+                let idx = schema
+                    .columns
+                    .iter()
+                    .position(|c| c.name == column)
+                    .ok_or_else(|| format!("Unknown column '{}'", column))?;
+                plan.bindings.push(input_binding("COMMENT", &column, idx));
+            }
+            Ok(plan)
+        }
+        Command::Alter { table, .. } => Ok(table_plan("alter table", &table, catalog.schema(&table)?)),
+        Command::CreateTrigger { table, .. } => {
+            Ok(table_plan("create trigger", &table, catalog.schema(&table)?))
+        }
+        Command::DropTrigger { .. } => Ok(control_plan("drop trigger")),
+        Command::CreateView { .. } => Ok(control_plan("create view")),
+        Command::DropView { .. } => Ok(control_plan("drop view")),
+        Command::RefreshMaterializedView { .. } => Ok(control_plan("refresh materialized view")),
+        Command::ShowTables { .. } => Ok(control_plan("show tables")),
+        Command::Checkpoint => Ok(control_plan("checkpoint")),
+        Command::ShowWal => Ok(control_plan("show wal")),
+        Command::Begin => Ok(control_plan("begin")),
+        Command::Commit => Ok(control_plan("commit")),
+        Command::Rollback => Ok(control_plan("rollback")),
+        Command::ShowTransaction => Ok(control_plan("show transaction")),
+        Command::Set { .. } => Ok(control_plan("set")),
+        Command::Show { .. } => Ok(control_plan("show")),
+        Command::CreateSnapshot { .. } => Ok(control_plan("create snapshot")),
+        Command::DropSnapshot { .. } => Ok(control_plan("drop snapshot")),
+        Command::ListSnapshots => Ok(control_plan("list snapshots")),
+        Command::Attach { .. } => Ok(control_plan("attach")),
+        Command::Detach { .. } => Ok(control_plan("detach")),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plan_select(
+    table: String,
+    distinct: bool,
+    join: Option<JoinClause>,
+    columns: Option<Vec<String>>,
+    filter: Option<WhereClause>,
+    group_by: Option<Vec<String>>,
+    having: Option<WhereClause>,
+    order_by: Option<OrderBy>,
+    catalog: &Catalog,
+) -> Result<StatementPlan, String> {
+    let mut bindings: Vec<ColumnBinding> = Vec::new();
+    let (schema, access, mut tables) = if let Some(j) = &join {
+        let (schema, lidx, ridx) = resolve_join_schema(catalog, &table, j)?;
+        let ridx = catalog.schema(&table)?.columns.len() + ridx;
+        for operand in [&j.left_column, &j.right_column] {
+            let idx = resolve_column_index(&schema, operand, "JOIN")?;
+            bindings.push(input_binding("JOIN", operand, idx));
+        }
+        let access = AccessPath::HashJoin {
+            left: schema.columns[lidx].name.clone(),
+            right: schema.columns[ridx].name.clone(),
+        };
+        (schema, access, vec![table.clone(), j.table.clone()])
+    } else {
+        let schema = catalog.schema(&table)?.clone();
+        let access = filter_access_path(&schema, filter.as_ref(), true);
+        (schema, access, vec![table.clone()])
+    };
+
+    if let Some(where_clause) = filter.as_ref() {
+        bind_where_columns(&schema, where_clause, "WHERE", BindingTarget::Input, &mut bindings)?;
+        for sub in crate::restricted::subquery_tables(where_clause) {
+            catalog.schema(sub)?;
+            if !tables.iter().any(|t| t == sub) {
+                tables.push(sub.to_string());
+            }
+        }
+    }
+
+    validate_star_projection(columns.as_ref(), group_by.as_ref())?;
+    let mut aliases: Vec<AliasBinding> = Vec::new();
+    for item in columns.iter().flatten() {
+        let (expr, alias) = split_select_alias(item);
+        if let Some(alias) = alias {
+            aliases.push(AliasBinding {
+                alias,
+                expr: expr.clone(),
+            });
+        }
+        if let Some((_, arg, _)) = parse_aggregate_expr_extended(&expr) {
+            if arg != "*" {
+                let idx = resolve_column_index(&schema, &arg, "SELECT aggregate")?;
+                bindings.push(input_binding("SELECT", &arg, idx));
+            }
+        } else {
+            let idx = resolve_scalar_expr(&schema, &expr, "SELECT list")?.source_index();
+            bindings.push(input_binding("SELECT", &expr, idx));
+        }
+    }
+
+    let order_names: Vec<String> = order_by
+        .iter()
+        .flat_map(|ob| std::iter::once(ob.column.clone()).chain(ob.then_by.iter().map(|(c, _)| c.clone())))
+        .collect();
+
+    let out_schema = if has_group_or_aggregate(columns.as_ref(), group_by.as_ref()) {
+        for g in group_by.iter().flatten() {
+            let idx = resolve_scalar_expr(&schema, g, "GROUP BY")?.source_index();
+            bindings.push(input_binding("GROUP BY", g, idx));
+        }
+        let (post_schema, _) = evaluate_grouped_select(&schema, &[], columns.as_ref(), group_by.as_ref())?;
+        if let Some(having_clause) = having.as_ref() {
+            bind_where_columns(&post_schema, having_clause, "HAVING", BindingTarget::Output, &mut bindings)?;
+        }
+        for name in &order_names {
+            let idx = resolve_grouped_order_column(&schema, &post_schema, columns.as_ref(), name)?;
+            bindings.push(output_binding("ORDER BY", name, idx));
+        }
+        post_schema
+    } else {
+        if having.is_some() {
+            return Err("HAVING requires GROUP BY or aggregate functions".to_string());
+        }
+        let (out_schema, _) = project_rows(&schema, &[], columns.as_ref())?;
+        for name in &order_names {
+            if distinct {
+                let idx = resolve_distinct_order_column(&schema, &out_schema, columns.as_ref(), name)?;
+                bindings.push(output_binding("ORDER BY", name, idx));
+                continue;
+            }
+            let alias_idx = aliases
+                .iter()
+                .find(|a| &a.alias == name)
+                .and_then(|a| resolve_column_index(&schema, &a.expr, "SELECT list").ok());
+            let idx = match alias_idx {
+                Some(idx) => idx,
+                None => resolve_column_index(&schema, name, "ORDER BY")?,
+            };
+            bindings.push(input_binding("ORDER BY", name, idx));
+        }
+        out_schema
+    };
+
+    Ok(StatementPlan {
+        statement: "select".to_string(),
+        tables,
+        access: Some(access),
+        columns: plan_columns(&schema),
+        bindings,
+        aliases,
+        output: plan_columns(&out_schema),
+    })
+}
+
+fn plan_insert(table: String, values: Vec<String>, catalog: &Catalog) -> Result<StatementPlan, String> {
+    let schema = catalog.schema(&table)?;
+    if values.len() > schema.column_count() {
+        return Err(insert_value_count_error(schema, values.len()));
+    }
+    let mut plan = table_plan("insert", &table, schema);
+    for (i, col) in schema.columns.iter().enumerate() {
+        match (values.get(i), col.default.as_ref()) {
+            (Some(v), _) => plan.bindings.push(input_binding("VALUES", v, i)),
+            (None, Some(d)) => plan.bindings.push(input_binding("DEFAULT", d, i)),
+            (None, None) => {
+                return Err(format!(
+                    "{}. Missing column '{}' has no DEFAULT",
+                    insert_value_count_error(schema, values.len()),
+                    col.name
+                ));
+            }
+        }
+    }
+    Ok(plan)
+}
+
+/// The plan of an INSERT ... SELECT: the target table's, binding each
+/// column to the SELECT column or DEFAULT that fills it, with the tables
+/// the SELECT reads after the target.
+fn plan_insert_select(
+    table: String,
+    columns: Option<Vec<String>>,
+    query: Command,
+    catalog: &Catalog,
+) -> Result<StatementPlan, String> {
+    let schema = catalog.schema(&table)?;
+    let (source, source_columns) = plan_source(query, catalog)?;
+    let sources = insert_select_sources(schema, columns.as_deref(), &source_columns)?;
+    let mut plan = table_plan("insert", &table, schema);
+    for (i, (col, src)) in schema.columns.iter().zip(sources).enumerate() {
+        match (src, col.default.as_ref()) {
+            (Some(src), _) => plan
+                .bindings
+                .push(input_binding("SELECT", &source_columns[src].0, i)),
+            (None, Some(d)) => plan.bindings.push(input_binding("DEFAULT", d, i)),
+            (None, None) => plan.bindings.push(input_binding("DEFAULT", "null", i)),
+        }
+    }
+    for t in source.tables {
+        if !plan.tables.contains(&t) {
+            plan.tables.push(t);
+        }
+    }
+    Ok(plan)
+}
+
+/// The plan of the SELECT feeding an INSERT ... SELECT or CREATE TABLE
+/// ... AS, with its output columns as names and types.
+fn plan_source(query: Command, catalog: &Catalog) -> Result<(StatementPlan, Vec<(String, DataType)>), String> {
+    let source = describe_command(query, catalog)?;
+    let source_columns = source
+        .output
+        .iter()
+        .map(|c| Ok((c.name.clone(), parse_datatype(&c.dtype)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok((source, source_columns))
+}
+
+fn plan_create_table_as(table: String, query: Command, catalog: &Catalog) -> Result<StatementPlan, String> {
+    let (source, source_columns) = plan_source(query, catalog)?;
+    let columns = create_table_as_columns(&source_columns)?;
+    let mut tables = vec![table];
+    for t in source.tables {
+        if !tables.contains(&t) {
+            tables.push(t);
+        }
+    }
+    Ok(StatementPlan {
+        statement: "create table".to_string(),
+        tables,
+        access: None,
+        columns: plan_column_defs(&columns),
+        bindings: source_columns
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| input_binding("SELECT", name, i))
+            .collect(),
+        aliases: Vec::new(),
+        output: Vec::new(),
+    })
+}
+
+fn plan_index(
+    statement: &str,
+    table: String,
+    columns: Vec<String>,
+    catalog: &Catalog,
+) -> Result<StatementPlan, String> {
+    let schema = catalog.schema(&table)?;
+    let mut plan = table_plan(statement, &table, schema);
+    for c in &columns {
+        let idx = resolve_column_index(schema, c, "INDEX")?;
+        plan.bindings.push(input_binding("INDEX", c, idx));
+    }
+    Ok(plan)
+}
+
+/// The first access path `filter` qualifies for, in the order the executor
+/// tries them: an equality lookup, a trigram candidate set (select only,
+/// with `trigram`), then partition pruning.
+fn filter_access_path(schema: &Schema, filter: Option<&WhereClause>, trigram: bool) -> AccessPath {
+    match eq_access_path(schema, filter) {
+        AccessPath::FullScan if trigram => match like_access_path(schema, filter) {
+            AccessPath::FullScan => partition_access_path(schema, filter),
+            access => access,
+        },
+        AccessPath::FullScan => partition_access_path(schema, filter),
+        access => access,
+    }
+}
+
+/// Mirrors the single-column equality fast paths in select/update/delete.
+fn eq_access_path(schema: &Schema, filter: Option<&WhereClause>) -> AccessPath {
+    let Some((col, _)) = filter.and_then(simple_eq_filter) else {
+        return AccessPath::FullScan;
+    };
+    if schema.primary_key.len() == 1 && schema.primary_key[0] == col {
+        return AccessPath::PrimaryKey { column: col };
+    }
+    let single = [col.clone()];
+    let is_unique = schema.unique_constraints.iter().any(|g| g[..] == single)
+        || schema
+            .columns
+            .iter()
+            .any(|c| c.name == col && c.unique && !c.primary_key);
+    if is_unique {
+        return AccessPath::UniqueIndex { column: col };
+    }
+    if schema.secondary_indexes.iter().any(|g| g[..] == single) {
+        return AccessPath::SecondaryIndex { column: col };
+    }
+    AccessPath::FullScan
+}
+
+/// Mirrors the `using trigram` candidate path in select: a `like` term on a
+/// trigram-indexed column whose pattern has a literal run of 3+ characters.
+fn like_access_path(schema: &Schema, filter: Option<&WhereClause>) -> AccessPath {
+    let served = filter.into_iter().flat_map(like_terms).find(|p| {
+        schema.trigram_indexes.contains(&p.column)
+            && p.value.split(['*', '?']).any(|run| run.chars().count() >= 3)
+    });
+    match served {
+        Some(p) => AccessPath::TrigramIndex {
+            column: p.column.clone(),
+        },
+        None => AccessPath::FullScan,
+    }
+}
+
+/// Mirrors partition pruning in select/update/delete: an AND term that
+/// reads only the `partition by` column.
+fn partition_access_path(schema: &Schema, filter: Option<&WhereClause>) -> AccessPath {
+    let Some(col) = &schema.partition_by else {
+        return AccessPath::FullScan;
+    };
+    let prunes = filter.into_iter().flat_map(where_conjuncts).any(|term| {
+        !contains_exists(term)
+            && where_column_names(term).iter().all(|c| {
+            resolve_column_index(schema, c, "WHERE").is_ok_and(|idx| schema.columns[idx].name == *col)
+        })
+    });
+    if prunes {
+        AccessPath::PartitionPruning { column: col.clone() }
+    } else {
+        AccessPath::FullScan
+    }
+}
+
+fn bind_where_columns(
+    schema: &Schema,
+    clause: &WhereClause,
+    label: &str,
+    target: BindingTarget,
+    out: &mut Vec<ColumnBinding>,
+) -> Result<(), String> {
+    match clause {
+        WhereClause::Predicate(p) => {
+            let index = resolve_column_index(schema, &p.column, label)?;
+            out.push(ColumnBinding {
+                clause: label.to_string(),
+                name: p.column.clone(),
+                target,
+                index,
+            });
+            Ok(())
+        }
+        WhereClause::Binary { left, right, .. } => {
+            bind_where_columns(schema, left, label, target, out)?;
+            bind_where_columns(schema, right, label, target, out)
+        }
+        WhereClause::Exists { subquery, .. } => {
+            for (_, outer) in &subquery.correlation {
+                let index = resolve_column_index(schema, outer, label)?;
+                out.push(ColumnBinding {
+                    clause: label.to_string(),
+                    name: outer.clone(),
+                    target,
+                    index,
+                });
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Lists a RETURNING clause's columns as the plan's output; a mutation
+/// without one has no output columns.
+fn plan_returning(
+    table: &str,
+    returning: Option<Vec<String>>,
+    catalog: &Catalog,
+    plan: &mut StatementPlan,
+) -> Result<(), String> {
+    let Some(columns) = returning else {
+        return Ok(());
+    };
+    let schema = catalog.schema(table)?;
+    let out_schema = match resolve_projection(schema, Some(&columns))? {
+        Some((projected, _)) => projected,
+        None => schema.clone(),
+    };
+    plan.output = plan_columns(&out_schema);
+    Ok(())
+}
+
+fn table_plan(statement: &str, table: &str, schema: &Schema) -> StatementPlan {
+    StatementPlan {
+        statement: statement.to_string(),
+        tables: vec![table.to_string()],
+        access: None,
+        columns: plan_columns(schema),
+        bindings: Vec::new(),
+        aliases: Vec::new(),
+        output: Vec::new(),
+    }
+}
+
+fn control_plan(statement: &str) -> StatementPlan {
+    StatementPlan {
+        statement: statement.to_string(),
+        tables: Vec::new(),
+        access: None,
+        columns: Vec::new(),
+        bindings: Vec::new(),
+        aliases: Vec::new(),
+        output: Vec::new(),
+    }
+}
+
+fn plan_columns(schema: &Schema) -> Vec<PlanColumn> {
+    schema
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(index, c)| PlanColumn {
+            index,
+            name: c.name.clone(),
+            dtype: datatype_name(&c.dtype),
+        })
+        .collect()
+}
+
+fn plan_column_defs(columns: &[ColumnDef]) -> Vec<PlanColumn> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, c)| PlanColumn {
+            index,
+            name: c.name.clone(),
+            dtype: datatype_name(&c.dtype),
+        })
+        .collect()
+}
+
+fn input_binding(clause: &str, name: &str, index: usize) -> ColumnBinding {
+    ColumnBinding {
+        clause: clause.to_string(),
+        name: name.to_string(),
+        target: BindingTarget::Input,
+        index,
+    }
+}
+
+fn output_binding(clause: &str, name: &str, index: usize) -> ColumnBinding {
+    ColumnBinding {
+        clause: clause.to_string(),
+        name: name.to_string(),
+        target: BindingTarget::Output,
+        index,
+    }
+}
@@ -120,17 +120,44 @@ fn incoming_foreign_keys(catalog: &Catalog, parent_table: &str) -> Vec<(String,
     out
 }
 
+/// Child rows deleted, rewritten or nulled by foreign key actions during
+/// one statement, per table in the order the tables were first reached.
+#[derive(Debug, Default)]
+struct CascadeCounts(Vec<(String, usize)>);
+
+impl CascadeCounts {
+    fn add(&mut self, table: &str, rows: usize) {
+        if rows == 0 {
+            return;
+        }
+        match self.0.iter_mut().find(|(t, _)| t == table) {
+            Some((_, n)) => *n += rows,
+            None => self.0.push((table.to_string(), rows)),
+        }
+    }
+
+    /// ` (cascaded: 3 orders, 5 items)`, or empty when nothing cascaded.
+    fn message_suffix(&self) -> String {
+        if self.0.is_empty() {
+            return String::new();
+        }
+        let parts: Vec<String> = self.0.iter().map(|(t, n)| format!("{n} {t}")).collect();
+        format!(" (cascaded: {})", parts.join(", "))
+    }
+}
+
 fn apply_on_delete_cascade(
     catalog: &Catalog,
     storage: &mut dyn StorageEngine,
     parent_table: &str,
     parent_schema: &Schema,
     deleted_parent_rows: &[Row],
+    counts: &mut CascadeCounts,
 ) -> Result<(), String> {
     if deleted_parent_rows.is_empty() {
         return Ok(());
     }
-    apply_on_delete_set_null(catalog, storage, parent_table, parent_schema, deleted_parent_rows)?;
+    apply_on_delete_set_null(catalog, storage, parent_table, parent_schema, deleted_parent_rows, counts)?;
     for (child_table, fk) in incoming_foreign_keys(catalog, parent_table) {
         if fk.on_delete != ForeignKeyAction::Cascade {
             continue;
@@ -146,7 +173,7 @@ fn apply_on_delete_cascade(
         for (idx, cr) in child_rows.iter().enumerate() {
             let referenced = deleted_parent_rows
                 .iter()
-                .any(|pr| tuple_eq(cr, &child_idxs, pr, &parent_idxs));
+                .any(|pr| fk_references(cr, &child_idxs, pr, &parent_idxs));
             if !referenced {
                 keep_rows.push(cr.clone());
                 keep_old_indices.push(idx);
@@ -157,7 +184,8 @@ fn apply_on_delete_cascade(
         }
         storage.replace_rows_with_alignment(&child_table, keep_rows, keep_old_indices)?;
         storage.rebuild_indexes(&child_table, child_schema)?;
-        apply_on_delete_cascade(catalog, storage, &child_table, child_schema, &deleted_child_rows)?;
+        counts.add(&child_table, deleted_child_rows.len());
+        apply_on_delete_cascade(catalog, storage, &child_table, child_schema, &deleted_child_rows, counts)?;
     }
     Ok(())
 }
@@ -168,6 +196,7 @@ fn apply_on_delete_set_null(
     parent_table: &str,
     parent_schema: &Schema,
     deleted_parent_rows: &[Row],
+    counts: &mut CascadeCounts,
 ) -> Result<(), String> {
     for (child_table, fk) in incoming_foreign_keys(catalog, parent_table) {
         if fk.on_delete != ForeignKeyAction::SetNull {
@@ -188,19 +217,24 @@ fn apply_on_delete_set_null(
         }
 
         let mut updated_child_rows = child_rows.to_vec();
+        let mut nulled = 0usize;
         for cr in &mut updated_child_rows {
             let referenced = deleted_parent_rows
                 .iter()
-                .any(|pr| tuple_eq(cr, &child_idxs, pr, &parent_idxs));
+                .any(|pr| fk_references(cr, &child_idxs, pr, &parent_idxs));
             if referenced {
                 for ci in &child_idxs {
                     cr[*ci] = Value::Null;
                 }
+                nulled += 1;
             }
         }
+        counts.add(&child_table, nulled);
 
+        // Only NULLs were written, which satisfy every foreign key. Other
+        // keys on these rows may still point at the changed parent rows
+        // until the CASCADE pass that follows, so they are not checked here.
         validate_all_unique_constraints(child_schema, &updated_child_rows)?;
-        validate_all_foreign_keys(catalog, storage, child_schema, &updated_child_rows)?;
         let keep_old_indices: Vec<usize> = (0..updated_child_rows.len()).collect();
         storage.replace_rows_with_alignment(&child_table, updated_child_rows, keep_old_indices)?;
         storage.rebuild_indexes(&child_table, child_schema)?;
@@ -215,11 +249,20 @@ fn apply_on_update_cascade(
     parent_schema: &Schema,
     old_parent_rows: &[Row],
     new_parent_rows: &[Row],
+    counts: &mut CascadeCounts,
 ) -> Result<(), String> {
     if old_parent_rows.len() != new_parent_rows.len() {
         return Err("Internal error: parent row alignment mismatch during ON UPDATE CASCADE".to_string());
     }
-    apply_on_update_set_null(catalog, storage, parent_table, parent_schema, old_parent_rows, new_parent_rows)?;
+    apply_on_update_set_null(
+        catalog,
+        storage,
+        parent_table,
+        parent_schema,
+        old_parent_rows,
+        new_parent_rows,
+        counts,
+    )?;
     for (child_table, fk) in incoming_foreign_keys(catalog, parent_table) {
         if fk.on_update != ForeignKeyAction::Cascade {
             continue;
@@ -231,17 +274,22 @@ fn apply_on_update_cascade(
         let parent_idxs = resolve_cols_to_idxs(parent_schema, &fk.ref_columns)?;
 
         let mut updated_child_rows = old_child_rows.clone();
+        let mut rewritten = 0usize;
         for cr in &mut updated_child_rows {
+            let mut changed = false;
             for (old_pr, new_pr) in old_parent_rows.iter().zip(new_parent_rows.iter()) {
-                if tuple_eq(cr, &child_idxs, old_pr, &parent_idxs)
+                if fk_references(cr, &child_idxs, old_pr, &parent_idxs)
                     && !tuple_eq(old_pr, &parent_idxs, new_pr, &parent_idxs)
                 {
                     for (ci, pi) in child_idxs.iter().zip(parent_idxs.iter()) {
                         cr[*ci] = new_pr[*pi].clone();
                     }
+                    changed = true;
                 }
             }
+            rewritten += usize::from(changed);
         }
+        counts.add(&child_table, rewritten);
 
         validate_all_unique_constraints(child_schema, &updated_child_rows)?;
         validate_all_foreign_keys(catalog, storage, child_schema, &updated_child_rows)?;
@@ -255,6 +303,7 @@ fn apply_on_update_cascade(
             child_schema,
             &old_child_rows,
             &post_child_rows,
+            counts,
         )?;
         storage.rebuild_indexes(&child_table, child_schema)?;
     }
@@ -268,6 +317,7 @@ fn apply_on_update_set_null(
     parent_schema: &Schema,
     old_parent_rows: &[Row],
     new_parent_rows: &[Row],
+    counts: &mut CascadeCounts,
 ) -> Result<(), String> {
     for (child_table, fk) in incoming_foreign_keys(catalog, parent_table) {
         if fk.on_update != ForeignKeyAction::SetNull {
@@ -288,20 +338,27 @@ fn apply_on_update_set_null(
         }
 
         let mut updated_child_rows = child_rows.to_vec();
+        let mut nulled = 0usize;
         for cr in &mut updated_child_rows {
+            let mut changed = false;
             for (old_pr, new_pr) in old_parent_rows.iter().zip(new_parent_rows.iter()) {
-                if tuple_eq(cr, &child_idxs, old_pr, &parent_idxs)
+                if fk_references(cr, &child_idxs, old_pr, &parent_idxs)
                     && !tuple_eq(old_pr, &parent_idxs, new_pr, &parent_idxs)
                 {
                     for ci in &child_idxs {
                         cr[*ci] = Value::Null;
                     }
+                    changed = true;
                 }
             }
+            nulled += usize::from(changed);
         }
+        counts.add(&child_table, nulled);
 
+        // Only NULLs were written, which satisfy every foreign key. Other
+        // keys on these rows may still point at the changed parent rows
+        // until the CASCADE pass that follows, so they are not checked here.
         validate_all_unique_constraints(child_schema, &updated_child_rows)?;
-        validate_all_foreign_keys(catalog, storage, child_schema, &updated_child_rows)?;
         let keep_old_indices: Vec<usize> = (0..updated_child_rows.len()).collect();
         storage.replace_rows_with_alignment(&child_table, updated_child_rows, keep_old_indices)?;
         storage.rebuild_indexes(&child_table, child_schema)?;
@@ -328,6 +385,16 @@ fn tuple_eq(a_row: &Row, a_idxs: &[usize], b_row: &Row, b_idxs: &[usize]) -> boo
         .all(|(ai, bi)| a_row.get(*ai) == b_row.get(*bi))
 }
 
+/// Whether `child_row` points at `parent_row` through a foreign key. A
+/// child key containing NULL references nothing, even a parent row whose
+/// UNIQUE key is NULL; children nulled by SET NULL stay detached.
+fn fk_references(child_row: &Row, child_idxs: &[usize], parent_row: &Row, parent_idxs: &[usize]) -> bool {
+    !child_idxs
+        .iter()
+        .any(|i| matches!(child_row.get(*i), Some(Value::Null)))
+        && tuple_eq(child_row, child_idxs, parent_row, parent_idxs)
+}
+
 fn fk_parent_exists(
     _catalog: &Catalog,
     storage: &dyn StorageEngine,
@@ -358,12 +425,24 @@ fn fk_parent_exists(
                 return Ok(true);
             }
         }
+    } else if storage.indexes_current(parent_table) {
+        // Composite PRIMARY KEY or UNIQUE parent: probe its tuple index.
+        let key = child_idxs
+            .iter()
+            .map(|i| child_row.get(*i).cloned().unwrap_or(Value::Null))
+            .collect::<Vec<_>>();
+        if storage
+            .lookup_key_row_index(parent_table, parent_idxs, &key)?
+            .is_some()
+        {
+            return Ok(true);
+        }
     }
 
     let parent_rows = storage.scan(parent_table)?;
     Ok(parent_rows
         .iter()
-        .any(|pr| tuple_eq(child_row, child_idxs, pr, parent_idxs)))
+        .any(|pr| fk_references(child_row, child_idxs, pr, parent_idxs)))
 }
 
 fn fk_child_references_parent(
@@ -407,5 +486,5 @@ fn fk_child_references_parent(
     let child_rows = storage.scan(child_table)?;
     Ok(child_rows
         .iter()
-        .any(|cr| tuple_eq(cr, child_idxs, parent_row, parent_idxs)))
+        .any(|cr| fk_references(cr, child_idxs, parent_row, parent_idxs)))
 }
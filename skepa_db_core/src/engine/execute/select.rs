@@ -10,12 +10,17 @@ fn handle_select(
     order_by: Option<OrderBy>,
     limit: Option<usize>,
     offset: Option<usize>,
-    catalog: &mut Catalog,
-    storage: &mut dyn StorageEngine,
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+    max_join_rows: Option<usize>,
 ) -> Result<QueryResult, String> {
+    validate_star_projection(columns.as_ref(), group_by.as_ref())?;
     let is_join = join.is_some();
-    let (select_schema, base_rows): (Schema, Option<Vec<Row>>) = if let Some(join_clause) = join {
-        let (schema, rows) = build_join_rows(catalog, storage, &table, &join_clause)?;
+    let mut left_stats: Option<ExecutionStats> = None;
+    let (select_schema, base_rows): (Schema, Option<KeyedRows>) = if let Some(join_clause) = join {
+        let (schema, rows, left) =
+            build_join_rows(catalog, storage, &table, &join_clause, filter.as_ref(), max_join_rows)?;
+        left_stats = Some(left);
         (schema, Some(rows))
     } else {
         let schema = catalog.schema(&table)?;
@@ -23,8 +28,32 @@ fn handle_select(
     };
     let mut stats = ExecutionStats::default();
 
-    let filtered_rows = if let Some(where_clause) = filter {
-        let simple_eq = simple_eq_filter(&where_clause);
+    // Validate before any index fast path so unknown-column errors match
+    // UPDATE and DELETE regardless of which access path would be taken.
+    if let Some(where_clause) = filter.as_ref() {
+        let aliases: Vec<String> = columns
+            .iter()
+            .flatten()
+            .filter_map(|c| split_select_alias(c).1)
+            .collect();
+        validate_where_columns(&select_schema, where_clause, &aliases)?;
+        validate_where_literals(&select_schema, where_clause, "WHERE")?;
+    }
+
+    let is_grouped = has_group_or_aggregate(columns.as_ref(), group_by.as_ref());
+    // Without ordering, grouping or DISTINCT the first offset + limit
+    // matches in row order are the result, so scanning can stop there.
+    let scan_cap = limit
+        .filter(|_| !is_grouped && !distinct && order_by.is_none() && having.is_none())
+        .map(|n| n.saturating_add(offset.unwrap_or(0)));
+
+    let scope = SubqueryScope { catalog, storage };
+    let compiled = filter
+        .as_ref()
+        .map(|clause| CompiledWhere::compile_in(&select_schema, clause, "WHERE", Some(scope)))
+        .transpose()?;
+    let filtered_rows = if let Some(where_clause) = filter.as_ref() {
+        let simple_eq = simple_eq_filter(where_clause).filter(|_| storage.indexes_current(&table));
         if !is_join
             && simple_eq.is_some()
             && select_schema.primary_key.len() == 1
@@ -38,14 +67,10 @@ fn handle_select(
             };
             stats.rows_scanned = Some(1);
             stats.index_used = Some(true);
-            if let Some(row_idx) = storage.lookup_pk_row_index(&table, &select_schema, &val)? {
-                match storage.row(&table, row_idx)? {
-                    Some(r) => vec![r.clone()],
-                    None => Vec::new(),
-                }
-            } else {
-                Vec::new()
-            }
+            let row_idx = storage.lookup_pk_row_index(&table, &select_schema, &val)?;
+            rows_at(&table, storage, row_idx)?
+                .map(|(key, r)| (key, r.clone()))
+                .collect()
         } else if !is_join && simple_eq.is_some() {
             let Some((col, val)) = simple_eq else {
                 return Err("Internal error: expected simple equality filter".to_string());
@@ -55,39 +80,71 @@ fn handle_select(
             {
                 stats.rows_scanned = Some(1);
                 stats.index_used = Some(true);
-                match storage.row(&table, row_idx)? {
-                    Some(r) => vec![r.clone()],
-                    None => Vec::new(),
-                }
+                rows_at(&table, storage, [row_idx])?
+                    .map(|(key, r)| (key, r.clone()))
+                    .collect()
             } else if let Some(row_indices) =
                 storage.lookup_secondary_row_indices(&table, &select_schema, &col, &val)?
             {
-                stats.rows_scanned = Some(row_indices.len());
+                let candidates = row_indices.len().min(scan_cap.unwrap_or(usize::MAX));
+                stats.rows_scanned = Some(candidates);
                 stats.index_used = Some(true);
-                row_indices
-                    .into_iter()
-                    .filter_map(|i| storage.row(&table, i).ok().flatten().cloned())
+                rows_at(&table, storage, row_indices.into_iter().take(candidates))?
+                    .map(|(key, r)| (key, r.clone()))
                     .collect()
+            } else if let Some(row_indices) =
+                partition_candidates(&table, &select_schema, where_clause, storage)?
+            {
+                let candidates = rows_at(&table, storage, row_indices)?;
+                let (rows, visited) = scan_rows(candidates, compiled.as_ref(), scan_cap)?;
+                stats.rows_scanned = Some(visited);
+                stats.index_used = Some(false);
+                rows
             } else {
-                let rows = load_base_rows(&table, storage, base_rows.as_ref())?;
-                stats.rows_scanned = Some(rows.len());
+                let base = keyed_base_rows(&table, storage, base_rows.as_ref())?;
+                let (rows, visited) = scan_rows(base, compiled.as_ref(), scan_cap)?;
+                stats.rows_scanned = Some(visited);
                 stats.index_used = Some(false);
-                filter_rows(&select_schema, &rows, &where_clause)?
+                rows
             }
+        } else if !is_join
+            && let Some(row_indices) = trigram_candidates(&table, &select_schema, where_clause, storage)?
+        {
+            let candidates = rows_at(&table, storage, row_indices)?;
+            let (rows, visited) = scan_rows(candidates, compiled.as_ref(), scan_cap)?;
+            stats.rows_scanned = Some(visited);
+            stats.index_used = Some(true);
+            rows
+        } else if !is_join
+            && let Some(row_indices) =
+                partition_candidates(&table, &select_schema, where_clause, storage)?
+        {
+            // Pruning skips whole segments but is not an index lookup.
+            let candidates = rows_at(&table, storage, row_indices)?;
+            let (rows, visited) = scan_rows(candidates, compiled.as_ref(), scan_cap)?;
+            stats.rows_scanned = Some(visited);
+            stats.index_used = Some(false);
+            rows
         } else {
-            let rows = load_base_rows(&table, storage, base_rows.as_ref())?;
-            stats.rows_scanned = Some(rows.len());
+            let base = keyed_base_rows(&table, storage, base_rows.as_ref())?;
+            let (rows, visited) = scan_rows(base, compiled.as_ref(), scan_cap)?;
+            stats.rows_scanned = Some(visited);
             stats.index_used = Some(false);
-            filter_rows(&select_schema, &rows, &where_clause)?
+            rows
         }
     } else {
-        let rows = load_base_rows(&table, storage, base_rows.as_ref())?;
-        stats.rows_scanned = Some(rows.len());
+        let base = keyed_base_rows(&table, storage, base_rows.as_ref())?;
+        let (rows, visited) = scan_rows(base, None, scan_cap)?;
+        stats.rows_scanned = Some(visited);
         stats.index_used = Some(false);
         rows
     };
-
-    let is_grouped = has_group_or_aggregate(columns.as_ref(), group_by.as_ref());
+    let (tie_keys, filtered_rows): (Vec<TieKey>, Vec<Row>) = filtered_rows.into_iter().unzip();
+    // A join also read its left rows, by scan or by an index lookup.
+    if let Some(left) = left_stats {
+        stats.rows_scanned = stats.rows_scanned.map(|n| n + left.rows_scanned.unwrap_or(0));
+        stats.index_used = left.index_used;
+    }
 
     if is_grouped {
         let (post_schema, mut post_rows) = evaluate_grouped_select(
@@ -97,7 +154,7 @@ fn handle_select(
             group_by.as_ref(),
         )?;
         if let Some(having_clause) = having.as_ref() {
-            post_rows = filter_rows(&post_schema, &post_rows, having_clause)?;
+            post_rows = filter_rows(&post_schema, &post_rows, having_clause, "HAVING")?;
         }
         if distinct {
             post_rows = dedupe_rows(post_rows);
@@ -105,23 +162,15 @@ fn handle_select(
 
         let mut ordered_rows = post_rows;
         if let Some(ob) = order_by {
+            let resolve = |name: &str| {
+                resolve_grouped_order_column(&select_schema, &post_schema, columns.as_ref(), name)
+            };
             let mut criteria: Vec<(usize, bool)> = Vec::new();
-            criteria.push((
-                resolve_column_index(&post_schema, &ob.column, "ORDER BY")?,
-                ob.asc,
-            ));
+            criteria.push((resolve(&ob.column)?, ob.asc));
             for (col, asc) in ob.then_by {
-                criteria.push((resolve_column_index(&post_schema, &col, "ORDER BY")?, asc));
+                criteria.push((resolve(&col)?, asc));
             }
-            ordered_rows.sort_by(|a, b| {
-                for (idx, asc) in &criteria {
-                    let ord = compare_for_order(a.get(*idx), b.get(*idx), *asc);
-                    if ord != Ordering::Equal {
-                        return ord;
-                    }
-                }
-                Ordering::Equal
-            });
+            ordered_rows = sort_rows(ordered_rows.into_iter().enumerate(), &criteria);
         }
         let start = offset.unwrap_or(0);
         let sliced_rows = if let Some(n) = limit {
@@ -144,20 +193,15 @@ fn handle_select(
         let (out_schema, projected_rows) = project_rows(&select_schema, &filtered_rows, columns.as_ref())?;
         let mut distinct_rows = dedupe_rows(projected_rows);
         if let Some(ob) = order_by {
+            let resolve = |name: &str| {
+                resolve_distinct_order_column(&select_schema, &out_schema, columns.as_ref(), name)
+            };
             let mut criteria: Vec<(usize, bool)> = Vec::new();
-            criteria.push((resolve_column_index(&out_schema, &ob.column, "ORDER BY")?, ob.asc));
+            criteria.push((resolve(&ob.column)?, ob.asc));
             for (col, asc) in ob.then_by {
-                criteria.push((resolve_column_index(&out_schema, &col, "ORDER BY")?, asc));
+                criteria.push((resolve(&col)?, asc));
             }
-            distinct_rows.sort_by(|a, b| {
-                for (idx, asc) in &criteria {
-                    let ord = compare_for_order(a.get(*idx), b.get(*idx), *asc);
-                    if ord != Ordering::Equal {
-                        return ord;
-                    }
-                }
-                Ordering::Equal
-            });
+            distinct_rows = sort_rows(distinct_rows.into_iter().enumerate(), &criteria);
         }
         let start = offset.unwrap_or(0);
         let limited_rows = if let Some(n) = limit {
@@ -182,39 +226,11 @@ fn handle_select(
             }
         }
         let mut criteria: Vec<(usize, bool)> = Vec::new();
-        let first_idx = resolve_column_index(&select_schema, &ob.column, "ORDER BY").or_else(|e| {
-            if e.contains("Unknown column") {
-                alias_to_idx
-                    .get(&ob.column)
-                    .copied()
-                    .ok_or_else(|| format!("Unknown column '{}' in ORDER BY", ob.column))
-            } else {
-                Err(e)
-            }
-        })?;
-        criteria.push((first_idx, ob.asc));
+        criteria.push((resolve_order_column(&select_schema, &alias_to_idx, &ob.column)?, ob.asc));
         for (col, asc) in ob.then_by {
-            let idx = resolve_column_index(&select_schema, &col, "ORDER BY").or_else(|e| {
-                if e.contains("Unknown column") {
-                    alias_to_idx
-                        .get(&col)
-                        .copied()
-                        .ok_or_else(|| format!("Unknown column '{}' in ORDER BY", col))
-                } else {
-                    Err(e)
-                }
-            })?;
-            criteria.push((idx, asc));
-        }
-        ordered_rows.sort_by(|a, b| {
-            for (idx, asc) in &criteria {
-                let ord = compare_for_order(a.get(*idx), b.get(*idx), *asc);
-                if ord != Ordering::Equal {
-                    return ord;
-                }
-            }
-            Ordering::Equal
-        });
+            criteria.push((resolve_order_column(&select_schema, &alias_to_idx, &col)?, asc));
+        }
+        ordered_rows = sort_rows(tie_keys.into_iter().zip(ordered_rows), &criteria);
     }
     let start = offset.unwrap_or(0);
     let limited_rows = if let Some(n) = limit {
@@ -232,14 +248,13 @@ fn handle_select(
 }
 
 fn dedupe_rows(rows: Vec<Row>) -> Vec<Row> {
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut seen: std::collections::HashSet<Vec<u8>> = std::collections::HashSet::new();
     let mut out: Vec<Row> = Vec::new();
     for r in rows {
-        let key = r
-            .iter()
-            .map(value_to_string)
-            .collect::<Vec<_>>()
-            .join("\u{1F}");
+        let mut key = Vec::new();
+        for v in &r {
+            push_value_key(&mut key, v);
+        }
         if seen.insert(key) {
             out.push(r);
         }
@@ -247,15 +262,38 @@ fn dedupe_rows(rows: Vec<Row>) -> Vec<Row> {
     out
 }
 
-fn load_base_rows(
+/// The joined rows when there are any, else every row of `table`, with
+/// their tie-break keys.
+fn keyed_base_rows<'a>(
     table: &str,
-    storage: &dyn StorageEngine,
-    preloaded_rows: Option<&Vec<Row>>,
-) -> Result<Vec<Row>, String> {
+    storage: &'a dyn StorageEngine,
+    preloaded_rows: Option<&'a KeyedRows>,
+) -> Result<Box<dyn Iterator<Item = (TieKey, &'a Row)> + 'a>, String> {
     if let Some(rows) = preloaded_rows {
-        return Ok(rows.clone());
+        return Ok(Box::new(rows.iter().map(|(key, r)| (*key, r))));
     }
-    Ok(storage.scan(table)?.to_vec())
+    let count = storage.scan(table)?.len();
+    Ok(Box::new(rows_at(table, storage, 0..count)?))
+}
+
+/// The rows of `table` at storage `indices`, keyed by row id. Indices past
+/// the end are skipped.
+fn rows_at<'a>(
+    table: &str,
+    storage: &'a dyn StorageEngine,
+    indices: impl IntoIterator<Item = usize> + 'a,
+) -> Result<impl Iterator<Item = (TieKey, &'a Row)> + 'a, String> {
+    let rows = storage.scan(table)?;
+    let ids = storage.row_ids(table);
+    Ok(indices
+        .into_iter()
+        .filter_map(move |i| Some(((row_id_at(ids, i), 0), rows.get(i)?))))
+}
+
+/// The id of the row at storage `index`; its position when the backend
+/// tracks no ids.
+fn row_id_at(ids: &[RowId], index: usize) -> RowId {
+    ids.get(index).copied().unwrap_or(index as RowId)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -267,6 +305,75 @@ enum AggregateFn {
     Max,
 }
 
+/// Rejects `*` in a grouped or aggregate projection before any rows are
+/// read, naming which of the two made it illegal.
+fn validate_star_projection(
+    columns: Option<&Vec<String>>,
+    group_by: Option<&Vec<String>>,
+) -> Result<(), String> {
+    let Some(cols) = columns else {
+        return Ok(());
+    };
+    if !cols.is_empty() && !cols.iter().any(|c| c == "*") {
+        return Ok(());
+    }
+    if group_by.is_some() {
+        return Err(
+            "SELECT * cannot be used with GROUP BY; list the grouped columns and aggregates explicitly"
+                .to_string(),
+        );
+    }
+    if let Some(agg) = cols
+        .iter()
+        .find(|c| parse_aggregate_expr(&split_select_alias(c).0).is_some())
+    {
+        return Err(format!(
+            "SELECT * cannot be combined with aggregate {agg}; select the aggregates on their own or add GROUP BY with explicit columns"
+        ));
+    }
+    Ok(())
+}
+
+/// Storage indices of the rows `select ... for update` locks: every row
+/// of the table matching WHERE, whatever LIMIT and OFFSET return.
+pub fn locked_row_indices(
+    cmd: &Command,
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+) -> Result<Vec<usize>, String> {
+    let Command::Select {
+        table,
+        distinct,
+        join,
+        columns,
+        filter,
+        group_by,
+        ..
+    } = cmd
+    else {
+        return Ok(Vec::new());
+    };
+    if join.is_some() || *distinct || has_group_or_aggregate(columns.as_ref(), group_by.as_ref()) {
+        return Err(
+            "FOR UPDATE is not supported with JOIN, DISTINCT, GROUP BY or aggregates".to_string(),
+        );
+    }
+    let schema = catalog.schema(table)?;
+    let rows = storage.scan(table)?;
+    let Some(filter) = filter else {
+        return Ok((0..rows.len()).collect());
+    };
+    let scope = SubqueryScope { catalog, storage };
+    let compiled = CompiledWhere::compile_in(schema, filter, "WHERE", Some(scope))?;
+    let mut out = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        if compiled.eval(row)? {
+            out.push(i);
+        }
+    }
+    Ok(out)
+}
+
 fn has_group_or_aggregate(columns: Option<&Vec<String>>, group_by: Option<&Vec<String>>) -> bool {
     if group_by.is_some() {
         return true;
@@ -308,6 +415,99 @@ fn parse_aggregate_expr_extended(token: &str) -> Option<(AggregateFn, String, bo
     Some((func, arg, false))
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DatePart {
+    Year,
+    Month,
+    Day,
+}
+
+/// A non-aggregate SELECT or GROUP BY item after name resolution. Comparing
+/// resolved forms means `month(created)`, `MONTH(t.created)` and
+/// `month( created )` all count as the same grouping key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScalarExpr {
+    Column(usize),
+    DatePart { part: DatePart, arg: usize },
+}
+
+impl ScalarExpr {
+    /// The input column the expression reads.
+    fn source_index(&self) -> usize {
+        match self {
+            ScalarExpr::Column(idx) => *idx,
+            ScalarExpr::DatePart { arg, .. } => *arg,
+        }
+    }
+
+    /// Plain columns keep their schema name unless aliased; computed items
+    /// are named after the expression text.
+    fn output_column(&self, schema: &Schema, expr: &str, alias: Option<String>) -> Column {
+        match self {
+            ScalarExpr::Column(idx) => {
+                let mut col = schema.columns[*idx].clone();
+                if let Some(alias) = alias {
+                    col.name = alias;
+                }
+                col
+            }
+            ScalarExpr::DatePart { arg, .. } => Column {
+                name: alias.unwrap_or_else(|| expr.to_string()),
+                dtype: DataType::Int,
+                primary_key: false,
+                unique: false,
+                not_null: schema.columns[*arg].not_null,
+                default: None,
+                json_shape: None,
+            },
+        }
+    }
+
+    fn eval(&self, row: &Row) -> Value {
+        match self {
+            ScalarExpr::Column(idx) => row[*idx].clone(),
+            ScalarExpr::DatePart { part, arg } => {
+                let date = match &row[*arg] {
+                    Value::Date(d) => *d,
+                    Value::Timestamp(ts) => ts.date(),
+                    _ => return Value::Null,
+                };
+                Value::Int(match part {
+                    DatePart::Year => chrono::Datelike::year(&date) as i64,
+                    DatePart::Month => chrono::Datelike::month(&date) as i64,
+                    DatePart::Day => chrono::Datelike::day(&date) as i64,
+                })
+            }
+        }
+    }
+}
+
+fn resolve_scalar_expr(schema: &Schema, expr: &str, clause: &str) -> Result<ScalarExpr, String> {
+    let Some((fname_raw, rest)) = expr.split_once('(') else {
+        return resolve_column_index(schema, expr, clause).map(ScalarExpr::Column);
+    };
+    let fname = fname_raw.trim().to_lowercase();
+    let part = match fname.as_str() {
+        "year" => DatePart::Year,
+        "month" => DatePart::Month,
+        "day" => DatePart::Day,
+        _ => return resolve_column_index(schema, expr, clause).map(ScalarExpr::Column),
+    };
+    let arg = rest
+        .strip_suffix(')')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .ok_or_else(|| format!("Bad {fname}() syntax in {clause}. Use {fname}(col)"))?;
+    let idx = resolve_column_index(schema, arg, clause)?;
+    if !matches!(schema.columns[idx].dtype, DataType::Date | DataType::Timestamp) {
+        return Err(format!(
+            "{fname}() requires a date or timestamp column but '{arg}' is {}",
+            datatype_name(&schema.columns[idx].dtype)
+        ));
+    }
+    Ok(ScalarExpr::DatePart { part, arg: idx })
+}
+
 #[derive(Debug, Clone, Copy)]
 struct AggregateMeta {
     func: AggregateFn,
@@ -324,19 +524,17 @@ fn evaluate_grouped_select(
     let Some(select_cols) = columns else {
         return Err("GROUP BY or aggregates require explicit SELECT columns".to_string());
     };
-    if select_cols.is_empty() {
-        return Err("SELECT * cannot be used with GROUP BY or aggregate functions".to_string());
-    }
+    validate_star_projection(Some(select_cols), group_by)?;
 
     let group_cols = group_by.cloned().unwrap_or_default();
-    let mut group_key_indices: Vec<usize> = Vec::new();
+    let mut group_keys: Vec<ScalarExpr> = Vec::new();
     for g in &group_cols {
-        group_key_indices.push(resolve_column_index(schema, g, "GROUP BY")?);
+        group_keys.push(resolve_scalar_expr(schema, g, "GROUP BY")?);
     }
 
     let mut output_columns: Vec<Column> = Vec::new();
-    let mut select_items: Vec<(bool, usize, Option<AggregateMeta>)> = Vec::new();
-    // (is_agg, source_idx_for_plain, agg meta)
+    let mut select_items: Vec<(Option<ScalarExpr>, Option<AggregateMeta>)> = Vec::new();
+    // (plain expression, agg meta) -- exactly one is set
     let mut has_agg = false;
     for sel in select_cols {
         let (sel_expr, sel_alias) = split_select_alias(sel);
@@ -360,10 +558,10 @@ fn evaluate_grouped_select(
                 unique: false,
                 not_null: false,
                 default: None,
+                json_shape: None,
             });
             select_items.push((
-                true,
-                0usize,
+                None,
                 Some(AggregateMeta {
                     func: agg_fn,
                     arg_idx: arg_idx_opt,
@@ -371,19 +569,15 @@ fn evaluate_grouped_select(
                 }),
             ));
         } else {
-            let idx = resolve_column_index(schema, &sel_expr, "SELECT list")?;
-            if !group_key_indices.contains(&idx) {
+            let expr = resolve_scalar_expr(schema, &sel_expr, "SELECT list")?;
+            if !group_keys.contains(&expr) {
                 return Err(format!(
                     "Column '{}' must appear in GROUP BY or be used in an aggregate function",
                     sel_expr
                 ));
             }
-            let mut out_col = schema.columns[idx].clone();
-            if let Some(alias) = sel_alias {
-                out_col.name = alias;
-            }
-            output_columns.push(out_col);
-            select_items.push((false, idx, None));
+            output_columns.push(expr.output_column(schema, &sel_expr, sel_alias));
+            select_items.push((Some(expr), None));
         }
     }
     if !has_agg && group_cols.is_empty() {
@@ -402,7 +596,7 @@ fn evaluate_grouped_select(
     evaluate_aggregate_groups(
         schema,
         rows,
-        &group_key_indices,
+        &group_keys,
         &select_items,
         Schema::new(output_columns),
     )
@@ -411,24 +605,22 @@ fn evaluate_grouped_select(
 fn evaluate_aggregate_groups(
     schema: &Schema,
     rows: &[Row],
-    group_indices: &[usize],
-    select_items: &[(bool, usize, Option<AggregateMeta>)],
+    group_keys: &[ScalarExpr],
+    select_items: &[(Option<ScalarExpr>, Option<AggregateMeta>)],
     out_schema: Schema,
 ) -> Result<(Schema, Vec<Row>), String> {
-    let mut grouped: std::collections::HashMap<String, Vec<Row>> = std::collections::HashMap::new();
-    let mut ordered_keys: Vec<String> = Vec::new();
+    let mut grouped: std::collections::HashMap<Vec<u8>, Vec<Row>> = std::collections::HashMap::new();
+    let mut ordered_keys: Vec<Vec<u8>> = Vec::new();
 
-    if group_indices.is_empty() {
-        let key = "__all__".to_string();
-        grouped.insert(key.clone(), rows.to_vec());
-        ordered_keys.push(key);
+    if group_keys.is_empty() {
+        grouped.insert(Vec::new(), rows.to_vec());
+        ordered_keys.push(Vec::new());
     } else {
         for r in rows {
-            let key = group_indices
-                .iter()
-                .map(|i| value_to_string(&r[*i]))
-                .collect::<Vec<_>>()
-                .join("\u{1F}");
+            let mut key = Vec::new();
+            for k in group_keys {
+                push_value_key(&mut key, &k.eval(r));
+            }
             if !grouped.contains_key(&key) {
                 ordered_keys.push(key.clone());
             }
@@ -442,11 +634,11 @@ fn evaluate_aggregate_groups(
         if group_rows.is_empty() {
             // Global aggregate over empty input still produces one row
             // (e.g. count(*) = 0, sum/avg/min/max = null).
-            if select_items.iter().any(|(is_agg, _, _)| !*is_agg) {
+            if select_items.iter().any(|(plain, _)| plain.is_some()) {
                 continue;
             }
             let mut out: Row = Vec::new();
-            for (_is_agg, _source_idx, agg_meta) in select_items {
+            for (_plain, agg_meta) in select_items {
                 let meta = agg_meta.expect("aggregate metadata");
                 let v = evaluate_single_aggregate(schema, group_rows, meta)?;
                 out.push(v);
@@ -456,9 +648,9 @@ fn evaluate_aggregate_groups(
         }
         let first = &group_rows[0];
         let mut out: Row = Vec::new();
-        for (is_agg, source_idx, agg_meta) in select_items {
-            if !*is_agg {
-                out.push(first[*source_idx].clone());
+        for (plain, agg_meta) in select_items {
+            if let Some(expr) = plain {
+                out.push(expr.eval(first));
                 continue;
             }
             let meta = agg_meta.expect("aggregate metadata");
@@ -477,8 +669,8 @@ fn aggregate_output_type(func: AggregateFn, dtype: &DataType) -> Result<DataType
         AggregateFn::Sum => match dtype {
             DataType::Int => Ok(DataType::Int),
             DataType::BigInt => Ok(DataType::BigInt),
-            DataType::Decimal { precision, scale } => Ok(DataType::Decimal {
-                precision: *precision,
+            DataType::Decimal { scale, .. } => Ok(DataType::Decimal {
+                precision: 38,
                 scale: *scale,
             }),
             _ => Err("sum() is only valid for int|bigint|decimal".to_string()),
@@ -488,8 +680,8 @@ fn aggregate_output_type(func: AggregateFn, dtype: &DataType) -> Result<DataType
                 precision: 38,
                 scale: 6,
             }),
-            DataType::Decimal { precision, scale } => Ok(DataType::Decimal {
-                precision: *precision,
+            DataType::Decimal { scale, .. } => Ok(DataType::Decimal {
+                precision: 38,
                 scale: (*scale).max(6),
             }),
             _ => Err("avg() is only valid for int|bigint|decimal".to_string()),
@@ -498,6 +690,18 @@ fn aggregate_output_type(func: AggregateFn, dtype: &DataType) -> Result<DataType
     }
 }
 
+/// Rounds a decimal `sum`/`avg` result to the scale of its output type
+/// and checks it fits, so it round-trips through that type's literal form.
+fn fit_aggregate_decimal(func: AggregateFn, input: &DataType, d: Decimal) -> Result<Value, String> {
+    let name = if func == AggregateFn::Avg { "avg" } else { "sum" };
+    match aggregate_output_type(func, input)? {
+        DataType::Decimal { precision, scale } => fit_decimal(d, precision, scale)
+            .map(Value::Decimal)
+            .map_err(|e| format!("{name}() result does not fit decimal({precision},{scale}): {e}")),
+        _ => Ok(Value::Decimal(d)),
+    }
+}
+
 fn evaluate_single_aggregate(
     schema: &Schema,
     rows: &[Row],
@@ -544,14 +748,16 @@ fn evaluate_single_aggregate(
                     }
                     Ok(Value::BigInt(acc))
                 }
-                DataType::Decimal { .. } => {
+                dtype @ DataType::Decimal { .. } => {
                     let mut acc = Decimal::ZERO;
                     for v in &vals {
                         if let Value::Decimal(v) = v {
-                            acc += *v;
+                            acc = acc
+                                .checked_add(*v)
+                                .ok_or_else(|| "sum(decimal) overflow".to_string())?;
                         }
                     }
-                    Ok(Value::Decimal(acc))
+                    fit_aggregate_decimal(func, dtype, acc)
                 }
                 _ => Err("sum() is only valid for int|bigint|decimal".to_string()),
             }
@@ -581,7 +787,9 @@ fn evaluate_single_aggregate(
                 DataType::Decimal { .. } => {
                     for v in &vals {
                         if let Value::Decimal(v) = v {
-                            acc += *v;
+                            acc = acc
+                                .checked_add(*v)
+                                .ok_or_else(|| "avg(decimal) overflow".to_string())?;
                             cnt += 1;
                         }
                     }
@@ -591,7 +799,10 @@ fn evaluate_single_aggregate(
             if cnt == 0 {
                 return Ok(Value::Null);
             }
-            Ok(Value::Decimal(acc / Decimal::from_i128_with_scale(cnt, 0)))
+            let avg = acc
+                .checked_div(Decimal::from_i128_with_scale(cnt, 0))
+                .ok_or_else(|| "avg() overflow".to_string())?;
+            fit_aggregate_decimal(func, &schema.columns[idx].dtype, avg)
         }
         AggregateFn::Min | AggregateFn::Max => {
             let idx = arg_idx.ok_or_else(|| "min/max(*) is not supported".to_string())?;
@@ -627,14 +838,14 @@ fn aggregate_input_values(rows: &[Row], idx: usize, distinct: bool) -> Vec<Value
         }
         return out;
     }
-    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut seen: std::collections::HashSet<Vec<u8>> = std::collections::HashSet::new();
     let mut out: Vec<Value> = Vec::new();
     for r in rows {
         let Some(v) = r.get(idx) else { continue };
         if matches!(v, Value::Null) {
             continue;
         }
-        let key = value_to_string(v);
+        let key = value_key(v);
         if seen.insert(key) {
             out.push(v.clone());
         }
@@ -677,11 +888,291 @@ fn build_join_rows(
     storage: &dyn StorageEngine,
     left_table: &str,
     join: &JoinClause,
-) -> Result<(Schema, Vec<Row>), String> {
+    filter: Option<&WhereClause>,
+    max_join_rows: Option<usize>,
+) -> Result<(Schema, KeyedRows, ExecutionStats), String> {
+    let (out_schema, lidx, ridx) = resolve_join_schema(catalog, left_table, join)?;
     let left_schema = catalog.schema(left_table)?;
-    let right_schema = catalog.schema(&join.table)?;
-    let left_rows = storage.scan(left_table)?;
+    let left_width = left_schema.columns.len();
+    let right_width = catalog.schema(&join.table)?.columns.len();
     let right_rows = storage.scan(&join.table)?;
+    let right_ids = storage.row_ids(&join.table);
+
+    // Top-level AND terms that only read left columns are applied to the
+    // left rows before joining. The padded right side of a LEFT JOIN never
+    // affects them, and the full WHERE still runs on the joined rows.
+    let left_terms: Vec<&WhereClause> = filter
+        .map(where_conjuncts)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| where_reads_only_prefix(&out_schema, c, left_width))
+        .collect();
+    let pushed: Vec<CompiledWhere> = left_terms
+        .iter()
+        .map(|c| CompiledWhere::compile(&out_schema, c, "WHERE"))
+        .collect::<Result<_, _>>()?;
+    let mut left_stats = ExecutionStats::default();
+    let candidates = match seed_left_rows(storage, left_table, left_schema, &out_schema, &left_terms)? {
+        Some(rows) => {
+            left_stats.index_used = Some(true);
+            rows
+        }
+        None => {
+            left_stats.index_used = Some(false);
+            let count = storage.scan(left_table)?.len();
+            rows_at(left_table, storage, 0..count)?
+                .map(|((id, _), r)| (id, r))
+                .collect()
+        }
+    };
+    left_stats.rows_scanned = Some(candidates.len());
+    let mut left_rows: Vec<(RowId, &Row)> = Vec::new();
+    'left: for (left_id, lr) in candidates {
+        for term in &pushed {
+            if !term.eval(lr)? {
+                continue 'left;
+            }
+        }
+        left_rows.push((left_id, lr));
+    }
+
+    // Join planning: build a hash index on the right side join key.
+    // This preserves left-table output order while avoiding O(n*m) scans.
+    // The index holds positions into `right_rows`, so right rows are only
+    // copied when a joined row is emitted.
+    let mut right_key_to_rows: std::collections::HashMap<Vec<u8>, Vec<usize>> =
+        std::collections::HashMap::new();
+    for (pos, rr) in right_rows.iter().enumerate() {
+        let Some(k) = rr.get(ridx) else { continue };
+        if matches!(k, Value::Null) {
+            continue;
+        }
+        right_key_to_rows.entry(value_key(k)).or_default().push(pos);
+    }
+
+    let mut out_rows: KeyedRows = Vec::new();
+    let mut emit = |key: TieKey, row: Row| -> Result<(), String> {
+        if let Some(max) = max_join_rows
+            && out_rows.len() >= max
+        {
+            return Err(format!(
+                "JOIN produced more than {max} rows; narrow the join or raise the limit with DbConfig::with_max_join_rows"
+            ));
+        }
+        out_rows.push((key, row));
+        Ok(())
+    };
+    for (left_id, lr) in left_rows {
+        let Some(left_key) = lr.get(lidx) else { continue };
+        let matching = if matches!(left_key, Value::Null) {
+            None
+        } else {
+            right_key_to_rows.get(&value_key(left_key))
+        };
+        if let Some(matching_positions) = matching {
+            for &pos in matching_positions {
+                let mut row = Vec::with_capacity(left_width + right_width);
+                row.extend(lr.iter().cloned());
+                row.extend(right_rows[pos].iter().cloned());
+                #[cfg(feature = "join-copy-count")]
+                JOIN_RIGHT_ROW_COPIES.with(|n| n.set(n.get() + 1));
+                emit((left_id, row_id_at(right_ids, pos)), row)?;
+            }
+        } else if join.join_type == JoinType::Left {
+            let mut row = Vec::with_capacity(left_width + right_width);
+            row.extend(lr.iter().cloned());
+            row.extend(std::iter::repeat_n(Value::Null, right_width));
+            emit((left_id, 0), row)?;
+        }
+    }
+
+    Ok((out_schema, out_rows, left_stats))
+}
+
+/// Fetches only the left rows matching a pushed-down `col = value` term on a
+/// left-table primary key, UNIQUE or secondary index, in table order, with
+/// their row ids. `None` means no such term has a usable index and the left
+/// table is scanned.
+fn seed_left_rows<'a>(
+    storage: &'a dyn StorageEngine,
+    left_table: &str,
+    left_schema: &Schema,
+    out_schema: &Schema,
+    left_terms: &[&WhereClause],
+) -> Result<Option<Vec<(RowId, &'a Row)>>, String> {
+    if !storage.indexes_current(left_table) {
+        return Ok(None);
+    }
+    for term in left_terms {
+        let Some((col, val)) = simple_eq_filter(term) else {
+            continue;
+        };
+        let col = &left_schema.columns[resolve_column_index(out_schema, &col, "WHERE")?].name;
+        let row_indices = if left_schema.primary_key.len() == 1 && &left_schema.primary_key[0] == col {
+            storage
+                .lookup_pk_row_index(left_table, left_schema, &val)?
+                .into_iter()
+                .collect()
+        } else if let Some(row_idx) = storage.lookup_unique_row_index(left_table, left_schema, col, &val)? {
+            vec![row_idx]
+        } else if let Some(row_indices) =
+            storage.lookup_secondary_row_indices(left_table, left_schema, col, &val)?
+        {
+            row_indices
+        } else {
+            continue;
+        };
+        let rows = rows_at(left_table, storage, row_indices)?
+            .map(|((id, _), r)| (id, r))
+            .collect();
+        return Ok(Some(rows));
+    }
+    Ok(None)
+}
+
+#[cfg(feature = "join-copy-count")]
+thread_local! {
+    static JOIN_RIGHT_ROW_COPIES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Number of right-side rows copied into join output on this thread so far.
+/// Exposed for tests that check the join hash index does not copy rows.
+#[cfg(feature = "join-copy-count")]
+#[doc(hidden)]
+pub fn join_right_row_copies() -> usize {
+    JOIN_RIGHT_ROW_COPIES.with(|n| n.get())
+}
+
+/// Top-level AND terms of `clause` that are `col like "pattern"`.
+fn like_terms(clause: &WhereClause) -> impl Iterator<Item = &Predicate> {
+    where_conjuncts(clause).into_iter().filter_map(|term| match term {
+        WhereClause::Predicate(p) if p.op == CompareOp::Like => Some(p),
+        _ => None,
+    })
+}
+
+/// Row indices a `using trigram` index narrows a single-table `filter` to,
+/// taken from the first `like` term the index can serve. Every candidate
+/// must still pass the whole filter.
+fn trigram_candidates(
+    table: &str,
+    schema: &Schema,
+    filter: &WhereClause,
+    storage: &dyn StorageEngine,
+) -> Result<Option<Vec<usize>>, String> {
+    if schema.trigram_indexes.is_empty() || !storage.indexes_current(table) {
+        return Ok(None);
+    }
+    for p in like_terms(filter) {
+        if let Some(rows) = storage.lookup_trigram_row_indices(table, schema, &p.column, &p.value)? {
+            return Ok(Some(rows));
+        }
+    }
+    Ok(None)
+}
+
+/// Row indices, in table order, of the partition segments whose value can
+/// satisfy the AND terms of `filter` that read only the `partition by`
+/// column. `None` when the table is unpartitioned, its segments are stale or
+/// no term restricts the partition column. Candidates still have to pass the
+/// whole filter.
+fn partition_candidates(
+    table: &str,
+    schema: &Schema,
+    filter: &WhereClause,
+    storage: &dyn StorageEngine,
+) -> Result<Option<Vec<usize>>, String> {
+    let Some(col) = &schema.partition_by else {
+        return Ok(None);
+    };
+    if !storage.indexes_current(table) {
+        return Ok(None);
+    }
+    let col_idx = resolve_column_index(schema, col, "PARTITION BY")?;
+    let reads_partition_only = |term: &WhereClause| {
+        !contains_exists(term)
+            && where_column_names(term)
+            .iter()
+            .all(|c| resolve_column_index(schema, c, "WHERE").is_ok_and(|idx| idx == col_idx))
+    };
+    let terms = where_conjuncts(filter)
+        .into_iter()
+        .filter(|term| reads_partition_only(term))
+        .map(|term| CompiledWhere::compile(schema, term, "WHERE"))
+        .collect::<Result<Vec<_>, _>>()?;
+    if terms.is_empty() {
+        return Ok(None);
+    }
+    let Some(segments) = storage.partition_segments(table)? else {
+        return Ok(None);
+    };
+    let mut probe: Row = vec![Value::Null; schema.columns.len()];
+    let mut rows: Vec<usize> = Vec::new();
+    for (value, segment_rows) in segments {
+        probe[col_idx] = value.clone();
+        // A term that fails to evaluate keeps the segment, so the full
+        // filter reports the error just as a scan would.
+        let results: Vec<Result<bool, String>> = terms.iter().map(|t| t.eval(&probe)).collect();
+        if results.iter().any(Result::is_err) || results.iter().all(|r| matches!(r, Ok(true))) {
+            rows.extend_from_slice(segment_rows);
+        }
+    }
+    rows.sort_unstable();
+    Ok(Some(rows))
+}
+
+/// Every column name `clause` reads.
+fn where_column_names(clause: &WhereClause) -> Vec<&str> {
+    match clause {
+        WhereClause::Predicate(p) => vec![p.column.as_str()],
+        WhereClause::Binary { left, right, .. } => {
+            let mut out = where_column_names(left);
+            out.extend(where_column_names(right));
+            out
+        }
+        WhereClause::Exists { subquery, .. } => {
+            subquery.correlation.iter().map(|(_, outer)| outer.as_str()).collect()
+        }
+    }
+}
+
+fn where_conjuncts(clause: &WhereClause) -> Vec<&WhereClause> {
+    match clause {
+        WhereClause::Binary {
+            left,
+            op: LogicalOp::And,
+            right,
+        } => {
+            let mut out = where_conjuncts(left);
+            out.extend(where_conjuncts(right));
+            out
+        }
+        other => vec![other],
+    }
+}
+
+/// True when every column in `clause` resolves below `width` in `schema`.
+fn where_reads_only_prefix(schema: &Schema, clause: &WhereClause, width: usize) -> bool {
+    match clause {
+        WhereClause::Predicate(p) => {
+            resolve_column_index(schema, &p.column, "WHERE").is_ok_and(|idx| idx < width)
+        }
+        WhereClause::Binary { left, right, .. } => {
+            where_reads_only_prefix(schema, left, width) && where_reads_only_prefix(schema, right, width)
+        }
+        WhereClause::Exists { .. } => false,
+    }
+}
+
+/// Resolves the JOIN ON operands and builds the qualified `table.col` schema.
+/// Returns the joined schema plus the left and right key column indexes.
+fn resolve_join_schema(
+    catalog: &Catalog,
+    left_table: &str,
+    join: &JoinClause,
+) -> Result<(Schema, usize, usize), String> {
+    let left_schema = catalog.schema(left_table)?;
+    let right_schema = catalog.schema(&join.table)?;
 
     let (left_side, left_idx) =
         resolve_join_operand(left_table, left_schema, &join.table, right_schema, &join.left_column)?;
@@ -698,8 +1189,16 @@ fn build_join_rows(
         (right_idx, left_idx)
     };
 
-    if left_schema.columns[lidx].dtype != right_schema.columns[ridx].dtype {
-        return Err("JOIN columns must have the same datatype".to_string());
+    if !join_key_types_compatible(&left_schema.columns[lidx].dtype, &right_schema.columns[ridx].dtype) {
+        return Err(format!(
+            "ON: column '{}.{}' ({}) cannot be compared with column '{}.{}' ({}); JOIN columns must have the same datatype (int and bigint may be mixed)",
+            left_table,
+            left_schema.columns[lidx].name,
+            datatype_name(&left_schema.columns[lidx].dtype),
+            join.table,
+            right_schema.columns[ridx].name,
+            datatype_name(&right_schema.columns[ridx].dtype),
+        ));
     }
 
     let mut out_columns: Vec<Column> = Vec::new();
@@ -711,6 +1210,7 @@ fn build_join_rows(
             unique: false,
             not_null: c.not_null,
             default: None,
+            json_shape: None,
         });
     }
     for c in &right_schema.columns {
@@ -721,45 +1221,21 @@ fn build_join_rows(
             unique: false,
             not_null: c.not_null,
             default: None,
+            json_shape: None,
         });
     }
 
-    // Join planning: build a hash index on the right side join key.
-    // This preserves left-table output order while avoiding O(n*m) scans.
-    let mut right_key_to_rows: std::collections::HashMap<String, Vec<Row>> = std::collections::HashMap::new();
-    for rr in right_rows {
-        let Some(k) = rr.get(ridx) else { continue };
-        if matches!(k, Value::Null) {
-            continue;
-        }
-        right_key_to_rows
-            .entry(value_to_string(k))
-            .or_default()
-            .push(rr.clone());
-    }
-
-    let mut out_rows: Vec<Row> = Vec::new();
-    for lr in left_rows {
-        let Some(left_key) = lr.get(lidx) else { continue };
-        let matching = if matches!(left_key, Value::Null) {
-            None
-        } else {
-            right_key_to_rows.get(&value_to_string(left_key))
-        };
-        if let Some(matching_right_rows) = matching {
-            for rr in matching_right_rows {
-                let mut row = lr.clone();
-                row.extend(rr.clone());
-                out_rows.push(row);
-            }
-        } else if join.join_type == JoinType::Left {
-            let mut row = lr.clone();
-            row.extend(std::iter::repeat_n(Value::Null, right_schema.columns.len()));
-            out_rows.push(row);
-        }
-    }
+    Ok((Schema::new(out_columns), lidx, ridx))
+}
 
-    Ok((Schema::new(out_columns), out_rows))
+/// Int and bigint keys join on numeric value: `value_key` gives both the
+/// same tag and width, so equal numbers hash alike.
+fn join_key_types_compatible(left: &DataType, right: &DataType) -> bool {
+    left == right
+        || matches!(
+            (left, right),
+            (DataType::Int, DataType::BigInt) | (DataType::BigInt, DataType::Int)
+        )
 }
 
 fn resolve_join_operand(
@@ -769,7 +1245,9 @@ fn resolve_join_operand(
     right_schema: &Schema,
     token: &str,
 ) -> Result<(bool, usize), String> {
-    if let Some((tbl, col)) = token.split_once('.') {
+    // Column names never contain '.', so the last one splits off the
+    // column even from an attached `<alias>.<table>` name.
+    if let Some((tbl, col)) = token.rsplit_once('.') {
         if tbl == left_table {
             let idx = left_schema
                 .columns
@@ -802,8 +1280,123 @@ fn resolve_join_operand(
     }
 }
 
+/// ORDER BY over unprojected rows: a SELECT alias wins over a same-named
+/// input column, so `select orders.user_id as id ... order by id` sorts by
+/// the alias even when a joined table has an `id` column.
+fn resolve_order_column(
+    schema: &Schema,
+    alias_to_idx: &std::collections::HashMap<String, usize>,
+    name: &str,
+) -> Result<usize, String> {
+    if let Some(idx) = alias_to_idx.get(name) {
+        return Ok(*idx);
+    }
+    resolve_column_index(schema, name, "ORDER BY")
+}
+
+/// ORDER BY in a grouped select: an output column name or alias, or a
+/// select item repeated as an expression. Aggregates match when function,
+/// DISTINCT and argument column agree, ignoring case and spacing, so
+/// `order by COUNT( id )` finds `count(id) as n`.
+fn resolve_grouped_order_column(
+    select_schema: &Schema,
+    post_schema: &Schema,
+    columns: Option<&Vec<String>>,
+    name: &str,
+) -> Result<usize, String> {
+    match resolve_column_index(post_schema, name, "ORDER BY") {
+        Ok(idx) => return Ok(idx),
+        Err(e) if e.starts_with("Ambiguous") => return Err(e),
+        Err(_) => {}
+    }
+    let mut items = columns
+        .into_iter()
+        .flatten()
+        .map(|item| split_select_alias(item).0);
+    let position = if let Some(target) = parse_aggregate_expr_extended(name) {
+        items.position(|expr| {
+            parse_aggregate_expr_extended(&expr)
+                .is_some_and(|item| same_aggregate(select_schema, &item, &target))
+        })
+    } else {
+        let target = resolve_scalar_expr(select_schema, name, "ORDER BY")?;
+        items.position(|expr| {
+            parse_aggregate_expr_extended(&expr).is_none()
+                && resolve_scalar_expr(select_schema, &expr, "SELECT list").is_ok_and(|e| e == target)
+        })
+    };
+    position.ok_or_else(|| {
+        format!(
+            "ORDER BY expression '{name}' is not in the select list of a grouped query; add it to the select list to order by it"
+        )
+    })
+}
+
+fn same_aggregate(
+    schema: &Schema,
+    a: &(AggregateFn, String, bool),
+    b: &(AggregateFn, String, bool),
+) -> bool {
+    a.0 == b.0
+        && a.2 == b.2
+        && (a.1 == b.1
+            || matches!(
+                (
+                    resolve_column_index(schema, &a.1, "ORDER BY"),
+                    resolve_column_index(schema, &b.1, "ORDER BY"),
+                ),
+                (Ok(x), Ok(y)) if x == y
+            ))
+}
+
+/// ORDER BY under DISTINCT may only use output columns, since rows that
+/// differ in any other column have already been merged. A term matches an
+/// output column by name or alias, or by repeating a projected expression.
+fn resolve_distinct_order_column(
+    select_schema: &Schema,
+    out_schema: &Schema,
+    columns: Option<&Vec<String>>,
+    name: &str,
+) -> Result<usize, String> {
+    match resolve_column_index(out_schema, name, "ORDER BY") {
+        Ok(idx) => return Ok(idx),
+        Err(e) if e.starts_with("Ambiguous") => return Err(e),
+        Err(e) if columns.is_none_or(|c| c.is_empty()) => return Err(e),
+        Err(_) => {}
+    }
+    let target = resolve_scalar_expr(select_schema, name, "ORDER BY")?;
+    let projected = columns.into_iter().flatten().position(|item| {
+        let (expr, _) = split_select_alias(item);
+        resolve_scalar_expr(select_schema, &expr, "SELECT list").is_ok_and(|e| e == target)
+    });
+    projected.ok_or_else(|| {
+        format!("ORDER BY column '{name}' must appear in the select list when using DISTINCT")
+    })
+}
+
+/// Resolves a column reference against `schema`.
+///
+/// - An exact name match wins. Stored table and column names cannot contain
+///   `.`, so in a joined schema (all names `table.col`) only qualified
+///   references match exactly; in a projected schema this is an alias or a
+///   plain column name.
+/// - More than one exact match is ambiguous rather than first-wins.
+/// - Otherwise an unqualified name must match exactly one `table.name`
+///   suffix. Qualified names never fall back to suffix matching.
 fn resolve_column_index(schema: &Schema, name: &str, clause: &str) -> Result<usize, String> {
-    if let Some(idx) = schema.columns.iter().position(|c| c.name == name) {
+    let mut exact = schema
+        .columns
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.name == name)
+        .map(|(idx, _)| idx);
+    if let Some(idx) = exact.next() {
+        if exact.next().is_some() {
+            return Err(format!(
+                "Ambiguous column '{}' in {}. Use qualified name table.column",
+                name, clause
+            ));
+        }
         return Ok(idx);
     }
     if name.contains('.') {
@@ -827,6 +1420,31 @@ fn resolve_column_index(schema: &Schema, name: &str, clause: &str) -> Result<usi
     }
 }
 
+/// ORDER BY's implicit final key for a plain select: the row id, or for a
+/// join row the left row id then the right one (0 for the nulls a LEFT JOIN
+/// fills in).
+type TieKey = (RowId, RowId);
+
+/// Rows, each with its [`TieKey`].
+type KeyedRows = Vec<(TieKey, Row)>;
+
+/// Sorts by `criteria`, breaking ties on each row's key: its [`TieKey`], so
+/// tied rows come out in the same order whether they were reached by a scan
+/// or an index lookup, or its position for grouped and DISTINCT output.
+fn sort_rows<K: Ord>(rows: impl IntoIterator<Item = (K, Row)>, criteria: &[(usize, bool)]) -> Vec<Row> {
+    let mut keyed: Vec<(K, Row)> = rows.into_iter().collect();
+    keyed.sort_unstable_by(|(pa, a), (pb, b)| {
+        for (idx, asc) in criteria {
+            let ord = compare_for_order(a.get(*idx), b.get(*idx), *asc);
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+        pa.cmp(pb)
+    });
+    keyed.into_iter().map(|(_, row)| row).collect()
+}
+
 fn compare_for_order(a: Option<&Value>, b: Option<&Value>, asc: bool) -> Ordering {
     let ord = match (a, b) {
         (Some(Value::Null), Some(Value::Null)) => Ordering::Equal,
@@ -0,0 +1,157 @@
+/// Runs `left intersect|except [all] right`. Rows match on the
+/// [`value_key`] of every column, so NULLs match each other and int and
+/// bigint match on numeric value. The result has the left SELECT's columns
+/// and keeps its row order. Without `all` it is deduplicated; with `all` a
+/// right row cancels at most one matching left row.
+#[allow(clippy::too_many_arguments)]
+fn handle_set_op(
+    kind: SetOpKind,
+    left: Command,
+    right: Command,
+    all: bool,
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+    max_join_rows: Option<usize>,
+) -> Result<QueryResult, String> {
+    let QueryResult::Select {
+        schema,
+        rows: left_rows,
+        ..
+    } = execute_read(left, catalog, storage, max_join_rows)?
+    else {
+        return Err("Internal error: SELECT did not produce rows".to_string());
+    };
+    let QueryResult::Select {
+        schema: right_schema,
+        rows: right_rows,
+        ..
+    } = execute_read(right, catalog, storage, max_join_rows)?
+    else {
+        return Err("Internal error: SELECT did not produce rows".to_string());
+    };
+    check_set_op_columns(kind, &schema_columns(&schema), &schema_columns(&right_schema))?;
+
+    let mut right_counts: std::collections::HashMap<Vec<u8>, usize> =
+        std::collections::HashMap::new();
+    for row in &right_rows {
+        *right_counts.entry(row_value_key(row)).or_default() += 1;
+    }
+    let mut rows = Vec::new();
+    for row in left_rows {
+        let in_right = match right_counts.get_mut(&row_value_key(&row)) {
+            Some(n) if *n > 0 => {
+                if all {
+                    *n -= 1;
+                }
+                true
+            }
+            _ => false,
+        };
+        if in_right == (kind == SetOpKind::Intersect) {
+            rows.push(row);
+        }
+    }
+    if !all {
+        rows = dedupe_rows(rows);
+    }
+    Ok(QueryResult::select(*schema, rows))
+}
+
+fn row_value_key(row: &Row) -> Vec<u8> {
+    let mut key = Vec::new();
+    for v in row {
+        push_value_key(&mut key, v);
+    }
+    key
+}
+
+fn schema_columns(schema: &Schema) -> Vec<(String, DataType)> {
+    schema
+        .columns
+        .iter()
+        .map(|c| (c.name.clone(), c.dtype.clone()))
+        .collect()
+}
+
+fn set_op_name(kind: SetOpKind) -> &'static str {
+    match kind {
+        SetOpKind::Intersect => "INTERSECT",
+        SetOpKind::Except => "EXCEPT",
+    }
+}
+
+/// Whether values of two column types can meet in one column: the same
+/// datatype, or, as in JOIN, int and bigint; so may text and varchar,
+/// which compare by content.
+fn column_types_combine(left: &DataType, right: &DataType) -> bool {
+    join_key_types_compatible(left, right)
+        || matches!(
+            (left, right),
+            (
+                DataType::Text | DataType::VarChar(_),
+                DataType::Text | DataType::VarChar(_)
+            )
+        )
+}
+
+/// Both SELECTs of a set operation must return as many columns, pairwise
+/// of types that combine.
+fn check_set_op_columns(
+    kind: SetOpKind,
+    left: &[(String, DataType)],
+    right: &[(String, DataType)],
+) -> Result<(), String> {
+    let op = set_op_name(kind);
+    if left.len() != right.len() {
+        return Err(format!(
+            "{op}: left SELECT returns {} column(s) but right SELECT returns {}",
+            left.len(),
+            right.len()
+        ));
+    }
+    for (i, ((lname, ltype), (rname, rtype))) in left.iter().zip(right).enumerate() {
+        if !column_types_combine(ltype, rtype) {
+            return Err(format!(
+                "{op}: column {} '{lname}' ({}) cannot be combined with column '{rname}' ({})",
+                i + 1,
+                datatype_name(ltype),
+                datatype_name(rtype)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The plan of a set operation: both SELECTs resolved, with the left one's
+/// output columns.
+fn plan_set_op(
+    kind: SetOpKind,
+    left: Command,
+    right: Command,
+    all: bool,
+    catalog: &Catalog,
+) -> Result<StatementPlan, String> {
+    let mut plan = describe_command(left, catalog)?;
+    let right = describe_command(right, catalog)?;
+    let plan_columns = |cols: &[PlanColumn]| -> Result<Vec<(String, DataType)>, String> {
+        cols.iter()
+            .map(|c| Ok((c.name.clone(), parse_datatype(&c.dtype)?)))
+            .collect()
+    };
+    check_set_op_columns(
+        kind,
+        &plan_columns(&plan.output)?,
+        &plan_columns(&right.output)?,
+    )?;
+    let op = set_op_name(kind).to_lowercase();
+    plan.statement = if all { format!("{op} all") } else { op };
+    plan.access = None;
+    for table in right.tables {
+        if !plan.tables.contains(&table) {
+            plan.tables.push(table);
+        }
+    }
+    plan.bindings.extend(right.bindings);
+    plan.aliases.extend(right.aliases);
+    Ok(plan)
+}
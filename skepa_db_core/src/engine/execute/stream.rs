@@ -0,0 +1,122 @@
+/// Runs a SELECT, handing each output row to `sink` as it is produced, and
+/// returns the output schema. Single-table selects without grouping,
+/// aggregates, DISTINCT, ORDER BY or an index-eligible filter are streamed
+/// straight from the table scan; everything else, INTERSECT and EXCEPT
+/// included, is materialized first and then replayed. With `max_rows`, at
+/// most that many rows reach `sink` and the scan stops once they have. An
+/// error from `sink` stops the scan and is returned as is.
+pub fn stream_select(
+    cmd: Command,
+    catalog: &Catalog,
+    storage: &dyn StorageEngine,
+    max_join_rows: Option<usize>,
+    max_rows: Option<usize>,
+    sink: &mut dyn FnMut(&Schema, &Row) -> Result<(), String>,
+) -> Result<Schema, String> {
+    if let Command::SetOp { .. } = cmd {
+        let QueryResult::Select { schema, rows, .. } =
+            execute_read(cmd, catalog, storage, max_join_rows)?
+        else {
+            return Err("Internal error: SELECT did not produce rows".to_string());
+        };
+        for row in rows.iter().take(max_rows.unwrap_or(usize::MAX)) {
+            sink(&schema, row)?;
+        }
+        return Ok(*schema);
+    }
+    let Command::Select {
+        table,
+        distinct,
+        join,
+        columns,
+        filter,
+        group_by,
+        having,
+        order_by,
+        limit,
+        offset,
+        for_update,
+        as_of,
+    } = cmd
+    else {
+        return Err("Only SELECT statements can be streamed".to_string());
+    };
+    if for_update {
+        return Err("FOR UPDATE is not supported when streaming".to_string());
+    }
+    if as_of.is_some() {
+        return Err("AS OF is not supported when streaming".to_string());
+    }
+
+    let streamable = join.is_none()
+        && !distinct
+        && having.is_none()
+        && order_by.is_none()
+        && !has_group_or_aggregate(columns.as_ref(), group_by.as_ref())
+        && !filter
+            .as_ref()
+            .and_then(simple_eq_filter)
+            .is_some_and(|_| storage.indexes_current(&table))
+        && !filter.as_ref().is_some_and(|f| {
+            catalog.schema(&table).is_ok_and(|schema| {
+                like_terms(f).any(|p| schema.trigram_indexes.contains(&p.column))
+            })
+        });
+    if !streamable {
+        let result = handle_select(
+            table, distinct, join, columns, filter, group_by, having, order_by, limit, offset,
+            catalog, storage, max_join_rows,
+        )?;
+        let QueryResult::Select { schema, rows, .. } = result else {
+            return Err("Internal error: SELECT did not produce rows".to_string());
+        };
+        for row in rows.iter().take(max_rows.unwrap_or(usize::MAX)) {
+            sink(&schema, row)?;
+        }
+        return Ok(*schema);
+    }
+
+    let schema = catalog.schema(&table)?;
+    let compiled = match filter.as_ref() {
+        Some(where_clause) => {
+            let aliases: Vec<String> = columns
+                .iter()
+                .flatten()
+                .filter_map(|c| split_select_alias(c).1)
+                .collect();
+            validate_where_columns(schema, where_clause, &aliases)?;
+            let scope = SubqueryScope { catalog, storage };
+            Some(CompiledWhere::compile_in(schema, where_clause, "WHERE", Some(scope))?)
+        }
+        None => None,
+    };
+    let projection = resolve_projection(schema, columns.as_ref())?;
+    let out_schema = projection
+        .as_ref()
+        .map_or_else(|| schema.clone(), |(s, _)| s.clone());
+
+    let mut to_skip = offset.unwrap_or(0);
+    let mut remaining = limit
+        .unwrap_or(usize::MAX)
+        .min(max_rows.unwrap_or(usize::MAX));
+    for row in storage.scan(&table)? {
+        if remaining == 0 {
+            break;
+        }
+        if let Some(compiled) = &compiled
+            && !compiled.eval(row)?
+        {
+            continue;
+        }
+        if to_skip > 0 {
+            to_skip -= 1;
+            continue;
+        }
+        remaining -= 1;
+        match &projection {
+            Some((_, exprs)) => sink(&out_schema, &project_row(exprs, row))?,
+            None => sink(&out_schema, row)?,
+        }
+    }
+    Ok(out_schema)
+}
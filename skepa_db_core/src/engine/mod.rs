@@ -1,4 +1,19 @@
 pub mod execute;
 
+pub use execute::create_table_as_columns;
+pub use execute::describe_command;
 pub use execute::execute_command;
+pub use execute::execute_read;
+pub use execute::insert_select_rows;
+pub use execute::is_read_command;
+#[cfg(feature = "join-copy-count")]
+pub use execute::join_right_row_copies;
+pub use execute::locked_row_indices;
+pub use execute::prepare_bulk_rows;
+pub use execute::relax_where_literals;
+pub use execute::resolve_index_name;
+pub use execute::stream_select;
+pub use execute::validate_deferred_foreign_keys;
+pub use execute::validate_json_size;
 pub use execute::validate_no_action_constraints;
+pub use execute::validate_table_unique_constraints;
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use thiserror::Error;
 
 pub type DbResult<T> = Result<T, DbError>;
@@ -8,6 +9,71 @@ pub enum DbError {
     Message(String),
 }
 
+/// Coarse category of a [`DbError`], for callers that branch on failures
+/// or report them in machine-readable form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DbErrorKind {
+    SqlParse,
+    UniqueViolation,
+    NotNullViolation,
+    ForeignKeyViolation,
+    InvalidDefault,
+    UnknownTable,
+    UnknownColumn,
+    DuplicateIndex,
+    UnsupportedSyntax,
+    Conflict,
+    MissingTableFile,
+    NotPermitted,
+    Execution,
+}
+
+impl DbError {
+    /// Classifies the error from its message. The checks run in order, so a
+    /// message naming both a unique and a foreign key reports the former.
+    pub fn kind(&self) -> DbErrorKind {
+        let Self::Message(message) = self;
+        let lowercase = message.to_lowercase();
+        if lowercase.contains("not permitted on this restricted handle") {
+            DbErrorKind::NotPermitted
+        } else if lowercase.contains("table file for")
+            && (lowercase.contains("is missing") || lowercase.contains("is unreadable"))
+        {
+            DbErrorKind::MissingTableFile
+        } else if lowercase.contains("unique")
+            || lowercase.contains("primary key")
+            || lowercase.contains("duplicate")
+        {
+            DbErrorKind::UniqueViolation
+        } else if lowercase.contains("not null") {
+            DbErrorKind::NotNullViolation
+        } else if lowercase.contains("foreign key") || lowercase.contains("references") {
+            DbErrorKind::ForeignKeyViolation
+        } else if lowercase.contains("invalid default") {
+            DbErrorKind::InvalidDefault
+        } else if lowercase.contains("does not exist")
+            && (lowercase.contains("table") || lowercase.contains("storage"))
+        {
+            DbErrorKind::UnknownTable
+        } else if lowercase.contains("unknown column") {
+            DbErrorKind::UnknownColumn
+        } else if lowercase.contains("index")
+            && (lowercase.contains("already exists") || lowercase.contains("duplicate"))
+        {
+            DbErrorKind::DuplicateIndex
+        } else if lowercase.contains("not supported") || lowercase.contains("unsupported") {
+            DbErrorKind::UnsupportedSyntax
+        } else if lowercase.contains("conflict") {
+            DbErrorKind::Conflict
+        } else if lowercase.contains("parse") || lowercase.contains("syntax") {
+            DbErrorKind::SqlParse
+        } else {
+            DbErrorKind::Execution
+        }
+    }
+}
+
 impl From<String> for DbError {
     fn from(value: String) -> Self {
         Self::Message(value)
@@ -0,0 +1,176 @@
+use super::*;
+
+impl Database {
+    /// Runs `insert into <table> [(<col>, ...)] select ...`. The SELECT is
+    /// read in full first, then each of its rows goes through the INSERT
+    /// path with its constraints and triggers. Outside a transaction the
+    /// rows commit as one; inside one, a failing row takes the rows before
+    /// it back out, so the statement stages all of its rows or none.
+    pub(super) fn insert_select(
+        &mut self,
+        table: &str,
+        columns: Option<&[String]>,
+        query: Command,
+    ) -> DbResult<QueryResult> {
+        if self.catalog.view(table).is_some() {
+            return Err(DbError::from(format!("Cannot modify view '{table}'")));
+        }
+        if let Some((alias, _)) = self.split_attached(table) {
+            return Err(DbError::from(format!(
+                "Attached database '{alias}' is read-only"
+            )));
+        }
+        let out = self.read_select_source(query).map_err(DbError::from)?;
+        let QueryResult::Select { schema, rows, .. } = out else {
+            return Err(DbError::from(
+                "INSERT ... SELECT: the SELECT did not produce rows",
+            ));
+        };
+        let inserts = engine::insert_select_rows(
+            table,
+            columns,
+            &schema,
+            rows,
+            &self.catalog,
+            &mut self.uuid_gen,
+        )
+        .map_err(DbError::from)?;
+        let count = inserts.len();
+
+        let autocommit = self.current_tx.is_none();
+        let saved = if autocommit {
+            self.handle_begin().map_err(DbError::from)?;
+            None
+        } else {
+            Some((self.storage.clone(), self.current_tx.clone()))
+        };
+        for row in inserts {
+            // Staged and logged as the INSERT it amounts to, so commit and
+            // WAL replay never rerun the SELECT; it runs parsed from that
+            // same text.
+            let literals: Vec<String> = row.iter().map(bulk::sql_literal).collect();
+            let sql = format!("insert into `{table}` values ({})", literals.join(", "));
+            let inserted = parser::parser::parse(&sql)
+                .map_err(DbError::from)
+                .and_then(|cmd| self.execute_statement(&sql, cmd));
+            if let Err(e) = inserted {
+                match saved {
+                    None => {
+                        self.handle_rollback().map_err(DbError::from)?;
+                    }
+                    Some((storage, tx)) => {
+                        self.storage = storage;
+                        self.current_tx = tx;
+                    }
+                }
+                return Err(e);
+            }
+        }
+        if autocommit {
+            self.handle_commit().map_err(DbError::from)?;
+        }
+
+        let out = QueryResult::mutation(format!("inserted {count} row(s) into {table}"), count);
+        Ok(if !autocommit && self.annotate_staged {
+            out.into_staged()
+        } else {
+            out
+        })
+    }
+
+    /// Runs `create table <table> as select ...`: creates `table` with a
+    /// plain column per SELECT output column holding the SELECT's rows.
+    /// Like CREATE TABLE it is auto-commit, and the schema and rows are
+    /// written together, the way a materialized view's are: if anything
+    /// fails, the table does not exist afterwards.
+    pub(super) fn create_table_as(&mut self, table: &str, query: Command) -> DbResult<QueryResult> {
+        if self.current_tx.is_some() {
+            return Err(DbError::from(
+                "CREATE TABLE ... AS is auto-commit and cannot run inside an active transaction",
+            ));
+        }
+        if let Some((alias, _)) = self.split_attached(table) {
+            return Err(DbError::from(format!(
+                "Attached database '{alias}' is read-only"
+            )));
+        }
+        let out = self.read_select_source(query).map_err(DbError::from)?;
+        let QueryResult::Select { schema, rows, .. } = out else {
+            return Err(DbError::from(
+                "CREATE TABLE ... AS: the SELECT did not produce rows",
+            ));
+        };
+        let source: Vec<_> = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.dtype.clone()))
+            .collect();
+        let columns = engine::create_table_as_columns(&source).map_err(DbError::from)?;
+        if let Some(max) = self.max_json_bytes {
+            for row in &rows {
+                Self::check_bulk_json_size(&schema, row, max)?;
+            }
+        }
+        let count = rows.len();
+
+        let create = Command::Create {
+            table: table.to_string(),
+            columns,
+            table_constraints: Vec::new(),
+            if_not_exists: false,
+            partition_by: None,
+        };
+        let base = self.change_base(table);
+        let before = (self.catalog.clone(), self.storage.snapshot_table(table));
+        let created = engine::execute_command(
+            create,
+            &mut self.catalog,
+            &mut self.storage,
+            &mut self.uuid_gen,
+            self.max_join_rows,
+            false,
+        )
+        .and_then(|_| {
+            let schema = self.catalog.schema(table)?.clone();
+            self.storage.load_rows(table, &schema, rows)
+        });
+        if let Err(e) = created {
+            let table_created = self.catalog.exists(table) && !before.0.exists(table);
+            self.catalog = before.0;
+            if table_created {
+                let _ = self.storage.remove_table(table);
+            }
+            return Err(DbError::from(e));
+        }
+        if let Err(e) = self.persist_schema_change(Some(table), Some(before)) {
+            let _ = self.storage.remove_table(table);
+            return Err(DbError::from(e));
+        }
+        let events = self.changes_since(base.iter().map(|(t, s)| (t, s)));
+        self.fire_changes(&events);
+        Ok(QueryResult::schema_change(format!(
+            "created table {table} ({count} row(s))"
+        )))
+    }
+
+    /// Runs the SELECT of an INSERT ... SELECT, a CREATE TABLE ... AS or a
+    /// stream the way a SELECT statement would run, reading views, attached
+    /// tables and snapshots, but without the `max_rows` cap.
+    pub(super) fn read_select_source(&self, query: Command) -> Result<QueryResult, String> {
+        if matches!(query, Command::Select { as_of: Some(_), .. }) {
+            return self.select_as_of(query);
+        }
+        if self.names_attached_table(&query) {
+            return self.execute_attached(query);
+        }
+        if self.names_view(&query) {
+            return self.execute_on_view(query);
+        }
+        engine::execute_read(
+            self.read_command(query, &self.catalog),
+            &self.catalog,
+            &self.storage,
+            self.max_join_rows,
+        )
+    }
+}
@@ -33,3 +33,19 @@ pub(crate) fn render_query_result(result: &QueryResult) -> String {
         QueryResult::Transaction { message, .. } => message.clone(),
     }
 }
+
+/// JSON layout for `output_format = json`: a SELECT becomes
+/// `{"columns": [...], "rows": [[...], ...]}` and every other result
+/// `{"message": "..."}`.
+pub(crate) fn render_query_result_json(result: &QueryResult) -> String {
+    let value = match result {
+        QueryResult::Select { schema, rows, .. } => serde_json::json!({
+            "columns": schema.columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            "rows": rows,
+        }),
+        QueryResult::Mutation { message, .. }
+        | QueryResult::SchemaChange { message, .. }
+        | QueryResult::Transaction { message, .. } => serde_json::json!({ "message": message }),
+    };
+    value.to_string()
+}
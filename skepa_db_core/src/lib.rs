@@ -3,36 +3,84 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::{fs, io::Write};
 
+pub mod builder;
+pub mod change_event;
 pub mod config;
+pub mod database_view;
 pub mod engine;
 pub mod error;
 pub mod execution_stats;
 pub mod parser;
 pub mod query_result;
+pub mod recovery_report;
+pub mod restricted;
+pub mod settings;
+pub mod statement_plan;
 pub mod storage;
+pub mod table_stats;
+pub mod transaction_info;
 pub mod types;
+pub mod wal_info;
 
+mod attachments;
+mod bulk;
+mod change_hooks;
+mod insert_select;
 mod legacy_render;
 mod recovery;
+mod snapshots;
 mod storage_test_hooks;
 mod transactions;
+mod views;
 
-use config::DbConfig;
+use config::{AutoCheckpoint, ConflictDetection, DbConfig};
 use error::{DbError, DbResult};
 use parser::command::Command;
 use query_result::QueryResult;
-use storage::{Catalog, DiskStorage};
+use recovery_report::RecoveryReport;
+use settings::{OutputFormat, SessionSettings};
+use statement_plan::StatementPlan;
+use storage::generations::Generations;
+use storage::{Catalog, DiskStorage, Schema, StorageEngine};
+use table_stats::TableStats;
+use transaction_info::TransactionInfo;
+use types::Row;
+use types::uuid_gen::UuidGenerator;
+use wal_info::WalInfo;
 
-pub const STORAGE_FORMAT_VERSION: u32 = 1;
+pub const STORAGE_FORMAT_VERSION: u32 = 2;
 
 #[derive(Debug, Clone)]
 struct TxState {
     txid: u64,
+    /// Statements to log at commit, as [`recovery::with_generated_uuids`]
+    /// renders them.
     staged_ops: Vec<String>,
+    /// Total length of the staged statements' source text, checked against
+    /// `tx_max_staged_bytes`.
+    staged_bytes: usize,
     touched_tables: std::collections::HashSet<String>,
-    table_versions_at_begin: std::collections::HashMap<String, u64>,
+    /// Each table's write counter at `begin`, from `generations.json`.
+    table_versions_at_begin: std::collections::BTreeMap<String, u64>,
+    /// Fingerprint of `catalog.json` at BEGIN; any committed schema change
+    /// by another handle alters it.
+    catalog_version_at_begin: u64,
+    /// Set once a write runs with `defer_constraints` on; commit then checks
+    /// every foreign key before writing the WAL.
+    deferred_foreign_keys: bool,
+    /// The catalog as of BEGIN, including the full set of tables.
     snapshot_catalog: Catalog,
-    snapshot_storage: DiskStorage,
+    /// Pre-transaction copies of the tables this transaction may have
+    /// written, taken the first time a statement could touch each one.
+    table_snapshots: std::collections::HashMap<String, storage::disk::TableSnapshot>,
+    /// Rows read with `select ... for update`, by table and row id, with
+    /// their values as of BEGIN. Commit fails if another handle changed
+    /// any of them.
+    locked_rows: std::collections::BTreeMap<String, std::collections::BTreeMap<u64, Row>>,
+    /// Tables read by a subquery in a staged UPDATE or DELETE. WAL replay
+    /// runs the subquery again, so commit fails if another handle changed
+    /// any of them.
+    subquery_tables: std::collections::BTreeSet<String>,
 }
 
 #[derive(Debug)]
@@ -42,14 +90,52 @@ pub struct Database {
     storage: DiskStorage,
     current_tx: Option<TxState>,
     next_txid: u64,
+    uuid_gen: UuidGenerator,
+    max_json_bytes: Option<usize>,
+    max_join_rows: Option<usize>,
+    annotate_staged: bool,
+    /// The directory write counter this handle last loaded or wrote.
+    disk_generation: u64,
+    wal_file: Option<fs::File>,
+    /// Lock on `db.lock`, held for the handle's lifetime: shared with
+    /// `AutoCheckpoint::EveryCommit`, exclusive otherwise.
+    _dir_lock: fs::File,
+    tx_snapshot_rows: u64,
+    reserved_table_names: Option<Vec<String>>,
+    recreate_missing_table_files: bool,
+    conflict_detection: ConflictDetection,
+    tx_max_staged_statements: Option<usize>,
+    tx_max_staged_bytes: Option<usize>,
+    auto_checkpoint: AutoCheckpoint,
+    /// Tables written since the last checkpoint; only these files are
+    /// rewritten before the WAL is truncated.
+    dirty_tables: std::collections::BTreeSet<String>,
+    settings: SessionSettings,
+    /// `attach` aliases and the database directories they name.
+    attachments: std::collections::BTreeMap<String, PathBuf>,
+    change_hooks: change_hooks::ChangeHooks,
+    recovery_report: RecoveryReport,
 }
 
 impl Database {
     /// Canonical stable engine constructor for the public API.
     pub fn open(config: DbConfig) -> DbResult<Self> {
-        let path = config.path;
+        let path = config.path.clone();
         let storage = Self::initialize_storage(&path)?;
-        let catalog = Self::load_catalog(&path)?;
+        let dir_lock =
+            Self::lock_directory(&path, config.auto_checkpoint != AutoCheckpoint::EveryCommit)
+                .map_err(DbError::from)?;
+        // Recovery may rewrite files another handle is about to write.
+        let write_lock = if config.auto_checkpoint == AutoCheckpoint::EveryCommit {
+            Some(Self::lock_writes(&path).map_err(DbError::from)?)
+        } else {
+            None
+        };
+        storage::persistence::recover_journal(&path).map_err(DbError::from)?;
+        let mut catalog = Self::load_catalog(&path)?;
+        if let Some(names) = &config.reserved_table_names {
+            catalog.set_reserved_table_names(names.clone());
+        }
 
         let mut db = Self {
             path,
@@ -57,10 +143,34 @@ impl Database {
             storage,
             current_tx: None,
             next_txid: 1,
+            uuid_gen: UuidGenerator::from_seed(config.uuid_seed),
+            max_json_bytes: config.max_json_bytes,
+            max_join_rows: config.max_join_rows,
+            annotate_staged: config.annotate_staged,
+            disk_generation: 0,
+            wal_file: None,
+            _dir_lock: dir_lock,
+            tx_snapshot_rows: 0,
+            reserved_table_names: config.reserved_table_names,
+            recreate_missing_table_files: config.recreate_missing_table_files,
+            conflict_detection: config.conflict_detection,
+            tx_max_staged_statements: config.tx_max_staged_statements,
+            tx_max_staged_bytes: config.tx_max_staged_bytes,
+            auto_checkpoint: config.auto_checkpoint,
+            dirty_tables: std::collections::BTreeSet::new(),
+            settings: SessionSettings::default(),
+            attachments: std::collections::BTreeMap::new(),
+            change_hooks: change_hooks::ChangeHooks::default(),
+            recovery_report: RecoveryReport::default(),
         };
 
         db.bootstrap_tables()?;
         db.recover()?;
+        if config.keep_wal_open {
+            db.wal_file = Some(Self::open_wal(&db.path).map_err(DbError::from)?);
+        }
+        db.mark_disk_seen();
+        drop(write_lock);
         Ok(db)
     }
 
@@ -85,13 +195,136 @@ impl Database {
     #[doc(hidden)]
     pub fn execute_legacy(&mut self, input: &str) -> Result<String, String> {
         self.execute(input)
-            .map(|result| legacy_render::render_query_result(&result))
+            .map(|result| self.render(&result))
             .map_err(|err| err.to_string())
     }
 
+    /// Renders `result` as text in the session's `output_format`.
+    pub fn render(&self, result: &QueryResult) -> String {
+        match self.settings.output_format {
+            OutputFormat::Tabs => legacy_render::render_query_result(result),
+            OutputFormat::Json => legacy_render::render_query_result_json(result),
+        }
+    }
+
+    /// Session settings changed by `set` on this handle.
+    pub fn settings(&self) -> &SessionSettings {
+        &self.settings
+    }
+
+    /// `cmd` the way reads on this handle run it against `catalog`: with the
+    /// `strict_types` setting off, a WHERE literal that is not a value of its
+    /// column's type matches no row instead of failing.
+    fn read_command(&self, cmd: Command, catalog: &Catalog) -> Command {
+        if self.settings.strict_types {
+            cmd
+        } else {
+            engine::relax_where_literals(cmd, catalog)
+        }
+    }
+
     /// Canonical stable engine execution entry point for the public API.
+    ///
+    /// Outside a transaction each statement first picks up anything other
+    /// handles on the same directory have committed; inside one it sees the
+    /// state as of `begin` plus its own staged writes.
     pub fn execute(&mut self, input: &str) -> DbResult<QueryResult> {
         let cmd = parser::parser::parse(input).map_err(DbError::from)?;
+        self.execute_parsed(input, cmd)
+    }
+
+    /// Runs an already parsed `cmd`; `input` is its source text, which is
+    /// what gets staged and written to the WAL.
+    pub(crate) fn execute_parsed(&mut self, input: &str, cmd: Command) -> DbResult<QueryResult> {
+        let write = !engine::is_read_command(&cmd);
+        self.with_fresh_view(write, |db| db.execute_statement(input, cmd))
+    }
+
+    /// Runs a SELECT and hands each output row to `sink` as it is produced
+    /// instead of collecting a [`QueryResult`], returning the output schema
+    /// (also available when no rows match). Plain single-table selects are
+    /// never held in memory as a whole; joins, grouping, aggregates,
+    /// DISTINCT and ORDER BY still materialize before the first row is
+    /// delivered. An error from `sink` stops the query and is returned.
+    pub fn query_stream(
+        &mut self,
+        sql: &str,
+        sink: &mut dyn FnMut(&Schema, &Row) -> Result<(), String>,
+    ) -> DbResult<Schema> {
+        self.with_fresh_view(false, |db| {
+            let cmd = parser::parser::parse(sql).map_err(DbError::from)?;
+            let resolved_elsewhere = matches!(cmd, Command::Select { as_of: Some(_), .. })
+                || db.names_attached_table(&cmd)
+                || db.names_view(&cmd);
+            if resolved_elsewhere {
+                return db.replay_select(cmd, sink).map_err(DbError::from);
+            }
+            let cmd = db.read_command(cmd, &db.catalog);
+            engine::stream_select(
+                cmd,
+                &db.catalog,
+                &db.storage,
+                db.max_join_rows,
+                db.settings.max_rows,
+                sink,
+            )
+            .map_err(DbError::from)
+        })
+    }
+
+    /// Streams a SELECT over snapshots, attached databases or views by
+    /// running it the way `execute` would and replaying its rows.
+    fn replay_select(
+        &self,
+        cmd: Command,
+        sink: &mut dyn FnMut(&Schema, &Row) -> Result<(), String>,
+    ) -> Result<Schema, String> {
+        match &cmd {
+            Command::Select {
+                for_update: true, ..
+            } => return Err("FOR UPDATE is not supported when streaming".to_string()),
+            Command::Select { .. } | Command::SetOp { .. } => {}
+            _ => return Err("Only SELECT statements can be streamed".to_string()),
+        }
+        let QueryResult::Select { schema, rows, .. } = self.read_select_source(cmd)? else {
+            return Err("Internal error: SELECT did not produce rows".to_string());
+        };
+        for row in rows
+            .iter()
+            .take(self.settings.max_rows.unwrap_or(usize::MAX))
+        {
+            sink(&schema, row)?;
+        }
+        Ok(*schema)
+    }
+
+    /// Runs `run` on the latest committed state. A `write` holds the write
+    /// lock throughout, so no other handle commits between the refresh and
+    /// its own files reaching disk.
+    fn with_fresh_view<T>(
+        &mut self,
+        write: bool,
+        run: impl FnOnce(&mut Self) -> DbResult<T>,
+    ) -> DbResult<T> {
+        let write_lock = if write {
+            self.write_guard().map_err(DbError::from)?
+        } else {
+            None
+        };
+        self.refresh_if_stale(write_lock.is_some())
+            .map_err(DbError::from)?;
+        let was_in_tx = self.current_tx.is_some();
+        let out = run(self);
+        // After commit/rollback the generation stays at its pre-`begin`
+        // value, so the next statement reloads whatever others committed.
+        if !was_in_tx {
+            self.mark_disk_seen();
+        }
+        out
+    }
+
+    fn execute_statement(&mut self, input: &str, cmd: Command) -> DbResult<QueryResult> {
+        let cmd = engine::resolve_index_name(cmd, &self.catalog).map_err(DbError::from)?;
         if matches!(cmd, Command::Begin) {
             return self
                 .handle_begin()
@@ -104,12 +337,127 @@ impl Database {
                 .map(QueryResult::transaction)
                 .map_err(DbError::from);
         }
+        if matches!(cmd, Command::ShowTransaction) {
+            return Ok(QueryResult::transaction(self.describe_transaction()));
+        }
+        if let Command::Set { name, value } = &cmd {
+            self.settings.set(name, value).map_err(DbError::from)?;
+            let value = self.settings.get(name).map_err(DbError::from)?;
+            return Ok(QueryResult::transaction(format!(
+                "set {} = {value}",
+                name.to_ascii_lowercase()
+            )));
+        }
+        if let Command::Show { name } = &cmd {
+            let value = self.settings.get(name).map_err(DbError::from)?;
+            return Ok(QueryResult::transaction(format!(
+                "{} = {value}",
+                name.to_ascii_lowercase()
+            )));
+        }
         if matches!(cmd, Command::Rollback) {
             return self
                 .handle_rollback()
                 .map(QueryResult::transaction)
                 .map_err(DbError::from);
         }
+        match &cmd {
+            Command::CreateSnapshot { name } => {
+                self.take_snapshot(name)?;
+                return Ok(QueryResult::transaction(format!("created snapshot {name}")));
+            }
+            Command::DropSnapshot { name } => {
+                self.drop_snapshot(name)?;
+                return Ok(QueryResult::transaction(format!("dropped snapshot {name}")));
+            }
+            Command::ListSnapshots => {
+                let schema = Schema::new(vec![storage::Column {
+                    name: "name".to_string(),
+                    dtype: types::datatype::DataType::Text,
+                    primary_key: false,
+                    unique: false,
+                    not_null: true,
+                    default: None,
+                    json_shape: None,
+                }]);
+                let rows = self
+                    .list_snapshots()?
+                    .into_iter()
+                    .map(|name| vec![types::value::Value::Text(name)])
+                    .collect();
+                return Ok(QueryResult::select(schema, rows));
+            }
+            Command::Attach { path, alias } => {
+                self.attach(path, alias)?;
+                return Ok(QueryResult::transaction(format!("attached {alias}")));
+            }
+            Command::Detach { alias } => {
+                self.detach(alias)?;
+                return Ok(QueryResult::transaction(format!("detached {alias}")));
+            }
+            Command::CreateView {
+                name,
+                materialized,
+                query,
+                sql,
+            } => {
+                self.create_view(name, *materialized, (**query).clone(), sql)?;
+                let kind = if *materialized {
+                    "materialized view"
+                } else {
+                    "view"
+                };
+                return Ok(QueryResult::schema_change(format!("created {kind} {name}")));
+            }
+            Command::RefreshMaterializedView { name } => {
+                let rows = self.refresh_materialized_view(name)?;
+                return Ok(QueryResult::schema_change(format!(
+                    "refreshed materialized view {name} ({rows} row(s))"
+                )));
+            }
+            Command::DropView { name } => {
+                self.drop_view(name)?;
+                return Ok(QueryResult::schema_change(format!("dropped view {name}")));
+            }
+            Command::InsertSelect {
+                table,
+                columns,
+                query,
+            } => return self.insert_select(table, columns.as_deref(), (**query).clone()),
+            Command::CreateTableAs { table, query } => {
+                return self.create_table_as(table, (**query).clone());
+            }
+            Command::ShowTables { with_stats } => return Ok(self.show_tables(*with_stats)),
+            Command::Checkpoint => {
+                let message = self.checkpoint_statement().map_err(DbError::from)?;
+                return Ok(QueryResult::transaction(message));
+            }
+            Command::ShowWal => return self.show_wal(),
+            Command::Select { as_of: Some(_), .. } => {
+                let out = self.select_as_of(cmd).map_err(DbError::from)?;
+                return Ok(match self.settings.max_rows {
+                    Some(max) => out.truncate_rows(max),
+                    None => out,
+                });
+            }
+            _ => {}
+        }
+        if self.names_attached_table(&cmd) {
+            let out = self.execute_attached(cmd).map_err(DbError::from)?;
+            return Ok(match self.settings.max_rows {
+                Some(max) => out.truncate_rows(max),
+                None => out,
+            });
+        }
+        // CREATE TABLE under a view's name falls through to the catalog's
+        // "already exists" error.
+        if !matches!(cmd, Command::Create { .. }) && self.names_view(&cmd) {
+            let out = self.execute_on_view(cmd).map_err(DbError::from)?;
+            return Ok(match self.settings.max_rows {
+                Some(max) => out.truncate_rows(max),
+                None => out,
+            });
+        }
 
         if self.current_tx.is_some()
             && matches!(
@@ -118,10 +466,13 @@ impl Database {
                     | Command::Alter { .. }
                     | Command::CreateIndex { .. }
                     | Command::DropIndex { .. }
+                    | Command::Comment { .. }
+                    | Command::CreateTrigger { .. }
+                    | Command::DropTrigger { .. }
             )
         {
             return Err(DbError::from(
-                "CREATE/ALTER TABLE and CREATE/DROP INDEX are auto-commit and cannot run inside an active transaction"
+                "CREATE/ALTER TABLE, CREATE/DROP INDEX, CREATE/DROP TRIGGER and COMMENT ON are auto-commit and cannot run inside an active transaction"
                     .to_string(),
             ));
         }
@@ -134,9 +485,30 @@ impl Database {
             Command::Insert { table, .. } => Some(table.clone()),
             Command::Update { table, .. } => Some(table.clone()),
             Command::Delete { table, .. } => Some(table.clone()),
-            Command::Describe { .. } => None,
-            Command::Select { .. } => None,
-            Command::Begin | Command::Commit | Command::Rollback => None,
+            Command::Comment { .. }
+            | Command::CreateTrigger { .. }
+            | Command::DropTrigger { .. } => None,
+            Command::Describe { .. } | Command::ShowIndexes { .. } => None,
+            Command::Select { .. } | Command::SetOp { .. } => None,
+            Command::Begin
+            | Command::Commit
+            | Command::Rollback
+            | Command::ShowTransaction
+            | Command::Set { .. }
+            | Command::Show { .. }
+            | Command::CreateSnapshot { .. }
+            | Command::DropSnapshot { .. }
+            | Command::ListSnapshots
+            | Command::Attach { .. }
+            | Command::Detach { .. }
+            | Command::InsertSelect { .. }
+            | Command::CreateTableAs { .. }
+            | Command::CreateView { .. }
+            | Command::DropView { .. }
+            | Command::RefreshMaterializedView { .. }
+            | Command::ShowTables { .. }
+            | Command::Checkpoint
+            | Command::ShowWal => None,
         };
         let is_schema_write = matches!(
             cmd,
@@ -144,7 +516,16 @@ impl Database {
                 | Command::Alter { .. }
                 | Command::CreateIndex { .. }
                 | Command::DropIndex { .. }
+                | Command::Comment { .. }
+                | Command::CreateTrigger { .. }
+                | Command::DropTrigger { .. }
         );
+        // Schema changes rewrite table files directly. Groups still waiting
+        // in the WAL go into those files first; replaying them on top after
+        // a crash would apply them twice.
+        if is_schema_write && !self.dirty_tables.is_empty() {
+            self.checkpoint_and_truncate_wal().map_err(DbError::from)?;
+        }
         let is_wal_write = matches!(
             cmd,
             Command::Insert { .. } | Command::Update { .. } | Command::Delete { .. }
@@ -161,16 +542,125 @@ impl Database {
         } else {
             None
         };
+        let pre_schema = match &table_name {
+            Some(table) if is_schema_write => {
+                Some((self.catalog.clone(), self.storage.snapshot_table(table)))
+            }
+            _ => None,
+        };
 
-        let out = engine::execute_command(cmd, &mut self.catalog, &mut self.storage)
-            .map_err(DbError::from)?;
+        if let Some(max) = self.max_json_bytes {
+            engine::validate_json_size(&cmd, &self.catalog, max).map_err(DbError::from)?;
+        }
+        let change_base = match &table_name {
+            Some(table) if !is_in_tx && is_wal_write => self.change_base(table),
+            _ => Vec::new(),
+        };
+
+        if is_in_tx && is_wal_write {
+            self.check_staging_limits(input.trim())
+                .map_err(DbError::from)?;
+        }
+        if is_in_tx
+            && is_wal_write
+            && let Some(table) = &table_name
+        {
+            self.snapshot_tables_for_write(table);
+        }
+        // A statement that fails part-way, say in a trigger after its own row
+        // went in, is undone across every table it can reach. Outside a
+        // transaction `pre_storage` already holds them all.
+        let pre_statement: Vec<(String, storage::disk::TableSnapshot)> = match &table_name {
+            Some(table) if is_in_tx && is_wal_write => {
+                let written = self.tables_written_by(table);
+                if written.len() > 1 {
+                    written
+                        .into_iter()
+                        .map(|name| {
+                            let snapshot = self.storage.snapshot_table(&name);
+                            (name, snapshot)
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        let subquery_tables: Vec<String> = match &cmd {
+            Command::Update { .. } | Command::Delete { .. } if is_in_tx => {
+                restricted::referenced_tables(&cmd)
+                    .into_iter()
+                    .skip(1)
+                    .map(str::to_string)
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let cmd = self.read_command(cmd, &self.catalog);
+        // Worked out before the select runs but reported after it, so the
+        // select's own errors come first.
+        let locked = match &cmd {
+            Command::Select {
+                table,
+                for_update: true,
+                ..
+            } => Some((
+                table.clone(),
+                engine::locked_row_indices(&cmd, &self.catalog, &self.storage),
+            )),
+            _ => None,
+        };
+
+        let defer_foreign_keys = is_in_tx && self.settings.defer_constraints;
+        self.uuid_gen.take_issued();
+        let result = if engine::is_read_command(&cmd) {
+            engine::execute_read(cmd, &self.catalog, &self.storage, self.max_join_rows)
+        } else {
+            engine::execute_command(
+                cmd,
+                &mut self.catalog,
+                &mut self.storage,
+                &mut self.uuid_gen,
+                self.max_join_rows,
+                defer_foreign_keys,
+            )
+        };
+        let mut out = match result {
+            Ok(out) => out,
+            Err(e) => {
+                if let (Some(c), Some(s)) = (pre_catalog, pre_storage) {
+                    self.catalog = c;
+                    self.storage = s;
+                }
+                for (name, snapshot) in pre_statement {
+                    self.storage.restore_table(&name, snapshot);
+                }
+                return Err(DbError::from(e));
+            }
+        };
+        if let Some((table, indices)) = locked {
+            let indices = indices.map_err(DbError::from)?;
+            self.lock_rows(&table, &indices);
+        }
+        if let Some(max) = self.settings.max_rows {
+            out = out.truncate_rows(max);
+        }
+        let logged = recovery::with_generated_uuids(input, &self.uuid_gen.take_issued());
 
         if let Some(tx) = &mut self.current_tx {
             if is_wal_write {
-                tx.staged_ops.push(input.trim().to_string());
+                tx.deferred_foreign_keys |= defer_foreign_keys;
+                tx.staged_bytes += input.trim().len();
+                tx.staged_ops.push(logged);
+                tx.subquery_tables.extend(subquery_tables);
                 if let Some(table) = table_name {
                     tx.touched_tables.insert(table);
                 }
+                if self.annotate_staged {
+                    return Ok(out.into_staged());
+                }
             }
             return Ok(out);
         }
@@ -186,22 +676,23 @@ impl Database {
         }
 
         if is_schema_write {
-            self.save_catalog().map_err(DbError::from)?;
-            if let Some(table) = table_name {
-                self.storage.persist_table(&table).map_err(DbError::from)?;
-            }
+            self.persist_schema_change(table_name.as_deref(), pre_schema)
+                .map_err(DbError::from)?;
         } else if is_wal_write {
             let txid = self.alloc_txid();
-            self.append_wal_line(&format!("BEGIN {}", txid))
-                .map_err(DbError::from)?;
-            self.append_wal_line(&format!("OP {} {}", txid, input.trim()))
-                .map_err(DbError::from)?;
-            self.append_wal_line(&format!("COMMIT {}", txid))
-                .map_err(DbError::from)?;
+            self.append_wal_group(&[
+                format!("BEGIN {}", txid),
+                recovery::wal_op_line(txid, &logged),
+                format!("COMMIT {}", txid),
+            ])
+            .map_err(DbError::from)?;
             if let Some(table) = table_name {
-                self.storage.persist_table(&table).map_err(DbError::from)?;
+                let written = self.tables_written_by(&table);
+                self.dirty_tables.extend(written);
             }
-            self.checkpoint_and_truncate_wal().map_err(DbError::from)?;
+            self.checkpoint_after_commit().map_err(DbError::from)?;
+            let events = self.changes_since(change_base.iter().map(|(t, s)| (t, s)));
+            self.fire_changes(&events);
         }
 
         Ok(out)
@@ -215,8 +706,110 @@ impl Database {
         self.current_tx.is_some()
     }
 
+    /// Details of the open transaction, or `None` outside one. Backs the
+    /// `show transaction` statement.
+    pub fn transaction_info(&self) -> Option<TransactionInfo> {
+        let tx = self.current_tx.as_ref()?;
+        let mut touched_tables: Vec<String> = tx.touched_tables.iter().cloned().collect();
+        touched_tables.sort();
+        Some(TransactionInfo {
+            txid: tx.txid,
+            staged_statements: tx.staged_ops.len(),
+            staged_bytes: tx.staged_bytes,
+            touched_tables,
+            no_action_violation: engine::validate_no_action_constraints(
+                &self.catalog,
+                &self.storage,
+            )
+            .err(),
+        })
+    }
+
     pub fn checkpoint(&self) -> DbResult<()> {
-        self.checkpoint_and_truncate_wal().map_err(DbError::from)
+        let _write_lock = self.write_guard().map_err(DbError::from)?;
+        self.checkpoint_all_and_truncate_wal()
+            .map_err(DbError::from)
+    }
+
+    /// Resolves a statement against the current catalog without executing it.
+    pub fn describe_statement(&self, sql: &str) -> DbResult<StatementPlan> {
+        let cmd = parser::parser::parse(sql).map_err(DbError::from)?;
+        if let Command::Select {
+            as_of: Some(name), ..
+        } = &cmd
+        {
+            let (_, catalog) = self.snapshot_catalog(name).map_err(DbError::from)?;
+            return engine::describe_command(cmd, &catalog).map_err(DbError::from);
+        }
+        if self.names_attached_table(&cmd) {
+            let tables: Vec<String> = restricted::referenced_tables(&cmd)
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+            let catalog = self.attached_catalog(&tables).map_err(DbError::from)?;
+            return engine::describe_command(cmd, &catalog).map_err(DbError::from);
+        }
+        if self.names_view(&cmd) {
+            let catalog = self.view_catalog(&cmd).map_err(DbError::from)?;
+            return engine::describe_command(cmd, &catalog).map_err(DbError::from);
+        }
+        let cmd = engine::resolve_index_name(cmd, &self.catalog).map_err(DbError::from)?;
+        engine::describe_command(cmd, &self.catalog).map_err(DbError::from)
+    }
+
+    /// Names of every table, sorted. Reads only the in-memory catalog, so it
+    /// is cheap enough for per-keystroke completion.
+    pub fn table_names(&self) -> Vec<String> {
+        self.catalog.table_names()
+    }
+
+    /// Column names of `table` in declaration order.
+    pub fn column_names(&self, table: &str) -> DbResult<Vec<String>> {
+        let schema = self.catalog.schema(table).map_err(DbError::from)?;
+        Ok(schema.columns.iter().map(|c| c.name.clone()).collect())
+    }
+
+    /// Every table name followed by its columns qualified as `table.column`,
+    /// tables sorted and columns in declaration order. Catalog-only, like
+    /// [`Database::table_names`].
+    pub fn all_identifiers(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        for table in self.catalog.table_names() {
+            let columns = self.column_names(&table).unwrap_or_default();
+            let qualified: Vec<String> = columns.iter().map(|c| format!("{table}.{c}")).collect();
+            out.push(table);
+            out.extend(qualified);
+        }
+        out
+    }
+
+    /// The parser's keyword list, [`parser::parser::KEYWORDS`].
+    pub fn keyword_list(&self) -> &'static [&'static str] {
+        parser::parser::KEYWORDS
+    }
+
+    /// Read-only size summary for one table: live rows, on-disk bytes of its
+    /// row and index files, and the number of indexes declared in the catalog.
+    pub fn table_stats(&self, table: &str) -> DbResult<TableStats> {
+        let schema = self.catalog.schema(table).map_err(DbError::from)?;
+        let rows = self.storage.scan(table).map_err(DbError::from)?.len();
+        let bytes = self.storage.table_byte_size(table).map_err(DbError::from)?;
+        Ok(TableStats {
+            rows,
+            bytes,
+            index_count: schema.index_count(),
+        })
+    }
+
+    /// Verifies every table's row ids and indexes against its rows. Returns
+    /// the first inconsistency found; intended for tests and diagnostics.
+    pub fn check_integrity(&self) -> DbResult<()> {
+        for (table, schema) in self.catalog.snapshot_tables() {
+            self.storage
+                .verify_table(&table, &schema)
+                .map_err(DbError::from)?;
+        }
+        Ok(())
     }
 
     pub fn storage_format_version(&self) -> u32 {
@@ -253,6 +846,7 @@ impl Database {
     pub fn debug_storage_json(&self) -> DbResult<serde_json::Value> {
         let mut snapshot = self.storage.debug_snapshot(&self.path);
         snapshot["has_active_transaction"] = serde_json::json!(self.has_active_transaction());
+        snapshot["tx_snapshot_rows"] = serde_json::json!(self.tx_snapshot_rows);
         Ok(snapshot)
     }
 }
@@ -26,6 +26,9 @@ pub struct Predicate {
     pub column: String,
     pub op: CompareOp,
     pub value: String,
+    /// IN list items; empty for every other operator.
+    #[serde(default)]
+    pub values: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -36,12 +39,41 @@ pub enum WhereClause {
         op: LogicalOp,
         right: Box<WhereClause>,
     },
+    /// `[not] exists (select ... from <table> ...)`: true when the subquery
+    /// has a row for the outer row being tested. `<col> in (select <c> ...)`
+    /// parses to this too, with `(<c>, <col>)` as a correlation pair.
+    Exists {
+        subquery: Subquery,
+        negated: bool,
+    },
+}
+
+/// The `select ... from <table> [<alias>] [where <expr>]` inside
+/// `exists (...)` or `in (...)`. Its select list is not kept: only whether
+/// a row exists matters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subquery {
+    pub table: String,
+    /// `from <table> <alias>`. The subquery's own column names no longer
+    /// carry the `<alias>.` prefix.
+    pub alias: Option<String>,
+    /// The subquery's WHERE without its correlation terms.
+    pub filter: Option<Box<WhereClause>>,
+    /// `(inner column, outer column)` pairs from `<inner> = <outer>.<col>`
+    /// terms ANDed into the subquery's WHERE. A subquery row matches an
+    /// outer row when every pair holds.
+    pub correlation: Vec<(String, String)>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Assignment {
     pub column: String,
     pub value: String,
+    /// `value` was written as a `"..."` string. A bare value naming a
+    /// column of the table, or a `<table>.<col>`, is read from the row as
+    /// it was before the update, so `set a = b, b = a` swaps the two columns.
+    #[serde(default)]
+    pub quoted: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -57,6 +89,24 @@ pub enum JoinType {
     Left,
 }
 
+/// The `using ...` method of `create index` / `drop index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexKind {
+    /// No `using`: equality lookups on whole column values.
+    Standard,
+    /// `using trigram`: three-character substrings of a text column, for `like`.
+    Trigram,
+}
+
+/// How a [`Command::SetOp`] combines the rows of its two SELECTs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetOpKind {
+    /// `intersect`: left rows that also appear on the right.
+    Intersect,
+    /// `except`: left rows that do not appear on the right.
+    Except,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JoinClause {
     pub join_type: JoinType,
@@ -73,12 +123,26 @@ pub struct ColumnDef {
     pub unique: bool,
     pub not_null: bool,
     pub default: Option<String>,
+    pub json_shape: Option<JsonShape>,
+}
+
+/// Top-level shape a `json` column requires (`check_json_object` /
+/// `check_json_array`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JsonShape {
+    Object,
+    Array,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TableConstraintDef {
     PrimaryKey(Vec<String>),
-    Unique(Vec<String>),
+    /// `unique(<cols>) [nulls not distinct]`; with the modifier two NULLs
+    /// count as equal, so at most one row may hold NULL in each position.
+    Unique {
+        columns: Vec<String>,
+        nulls_not_distinct: bool,
+    },
     ForeignKey {
         columns: Vec<String>,
         ref_table: String,
@@ -98,7 +162,10 @@ pub enum ForeignKeyAction {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AlterAction {
-    AddUnique(Vec<String>),
+    AddUnique {
+        columns: Vec<String>,
+        nulls_not_distinct: bool,
+    },
     DropUnique(Vec<String>),
     AddForeignKey {
         columns: Vec<String>,
@@ -114,53 +181,207 @@ pub enum AlterAction {
     },
     SetNotNull(String),
     DropNotNull(String),
+    /// `add column <def> [first | after <col>]`
+    AddColumn {
+        column: ColumnDef,
+        position: ColumnPosition,
+    },
+}
+
+/// Where `alter table ... add column` places the new column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ColumnPosition {
+    Last,
+    First,
+    After(String),
+}
+
+/// One value of a trigger's INSERT.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriggerValue {
+    /// A literal token, converted like an INSERT value.
+    Literal(String),
+    /// `new.<col>`: the inserted row's value of `<col>`.
+    New(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CommentTarget {
+    Table(String),
+    Column { table: String, column: String },
 }
 
-#[derive(Debug)]
+/// A parsed statement. With the `ast-serde` feature it implements serde's
+/// `Serialize` and `Deserialize`, and a deserialized value runs through
+/// `engine::execute_command` exactly like the one `parse` returned.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-serde", derive(Serialize, Deserialize))]
 #[allow(clippy::large_enum_variant)]
 pub enum Command {
     Begin,
     Commit,
     Rollback,
+    ShowTransaction,
+    /// `set <name> = <value>`: changes a session setting.
+    Set {
+        name: String,
+        value: String,
+    },
+    /// `show <name>`: reports a session setting.
+    Show {
+        name: String,
+    },
+    /// `show indexes from|on <table>`: lists every index on a table.
+    ShowIndexes {
+        table: String,
+    },
+    /// `create snapshot <name>`: copies the checkpointed database aside.
+    CreateSnapshot {
+        name: String,
+    },
+    /// `drop snapshot <name>`
+    DropSnapshot {
+        name: String,
+    },
+    /// `list snapshots`
+    ListSnapshots,
+    /// `attach <path> as <alias>`: opens another database directory
+    /// read-only; its tables are queried as `<alias>.<table>`.
+    Attach {
+        path: String,
+        alias: String,
+    },
+    /// `detach <alias>`
+    Detach {
+        alias: String,
+    },
 
     Create {
         table: String,
         columns: Vec<ColumnDef>,
         table_constraints: Vec<TableConstraintDef>,
+        /// `create table if not exists`: an existing table is left alone.
+        if_not_exists: bool,
+        /// `partition by (<col>)`: rows are stored in one segment per
+        /// distinct value of this column.
+        partition_by: Option<String>,
+    },
+    /// `create table <table> as select ...`: a new table with the SELECT's
+    /// output columns, filled with its rows.
+    CreateTableAs {
+        table: String,
+        /// The parsed SELECT.
+        query: Box<Command>,
     },
     CreateIndex {
         table: String,
         columns: Vec<String>,
+        /// `create index if not exists`: an existing index is left alone.
+        if_not_exists: bool,
+        kind: IndexKind,
+        /// `create index <name> on ...`: lets the index be dropped by name.
+        name: Option<String>,
     },
     DropIndex {
         table: String,
         columns: Vec<String>,
+        kind: IndexKind,
+        /// `drop index <name>`: `table` and `columns` stay empty until the
+        /// name is resolved against the catalog.
+        name: Option<String>,
     },
     Alter {
         table: String,
         action: AlterAction,
     },
+    /// `create trigger <name> after insert on <table> begin insert into
+    /// <target> values (...) end`
+    CreateTrigger {
+        name: String,
+        table: String,
+        target: String,
+        values: Vec<TriggerValue>,
+    },
+    /// `drop trigger <name>`
+    DropTrigger {
+        name: String,
+    },
+    /// `create [materialized] view <name> as select ...`
+    CreateView {
+        name: String,
+        /// `materialized`: the rows are computed once and stored, and only
+        /// `refresh materialized view` recomputes them.
+        materialized: bool,
+        /// The parsed SELECT.
+        query: Box<Command>,
+        /// The SELECT as written, which is what the catalog stores.
+        sql: String,
+    },
+    /// `drop [materialized] view <name>`
+    DropView {
+        name: String,
+    },
+    /// `refresh materialized view <name>`
+    RefreshMaterializedView {
+        name: String,
+    },
+    /// `show tables [with stats]`: every table and view.
+    ShowTables {
+        /// `with stats`: add each table's row count, bytes on disk and
+        /// index count, as [`crate::Database::table_stats`] reports them.
+        with_stats: bool,
+    },
+    /// `checkpoint`: writes committed WAL groups to the table files and
+    /// truncates the WAL.
+    Checkpoint,
+    /// `show wal`: the WAL's size and how many committed groups in it wait
+    /// for a checkpoint.
+    ShowWal,
 
     Insert {
         table: String,
         values: Vec<String>,
+        /// `returning <cols>`: answer with the written row instead of a
+        /// count. An empty list is `returning *`.
+        returning: Option<Vec<String>>,
+    },
+    /// `insert into <table> [(<col>, ...)] select ...`: inserts every row
+    /// the SELECT returns.
+    InsertSelect {
+        table: String,
+        /// The target columns the SELECT's columns fill, in order; without
+        /// a list, every column of the table.
+        columns: Option<Vec<String>>,
+        /// The parsed SELECT.
+        query: Box<Command>,
     },
 
     Update {
         table: String,
         assignments: Vec<Assignment>,
         filter: WhereClause,
+        /// `returning <cols>`: answer with the updated rows. An empty list
+        /// is `returning *`.
+        returning: Option<Vec<String>>,
     },
 
     Delete {
         table: String,
         filter: WhereClause,
+        /// `returning <cols>`: answer with the rows as they were before the
+        /// delete. An empty list is `returning *`.
+        returning: Option<Vec<String>>,
     },
 
     Describe {
         table: String,
     },
 
+    Comment {
+        target: CommentTarget,
+        text: Option<String>,
+    },
+
     Select {
         table: String,
         distinct: bool,
@@ -172,5 +393,22 @@ pub enum Command {
         order_by: Option<OrderBy>,
         limit: Option<usize>,
         offset: Option<usize>,
+        /// `for update`: inside a transaction, commit fails if another
+        /// handle changes any row matching the WHERE clause.
+        for_update: bool,
+        /// `from <table> as of <snapshot>`: reads the named snapshot
+        /// instead of the live tables.
+        as_of: Option<String>,
+    },
+
+    /// `<select> intersect|except [all] <select>`. Chains group to the
+    /// left, so `left` may itself be a `SetOp`; `right` is a `Select`.
+    SetOp {
+        kind: SetOpKind,
+        left: Box<Command>,
+        right: Box<Command>,
+        /// `all`: duplicates are kept with multiset semantics instead of
+        /// the result being deduplicated.
+        all: bool,
     },
 }
@@ -1,42 +1,183 @@
 use crate::parser::command::Command;
 
 mod alter;
+mod comment;
 mod common;
 mod create;
 mod dml;
+mod identifiers;
 mod select;
 mod tokenizer;
 mod tx;
 mod where_clause;
 
+/// Every word the parser treats as a keyword: statement names, clause words,
+/// operators, constraint actions and type names. Compared case-insensitively.
+pub const KEYWORDS: &[&str] = &[
+    "action",
+    "add",
+    "after",
+    "all",
+    "alter",
+    "and",
+    "as",
+    "asc",
+    "attach",
+    "begin",
+    "bigint",
+    "bool",
+    "by",
+    "cascade",
+    "checkpoint",
+    "column",
+    "comment",
+    "commit",
+    "create",
+    "date",
+    "decimal",
+    "default",
+    "delete",
+    "desc",
+    "describe",
+    "detach",
+    "distinct",
+    "drop",
+    "end",
+    "except",
+    "exists",
+    "for",
+    "foreign",
+    "from",
+    "group",
+    "having",
+    "if",
+    "in",
+    "index",
+    "indexes",
+    "insert",
+    "int",
+    "intersect",
+    "into",
+    "is",
+    "join",
+    "json",
+    "key",
+    "left",
+    "like",
+    "limit",
+    "list",
+    "materialized",
+    "no",
+    "not",
+    "null",
+    "of",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "primary",
+    "references",
+    "refresh",
+    "restrict",
+    "returning",
+    "rollback",
+    "select",
+    "set",
+    "show",
+    "snapshot",
+    "snapshots",
+    "table",
+    "tables",
+    "text",
+    "timestamp",
+    "transaction",
+    "trigger",
+    "trigram",
+    "unique",
+    "update",
+    "using",
+    "uuid",
+    "values",
+    "varchar",
+    "view",
+    "wal",
+    "where",
+];
+
 pub fn parse(input: &str) -> Result<Command, String> {
-    let tokens = tokenizer::tokenize(input)?;
+    let (tokens, quoted) = tokenizer::tokenize(input)?;
     if tokens.is_empty() {
         return Err(
-            "Empty command. Supported commands: begin, commit, rollback, create table, create index, drop index, alter table, insert, update, delete, select, describe"
+            "Empty command. Supported commands: begin, commit, rollback, show transaction, show indexes, set, show, create table, create index, drop index, create snapshot, drop snapshot, create trigger, drop trigger, create view, create materialized view, refresh materialized view, drop view, show tables, show wal, checkpoint, list snapshots, attach, detach, alter table, comment on, insert, update, delete, select, describe"
                 .to_string(),
         );
     }
 
     let keyword = tokens[0].to_lowercase();
+    // `update <t> as of ...`, `insert into <t> as of ...`: snapshots are
+    // read-only, so only SELECT takes AS OF.
+    let after_table = match keyword.as_str() {
+        "update" => 2,
+        "insert" | "delete" => 3,
+        _ => usize::MAX,
+    };
+    if tokens
+        .get(after_table)
+        .is_some_and(|t| t.eq_ignore_ascii_case("as"))
+        && tokens
+            .get(after_table + 1)
+            .is_some_and(|t| t.eq_ignore_ascii_case("of"))
+    {
+        return Err("Snapshots are read-only: AS OF is only allowed in SELECT".to_string());
+    }
 
-    match keyword.as_str() {
+    let mut cmd = match keyword.as_str() {
         "begin" => tx::parse_begin(&tokens),
         "commit" => tx::parse_commit(&tokens),
         "rollback" => tx::parse_rollback(&tokens),
+        "show" => tx::parse_show(&tokens),
+        "set" => tx::parse_set(&tokens),
+        "checkpoint" => tx::parse_checkpoint(&tokens),
+        "list" => tx::parse_list(&tokens),
+        "attach" => tx::parse_attach(&tokens),
+        "detach" => tx::parse_detach(&tokens),
+        "create"
+            if tokens
+                .get(1)
+                .is_some_and(|t| t.eq_ignore_ascii_case("trigger")) =>
+        {
+            create::parse_create_trigger(&tokens, &quoted)
+        }
+        "create"
+            if tokens.get(1).is_some_and(|t| {
+                t.eq_ignore_ascii_case("view") || t.eq_ignore_ascii_case("materialized")
+            }) =>
+        {
+            create::parse_create_view(&tokens, input)
+        }
+        "create" if create::is_create_table_as(&tokens) => {
+            create::parse_create_table_as(&tokens, input)
+        }
         "create" => create::parse_create(&tokens),
         "drop" => create::parse_drop(&tokens),
+        "refresh" => create::parse_refresh(&tokens),
         "alter" => alter::parse_alter(&tokens),
-        "insert" => dml::parse_insert(&tokens),
-        "update" => dml::parse_update(&tokens),
+        "comment" => comment::parse_comment(&tokens),
+        "insert" => match dml::insert_select_start(&tokens) {
+            Some(select_at) => dml::parse_insert_select(&tokens, select_at, input),
+            None => dml::parse_insert(&tokens),
+        },
+        "update" => dml::parse_update(&tokens, &quoted),
         "delete" => dml::parse_delete(&tokens),
         "describe" => parse_describe(&tokens),
-        "select" => select::parse_select(&tokens),
+        "select" => select::parse_select_statement(&tokens, &quoted),
         _ => Err(format!(
-            "Unknown command '{}'. Supported commands: begin, commit, rollback, create table, create index, drop index, alter table, insert, update, delete, select, describe",
+            "Unknown command '{}'. Supported commands: begin, commit, rollback, show transaction, show indexes, set, show, create table, create index, drop index, create snapshot, drop snapshot, create trigger, drop trigger, create view, create materialized view, refresh materialized view, drop view, show tables, show wal, checkpoint, list snapshots, attach, detach, alter table, comment on, insert, update, delete, select, describe",
             tokens[0]
         )),
-    }
+    }?;
+    identifiers::unquote_command(&mut cmd);
+    Ok(cmd)
 }
 
 fn parse_describe(tokens: &[String]) -> Result<Command, String> {
@@ -1,5 +1,6 @@
-use super::common::{parse_column_name_list, parse_foreign_key_action};
-use crate::parser::command::{AlterAction, Command, ForeignKeyAction};
+use super::common::{parse_column_name_list, parse_foreign_key_action, parse_nulls_distinct};
+use super::create::{parse_constraints_in_create, parse_datatype_in_create};
+use crate::parser::command::{AlterAction, ColumnPosition, Command, ForeignKeyAction};
 
 pub(super) fn parse_alter(tokens: &[String]) -> Result<Command, String> {
     if tokens.len() < 5 || !tokens[1].eq_ignore_ascii_case("table") {
@@ -13,7 +14,7 @@ pub(super) fn parse_alter(tokens: &[String]) -> Result<Command, String> {
         "alter" => parse_alter_column(tokens)?,
         _ => {
             return Err(
-                "ALTER TABLE supports: add unique(...), add foreign key(...) references ... , drop unique(...), drop foreign key(...) references ..., alter column <col> set not null, alter column <col> drop not null"
+                "ALTER TABLE supports: add column <col> <type> [first | after <col>], add unique(...), add foreign key(...) references ... , drop unique(...), drop foreign key(...) references ..., alter column <col> set not null, alter column <col> drop not null"
                     .to_string(),
             )
         }
@@ -24,19 +25,26 @@ pub(super) fn parse_alter(tokens: &[String]) -> Result<Command, String> {
 fn parse_alter_add(tokens: &[String]) -> Result<AlterAction, String> {
     if tokens.len() < 6 {
         return Err(
-            "ALTER TABLE ADD supports: unique(<col>, ...) or foreign key(<col>, ...) references <table>(<col>, ...)"
+            "ALTER TABLE ADD supports: column <col> <type>, unique(<col>, ...) or foreign key(<col>, ...) references <table>(<col>, ...)"
                 .to_string(),
         );
     }
+    if tokens[4].eq_ignore_ascii_case("column") {
+        return parse_alter_add_column(tokens);
+    }
     if tokens[4].eq_ignore_ascii_case("unique") {
-        let (cols, next) = parse_column_name_list(tokens, 5, tokens.len())?;
+        let (columns, next) = parse_column_name_list(tokens, 5, tokens.len())?;
+        let (nulls_not_distinct, next) = parse_nulls_distinct(tokens, next, tokens.len())?;
         if next != tokens.len() {
             return Err(
-                "Bad ALTER TABLE ADD UNIQUE syntax. Use: alter table <table> add unique(<col>, ...)"
+                "Bad ALTER TABLE ADD UNIQUE syntax. Use: alter table <table> add unique(<col>, ...) [nulls not distinct]"
                     .to_string(),
             );
         }
-        return Ok(AlterAction::AddUnique(cols));
+        return Ok(AlterAction::AddUnique {
+            columns,
+            nulls_not_distinct,
+        });
     }
     if tokens[4].eq_ignore_ascii_case("foreign") {
         if tokens.len() < 10 || !tokens[5].eq_ignore_ascii_case("key") {
@@ -93,7 +101,46 @@ fn parse_alter_add(tokens: &[String]) -> Result<AlterAction, String> {
             on_update,
         });
     }
-    Err("ALTER TABLE ADD supports UNIQUE(...) or FOREIGN KEY(...) REFERENCES ...".to_string())
+    Err(
+        "ALTER TABLE ADD supports COLUMN <col> <type>, UNIQUE(...) or FOREIGN KEY(...) REFERENCES ..."
+            .to_string(),
+    )
+}
+
+/// `alter table <t> add column <col> <type> [constraints] [first | after <col>]`
+fn parse_alter_add_column(tokens: &[String]) -> Result<AlterAction, String> {
+    let usage = "Bad ALTER TABLE ADD COLUMN syntax. Use: alter table <table> add column <col> <type> [constraints] [first | after <col>]";
+    let mut end = tokens.len();
+    // A quoted DEFAULT literal tokenizes like a bare word, so a trailing
+    // `first` / `after` right after DEFAULT is its value, not a placement.
+    let follows_default = |i: usize| i > 0 && tokens[i - 1].eq_ignore_ascii_case("default");
+    let mut position = ColumnPosition::Last;
+    if end > 7 && tokens[end - 1].eq_ignore_ascii_case("first") && !follows_default(end - 1) {
+        position = ColumnPosition::First;
+        end -= 1;
+    } else if end > 8 && tokens[end - 2].eq_ignore_ascii_case("after") && !follows_default(end - 2)
+    {
+        position = ColumnPosition::After(tokens[end - 1].clone());
+        end -= 2;
+    }
+    if end < 7 {
+        return Err(usage.to_string());
+    }
+    let name = tokens[5].clone();
+    let (dtype, next) = parse_datatype_in_create(tokens, 6, end)?;
+    let mut unique_constraints = Vec::new();
+    let (column, next) =
+        parse_constraints_in_create(name, dtype, tokens, next, end, &mut unique_constraints)?;
+    if next != end {
+        return Err(usage.to_string());
+    }
+    if !unique_constraints.is_empty() {
+        return Err(
+            "ALTER TABLE ADD COLUMN does not support NULLS NOT DISTINCT. Add the column, then use: alter table <table> add unique(<col>) nulls not distinct"
+                .to_string(),
+        );
+    }
+    Ok(AlterAction::AddColumn { column, position })
 }
 
 fn parse_alter_drop(tokens: &[String]) -> Result<AlterAction, String> {
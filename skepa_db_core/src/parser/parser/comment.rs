@@ -0,0 +1,45 @@
+use crate::parser::command::{Command, CommentTarget};
+
+pub(super) fn parse_comment(tokens: &[String]) -> Result<Command, String> {
+    // comment on table <table> is <text|null>
+    // comment on column <table>.<column> is <text|null>
+    let usage =
+        "Usage: comment on table <table> is <text> | comment on column <table>.<column> is <text>";
+    if tokens.len() != 6
+        || !tokens[1].eq_ignore_ascii_case("on")
+        || !tokens[4].eq_ignore_ascii_case("is")
+    {
+        return Err(usage.to_string());
+    }
+
+    let target = match tokens[2].to_lowercase().as_str() {
+        "table" => CommentTarget::Table(tokens[3].clone()),
+        "column" => {
+            let Some((table, column)) = tokens[3].split_once('.') else {
+                return Err(format!(
+                    "Bad COMMENT target '{}'. Use <table>.<column>",
+                    tokens[3]
+                ));
+            };
+            if table.is_empty() || column.is_empty() {
+                return Err(format!(
+                    "Bad COMMENT target '{}'. Use <table>.<column>",
+                    tokens[3]
+                ));
+            }
+            CommentTarget::Column {
+                table: table.to_string(),
+                column: column.to_string(),
+            }
+        }
+        _ => return Err(usage.to_string()),
+    };
+
+    let text = if tokens[5].eq_ignore_ascii_case("null") {
+        None
+    } else {
+        Some(tokens[5].clone())
+    };
+
+    Ok(Command::Comment { target, text })
+}
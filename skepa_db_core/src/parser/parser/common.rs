@@ -73,3 +73,49 @@ pub(super) fn parse_column_name_list(
     }
     Err("Unclosed constraint column list".to_string())
 }
+
+/// Reads an optional `nulls [not] distinct` modifier after a UNIQUE
+/// constraint, returning whether NULLs compare equal and the next index.
+pub(super) fn parse_nulls_distinct(
+    tokens: &[String],
+    start: usize,
+    end: usize,
+) -> Result<(bool, usize), String> {
+    if start >= end || !tokens[start].eq_ignore_ascii_case("nulls") {
+        return Ok((false, start));
+    }
+    if start + 1 < end && tokens[start + 1].eq_ignore_ascii_case("distinct") {
+        return Ok((false, start + 2));
+    }
+    if start + 2 < end
+        && tokens[start + 1].eq_ignore_ascii_case("not")
+        && tokens[start + 2].eq_ignore_ascii_case("distinct")
+    {
+        return Ok((true, start + 3));
+    }
+    Err("Bad UNIQUE modifier. Use 'nulls distinct' or 'nulls not distinct'".to_string())
+}
+
+/// The text of a statement's embedded SELECT, from its `select` keyword to
+/// the end of `input` without a trailing `;`.
+pub(super) fn embedded_select(input: &str) -> Option<&str> {
+    let lower = input.to_ascii_lowercase();
+    let start = lower.match_indices("select").map(|(i, _)| i).find(|&i| {
+        (lower[..i].ends_with(char::is_whitespace) || lower[..i].ends_with(')'))
+            && lower[i + 6..].starts_with(char::is_whitespace)
+    })?;
+    let sql = input[start..].trim();
+    Some(sql.strip_suffix(';').unwrap_or(sql).trim_end())
+}
+
+/// Reads one literal value token, folding the `gen_uuid ( )` call into a single `gen_uuid()` token.
+pub(super) fn take_value_token(tokens: &[String], start: usize, end: usize) -> (String, usize) {
+    if start + 2 < end
+        && tokens[start].eq_ignore_ascii_case("gen_uuid")
+        && tokens[start + 1] == "("
+        && tokens[start + 2] == ")"
+    {
+        return (crate::types::uuid_gen::GEN_UUID_CALL.to_string(), start + 3);
+    }
+    (tokens[start].clone(), start + 1)
+}
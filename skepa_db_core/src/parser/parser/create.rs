@@ -1,11 +1,27 @@
-use super::common::{parse_column_name_list, parse_foreign_key_action};
-use crate::parser::command::{ColumnDef, Command, ForeignKeyAction, TableConstraintDef};
+use super::common::{
+    embedded_select, parse_column_name_list, parse_foreign_key_action, parse_nulls_distinct,
+    take_value_token,
+};
+use crate::parser::command::{
+    ColumnDef, Command, ForeignKeyAction, IndexKind, JsonShape, TableConstraintDef, TriggerValue,
+};
 use crate::types::datatype::{DataType, parse_datatype};
 
 pub(super) fn parse_create(tokens: &[String]) -> Result<Command, String> {
+    let (if_not_exists, tokens) = strip_if_not_exists(tokens);
+    let tokens = tokens.as_slice();
     if tokens.len() >= 2 && tokens[1].eq_ignore_ascii_case("index") {
-        return parse_create_index(tokens);
+        return parse_create_index(tokens, if_not_exists);
     }
+    if tokens.len() >= 2 && tokens[1].eq_ignore_ascii_case("snapshot") {
+        if if_not_exists {
+            return Err("Usage: create snapshot <name>".to_string());
+        }
+        return Ok(Command::CreateSnapshot {
+            name: parse_snapshot_name(tokens, "create")?,
+        });
+    }
+    let (partition_by, tokens) = strip_partition_by(tokens)?;
     // create table <table> ( <col> <type> [, <col> <type> ...] )
     if tokens.len() < 7 {
         return Err(
@@ -33,10 +49,7 @@ pub(super) fn parse_create(tokens: &[String]) -> Result<Command, String> {
         if i >= end {
             return Err("Bad CREATE column list. Use: (id int, name text)".to_string());
         }
-        if tokens[i].eq_ignore_ascii_case("primary")
-            || tokens[i].eq_ignore_ascii_case("unique")
-            || tokens[i].eq_ignore_ascii_case("foreign")
-        {
+        if starts_table_constraint(tokens, i) {
             let (constraint, next_i) = parse_table_constraint_in_create(tokens, i, end)?;
             table_constraints.push(constraint);
             i = next_i;
@@ -44,17 +57,16 @@ pub(super) fn parse_create(tokens: &[String]) -> Result<Command, String> {
             let name = tokens[i].clone();
             i += 1;
             let (dtype, next_i) = parse_datatype_in_create(tokens, i, end)?;
-            let (primary_key, unique, not_null, default, after_constraints) =
-                parse_constraints_in_create(tokens, next_i, end)?;
-            i = after_constraints;
-            cols.push(ColumnDef {
+            let (col, after_constraints) = parse_constraints_in_create(
                 name,
                 dtype,
-                primary_key,
-                unique,
-                not_null,
-                default,
-            });
+                tokens,
+                next_i,
+                end,
+                &mut table_constraints,
+            )?;
+            i = after_constraints;
+            cols.push(col);
         }
         if i < end {
             if tokens[i] != "," {
@@ -75,48 +87,329 @@ pub(super) fn parse_create(tokens: &[String]) -> Result<Command, String> {
         table,
         columns: cols,
         table_constraints,
+        if_not_exists,
+        partition_by,
     })
 }
 
+/// Removes `if not exists` after `create table` / `create index` so the rest
+/// of the parser sees the plain statement.
+fn strip_if_not_exists(tokens: &[String]) -> (bool, Vec<String>) {
+    let found = tokens.len() >= 5
+        && tokens[2].eq_ignore_ascii_case("if")
+        && tokens[3].eq_ignore_ascii_case("not")
+        && tokens[4].eq_ignore_ascii_case("exists");
+    if !found {
+        return (false, tokens.to_vec());
+    }
+    let mut rest = tokens[..2].to_vec();
+    rest.extend_from_slice(&tokens[5..]);
+    (true, rest)
+}
+
+/// Removes a trailing `partition by (<col>)` from `create table`.
+fn strip_partition_by(tokens: &[String]) -> Result<(Option<String>, &[String]), String> {
+    let n = tokens.len();
+    let Some(at) = tokens
+        .iter()
+        .rposition(|t| t.eq_ignore_ascii_case("partition"))
+    else {
+        return Ok((None, tokens));
+    };
+    // The clause only counts after the closing paren of the column list.
+    let opens_clause = at > 0
+        && tokens[at - 1] == ")"
+        && tokens
+            .get(at + 1)
+            .is_some_and(|t| t.eq_ignore_ascii_case("by"));
+    if !opens_clause {
+        return Ok((None, tokens));
+    }
+    let well_formed = n == at + 5 && tokens[at + 2] == "(" && tokens[at + 4] == ")";
+    if !well_formed {
+        return Err("PARTITION BY expects exactly one column: partition by (<col>)".to_string());
+    }
+    Ok((Some(tokens[at + 3].clone()), &tokens[..at]))
+}
+
+/// `primary key (...)`, `unique (...)` and `foreign key (...)` open a table
+/// constraint; any other use of those words in column-name position is a
+/// column called `primary`, `unique` or `foreign`.
+fn starts_table_constraint(tokens: &[String], i: usize) -> bool {
+    let next = tokens.get(i + 1).map(String::as_str).unwrap_or("");
+    if tokens[i].eq_ignore_ascii_case("primary") || tokens[i].eq_ignore_ascii_case("foreign") {
+        return next.eq_ignore_ascii_case("key") || next == "(";
+    }
+    tokens[i].eq_ignore_ascii_case("unique") && next == "("
+}
+
 pub(super) fn parse_drop(tokens: &[String]) -> Result<Command, String> {
     if tokens.len() >= 2 && tokens[1].eq_ignore_ascii_case("index") {
         return parse_drop_index(tokens);
     }
-    Err("DROP currently supports only: drop index on <table> (<col>, ...)".to_string())
+    let view_at = if tokens.len() >= 2 && tokens[1].eq_ignore_ascii_case("materialized") {
+        2
+    } else {
+        1
+    };
+    if tokens
+        .get(view_at)
+        .is_some_and(|t| t.eq_ignore_ascii_case("view"))
+    {
+        if tokens.len() != view_at + 2 {
+            return Err("Usage: drop [materialized] view <name>".to_string());
+        }
+        return Ok(Command::DropView {
+            name: tokens[view_at + 1].clone(),
+        });
+    }
+    if tokens.len() >= 2 && tokens[1].eq_ignore_ascii_case("trigger") {
+        if tokens.len() != 3 {
+            return Err("Usage: drop trigger <name>".to_string());
+        }
+        return Ok(Command::DropTrigger {
+            name: tokens[2].clone(),
+        });
+    }
+    if tokens.len() >= 2 && tokens[1].eq_ignore_ascii_case("snapshot") {
+        return Ok(Command::DropSnapshot {
+            name: parse_snapshot_name(tokens, "drop")?,
+        });
+    }
+    Err(
+        "DROP currently supports only: drop index <name> | drop index on <table> (<col>, ...) | drop snapshot <name> | drop trigger <name> | drop [materialized] view <name>"
+            .to_string(),
+    )
 }
 
-fn parse_create_index(tokens: &[String]) -> Result<Command, String> {
-    // create index on <table> (col[,col...])
-    if tokens.len() < 7 || !tokens[2].eq_ignore_ascii_case("on") {
-        return Err("Usage: create index on <table> (<col>, ...)".to_string());
+/// `create trigger <name> after insert on <table> begin insert into
+/// <target> values (<v>, ...) end`. Unquoted `new.<col>` values refer to
+/// the inserted row.
+pub(super) fn parse_create_trigger(tokens: &[String], quoted: &[bool]) -> Result<Command, String> {
+    const USAGE: &str = "Usage: create trigger <name> after insert on <table> begin insert into <table> values (<v>, ...) end";
+    if tokens.len() >= 5
+        && (tokens[3].eq_ignore_ascii_case("before")
+            || tokens[3].eq_ignore_ascii_case("after") && !tokens[4].eq_ignore_ascii_case("insert"))
+    {
+        return Err("Only AFTER INSERT triggers are supported".to_string());
     }
-    let table = tokens[3].clone();
-    let (cols, next) = parse_column_name_list(tokens, 4, tokens.len())?;
-    if next != tokens.len() {
-        return Err("Usage: create index on <table> (<col>, ...)".to_string());
+    if tokens.len() < 9
+        || !tokens[3].eq_ignore_ascii_case("after")
+        || !tokens[4].eq_ignore_ascii_case("insert")
+        || !tokens[5].eq_ignore_ascii_case("on")
+        || !tokens[7].eq_ignore_ascii_case("begin")
+        || !tokens[tokens.len() - 1].eq_ignore_ascii_case("end")
+    {
+        return Err(USAGE.to_string());
+    }
+    let body = &tokens[8..tokens.len() - 1];
+    let body_quoted = &quoted[8..tokens.len() - 1];
+    if !body
+        .first()
+        .is_some_and(|t| t.eq_ignore_ascii_case("insert"))
+    {
+        return Err("A trigger body must be a single INSERT statement".to_string());
+    }
+    let Command::Insert {
+        table: target,
+        returning,
+        ..
+    } = super::dml::parse_insert(body)?
+    else {
+        unreachable!("parse_insert returns Command::Insert");
+    };
+    if returning.is_some() {
+        return Err("RETURNING is not allowed in a trigger body".to_string());
+    }
+
+    let mut values = Vec::new();
+    let end = body.len() - 1;
+    let mut i = 5usize;
+    while i < end {
+        let (value, next) = take_value_token(body, i, end);
+        let reference = (next == i + 1 && !body_quoted[i])
+            .then(|| value.split_once('.'))
+            .flatten();
+        values.push(match reference {
+            Some((row, col)) if row.eq_ignore_ascii_case("new") => {
+                TriggerValue::New(col.to_string())
+            }
+            Some((row, _)) if row.eq_ignore_ascii_case("old") => {
+                return Err("OLD is not available in an AFTER INSERT trigger".to_string());
+            }
+            _ => TriggerValue::Literal(value),
+        });
+        // parse_insert has checked the separators.
+        i = next + 1;
+    }
+    Ok(Command::CreateTrigger {
+        name: tokens[2].clone(),
+        table: tokens[6].clone(),
+        target,
+        values,
+    })
+}
+
+/// Whether `tokens` are `create table <table> as ...`.
+pub(super) fn is_create_table_as(tokens: &[String]) -> bool {
+    tokens
+        .get(1)
+        .is_some_and(|t| t.eq_ignore_ascii_case("table"))
+        && tokens.get(3).is_some_and(|t| t.eq_ignore_ascii_case("as"))
+}
+
+/// `create table <table> as select ...`. The SELECT is cut from `input` at
+/// its `select` keyword and parsed on its own.
+pub(super) fn parse_create_table_as(tokens: &[String], input: &str) -> Result<Command, String> {
+    let usage = || "Usage: create table <table> as select ...".to_string();
+    if !tokens
+        .get(4)
+        .is_some_and(|t| t.eq_ignore_ascii_case("select"))
+    {
+        return Err(usage());
+    }
+    let sql = embedded_select(input).ok_or_else(usage)?;
+    let query = super::parse(sql)?;
+    match &query {
+        Command::Select {
+            for_update: true, ..
+        } => return Err("CREATE TABLE ... AS cannot select FOR UPDATE".to_string()),
+        Command::Select { .. } | Command::SetOp { .. } => {}
+        _ => return Err(usage()),
+    }
+    Ok(Command::CreateTableAs {
+        table: tokens[2].clone(),
+        query: Box::new(query),
+    })
+}
+
+/// `create [materialized] view <name> as select ...`. The SELECT is parsed
+/// on its own and also kept as the text `input` has from its `select`
+/// keyword on.
+pub(super) fn parse_create_view(tokens: &[String], input: &str) -> Result<Command, String> {
+    let usage = || "Usage: create [materialized] view <name> as select ...".to_string();
+    let materialized = tokens[1].eq_ignore_ascii_case("materialized");
+    let tokens = if materialized {
+        if !tokens
+            .get(2)
+            .is_some_and(|t| t.eq_ignore_ascii_case("view"))
+        {
+            return Err(usage());
+        }
+        &tokens[1..]
+    } else {
+        tokens
+    };
+    if tokens.len() < 5
+        || !tokens[3].eq_ignore_ascii_case("as")
+        || !tokens[4].eq_ignore_ascii_case("select")
+    {
+        return Err(usage());
     }
+    let lower = input.to_ascii_lowercase();
+    let start = lower
+        .match_indices("select")
+        .map(|(i, _)| i)
+        .find(|&i| lower[..i].ends_with(char::is_whitespace))
+        .ok_or_else(usage)?;
+    let sql = input[start..].trim();
+    let sql = sql.strip_suffix(';').unwrap_or(sql).trim_end().to_string();
+    let query = super::parse(&sql)?;
+    Ok(Command::CreateView {
+        name: tokens[2].clone(),
+        materialized,
+        query: Box::new(query),
+        sql,
+    })
+}
+
+/// `refresh materialized view <name>`
+pub(super) fn parse_refresh(tokens: &[String]) -> Result<Command, String> {
+    if tokens.len() != 4
+        || !tokens[1].eq_ignore_ascii_case("materialized")
+        || !tokens[2].eq_ignore_ascii_case("view")
+    {
+        return Err("Usage: refresh materialized view <name>".to_string());
+    }
+    Ok(Command::RefreshMaterializedView {
+        name: tokens[3].clone(),
+    })
+}
+
+/// `<verb> snapshot <name>`
+fn parse_snapshot_name(tokens: &[String], verb: &str) -> Result<String, String> {
+    if tokens.len() != 3 {
+        return Err(format!("Usage: {verb} snapshot <name>"));
+    }
+    Ok(tokens[2].clone())
+}
+
+fn parse_create_index(tokens: &[String], if_not_exists: bool) -> Result<Command, String> {
+    // create index [if not exists] [<name>] on <table> (col[,col...]) [using trigram]
+    const USAGE: &str = "Usage: create index on <table> (<col>, ...) [using trigram] | create index <name> on <table> (<col>, ...) [using trigram]";
+    let (name, on) = match tokens.get(2) {
+        Some(t) if !t.eq_ignore_ascii_case("on") => (Some(t.clone()), 3),
+        _ => (None, 2),
+    };
+    if tokens.len() < on + 5 || !tokens[on].eq_ignore_ascii_case("on") {
+        return Err(USAGE.to_string());
+    }
+    let table = tokens[on + 1].clone();
+    let (cols, next) = parse_column_name_list(tokens, on + 2, tokens.len())?;
+    let kind = parse_index_using(tokens, next, USAGE)?;
     Ok(Command::CreateIndex {
         table,
         columns: cols,
+        if_not_exists,
+        kind,
+        name,
     })
 }
 
 fn parse_drop_index(tokens: &[String]) -> Result<Command, String> {
-    // drop index on <table> (col[,col...])
+    // drop index <name>
+    // drop index on <table> (col[,col...]) [using trigram]
+    const USAGE: &str =
+        "Usage: drop index <name> | drop index on <table> (<col>, ...) [using trigram]";
+    if tokens.len() == 3 && !tokens[2].eq_ignore_ascii_case("on") {
+        return Ok(Command::DropIndex {
+            table: String::new(),
+            columns: Vec::new(),
+            kind: IndexKind::Standard,
+            name: Some(tokens[2].clone()),
+        });
+    }
     if tokens.len() < 7 || !tokens[2].eq_ignore_ascii_case("on") {
-        return Err("Usage: drop index on <table> (<col>, ...)".to_string());
+        return Err(USAGE.to_string());
     }
     let table = tokens[3].clone();
     let (cols, next) = parse_column_name_list(tokens, 4, tokens.len())?;
-    if next != tokens.len() {
-        return Err("Usage: drop index on <table> (<col>, ...)".to_string());
-    }
+    let kind = parse_index_using(tokens, next, USAGE)?;
     Ok(Command::DropIndex {
         table,
         columns: cols,
+        kind,
+        name: None,
     })
 }
 
+/// Parses the optional `using <method>` tail that starts at `start`.
+fn parse_index_using(tokens: &[String], start: usize, usage: &str) -> Result<IndexKind, String> {
+    match &tokens[start..] {
+        [] => Ok(IndexKind::Standard),
+        [using, method] if using.eq_ignore_ascii_case("using") => {
+            if method.eq_ignore_ascii_case("trigram") {
+                Ok(IndexKind::Trigram)
+            } else {
+                Err(format!(
+                    "Unknown index method '{method}'; supported: trigram"
+                ))
+            }
+        }
+        _ => Err(usage.to_string()),
+    }
+}
+
 pub(super) fn parse_datatype_in_create(
     tokens: &[String],
     start: usize,
@@ -152,15 +445,22 @@ pub(super) fn parse_datatype_in_create(
     }
 }
 
-fn parse_constraints_in_create(
+/// Parses the constraints after a column's type. A column-level `unique
+/// nulls not distinct` also lands in `table_constraints` as the equivalent
+/// one-column table constraint.
+pub(super) fn parse_constraints_in_create(
+    name: String,
+    dtype: DataType,
     tokens: &[String],
     mut i: usize,
     end: usize,
-) -> Result<(bool, bool, bool, Option<String>, usize), String> {
+    table_constraints: &mut Vec<TableConstraintDef>,
+) -> Result<(ColumnDef, usize), String> {
     let mut primary_key = false;
     let mut unique = false;
     let mut not_null = false;
     let mut default: Option<String> = None;
+    let mut json_shape: Option<JsonShape> = None;
 
     while i < end && tokens[i] != "," {
         let t = tokens[i].to_lowercase();
@@ -174,7 +474,14 @@ fn parse_constraints_in_create(
             }
             "unique" => {
                 unique = true;
-                i += 1;
+                let (nulls_not_distinct, next) = parse_nulls_distinct(tokens, i + 1, end)?;
+                if nulls_not_distinct {
+                    table_constraints.push(TableConstraintDef::Unique {
+                        columns: vec![name.clone()],
+                        nulls_not_distinct,
+                    });
+                }
+                i = next;
             }
             "not" => {
                 if i + 1 >= end || !tokens[i + 1].eq_ignore_ascii_case("null") {
@@ -190,8 +497,20 @@ fn parse_constraints_in_create(
                 if i + 1 >= end || tokens[i + 1] == "," {
                     return Err("Bad DEFAULT constraint. Use default <literal>".to_string());
                 }
-                default = Some(tokens[i + 1].clone());
-                i += 2;
+                let (value, next_i) = take_value_token(tokens, i + 1, end);
+                default = Some(value);
+                i = next_i;
+            }
+            "check_json_object" | "check_json_array" => {
+                if json_shape.is_some() {
+                    return Err("JSON shape check specified more than once for column".to_string());
+                }
+                json_shape = Some(if t == "check_json_object" {
+                    JsonShape::Object
+                } else {
+                    JsonShape::Array
+                });
+                i += 1;
             }
             other => return Err(format!("Unknown column constraint token '{other}'")),
         }
@@ -202,7 +521,18 @@ fn parse_constraints_in_create(
         not_null = true;
     }
 
-    Ok((primary_key, unique, not_null, default, i))
+    Ok((
+        ColumnDef {
+            name,
+            dtype,
+            primary_key,
+            unique,
+            not_null,
+            default,
+            json_shape,
+        },
+        i,
+    ))
 }
 
 fn parse_table_constraint_in_create(
@@ -218,8 +548,15 @@ fn parse_table_constraint_in_create(
         return Ok((TableConstraintDef::PrimaryKey(cols), next));
     }
     if tokens[start].eq_ignore_ascii_case("unique") {
-        let (cols, next) = parse_column_name_list(tokens, start + 1, end)?;
-        return Ok((TableConstraintDef::Unique(cols), next));
+        let (columns, next) = parse_column_name_list(tokens, start + 1, end)?;
+        let (nulls_not_distinct, next) = parse_nulls_distinct(tokens, next, end)?;
+        return Ok((
+            TableConstraintDef::Unique {
+                columns,
+                nulls_not_distinct,
+            },
+            next,
+        ));
     }
     if tokens[start].eq_ignore_ascii_case("foreign") {
         if start + 1 >= end || !tokens[start + 1].eq_ignore_ascii_case("key") {
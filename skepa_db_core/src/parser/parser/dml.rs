@@ -1,8 +1,42 @@
+use super::common::{embedded_select, parse_column_name_list, take_value_token};
+use super::select::{bind_subqueries, parse_select_columns};
 use super::where_clause::parse_where_clause;
 use crate::parser::command::{Assignment, Command};
 
+/// Tokens that put a value or a column name right after them. A `returning`
+/// token following one of these is a quoted literal or a column, not the
+/// start of a RETURNING clause.
+const OPERAND_LEADS: [&str; 20] = [
+    "=", "!=", "<>", ">", "<", ">=", "<=", "eq", "neq", "gt", "lt", "gte", "lte", "like", "(", ",",
+    "where", "and", "or", "set",
+];
+
+/// Splits a trailing `returning * | returning <col> [as <alias>], ...` off a
+/// DML statement, searching from `body_start` on.
+fn split_returning(
+    tokens: &[String],
+    body_start: usize,
+) -> Result<(&[String], Option<Vec<String>>), String> {
+    let pos = (body_start.max(1)..tokens.len()).find(|&i| {
+        tokens[i].eq_ignore_ascii_case("returning")
+            && !OPERAND_LEADS
+                .iter()
+                .any(|lead| tokens[i - 1].eq_ignore_ascii_case(lead))
+    });
+    let Some(pos) = pos else {
+        return Ok((tokens, None));
+    };
+    let list = &tokens[pos + 1..];
+    if list.is_empty() {
+        return Err("RETURNING requires '*' or a comma-separated column list".to_string());
+    }
+    let columns = parse_select_columns(list)?;
+    Ok((&tokens[..pos], Some(columns)))
+}
+
 pub(super) fn parse_insert(tokens: &[String]) -> Result<Command, String> {
-    // insert into <table> values (<v1>, <v2>, ...)
+    // insert into <table> values (<v1>, <v2>, ...) [returning <cols>]
+    let (tokens, returning) = split_returning(tokens, 5)?;
     if tokens.len() < 7 {
         return Err("Usage: insert into <table> values (<v1>, <v2>, ...)".to_string());
     }
@@ -19,8 +53,9 @@ pub(super) fn parse_insert(tokens: &[String]) -> Result<Command, String> {
     let end = tokens.len() - 1;
 
     while i < end {
-        values.push(tokens[i].clone());
-        i += 1;
+        let (value, next_i) = take_value_token(tokens, i, end);
+        values.push(value);
+        i = next_i;
         if i < end {
             if tokens[i] != "," {
                 return Err("Bad INSERT values. Values must be comma-separated.".to_string());
@@ -36,11 +71,60 @@ pub(super) fn parse_insert(tokens: &[String]) -> Result<Command, String> {
         return Err("INSERT requires at least one value".to_string());
     }
 
-    Ok(Command::Insert { table, values })
+    Ok(Command::Insert {
+        table,
+        values,
+        returning,
+    })
+}
+
+/// Where the SELECT of `insert into <table> [(<col>, ...)] select ...`
+/// starts, or `None` for an INSERT ... VALUES.
+pub(super) fn insert_select_start(tokens: &[String]) -> Option<usize> {
+    let start = match tokens.get(3).map(String::as_str) {
+        Some("(") => tokens.iter().position(|t| t == ")")? + 1,
+        _ => 3,
+    };
+    tokens
+        .get(start)
+        .is_some_and(|t| t.eq_ignore_ascii_case("select"))
+        .then_some(start)
+}
+
+/// `insert into <table> [(<col>, ...)] select ...`. The SELECT is cut
+/// from `input` at its `select` keyword and parsed on its own.
+pub(super) fn parse_insert_select(
+    tokens: &[String],
+    select_at: usize,
+    input: &str,
+) -> Result<Command, String> {
+    let usage = || "Usage: insert into <table> [(<col>, ...)] select ...".to_string();
+    if !tokens[1].eq_ignore_ascii_case("into") {
+        return Err(usage());
+    }
+    let columns = match select_at {
+        3 => None,
+        _ => Some(parse_column_name_list(tokens, 3, select_at)?.0),
+    };
+    let sql = embedded_select(input).ok_or_else(usage)?;
+    let query = super::parse(sql)?;
+    match &query {
+        Command::Select {
+            for_update: true, ..
+        } => return Err("INSERT ... SELECT cannot select FOR UPDATE".to_string()),
+        Command::Select { .. } | Command::SetOp { .. } => {}
+        _ => return Err(usage()),
+    }
+    Ok(Command::InsertSelect {
+        table: tokens[2].clone(),
+        columns,
+        query: Box::new(query),
+    })
 }
 
-pub(super) fn parse_update(tokens: &[String]) -> Result<Command, String> {
-    // update <table> set <col> = <val> [, <col> = <val> ...] where <col> <op> <val>
+pub(super) fn parse_update(tokens: &[String], quoted: &[bool]) -> Result<Command, String> {
+    // update <table> set <col> = <val> [, <col> = <val> ...] where <col> <op> <val> [returning <cols>]
+    let (tokens, returning) = split_returning(tokens, 3)?;
     if tokens.len() < 10 {
         return Err(
             "Usage: update <table> set <col> = <value> [, <col> = <value> ...] where <column> <op> <value>"
@@ -81,6 +165,7 @@ pub(super) fn parse_update(tokens: &[String]) -> Result<Command, String> {
         assignments.push(Assignment {
             column: set_tokens[i].clone(),
             value: set_tokens[i + 2].clone(),
+            quoted: quoted.get(3 + i + 2).copied().unwrap_or(false),
         });
         i += 3;
         if i < set_tokens.len() {
@@ -92,20 +177,23 @@ pub(super) fn parse_update(tokens: &[String]) -> Result<Command, String> {
     }
 
     let where_tokens = &tokens[where_idx + 1..];
-    let filter = parse_where_clause(
+    let mut filter = parse_where_clause(
         where_tokens,
         "Bad UPDATE WHERE clause. Use: where <expr>, e.g. col = 1, col is null, col in (1,2), with and/or and parentheses",
     )?;
+    bind_subqueries(&mut filter, &table)?;
 
     Ok(Command::Update {
         table,
         assignments,
         filter,
+        returning,
     })
 }
 
 pub(super) fn parse_delete(tokens: &[String]) -> Result<Command, String> {
-    // delete from <table> where <column> <op> <value>
+    // delete from <table> where <column> <op> <value> [returning <cols>]
+    let (tokens, returning) = split_returning(tokens, 4)?;
     if tokens.len() < 6
         || !tokens[1].eq_ignore_ascii_case("from")
         || !tokens[3].eq_ignore_ascii_case("where")
@@ -113,9 +201,11 @@ pub(super) fn parse_delete(tokens: &[String]) -> Result<Command, String> {
         return Err("Usage: delete from <table> where <expr>".to_string());
     }
 
-    let filter = parse_where_clause(&tokens[4..], "Usage: delete from <table> where <expr>")?;
+    let mut filter = parse_where_clause(&tokens[4..], "Usage: delete from <table> where <expr>")?;
+    bind_subqueries(&mut filter, &tokens[2])?;
     Ok(Command::Delete {
         table: tokens[2].clone(),
         filter,
+        returning,
     })
 }
@@ -0,0 +1,277 @@
+use crate::parser::command::{
+    AlterAction, ColumnDef, ColumnPosition, Command, CommentTarget, TableConstraintDef,
+    TriggerValue, WhereClause,
+};
+
+/// Marks a token that came from a `` `name` `` or `[name]` quoted
+/// identifier. Keyword checks compare whole tokens, so a marked token never
+/// matches `select`, `from` and friends; [`unquote_command`] strips the
+/// marker once parsing is done. The tokenizer rejects input containing it.
+pub(super) const QUOTED_IDENT: char = '\u{E000}';
+
+fn unquote(s: &mut String) {
+    if s.contains(QUOTED_IDENT) {
+        s.retain(|c| c != QUOTED_IDENT);
+    }
+}
+
+fn unquote_all(items: &mut [String]) {
+    items.iter_mut().for_each(unquote);
+}
+
+fn unquote_where(clause: &mut WhereClause) {
+    match clause {
+        WhereClause::Predicate(p) => {
+            unquote(&mut p.column);
+            unquote(&mut p.value);
+            unquote_all(&mut p.values);
+        }
+        WhereClause::Binary { left, right, .. } => {
+            unquote_where(left);
+            unquote_where(right);
+        }
+        WhereClause::Exists { subquery, .. } => {
+            unquote(&mut subquery.table);
+            if let Some(filter) = &mut subquery.filter {
+                unquote_where(filter);
+            }
+            for (inner, outer) in &mut subquery.correlation {
+                unquote(inner);
+                unquote(outer);
+            }
+        }
+    }
+}
+
+fn unquote_column_def(col: &mut ColumnDef) {
+    unquote(&mut col.name);
+    if let Some(default) = &mut col.default {
+        unquote(default);
+    }
+}
+
+fn unquote_constraint(constraint: &mut TableConstraintDef) {
+    match constraint {
+        TableConstraintDef::PrimaryKey(cols) | TableConstraintDef::Unique { columns: cols, .. } => {
+            unquote_all(cols)
+        }
+        TableConstraintDef::ForeignKey {
+            columns,
+            ref_table,
+            ref_columns,
+            ..
+        } => {
+            unquote_all(columns);
+            unquote(ref_table);
+            unquote_all(ref_columns);
+        }
+    }
+}
+
+fn unquote_alter(action: &mut AlterAction) {
+    match action {
+        AlterAction::AddUnique { columns: cols, .. } | AlterAction::DropUnique(cols) => {
+            unquote_all(cols)
+        }
+        AlterAction::AddForeignKey {
+            columns,
+            ref_table,
+            ref_columns,
+            ..
+        }
+        | AlterAction::DropForeignKey {
+            columns,
+            ref_table,
+            ref_columns,
+        } => {
+            unquote_all(columns);
+            unquote(ref_table);
+            unquote_all(ref_columns);
+        }
+        AlterAction::SetNotNull(col) | AlterAction::DropNotNull(col) => unquote(col),
+        AlterAction::AddColumn { column, position } => {
+            unquote_column_def(column);
+            if let ColumnPosition::After(col) = position {
+                unquote(col);
+            }
+        }
+    }
+}
+
+/// Strips [`QUOTED_IDENT`] markers from every name and literal in `cmd`, so
+/// quoted identifiers are stored and resolved exactly like bare ones.
+pub(super) fn unquote_command(cmd: &mut Command) {
+    match cmd {
+        Command::Begin | Command::Commit | Command::Rollback | Command::ShowTransaction => {}
+        Command::Set { name, value } => {
+            unquote(name);
+            unquote(value);
+        }
+        Command::Show { name } => unquote(name),
+        Command::Create {
+            table,
+            columns,
+            table_constraints,
+            partition_by,
+            ..
+        } => {
+            unquote(table);
+            if let Some(col) = partition_by {
+                unquote(col);
+            }
+            columns.iter_mut().for_each(unquote_column_def);
+            table_constraints.iter_mut().for_each(unquote_constraint);
+        }
+        Command::CreateTableAs { table, .. } => unquote(table),
+        Command::CreateIndex {
+            table,
+            columns,
+            name,
+            ..
+        }
+        | Command::DropIndex {
+            table,
+            columns,
+            name,
+            ..
+        } => {
+            unquote(table);
+            unquote_all(columns);
+            if let Some(name) = name {
+                unquote(name);
+            }
+        }
+        Command::Alter { table, action } => {
+            unquote(table);
+            unquote_alter(action);
+        }
+        Command::Insert {
+            table,
+            values,
+            returning,
+        } => {
+            unquote(table);
+            unquote_all(values);
+            if let Some(returning) = returning {
+                unquote_all(returning);
+            }
+        }
+        Command::InsertSelect { table, columns, .. } => {
+            unquote(table);
+            if let Some(columns) = columns {
+                unquote_all(columns);
+            }
+        }
+        Command::Update {
+            table,
+            assignments,
+            filter,
+            returning,
+        } => {
+            unquote(table);
+            for a in assignments {
+                unquote(&mut a.column);
+                unquote(&mut a.value);
+            }
+            unquote_where(filter);
+            if let Some(returning) = returning {
+                unquote_all(returning);
+            }
+        }
+        Command::Delete {
+            table,
+            filter,
+            returning,
+        } => {
+            unquote(table);
+            unquote_where(filter);
+            if let Some(returning) = returning {
+                unquote_all(returning);
+            }
+        }
+        Command::Describe { table } | Command::ShowIndexes { table } => unquote(table),
+        Command::CreateSnapshot { name } | Command::DropSnapshot { name } => unquote(name),
+        Command::ListSnapshots => {}
+        Command::Attach { path, alias } => {
+            unquote(path);
+            unquote(alias);
+        }
+        Command::Detach { alias } => unquote(alias),
+        Command::CreateTrigger {
+            name,
+            table,
+            target,
+            values,
+        } => {
+            unquote(name);
+            unquote(table);
+            unquote(target);
+            for value in values {
+                match value {
+                    TriggerValue::Literal(s) | TriggerValue::New(s) => unquote(s),
+                }
+            }
+        }
+        Command::DropTrigger { name } => unquote(name),
+        // The SELECT was unquoted when it was parsed on its own.
+        Command::CreateView { name, .. }
+        | Command::DropView { name }
+        | Command::RefreshMaterializedView { name } => unquote(name),
+        Command::ShowTables { .. } | Command::Checkpoint | Command::ShowWal => {}
+        Command::Comment { target, text } => {
+            match target {
+                CommentTarget::Table(table) => unquote(table),
+                CommentTarget::Column { table, column } => {
+                    unquote(table);
+                    unquote(column);
+                }
+            }
+            if let Some(text) = text {
+                unquote(text);
+            }
+        }
+        Command::SetOp { left, right, .. } => {
+            unquote_command(left);
+            unquote_command(right);
+        }
+        Command::Select {
+            table,
+            join,
+            columns,
+            filter,
+            group_by,
+            having,
+            order_by,
+            as_of,
+            ..
+        } => {
+            unquote(table);
+            if let Some(as_of) = as_of {
+                unquote(as_of);
+            }
+            if let Some(join) = join {
+                unquote(&mut join.table);
+                unquote(&mut join.left_column);
+                unquote(&mut join.right_column);
+            }
+            if let Some(columns) = columns {
+                unquote_all(columns);
+            }
+            if let Some(filter) = filter {
+                unquote_where(filter);
+            }
+            if let Some(group_by) = group_by {
+                unquote_all(group_by);
+            }
+            if let Some(having) = having {
+                unquote_where(having);
+            }
+            if let Some(order_by) = order_by {
+                unquote(&mut order_by.column);
+                for (col, _) in &mut order_by.then_by {
+                    unquote(col);
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,105 @@
-use super::where_clause::parse_where_clause;
-use crate::parser::command::{Command, JoinClause, JoinType, OrderBy, WhereClause};
+use super::KEYWORDS;
+use super::where_clause::{parse_compare_op, parse_where_clause};
+use crate::parser::command::{
+    Command, CompareOp, JoinClause, JoinType, LogicalOp, OrderBy, SetOpKind, Subquery, WhereClause,
+};
+
+/// A SELECT, or SELECTs joined by `intersect` / `except` [`all`]. The last
+/// operator outside parentheses and quotes splits the statement, so chains
+/// group to the left. Each operand keeps its own WHERE, ORDER BY and LIMIT.
+pub(super) fn parse_select_statement(
+    tokens: &[String],
+    quoted: &[bool],
+) -> Result<Command, String> {
+    let mut depth = 0usize;
+    let mut split = None;
+    for (i, token) in tokens.iter().enumerate() {
+        if quoted[i] {
+            continue;
+        }
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" => depth = depth.saturating_sub(1),
+            t if depth == 0 && t.eq_ignore_ascii_case("intersect") => {
+                split = Some((i, SetOpKind::Intersect))
+            }
+            t if depth == 0 && t.eq_ignore_ascii_case("except") => {
+                split = Some((i, SetOpKind::Except))
+            }
+            _ => {}
+        }
+    }
+    let Some((at, kind)) = split else {
+        return parse_select(tokens);
+    };
+    let all = tokens
+        .get(at + 1)
+        .is_some_and(|t| !quoted[at + 1] && t.eq_ignore_ascii_case("all"));
+    let right_start = if all { at + 2 } else { at + 1 };
+    if at == 0
+        || !tokens
+            .get(right_start)
+            .is_some_and(|t| t.eq_ignore_ascii_case("select"))
+    {
+        return Err(
+            "Usage: select ... intersect [all] select ... | select ... except [all] select ..."
+                .to_string(),
+        );
+    }
+    let left = parse_select_statement(&tokens[..at], &quoted[..at])?;
+    let right = parse_select(&tokens[right_start..])?;
+    for operand in [&left, &right] {
+        if let Command::Select {
+            for_update, as_of, ..
+        } = operand
+            && (*for_update || as_of.is_some())
+        {
+            return Err("FOR UPDATE and AS OF cannot be used with INTERSECT or EXCEPT".to_string());
+        }
+    }
+    Ok(Command::SetOp {
+        kind,
+        left: Box::new(left),
+        right: Box::new(right),
+        all,
+    })
+}
 
 pub(super) fn parse_select(tokens: &[String]) -> Result<Command, String> {
-    parse_select_projection(tokens)
+    let (for_update, tokens) = match tokens {
+        [rest @ .., f, u] if f.eq_ignore_ascii_case("for") && u.eq_ignore_ascii_case("update") => {
+            (true, rest)
+        }
+        _ => (false, tokens),
+    };
+    // `from <table> as of <snapshot>` is cut out so the clauses after it
+    // parse as usual.
+    let from_idx = tokens.iter().position(|t| t.eq_ignore_ascii_case("from"));
+    if let Some(at) = from_idx.map(|f| f + 2)
+        && at + 1 < tokens.len()
+        && tokens[at].eq_ignore_ascii_case("as")
+        && tokens[at + 1].eq_ignore_ascii_case("of")
+    {
+        let Some(name) = tokens.get(at + 2) else {
+            return Err(
+                "Bad AS OF syntax. Use: select ... from <table> as of <snapshot>".to_string(),
+            );
+        };
+        let rest: Vec<String> = tokens[..at]
+            .iter()
+            .chain(&tokens[at + 3..])
+            .cloned()
+            .collect();
+        return parse_select_projection(&rest, for_update, Some(name.clone()));
+    }
+    parse_select_projection(tokens, for_update, None)
 }
 
-fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
+fn parse_select_projection(
+    tokens: &[String],
+    for_update: bool,
+    as_of: Option<String>,
+) -> Result<Command, String> {
     let mut distinct = false;
     let projection_start = if tokens.len() > 1 && tokens[1].eq_ignore_ascii_case("distinct") {
         distinct = true;
@@ -31,14 +125,19 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
         return Err("SELECT missing table name after FROM".to_string());
     }
     let table = tokens[from_idx + 1].clone();
+    let alias = tokens
+        .get(from_idx + 2)
+        .filter(|t| is_table_alias(t))
+        .cloned();
 
-    let mut i = from_idx + 2;
+    let mut i = from_idx + 2 + usize::from(alias.is_some());
     let mut join: Option<JoinClause> = None;
     let mut filter: Option<WhereClause> = None;
     let mut group_by: Option<Vec<String>> = None;
     let mut having: Option<WhereClause> = None;
     let mut order_by: Option<OrderBy> = None;
     let mut limit: Option<usize> = None;
+    let mut limit_seen = false;
     let mut offset: Option<usize> = None;
 
     if i < tokens.len()
@@ -47,19 +146,22 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
         let (join_type, join_kw_idx) = if tokens[i].eq_ignore_ascii_case("left") {
             if i + 1 >= tokens.len() || !tokens[i + 1].eq_ignore_ascii_case("join") {
                 return Err(
-                    "Usage: select <col1,col2|*> from <table> [join|left join <table2> on <left_col> = <right_col>] [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>] [offset <n>]".to_string(),
+                    "Usage: select <col1,col2|*> from <table> [join|left join <table2> on <left_col> = <right_col>] [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>|all] [offset <n>]".to_string(),
                 );
             }
             (JoinType::Left, i + 1)
         } else {
             (JoinType::Inner, i)
         };
+        if alias.is_some() {
+            return Err("Table aliases are not supported with JOIN".to_string());
+        }
         if join_kw_idx + 5 >= tokens.len()
             || !tokens[join_kw_idx + 2].eq_ignore_ascii_case("on")
             || tokens[join_kw_idx + 4] != "="
         {
             return Err(
-                "Usage: select <col1,col2|*> from <table> [join|left join <table2> on <left_col> = <right_col>] [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>] [offset <n>]".to_string(),
+                "Usage: select <col1,col2|*> from <table> [join|left join <table2> on <left_col> = <right_col>] [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>|all] [offset <n>]".to_string(),
             );
         }
         join = Some(JoinClause {
@@ -74,7 +176,7 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
     if i < tokens.len() && tokens[i].eq_ignore_ascii_case("where") {
         if i + 2 >= tokens.len() {
             return Err(
-                "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>] [offset <n>]".to_string(),
+                "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>|all] [offset <n>]".to_string(),
             );
         }
         let where_end = find_where_end(tokens, i + 1)?;
@@ -114,7 +216,7 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
     if i < tokens.len() && tokens[i].eq_ignore_ascii_case("order") {
         if i + 2 >= tokens.len() || !tokens[i + 1].eq_ignore_ascii_case("by") {
             return Err(
-                "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>] [offset <n>]".to_string(),
+                "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>|all] [offset <n>]".to_string(),
             );
         }
         let (ob, next_i) = parse_order_by_list(tokens, i + 2)?;
@@ -124,14 +226,23 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
 
     while i < tokens.len() {
         if tokens[i].eq_ignore_ascii_case("limit") {
-            if limit.is_some() {
+            if limit_seen {
                 return Err("LIMIT specified more than once".to_string());
             }
+            limit_seen = true;
             if i + 1 >= tokens.len() {
                 return Err(
-                    "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>] [offset <n>]".to_string(),
+                    "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>|all] [offset <n>]".to_string(),
                 );
             }
+            if tokens[i + 1] == "?" {
+                return Err(bound_parameter_not_supported("LIMIT"));
+            }
+            // `limit all` is the same as no LIMIT clause.
+            if tokens[i + 1].eq_ignore_ascii_case("all") {
+                i += 2;
+                continue;
+            }
             let n = tokens[i + 1]
                 .parse::<usize>()
                 .map_err(|_| "LIMIT must be a non-negative integer".to_string())?;
@@ -145,9 +256,12 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
             }
             if i + 1 >= tokens.len() {
                 return Err(
-                    "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>] [offset <n>]".to_string(),
+                    "Usage: select <col1,col2|*> from <table> [where <column> <op> <value>] [order by <column> [asc|desc]] [limit <n>|all] [offset <n>]".to_string(),
                 );
             }
+            if tokens[i + 1] == "?" {
+                return Err(bound_parameter_not_supported("OFFSET"));
+            }
             let n = tokens[i + 1]
                 .parse::<usize>()
                 .map_err(|_| "OFFSET must be a non-negative integer".to_string())?;
@@ -160,10 +274,35 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
 
     if i != tokens.len() {
         return Err(
-            "Usage: select <col1,col2|*> from <table> [join|left join <table2> on <left_col> = <right_col>] [where <expr>] [group by <col1,col2>] [having <expr>] [order by <column> [asc|desc]] [limit <n>] [offset <n>]".to_string(),
+            "Usage: select <col1,col2|*> from <table> [join|left join <table2> on <left_col> = <right_col>] [where <expr>] [group by <col1,col2>] [having <expr>] [order by <column> [asc|desc]] [limit <n>|all] [offset <n>]".to_string(),
         );
     }
 
+    // EXISTS subqueries take their outer references first, while outer
+    // columns are still written `<alias>.<col>`.
+    let qualifier = alias.as_deref().unwrap_or(&table);
+    if let Some(filter) = filter.as_mut() {
+        bind_subqueries(filter, qualifier)?;
+    }
+    let mut columns = columns;
+    if let Some(alias) = alias.as_deref() {
+        for c in &mut columns {
+            *c = strip_qualifier(c, alias);
+        }
+        for clause in filter.iter_mut().chain(having.iter_mut()) {
+            strip_where_qualifier(clause, alias);
+        }
+        for c in group_by.iter_mut().flatten() {
+            *c = strip_qualifier(c, alias);
+        }
+        if let Some(ob) = order_by.as_mut() {
+            ob.column = strip_qualifier(&ob.column, alias);
+            for (c, _) in &mut ob.then_by {
+                *c = strip_qualifier(c, alias);
+            }
+        }
+    }
+
     Ok(Command::Select {
         table,
         distinct,
@@ -175,9 +314,203 @@ fn parse_select_projection(tokens: &[String]) -> Result<Command, String> {
         order_by,
         limit,
         offset,
+        for_update,
+        as_of,
     })
 }
 
+/// The query inside `exists (...)` or `in (...)`: `select <cols> from
+/// <table> [<alias>] [where <expr>]`, with its select list (empty for `*`).
+/// References to the outer query stay qualified until the enclosing
+/// statement binds them.
+pub(super) fn parse_subquery(
+    tokens: &[String],
+    usage: &str,
+) -> Result<(Subquery, Vec<String>), String> {
+    if !tokens
+        .first()
+        .is_some_and(|t| t.eq_ignore_ascii_case("select"))
+    {
+        return Err(usage.to_string());
+    }
+    let from_idx = tokens.iter().position(|t| t.eq_ignore_ascii_case("from"));
+    let alias = from_idx
+        .and_then(|f| tokens.get(f + 2))
+        .filter(|t| is_table_alias(t))
+        .cloned();
+    let Command::Select {
+        table,
+        join: None,
+        columns,
+        filter,
+        group_by: None,
+        having: None,
+        order_by: None,
+        limit: None,
+        offset: None,
+        ..
+    } = parse_select_projection(tokens, false, None)?
+    else {
+        return Err(usage.to_string());
+    };
+    if filter.as_ref().is_some_and(contains_exists) {
+        return Err("Subqueries cannot be nested".to_string());
+    }
+    let subquery = Subquery {
+        table,
+        alias,
+        filter: filter.map(Box::new),
+        correlation: Vec::new(),
+    };
+    Ok((subquery, columns.unwrap_or_default()))
+}
+
+/// A bare word after `from <table>` that is not a keyword names the table.
+fn is_table_alias(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.contains(&token.to_ascii_lowercase().as_str())
+}
+
+fn contains_exists(clause: &WhereClause) -> bool {
+    match clause {
+        WhereClause::Predicate(_) => false,
+        WhereClause::Binary { left, right, .. } => contains_exists(left) || contains_exists(right),
+        WhereClause::Exists { .. } => true,
+    }
+}
+
+/// Moves the `<inner col> = <outer>.<col>` terms of every subquery in
+/// `clause` into its correlation list, `outer` being the enclosing
+/// statement's alias, or its table name when it has none.
+pub(super) fn bind_subqueries(clause: &mut WhereClause, outer: &str) -> Result<(), String> {
+    match clause {
+        WhereClause::Predicate(_) => Ok(()),
+        WhereClause::Binary { left, right, .. } => {
+            bind_subqueries(left, outer)?;
+            bind_subqueries(right, outer)
+        }
+        WhereClause::Exists { subquery, .. } => bind_correlation(subquery, outer),
+    }
+}
+
+fn bind_correlation(subquery: &mut Subquery, outer: &str) -> Result<(), String> {
+    if subquery.alias.as_deref() == Some(outer) {
+        return Err(format!(
+            "Subquery alias '{outer}' must differ from the outer statement's"
+        ));
+    }
+    let Some(filter) = subquery.filter.take() else {
+        return Ok(());
+    };
+    let prefix = format!("{outer}.");
+    let mut kept: Vec<WhereClause> = Vec::new();
+    for term in and_terms(*filter) {
+        if let WhereClause::Predicate(p) = &term
+            && p.op == CompareOp::Eq
+        {
+            if let Some(outer_col) = p.value.strip_prefix(&prefix) {
+                subquery
+                    .correlation
+                    .push((p.column.clone(), outer_col.to_string()));
+                continue;
+            }
+            if let Some(outer_col) = p.column.strip_prefix(&prefix) {
+                let inner = match subquery.alias.as_deref() {
+                    Some(alias) => strip_qualifier(&p.value, alias),
+                    None => p.value.clone(),
+                };
+                subquery.correlation.push((inner, outer_col.to_string()));
+                continue;
+            }
+        }
+        if reads_qualifier(&term, &prefix) {
+            return Err(format!(
+                "A subquery can only reference the outer statement in '<column> = {outer}.<column>' terms joined by AND"
+            ));
+        }
+        kept.push(term);
+    }
+    subquery.filter = kept
+        .into_iter()
+        .reduce(|left, right| WhereClause::Binary {
+            left: Box::new(left),
+            op: LogicalOp::And,
+            right: Box::new(right),
+        })
+        .map(Box::new);
+    Ok(())
+}
+
+fn and_terms(clause: WhereClause) -> Vec<WhereClause> {
+    match clause {
+        WhereClause::Binary {
+            left,
+            op: LogicalOp::And,
+            right,
+        } => {
+            let mut out = and_terms(*left);
+            out.extend(and_terms(*right));
+            out
+        }
+        other => vec![other],
+    }
+}
+
+fn reads_qualifier(clause: &WhereClause, prefix: &str) -> bool {
+    match clause {
+        WhereClause::Predicate(p) => {
+            p.column.starts_with(prefix)
+                || p.value.starts_with(prefix)
+                || p.values.iter().any(|v| v.starts_with(prefix))
+        }
+        WhereClause::Binary { left, right, .. } => {
+            reads_qualifier(left, prefix) || reads_qualifier(right, prefix)
+        }
+        WhereClause::Exists { .. } => false,
+    }
+}
+
+/// Removes `<alias>.` from the columns of `clause` that read the row being
+/// filtered. A subquery resolves its own names, so only its outer
+/// correlation columns are touched.
+fn strip_where_qualifier(clause: &mut WhereClause, alias: &str) {
+    match clause {
+        WhereClause::Predicate(p) => p.column = strip_qualifier(&p.column, alias),
+        WhereClause::Binary { left, right, .. } => {
+            strip_where_qualifier(left, alias);
+            strip_where_qualifier(right, alias);
+        }
+        WhereClause::Exists { subquery, .. } => {
+            for (_, outer) in &mut subquery.correlation {
+                *outer = strip_qualifier(outer, alias);
+            }
+        }
+    }
+}
+
+/// `expr` with every `<alias>.` that starts a name removed, so
+/// `count(u.id) as n` becomes `count(id) as n`.
+fn strip_qualifier(expr: &str, alias: &str) -> String {
+    let prefix = format!("{alias}.");
+    let mut out = String::with_capacity(expr.len());
+    let mut i = 0usize;
+    while i < expr.len() {
+        let starts_name = i == 0 || matches!(expr.as_bytes()[i - 1], b'(' | b' ' | b',');
+        if starts_name && expr[i..].starts_with(&prefix) {
+            i += prefix.len();
+            continue;
+        }
+        let ch = expr[i..].chars().next().unwrap_or_default();
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+    out
+}
+
 fn parse_order_by_list(tokens: &[String], mut i: usize) -> Result<(OrderBy, usize), String> {
     let mut items: Vec<(String, bool)> = Vec::new();
     loop {
@@ -249,7 +582,7 @@ fn parse_order_by_list(tokens: &[String], mut i: usize) -> Result<(OrderBy, usiz
     ))
 }
 
-fn parse_select_columns(tokens: &[String]) -> Result<Vec<String>, String> {
+pub(super) fn parse_select_columns(tokens: &[String]) -> Result<Vec<String>, String> {
     if tokens.len() == 1 && tokens[0] == "*" {
         return Ok(Vec::new());
     }
@@ -305,6 +638,12 @@ fn parse_select_columns(tokens: &[String]) -> Result<Vec<String>, String> {
             if i + 1 >= tokens.len() || tokens[i + 1] == "," {
                 return Err("Bad SELECT alias syntax. Use: <expr> as <alias>".to_string());
             }
+            if tokens[i + 1].contains('.') {
+                return Err(format!(
+                    "Bad SELECT alias '{}'. Aliases cannot contain '.'",
+                    tokens[i + 1]
+                ));
+            }
             expr = format!("{expr} as {}", tokens[i + 1]);
             i += 2;
         }
@@ -338,8 +677,20 @@ fn parse_group_by_columns(tokens: &[String], mut i: usize) -> Result<(Vec<String
         if tokens[i] == "," || tokens[i] == "(" || tokens[i] == ")" {
             return Err("Bad GROUP BY column list".to_string());
         }
-        cols.push(tokens[i].clone());
-        i += 1;
+        // fn(col) keys are folded into one item, matching the SELECT list form.
+        if tokens.get(i + 1).is_some_and(|t| t == "(") {
+            let arg = tokens.get(i + 2).filter(|t| *t != "(" && *t != ")");
+            match (arg, tokens.get(i + 3)) {
+                (Some(arg), Some(close)) if close == ")" => {
+                    cols.push(format!("{}({})", tokens[i], arg));
+                    i += 4;
+                }
+                _ => return Err("Bad GROUP BY expression. Use fn(col)".to_string()),
+            }
+        } else {
+            cols.push(tokens[i].clone());
+            i += 1;
+        }
         if i < tokens.len() && tokens[i] == "," {
             i += 1;
             continue;
@@ -351,12 +702,21 @@ fn parse_group_by_columns(tokens: &[String], mut i: usize) -> Result<(Vec<String
 
 pub(super) fn find_where_end(tokens: &[String], start: usize) -> Result<usize, String> {
     let mut i = start;
+    // Clause words inside `exists (...)` belong to the subquery.
+    let mut depth = 0usize;
     while i < tokens.len() {
-        if tokens[i].eq_ignore_ascii_case("group")
-            || tokens[i].eq_ignore_ascii_case("having")
-            || tokens[i].eq_ignore_ascii_case("order")
-            || tokens[i].eq_ignore_ascii_case("limit")
-            || tokens[i].eq_ignore_ascii_case("offset")
+        match tokens[i].as_str() {
+            "(" => depth += 1,
+            ")" => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0
+            && is_clause_keyword(
+                tokens,
+                start,
+                i,
+                &["group", "having", "order", "limit", "offset"],
+            )
         {
             return Ok(i);
         }
@@ -368,10 +728,7 @@ pub(super) fn find_where_end(tokens: &[String], start: usize) -> Result<usize, S
 fn find_having_end(tokens: &[String], start: usize) -> Result<usize, String> {
     let mut i = start;
     while i < tokens.len() {
-        if tokens[i].eq_ignore_ascii_case("order")
-            || tokens[i].eq_ignore_ascii_case("limit")
-            || tokens[i].eq_ignore_ascii_case("offset")
-        {
+        if is_clause_keyword(tokens, start, i, &["order", "limit", "offset"]) {
             return Ok(i);
         }
         i += 1;
@@ -379,6 +736,32 @@ fn find_having_end(tokens: &[String], start: usize) -> Result<usize, String> {
     Ok(tokens.len())
 }
 
+/// A clause keyword only ends a predicate list when it follows a complete
+/// predicate. In column or value position (`where order = 1`,
+/// `and limit > 2`, `= 'group'`) it is an ordinary identifier or literal.
+fn is_clause_keyword(tokens: &[String], start: usize, i: usize, keywords: &[&str]) -> bool {
+    let tok = &tokens[i];
+    if !keywords.iter().any(|k| tok.eq_ignore_ascii_case(k)) {
+        return false;
+    }
+    if (tok.eq_ignore_ascii_case("group") || tok.eq_ignore_ascii_case("order"))
+        && !tokens
+            .get(i + 1)
+            .is_some_and(|t| t.eq_ignore_ascii_case("by"))
+    {
+        return false;
+    }
+    if i == start {
+        return false;
+    }
+    let prev = tokens[i - 1].as_str();
+    let expects_operand = ["and", "or", "not", "is", "in", "(", ","]
+        .iter()
+        .any(|k| prev.eq_ignore_ascii_case(k))
+        || parse_compare_op(prev).is_ok();
+    !expects_operand
+}
+
 fn normalize_function_tokens(tokens: &[String]) -> Result<Vec<String>, String> {
     let mut out: Vec<String> = Vec::new();
     let mut i = 0usize;
@@ -409,3 +792,11 @@ fn normalize_function_tokens(tokens: &[String]) -> Result<Vec<String>, String> {
     }
     Ok(out)
 }
+
+/// `limit ?` only makes sense for prepared statements, which the engine does
+/// not have yet; say so instead of reporting a bad integer.
+fn bound_parameter_not_supported(clause: &str) -> String {
+    format!(
+        "{clause} ? is not supported yet: prepared statements and bound parameters are not implemented"
+    )
+}
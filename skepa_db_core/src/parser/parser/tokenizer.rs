@@ -1,12 +1,30 @@
-pub(super) fn tokenize(input: &str) -> Result<Vec<String>, String> {
+use super::identifiers::QUOTED_IDENT;
+
+/// Splits `input` into tokens. The second vector is parallel to the first
+/// and marks the tokens that were written as `"..."` strings, which
+/// otherwise look exactly like bare words.
+pub(super) fn tokenize(input: &str) -> Result<(Vec<String>, Vec<bool>), String> {
+    if input.contains(QUOTED_IDENT) {
+        return Err("Input contains a reserved character (U+E000)".to_string());
+    }
     let mut tokens: Vec<String> = Vec::new();
+    let mut quoted: Vec<bool> = Vec::new();
     let mut current = String::new();
     let mut in_quotes = false;
     let mut just_closed_quote = false;
+    // Set by a `;` outside quotes. Only whitespace may follow it: one
+    // statement per call, with a trailing `;` tolerated as paste leftover.
+    let mut terminated = false;
 
     let mut it = input.chars().peekable();
 
     while let Some(ch) = it.next() {
+        if terminated && !ch.is_whitespace() {
+            return Err(
+                "Unexpected input after ';'. Run one statement at a time; only a single trailing ';' is allowed"
+                    .to_string(),
+            );
+        }
         match ch {
             '"' => {
                 if just_closed_quote {
@@ -28,6 +46,22 @@ pub(super) fn tokenize(input: &str) -> Result<Vec<String>, String> {
                 }
             }
 
+            '`' | '[' if !in_quotes => {
+                if just_closed_quote {
+                    return Err("Characters found immediately after a closing quote. Add whitespace after the quoted string."
+                        .to_string());
+                }
+                let name = read_quoted_identifier(ch, &mut it)?;
+                if current.is_empty() {
+                    current.push(QUOTED_IDENT);
+                } else if !current.ends_with('.') {
+                    return Err(format!(
+                        "Quoted identifier cannot start in the middle of a token near '{current}'. Add whitespace before it."
+                    ));
+                }
+                current.push_str(&name);
+            }
+
             '\\' if in_quotes => {
                 match it.peek().copied() {
                     Some('"') => {
@@ -48,6 +82,8 @@ pub(super) fn tokenize(input: &str) -> Result<Vec<String>, String> {
 
             c if c.is_whitespace() && !in_quotes => {
                 if just_closed_quote {
+                    quoted.resize(tokens.len(), false);
+                    quoted.push(true);
                     tokens.push(std::mem::take(&mut current));
                     just_closed_quote = false;
                     continue;
@@ -58,8 +94,20 @@ pub(super) fn tokenize(input: &str) -> Result<Vec<String>, String> {
                 }
             }
 
+            ';' if !in_quotes => {
+                if !current.is_empty() || just_closed_quote {
+                    quoted.resize(tokens.len(), false);
+                    quoted.push(just_closed_quote);
+                    tokens.push(std::mem::take(&mut current));
+                    just_closed_quote = false;
+                }
+                terminated = true;
+            }
+
             ',' | '(' | ')' if !in_quotes => {
                 if just_closed_quote {
+                    quoted.resize(tokens.len(), false);
+                    quoted.push(true);
                     tokens.push(std::mem::take(&mut current));
                     just_closed_quote = false;
                 } else if !current.is_empty() {
@@ -70,6 +118,8 @@ pub(super) fn tokenize(input: &str) -> Result<Vec<String>, String> {
 
             '>' | '<' | '=' | '!' if !in_quotes => {
                 if just_closed_quote {
+                    quoted.resize(tokens.len(), false);
+                    quoted.push(true);
                     tokens.push(std::mem::take(&mut current));
                     just_closed_quote = false;
                 } else if !current.is_empty() {
@@ -101,8 +151,43 @@ pub(super) fn tokenize(input: &str) -> Result<Vec<String>, String> {
     }
 
     if !current.is_empty() || just_closed_quote {
+        quoted.resize(tokens.len(), false);
+        quoted.push(just_closed_quote);
         tokens.push(current);
     }
+    quoted.resize(tokens.len(), false);
+
+    Ok((tokens, quoted))
+}
 
-    Ok(tokens)
+/// Reads a `` `name` `` or `[name]` identifier after its opening delimiter.
+/// The name must be non-empty and may only contain characters a bare
+/// identifier could, so the rest of the parser never sees whitespace,
+/// quotes or punctuation inside a name.
+fn read_quoted_identifier(
+    open: char,
+    it: &mut std::iter::Peekable<std::str::Chars<'_>>,
+) -> Result<String, String> {
+    let close = if open == '[' { ']' } else { '`' };
+    let mut name = String::new();
+    loop {
+        match it.next() {
+            Some(c) if c == close => break,
+            Some(c) => name.push(c),
+            None => return Err(format!("Unclosed quoted identifier ({open}) in input")),
+        }
+    }
+    if name.is_empty() {
+        return Err("Quoted identifier cannot be empty".to_string());
+    }
+    if let Some(bad) = name.chars().find(|c| {
+        c.is_whitespace()
+            || matches!(
+                c,
+                '"' | '`' | '[' | ']' | '.' | ',' | '(' | ')' | '<' | '>' | '=' | '!'
+            )
+    }) {
+        return Err(format!("Quoted identifier '{name}' cannot contain '{bad}'"));
+    }
+    Ok(name)
 }
@@ -20,3 +20,82 @@ pub(super) fn parse_rollback(tokens: &[String]) -> Result<Command, String> {
     }
     Ok(Command::Rollback)
 }
+
+pub(super) fn parse_show(tokens: &[String]) -> Result<Command, String> {
+    if tokens.len() == 4
+        && tokens[1].eq_ignore_ascii_case("indexes")
+        && (tokens[2].eq_ignore_ascii_case("from") || tokens[2].eq_ignore_ascii_case("on"))
+    {
+        return Ok(Command::ShowIndexes {
+            table: tokens[3].clone(),
+        });
+    }
+    if tokens.len() == 4
+        && tokens[1].eq_ignore_ascii_case("tables")
+        && tokens[2].eq_ignore_ascii_case("with")
+        && tokens[3].eq_ignore_ascii_case("stats")
+    {
+        return Ok(Command::ShowTables { with_stats: true });
+    }
+    if tokens.len() != 2 {
+        return Err(
+            "Usage: show transaction | show tables [with stats] | show wal | show indexes from|on <table> | show <setting>"
+                .to_string(),
+        );
+    }
+    if tokens[1].eq_ignore_ascii_case("transaction") {
+        return Ok(Command::ShowTransaction);
+    }
+    if tokens[1].eq_ignore_ascii_case("tables") {
+        return Ok(Command::ShowTables { with_stats: false });
+    }
+    if tokens[1].eq_ignore_ascii_case("wal") {
+        return Ok(Command::ShowWal);
+    }
+    Ok(Command::Show {
+        name: tokens[1].clone(),
+    })
+}
+
+pub(super) fn parse_checkpoint(tokens: &[String]) -> Result<Command, String> {
+    if tokens.len() != 1 {
+        return Err("Usage: checkpoint".to_string());
+    }
+    Ok(Command::Checkpoint)
+}
+
+pub(super) fn parse_set(tokens: &[String]) -> Result<Command, String> {
+    if tokens.len() != 4 || tokens[2] != "=" {
+        return Err("Usage: set <setting> = <value>".to_string());
+    }
+    Ok(Command::Set {
+        name: tokens[1].clone(),
+        value: tokens[3].clone(),
+    })
+}
+
+pub(super) fn parse_list(tokens: &[String]) -> Result<Command, String> {
+    if tokens.len() != 2 || !tokens[1].eq_ignore_ascii_case("snapshots") {
+        return Err("Usage: list snapshots".to_string());
+    }
+    Ok(Command::ListSnapshots)
+}
+
+pub(super) fn parse_attach(tokens: &[String]) -> Result<Command, String> {
+    if tokens.len() != 4 || !tokens[2].eq_ignore_ascii_case("as") {
+        return Err("Usage: attach <path> as <alias>".to_string());
+    }
+    Ok(Command::Attach {
+        path: tokens[1].clone(),
+        alias: tokens[3].clone(),
+    })
+}
+
+pub(super) fn parse_detach(tokens: &[String]) -> Result<Command, String> {
+    if tokens.len() != 2 {
+        return Err("Usage: detach <alias>".to_string());
+    }
+    Ok(Command::Detach {
+        alias: tokens[1].clone(),
+    })
+}
@@ -1,3 +1,4 @@
+use super::select::parse_subquery;
 use crate::parser::command::{CompareOp, LogicalOp, Predicate, WhereClause};
 
 pub(super) fn parse_compare_op(raw: &str) -> Result<CompareOp, String> {
@@ -81,9 +82,41 @@ fn parse_primary_expr(
         *idx += 1;
         return Ok(expr);
     }
+    let negated = tokens[*idx].eq_ignore_ascii_case("not");
+    let at = *idx + usize::from(negated);
+    if tokens
+        .get(at)
+        .is_some_and(|t| t.eq_ignore_ascii_case("exists"))
+        && tokens.get(at + 1).is_some_and(|t| t == "(")
+    {
+        let close = matching_paren(tokens, at + 1)
+            .ok_or_else(|| "Malformed EXISTS. Missing closing ')'".to_string())?;
+        let (subquery, _) = parse_subquery(
+            &tokens[at + 2..close],
+            "Usage: [not] exists (select <cols> from <table> [<alias>] [where <expr>])",
+        )?;
+        *idx = close + 1;
+        return Ok(WhereClause::Exists { subquery, negated });
+    }
     parse_predicate(tokens, idx, usage_msg)
 }
 
+/// Index of the `)` closing the `(` at `open`.
+fn matching_paren(tokens: &[String], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, t) in tokens.iter().enumerate().skip(open) {
+        if t == "(" {
+            depth += 1;
+        } else if t == ")" {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
 fn parse_predicate(
     tokens: &[String],
     idx: &mut usize,
@@ -97,6 +130,7 @@ fn parse_predicate(
             column: tokens[*idx].clone(),
             op: CompareOp::IsNull,
             value: String::new(),
+            values: Vec::new(),
         };
         *idx += 3;
         return Ok(WhereClause::Predicate(p));
@@ -110,10 +144,39 @@ fn parse_predicate(
             column: tokens[*idx].clone(),
             op: CompareOp::IsNotNull,
             value: String::new(),
+            values: Vec::new(),
         };
         *idx += 4;
         return Ok(WhereClause::Predicate(p));
     }
+    // `<col> in (select <c> ...)` is `exists (select ... where <c> = <col>)`:
+    // the selected column pairs with `<col>` like a correlation term.
+    if *idx + 3 < tokens.len()
+        && tokens[*idx + 1].eq_ignore_ascii_case("in")
+        && tokens[*idx + 2] == "("
+        && tokens[*idx + 3].eq_ignore_ascii_case("select")
+    {
+        let close = matching_paren(tokens, *idx + 2)
+            .ok_or_else(|| "Malformed IN subquery. Missing closing ')'".to_string())?;
+        let (mut subquery, columns) = parse_subquery(
+            &tokens[*idx + 3..close],
+            "Usage: <column> in (select <column> from <table> [<alias>] [where <expr>])",
+        )?;
+        let [selected] = columns.as_slice() else {
+            return Err("IN subquery must select exactly one column".to_string());
+        };
+        if selected.contains(['(', ' ']) {
+            return Err("IN subquery must select a plain column".to_string());
+        }
+        subquery
+            .correlation
+            .push((selected.clone(), tokens[*idx].clone()));
+        *idx = close + 1;
+        return Ok(WhereClause::Exists {
+            subquery,
+            negated: false,
+        });
+    }
     if *idx + 2 < tokens.len() && tokens[*idx + 1].eq_ignore_ascii_case("in") {
         if tokens[*idx + 2] != "(" {
             return Err(format!(
@@ -121,20 +184,16 @@ fn parse_predicate(
                 tokens[*idx]
             ));
         }
-        if *idx + 3 < tokens.len() && tokens[*idx + 3] == ")" {
-            return Err("IN list cannot be empty".to_string());
-        }
+        // `x in ()` is accepted and matches no rows.
         let mut vals: Vec<String> = Vec::new();
         let mut i = *idx + 3;
         while i < tokens.len() {
             if tokens[i] == ")" {
-                if vals.is_empty() {
-                    return Err("IN list cannot be empty".to_string());
-                }
                 let p = Predicate {
                     column: tokens[*idx].clone(),
                     op: CompareOp::In,
-                    value: vals.join("\u{1F}"),
+                    value: String::new(),
+                    values: vals,
                 };
                 *idx = i + 1;
                 return Ok(WhereClause::Predicate(p));
@@ -162,6 +221,7 @@ fn parse_predicate(
             column: tokens[*idx].clone(),
             op,
             value: tokens[*idx + 2].clone(),
+            values: Vec::new(),
         };
         *idx += 3;
         return Ok(WhereClause::Predicate(p));
@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QueryResult {
     Select {
-        schema: Schema,
+        /// Boxed so the message-only variants stay small.
+        schema: Box<Schema>,
         rows: Vec<Row>,
         stats: ExecutionStats,
     },
@@ -34,7 +35,7 @@ impl QueryResult {
             index_used: None,
         };
         Self::Select {
-            schema,
+            schema: Box::new(schema),
             rows,
             stats,
         }
@@ -44,12 +45,22 @@ impl QueryResult {
         stats.rows_returned = Some(rows.len());
         stats.rows_affected = None;
         Self::Select {
-            schema,
+            schema: Box::new(schema),
             rows,
             stats,
         }
     }
 
+    /// Rows a mutation's RETURNING clause asked for. Shaped like a SELECT,
+    /// but `rows_affected` still carries the mutation's count.
+    pub fn returning(schema: Schema, rows: Vec<Row>, rows_affected: usize) -> Self {
+        let mut out = Self::select(schema, rows);
+        if let Self::Select { stats, .. } = &mut out {
+            stats.rows_affected = Some(rows_affected);
+        }
+        out
+    }
+
     pub fn mutation(message: impl Into<String>, rows_affected: usize) -> Self {
         Self::Mutation {
             message: message.into(),
@@ -95,9 +106,29 @@ impl QueryResult {
         }
     }
 
+    /// Keeps at most `max` rows of a SELECT result.
+    pub(crate) fn truncate_rows(mut self, max: usize) -> Self {
+        if let Self::Select { rows, stats, .. } = &mut self
+            && rows.len() > max
+        {
+            rows.truncate(max);
+            stats.rows_returned = Some(max);
+        }
+        self
+    }
+
+    /// Marks a mutation as staged in an open transaction rather than committed.
+    pub(crate) fn into_staged(mut self) -> Self {
+        if let Self::Mutation { message, .. } = &mut self {
+            *message = format!("staged: {message}");
+        }
+        self
+    }
+
     pub fn rows_affected(&self) -> Option<usize> {
         match self {
             Self::Mutation { rows_affected, .. } => Some(*rows_affected),
+            Self::Select { stats, .. } => stats.rows_affected,
             _ => None,
         }
     }
@@ -1,5 +1,87 @@
 use super::*;
 
+/// Formats a WAL `OP` record as `OP <txid> crc=<crc32> <statement>`. The
+/// statement is escaped (`\\`, `\n`, `\r`) so a quoted value containing a
+/// line break stays on one line, and the checksum covers the unescaped text.
+pub(crate) fn wal_op_line(txid: u64, stmt: &str) -> String {
+    let stmt = stmt.trim();
+    let mut escaped = String::with_capacity(stmt.len());
+    for ch in stmt.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(ch),
+        }
+    }
+    format!("OP {txid} crc={:08x} {escaped}", crc32(stmt.as_bytes()))
+}
+
+/// `stmt` as logged: prefixed with `uuids=<uuid>,<uuid> ` when its
+/// `gen_uuid()` calls, its triggers' included, handed out `uuids`, so
+/// replay stores the same values instead of generating new ones.
+pub(crate) fn with_generated_uuids(stmt: &str, uuids: &[uuid::Uuid]) -> String {
+    if uuids.is_empty() {
+        return stmt.trim().to_string();
+    }
+    let list: Vec<String> = uuids.iter().map(|u| u.to_string()).collect();
+    format!("uuids={} {}", list.join(","), stmt.trim())
+}
+
+/// Splits a logged statement into the uuids [`with_generated_uuids`]
+/// recorded for it and the statement itself.
+pub(super) fn split_generated_uuids(logged: &str) -> Result<(Vec<uuid::Uuid>, &str), String> {
+    let Some(rest) = logged.strip_prefix("uuids=") else {
+        return Ok((Vec::new(), logged));
+    };
+    let (list, stmt) = rest
+        .split_once(' ')
+        .ok_or_else(|| "malformed uuids= prefix".to_string())?;
+    let uuids = list
+        .split(',')
+        .map(|u| uuid::Uuid::parse_str(u).map_err(|_| format!("bad logged uuid '{u}'")))
+        .collect::<Result<_, _>>()?;
+    Ok((uuids, stmt))
+}
+
+/// Recovers the statement from the text after `OP <txid> `. Records written
+/// before checksums were added carry the raw statement and are returned
+/// as-is; a checksummed record that fails to decode or verify is `None`.
+fn decode_wal_op(payload: &str) -> Option<String> {
+    let Some(rest) = payload.strip_prefix("crc=") else {
+        return Some(payload.to_string());
+    };
+    let (crc, escaped) = rest.split_once(' ')?;
+    let expected = u32::from_str_radix(crc, 16).ok()?;
+    let mut stmt = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            stmt.push(ch);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => stmt.push('\\'),
+            'n' => stmt.push('\n'),
+            'r' => stmt.push('\r'),
+            _ => return None,
+        }
+    }
+    (crc.len() == 8 && crc32(stmt.as_bytes()) == expected).then_some(stmt)
+}
+
+/// CRC-32 (IEEE), as used by zip and PNG.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &b in bytes {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & 0u32.wrapping_sub(crc & 1));
+        }
+    }
+    !crc
+}
+
 impl Database {
     pub(super) fn initialize_storage(path: &Path) -> DbResult<DiskStorage> {
         DiskStorage::new(path.to_path_buf()).map_err(DbError::from)
@@ -28,45 +110,193 @@ impl Database {
         }
     }
 
+    /// Loads every table in the catalog, reconciling each table's saved
+    /// index data with the indexes the catalog defines. A crash between
+    /// saving the catalog and the index file of `create index` or `drop
+    /// index` leaves them disagreeing; what was rebuilt or dropped goes to
+    /// [`Database::recovery_report`].
     pub(super) fn bootstrap_tables(&mut self) -> DbResult<()> {
         for (table, _) in self.catalog.snapshot_tables() {
             let schema = self.catalog.schema(&table).map_err(DbError::from)?;
-            self.storage
-                .bootstrap_table(&table, schema)
+            self.recreate_if_missing(&self.storage, &table)
                 .map_err(DbError::from)?;
+            let repairs = self
+                .storage
+                .bootstrap_table_with_repairs(&table, schema)
+                .map_err(DbError::from)?;
+            self.recovery_report.index_repairs.extend(repairs);
+        }
+        Ok(())
+    }
+
+    /// What [`Database::open`] repaired while loading this handle.
+    pub fn recovery_report(&self) -> &RecoveryReport {
+        &self.recovery_report
+    }
+
+    /// With `recreate_missing_table_files`, replaces a deleted `.rows` file
+    /// with an empty one so the table opens empty instead of failing.
+    pub(super) fn recreate_if_missing(
+        &self,
+        storage: &DiskStorage,
+        table: &str,
+    ) -> Result<(), String> {
+        if self.recreate_missing_table_files && storage.recreate_missing_table_file(table)? {
+            eprintln!(
+                "skepa-db: table file for '{}' was missing, recreated it empty",
+                table
+            );
         }
         Ok(())
     }
 
     pub(super) fn recover(&mut self) -> DbResult<()> {
-        self.replay_wal().map_err(DbError::from)?;
-        self.checkpoint_and_truncate_wal().map_err(DbError::from)?;
+        self.replay_wal(true).map_err(DbError::from)?;
+        self.checkpoint_all_and_truncate_wal()
+            .map_err(DbError::from)?;
         Ok(())
     }
 
     pub(super) fn save_catalog(&self) -> Result<(), String> {
+        Generations::bump(&self.path, &[])?;
         self.catalog.save_to_path(&self.path.join("catalog.json"))
     }
 
-    pub(super) fn append_wal_line(&self, line: &str) -> Result<(), String> {
-        let wal_path = self.path.join("wal.log");
-        let mut f = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&wal_path)
-            .map_err(|e| format!("Failed to open WAL: {e}"))?;
-        f.write_all(line.trim().as_bytes())
+    /// Writes a schema change: the catalog and the table it rewrote go
+    /// through one journal, so a crash never leaves a saved catalog that
+    /// describes rows in their old shape. If the write fails, the catalog
+    /// and table go back to `before` in memory and, as far as the disk
+    /// allows, on disk.
+    pub(super) fn persist_schema_change(
+        &mut self,
+        table: Option<&str>,
+        before: Option<(Catalog, storage::disk::TableSnapshot)>,
+    ) -> Result<(), String> {
+        let written = match table {
+            Some(_) if crate::storage_test_hooks::should_fail_schema_table_write(&self.path) => {
+                Err("Simulated table write failure".to_string())
+            }
+            Some(table) => self.save_catalog_with_table(table),
+            None => self.save_catalog(),
+        };
+        if let Err(e) = written {
+            if let (Some(table), Some((catalog, snapshot))) = (table, before) {
+                self.catalog = catalog;
+                self.storage.restore_table(table, snapshot);
+                let _ = if self.catalog.exists(table) {
+                    self.save_catalog_with_table(table)
+                } else {
+                    self.save_catalog()
+                };
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn save_catalog_with_table(&self, table: &str) -> Result<(), String> {
+        let mut changes = storage::persistence::FileJournal::new();
+        changes.write(
+            self.path.join("catalog.json"),
+            self.catalog.to_json()?.into_bytes(),
+        );
+        self.storage.persist_table_with(table, changes)
+    }
+
+    /// Appends one WAL record group (a statement's or a commit's
+    /// BEGIN/OP/COMMIT lines) with a single write and sync, so a group is
+    /// never interleaved with another and costs one open at most.
+    pub(super) fn append_wal_group(&self, lines: &[String]) -> Result<(), String> {
+        Generations::bump(&self.path, &[])?;
+        let mut buf: Vec<u8> = Vec::new();
+        for line in lines {
+            buf.extend_from_slice(line.trim().as_bytes());
+            buf.push(b'\n');
+        }
+        let opened;
+        let mut f: &fs::File = match &self.wal_file {
+            Some(f) => f,
+            None => {
+                opened = Self::open_wal(&self.path)?;
+                &opened
+            }
+        };
+        f.write_all(&buf)
             .map_err(|e| format!("Failed to write WAL entry: {e}"))?;
-        f.write_all(b"\n")
-            .map_err(|e| format!("Failed to write WAL newline: {e}"))?;
-        f.flush()
-            .map_err(|e| format!("Failed to flush WAL entry: {e}"))?;
         f.sync_data()
             .map_err(|e| format!("Failed to sync WAL entry: {e}"))?;
         Ok(())
     }
 
-    pub(super) fn replay_wal(&mut self) -> Result<(), String> {
+    pub(super) fn open_wal(path: &Path) -> Result<fs::File, String> {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.join("wal.log"))
+            .map_err(|e| format!("Failed to open WAL: {e}"))
+    }
+
+    /// Locks `db.lock` in the database directory. Deferred checkpoints leave
+    /// commits in the WAL alone, which other handles neither read nor keep
+    /// when they truncate it, so such a handle takes the directory for
+    /// itself (`exclusive`); handles checkpointing every commit share it.
+    pub(super) fn lock_directory(path: &Path, exclusive: bool) -> Result<fs::File, String> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.join("db.lock"))
+            .map_err(|e| format!("Failed to open directory lock: {e}"))?;
+        let locked = if exclusive {
+            file.try_lock()
+        } else {
+            file.try_lock_shared()
+        };
+        match locked {
+            Ok(()) => Ok(file),
+            Err(fs::TryLockError::WouldBlock) if exclusive => Err(format!(
+                "Database directory '{}' is already open; a handle with deferred checkpoints must be the only one",
+                path.display()
+            )),
+            Err(fs::TryLockError::WouldBlock) => Err(format!(
+                "Database directory '{}' is open by a handle with deferred checkpoints",
+                path.display()
+            )),
+            Err(fs::TryLockError::Error(e)) => {
+                Err(format!("Failed to lock database directory: {e}"))
+            }
+        }
+    }
+
+    /// Locks `write.lock` in the database directory for this handle alone,
+    /// waiting while another handle holds it. Handles that checkpoint every
+    /// commit share the directory, so each write holds this lock from
+    /// reading the state it starts from until its files are on disk; a
+    /// schema change and a row write can then never interleave.
+    pub(super) fn lock_writes(path: &Path) -> Result<fs::File, String> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.join("write.lock"))
+            .map_err(|e| format!("Failed to open write lock: {e}"))?;
+        file.lock()
+            .map_err(|e| format!("Failed to lock database writes: {e}"))?;
+        Ok(file)
+    }
+
+    /// [`Database::lock_writes`] for a handle that may share its directory;
+    /// a handle with deferred checkpoints has it to itself and takes none.
+    pub(super) fn write_guard(&self) -> Result<Option<fs::File>, String> {
+        if self.auto_checkpoint != AutoCheckpoint::EveryCommit {
+            return Ok(None);
+        }
+        Self::lock_writes(&self.path).map(Some)
+    }
+
+    /// Applies the WAL's committed groups to the loaded tables. With
+    /// `report`, a summary of what was replayed and skipped goes to stderr.
+    pub(super) fn replay_wal(&mut self, report: bool) -> Result<(), String> {
         let wal_path = self.path.join("wal.log");
         if !wal_path.exists() {
             return Ok(());
@@ -76,9 +306,12 @@ impl Database {
 
         #[derive(Default)]
         struct ReplayTx {
-            first_line: usize,
+            commit_line: usize,
             committed: bool,
             rolled_back: bool,
+            /// An `OP` record failed its checksum; the whole transaction is
+            /// skipped.
+            corrupt: bool,
             ops: Vec<(usize, String)>,
         }
 
@@ -88,6 +321,7 @@ impl Database {
         let total_lines = content.lines().count();
 
         let mut ignored_truncated_tail = false;
+        let mut corrupt_record_count = 0usize;
 
         for (idx, raw_line) in content.lines().enumerate() {
             if idx + 1 == total_lines && !ends_with_newline {
@@ -110,10 +344,7 @@ impl Database {
                     let txid: u64 = parts[1]
                         .parse()
                         .map_err(|_| format!("WAL parse error at line {}: bad txid", idx + 1))?;
-                    let tx = txs.entry(txid).or_default();
-                    if tx.first_line == 0 {
-                        tx.first_line = idx + 1;
-                    }
+                    txs.entry(txid).or_default();
                 }
                 Some("OP") => {
                     if parts.len() != 3 {
@@ -126,10 +357,20 @@ impl Database {
                         .parse()
                         .map_err(|_| format!("WAL parse error at line {}: bad txid", idx + 1))?;
                     let tx = txs.entry(txid).or_default();
-                    if tx.first_line == 0 {
-                        tx.first_line = idx + 1;
+                    match decode_wal_op(parts[2]) {
+                        Some(stmt) => tx.ops.push((idx + 1, stmt)),
+                        None => {
+                            if report {
+                                eprintln!(
+                                    "skepa-db: WAL record at line {} failed its checksum, skipping transaction {}",
+                                    idx + 1,
+                                    txid
+                                );
+                            }
+                            corrupt_record_count += 1;
+                            tx.corrupt = true;
+                        }
                     }
-                    tx.ops.push((idx + 1, parts[2].to_string()));
                 }
                 Some("COMMIT") => {
                     if parts.len() != 2 {
@@ -142,9 +383,7 @@ impl Database {
                         .parse()
                         .map_err(|_| format!("WAL parse error at line {}: bad txid", idx + 1))?;
                     let tx = txs.entry(txid).or_default();
-                    if tx.first_line == 0 {
-                        tx.first_line = idx + 1;
-                    }
+                    tx.commit_line = idx + 1;
                     tx.committed = true;
                 }
                 Some("ROLLBACK") => {
@@ -157,11 +396,7 @@ impl Database {
                     let txid: u64 = parts[1]
                         .parse()
                         .map_err(|_| format!("WAL parse error at line {}: bad txid", idx + 1))?;
-                    let tx = txs.entry(txid).or_default();
-                    if tx.first_line == 0 {
-                        tx.first_line = idx + 1;
-                    }
-                    tx.rolled_back = true;
+                    txs.entry(txid).or_default().rolled_back = true;
                 }
                 Some(other) => {
                     return Err(format!(
@@ -173,10 +408,12 @@ impl Database {
             }
         }
 
+        // Commits are serialized, so COMMIT records are in the order the
+        // transactions took effect; BEGIN order can differ when they overlap.
         let mut ordered_txs: Vec<(usize, ReplayTx)> = txs
             .into_values()
             .filter(|tx| tx.committed && !tx.rolled_back)
-            .map(|tx| (tx.first_line, tx))
+            .map(|tx| (tx.commit_line, tx))
             .collect();
         ordered_txs.sort_by_key(|(line, _)| *line);
         let committed_tx_count = ordered_txs.len();
@@ -184,6 +421,10 @@ impl Database {
         let mut skipped_tx_count = 0usize;
 
         for (_, tx) in ordered_txs {
+            if tx.corrupt {
+                skipped_tx_count += 1;
+                continue;
+            }
             let before_catalog = self.catalog.clone();
             let before_storage = self.storage.clone();
             let mut invalid_tx = false;
@@ -191,21 +432,33 @@ impl Database {
             let mut ops = tx.ops;
             ops.sort_by_key(|(line_no, _)| *line_no);
 
-            for (line_no, stmt) in ops {
-                let cmd = parser::parser::parse(&stmt)
+            for (line_no, logged) in ops {
+                let (uuids, stmt) = split_generated_uuids(&logged)
                     .map_err(|e| format!("WAL parse error at line {}: {}", line_no, e))?;
+                let cmd = parser::parser::parse(stmt)
+                    .map_err(|e| format!("WAL parse error at line {}: {}", line_no, e))?;
+                self.uuid_gen.replay(uuids);
                 if matches!(
                     cmd,
                     Command::Create { .. } | Command::Begin | Command::Commit | Command::Rollback
                 ) {
                     continue;
                 }
-                if let Err(_e) = engine::execute_command(cmd, &mut self.catalog, &mut self.storage)
-                {
+                if let Err(_e) = engine::execute_command(
+                    cmd,
+                    &mut self.catalog,
+                    &mut self.storage,
+                    &mut self.uuid_gen,
+                    None,
+                    // Committed groups were validated as a whole at commit.
+                    true,
+                ) {
                     invalid_tx = true;
                     break;
                 }
             }
+            self.uuid_gen.replay(Vec::new());
+            self.uuid_gen.take_issued();
 
             if invalid_tx
                 || engine::validate_no_action_constraints(&self.catalog, &self.storage).is_err()
@@ -218,10 +471,11 @@ impl Database {
             }
         }
 
-        if committed_tx_count > 0 || ignored_truncated_tail {
+        if report && (committed_tx_count > 0 || ignored_truncated_tail || corrupt_record_count > 0)
+        {
             eprintln!(
-                "skepa-db: recovery replayed {} committed transaction(s), skipped {}, truncated_tail_ignored={}",
-                replayed_tx_count, skipped_tx_count, ignored_truncated_tail
+                "skepa-db: recovery replayed {} committed transaction(s), skipped {}, corrupt_records={}, truncated_tail_ignored={}",
+                replayed_tx_count, skipped_tx_count, corrupt_record_count, ignored_truncated_tail
             );
         }
 
@@ -229,16 +483,133 @@ impl Database {
     }
 
     pub(super) fn truncate_wal(&self) -> Result<(), String> {
+        // A held handle must keep pointing at the live file, so truncate in
+        // place rather than replacing it.
+        if let Some(f) = &self.wal_file {
+            f.set_len(0)
+                .map_err(|e| format!("Failed to truncate WAL: {e}"))?;
+            return f
+                .sync_data()
+                .map_err(|e| format!("Failed to truncate WAL: {e}"));
+        }
         let wal_path = self.path.join("wal.log");
         crate::storage::persistence::write_file_atomic(&wal_path, b"")
             .map_err(|e| format!("Failed to truncate WAL: {e}"))
     }
 
-    pub(super) fn checkpoint_and_truncate_wal(&self) -> Result<(), String> {
+    /// Checkpoints after a WAL group commits, when `auto_checkpoint` calls
+    /// for it.
+    pub(super) fn checkpoint_after_commit(&mut self) -> Result<(), String> {
+        let due = match self.auto_checkpoint {
+            AutoCheckpoint::EveryCommit => true,
+            AutoCheckpoint::WalBytes(max) => self.wal_bytes()? >= max,
+            AutoCheckpoint::Manual => false,
+        };
+        if due {
+            self.checkpoint_and_truncate_wal()?;
+        }
+        Ok(())
+    }
+
+    /// The `checkpoint` statement: checkpoints now, whatever
+    /// `auto_checkpoint` says, and reports what it wrote and truncated.
+    pub(super) fn checkpoint_statement(&mut self) -> Result<String, String> {
+        if self.current_tx.is_some() {
+            return Err("CHECKPOINT cannot run inside an active transaction".to_string());
+        }
+        let tables = self.dirty_tables.len();
+        let bytes = self.wal_bytes()?;
+        self.checkpoint_and_truncate_wal()?;
+        Ok(format!(
+            "checkpointed {tables} table(s), truncated {} of WAL",
+            format_bytes(bytes)
+        ))
+    }
+
+    /// Size of the WAL and how many committed groups in it still wait for
+    /// a checkpoint. Backs the `show wal` statement.
+    pub fn wal_info(&self) -> DbResult<WalInfo> {
+        let wal_path = self.path.join("wal.log");
+        let content = match fs::read_to_string(&wal_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(DbError::from(format!("Failed to read WAL: {e}"))),
+        };
+        Ok(WalInfo {
+            bytes: content.len() as u64,
+            pending_transactions: content
+                .lines()
+                .filter(|line| line.trim().starts_with("COMMIT "))
+                .count(),
+        })
+    }
+
+    /// `show wal`: one row of [`Database::wal_info`].
+    pub(super) fn show_wal(&self) -> DbResult<QueryResult> {
+        let info = self.wal_info()?;
+        let column = |name: &str| storage::Column {
+            name: name.to_string(),
+            dtype: types::datatype::DataType::BigInt,
+            primary_key: false,
+            unique: false,
+            not_null: true,
+            default: None,
+            json_shape: None,
+        };
+        let schema = Schema::new(vec![column("bytes"), column("pending_transactions")]);
+        let row = vec![
+            types::value::Value::BigInt(i128::from(info.bytes)),
+            types::value::Value::BigInt(info.pending_transactions as i128),
+        ];
+        Ok(QueryResult::select(schema, vec![row]))
+    }
+
+    fn wal_bytes(&self) -> Result<u64, String> {
+        match fs::metadata(self.path.join("wal.log")) {
+            Ok(meta) => Ok(meta.len()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(format!("Failed to read WAL size: {e}")),
+        }
+    }
+
+    /// Rewrites the tables written since the last checkpoint, then
+    /// truncates the WAL. A failed write leaves them marked for next time.
+    /// Every group in the WAL is this handle's: with deferred checkpoints
+    /// it holds the directory alone, and otherwise each handle checkpoints
+    /// its own group as soon as it is written.
+    pub(super) fn checkpoint_and_truncate_wal(&mut self) -> Result<(), String> {
+        let tables: Vec<&str> = self.dirty_tables.iter().map(String::as_str).collect();
+        self.storage.checkpoint_tables(&tables)?;
+        self.dirty_tables.clear();
+        self.finish_checkpoint()
+    }
+
+    /// Rewrites every table, for recovery and explicit checkpoints.
+    pub(super) fn checkpoint_all_and_truncate_wal(&self) -> Result<(), String> {
         self.storage.checkpoint_all()?;
+        self.finish_checkpoint()
+    }
+
+    fn finish_checkpoint(&self) -> Result<(), String> {
         if crate::storage_test_hooks::should_interrupt_checkpoint_after_tables(&self.path) {
             return Err("Simulated checkpoint interruption after table persistence".to_string());
         }
         self.truncate_wal()
     }
 }
+
+/// `n` bytes for messages: exact below 1 KiB, else one decimal place in the
+/// largest binary unit that keeps the number at least 1.
+fn format_bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if n < 1024 {
+        return format!("{n} B");
+    }
+    let mut value = n as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// What `Database::open` repaired while loading the database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    /// Indexes whose saved data disagreed with the catalog, in table order.
+    pub index_repairs: Vec<IndexRepair>,
+}
+
+/// One index brought back in line with the catalog on open.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexRepair {
+    pub table: String,
+    /// The index as `primary key (id)`, `unique (email)`, `index (a, b)`
+    /// or `trigram index (body)`.
+    pub index: String,
+    pub action: IndexRepairAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexRepairAction {
+    /// Defined in the catalog but missing or invalid in the index file:
+    /// rebuilt from the table's rows and saved.
+    Rebuilt,
+    /// Saved in the index file but not defined in the catalog: removed.
+    Dropped,
+}
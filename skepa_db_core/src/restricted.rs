@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Database;
+use crate::error::{DbError, DbResult};
+use crate::parser::command::{Command, CommentTarget, WhereClause};
+use crate::query_result::QueryResult;
+
+/// Statement category a [`RestrictedDatabase`] can allow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementKind {
+    Begin,
+    Commit,
+    Rollback,
+    ShowTransaction,
+    Set,
+    Show,
+    CreateTable,
+    CreateIndex,
+    DropIndex,
+    Alter,
+    Insert,
+    Update,
+    Delete,
+    Describe,
+    ShowIndexes,
+    Comment,
+    Select,
+    CreateSnapshot,
+    DropSnapshot,
+    ListSnapshots,
+    Attach,
+    Detach,
+    CreateTrigger,
+    DropTrigger,
+    CreateView,
+    DropView,
+    RefreshMaterializedView,
+    ShowTables,
+    Checkpoint,
+    ShowWal,
+}
+
+impl StatementKind {
+    pub fn of(cmd: &Command) -> Self {
+        match cmd {
+            Command::Begin => Self::Begin,
+            Command::Commit => Self::Commit,
+            Command::Rollback => Self::Rollback,
+            Command::ShowTransaction => Self::ShowTransaction,
+            Command::Set { .. } => Self::Set,
+            Command::Show { .. } => Self::Show,
+            Command::Create { .. } | Command::CreateTableAs { .. } => Self::CreateTable,
+            Command::CreateIndex { .. } => Self::CreateIndex,
+            Command::DropIndex { .. } => Self::DropIndex,
+            Command::Alter { .. } => Self::Alter,
+            Command::Insert { .. } | Command::InsertSelect { .. } => Self::Insert,
+            Command::Update { .. } => Self::Update,
+            Command::Delete { .. } => Self::Delete,
+            Command::Describe { .. } => Self::Describe,
+            Command::ShowIndexes { .. } => Self::ShowIndexes,
+            Command::Comment { .. } => Self::Comment,
+            Command::Select { .. } | Command::SetOp { .. } => Self::Select,
+            Command::CreateSnapshot { .. } => Self::CreateSnapshot,
+            Command::DropSnapshot { .. } => Self::DropSnapshot,
+            Command::ListSnapshots => Self::ListSnapshots,
+            Command::Attach { .. } => Self::Attach,
+            Command::Detach { .. } => Self::Detach,
+            Command::CreateTrigger { .. } => Self::CreateTrigger,
+            Command::DropTrigger { .. } => Self::DropTrigger,
+            Command::CreateView { .. } => Self::CreateView,
+            Command::DropView { .. } => Self::DropView,
+            Command::RefreshMaterializedView { .. } => Self::RefreshMaterializedView,
+            Command::ShowTables { .. } => Self::ShowTables,
+            Command::Checkpoint => Self::Checkpoint,
+            Command::ShowWal => Self::ShowWal,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Begin => "begin",
+            Self::Commit => "commit",
+            Self::Rollback => "rollback",
+            Self::ShowTransaction => "show transaction",
+            Self::Set => "set",
+            Self::Show => "show",
+            Self::CreateTable => "create table",
+            Self::CreateIndex => "create index",
+            Self::DropIndex => "drop index",
+            Self::Alter => "alter table",
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Describe => "describe",
+            Self::ShowIndexes => "show indexes",
+            Self::Comment => "comment",
+            Self::Select => "select",
+            Self::CreateSnapshot => "create snapshot",
+            Self::DropSnapshot => "drop snapshot",
+            Self::ListSnapshots => "list snapshots",
+            Self::Attach => "attach",
+            Self::Detach => "detach",
+            Self::CreateTrigger => "create trigger",
+            Self::DropTrigger => "drop trigger",
+            Self::CreateView => "create view",
+            Self::DropView => "drop view",
+            Self::RefreshMaterializedView => "refresh materialized view",
+            Self::ShowTables => "show tables",
+            Self::Checkpoint => "checkpoint",
+            Self::ShowWal => "show wal",
+        }
+    }
+}
+
+/// Tables a statement names directly: the target table first, plus the
+/// joined table of a SELECT, the subquery tables of a SELECT, UPDATE or
+/// DELETE, the tables an INSERT ... SELECT reads and the tables of both
+/// sides of INTERSECT/EXCEPT, each listed once. Tables reached through
+/// foreign key actions are not included.
+pub(crate) fn referenced_tables(cmd: &Command) -> Vec<&str> {
+    match cmd {
+        Command::Begin
+        | Command::Commit
+        | Command::Rollback
+        | Command::ShowTransaction
+        | Command::Set { .. }
+        | Command::Show { .. }
+        | Command::CreateSnapshot { .. }
+        | Command::DropSnapshot { .. }
+        | Command::ListSnapshots
+        | Command::Attach { .. }
+        | Command::Detach { .. }
+        | Command::DropTrigger { .. }
+        | Command::ShowTables { .. }
+        | Command::Checkpoint
+        | Command::ShowWal => Vec::new(),
+        Command::CreateView { name, .. }
+        | Command::DropView { name }
+        | Command::RefreshMaterializedView { name } => vec![name],
+        Command::CreateTrigger { table, target, .. } => vec![table, target],
+        Command::Create { table, .. }
+        | Command::CreateIndex { table, .. }
+        | Command::DropIndex { table, .. }
+        | Command::Alter { table, .. }
+        | Command::Insert { table, .. }
+        | Command::Describe { table }
+        | Command::ShowIndexes { table } => vec![table],
+        Command::Comment { target, .. } => match target {
+            CommentTarget::Table(table) | CommentTarget::Column { table, .. } => vec![table],
+        },
+        Command::InsertSelect { table, query, .. } | Command::CreateTableAs { table, query } => {
+            let mut tables = vec![table.as_str()];
+            for source in referenced_tables(query) {
+                if !tables.contains(&source) {
+                    tables.push(source);
+                }
+            }
+            tables
+        }
+        Command::Update { table, filter, .. } | Command::Delete { table, filter, .. } => {
+            let mut tables = vec![table.as_str()];
+            for sub in subquery_tables(filter) {
+                if !tables.contains(&sub) {
+                    tables.push(sub);
+                }
+            }
+            tables
+        }
+        Command::Select {
+            table,
+            join,
+            filter,
+            ..
+        } => {
+            let mut tables = vec![table.as_str()];
+            if let Some(join) = join {
+                tables.push(&join.table);
+            }
+            for sub in filter.iter().flat_map(subquery_tables) {
+                if !tables.contains(&sub) {
+                    tables.push(sub);
+                }
+            }
+            tables
+        }
+        Command::SetOp { left, right, .. } => {
+            let mut tables = referenced_tables(left);
+            for table in referenced_tables(right) {
+                if !tables.contains(&table) {
+                    tables.push(table);
+                }
+            }
+            tables
+        }
+    }
+}
+
+/// A [`Database`] handle that only runs allow-listed statement kinds,
+/// optionally limited to a set of tables. Statements are checked after
+/// parsing and before the catalog or storage is consulted.
+#[derive(Debug)]
+pub struct RestrictedDatabase<'a> {
+    db: &'a mut Database,
+    allow: Vec<StatementKind>,
+    allowed_tables: Option<Vec<String>>,
+}
+
+impl<'a> RestrictedDatabase<'a> {
+    /// Limits statements to the named tables. Both sides of a join must be
+    /// listed. Names are matched exactly.
+    pub fn with_allowed_tables<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_tables = Some(tables.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn execute(&mut self, input: &str) -> DbResult<QueryResult> {
+        let cmd = crate::parser::parser::parse(input).map_err(DbError::from)?;
+        self.check_kind(&cmd)?;
+        // A named `drop index` is checked against the table it belongs to.
+        let cmd =
+            crate::engine::resolve_index_name(cmd, &self.db.catalog).map_err(DbError::from)?;
+        self.check_tables(&cmd)?;
+        self.db.execute_parsed(input, cmd)
+    }
+
+    fn check_kind(&self, cmd: &Command) -> DbResult<()> {
+        let kind = StatementKind::of(cmd);
+        if !self.allow.contains(&kind) {
+            return Err(DbError::from(format!(
+                "Statement '{}' is not permitted on this restricted handle",
+                kind.as_str()
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_tables(&self, cmd: &Command) -> DbResult<()> {
+        if let Some(allowed) = &self.allowed_tables
+            && let Some(table) = referenced_tables(cmd)
+                .into_iter()
+                .find(|t| !allowed.iter().any(|a| a == t))
+        {
+            return Err(DbError::from(format!(
+                "Table '{table}' is not permitted on this restricted handle"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Database {
+    /// Borrows this handle as a [`RestrictedDatabase`] that only runs the
+    /// `allow`ed statement kinds.
+    pub fn restricted(&mut self, allow: &[StatementKind]) -> RestrictedDatabase<'_> {
+        RestrictedDatabase {
+            db: self,
+            allow: allow.to_vec(),
+            allowed_tables: None,
+        }
+    }
+}
+
+/// Tables of the subqueries in `clause`, in order of appearance.
+pub(crate) fn subquery_tables(clause: &WhereClause) -> Vec<&str> {
+    match clause {
+        WhereClause::Predicate(_) => Vec::new(),
+        WhereClause::Binary { left, right, .. } => {
+            let mut tables = subquery_tables(left);
+            tables.extend(subquery_tables(right));
+            tables
+        }
+        WhereClause::Exists { subquery, .. } => vec![subquery.table.as_str()],
+    }
+}
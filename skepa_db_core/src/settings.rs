@@ -0,0 +1,125 @@
+use std::fmt;
+
+/// Setting names accepted by `set` and `show`, in the order error messages
+/// list them.
+pub const KNOWN_SETTINGS: [&str; 4] = [
+    "output_format",
+    "defer_constraints",
+    "max_rows",
+    "strict_types",
+];
+
+/// Layout used when a result is rendered as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Tab-separated header and rows.
+    Tabs,
+    /// One JSON object per result.
+    Json,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tabs => "tabs",
+            Self::Json => "json",
+        })
+    }
+}
+
+/// Per-handle toggles changed with `set <name> = <value>` and read back with
+/// `show <name>`. They are not persisted: every new [`crate::Database`]
+/// starts from [`SessionSettings::default`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSettings {
+    pub output_format: OutputFormat,
+    /// Inside a transaction, check foreign keys of inserted and updated
+    /// rows at commit instead of per statement.
+    pub defer_constraints: bool,
+    /// Most rows a SELECT returns; extra rows are dropped. `None` is unlimited.
+    pub max_rows: Option<usize>,
+    /// A SELECT's WHERE literal that is not a value of its column's type
+    /// is an error. When off, the comparison matches no row instead.
+    pub strict_types: bool,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            output_format: OutputFormat::Tabs,
+            defer_constraints: false,
+            max_rows: None,
+            strict_types: true,
+        }
+    }
+}
+
+impl SessionSettings {
+    /// Validates `value` for the setting `name` and applies it. Names and
+    /// keyword values are case-insensitive.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let lowered = value.to_ascii_lowercase();
+        match canonical_name(name)? {
+            "output_format" => {
+                self.output_format = match lowered.as_str() {
+                    "tabs" => OutputFormat::Tabs,
+                    "json" => OutputFormat::Json,
+                    _ => return Err(bad_value("output_format", value, "tabs or json")),
+                }
+            }
+            "defer_constraints" => {
+                self.defer_constraints = match lowered.as_str() {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(bad_value("defer_constraints", value, "on or off")),
+                }
+            }
+            "strict_types" => {
+                self.strict_types = match lowered.as_str() {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(bad_value("strict_types", value, "on or off")),
+                }
+            }
+            _ => {
+                self.max_rows = if lowered == "none" {
+                    None
+                } else {
+                    Some(value.parse::<usize>().map_err(|_| {
+                        bad_value("max_rows", value, "a non-negative integer or none")
+                    })?)
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Current value of the setting `name`, spelled the way `set` accepts it.
+    pub fn get(&self, name: &str) -> Result<String, String> {
+        Ok(match canonical_name(name)? {
+            "output_format" => self.output_format.to_string(),
+            "defer_constraints" => if self.defer_constraints { "on" } else { "off" }.to_string(),
+            "strict_types" => if self.strict_types { "on" } else { "off" }.to_string(),
+            _ => self
+                .max_rows
+                .map_or_else(|| "none".to_string(), |n| n.to_string()),
+        })
+    }
+}
+
+fn canonical_name(name: &str) -> Result<&'static str, String> {
+    KNOWN_SETTINGS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| {
+            format!(
+                "Unknown setting '{name}'; known settings: {}",
+                KNOWN_SETTINGS.join(", ")
+            )
+        })
+}
+
+fn bad_value(name: &str, value: &str, expected: &str) -> String {
+    format!("Invalid value '{value}' for setting '{name}'; expected {expected}")
+}
@@ -0,0 +1,131 @@
+use super::*;
+
+impl Database {
+    /// Copies the checkpointed catalog, table and index files into
+    /// `snapshots/<name>/`, where `select ... from <table> as of <name>` can
+    /// read them until the snapshot is dropped. Later writes, checkpoints
+    /// and WAL truncation never touch the copy.
+    pub fn create_snapshot(&mut self, name: &str) -> DbResult<()> {
+        self.with_fresh_view(true, |db| db.take_snapshot(name))
+    }
+
+    /// [`Database::create_snapshot`] for a caller that already holds the
+    /// write lock on a fresh view, such as the `create snapshot` statement.
+    pub(super) fn take_snapshot(&mut self, name: &str) -> DbResult<()> {
+        if self.current_tx.is_some() {
+            return Err(DbError::from(
+                "Cannot create a snapshot inside an active transaction",
+            ));
+        }
+        let dir = snapshot_dir(&self.path, name).map_err(DbError::from)?;
+        if dir.exists() {
+            return Err(DbError::from(format!("Snapshot '{name}' already exists")));
+        }
+        self.checkpoint_all_and_truncate_wal()
+            .map_err(DbError::from)?;
+        copy_snapshot_files(&self.path, &dir)
+            .map_err(|e| DbError::from(format!("Failed to create snapshot '{name}': {e}")))
+    }
+
+    /// Deletes a snapshot made by [`Database::create_snapshot`].
+    pub fn drop_snapshot(&mut self, name: &str) -> DbResult<()> {
+        let dir = existing_snapshot_dir(&self.path, name).map_err(DbError::from)?;
+        fs::remove_dir_all(&dir)
+            .map_err(|e| DbError::from(format!("Failed to drop snapshot '{name}': {e}")))
+    }
+
+    /// Names of the retained snapshots, sorted.
+    pub fn list_snapshots(&self) -> DbResult<Vec<String>> {
+        let entries = match fs::read_dir(self.path.join("snapshots")) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(DbError::from(format!("Failed to list snapshots: {e}"))),
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| e.file_name().into_string().ok())
+            // Half-written copies are staged under a leading '.'.
+            .filter(|n| !n.starts_with('.'))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// The catalog stored in snapshot `name`, with the snapshot's directory.
+    pub(super) fn snapshot_catalog(&self, name: &str) -> Result<(PathBuf, Catalog), String> {
+        let dir = existing_snapshot_dir(&self.path, name)?;
+        let catalog = Self::load_catalog(&dir).map_err(|e| e.to_string())?;
+        Ok((dir, catalog))
+    }
+
+    /// Runs a `select ... as of <snapshot>` against the snapshot's catalog
+    /// and only the tables the query names, loaded read-only from its files.
+    pub(super) fn select_as_of(&self, mut cmd: Command) -> Result<QueryResult, String> {
+        let Command::Select {
+            for_update, as_of, ..
+        } = &mut cmd
+        else {
+            return Err("AS OF applies only to SELECT".to_string());
+        };
+        if *for_update {
+            return Err("FOR UPDATE cannot be combined with AS OF".to_string());
+        }
+        let name = as_of.take().unwrap_or_default();
+        let (dir, catalog) = self.snapshot_catalog(&name)?;
+        let mut storage = DiskStorage::new(dir)?;
+        for t in restricted::referenced_tables(&cmd) {
+            storage.bootstrap_table(t, catalog.schema(t)?)?;
+        }
+        engine::execute_read(
+            self.read_command(cmd, &catalog),
+            &catalog,
+            &storage,
+            self.max_join_rows,
+        )
+    }
+}
+
+fn snapshot_dir(root: &Path, name: &str) -> Result<PathBuf, String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(format!(
+            "Invalid snapshot name '{name}': use letters, digits, '_' and '-'"
+        ));
+    }
+    Ok(root.join("snapshots").join(name))
+}
+
+fn existing_snapshot_dir(root: &Path, name: &str) -> Result<PathBuf, String> {
+    let dir = snapshot_dir(root, name)?;
+    if !dir.is_dir() {
+        return Err(format!("Snapshot '{name}' does not exist"));
+    }
+    Ok(dir)
+}
+
+/// Copies `catalog.json`, `tables/` and `indexes/` from `root` into `dest`.
+/// The copy is assembled beside `dest` and renamed into place, so a failed
+/// copy never shows up as a snapshot.
+fn copy_snapshot_files(root: &Path, dest: &Path) -> std::io::Result<()> {
+    let name = dest.file_name().unwrap_or_default().to_string_lossy();
+    let staging = dest.with_file_name(format!(".{name}.tmp"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+    fs::copy(root.join("catalog.json"), staging.join("catalog.json"))?;
+    for sub in ["tables", "indexes"] {
+        fs::create_dir_all(staging.join(sub))?;
+        for entry in fs::read_dir(root.join(sub))? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                fs::copy(entry.path(), staging.join(sub).join(entry.file_name()))?;
+            }
+        }
+    }
+    fs::rename(&staging, dest)
+}
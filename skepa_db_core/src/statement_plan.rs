@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One column of a resolved schema, with its position and datatype.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanColumn {
+    pub index: usize,
+    pub name: String,
+    pub dtype: String,
+}
+
+/// Which schema a column reference was resolved against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BindingTarget {
+    /// The table (or joined) schema rows are read from.
+    Input,
+    /// The projected or grouped schema the statement returns.
+    Output,
+}
+
+/// A name used in some clause and the column index it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnBinding {
+    pub clause: String,
+    pub name: String,
+    pub target: BindingTarget,
+    pub index: usize,
+}
+
+/// A `SELECT expr as alias` mapping.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AliasBinding {
+    pub alias: String,
+    pub expr: String,
+}
+
+/// How the executor will locate rows for a statement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessPath {
+    FullScan,
+    PrimaryKey { column: String },
+    UniqueIndex { column: String },
+    SecondaryIndex { column: String },
+    TrigramIndex { column: String },
+    PartitionPruning { column: String },
+    HashJoin { left: String, right: String },
+}
+
+/// Resolved execution info for one statement, produced without running it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatementPlan {
+    pub statement: String,
+    pub tables: Vec<String>,
+    pub access: Option<AccessPath>,
+    pub columns: Vec<PlanColumn>,
+    pub bindings: Vec<ColumnBinding>,
+    pub aliases: Vec<AliasBinding>,
+    pub output: Vec<PlanColumn>,
+}
+
+impl fmt::Display for AccessPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessPath::FullScan => write!(f, "full scan"),
+            AccessPath::PrimaryKey { column } => write!(f, "primary key lookup ({column})"),
+            AccessPath::UniqueIndex { column } => write!(f, "unique index lookup ({column})"),
+            AccessPath::SecondaryIndex { column } => {
+                write!(f, "secondary index lookup ({column})")
+            }
+            AccessPath::TrigramIndex { column } => write!(f, "trigram index scan ({column})"),
+            AccessPath::PartitionPruning { column } => write!(f, "partition pruning ({column})"),
+            AccessPath::HashJoin { left, right } => write!(f, "hash join ({left} = {right})"),
+        }
+    }
+}
+
+impl fmt::Display for StatementPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "statement: {}", self.statement)?;
+        if !self.tables.is_empty() {
+            writeln!(f, "tables: {}", self.tables.join(", "))?;
+        }
+        if let Some(access) = &self.access {
+            writeln!(f, "access: {access}")?;
+        }
+        write_columns(f, "columns", &self.columns)?;
+        if !self.bindings.is_empty() {
+            writeln!(f, "bindings:")?;
+            for b in &self.bindings {
+                let target = match b.target {
+                    BindingTarget::Input => "columns",
+                    BindingTarget::Output => "output",
+                };
+                writeln!(f, "  {} {} -> {} #{}", b.clause, b.name, target, b.index)?;
+            }
+        }
+        if !self.aliases.is_empty() {
+            writeln!(f, "aliases:")?;
+            for a in &self.aliases {
+                writeln!(f, "  {} = {}", a.alias, a.expr)?;
+            }
+        }
+        write_columns(f, "output", &self.output)
+    }
+}
+
+fn write_columns(f: &mut fmt::Formatter<'_>, label: &str, columns: &[PlanColumn]) -> fmt::Result {
+    if columns.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{label}:")?;
+    for c in columns {
+        writeln!(f, "  #{} {} {}", c.index, c.name, c.dtype)?;
+    }
+    Ok(())
+}
@@ -1,15 +1,41 @@
-use crate::parser::command::{ColumnDef, ForeignKeyAction, TableConstraintDef};
-use crate::storage::schema::{Column, ForeignKeyDef, Schema};
-use crate::types::datatype::DataType;
+use crate::parser::command::{
+    ColumnDef, ColumnPosition, Command, ForeignKeyAction, IndexKind, JsonShape, TableConstraintDef,
+};
+use crate::storage::schema::{
+    Column, ForeignKeyDef, IndexInfo, IndexSource, NamedIndex, Schema, TriggerDef,
+};
+use crate::types::datatype::{DataType, datatype_name};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::Path;
 
+/// Table names `create table` refuses by default: the names of the files
+/// the database keeps beside its tables, plus ones held back for
+/// introspection features.
+pub const DEFAULT_RESERVED_TABLE_NAMES: &[&str] =
+    &["catalog", "wal", "pragma", "information_schema", "system"];
+
 /// Manages table schemas (metadata catalog)
 #[derive(Debug, Clone)]
 pub struct Catalog {
     tables: HashMap<String, Schema>,
+    /// Table names in creation order, which `snapshot_tables` follows.
+    table_order: Vec<String>,
+    reserved_table_names: Vec<String>,
+    views: BTreeMap<String, ViewDef>,
+}
+
+/// A view created with `create view <name> as select ...`.
+#[derive(Debug, Clone)]
+pub struct ViewDef {
+    /// The SELECT as written, which is what the catalog file stores.
+    pub sql: String,
+    /// `sql` parsed.
+    pub query: Command,
+    /// Whether the rows are stored in a table of the same name, recomputed
+    /// only by `refresh materialized view`.
+    pub materialized: bool,
 }
 
 impl Default for Catalog {
@@ -22,9 +48,21 @@ impl Default for Catalog {
 struct CatalogFile {
     #[serde(default = "default_catalog_format_version")]
     format_version: u32,
-    tables: HashMap<String, Vec<ColumnFile>>,
+    tables: BTreeMap<String, Vec<ColumnFile>>,
+    /// Creation order of `tables`. Catalogs written before it existed load
+    /// their tables in name order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    table_order: Vec<String>,
     #[serde(default)]
-    table_constraints: HashMap<String, TableConstraintFile>,
+    table_constraints: BTreeMap<String, TableConstraintFile>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    comments: BTreeMap<String, CommentFile>,
+    /// View names with their SELECT text, reparsed on load.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    views: BTreeMap<String, String>,
+    /// The views in `views` whose rows are stored as a table.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    materialized_views: BTreeSet<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -39,6 +77,8 @@ struct ColumnFile {
     not_null: bool,
     #[serde(default)]
     default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json_shape: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -47,10 +87,28 @@ struct TableConstraintFile {
     primary_key: Vec<String>,
     #[serde(default)]
     unique: Vec<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    unique_nulls_not_distinct: Vec<Vec<String>>,
     #[serde(default)]
     secondary_indexes: Vec<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trigram_indexes: Vec<String>,
     #[serde(default)]
     foreign_keys: Vec<ForeignKeyFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    partition_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    index_names: Vec<NamedIndex>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    triggers: Vec<TriggerDef>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+struct CommentFile {
+    #[serde(default)]
+    table: Option<String>,
+    #[serde(default)]
+    columns: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -72,8 +130,82 @@ fn default_on_update() -> String {
     "restrict".to_string()
 }
 
+/// Catalogs written before `format_version` existed are version 1.
 fn default_catalog_format_version() -> u32 {
-    crate::STORAGE_FORMAT_VERSION
+    1
+}
+
+/// Pairs each FOREIGN KEY column with its referenced parent column and
+/// requires the same datatype at every position.
+fn check_foreign_key_types(
+    fk: &ForeignKeyDef,
+    child_columns: &[Column],
+    parent: &Schema,
+) -> Result<(), String> {
+    for (pos, (c, p)) in fk.columns.iter().zip(&fk.ref_columns).enumerate() {
+        let child_col = child_columns.iter().find(|col| &col.name == c);
+        let parent_col = parent.columns.iter().find(|col| &col.name == p);
+        if let (Some(child_col), Some(parent_col)) = (child_col, parent_col)
+            && child_col.dtype != parent_col.dtype
+        {
+            return Err(format!(
+                "FOREIGN KEY column {} type mismatch: {} is {} but {}.{} is {}",
+                pos + 1,
+                c,
+                datatype_name(&child_col.dtype),
+                fk.ref_table,
+                p,
+                datatype_name(&parent_col.dtype)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks a column's JSON shape hint and DEFAULT against its type: a shape
+/// needs a json column, and a DEFAULT must parse as the column's type (or
+/// be `gen_uuid()` on a uuid column) and fit its shape.
+fn check_column_definition(col: &Column) -> Result<(), String> {
+    if col.json_shape.is_some() && col.dtype != DataType::Json {
+        return Err(format!(
+            "JSON shape check on column '{}' requires a json column",
+            col.name
+        ));
+    }
+    let Some(default) = &col.default else {
+        return Ok(());
+    };
+    if crate::types::uuid_gen::is_gen_uuid_call(default) {
+        if col.dtype != DataType::Uuid {
+            return Err(format!(
+                "Invalid DEFAULT for column '{}': gen_uuid() requires a uuid column",
+                col.name
+            ));
+        }
+        return Ok(());
+    }
+    crate::types::value::parse_value(&col.dtype, default)
+        .and_then(|v| col.check_json_shape(&v))
+        .map(|_| ())
+        .map_err(|e| format!("Invalid DEFAULT for column '{}': {}", col.name, e))
+}
+
+/// Requires the referenced parent columns to be exactly the PRIMARY KEY or
+/// one UNIQUE constraint, single or composite, listed in any order.
+fn check_foreign_key_target(fk: &ForeignKeyDef, parent: &Schema) -> Result<(), String> {
+    let same_columns = |key: &[String]| {
+        key.len() == fk.ref_columns.len() && key.iter().all(|c| fk.ref_columns.contains(c))
+    };
+    let ref_is_pk = !parent.primary_key.is_empty() && same_columns(&parent.primary_key);
+    let ref_is_unique = parent.unique_constraints.iter().any(|u| same_columns(u));
+    if !(ref_is_pk || ref_is_unique) {
+        return Err(format!(
+            "FOREIGN KEY reference {}({}) must target PRIMARY KEY or UNIQUE columns",
+            fk.ref_table,
+            fk.ref_columns.join(",")
+        ));
+    }
+    Ok(())
 }
 
 include!("catalog/core.inc.rs");
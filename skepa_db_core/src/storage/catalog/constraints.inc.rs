@@ -1,5 +1,10 @@
 impl Catalog {
-    pub fn add_unique_constraint(&mut self, table: &str, mut cols: Vec<String>) -> Result<(), String> {
+    pub fn add_unique_constraint(
+        &mut self,
+        table: &str,
+        mut cols: Vec<String>,
+        nulls_not_distinct: bool,
+    ) -> Result<(), String> {
         let schema = self
             .tables
             .get_mut(table)
@@ -19,6 +24,9 @@ impl Catalog {
             ));
         }
         cols.shrink_to_fit();
+        if nulls_not_distinct {
+            schema.unique_nulls_not_distinct.push(cols.clone());
+        }
         schema.unique_constraints.push(cols);
         Ok(())
     }
@@ -37,7 +45,7 @@ impl Catalog {
             }
         }
         if schema.secondary_indexes.iter().any(|x| x == &cols) {
-            return Err(format!("INDEX on ({}) already exists", cols.join(",")));
+            return Err(format!("index on {}({}) already exists", table, cols.join(",")));
         }
         schema.secondary_indexes.push(cols);
         Ok(())
@@ -50,12 +58,145 @@ impl Catalog {
             .ok_or_else(|| format!("Table '{}' does not exist", table))?;
         let before = schema.secondary_indexes.len();
         schema.secondary_indexes.retain(|x| x != cols);
+        schema
+            .index_names
+            .retain(|n| !(n.kind == IndexKind::Standard && n.columns == cols));
         if before == schema.secondary_indexes.len() {
-            return Err(format!("INDEX on ({}) does not exist", cols.join(",")));
+            if let Some(implicit) = schema
+                .indexes()
+                .into_iter()
+                .find(|i| i.columns == cols && i.source != IndexSource::Trigram)
+            {
+                return Err(format!(
+                    "index on {}({}) backs the {} constraint; drop the constraint instead",
+                    table,
+                    cols.join(","),
+                    implicit.source.as_str().to_uppercase()
+                ));
+            }
+            return Err(format!("index on {}({}) does not exist", table, cols.join(",")));
+        }
+        Ok(())
+    }
+
+    pub fn add_trigram_index(&mut self, table: &str, cols: Vec<String>) -> Result<(), String> {
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        let [col] = cols.as_slice() else {
+            return Err("TRIGRAM index takes exactly one column".to_string());
+        };
+        let column = schema
+            .columns
+            .iter()
+            .find(|c| c.name == *col)
+            .ok_or_else(|| format!("INDEX references unknown column '{}'", col))?;
+        if !matches!(column.dtype, DataType::Text | DataType::VarChar(_)) {
+            return Err(format!(
+                "TRIGRAM index requires a text column; '{}' is {}",
+                col,
+                datatype_name(&column.dtype)
+            ));
+        }
+        if schema.trigram_indexes.contains(col) {
+            return Err(format!("index on {}({}) using trigram already exists", table, col));
+        }
+        schema.trigram_indexes.push(col.clone());
+        Ok(())
+    }
+
+    pub fn drop_trigram_index(&mut self, table: &str, cols: &[String]) -> Result<(), String> {
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        let before = schema.trigram_indexes.len();
+        schema.trigram_indexes.retain(|c| cols != std::slice::from_ref(c));
+        schema
+            .index_names
+            .retain(|n| !(n.kind == IndexKind::Trigram && n.columns == cols));
+        if before == schema.trigram_indexes.len() {
+            return Err(format!(
+                "index on {}({}) using trigram does not exist",
+                table,
+                cols.join(",")
+            ));
+        }
+        Ok(())
+    }
+
+    /// The table and definition of the index called `name`. Index names
+    /// are unique across the database.
+    pub fn named_index(&self, name: &str) -> Option<(&str, &NamedIndex)> {
+        self.table_order.iter().find_map(|table| {
+            self.tables[table]
+                .index_names
+                .iter()
+                .find(|n| n.name == name)
+                .map(|n| (table.as_str(), n))
+        })
+    }
+
+    /// Gives the existing `kind` index on `table(cols)` the name `name`.
+    pub fn name_index(
+        &mut self,
+        table: &str,
+        name: &str,
+        kind: IndexKind,
+        cols: Vec<String>,
+    ) -> Result<(), String> {
+        if self.named_index(name).is_some() {
+            return Err(format!("index {} already exists", name));
+        }
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        schema.index_names.push(NamedIndex {
+            name: name.to_string(),
+            kind,
+            columns: cols,
+        });
+        Ok(())
+    }
+
+    /// The table and definition of the trigger called `name`. Trigger
+    /// names are unique across the database.
+    pub fn trigger(&self, name: &str) -> Option<(&str, &TriggerDef)> {
+        self.table_order.iter().find_map(|table| {
+            self.tables[table]
+                .triggers
+                .iter()
+                .find(|t| t.name == name)
+                .map(|t| (table.as_str(), t))
+        })
+    }
+
+    pub fn create_trigger(&mut self, table: &str, trigger: TriggerDef) -> Result<(), String> {
+        if self.trigger(&trigger.name).is_some() {
+            return Err(format!("trigger {} already exists", trigger.name));
         }
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        schema.triggers.push(trigger);
         Ok(())
     }
 
+    /// Removes the trigger called `name`, returning the table it was on.
+    pub fn drop_trigger(&mut self, name: &str) -> Result<String, String> {
+        let table = self
+            .trigger(name)
+            .map(|(table, _)| table.to_string())
+            .ok_or_else(|| format!("trigger {} does not exist", name))?;
+        if let Some(schema) = self.tables.get_mut(&table) {
+            schema.triggers.retain(|t| t.name != name);
+        }
+        Ok(table)
+    }
+
     pub fn drop_unique_constraint(&mut self, table: &str, cols: &[String]) -> Result<(), String> {
         let schema = self
             .tables
@@ -69,6 +210,7 @@ impl Catalog {
                 cols.join(",")
             ));
         }
+        schema.unique_nulls_not_distinct.retain(|u| u != cols);
         Ok(())
     }
 
@@ -122,18 +264,8 @@ impl Catalog {
                 ));
             }
         }
-        let ref_is_pk = parent.primary_key == fk.ref_columns;
-        let ref_is_unique = parent
-            .unique_constraints
-            .iter()
-            .any(|u| u == &fk.ref_columns);
-        if !(ref_is_pk || ref_is_unique) {
-            return Err(format!(
-                "FOREIGN KEY reference {}({}) must target PRIMARY KEY or UNIQUE columns",
-                fk.ref_table,
-                fk.ref_columns.join(",")
-            ));
-        }
+        check_foreign_key_types(&fk, &child_schema.columns, parent)?;
+        check_foreign_key_target(&fk, parent)?;
 
         let child_schema = self
             .tables
@@ -188,11 +320,98 @@ impl Catalog {
         Ok(())
     }
 
-    /// Returns cloned table names and schemas for bootstrapping storage.
+    /// Adds `def` to `table` at `position` and returns its column index.
+    /// Existing rows are not touched; the caller rewrites them.
+    pub fn add_column(
+        &mut self,
+        table: &str,
+        def: ColumnDef,
+        position: &ColumnPosition,
+    ) -> Result<usize, String> {
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        if def.name.contains('.') {
+            return Err(format!("Column name '{}' cannot contain '.'", def.name));
+        }
+        if schema.columns.iter().any(|c| c.name == def.name) {
+            return Err(format!(
+                "Column '{}' already exists in table '{}'",
+                def.name, table
+            ));
+        }
+        if def.primary_key {
+            return Err("ALTER TABLE ADD COLUMN cannot add a PRIMARY KEY column".to_string());
+        }
+        let idx = match position {
+            ColumnPosition::Last => schema.columns.len(),
+            ColumnPosition::First => 0,
+            ColumnPosition::After(col) => {
+                schema
+                    .columns
+                    .iter()
+                    .position(|c| &c.name == col)
+                    .ok_or_else(|| format!("Unknown column '{}'", col))?
+                    + 1
+            }
+        };
+        let column = Column {
+            name: def.name,
+            dtype: def.dtype,
+            primary_key: false,
+            unique: def.unique,
+            not_null: def.not_null,
+            default: def.default,
+            json_shape: def.json_shape,
+        };
+        check_column_definition(&column)?;
+        if column.unique {
+            schema.unique_constraints.push(vec![column.name.clone()]);
+        }
+        schema.columns.insert(idx, column);
+        Ok(idx)
+    }
+
+    pub fn set_table_comment(&mut self, table: &str, text: Option<String>) -> Result<(), String> {
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        schema.comment = text;
+        Ok(())
+    }
+
+    pub fn set_column_comment(
+        &mut self,
+        table: &str,
+        column: &str,
+        text: Option<String>,
+    ) -> Result<(), String> {
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        if !schema.columns.iter().any(|c| c.name == column) {
+            return Err(format!("Unknown column '{}'", column));
+        }
+        match text {
+            Some(text) => {
+                schema.column_comments.insert(column.to_string(), text);
+            }
+            None => {
+                schema.column_comments.remove(column);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns cloned table names and schemas for bootstrapping storage,
+    /// in the order the tables were created.
     pub fn snapshot_tables(&self) -> Vec<(String, Schema)> {
-        self.tables
+        self.table_order
             .iter()
-            .map(|(name, schema)| (name.clone(), schema.clone()))
+            .map(|name| (name.clone(), self.tables[name].clone()))
             .collect()
     }
 
@@ -2,9 +2,27 @@ impl Catalog {
     pub fn new() -> Self {
         Self {
             tables: HashMap::new(),
+            table_order: Vec::new(),
+            reserved_table_names: DEFAULT_RESERVED_TABLE_NAMES
+                .iter()
+                .map(|n| n.to_string())
+                .collect(),
+            views: BTreeMap::new(),
         }
     }
 
+    /// Replaces the names `create_table` rejects. Matching ignores ASCII case.
+    /// Tables that already exist under a newly reserved name are kept.
+    pub fn set_reserved_table_names(&mut self, names: Vec<String>) {
+        self.reserved_table_names = names;
+    }
+
+    pub fn is_reserved_table_name(&self, table: &str) -> bool {
+        self.reserved_table_names
+            .iter()
+            .any(|n| n.eq_ignore_ascii_case(table))
+    }
+
     /// Checks if a table exists in the catalog
     pub fn exists(&self, table: &str) -> bool {
         self.tables.contains_key(table)
@@ -21,9 +39,24 @@ impl Catalog {
         if self.exists(&table) {
             return Err(format!("Table '{}' already exists", table));
         }
+        if self.views.contains_key(&table) {
+            return Err(format!("View '{}' already exists", table));
+        }
+        if self.is_reserved_table_name(&table) {
+            return Err(format!("'{table}' is a reserved table name"));
+        }
+        // `table.col` is how joins qualify names, so a stored dot would make
+        // a plain name indistinguishable from a qualified one.
+        if table.contains('.') {
+            return Err(format!("Table name '{table}' cannot contain '.'"));
+        }
+        if let Some(c) = cols.iter().find(|c| c.name.contains('.')) {
+            return Err(format!("Column name '{}' cannot contain '.'", c.name));
+        }
 
         let mut primary_key: Vec<String> = Vec::new();
         let mut unique_constraints: Vec<Vec<String>> = Vec::new();
+        let mut unique_nulls_not_distinct: Vec<Vec<String>> = Vec::new();
         let mut foreign_keys: Vec<ForeignKeyDef> = Vec::new();
 
         let columns: Vec<Column> = cols
@@ -35,15 +68,12 @@ impl Catalog {
                 unique: c.unique,
                 not_null: c.not_null,
                 default: c.default.clone(),
+                json_shape: c.json_shape,
             })
             .collect();
 
         for col in &columns {
-            if let Some(default) = &col.default {
-                crate::types::value::parse_value(&col.dtype, default).map_err(|e| {
-                    format!("Invalid DEFAULT for column '{}': {}", col.name, e)
-                })?;
-            }
+            check_column_definition(col)?;
         }
 
         for c in &columns {
@@ -67,8 +97,18 @@ impl Catalog {
                     }
                     primary_key = cols;
                 }
-                TableConstraintDef::Unique(cols) => {
-                    unique_constraints.push(cols);
+                TableConstraintDef::Unique {
+                    columns: cols,
+                    nulls_not_distinct,
+                } => {
+                    if nulls_not_distinct && !unique_nulls_not_distinct.contains(&cols) {
+                        unique_nulls_not_distinct.push(cols.clone());
+                    }
+                    // A column-level `unique nulls not distinct` arrives both
+                    // as the column flag and as this constraint.
+                    if !unique_constraints.contains(&cols) {
+                        unique_constraints.push(cols);
+                    }
                 }
                 TableConstraintDef::ForeignKey {
                     columns,
@@ -151,18 +191,8 @@ impl Catalog {
                 }
             }
 
-            let ref_is_pk = parent.primary_key == fk.ref_columns;
-            let ref_is_unique = parent
-                .unique_constraints
-                .iter()
-                .any(|u| u == &fk.ref_columns);
-            if !(ref_is_pk || ref_is_unique) {
-                return Err(format!(
-                    "FOREIGN KEY reference {}({}) must target PRIMARY KEY or UNIQUE columns",
-                    fk.ref_table,
-                    fk.ref_columns.join(",")
-                ));
-            }
+            check_foreign_key_types(fk, &columns, parent)?;
+            check_foreign_key_target(fk, parent)?;
         }
 
         let mut schema = Schema::with_constraints(
@@ -171,16 +201,112 @@ impl Catalog {
             unique_constraints.clone(),
             foreign_keys.clone(),
         );
+        schema.unique_nulls_not_distinct = unique_nulls_not_distinct;
         // PK implies NOT NULL on referenced columns.
         for c in &mut schema.columns {
             if primary_key.iter().any(|pk| pk == &c.name) {
                 c.not_null = true;
             }
         }
+        self.table_order.push(table.clone());
+        self.tables.insert(table, schema);
+        Ok(())
+    }
+
+    /// Marks `column` as the column `table` is partitioned by.
+    pub fn set_partition_by(&mut self, table: &str, column: &str) -> Result<(), String> {
+        let schema = self
+            .tables
+            .get_mut(table)
+            .ok_or_else(|| format!("Table '{}' does not exist", table))?;
+        if !schema.columns.iter().any(|c| c.name == column) {
+            return Err(format!("PARTITION BY references unknown column '{column}'"));
+        }
+        schema.partition_by = Some(column.to_string());
+        Ok(())
+    }
+
+    /// Registers `schema` under `table` without the checks `create_table`
+    /// makes. Used for the `<alias>.<table>` names of attached databases,
+    /// which only ever appear in a per-statement catalog.
+    pub(crate) fn insert_schema(&mut self, table: String, schema: Schema) {
+        if !self.tables.contains_key(&table) {
+            self.table_order.push(table.clone());
+        }
         self.tables.insert(table, schema);
+    }
+
+    /// Registers view `name`. Views share the namespace of tables; a
+    /// materialized view also registers `schema` as the table holding its
+    /// rows.
+    pub fn create_view(
+        &mut self,
+        name: String,
+        view: ViewDef,
+        schema: Option<Schema>,
+    ) -> Result<(), String> {
+        if self.exists(&name) {
+            return Err(format!("Table '{}' already exists", name));
+        }
+        if self.views.contains_key(&name) {
+            return Err(format!("View '{}' already exists", name));
+        }
+        if self.is_reserved_table_name(&name) {
+            return Err(format!("'{name}' is a reserved table name"));
+        }
+        if name.contains('.') {
+            return Err(format!("View name '{name}' cannot contain '.'"));
+        }
+        if let Some(schema) = schema {
+            self.insert_schema(name.clone(), schema);
+        }
+        self.views.insert(name, view);
         Ok(())
     }
 
+    /// Removes view `name`, and the table holding its rows if it is
+    /// materialized.
+    pub fn drop_view(&mut self, name: &str) -> Result<ViewDef, String> {
+        let view = self
+            .views
+            .remove(name)
+            .ok_or_else(|| format!("View '{}' does not exist", name))?;
+        if view.materialized {
+            self.tables.remove(name);
+            self.table_order.retain(|t| t != name);
+        }
+        Ok(view)
+    }
+
+    /// Replaces the table schema of materialized view `name`.
+    pub(crate) fn set_materialized_schema(
+        &mut self,
+        name: &str,
+        schema: Schema,
+    ) -> Result<(), String> {
+        if !self.views.get(name).is_some_and(|v| v.materialized) {
+            return Err(format!("'{name}' is not a materialized view"));
+        }
+        self.tables.insert(name.to_string(), schema);
+        Ok(())
+    }
+
+    pub fn view(&self, name: &str) -> Option<&ViewDef> {
+        self.views.get(name)
+    }
+
+    /// Names of every view, sorted.
+    pub fn view_names(&self) -> Vec<String> {
+        self.views.keys().cloned().collect()
+    }
+
+    /// Names of every table, sorted.
+    pub fn table_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.tables.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     /// Retrieves the schema for a given table
     /// Returns an error if the table does not exist
     pub fn schema(&self, table: &str) -> Result<&Schema, String> {
@@ -188,4 +314,9 @@ impl Catalog {
             .get(table)
             .ok_or_else(|| format!("Table '{}' does not exist", table))
     }
+
+    /// Indexes on `table`, including the implicit PRIMARY KEY and UNIQUE ones.
+    pub fn indexes(&self, table: &str) -> Result<Vec<IndexInfo>, String> {
+        Ok(self.schema(table)?.indexes())
+    }
 }
@@ -1,27 +1,34 @@
 impl Catalog {
     pub fn save_to_path(&self, path: &Path) -> Result<(), String> {
-        let mut tables: HashMap<String, Vec<ColumnFile>> = HashMap::new();
-        let mut table_constraints: HashMap<String, TableConstraintFile> = HashMap::new();
+        let payload = self.to_json()?;
+        crate::storage::persistence::write_file_atomic(path, payload.as_bytes())
+            .map_err(|e| format!("Failed to write catalog file: {e}"))
+    }
+
+    /// The catalog as `catalog.json` stores it.
+    pub fn to_json(&self) -> Result<String, String> {
+        let mut tables: BTreeMap<String, Vec<ColumnFile>> = BTreeMap::new();
+        let mut table_constraints: BTreeMap<String, TableConstraintFile> = BTreeMap::new();
+        let mut comments: BTreeMap<String, CommentFile> = BTreeMap::new();
         for (table, schema) in &self.tables {
+            if schema.comment.is_some() || !schema.column_comments.is_empty() {
+                comments.insert(
+                    table.clone(),
+                    CommentFile {
+                        table: schema.comment.clone(),
+                        columns: schema
+                            .column_comments
+                            .iter()
+                            .map(|(k, v)| (k.clone(), v.clone()))
+                            .collect(),
+                    },
+                );
+            }
             let cols: Vec<ColumnFile> = schema
                 .columns
                 .iter()
                 .map(|c| {
-                    let dtype = match &c.dtype {
-                        DataType::Bool => "bool".to_string(),
-                        DataType::Int => "int".to_string(),
-                        DataType::BigInt => "bigint".to_string(),
-                        DataType::Decimal { precision, scale } => {
-                            format!("decimal({precision},{scale})")
-                        }
-                        DataType::VarChar(n) => format!("varchar({n})"),
-                        DataType::Text => "text".to_string(),
-                        DataType::Date => "date".to_string(),
-                        DataType::Timestamp => "timestamp".to_string(),
-                        DataType::Uuid => "uuid".to_string(),
-                        DataType::Json => "json".to_string(),
-                        DataType::Blob => "blob".to_string(),
-                    };
+                    let dtype = datatype_name(&c.dtype);
                     ColumnFile {
                         name: c.name.clone(),
                         dtype,
@@ -29,6 +36,10 @@ impl Catalog {
                         unique: c.unique,
                         not_null: c.not_null,
                         default: c.default.clone(),
+                        json_shape: c.json_shape.map(|shape| match shape {
+                            JsonShape::Object => "object".to_string(),
+                            JsonShape::Array => "array".to_string(),
+                        }),
                     }
                 })
                 .collect();
@@ -38,7 +49,9 @@ impl Catalog {
                 TableConstraintFile {
                     primary_key: schema.primary_key.clone(),
                     unique: schema.unique_constraints.clone(),
+                    unique_nulls_not_distinct: schema.unique_nulls_not_distinct.clone(),
                     secondary_indexes: schema.secondary_indexes.clone(),
+                    trigram_indexes: schema.trigram_indexes.clone(),
                     foreign_keys: schema
                         .foreign_keys
                         .iter()
@@ -60,6 +73,9 @@ impl Catalog {
                             },
                         })
                         .collect(),
+                    partition_by: schema.partition_by.clone(),
+                    index_names: schema.index_names.clone(),
+                    triggers: schema.triggers.clone(),
                 },
             );
         }
@@ -67,11 +83,23 @@ impl Catalog {
         let payload = serde_json::to_string_pretty(&CatalogFile {
             format_version: crate::STORAGE_FORMAT_VERSION,
             tables,
+            table_order: self.table_order.clone(),
             table_constraints,
+            comments,
+            views: self
+                .views
+                .iter()
+                .map(|(name, view)| (name.clone(), view.sql.clone()))
+                .collect(),
+            materialized_views: self
+                .views
+                .iter()
+                .filter(|(_, view)| view.materialized)
+                .map(|(name, _)| name.clone())
+                .collect(),
         })
         .map_err(|e| format!("Failed to serialize catalog as JSON: {e}"))?;
-        crate::storage::persistence::write_file_atomic(path, payload.as_bytes())
-            .map_err(|e| format!("Failed to write catalog file: {e}"))
+        Ok(payload)
     }
 
     /// Loads catalog metadata from disk.
@@ -90,14 +118,36 @@ impl Catalog {
         let CatalogFile {
             format_version,
             tables: file_tables,
+            table_order: file_order,
             table_constraints: file_constraints,
+            comments: mut file_comments,
+            views: file_views,
+            materialized_views,
         } = file;
         crate::storage::migrations::plan_catalog_migration(format_version)?;
+        // Listed names first, then any table the order misses, by name.
+        let mut table_order: Vec<String> = Vec::new();
+        for table in file_order.into_iter().chain(file_tables.keys().cloned()) {
+            if file_tables.contains_key(&table) && !table_order.contains(&table) {
+                table_order.push(table);
+            }
+        }
         let mut tables: HashMap<String, Schema> = HashMap::new();
         for (table, cols) in file_tables {
             let mut columns: Vec<Column> = Vec::new();
             for c in cols {
                 let dtype = crate::types::datatype::parse_datatype(&c.dtype)?;
+                let json_shape = match c.json_shape.as_deref() {
+                    None => None,
+                    Some("object") => Some(JsonShape::Object),
+                    Some("array") => Some(JsonShape::Array),
+                    Some(other) => {
+                        return Err(format!(
+                            "Malformed catalog JSON: unknown json_shape '{other}' on column '{}'",
+                            c.name
+                        ));
+                    }
+                };
                 columns.push(Column {
                     name: c.name,
                     dtype,
@@ -105,6 +155,7 @@ impl Catalog {
                     unique: c.unique,
                     not_null: c.not_null,
                     default: c.default,
+                    json_shape,
                 });
             }
             let tc = file_constraints.get(&table).cloned().unwrap_or_default();
@@ -136,12 +187,46 @@ impl Catalog {
                             })
                             .collect(),
                     );
+                    schema.unique_nulls_not_distinct = tc.unique_nulls_not_distinct;
                     schema.secondary_indexes = tc.secondary_indexes;
+                    schema.trigram_indexes = tc.trigram_indexes;
+                    schema.partition_by = tc.partition_by;
+                    schema.index_names = tc.index_names;
+                    schema.triggers = tc.triggers;
+                    if let Some(comment) = file_comments.remove(&table) {
+                        schema.comment = comment.table;
+                        schema.column_comments = comment.columns.into_iter().collect();
+                    }
                     schema
                 },
             );
         }
 
-        Ok(Self { tables })
+        let mut views = BTreeMap::new();
+        for (name, sql) in file_views {
+            let query = crate::parser::parser::parse(&sql)
+                .map_err(|e| format!("Malformed catalog JSON: view '{name}': {e}"))?;
+            let materialized = materialized_views.contains(&name);
+            if materialized && !tables.contains_key(&name) {
+                return Err(format!(
+                    "Malformed catalog JSON: materialized view '{name}' has no table"
+                ));
+            }
+            views.insert(
+                name,
+                ViewDef {
+                    sql,
+                    query,
+                    materialized,
+                },
+            );
+        }
+
+        Ok(Self {
+            tables,
+            table_order,
+            views,
+            ..Self::new()
+        })
     }
 }
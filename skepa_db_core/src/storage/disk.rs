@@ -4,11 +4,14 @@ use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::path::{Path, PathBuf};
 
+use crate::recovery_report::{IndexRepair, IndexRepairAction};
 use crate::storage::Schema;
-use crate::storage::engine::StorageEngine;
+use crate::storage::engine::{PartitionSegments, StorageEngine};
+use crate::storage::generations::Generations;
+use crate::storage::persistence::FileJournal;
 use crate::types::Row;
 use crate::types::datatype::DataType;
-use crate::types::value::{Value, parse_value, value_to_string};
+use crate::types::value::{Value, index_key, parse_value};
 
 /// Disk-backed storage scaffold.
 /// For now this keeps rows in-memory during process lifetime while
@@ -22,28 +25,81 @@ pub struct DiskStorage {
     pk_indexes: HashMap<String, PrimaryIndex>,
     unique_indexes: HashMap<String, Vec<UniqueIndex>>,
     secondary_indexes: HashMap<String, Vec<SecondaryIndex>>,
+    /// `using trigram` indexes: each map key is one three-character window
+    /// of the column's text.
+    trigram_indexes: HashMap<String, Vec<SecondaryIndex>>,
+    /// Row positions of each `partition by` table, grouped by value.
+    partitions: HashMap<String, PartitionMap>,
+    /// Bumped on every row mutation; indexes record the value they were built at.
+    row_versions: HashMap<String, u64>,
+    index_versions: HashMap<String, u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct PrimaryIndex {
     col_idxs: Vec<usize>,
     map: BTreeMap<String, u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct UniqueIndex {
     cols: Vec<String>,
     col_idxs: Vec<usize>,
+    /// Rows with a NULL in the key are indexed too (`nulls not distinct`).
+    nulls_not_distinct: bool,
     map: BTreeMap<String, u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct SecondaryIndex {
     cols: Vec<String>,
     col_idxs: Vec<usize>,
     map: BTreeMap<String, Vec<u64>>,
 }
 
+/// Row positions of a partitioned table grouped by partition value. Each
+/// group is persisted as its own `.rows` segment file.
+#[derive(Debug, Clone, PartialEq)]
+struct PartitionMap {
+    col_idx: usize,
+    /// Keyed by the encoded partition value, as written to disk.
+    segments: BTreeMap<String, PartitionSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PartitionSegment {
+    value: Value,
+    rows: Vec<usize>,
+}
+
+/// One table's rows, row ids and indexes, copied so a transaction can put
+/// the table back on rollback without cloning the whole storage.
+#[derive(Debug, Clone)]
+pub(crate) struct TableSnapshot {
+    rows: Option<Vec<Row>>,
+    row_ids: Option<Vec<u64>>,
+    next_row_id: Option<u64>,
+    pk_index: Option<PrimaryIndex>,
+    unique_indexes: Option<Vec<UniqueIndex>>,
+    secondary_indexes: Option<Vec<SecondaryIndex>>,
+    trigram_indexes: Option<Vec<SecondaryIndex>>,
+    partitions: Option<PartitionMap>,
+    row_version: Option<u64>,
+    index_version: Option<u64>,
+}
+
+impl TableSnapshot {
+    pub(crate) fn row_count(&self) -> usize {
+        self.rows.as_ref().map_or(0, Vec::len)
+    }
+
+    /// The row with stable id `id` as of the snapshot.
+    pub(crate) fn row_by_id(&self, id: u64) -> Option<&Row> {
+        let pos = self.row_ids.as_ref()?.iter().position(|&r| r == id)?;
+        self.rows.as_ref()?.get(pos)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct TableIndexSnapshot {
     #[serde(default)]
@@ -52,6 +108,8 @@ struct TableIndexSnapshot {
     unique: Vec<IndexSnapshot>,
     #[serde(default)]
     secondary: Vec<SecondaryIndexSnapshot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trigram: Vec<SecondaryIndexSnapshot>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
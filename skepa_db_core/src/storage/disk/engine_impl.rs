@@ -22,6 +22,10 @@ impl StorageEngine for DiskStorage {
         self.pk_indexes.remove(table);
         self.unique_indexes.remove(table);
         self.secondary_indexes.remove(table);
+        self.trigram_indexes.remove(table);
+        self.partitions.remove(table);
+        self.row_versions.remove(table);
+        self.index_versions.remove(table);
         Ok(())
     }
 
@@ -41,6 +45,7 @@ impl StorageEngine for DiskStorage {
         rows.push(row);
         ids.push(*next);
         *next += 1;
+        self.bump_row_version(table);
         Ok(())
     }
 
@@ -52,6 +57,10 @@ impl StorageEngine for DiskStorage {
         Ok(rows.as_slice())
     }
 
+    fn row_ids(&self, table: &str) -> &[u64] {
+        self.row_ids.get(table).map_or(&[], Vec::as_slice)
+    }
+
     fn row(&self, table: &str, index: usize) -> Result<Option<&Row>, String> {
         let rows = self
             .tables
@@ -60,7 +69,25 @@ impl StorageEngine for DiskStorage {
         Ok(rows.get(index))
     }
 
+    fn table_byte_size(&self, table: &str) -> Result<u64, String> {
+        if !self.tables.contains_key(table) {
+            return Err(format!("Table '{}' does not exist in storage", table));
+        }
+        let mut total = 0;
+        let mut paths = table_data_files(&self.root, table);
+        paths.push(self.index_file_path(table));
+        for path in paths {
+            match fs::metadata(&path) {
+                Ok(meta) => total += meta.len(),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("Failed to stat '{}': {e}", path.display())),
+            }
+        }
+        Ok(total)
+    }
+
     fn scan_mut(&mut self, table: &str) -> Result<&mut Vec<Row>, String> {
+        self.bump_row_version(table);
         self.tables
             .get_mut(table)
             .ok_or_else(|| format!("Table '{}' does not exist in storage", table))
@@ -73,23 +100,59 @@ impl StorageEngine for DiskStorage {
         old_indices: Vec<usize>,
     ) -> Result<(), String> {
         if new_rows.len() != old_indices.len() {
-            return Err("Row replacement alignment mismatch".to_string());
+            return Err(format!(
+                "Internal error: row replacement for '{}' got {} row(s) but {} source index(es)",
+                table,
+                new_rows.len(),
+                old_indices.len()
+            ));
         }
+        let row_count = self
+            .tables
+            .get(table)
+            .map(Vec::len)
+            .ok_or_else(|| format!("Table '{}' does not exist in storage", table))?;
         let old_ids = self
             .row_ids
             .get(table)
             .cloned()
             .ok_or_else(|| format!("Table '{}' row ids are missing", table))?;
+        if old_ids.len() != row_count {
+            return Err(format!(
+                "Internal error: table '{}' has {} row(s) but {} row id(s)",
+                table,
+                row_count,
+                old_ids.len()
+            ));
+        }
+        // Each source row may be kept at most once and in its original order;
+        // anything else means the caller worked from a stale copy of the rows.
+        for (pos, pair) in old_indices.windows(2).enumerate() {
+            if pair[1] <= pair[0] {
+                return Err(format!(
+                    "Internal error: row replacement for '{}' source indexes are not strictly increasing at position {} ({} then {})",
+                    table,
+                    pos + 1,
+                    pair[0],
+                    pair[1]
+                ));
+            }
+        }
+        if let Some(&last) = old_indices.last()
+            && last >= row_count
+        {
+            return Err(format!(
+                "Internal error: row replacement for '{}' source index {} is out of range for {} row(s)",
+                table, last, row_count
+            ));
+        }
         let mut new_ids: Vec<u64> = Vec::with_capacity(new_rows.len());
         for old_i in old_indices {
-            let id = old_ids
-                .get(old_i)
-                .copied()
-                .ok_or_else(|| "Row replacement old index out of range".to_string())?;
-            new_ids.push(id);
+            new_ids.push(old_ids[old_i]);
         }
         self.tables.insert(table.to_string(), new_rows);
         self.row_ids.insert(table.to_string(), new_ids);
+        self.bump_row_version(table);
         Ok(())
     }
 
@@ -110,7 +173,7 @@ impl StorageEngine for DiskStorage {
             .ok_or_else(|| format!("Unknown column '{}' in primary key", pk_col))?;
         let dtype = &schema.columns[col_idx].dtype;
         let rhs = parse_value(dtype, rhs_token)?;
-        let key = encode_key_parts(&[value_to_string(&rhs)]);
+        let key = encode_key_parts(&[index_key(&rhs)]);
         let row_id = self
             .pk_indexes
             .get(table)
@@ -118,10 +181,21 @@ impl StorageEngine for DiskStorage {
         Ok(row_id.and_then(|rid| self.row_index_by_id(table, rid)))
     }
 
+    fn checkpoint_tables(&self, tables: &[&str]) -> Result<(), String> {
+        for table in tables {
+            self.persist_table(table)?;
+        }
+        Ok(())
+    }
+
     fn rebuild_indexes(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
         self.rebuild_indexes_internal(table, schema)
     }
 
+    fn indexes_current(&self, table: &str) -> bool {
+        self.row_versions.get(table) == self.index_versions.get(table)
+    }
+
     fn lookup_pk_conflict(
         &self,
         table: &str,
@@ -142,7 +216,7 @@ impl StorageEngine for DiskStorage {
             .map(|i| {
                 candidate
                     .get(*i)
-                    .map(value_to_string)
+                    .map(index_key)
                     .ok_or_else(|| "Candidate row missing PK column".to_string())
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -179,11 +253,46 @@ impl StorageEngine for DiskStorage {
         };
         let dtype = &schema.columns[col_idx].dtype;
         let rhs = parse_value(dtype, rhs_token)?;
-        let key = encode_key_parts(&[value_to_string(&rhs)]);
+        let key = encode_key_parts(&[index_key(&rhs)]);
         let row_id = idx.map.get(&key).copied();
         Ok(row_id.and_then(|rid| self.row_index_by_id(table, rid)))
     }
 
+    fn lookup_key_row_index(
+        &self,
+        table: &str,
+        col_idxs: &[usize],
+        key: &[Value],
+    ) -> Result<Option<usize>, String> {
+        let pk = self.pk_indexes.get(table).map(|idx| (&idx.col_idxs, &idx.map));
+        let uniques = self
+            .unique_indexes
+            .get(table)
+            .into_iter()
+            .flatten()
+            .map(|idx| (&idx.col_idxs, &idx.map));
+        for (idx_cols, map) in pk.into_iter().chain(uniques) {
+            if idx_cols.len() != col_idxs.len() {
+                continue;
+            }
+            // Reorder the probe into the index's own column order.
+            let parts = idx_cols
+                .iter()
+                .map(|c| {
+                    col_idxs
+                        .iter()
+                        .position(|x| x == c)
+                        .and_then(|pos| key.get(pos))
+                        .map(index_key)
+                })
+                .collect::<Option<Vec<_>>>();
+            let Some(parts) = parts else { continue };
+            let row_id = map.get(&encode_key_parts(&parts)).copied();
+            return Ok(row_id.and_then(|rid| self.row_index_by_id(table, rid)));
+        }
+        Ok(None)
+    }
+
     fn lookup_unique_conflict(
         &self,
         table: &str,
@@ -200,20 +309,10 @@ impl StorageEngine for DiskStorage {
                 .col_idxs
                 .iter()
                 .any(|i| matches!(candidate.get(*i), Some(Value::Null)));
-            if has_null {
+            if has_null && !idx.nulls_not_distinct {
                 continue;
             }
-            let parts = idx
-                .col_idxs
-                .iter()
-                .map(|i| {
-                    candidate
-                        .get(*i)
-                        .map(value_to_string)
-                        .ok_or_else(|| "Candidate row missing UNIQUE column".to_string())
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-            let key = encode_key_parts(&parts);
+            let key = unique_index_key(candidate, &idx.col_idxs)?;
             if let Some(found) = idx.map.get(&key).copied() {
                 let skip_row_id = skip_idx
                     .and_then(|i| self.row_ids.get(table).and_then(|ids| ids.get(i).copied()));
@@ -247,17 +346,77 @@ impl StorageEngine for DiskStorage {
         };
         let dtype = &schema.columns[col_idx].dtype;
         let rhs = parse_value(dtype, rhs_token)?;
-        let key = encode_key_parts(&[value_to_string(&rhs)]);
+        let key = encode_key_parts(&[index_key(&rhs)]);
         let row_ids = match idx.map.get(&key) {
             Some(v) => v,
             None => return Ok(Some(Vec::new())),
         };
-        let rows = row_ids
+        let mut rows = row_ids
             .iter()
             .filter_map(|rid| self.row_index_by_id(table, *rid))
             .collect::<Vec<_>>();
+        // Hand rows back in table order so ties under ORDER BY (and unordered
+        // output) come out exactly as a full scan would produce them.
+        rows.sort_unstable();
         Ok(Some(rows))
     }
+
+    fn partition_segments(&self, table: &str) -> Result<Option<PartitionSegments<'_>>, String> {
+        Ok(self.partitions.get(table).map(|parts| {
+            parts
+                .segments
+                .values()
+                .map(|seg| (&seg.value, seg.rows.as_slice()))
+                .collect()
+        }))
+    }
+
+    fn lookup_trigram_row_indices(
+        &self,
+        table: &str,
+        schema: &Schema,
+        column: &str,
+        pattern: &str,
+    ) -> Result<Option<Vec<usize>>, String> {
+        let Some(col_idx) = schema.columns.iter().position(|c| c.name == column) else {
+            return Ok(None);
+        };
+        let Some(idx) = self
+            .trigram_indexes
+            .get(table)
+            .and_then(|v| v.iter().find(|t| t.col_idxs == [col_idx]))
+        else {
+            return Ok(None);
+        };
+        let wanted = like_pattern_trigrams(pattern);
+        if wanted.is_empty() {
+            return Ok(None);
+        }
+        let mut lists = Vec::with_capacity(wanted.len());
+        for gram in &wanted {
+            match idx.map.get(gram) {
+                Some(ids) => lists.push(ids),
+                None => return Ok(Some(Vec::new())),
+            }
+        }
+        lists.sort_by_key(|ids| ids.len());
+        let mut candidates: std::collections::HashSet<u64> = lists[0].iter().copied().collect();
+        for ids in &lists[1..] {
+            let ids: std::collections::HashSet<u64> = ids.iter().copied().collect();
+            candidates.retain(|id| ids.contains(id));
+        }
+        let ids = self
+            .row_ids
+            .get(table)
+            .ok_or_else(|| format!("Table '{}' row ids are missing", table))?;
+        Ok(Some(
+            ids.iter()
+                .enumerate()
+                .filter(|(_, id)| candidates.contains(id))
+                .map(|(i, _)| i)
+                .collect(),
+        ))
+    }
 }
 
 impl DiskStorage {
@@ -298,24 +457,31 @@ impl DiskStorage {
             })
             .collect::<Vec<_>>();
 
-        let secondary = self
-            .secondary_indexes
-            .get(table)
-            .cloned()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|s| SecondaryIndexSnapshot {
-                cols: s.cols,
-                col_idxs: s.col_idxs,
-                entries: s
-                    .map
-                    .into_iter()
-                    .map(|(k, v)| SecondaryIndexEntry { key: k, row_ids: v })
-                    .collect(),
-            })
-            .collect::<Vec<_>>();
+        let postings = |indexes: Option<&Vec<SecondaryIndex>>| {
+            indexes
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| SecondaryIndexSnapshot {
+                    cols: s.cols,
+                    col_idxs: s.col_idxs,
+                    entries: s
+                        .map
+                        .into_iter()
+                        .map(|(k, v)| SecondaryIndexEntry { key: k, row_ids: v })
+                        .collect(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let secondary = postings(self.secondary_indexes.get(table));
+        let trigram = postings(self.trigram_indexes.get(table));
 
-        let payload = serde_json::to_string_pretty(&TableIndexSnapshot { pk, unique, secondary })
+        let payload = serde_json::to_string_pretty(&TableIndexSnapshot {
+            pk,
+            unique,
+            secondary,
+            trigram,
+        })
             .map_err(|e| format!("Failed to serialize indexes for '{table}': {e}"))?;
         crate::storage::persistence::write_file_atomic(
             &self.index_file_path(table),
@@ -324,7 +490,12 @@ impl DiskStorage {
         .map_err(|e| format!("Failed to write index file for '{table}': {e}"))
     }
 
-    fn load_indexes_from_disk(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
+    /// Loads the saved index data of `table` over indexes rebuilt from its
+    /// rows. Returns the indexes whose saved data disagreed with `schema`:
+    /// missing or invalid ones keep the rebuilt data, saved ones the schema
+    /// no longer defines are left out, and either way the index file is
+    /// rewritten.
+    fn load_indexes_from_disk(&mut self, table: &str, schema: &Schema) -> Result<Vec<IndexRepair>, String> {
         let path = self.index_file_path(table);
         if !path.exists() {
             return Err("Index file missing".to_string());
@@ -345,61 +516,123 @@ impl DiskStorage {
             .ok_or_else(|| format!("Table '{}' row ids are missing in storage", table))?
             .clone();
 
-        let mut should_heal = false;
+        let mut repairs: Vec<IndexRepair> = Vec::new();
+        let repair = |index: String, action: IndexRepairAction| IndexRepair {
+            table: table.to_string(),
+            index,
+            action,
+        };
 
-        if let (Some(idx), Some(snap)) = (self.pk_indexes.get_mut(table), snapshot.pk) {
-            if idx.col_idxs == snap.col_idxs {
+        let pk_label = format!("primary key ({})", schema.primary_key.join(", "));
+        match (self.pk_indexes.get_mut(table), snapshot.pk) {
+            (Some(idx), Some(snap)) if idx.col_idxs == snap.col_idxs => {
                 match validate_snapshot_entries(snap.entries, &row_ids) {
                     Ok(map) => idx.map = map,
-                    Err(_) => should_heal = true,
+                    Err(_) => repairs.push(repair(pk_label, IndexRepairAction::Rebuilt)),
                 }
-            } else {
-                should_heal = true;
             }
+            (Some(_), _) => repairs.push(repair(pk_label, IndexRepairAction::Rebuilt)),
+            (None, Some(snap)) => repairs.push(repair(
+                format!("primary key ({})", snap.cols.join(", ")),
+                IndexRepairAction::Dropped,
+            )),
+            (None, None) => {}
         }
 
-        if let Some(existing) = self.unique_indexes.get_mut(table) {
-            for u in existing {
-                if let Some(su) = snapshot
-                    .unique
-                    .iter()
-                    .find(|s| s.col_idxs == u.col_idxs && s.cols == u.cols)
-                {
-                    match validate_snapshot_entries(su.entries.clone(), &row_ids) {
-                        Ok(map) => u.map = map,
-                        Err(_) => should_heal = true,
-                    }
-                } else {
-                    should_heal = true;
-                }
+        let existing = self.unique_indexes.get_mut(table).map_or(&mut [][..], Vec::as_mut_slice);
+        for u in existing.iter_mut() {
+            let label = format!("unique ({})", u.cols.join(", "));
+            let saved = snapshot
+                .unique
+                .iter()
+                .find(|s| s.col_idxs == u.col_idxs && s.cols == u.cols);
+            match saved.map(|su| validate_snapshot_entries(su.entries.clone(), &row_ids)) {
+                Some(Ok(map)) => u.map = map,
+                _ => repairs.push(repair(label, IndexRepairAction::Rebuilt)),
             }
         }
-        if let Some(existing) = self.secondary_indexes.get_mut(table) {
-            for s in existing {
-                if let Some(ss) = snapshot
-                    .secondary
+        for su in &snapshot.unique {
+            if !existing.iter().any(|u| su.col_idxs == u.col_idxs && su.cols == u.cols) {
+                repairs.push(repair(
+                    format!("unique ({})", su.cols.join(", ")),
+                    IndexRepairAction::Dropped,
+                ));
+            }
+        }
+
+        for (kind, saved_indexes) in [("index", &snapshot.secondary), ("trigram index", &snapshot.trigram)] {
+            let existing = match kind {
+                "index" => self.secondary_indexes.get_mut(table),
+                _ => self.trigram_indexes.get_mut(table),
+            }
+            .map_or(&mut [][..], Vec::as_mut_slice);
+            for s in existing.iter_mut() {
+                let label = format!("{kind} ({})", s.cols.join(", "));
+                let saved = saved_indexes
                     .iter()
-                    .find(|x| x.col_idxs == s.col_idxs && x.cols == s.cols)
-                {
-                    match validate_secondary_snapshot_entries(ss.entries.clone(), &row_ids) {
-                        Ok(map) => s.map = map,
-                        Err(_) => should_heal = true,
-                    }
-                } else {
-                    should_heal = true;
+                    .find(|x| x.col_idxs == s.col_idxs && x.cols == s.cols);
+                match saved.map(|ss| validate_secondary_snapshot_entries(ss.entries.clone(), &row_ids)) {
+                    Some(Ok(map)) => s.map = map,
+                    _ => repairs.push(repair(label, IndexRepairAction::Rebuilt)),
+                }
+            }
+            for ss in saved_indexes {
+                if !existing.iter().any(|s| ss.col_idxs == s.col_idxs && ss.cols == s.cols) {
+                    repairs.push(repair(
+                        format!("{kind} ({})", ss.cols.join(", ")),
+                        IndexRepairAction::Dropped,
+                    ));
                 }
             }
         }
-        if should_heal {
+
+        if !repairs.is_empty() {
             self.persist_indexes(table)?;
         }
-        Ok(())
+        Ok(repairs)
+    }
+
+    /// Every index `schema` defines on `table`, labelled as in
+    /// [`IndexRepair::index`], after a rebuild from the rows.
+    fn rebuilt_index_repairs(&self, table: &str, schema: &Schema) -> Vec<IndexRepair> {
+        let mut labels: Vec<String> = Vec::new();
+        if self.pk_indexes.contains_key(table) {
+            labels.push(format!("primary key ({})", schema.primary_key.join(", ")));
+        }
+        for u in self.unique_indexes.get(table).into_iter().flatten() {
+            labels.push(format!("unique ({})", u.cols.join(", ")));
+        }
+        for s in self.secondary_indexes.get(table).into_iter().flatten() {
+            labels.push(format!("index ({})", s.cols.join(", ")));
+        }
+        for t in self.trigram_indexes.get(table).into_iter().flatten() {
+            labels.push(format!("trigram index ({})", t.cols.join(", ")));
+        }
+        labels
+            .into_iter()
+            .map(|index| IndexRepair {
+                table: table.to_string(),
+                index,
+                action: IndexRepairAction::Rebuilt,
+            })
+            .collect()
     }
 
     fn rebuild_indexes_internal(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
         self.rebuild_primary_index(table, schema)?;
         self.rebuild_unique_indexes(table, schema)?;
-        self.rebuild_secondary_indexes(table, schema)
+        self.rebuild_secondary_indexes(table, schema)?;
+        self.rebuild_trigram_indexes(table, schema)?;
+        self.rebuild_partitions(table, schema)?;
+        match self.row_versions.get(table) {
+            Some(v) => self.index_versions.insert(table.to_string(), *v),
+            None => self.index_versions.remove(table),
+        };
+        Ok(())
+    }
+
+    fn bump_row_version(&mut self, table: &str) {
+        *self.row_versions.entry(table.to_string()).or_insert(0) += 1;
     }
 
     fn rebuild_primary_index(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
@@ -431,7 +664,7 @@ impl DiskStorage {
                 let v = row
                     .get(*i)
                     .ok_or_else(|| format!("Row is missing PK column '{}'", pk_col))?;
-                parts.push(value_to_string(v));
+                parts.push(index_key(v));
             }
             let row_id = *ids
                 .get(row_idx)
@@ -468,28 +701,27 @@ impl DiskStorage {
                     .ok_or_else(|| format!("Unknown UNIQUE column '{}'", c))?;
                 col_idxs.push(i);
             }
+            let nulls_not_distinct = schema.unique_nulls_not_distinct(&cols);
             let mut map: BTreeMap<String, u64> = BTreeMap::new();
             for (row_idx, row) in rows.iter().enumerate() {
-                if col_idxs
-                    .iter()
-                    .any(|i| matches!(row.get(*i), Some(Value::Null)))
+                if !nulls_not_distinct
+                    && col_idxs
+                        .iter()
+                        .any(|i| matches!(row.get(*i), Some(Value::Null)))
                 {
                     continue;
                 }
-                let parts = col_idxs
-                    .iter()
-                    .map(|i| {
-                        row.get(*i)
-                            .map(value_to_string)
-                            .ok_or_else(|| "Row missing UNIQUE column".to_string())
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
                 let row_id = *ids
                     .get(row_idx)
                     .ok_or_else(|| format!("Table '{}' row-id alignment is corrupted", table))?;
-                map.insert(encode_key_parts(&parts), row_id);
+                map.insert(unique_index_key(row, &col_idxs)?, row_id);
             }
-            indexes.push(UniqueIndex { cols, col_idxs, map });
+            indexes.push(UniqueIndex {
+                cols,
+                col_idxs,
+                nulls_not_distinct,
+                map,
+            });
         }
         self.unique_indexes.insert(table.to_string(), indexes);
         Ok(())
@@ -531,7 +763,7 @@ impl DiskStorage {
                     .iter()
                     .map(|i| {
                         row.get(*i)
-                            .map(value_to_string)
+                            .map(index_key)
                             .ok_or_else(|| "Row missing INDEX column".to_string())
                     })
                     .collect::<Result<Vec<_>, _>>()?;
@@ -550,4 +782,78 @@ impl DiskStorage {
         self.secondary_indexes.insert(table.to_string(), indexes);
         Ok(())
     }
+
+    fn rebuild_trigram_indexes(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
+        if schema.trigram_indexes.is_empty() {
+            self.trigram_indexes.remove(table);
+            return Ok(());
+        }
+        let rows = self
+            .tables
+            .get(table)
+            .ok_or_else(|| format!("Table '{}' does not exist in storage", table))?;
+        let ids = self
+            .row_ids
+            .get(table)
+            .ok_or_else(|| format!("Table '{}' row ids are missing", table))?;
+        let mut indexes: Vec<SecondaryIndex> = Vec::new();
+        for col in &schema.trigram_indexes {
+            let col_idx = schema
+                .columns
+                .iter()
+                .position(|x| x.name == *col)
+                .ok_or_else(|| format!("Unknown INDEX column '{}'", col))?;
+            let mut map: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+            for (row_idx, row) in rows.iter().enumerate() {
+                let text = match row.get(col_idx) {
+                    Some(Value::Text(t)) | Some(Value::VarChar(t)) => t,
+                    _ => continue,
+                };
+                let row_id = *ids
+                    .get(row_idx)
+                    .ok_or_else(|| format!("Table '{}' row-id alignment is corrupted", table))?;
+                for gram in trigrams(text) {
+                    map.entry(gram).or_default().push(row_id);
+                }
+            }
+            indexes.push(SecondaryIndex {
+                cols: vec![col.clone()],
+                col_idxs: vec![col_idx],
+                map,
+            });
+        }
+        self.trigram_indexes.insert(table.to_string(), indexes);
+        Ok(())
+    }
+
+    fn rebuild_partitions(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
+        let Some(col) = &schema.partition_by else {
+            self.partitions.remove(table);
+            return Ok(());
+        };
+        let col_idx = schema
+            .columns
+            .iter()
+            .position(|c| c.name == *col)
+            .ok_or_else(|| format!("Unknown column '{}' in PARTITION BY", col))?;
+        let rows = self
+            .tables
+            .get(table)
+            .ok_or_else(|| format!("Table '{}' does not exist in storage", table))?;
+        let mut segments: BTreeMap<String, PartitionSegment> = BTreeMap::new();
+        for (i, row) in rows.iter().enumerate() {
+            let value = &row[col_idx];
+            segments
+                .entry(encode_value(value))
+                .or_insert_with(|| PartitionSegment {
+                    value: value.clone(),
+                    rows: Vec::new(),
+                })
+                .rows
+                .push(i);
+        }
+        self.partitions
+            .insert(table.to_string(), PartitionMap { col_idx, segments });
+        Ok(())
+    }
 }
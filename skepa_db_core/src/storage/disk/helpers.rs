@@ -10,6 +10,20 @@ fn encode_key_parts(parts: &[String]) -> String {
     out
 }
 
+/// Key of a UNIQUE index entry. A NULL part carries no length prefix, so it
+/// never collides with the text 'null'.
+fn unique_index_key(row: &Row, col_idxs: &[usize]) -> Result<String, String> {
+    let mut out = String::new();
+    for i in col_idxs {
+        match row.get(*i) {
+            Some(Value::Null) => out.push_str("null;"),
+            Some(v) => out.push_str(&encode_key_parts(&[index_key(v)])),
+            None => return Err("Row missing UNIQUE column".to_string()),
+        }
+    }
+    Ok(out)
+}
+
 fn unique_groups(schema: &Schema) -> Result<Vec<Vec<String>>, String> {
     let mut out: Vec<Vec<String>> = Vec::new();
     let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
@@ -82,6 +96,20 @@ fn validate_secondary_snapshot_entries(
     Ok(out)
 }
 
+/// Distinct three-character windows of `text`. Windows are taken over
+/// `char`s, so a multi-byte character is never split.
+fn trigrams(text: &str) -> std::collections::BTreeSet<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+/// Trigrams every value matching a `like` pattern must contain: those of
+/// each literal run between `*` and `?` wildcards. Empty when no run is at
+/// least three characters long.
+fn like_pattern_trigrams(pattern: &str) -> std::collections::BTreeSet<String> {
+    pattern.split(['*', '?']).flat_map(trigrams).collect()
+}
+
 fn encode_value(v: &Value) -> String {
     match v {
         Value::Null => "n:".to_string(),
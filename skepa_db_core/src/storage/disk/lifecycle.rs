@@ -40,88 +40,356 @@ impl DiskStorage {
             pk_indexes: HashMap::new(),
             unique_indexes: HashMap::new(),
             secondary_indexes: HashMap::new(),
+            trigram_indexes: HashMap::new(),
+            partitions: HashMap::new(),
+            row_versions: HashMap::new(),
+            index_versions: HashMap::new(),
         })
     }
 
-    fn table_file_path(&self, table: &str) -> PathBuf {
-        self.root.join("tables").join(format!("{table}.rows"))
+    /// The row of `table` with stable id `id`.
+    pub(crate) fn row_by_id(&self, table: &str, id: u64) -> Option<&Row> {
+        let pos = self.row_ids(table).iter().position(|&r| r == id)?;
+        self.tables.get(table)?.get(pos)
     }
 
-    fn index_file_path(&self, table: &str) -> PathBuf {
-        self.root.join("indexes").join(format!("{table}.indexes.json"))
+    pub(crate) fn snapshot_table(&self, table: &str) -> TableSnapshot {
+        TableSnapshot {
+            rows: self.tables.get(table).cloned(),
+            row_ids: self.row_ids.get(table).cloned(),
+            next_row_id: self.next_row_id.get(table).copied(),
+            pk_index: self.pk_indexes.get(table).cloned(),
+            unique_indexes: self.unique_indexes.get(table).cloned(),
+            secondary_indexes: self.secondary_indexes.get(table).cloned(),
+            trigram_indexes: self.trigram_indexes.get(table).cloned(),
+            partitions: self.partitions.get(table).cloned(),
+            row_version: self.row_versions.get(table).copied(),
+            index_version: self.index_versions.get(table).copied(),
+        }
     }
 
-    pub fn bootstrap_table(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
-        if self.tables.contains_key(table) {
-            return Ok(());
+    /// Puts a table back exactly as [`DiskStorage::snapshot_table`] saw it,
+    /// including entries that were absent at the time.
+    pub(crate) fn restore_table(&mut self, table: &str, snapshot: TableSnapshot) {
+        fn put<T>(map: &mut HashMap<String, T>, table: &str, value: Option<T>) {
+            match value {
+                Some(v) => {
+                    map.insert(table.to_string(), v);
+                }
+                None => {
+                    map.remove(table);
+                }
+            }
         }
-        let table_file = self.table_file_path(table);
-        if !table_file.exists() {
-            File::create(&table_file)
-                .map_err(|e| format!("Failed to create table file for '{table}': {e}"))?;
+        put(&mut self.tables, table, snapshot.rows);
+        put(&mut self.row_ids, table, snapshot.row_ids);
+        put(&mut self.next_row_id, table, snapshot.next_row_id);
+        put(&mut self.pk_indexes, table, snapshot.pk_index);
+        put(&mut self.unique_indexes, table, snapshot.unique_indexes);
+        put(&mut self.secondary_indexes, table, snapshot.secondary_indexes);
+        put(&mut self.trigram_indexes, table, snapshot.trigram_indexes);
+        put(&mut self.partitions, table, snapshot.partitions);
+        put(&mut self.row_versions, table, snapshot.row_version);
+        put(&mut self.index_versions, table, snapshot.index_version);
+    }
+
+    /// Holds `rows` in memory as `table`, under fresh row ids, without
+    /// touching any file. Used for the rows of a view: a plain view's only
+    /// ever appear in a per-statement storage, and a materialized view's are
+    /// persisted by the caller.
+    pub(crate) fn load_rows(
+        &mut self,
+        table: &str,
+        schema: &Schema,
+        rows: Vec<Row>,
+    ) -> Result<(), String> {
+        let count = rows.len() as u64;
+        self.tables.insert(table.to_string(), rows);
+        self.row_ids.insert(table.to_string(), (1..=count).collect());
+        self.next_row_id.insert(table.to_string(), count + 1);
+        self.bump_row_version(table);
+        self.rebuild_indexes_internal(table, schema)
+    }
+
+    /// Forgets `table` and deletes its row and index files.
+    pub(crate) fn remove_table(&mut self, table: &str) -> Result<(), String> {
+        let files = table_data_files(&self.root, table);
+        Generations::bump(&self.root, &[table])?;
+        self.restore_table(
+            table,
+            TableSnapshot {
+                rows: None,
+                row_ids: None,
+                next_row_id: None,
+                pk_index: None,
+                unique_indexes: None,
+                secondary_indexes: None,
+                trigram_indexes: None,
+                partitions: None,
+                row_version: None,
+                index_version: None,
+            },
+        );
+        for path in files.into_iter().chain([self.index_file_path(table)]) {
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(format!("Failed to remove '{}': {e}", path.display()));
+                }
+                _ => {}
+            }
         }
+        Ok(())
+    }
 
-        let content = fs::read_to_string(&table_file)
-            .map_err(|e| format!("Failed to read table file for '{table}': {e}"))?;
-        let mut rows: Vec<Row> = Vec::new();
-        let mut row_ids: Vec<u64> = Vec::new();
-        let mut max_row_id = 0u64;
+    /// Whether every row `mine` updated or deleted in `table` since `base`
+    /// is, matched by id, still as it was in `base` in this storage.
+    pub(crate) fn changed_rows_intact(
+        &self,
+        table: &str,
+        base: &TableSnapshot,
+        mine: &DiskStorage,
+    ) -> bool {
+        fn rows_by_id<'a>(storage: &'a DiskStorage, table: &str) -> HashMap<u64, &'a Row> {
+            storage
+                .row_ids(table)
+                .iter()
+                .copied()
+                .zip(storage.tables.get(table).into_iter().flatten())
+                .collect()
+        }
+        let (my_rows, here) = (rows_by_id(mine, table), rows_by_id(self, table));
+        let base_rows = base.row_ids.iter().flatten().zip(base.rows.iter().flatten());
+        base_rows
+            .into_iter()
+            .all(|(id, row)| my_rows.get(id) == Some(&row) || here.get(id) == Some(&row))
+    }
 
-        for (line_no, line) in content.lines().enumerate() {
-            if line.trim().is_empty() {
-                continue;
+    /// Row changes from `base` to this storage's copy of `table`, matching
+    /// rows by id: updates and inserts in current row order, then deletes.
+    pub(crate) fn changes_since(
+        &self,
+        table: &str,
+        base: &TableSnapshot,
+    ) -> Vec<crate::change_event::ChangeEvent> {
+        use crate::change_event::{ChangeEvent, ChangeOp};
+        let base_rows: HashMap<u64, &Row> = base
+            .row_ids
+            .iter()
+            .flatten()
+            .copied()
+            .zip(base.rows.iter().flatten())
+            .collect();
+        let ids = self.row_ids(table);
+        let event = |op, row_id: u64, old: Option<&Row>, new: Option<&Row>| ChangeEvent {
+            table: table.to_string(),
+            op,
+            row_id,
+            old: old.cloned(),
+            new: new.cloned(),
+        };
+        let current: std::collections::HashSet<u64> = ids.iter().copied().collect();
+        let mut events = Vec::new();
+        for (id, row) in ids.iter().zip(self.tables.get(table).into_iter().flatten()) {
+            match base_rows.get(id) {
+                None => events.push(event(ChangeOp::Insert, *id, None, Some(row))),
+                Some(&old) if old != row => {
+                    events.push(event(ChangeOp::Update, *id, Some(old), Some(row)))
+                }
+                Some(_) => {}
             }
-            let mut tokens: Vec<&str> = line.split('\t').collect();
-            let parsed_row_id = parse_row_id_prefix(tokens.first().copied().unwrap_or(""));
-            let row_id = if let Some(id) = parsed_row_id {
-                tokens.remove(0);
-                id
-            } else {
-                (line_no as u64) + 1
-            };
-            if tokens.len() != schema.columns.len() {
+        }
+        for (id, old) in base.row_ids.iter().flatten().zip(base.rows.iter().flatten()) {
+            if !current.contains(id) {
+                events.push(event(ChangeOp::Delete, *id, Some(old), None));
+            }
+        }
+        events
+    }
+
+    /// Checks that a table's row ids line up with its rows and, when the
+    /// indexes claim to be current, that they match a fresh rebuild.
+    pub fn verify_table(&self, table: &str, schema: &Schema) -> Result<(), String> {
+        let rows = self
+            .tables
+            .get(table)
+            .ok_or_else(|| format!("Table '{}' does not exist in storage", table))?;
+        let ids = self
+            .row_ids
+            .get(table)
+            .ok_or_else(|| format!("Table '{}' row ids are missing", table))?;
+        if ids.len() != rows.len() {
+            return Err(format!(
+                "Integrity check failed for '{}': {} row(s) but {} row id(s)",
+                table,
+                rows.len(),
+                ids.len()
+            ));
+        }
+        let next = self.next_row_id.get(table).copied().unwrap_or(0);
+        let mut seen = std::collections::HashSet::with_capacity(ids.len());
+        for id in ids {
+            if !seen.insert(*id) || *id >= next {
                 return Err(format!(
-                    "Malformed row in table '{}' at line {}: expected {} values, got {}",
-                    table,
-                    line_no + 1,
-                    schema.columns.len(),
-                    tokens.len()
+                    "Integrity check failed for '{}': row id {} is duplicated or not yet allocated",
+                    table, id
                 ));
             }
+        }
+        if self.row_versions.get(table) != self.index_versions.get(table) {
+            return Ok(());
+        }
 
-            let mut row: Row = Vec::with_capacity(tokens.len());
-            for (i, tok) in tokens.iter().enumerate() {
-                let dtype = &schema.columns[i].dtype;
-                let decoded = decode_token(tok, dtype)?;
-                row.push(parse_value(dtype, &decoded)?);
-            }
-            rows.push(row);
-            row_ids.push(row_id);
-            if row_id > max_row_id {
-                max_row_id = row_id;
-            }
+        let mut fresh = Self {
+            root: self.root.clone(),
+            tables: HashMap::from([(table.to_string(), rows.clone())]),
+            row_ids: HashMap::from([(table.to_string(), ids.clone())]),
+            next_row_id: HashMap::new(),
+            pk_indexes: HashMap::new(),
+            unique_indexes: HashMap::new(),
+            secondary_indexes: HashMap::new(),
+            trigram_indexes: HashMap::new(),
+            partitions: HashMap::new(),
+            row_versions: HashMap::new(),
+            index_versions: HashMap::new(),
+        };
+        fresh.rebuild_indexes_internal(table, schema)?;
+        let consistent = self.pk_indexes.get(table) == fresh.pk_indexes.get(table)
+            && self.unique_indexes.get(table).filter(|v| !v.is_empty())
+                == fresh.unique_indexes.get(table).filter(|v| !v.is_empty())
+            && self.secondary_indexes.get(table).filter(|v| !v.is_empty())
+                == fresh.secondary_indexes.get(table).filter(|v| !v.is_empty())
+            && self.trigram_indexes.get(table).filter(|v| !v.is_empty())
+                == fresh.trigram_indexes.get(table).filter(|v| !v.is_empty())
+            && self.partitions.get(table) == fresh.partitions.get(table);
+        if !consistent {
+            return Err(format!(
+                "Integrity check failed for '{}': indexes do not match the stored rows",
+                table
+            ));
         }
+        Ok(())
+    }
+
+    /// Creates an empty `.rows` file for `table` if it has gone missing, so
+    /// the table can still be opened (empty). Returns whether it created one.
+    pub fn recreate_missing_table_file(&self, table: &str) -> Result<bool, String> {
+        let table_file = self.table_file_path(table);
+        if table_file.exists() {
+            return Ok(false);
+        }
+        Generations::bump(&self.root, &[table])?;
+        File::create(&table_file)
+            .map_err(|e| format!("Failed to create table file for '{table}': {e}"))?;
+        Ok(true)
+    }
+
+    fn table_file_path(&self, table: &str) -> PathBuf {
+        self.root.join("tables").join(format!("{table}.rows"))
+    }
+
+    /// `tables/{table}.{hex}.rows`, holding the rows whose partition column
+    /// encodes to `key`.
+    fn segment_file_path(&self, table: &str, key: &str) -> PathBuf {
+        self.root
+            .join("tables")
+            .join(format!("{table}.{}.rows", hex::encode(key)))
+    }
+
+    fn index_file_path(&self, table: &str) -> PathBuf {
+        self.root.join("indexes").join(format!("{table}.indexes.json"))
+    }
+
+    pub fn bootstrap_table(&mut self, table: &str, schema: &Schema) -> Result<(), String> {
+        self.bootstrap_table_with_repairs(table, schema).map(|_| ())
+    }
+
+    /// [`DiskStorage::bootstrap_table`], returning the indexes whose saved
+    /// data had to be rebuilt or dropped to match `schema`. A missing or
+    /// unreadable index file counts as every index being rebuilt.
+    pub(crate) fn bootstrap_table_with_repairs(
+        &mut self,
+        table: &str,
+        schema: &Schema,
+    ) -> Result<Vec<IndexRepair>, String> {
+        if self.tables.contains_key(table) {
+            return Ok(Vec::new());
+        }
+        let files = table_data_files(&self.root, table);
+        let mut loaded: Vec<(u64, Row)> = Vec::new();
+        for (file_no, path) in files.iter().enumerate() {
+            // Only the main file may predate row-id prefixes; segment files
+            // are always written with them.
+            loaded.extend(read_table_file(table, path, schema, file_no == 0)?);
+        }
+        if files.len() > 1 {
+            // Each segment holds one partition value; row-id order puts the
+            // rows back in insertion order across them.
+            loaded.sort_by_key(|(id, _)| *id);
+        }
+        let max_row_id = loaded.iter().map(|(id, _)| *id).max().unwrap_or(0);
+        let (row_ids, rows): (Vec<u64>, Vec<Row>) = loaded.into_iter().unzip();
 
         self.tables.insert(table.to_string(), rows);
         self.row_ids.insert(table.to_string(), row_ids);
         self.next_row_id.insert(table.to_string(), max_row_id + 1);
-        if self.load_indexes_from_disk(table, schema).is_err() {
-            self.rebuild_indexes_internal(table, schema)?;
-            self.persist_indexes(table)?;
+        match self.load_indexes_from_disk(table, schema) {
+            Ok(repairs) => Ok(repairs),
+            Err(_) => {
+                self.rebuild_indexes_internal(table, schema)?;
+                self.persist_indexes(table)?;
+                Ok(self.rebuilt_index_repairs(table, schema))
+            }
         }
-        Ok(())
     }
 
     pub fn checkpoint_all(&self) -> Result<(), String> {
         let mut names: Vec<&String> = self.tables.keys().collect();
         names.sort();
         for table in names {
-            self.persist_table(table)?;
+            // Files already holding these rows keep their write counter, so
+            // opening a database does not look like a commit to other handles.
+            let changes = self.table_file_changes(table)?;
+            if !changes.is_unchanged() {
+                self.write_table_files(table, changes)?;
+            }
+            self.persist_indexes(table)?;
         }
         Ok(())
     }
 
     pub fn persist_table(&self, table: &str) -> Result<(), String> {
+        self.write_table_files(table, self.table_file_changes(table)?)?;
+        self.persist_indexes(table)
+    }
+
+    fn write_table_files(&self, table: &str, changes: FileJournal) -> Result<(), String> {
+        Generations::bump(&self.root, &[table])?;
+        if self.partitions.contains_key(table) {
+            // Segments land together: a crash part-way could otherwise
+            // leave a row in both its old and its new segment.
+            changes.commit(&self.root)
+        } else {
+            changes.apply_unjournaled()
+        }
+    }
+
+    /// Writes `table` together with the other `changes` through one
+    /// journal, so a crash leaves either all of them on disk or none.
+    pub(crate) fn persist_table_with(
+        &self,
+        table: &str,
+        mut changes: FileJournal,
+    ) -> Result<(), String> {
+        changes.extend(self.table_file_changes(table)?);
+        Generations::bump(&self.root, &[table])?;
+        changes.commit(&self.root)?;
+        self.persist_indexes(table)
+    }
+
+    /// The file writes that store `table`'s rows: its `.rows` file, its
+    /// partition segments, and the removal of segments for values that no
+    /// longer occur.
+    pub(crate) fn table_file_changes(&self, table: &str) -> Result<FileJournal, String> {
         let rows = self
             .tables
             .get(table)
@@ -133,25 +401,140 @@ impl DiskStorage {
         if rows.len() != row_ids.len() {
             return Err(format!("Table '{}' row-id alignment is corrupted", table));
         }
-        let table_file = self.table_file_path(table);
-        let mut lines: Vec<String> = Vec::with_capacity(rows.len());
-        for (i, row) in rows.iter().enumerate() {
-            let encoded = row
+        let encode_line = |i: usize| {
+            let encoded = rows[i]
                 .iter()
                 .map(encode_value)
                 .collect::<Vec<_>>()
                 .join("\t");
-            lines.push(format!("@{}|\t{}", row_ids[i], encoded));
+            format!("@{}|\t{}", row_ids[i], encoded)
+        };
+        let payload = |lines: &[String]| {
+            if lines.is_empty() {
+                Vec::new()
+            } else {
+                format!("{}\n", lines.join("\n")).into_bytes()
+            }
+        };
+
+        let mut segments: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut main_lines: Vec<String> = Vec::new();
+        match self.partitions.get(table) {
+            // Grouped from the rows themselves rather than the segment map,
+            // which may not have been rebuilt since the last write.
+            Some(parts) => {
+                for (i, row) in rows.iter().enumerate() {
+                    let key = encode_value(&row[parts.col_idx]);
+                    segments.entry(key).or_default().push(encode_line(i));
+                }
+            }
+            None => main_lines = (0..rows.len()).map(encode_line).collect(),
         }
-        let payload = if lines.is_empty() {
-            String::new()
-        } else {
-            format!("{}\n", lines.join("\n"))
+        let mut changes = FileJournal::new();
+        let mut keep: Vec<PathBuf> = Vec::with_capacity(segments.len());
+        for (key, lines) in &segments {
+            let path = self.segment_file_path(table, key);
+            changes.write(path.clone(), payload(lines));
+            keep.push(path);
+        }
+        changes.write(self.table_file_path(table), payload(&main_lines));
+        for stale in table_data_files(&self.root, table).into_iter().skip(1) {
+            if !keep.contains(&stale) {
+                changes.remove(stale);
+            }
+        }
+        Ok(changes)
+    }
+}
+
+/// Every file holding rows of `table`: the main `.rows` file first, then any
+/// partition segment files in name order.
+pub(crate) fn table_data_files(root: &Path, table: &str) -> Vec<PathBuf> {
+    let dir = root.join("tables");
+    let prefix = format!("{table}.");
+    let mut segments: Vec<PathBuf> = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".rows"))
+                .is_some_and(|hex| !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    segments.sort();
+    let mut files = vec![dir.join(format!("{table}.rows"))];
+    files.extend(segments);
+    files
+}
+
+/// Parses one `.rows` file into `(row id, row)` pairs. Lines without an
+/// `@id|` prefix take their line number as the id when `positional` is set.
+fn read_table_file(
+    table: &str,
+    path: &Path,
+    schema: &Schema,
+    positional: bool,
+) -> Result<Vec<(u64, Row)>, String> {
+    let content = fs::read_to_string(path).map_err(|e| table_file_error(table, path, &e))?;
+    let mut out: Vec<(u64, Row)> = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut tokens: Vec<&str> = line.split('\t').collect();
+        let row_id = match parse_row_id_prefix(tokens.first().copied().unwrap_or("")) {
+            Some(id) => {
+                tokens.remove(0);
+                id
+            }
+            None if positional => (line_no as u64) + 1,
+            None => {
+                return Err(format!(
+                    "Malformed row in table '{}' at line {} of {}: missing row id",
+                    table,
+                    line_no + 1,
+                    path.display()
+                ));
+            }
         };
-        crate::storage::persistence::write_file_atomic(&table_file, payload.as_bytes())
-            .map_err(|e| format!("Failed to write table snapshot for '{table}': {e}"))?;
-        self.persist_indexes(table)
+        if tokens.len() != schema.columns.len() {
+            return Err(format!(
+                "Malformed row in table '{}' at line {}: expected {} values, got {}",
+                table,
+                line_no + 1,
+                schema.columns.len(),
+                tokens.len()
+            ));
+        }
+
+        let mut row: Row = Vec::with_capacity(tokens.len());
+        for (i, tok) in tokens.iter().enumerate() {
+            let dtype = &schema.columns[i].dtype;
+            let decoded = decode_token(tok, dtype)?;
+            row.push(parse_value(dtype, &decoded)?);
+        }
+        out.push((row_id, row));
+    }
+    Ok(out)
+}
+
+/// The error for a `.rows` file that cannot be read, worded for the person
+/// running the database rather than as a raw OS message. A file that is
+/// gone reads differently from one that exists but cannot be opened.
+pub(crate) fn table_file_error(table: &str, path: &Path, err: &std::io::Error) -> String {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        return format!("Table file for '{}' is missing: {}", table, path.display());
     }
+    format!(
+        "Table file for '{}' is unreadable ({}): {}",
+        table,
+        err.kind(),
+        path.display()
+    )
 }
 
 fn initialize_layout(root: &Path) -> Result<(), String> {
@@ -1,5 +1,10 @@
 use crate::storage::Schema;
-use crate::types::Row;
+use crate::types::value::Value;
+use crate::types::{Row, RowId};
+
+/// One `(partition value, row indices)` entry per segment of a
+/// partitioned table.
+pub type PartitionSegments<'a> = Vec<(&'a Value, &'a [usize])>;
 
 /// Storage engine trait - abstraction for different storage backends
 /// (in-memory, disk-based, etc.)
@@ -13,6 +18,17 @@ pub trait StorageEngine {
     /// Scans all rows from the specified table
     fn scan(&self, table: &str) -> Result<&[Row], String>;
 
+    /// Stable ids of the table's rows, aligned with [`StorageEngine::scan`].
+    /// Empty for a backend that does not track them.
+    fn row_ids(&self, _table: &str) -> &[RowId] {
+        &[]
+    }
+
+    /// On-disk size in bytes of the table's row and index data.
+    fn table_byte_size(&self, _table: &str) -> Result<u64, String> {
+        Ok(0)
+    }
+
     /// Direct immutable access to a row by storage index.
     fn row(&self, table: &str, index: usize) -> Result<Option<&Row>, String> {
         Ok(self.scan(table)?.get(index))
@@ -42,11 +58,22 @@ pub trait StorageEngine {
         Ok(None)
     }
 
+    /// Writes the named tables' rows and indexes to durable storage.
+    fn checkpoint_tables(&self, _tables: &[&str]) -> Result<(), String> {
+        Ok(())
+    }
+
     /// Rebuild storage-side indexes for a table after bulk row mutation.
     fn rebuild_indexes(&mut self, _table: &str, _schema: &Schema) -> Result<(), String> {
         Ok(())
     }
 
+    /// Whether index lookups reflect the table's current rows. Targeted
+    /// paths fall back to a full predicate scan when this is false.
+    fn indexes_current(&self, _table: &str) -> bool {
+        true
+    }
+
     /// Lookup conflicting existing row for the candidate primary-key tuple.
     fn lookup_pk_conflict(
         &self,
@@ -69,6 +96,18 @@ pub trait StorageEngine {
         Ok(None)
     }
 
+    /// Lookup row index by equality on every column of the primary key or a
+    /// UNIQUE constraint, single or composite. `col_idxs` may name the key
+    /// columns in any order; `key[i]` is the value for `col_idxs[i]`.
+    fn lookup_key_row_index(
+        &self,
+        _table: &str,
+        _col_idxs: &[usize],
+        _key: &[Value],
+    ) -> Result<Option<usize>, String> {
+        Ok(None)
+    }
+
     /// Lookup row indices by equality on a single-column non-unique secondary index.
     fn lookup_secondary_row_indices(
         &self,
@@ -80,6 +119,27 @@ pub trait StorageEngine {
         Ok(None)
     }
 
+    /// Row indices of a `partition by` table grouped by partition value, one
+    /// entry per segment with its rows in table order. `None` when the table
+    /// is not partitioned. Only meaningful while `indexes_current` holds.
+    fn partition_segments(&self, _table: &str) -> Result<Option<PartitionSegments<'_>>, String> {
+        Ok(None)
+    }
+
+    /// Candidate row indices, in table order, for `column like pattern` from a
+    /// `using trigram` index. Candidates still have to be checked against the
+    /// pattern. `None` when the column has no trigram index or the pattern has
+    /// no literal run of three or more characters.
+    fn lookup_trigram_row_indices(
+        &self,
+        _table: &str,
+        _schema: &Schema,
+        _column: &str,
+        _pattern: &str,
+    ) -> Result<Option<Vec<usize>>, String> {
+        Ok(None)
+    }
+
     /// Lookup conflicting existing row for any UNIQUE tuple (single or composite).
     fn lookup_unique_conflict(
         &self,
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const GENERATIONS_FILE: &str = "generations.json";
+
+/// Write counters kept in `generations.json`, by which handles sharing a
+/// directory notice each other's commits without reading table files.
+/// Every write bumps its counters before it touches the files, under the
+/// write lock, so a counter that has not moved means the files have not
+/// either. A missing file reads as all zeros.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Generations {
+    /// Bumped by every write: a table file, the catalog or the WAL.
+    pub generation: u64,
+    /// Bumped by each write of a table's files, including their removal.
+    #[serde(default)]
+    pub tables: BTreeMap<String, u64>,
+}
+
+impl Generations {
+    pub fn load(root: &Path) -> Result<Self, String> {
+        match fs::read_to_string(root.join(GENERATIONS_FILE)) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Malformed {GENERATIONS_FILE}: {e}")),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {GENERATIONS_FILE}: {e}")),
+        }
+    }
+
+    /// The counter of `table`; zero for one never written.
+    pub fn table(&self, table: &str) -> u64 {
+        self.tables.get(table).copied().unwrap_or(0)
+    }
+
+    /// Bumps the directory's counter and those of `tables`. The file only
+    /// tells live handles apart and is never read after a crash, so it is
+    /// replaced without a sync.
+    pub(crate) fn bump(root: &Path, tables: &[&str]) -> Result<(), String> {
+        let mut generations = Self::load(root)?;
+        generations.generation += 1;
+        for table in tables {
+            *generations.tables.entry(table.to_string()).or_insert(0) += 1;
+        }
+        let payload = serde_json::to_string(&generations)
+            .map_err(|e| format!("Failed to serialize {GENERATIONS_FILE}: {e}"))?;
+        let path = root.join(GENERATIONS_FILE);
+        let temp = root.join(format!("{GENERATIONS_FILE}.tmp.{}", std::process::id()));
+        fs::write(&temp, payload)
+            .and_then(|()| fs::rename(&temp, &path))
+            .map_err(|e| format!("Failed to write {GENERATIONS_FILE}: {e}"))
+    }
+}
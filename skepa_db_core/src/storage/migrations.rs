@@ -6,6 +6,11 @@ pub struct StorageMigrationPlan {
     pub steps: Vec<&'static str>,
 }
 
+/// The oldest format current code opens in place. Version 2 only added
+/// `journal/`, which a version 1 directory lacks until its first multi-file
+/// write creates it.
+const OLDEST_DIRECT_OPEN_VERSION: u32 = 1;
+
 pub fn plan_catalog_migration(from_version: u32) -> Result<StorageMigrationPlan, String> {
     if from_version > crate::STORAGE_FORMAT_VERSION {
         return Err(format!(
@@ -23,6 +28,15 @@ pub fn plan_catalog_migration(from_version: u32) -> Result<StorageMigrationPlan,
         });
     }
 
+    if from_version >= OLDEST_DIRECT_OPEN_VERSION {
+        return Ok(StorageMigrationPlan {
+            from_version,
+            to_version: crate::STORAGE_FORMAT_VERSION,
+            requires_import_export: false,
+            steps: vec!["direct-open", "create-journal-on-first-multi-file-write"],
+        });
+    }
+
     Ok(StorageMigrationPlan {
         from_version,
         to_version: crate::STORAGE_FORMAT_VERSION,
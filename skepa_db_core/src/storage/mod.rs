@@ -1,12 +1,13 @@
 pub mod catalog;
 pub mod disk;
 pub mod engine;
+pub mod generations;
 pub mod migrations;
 pub mod persistence;
 pub mod schema;
 
 // Re-export main types for convenience
-pub use catalog::Catalog;
+pub use catalog::{Catalog, ViewDef};
 pub use disk::DiskStorage;
 pub use engine::StorageEngine;
-pub use schema::{Column, Schema};
+pub use schema::{Column, IndexInfo, IndexSource, Schema};
@@ -73,3 +73,193 @@ fn temp_path_for(path: &Path) -> PathBuf {
         .unwrap_or("snapshot");
     path.with_file_name(format!("{file_name}.tmp.{nanos}.{counter}"))
 }
+
+/// File writes and removals under a database directory that take effect
+/// together. Each write is first staged in a directory of its own under
+/// `journal/`; writing that directory's `commit` file, which lists every
+/// change, is the point the whole set counts as made. The changes are then
+/// applied in place, and [`recover_journal`] finishes them if the writer
+/// stopped part-way.
+#[derive(Debug, Default)]
+pub struct FileJournal {
+    writes: Vec<(PathBuf, Vec<u8>)>,
+    removals: Vec<PathBuf>,
+}
+
+impl FileJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces `path` with `payload`.
+    pub fn write(&mut self, path: PathBuf, payload: Vec<u8>) {
+        self.writes.push((path, payload));
+    }
+
+    /// Deletes `path` if it exists.
+    pub fn remove(&mut self, path: PathBuf) {
+        self.removals.push(path);
+    }
+
+    /// Adds the changes of `other` after these.
+    pub fn extend(&mut self, other: FileJournal) {
+        self.writes.extend(other.writes);
+        self.removals.extend(other.removals);
+    }
+
+    /// Whether applying would change nothing: every write matches the file
+    /// already there and no file to remove exists.
+    pub fn is_unchanged(&self) -> bool {
+        self.writes
+            .iter()
+            .all(|(path, payload)| fs::read(path).is_ok_and(|bytes| bytes == *payload))
+            && self.removals.iter().all(|path| !path.exists())
+    }
+
+    /// Stages, commits and applies the changes to the database at `root`;
+    /// every path must lie under it. Runs under the journal lock, after
+    /// finishing whatever an earlier writer left behind. A failure before
+    /// the commit record is written leaves the directory as it was.
+    pub fn commit(self, root: &Path) -> Result<(), String> {
+        let _lock = lock_journal(root)?;
+        recover_locked(root)?;
+        let dir = root.join(JOURNAL_DIR).join(format!(
+            "{}-{}",
+            std::process::id(),
+            JOURNAL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create journal '{}': {e}", dir.display()))?;
+        let relative = |path: &Path| {
+            path.strip_prefix(root)
+                .map(|rel| rel.display().to_string())
+                .map_err(|_| format!("'{}' is outside '{}'", path.display(), root.display()))
+        };
+        let mut entries: Vec<String> = Vec::new();
+        for (i, (path, payload)) in self.writes.iter().enumerate() {
+            let staged = dir.join(i.to_string());
+            File::create(&staged)
+                .and_then(|mut f| {
+                    f.write_all(payload)?;
+                    f.sync_all()
+                })
+                .map_err(|e| format!("Failed to stage '{}': {e}", path.display()))?;
+            entries.push(format!("write {i} {}", relative(path)?));
+        }
+        for path in &self.removals {
+            entries.push(format!("remove {}", relative(path)?));
+        }
+        if crate::storage_test_hooks::should_interrupt_journal_before_commit(root) {
+            return Err("Simulated interruption before journal commit".to_string());
+        }
+        write_file_atomic(
+            &dir.join("commit"),
+            format!("{}\n", entries.join("\n")).as_bytes(),
+        )?;
+        if crate::storage_test_hooks::should_interrupt_journal_after_commit(root) {
+            return Err("Simulated interruption after journal commit".to_string());
+        }
+        apply_journal(&dir, root)
+    }
+
+    /// Applies the changes one file at a time, each write atomic on its
+    /// own; for a single file this is as safe as [`FileJournal::commit`].
+    pub fn apply_unjournaled(self) -> Result<(), String> {
+        for (path, payload) in &self.writes {
+            write_file_atomic(path, payload)?;
+        }
+        for path in &self.removals {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(format!("Failed to remove '{}': {e}", path.display()));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+const JOURNAL_DIR: &str = "journal";
+
+static JOURNAL_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Takes `journal/lock` for the caller alone, waiting for any handle that
+/// holds it. Every journal is staged, committed and applied under this
+/// lock, so one writer never touches another's files part-way.
+fn lock_journal(root: &Path) -> Result<File, String> {
+    let dir = root.join(JOURNAL_DIR);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create journal '{}': {e}", dir.display()))?;
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join("lock"))
+        .map_err(|e| format!("Failed to open journal lock: {e}"))?;
+    file.lock()
+        .map_err(|e| format!("Failed to lock journal: {e}"))?;
+    Ok(file)
+}
+
+/// Finishes each [`FileJournal`] that was committed but not fully applied,
+/// and drops each one that never reached its commit record. Run when a
+/// database is opened, before anything is read; it waits for any other
+/// handle's write in progress, so only journals whose writers have stopped
+/// are left to it.
+pub fn recover_journal(root: &Path) -> Result<(), String> {
+    if !root.join(JOURNAL_DIR).exists() {
+        return Ok(());
+    }
+    let _lock = lock_journal(root)?;
+    recover_locked(root)
+}
+
+/// [`recover_journal`] for a caller already holding the journal lock.
+/// Every writer clears what it finds before staging its own journal, so
+/// at most one committed journal is ever waiting.
+fn recover_locked(root: &Path) -> Result<(), String> {
+    let dir = root.join(JOURNAL_DIR);
+    let mut pending: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read journal '{}': {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    pending.sort();
+    for journal in pending {
+        if journal.join("commit").exists() {
+            apply_journal(&journal, root)?;
+        } else {
+            fs::remove_dir_all(&journal)
+                .map_err(|e| format!("Failed to clear journal '{}': {e}", journal.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies the committed journal `dir` to `root`. Every step can be
+/// repeated: a staged file that is gone was already moved into place.
+fn apply_journal(dir: &Path, root: &Path) -> Result<(), String> {
+    let commit = fs::read_to_string(dir.join("commit"))
+        .map_err(|e| format!("Failed to read journal commit record: {e}"))?;
+    for entry in commit.lines() {
+        let malformed = || format!("Malformed journal entry '{entry}'");
+        match entry.split_once(' ') {
+            Some(("write", rest)) => {
+                let (staged, target) = rest.split_once(' ').ok_or_else(malformed)?;
+                let staged = dir.join(staged);
+                if staged.exists() {
+                    replace_file(&staged, &root.join(target))?;
+                }
+            }
+            Some(("remove", target)) => match fs::remove_file(root.join(target)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(format!("Failed to remove '{target}': {e}"));
+                }
+                _ => {}
+            },
+            _ => return Err(malformed()),
+        }
+    }
+    fs::remove_dir_all(dir).map_err(|e| format!("Failed to clear journal '{}': {e}", dir.display()))
+}
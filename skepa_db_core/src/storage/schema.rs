@@ -1,6 +1,8 @@
-use crate::parser::command::ForeignKeyAction;
+use crate::parser::command::{ForeignKeyAction, IndexKind, JsonShape, TriggerValue};
 use crate::types::datatype::DataType;
+use crate::types::value::Value;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForeignKeyDef {
@@ -21,6 +23,73 @@ pub struct Column {
     pub not_null: bool,
     #[serde(default)]
     pub default: Option<String>,
+    #[serde(default)]
+    pub json_shape: Option<JsonShape>,
+}
+
+impl Column {
+    /// Enforces the column's `check_json_object` / `check_json_array` hint.
+    /// Nulls and non-json values pass through untouched.
+    pub fn check_json_shape(&self, value: &Value) -> Result<(), String> {
+        let (Some(shape), Value::Json(j)) = (self.json_shape, value) else {
+            return Ok(());
+        };
+        match shape {
+            JsonShape::Object if !j.is_object() => {
+                Err(format!("Column '{}' requires a JSON object", self.name))
+            }
+            JsonShape::Array if !j.is_array() => {
+                Err(format!("Column '{}' requires a JSON array", self.name))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What declared an index listed by [`Schema::indexes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSource {
+    PrimaryKey,
+    Unique,
+    Secondary,
+    Trigram,
+}
+
+impl IndexSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PrimaryKey => "primary key",
+            Self::Unique => "unique",
+            Self::Secondary => "index",
+            Self::Trigram => "trigram",
+        }
+    }
+}
+
+/// One index backing a table and the columns it covers, in key order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexInfo {
+    pub source: IndexSource,
+    pub columns: Vec<String>,
+    /// The name given with `create index <name>`, if any.
+    pub name: Option<String>,
+}
+
+/// A name given to a secondary or trigram index with `create index <name>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedIndex {
+    pub name: String,
+    pub kind: IndexKind,
+    pub columns: Vec<String>,
+}
+
+/// An `after insert` trigger: once a row is inserted into the table, one
+/// row built from `values` is inserted into `target`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriggerDef {
+    pub name: String,
+    pub target: String,
+    pub values: Vec<TriggerValue>,
 }
 
 /// Represents the schema of a table (list of columns)
@@ -29,8 +98,27 @@ pub struct Schema {
     pub columns: Vec<Column>,
     pub primary_key: Vec<String>,
     pub unique_constraints: Vec<Vec<String>>,
+    /// The groups in `unique_constraints` declared `nulls not distinct`.
+    #[serde(default)]
+    pub unique_nulls_not_distinct: Vec<Vec<String>>,
     pub secondary_indexes: Vec<Vec<String>>,
+    /// Text columns with a `using trigram` index.
+    #[serde(default)]
+    pub trigram_indexes: Vec<String>,
     pub foreign_keys: Vec<ForeignKeyDef>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub column_comments: HashMap<String, String>,
+    /// Column whose distinct values each get their own row segment.
+    #[serde(default)]
+    pub partition_by: Option<String>,
+    /// Names of the secondary and trigram indexes created with one.
+    #[serde(default)]
+    pub index_names: Vec<NamedIndex>,
+    /// Triggers on this table, in creation order.
+    #[serde(default)]
+    pub triggers: Vec<TriggerDef>,
 }
 
 impl Schema {
@@ -40,8 +128,15 @@ impl Schema {
             columns,
             primary_key: Vec::new(),
             unique_constraints: Vec::new(),
+            unique_nulls_not_distinct: Vec::new(),
             secondary_indexes: Vec::new(),
+            trigram_indexes: Vec::new(),
             foreign_keys: Vec::new(),
+            comment: None,
+            column_comments: HashMap::new(),
+            partition_by: None,
+            index_names: Vec::new(),
+            triggers: Vec::new(),
         }
     }
 
@@ -55,11 +150,83 @@ impl Schema {
             columns,
             primary_key,
             unique_constraints,
+            unique_nulls_not_distinct: Vec::new(),
             secondary_indexes: Vec::new(),
+            trigram_indexes: Vec::new(),
             foreign_keys,
+            comment: None,
+            column_comments: HashMap::new(),
+            partition_by: None,
+            index_names: Vec::new(),
+            triggers: Vec::new(),
         }
     }
 
+    /// Whether the UNIQUE group on `cols` treats two NULLs as equal.
+    pub fn unique_nulls_not_distinct(&self, cols: &[String]) -> bool {
+        self.unique_nulls_not_distinct.iter().any(|u| u == cols)
+    }
+
+    /// Number of indexes backing this schema: the primary key, each distinct
+    /// UNIQUE group (table-level or column-level), each secondary index and
+    /// each trigram index.
+    pub fn index_count(&self) -> usize {
+        self.indexes().len()
+    }
+
+    /// Every index backing this schema: the primary key, then each distinct
+    /// UNIQUE group (column-level first, in column order), then secondary
+    /// and trigram indexes in creation order.
+    pub fn indexes(&self) -> Vec<IndexInfo> {
+        let mut out = Vec::new();
+        if !self.primary_key.is_empty() {
+            out.push(IndexInfo {
+                source: IndexSource::PrimaryKey,
+                columns: self.primary_key.clone(),
+                name: None,
+            });
+        }
+        let column_unique = self
+            .columns
+            .iter()
+            .filter(|c| c.unique && !c.primary_key)
+            .map(|c| vec![c.name.clone()]);
+        for cols in column_unique.chain(self.unique_constraints.iter().cloned()) {
+            if !out
+                .iter()
+                .any(|i| i.source == IndexSource::Unique && i.columns == cols)
+            {
+                out.push(IndexInfo {
+                    source: IndexSource::Unique,
+                    columns: cols,
+                    name: None,
+                });
+            }
+        }
+        out.extend(self.secondary_indexes.iter().map(|cols| IndexInfo {
+            source: IndexSource::Secondary,
+            columns: cols.clone(),
+            name: self.index_name(IndexKind::Standard, cols),
+        }));
+        out.extend(self.trigram_indexes.iter().map(|col| {
+            let columns = vec![col.clone()];
+            IndexInfo {
+                source: IndexSource::Trigram,
+                name: self.index_name(IndexKind::Trigram, &columns),
+                columns,
+            }
+        }));
+        out
+    }
+
+    /// The name of the `kind` index on `columns`, if it was given one.
+    pub fn index_name(&self, kind: IndexKind, columns: &[String]) -> Option<String> {
+        self.index_names
+            .iter()
+            .find(|n| n.kind == kind && n.columns == columns)
+            .map(|n| n.name.clone())
+    }
+
     /// Returns the number of columns in this schema
     pub fn column_count(&self) -> usize {
         self.columns.len()
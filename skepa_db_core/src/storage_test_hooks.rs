@@ -1,7 +1,23 @@
 use std::path::Path;
 
+pub(crate) fn should_fail_schema_table_write(db_path: &Path) -> bool {
+    db_path.join(".simulate_fail_schema_table_write").exists()
+}
+
 pub(crate) fn should_interrupt_checkpoint_after_tables(db_path: &Path) -> bool {
     db_path
         .join(".simulate_interrupt_checkpoint_after_tables")
         .exists()
 }
+
+pub(crate) fn should_interrupt_journal_before_commit(db_path: &Path) -> bool {
+    db_path
+        .join(".simulate_interrupt_journal_before_commit")
+        .exists()
+}
+
+pub(crate) fn should_interrupt_journal_after_commit(db_path: &Path) -> bool {
+    db_path
+        .join(".simulate_interrupt_journal_after_commit")
+        .exists()
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of the open transaction on a `Database` handle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionInfo {
+    pub txid: u64,
+    /// INSERT/UPDATE/DELETE statements staged for the WAL at commit.
+    pub staged_statements: usize,
+    /// Total text length of the staged statements.
+    pub staged_bytes: usize,
+    /// Tables targeted by staged statements, sorted by name.
+    pub touched_tables: Vec<String>,
+    /// First deferred (NO ACTION) foreign key violation that commit would
+    /// currently reject, if any.
+    pub no_action_violation: Option<String>,
+}
@@ -11,106 +11,496 @@ impl Database {
         if self.current_tx.is_some() {
             return Err("Transaction already active".to_string());
         }
-        let mut table_versions_at_begin: std::collections::HashMap<String, u64> =
-            std::collections::HashMap::new();
-        for (table, _) in self.catalog.snapshot_tables() {
-            let ver = self.table_file_version(&table)?;
-            table_versions_at_begin.insert(table, ver);
-        }
+        // One small file, whatever the size of the tables.
+        let table_versions_at_begin = Generations::load(&self.path)?.tables;
         let tx = TxState {
             txid: self.alloc_txid(),
             staged_ops: Vec::new(),
+            staged_bytes: 0,
             touched_tables: std::collections::HashSet::new(),
             table_versions_at_begin,
+            catalog_version_at_begin: self.catalog_file_version(),
+            deferred_foreign_keys: false,
             snapshot_catalog: self.catalog.clone(),
-            snapshot_storage: self.storage.clone(),
+            table_snapshots: std::collections::HashMap::new(),
+            locked_rows: std::collections::BTreeMap::new(),
+            subquery_tables: std::collections::BTreeSet::new(),
         };
         self.current_tx = Some(tx);
         Ok("transaction started".to_string())
     }
 
     pub(super) fn handle_commit(&mut self) -> Result<String, String> {
-        let snapshot_catalog = self
-            .current_tx
-            .as_ref()
-            .ok_or_else(|| "No active transaction".to_string())?
-            .snapshot_catalog
-            .clone();
-        let snapshot_storage = self
-            .current_tx
-            .as_ref()
-            .ok_or_else(|| "No active transaction".to_string())?
-            .snapshot_storage
-            .clone();
-
-        if let Err(e) = engine::validate_no_action_constraints(&self.catalog, &self.storage) {
-            self.catalog = snapshot_catalog;
-            self.storage = snapshot_storage;
-            self.current_tx = None;
+        let Some(tx) = &self.current_tx else {
+            return Err("No active transaction".to_string());
+        };
+
+        // Staged statements ran against the BEGIN catalog, so they cannot
+        // be applied on top of a schema another handle has since changed.
+        if !tx.staged_ops.is_empty() && self.catalog_file_version() != tx.catalog_version_at_begin {
+            let detail = self.schema_change_detail(&tx.snapshot_catalog);
+            self.reload_from_disk()?;
+            return Err(format!(
+                "Transaction conflict: schema changed during transaction{detail}"
+            ));
+        }
+
+        if let Err(e) = engine::validate_no_action_constraints(&tx.snapshot_catalog, &self.storage)
+        {
+            self.handle_rollback()?;
             return Err(e);
         }
+        if tx.deferred_foreign_keys
+            && let Err(e) =
+                engine::validate_deferred_foreign_keys(&tx.snapshot_catalog, &self.storage)
+        {
+            self.handle_rollback()?;
+            return Err(format!("Deferred constraint check failed at commit: {e}"));
+        }
 
-        let tx = self
-            .current_tx
-            .take()
-            .ok_or_else(|| "No active transaction".to_string())?;
+        match self.changed_subquery_table() {
+            Ok(None) => {}
+            Ok(Some(table)) => {
+                self.reload_from_disk()?;
+                return Err(format!(
+                    "Transaction conflict on table '{}': a table read by a subquery changed outside this transaction",
+                    table
+                ));
+            }
+            Err(e) => {
+                self.handle_rollback()?;
+                self.restore_missing_table_files()?;
+                return Err(e);
+            }
+        }
 
-        for table in &tx.touched_tables {
-            let begin_ver = tx.table_versions_at_begin.get(table).copied().unwrap_or(0);
-            let now_ver = self.table_file_version(table)?;
-            if now_ver != begin_ver {
+        // With row-level detection a rebase runs this transaction's
+        // statements again on the changed tables; change events then diff
+        // against the disk state it rebased onto.
+        let mut rebase_bases = None;
+        match self.conflict_detection {
+            ConflictDetection::Table => {
+                let touched: Vec<(String, u64)> = tx
+                    .touched_tables
+                    .iter()
+                    .map(|t| {
+                        let begin_ver = tx.table_versions_at_begin.get(t).copied().unwrap_or(0);
+                        (t.clone(), begin_ver)
+                    })
+                    .collect();
+                for (table, begin_ver) in touched {
+                    match self.table_file_version(&table) {
+                        Ok(now_ver) if now_ver == begin_ver => {}
+                        Ok(_) => {
+                            self.reload_from_disk()?;
+                            return Err(format!(
+                                "Transaction conflict on table '{}': data changed outside this transaction",
+                                table
+                            ));
+                        }
+                        // The file vanished or became unreadable: nothing has been
+                        // written yet, so the transaction is rolled back whole.
+                        Err(e) => {
+                            self.handle_rollback()?;
+                            self.restore_missing_table_files()?;
+                            return Err(e);
+                        }
+                    }
+                }
+            }
+            ConflictDetection::Row => match self.written_table_changed() {
+                Ok(false) => {}
+                Ok(true) => match self.rebase_onto_disk() {
+                    Ok(bases) => rebase_bases = Some(bases),
+                    Err(e) => {
+                        self.reload_from_disk()?;
+                        return Err(e);
+                    }
+                },
+                Err(e) => {
+                    self.handle_rollback()?;
+                    self.restore_missing_table_files()?;
+                    return Err(e);
+                }
+            },
+        }
+
+        match self.changed_locked_table() {
+            Ok(None) => {}
+            Ok(Some(table)) => {
                 self.reload_from_disk()?;
                 return Err(format!(
-                    "Transaction conflict on table '{}': data changed outside this transaction",
+                    "Transaction conflict on table '{}': a row locked with FOR UPDATE changed outside this transaction",
                     table
                 ));
             }
+            Err(e) => {
+                self.handle_rollback()?;
+                self.restore_missing_table_files()?;
+                return Err(e);
+            }
         }
 
+        let tx = self
+            .current_tx
+            .take()
+            .ok_or_else(|| "No active transaction".to_string())?;
+
         if !tx.staged_ops.is_empty() {
-            self.append_wal_line(&format!("BEGIN {}", tx.txid))?;
-            for op in &tx.staged_ops {
-                self.append_wal_line(&format!("OP {} {}", tx.txid, op))?;
+            let events = match &rebase_bases {
+                _ if !self.has_change_hooks() => Vec::new(),
+                Some(bases) => self.changes_since(bases),
+                None => self.changes_since(&tx.table_snapshots),
+            };
+            let mut group = Vec::with_capacity(tx.staged_ops.len() + 2);
+            group.push(format!("BEGIN {}", tx.txid));
+            group.extend(
+                tx.staged_ops
+                    .iter()
+                    .map(|op| recovery::wal_op_line(tx.txid, op)),
+            );
+            group.push(format!("COMMIT {}", tx.txid));
+            if let Err(e) = self.append_wal_group(&group) {
+                self.restore_tx_snapshots(tx);
+                return Err(e);
             }
-            self.append_wal_line(&format!("COMMIT {}", tx.txid))?;
 
-            for table in &tx.touched_tables {
-                self.storage.persist_table(table)?;
-            }
-            self.checkpoint_and_truncate_wal()?;
+            // The snapshotted tables are exactly those the staged statements
+            // could write, including foreign-key action and trigger targets.
+            self.dirty_tables.extend(tx.table_snapshots.into_keys());
+            self.checkpoint_after_commit()?;
+            self.fire_changes(&events);
         }
         Ok("transaction committed".to_string())
     }
 
+    pub(super) fn describe_transaction(&self) -> String {
+        let Some(info) = self.transaction_info() else {
+            return "no active transaction".to_string();
+        };
+        let tables = if info.touched_tables.is_empty() {
+            "none".to_string()
+        } else {
+            info.touched_tables.join(", ")
+        };
+        let deferred = match &info.no_action_violation {
+            None => "none unresolved".to_string(),
+            Some(e) => format!("unresolved ({e})"),
+        };
+        format!(
+            "transaction {}: {} staged statement(s) ({} bytes); touched tables: {}; deferred NO ACTION constraints: {}",
+            info.txid, info.staged_statements, info.staged_bytes, tables, deferred
+        )
+    }
+
+    /// Refuses to stage `statement` when it would take the transaction past
+    /// `tx_max_staged_statements` or `tx_max_staged_bytes`. Runs before the
+    /// statement does, so the transaction is left as it was.
+    pub(super) fn check_staging_limits(&self, statement: &str) -> Result<(), String> {
+        let Some(tx) = &self.current_tx else {
+            return Ok(());
+        };
+        if let Some(max) = self.tx_max_staged_statements
+            && tx.staged_ops.len() >= max
+        {
+            return Err(format!(
+                "Transaction too large: it already stages {max} statement(s); commit or increase tx_max_staged_statements"
+            ));
+        }
+        if let Some(max) = self.tx_max_staged_bytes
+            && tx.staged_bytes + statement.len() > max
+        {
+            return Err(format!(
+                "Transaction too large: staging {} more byte(s) would exceed {max}; commit or increase tx_max_staged_bytes",
+                statement.len()
+            ));
+        }
+        Ok(())
+    }
+
     pub(super) fn handle_rollback(&mut self) -> Result<String, String> {
         let tx = self
             .current_tx
             .take()
             .ok_or_else(|| "No active transaction".to_string())?;
-        self.catalog = tx.snapshot_catalog;
-        self.storage = tx.snapshot_storage;
+        self.restore_tx_snapshots(tx);
         Ok("transaction rolled back".to_string())
     }
 
-    pub(super) fn table_file_version(&self, table: &str) -> Result<u64, String> {
-        let path = self.path.join("tables").join(format!("{table}.rows"));
-        let bytes = fs::read(&path).map_err(|e| {
-            format!(
-                "Failed to read table file for '{table}' while checking transaction conflict: {e}"
+    /// Records the rows of `table` at storage `indices` as locked by
+    /// `select ... for update`, keeping each one's value as of BEGIN. Rows
+    /// this transaction inserted are not on disk yet and are skipped. Does
+    /// nothing outside a transaction.
+    pub(super) fn lock_rows(&mut self, table: &str, indices: &[usize]) {
+        let Some(tx) = &mut self.current_tx else {
+            return;
+        };
+        let ids = self.storage.row_ids(table);
+        let locked = tx.locked_rows.entry(table.to_string()).or_default();
+        for &i in indices {
+            let Some(&id) = ids.get(i) else {
+                continue;
+            };
+            if locked.contains_key(&id) {
+                continue;
+            }
+            let at_begin = match tx.table_snapshots.get(table) {
+                Some(snapshot) => snapshot.row_by_id(id),
+                None => self.storage.row_by_id(table, id),
+            };
+            if let Some(row) = at_begin {
+                locked.insert(id, row.clone());
+            }
+        }
+    }
+
+    /// The first table holding a row locked with `select ... for update`
+    /// that another handle has since changed or deleted. With table-level
+    /// conflict detection, tables this transaction wrote are skipped; the
+    /// whole-file check covers them.
+    fn changed_locked_table(&self) -> Result<Option<String>, String> {
+        let Some(tx) = &self.current_tx else {
+            return Ok(None);
+        };
+        for (table, rows) in &tx.locked_rows {
+            let begin_ver = tx.table_versions_at_begin.get(table).copied().unwrap_or(0);
+            let covered = self.conflict_detection == ConflictDetection::Table
+                && tx.touched_tables.contains(table);
+            if covered || self.table_file_version(table)? == begin_ver {
+                continue;
+            }
+            let mut disk = DiskStorage::new(self.path.clone())?;
+            disk.bootstrap_table(table, tx.snapshot_catalog.schema(table)?)?;
+            if rows
+                .iter()
+                .any(|(id, row)| disk.row_by_id(table, *id) != Some(row))
+            {
+                return Ok(Some(table.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The first table read by a subquery of a staged UPDATE or DELETE
+    /// whose file another handle has changed since `begin`. Replaying the
+    /// statement from the WAL would see the new rows.
+    fn changed_subquery_table(&self) -> Result<Option<String>, String> {
+        let Some(tx) = &self.current_tx else {
+            return Ok(None);
+        };
+        for table in &tx.subquery_tables {
+            let begin_ver = tx.table_versions_at_begin.get(table).copied().unwrap_or(0);
+            if self.table_file_version(table)? != begin_ver {
+                return Ok(Some(table.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether another handle has changed the file of any table the
+    /// transaction may have written since `begin`.
+    fn written_table_changed(&self) -> Result<bool, String> {
+        let Some(tx) = &self.current_tx else {
+            return Ok(false);
+        };
+        for table in tx.table_snapshots.keys() {
+            let begin_ver = tx.table_versions_at_begin.get(table).copied().unwrap_or(0);
+            if self.table_file_version(table)? != begin_ver {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Row-level conflict detection: reloads every table from disk, fails
+    /// if a row the transaction updated or deleted was changed there, and
+    /// otherwise runs the staged statements again on top of it, exactly as
+    /// WAL replay will. Keys and foreign keys are re-checked over the
+    /// result, which replaces this handle's state, ready for the WAL write.
+    /// The table files hold every commit: only `EveryCommit` handles share
+    /// a directory, and they checkpoint each one. Returns, when change hooks
+    /// are registered, each written table as it was on disk before the
+    /// statements ran again.
+    fn rebase_onto_disk(
+        &mut self,
+    ) -> Result<std::collections::HashMap<String, storage::disk::TableSnapshot>, String> {
+        let with_bases = self.has_change_hooks();
+        let tx = self
+            .current_tx
+            .as_ref()
+            .ok_or_else(|| "No active transaction".to_string())?;
+        let mut catalog = tx.snapshot_catalog.clone();
+        let mut merged = DiskStorage::new(self.path.clone())?;
+        for (table, schema) in catalog.snapshot_tables() {
+            merged.bootstrap_table(&table, &schema)?;
+        }
+        let mut written: Vec<&String> = tx.table_snapshots.keys().collect();
+        written.sort();
+        let mut disk_bases = std::collections::HashMap::new();
+        for table in &written {
+            if with_bases {
+                disk_bases.insert(table.to_string(), merged.snapshot_table(table));
+            }
+            if !merged.changed_rows_intact(table, &tx.table_snapshots[*table], &self.storage) {
+                return Err(format!(
+                    "Transaction conflict on table '{}': a row this transaction changed was also changed outside it",
+                    table
+                ));
+            }
+        }
+        let mut rerun = Ok(());
+        for logged in &tx.staged_ops {
+            let (uuids, stmt) = recovery::split_generated_uuids(logged)?;
+            let cmd = parser::parser::parse(stmt)?;
+            let table = restricted::referenced_tables(&cmd)
+                .first()
+                .map(|t| t.to_string())
+                .unwrap_or_default();
+            self.uuid_gen.replay(uuids);
+            rerun = engine::execute_command(
+                cmd,
+                &mut catalog,
+                &mut merged,
+                &mut self.uuid_gen,
+                self.max_join_rows,
+                true,
             )
-        })?;
+            .map(|_| ())
+            .map_err(|e| format!("Transaction conflict on table '{}': {}", table, e));
+            if rerun.is_err() {
+                break;
+            }
+        }
+        self.uuid_gen.replay(Vec::new());
+        self.uuid_gen.take_issued();
+        rerun?;
+        for table in written {
+            engine::validate_table_unique_constraints(&catalog, &merged, table)
+                .map_err(|e| format!("Transaction conflict on table '{}': {}", table, e))?;
+        }
+        engine::validate_no_action_constraints(&catalog, &merged)
+            .and_then(|()| engine::validate_deferred_foreign_keys(&catalog, &merged))
+            .map_err(|e| format!("Transaction conflict: {e}"))?;
+        self.catalog = catalog;
+        self.storage = merged;
+        Ok(disk_bases)
+    }
+
+    /// Puts the catalog and every table `tx` wrote back as of `begin`.
+    fn restore_tx_snapshots(&mut self, tx: TxState) {
+        self.catalog = tx.snapshot_catalog;
+        for (table, snapshot) in tx.table_snapshots {
+            self.storage.restore_table(&table, snapshot);
+        }
+    }
+
+    /// Writes back, from memory, each table whose `.rows` file has been
+    /// deleted from under this handle. Only called with no transaction
+    /// active, when memory holds the last committed state this handle saw.
+    fn restore_missing_table_files(&mut self) -> Result<(), String> {
+        for (table, _) in self.catalog.snapshot_tables() {
+            let main = crate::storage::disk::table_data_files(&self.path, &table).remove(0);
+            if !main.exists() {
+                self.storage.persist_table(&table)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies `table`, and every table a foreign-key action or trigger on it
+    /// can reach, the first time the transaction is about to write them.
+    pub(super) fn snapshot_tables_for_write(&mut self, table: &str) {
+        let written = self.tables_written_by(table);
+        let Some(tx) = &mut self.current_tx else {
+            return;
+        };
+        for name in written {
+            if tx.table_snapshots.contains_key(&name) {
+                continue;
+            }
+            let snapshot = self.storage.snapshot_table(&name);
+            self.tx_snapshot_rows += snapshot.row_count() as u64;
+            tx.table_snapshots.insert(name, snapshot);
+        }
+    }
+
+    /// `table` plus every table a foreign-key action or trigger on it can
+    /// reach.
+    pub(super) fn tables_written_by(&self, table: &str) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        let mut pending = vec![table.to_string()];
+        while let Some(next) = pending.pop() {
+            if out.contains(&next) {
+                continue;
+            }
+            for (child, schema) in self.catalog.snapshot_tables() {
+                if child == next {
+                    pending.extend(schema.triggers.iter().map(|t| t.target.clone()));
+                }
+                if schema.foreign_keys.iter().any(|fk| fk.ref_table == next) {
+                    pending.push(child);
+                }
+            }
+            out.push(next);
+        }
+        out
+    }
+
+    /// The write counter of `table`, once its files are known to still
+    /// open; a missing or unreadable file is an error.
+    pub(super) fn table_file_version(&self, table: &str) -> Result<u64, String> {
+        for path in crate::storage::disk::table_data_files(&self.path, table) {
+            fs::File::open(&path)
+                .map_err(|e| crate::storage::disk::table_file_error(table, &path, &e))?;
+        }
+        Ok(Generations::load(&self.path)?.table(table))
+    }
+
+    pub(super) fn catalog_file_version(&self) -> u64 {
+        let bytes = fs::read(self.path.join("catalog.json")).unwrap_or_default();
         let mut h = std::collections::hash_map::DefaultHasher::new();
         bytes.hash(&mut h);
-        Ok(h.finish())
+        h.finish()
+    }
+
+    /// Names the tables created or dropped on disk since `before`, as a
+    /// `" (created: a; dropped: b)"` suffix; empty when the change only
+    /// altered existing tables.
+    fn schema_change_detail(&self, before: &Catalog) -> String {
+        let Ok(now) = Self::load_catalog(&self.path) else {
+            return String::new();
+        };
+        let names = |c: &Catalog| -> std::collections::BTreeSet<String> {
+            c.snapshot_tables().into_iter().map(|(t, _)| t).collect()
+        };
+        let (before, now) = (names(before), names(&now));
+        let mut parts: Vec<String> = Vec::new();
+        let created: Vec<&str> = now.difference(&before).map(String::as_str).collect();
+        if !created.is_empty() {
+            parts.push(format!("created: {}", created.join(", ")));
+        }
+        let dropped: Vec<&str> = before.difference(&now).map(String::as_str).collect();
+        if !dropped.is_empty() {
+            parts.push(format!("dropped: {}", dropped.join(", ")));
+        }
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", parts.join("; "))
+        }
     }
 
     pub(super) fn reload_from_disk(&mut self) -> Result<(), String> {
         let mut storage = DiskStorage::new(self.path.clone())?;
-        let catalog = Self::load_catalog(&self.path).map_err(|e| e.to_string())?;
+        let mut catalog = Self::load_catalog(&self.path).map_err(|e| e.to_string())?;
+        if let Some(names) = &self.reserved_table_names {
+            catalog.set_reserved_table_names(names.clone());
+        }
         for (table, _) in catalog.snapshot_tables() {
             let schema = catalog
                 .schema(&table)
                 .map_err(|e| format!("Failed to refresh schema for '{table}': {e}"))?;
+            self.recreate_if_missing(&storage, &table)?;
             storage
                 .bootstrap_table(&table, schema)
                 .map_err(|e| format!("Failed to refresh table '{table}' from disk: {e}"))?;
@@ -118,6 +508,42 @@ impl Database {
         self.catalog = catalog;
         self.storage = storage;
         self.current_tx = None;
+        // Commits waiting for a checkpoint are only in the WAL.
+        self.replay_wal(false)
+    }
+
+    /// The directory's write counter from `generations.json`. Every WAL
+    /// append, catalog save and table write by any handle bumps it, so an
+    /// unchanged value means there is nothing new to load.
+    pub(super) fn disk_generation(&self) -> Result<u64, String> {
+        Generations::load(&self.path).map(|g| g.generation)
+    }
+
+    /// Called before each statement outside a transaction so it reads the
+    /// latest committed state rather than what this handle last loaded.
+    /// Inside a transaction nothing is refreshed: the transaction keeps
+    /// reading the state it began with plus its own staged writes.
+    /// Unless the caller holds the write lock (`locked`), a reload takes
+    /// it, so it never reads a write that is only part-way to disk.
+    pub(super) fn refresh_if_stale(&mut self, locked: bool) -> Result<(), String> {
+        if self.current_tx.is_some() {
+            return Ok(());
+        }
+        let now = self.disk_generation()?;
+        if now != self.disk_generation {
+            let _write_lock = if locked { None } else { self.write_guard()? };
+            self.reload_from_disk()?;
+        }
+        self.disk_generation = now;
         Ok(())
     }
+
+    /// Records the on-disk state this handle just wrote as already seen.
+    pub(super) fn mark_disk_seen(&mut self) {
+        if self.current_tx.is_none()
+            && let Ok(now) = self.disk_generation()
+        {
+            self.disk_generation = now;
+        }
+    }
 }
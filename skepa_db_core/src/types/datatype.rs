@@ -31,6 +31,23 @@ pub fn parse_datatype(s: &str) -> Result<DataType, String> {
     }
 }
 
+/// The SQL spelling of `dtype`, as accepted by [`parse_datatype`].
+pub fn datatype_name(dtype: &DataType) -> String {
+    match dtype {
+        DataType::Bool => "bool".to_string(),
+        DataType::Int => "int".to_string(),
+        DataType::BigInt => "bigint".to_string(),
+        DataType::Decimal { precision, scale } => format!("decimal({precision},{scale})"),
+        DataType::VarChar(size) => format!("varchar({size})"),
+        DataType::Text => "text".to_string(),
+        DataType::Date => "date".to_string(),
+        DataType::Timestamp => "timestamp".to_string(),
+        DataType::Uuid => "uuid".to_string(),
+        DataType::Json => "json".to_string(),
+        DataType::Blob => "blob".to_string(),
+    }
+}
+
 fn parse_parametric_type(s: &str) -> Result<DataType, String> {
     if let Some(inner) = s.strip_prefix("varchar(").and_then(|x| x.strip_suffix(')')) {
         let n: usize = inner
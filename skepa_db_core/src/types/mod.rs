@@ -1,7 +1,17 @@
 pub mod datatype;
+pub mod uuid_gen;
 pub mod value;
 
 use value::Value;
 
 /// A row is a vector of values, one per column
 pub type Row = Vec<Value>;
+
+/// Stable id of a stored row within its table. A committed row keeps its id
+/// across updates, checkpoints and reopening for as long as it exists. A new
+/// row gets an id above every row in the table, but once a row is deleted a
+/// later insert may be given its id. ORDER BY breaks ties on it. A row
+/// inserted in a transaction holds a provisional id until commit: with
+/// `ConflictDetection::Row`, a commit that reruns on top of another handle's
+/// changes gives it a fresh one.
+pub type RowId = u64;
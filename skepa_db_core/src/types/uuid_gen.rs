@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+use uuid::{Builder, Uuid};
+
+/// Call token accepted in insert value positions and as a column DEFAULT.
+pub const GEN_UUID_CALL: &str = "gen_uuid()";
+
+pub fn is_gen_uuid_call(token: &str) -> bool {
+    token.eq_ignore_ascii_case(GEN_UUID_CALL)
+}
+
+/// Source of v4 uuids for `gen_uuid()`. A seeded generator is a
+/// deterministic splitmix64 stream so tests can assert exact values.
+///
+/// Every uuid handed out is recorded until [`UuidGenerator::take_issued`],
+/// so the WAL can log the values a statement generated; WAL replay queues
+/// them with [`UuidGenerator::replay`] to hand the same ones out again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UuidGenerator {
+    seed_state: Option<u64>,
+    replay: VecDeque<Uuid>,
+    issued: Vec<Uuid>,
+}
+
+impl UuidGenerator {
+    pub fn from_seed(seed: Option<u64>) -> Self {
+        Self {
+            seed_state: seed,
+            replay: VecDeque::new(),
+            issued: Vec::new(),
+        }
+    }
+
+    pub fn next_v4(&mut self) -> Uuid {
+        let uuid = match (self.replay.pop_front(), &mut self.seed_state) {
+            (Some(uuid), _) => uuid,
+            (None, None) => Uuid::new_v4(),
+            (None, Some(state)) => {
+                let mut bytes = [0u8; 16];
+                bytes[..8].copy_from_slice(&splitmix64(state).to_be_bytes());
+                bytes[8..].copy_from_slice(&splitmix64(state).to_be_bytes());
+                Builder::from_random_bytes(bytes).into_uuid()
+            }
+        };
+        self.issued.push(uuid);
+        uuid
+    }
+
+    /// The uuids handed out since the last call, in order.
+    pub fn take_issued(&mut self) -> Vec<Uuid> {
+        std::mem::take(&mut self.issued)
+    }
+
+    /// Hands out `uuids`, in order, before generating any more; replaces
+    /// whatever an earlier call left unused.
+    pub fn replay(&mut self, uuids: Vec<Uuid>) {
+        self.replay = uuids.into();
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
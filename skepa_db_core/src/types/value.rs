@@ -1,6 +1,6 @@
 use crate::types::datatype::DataType;
-use chrono::{NaiveDate, NaiveDateTime};
-use rust_decimal::Decimal;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, TimeDelta};
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
@@ -32,7 +32,7 @@ impl Serialize for Value {
             Value::Bool(value) => serializer.serialize_bool(*value),
             Value::Int(value) => serializer.serialize_i64(*value),
             Value::BigInt(value) => serializer.serialize_str(&value.to_string()),
-            Value::Decimal(value) => serializer.serialize_str(&value.normalize().to_string()),
+            Value::Decimal(value) => serializer.serialize_str(&value.to_string()),
             Value::VarChar(value) | Value::Text(value) => serializer.serialize_str(value),
             Value::Date(value) => serializer.serialize_str(&value.format("%Y-%m-%d").to_string()),
             Value::Timestamp(value) => {
@@ -94,25 +94,62 @@ pub fn parse_value(dtype: &DataType, token: &str) -> Result<Value, String> {
     if token.eq_ignore_ascii_case("null") {
         return Ok(Value::Null);
     }
+    parse_non_null(dtype, token)
+}
+
+/// Converts a stored value to `dtype` the way [`parse_value`] would read its
+/// text, except that only `Value::Null` becomes null: a text value `null`
+/// stays text.
+pub fn convert_value(dtype: &DataType, v: &Value) -> Result<Value, String> {
+    match v {
+        Value::Null => Ok(Value::Null),
+        v => parse_non_null(dtype, &value_to_string(v)),
+    }
+}
+
+fn parse_non_null(dtype: &DataType, token: &str) -> Result<Value, String> {
     match dtype {
         DataType::Bool => parse_bool(token).map(Value::Bool),
         DataType::Int => {
+            check_numeric_literal(token, "int")?;
             let n: i64 = token
                 .parse()
                 .map_err(|_| format!("Expected int but got '{token}'"))?;
             Ok(Value::Int(n))
         }
         DataType::BigInt => {
+            check_numeric_literal(token, "bigint")?;
+            if let Some(expanded) = expand_scientific(token) {
+                let expanded = expanded?;
+                let (int_part, frac_part) = expanded.split_once('.').unwrap_or((&expanded, ""));
+                if frac_part.chars().any(|c| c != '0') {
+                    return Err(format!(
+                        "Expected bigint but got '{token}', which is not a whole number"
+                    ));
+                }
+                let n: i128 = int_part
+                    .parse()
+                    .map_err(|_| format!("Bigint literal '{token}' is out of range"))?;
+                return Ok(Value::BigInt(n));
+            }
             let n: i128 = token
                 .parse()
                 .map_err(|_| format!("Expected bigint but got '{token}'"))?;
             Ok(Value::BigInt(n))
         }
         DataType::Decimal { precision, scale } => {
-            let d = token
-                .parse::<Decimal>()
-                .map_err(|_| format!("Expected decimal but got '{token}'"))?;
+            check_numeric_literal(token, "decimal")?;
+            let d = match expand_scientific(token) {
+                Some(expanded) => expanded?
+                    .parse::<Decimal>()
+                    .map_err(|_| format!("Decimal literal '{token}' is out of range"))?,
+                None => token
+                    .parse::<Decimal>()
+                    .map_err(|_| format!("Expected decimal but got '{token}'"))?,
+            };
             validate_decimal_bounds(&d, *precision, *scale)?;
+            let mut d = d;
+            d.rescale(*scale);
             Ok(Value::Decimal(d))
         }
         DataType::VarChar(max) => {
@@ -133,14 +170,13 @@ pub fn parse_value(dtype: &DataType, token: &str) -> Result<Value, String> {
             Ok(Value::Timestamp(ts))
         }
         DataType::Uuid => {
-            let u =
-                Uuid::parse_str(token).map_err(|_| format!("Expected uuid but got '{token}'"))?;
+            let u = parse_uuid(token)?;
             Ok(Value::Uuid(u))
         }
         DataType::Json => {
             let j: JsonValue = serde_json::from_str(token)
                 .map_err(|_| format!("Expected valid JSON but got '{token}'"))?;
-            Ok(Value::Json(j))
+            Ok(Value::Json(canonicalize_json(j)))
         }
         DataType::Blob => {
             let raw = token.strip_prefix("0x").unwrap_or(token);
@@ -157,7 +193,7 @@ pub fn value_to_string(v: &Value) -> String {
         Value::Bool(b) => b.to_string(),
         Value::Int(n) => n.to_string(),
         Value::BigInt(n) => n.to_string(),
-        Value::Decimal(d) => d.normalize().to_string(),
+        Value::Decimal(d) => d.to_string(),
         Value::VarChar(s) => s.clone(),
         Value::Text(s) => s.clone(),
         Value::Date(d) => d.format("%Y-%m-%d").to_string(),
@@ -168,6 +204,108 @@ pub fn value_to_string(v: &Value) -> String {
     }
 }
 
+/// Text equality key stored in index snapshots. Unlike [`value_to_string`],
+/// decimals are normalized so `1.1` and `1.10` share a key whatever scale
+/// they carry. In-memory keying uses [`value_key`] instead.
+pub(crate) fn index_key(v: &Value) -> String {
+    match v {
+        Value::Decimal(d) => d.normalize().to_string(),
+        _ => value_to_string(v),
+    }
+}
+
+/// Canonical equality key for dedupe, grouping, join hashing and IN sets.
+/// Each value is a type tag followed by a fixed-width or length-prefixed
+/// payload, so keys of several values can be concatenated without
+/// separators and never collide. Int and bigint share a tag (they join on
+/// numeric value), as do text and varchar; decimals are normalized.
+pub(crate) fn value_key(v: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_value_key(&mut out, v);
+    out
+}
+
+/// Appends the [`value_key`] of `v` to `out`.
+pub(crate) fn push_value_key(out: &mut Vec<u8>, v: &Value) {
+    fn push_bytes(out: &mut Vec<u8>, tag: u8, bytes: &[u8]) {
+        out.push(tag);
+        out.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        out.extend_from_slice(bytes);
+    }
+    match v {
+        Value::Null => out.push(0),
+        Value::Bool(b) => out.extend_from_slice(&[1, u8::from(*b)]),
+        Value::Int(n) => {
+            out.push(2);
+            out.extend_from_slice(&i128::from(*n).to_be_bytes());
+        }
+        Value::BigInt(n) => {
+            out.push(2);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+        Value::Decimal(d) => push_bytes(out, 3, d.normalize().to_string().as_bytes()),
+        Value::VarChar(s) | Value::Text(s) => push_bytes(out, 4, s.as_bytes()),
+        Value::Date(d) => {
+            out.push(5);
+            out.extend_from_slice(&d.num_days_from_ce().to_be_bytes());
+        }
+        Value::Timestamp(ts) => {
+            let ts = ts.and_utc();
+            out.push(6);
+            out.extend_from_slice(&ts.timestamp().to_be_bytes());
+            out.extend_from_slice(&ts.timestamp_subsec_nanos().to_be_bytes());
+        }
+        Value::Uuid(u) => {
+            out.push(7);
+            out.extend_from_slice(u.as_bytes());
+        }
+        Value::Json(j) => push_bytes(out, 8, j.to_string().as_bytes()),
+        Value::Blob(b) => push_bytes(out, 9, b),
+    }
+}
+
+/// Display-only rendering options; stored values are never changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Rounds decimals half away from zero and pads them to this many
+    /// places. `None` prints the stored value with trailing zeros trimmed.
+    pub decimal_places: Option<u32>,
+}
+
+/// [`value_to_string`] with `options` applied.
+pub fn format_value(v: &Value, options: &FormatOptions) -> String {
+    match (v, options.decimal_places) {
+        (Value::Decimal(d), Some(places)) => {
+            let mut rounded =
+                d.round_dp_with_strategy(places, RoundingStrategy::MidpointAwayFromZero);
+            rounded.rescale(places);
+            rounded.to_string()
+        }
+        _ => value_to_string(v),
+    }
+}
+
+/// Sorts object keys recursively so equal documents compare, group and
+/// serialize identically whatever key order they were written in.
+fn canonicalize_json(j: JsonValue) -> JsonValue {
+    match j {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<(String, JsonValue)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            JsonValue::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonicalize_json(v)))
+                    .collect(),
+            )
+        }
+        JsonValue::Array(items) => {
+            JsonValue::Array(items.into_iter().map(canonicalize_json).collect())
+        }
+        other => other,
+    }
+}
+
 fn parse_bool(token: &str) -> Result<bool, String> {
     match token.to_lowercase().as_str() {
         "true" | "1" => Ok(true),
@@ -176,10 +314,175 @@ fn parse_bool(token: &str) -> Result<bool, String> {
     }
 }
 
+/// Parses a timestamp literal and normalizes it to UTC.
+///
+/// Accepts `YYYY-MM-DD HH:MM:SS` or `YYYY-MM-DDTHH:MM:SS`, optionally followed by
+/// `Z` or a `+HH:MM` / `-HH:MM` offset. Literals without an offset are taken as UTC.
 fn parse_timestamp(token: &str) -> Result<NaiveDateTime, String> {
-    NaiveDateTime::parse_from_str(token, "%Y-%m-%d %H:%M:%S")
-        .or_else(|_| NaiveDateTime::parse_from_str(token, "%Y-%m-%dT%H:%M:%S"))
-        .map_err(|_| format!("Expected timestamp 'YYYY-MM-DD HH:MM:SS' but got '{token}'"))
+    let err = || format!("Expected timestamp 'YYYY-MM-DD HH:MM:SS[+HH:MM]' but got '{token}'");
+    let (local, offset_secs) = split_utc_offset(token).ok_or_else(err)?;
+    let ts = NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(local, "%Y-%m-%dT%H:%M:%S"))
+        .map_err(|_| err())?;
+    ts.checked_sub_signed(TimeDelta::seconds(offset_secs))
+        .ok_or_else(err)
+}
+
+fn split_utc_offset(token: &str) -> Option<(&str, i64)> {
+    if let Some(local) = token.strip_suffix(['Z', 'z']) {
+        return Some((local, 0));
+    }
+    // The date/time part is always 19 chars; anything after it must be an offset.
+    let (Some(local), Some(suffix)) = (token.get(..19), token.get(19..)) else {
+        return Some((token, 0));
+    };
+    if suffix.is_empty() {
+        return Some((local, 0));
+    }
+    let sign = match suffix.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = suffix[1..].replace(':', "");
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i64>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i64>().ok()?,
+            digits[2..].parse::<i64>().ok()?,
+        ),
+        _ => return None,
+    };
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some((local, sign * (hours * 3600 + minutes * 60)))
+}
+
+/// Strict 8-4-4-4-12 hyphenated hex. Uppercase is accepted; values render lowercase.
+fn parse_uuid(token: &str) -> Result<Uuid, String> {
+    let err = || format!("Expected uuid xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx but got '{token}'");
+    let bytes = token.as_bytes();
+    if bytes.len() != 36 {
+        return Err(err());
+    }
+    for (i, b) in bytes.iter().enumerate() {
+        let ok = match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        };
+        if !ok {
+            return Err(err());
+        }
+    }
+    Uuid::parse_str(token).map_err(|_| err())
+}
+
+/// Largest exponent magnitude accepted in scientific notation; beyond it
+/// no bigint or decimal value can be represented.
+const MAX_SCIENTIFIC_EXPONENT: i64 = 64;
+
+/// Checks `token` against the numeric literal grammar int, bigint and
+/// decimal share: `[+-]digits[.digits][(e|E)[+-]digits]`, with a digit on
+/// each side of the point. Each type then decides whether it takes a
+/// fraction or an exponent. Separators, bare points and NaN/infinity get
+/// their own message, since the number parsers would accept some of them.
+fn check_numeric_literal(token: &str, type_name: &str) -> Result<(), String> {
+    let unsigned = token.strip_prefix(['+', '-']).unwrap_or(token);
+    if ["nan", "inf", "infinity"]
+        .iter()
+        .any(|w| unsigned.eq_ignore_ascii_case(w))
+    {
+        return Err(format!(
+            "Expected {type_name} but got '{token}': NaN and infinity are not numbers"
+        ));
+    }
+    if token.contains([',', '_']) || token.contains(char::is_whitespace) {
+        return Err(format!(
+            "Expected {type_name} but got '{token}': write digits without ',', '_' or spaces, with '.' as the decimal point"
+        ));
+    }
+    let (mantissa, exp) = match unsigned.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e.strip_prefix(['+', '-']).unwrap_or(e))),
+        None => (unsigned, None),
+    };
+    let (int_digits, frac_digits) = match mantissa.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (mantissa, None),
+    };
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if let Some(frac) = frac_digits
+        && (int_digits.is_empty() || frac.is_empty())
+        && (is_digits(int_digits) || is_digits(frac))
+    {
+        return Err(format!(
+            "Expected {type_name} but got '{token}': write a digit on each side of '.', as in 0.5 or 5.0"
+        ));
+    }
+    if !is_digits(int_digits) || !frac_digits.is_none_or(is_digits) || !exp.is_none_or(is_digits) {
+        return Err(format!("Expected {type_name} but got '{token}'"));
+    }
+    Ok(())
+}
+
+/// Expands `[+-]?mantissa[eE][+-]?exp` into positional notation by moving
+/// the decimal point in the digit string, so no precision is lost to
+/// floating point. Returns `None` when `token` is not in that form.
+fn expand_scientific(token: &str) -> Option<Result<String, String>> {
+    let (mantissa, exp) = token.split_once(['e', 'E'])?;
+    let (negative, mantissa) = match mantissa.as_bytes().first()? {
+        b'-' => (true, &mantissa[1..]),
+        b'+' => (false, &mantissa[1..]),
+        _ => (false, mantissa),
+    };
+    let (int_digits, frac_digits) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{int_digits}{frac_digits}");
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let exp_digits = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+    if exp_digits.is_empty() || !exp_digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let exp: i64 = match exp.parse() {
+        Ok(e) if (-MAX_SCIENTIFIC_EXPONENT..=MAX_SCIENTIFIC_EXPONENT).contains(&e) => e,
+        _ => return Some(Err(format!("Numeric literal '{token}' is out of range"))),
+    };
+
+    let point = int_digits.len() as i64 + exp;
+    let mut out = String::new();
+    if negative {
+        out.push('-');
+    }
+    if point <= 0 {
+        out.push_str("0.");
+        out.push_str(&"0".repeat((-point) as usize));
+        out.push_str(&digits);
+    } else if point as usize >= digits.len() {
+        out.push_str(&digits);
+        out.push_str(&"0".repeat(point as usize - digits.len()));
+    } else {
+        let (whole, frac) = digits.split_at(point as usize);
+        out.push_str(whole);
+        out.push('.');
+        out.push_str(frac);
+    }
+    Some(Ok(out))
+}
+
+/// Rounds `d` half away from zero to at most `scale` places, without
+/// padding, and checks the result fits `decimal(precision, scale)`.
+pub(crate) fn fit_decimal(d: Decimal, precision: u32, scale: u32) -> Result<Decimal, String> {
+    let d = if d.scale() > scale {
+        d.round_dp_with_strategy(scale, RoundingStrategy::MidpointAwayFromZero)
+    } else {
+        d
+    };
+    validate_decimal_bounds(&d, precision, scale)?;
+    Ok(d)
 }
 
 fn validate_decimal_bounds(d: &Decimal, precision: u32, scale: u32) -> Result<(), String> {
@@ -190,8 +493,15 @@ fn validate_decimal_bounds(d: &Decimal, precision: u32, scale: u32) -> Result<()
             actual_scale, scale
         ));
     }
-    let s = d.abs().normalize().to_string();
-    let digits = s.chars().filter(|c| c.is_ascii_digit()).count() as u32;
+    // Stored values are padded to `scale` places, so those all count
+    // towards the precision along with the integer digits.
+    let whole = d.abs().trunc();
+    let integer_digits = if whole.is_zero() {
+        0
+    } else {
+        whole.normalize().to_string().len() as u32
+    };
+    let digits = integer_digits + scale;
     if digits > precision {
         return Err(format!(
             "Decimal precision {} exceeds allowed precision {}",
@@ -0,0 +1,302 @@
+use super::*;
+
+/// Views defined on views nested deeper than this fail instead of
+/// materializing without end.
+const MAX_VIEW_DEPTH: usize = 16;
+
+impl Database {
+    /// Stores `query` as view `name`. The query runs once first, so a view
+    /// over a missing table or column is rejected here rather than on its
+    /// first read. A materialized view keeps that first result as a table.
+    pub(super) fn create_view(
+        &mut self,
+        name: &str,
+        materialized: bool,
+        query: Command,
+        sql: &str,
+    ) -> DbResult<()> {
+        self.check_view_ddl_outside_tx()?;
+        match &query {
+            Command::Select { as_of: Some(_), .. } => {
+                return Err(DbError::from("A view cannot read AS OF a snapshot"));
+            }
+            Command::Select {
+                for_update: true, ..
+            } => return Err(DbError::from("A view cannot select FOR UPDATE")),
+            Command::Select { .. } | Command::SetOp { .. } => {}
+            _ => return Err(DbError::from("A view must be defined by a SELECT")),
+        }
+        if self.names_attached_table(&query) {
+            return Err(DbError::from("A view cannot read an attached database"));
+        }
+        if restricted::referenced_tables(&query).contains(&name) {
+            return Err(DbError::from(format!("View '{name}' cannot read itself")));
+        }
+        let out = self
+            .execute_view_read(query.clone(), 0)
+            .map_err(DbError::from)?;
+        let view = storage::ViewDef {
+            sql: sql.to_string(),
+            query,
+            materialized,
+        };
+        if !materialized {
+            self.catalog
+                .create_view(name.to_string(), view, None)
+                .map_err(DbError::from)?;
+            if let Err(e) = self.save_catalog() {
+                let _ = self.catalog.drop_view(name);
+                return Err(DbError::from(e));
+            }
+            return Ok(());
+        }
+        let (schema, rows) = view_table(name, out).map_err(DbError::from)?;
+        let before = (self.catalog.clone(), self.storage.snapshot_table(name));
+        self.catalog
+            .create_view(name.to_string(), view, Some(schema.clone()))
+            .map_err(DbError::from)?;
+        if let Err(e) = self.storage.load_rows(name, &schema, rows) {
+            self.catalog = before.0;
+            self.storage.restore_table(name, before.1);
+            return Err(DbError::from(e));
+        }
+        self.persist_schema_change(Some(name), Some(before))
+            .map_err(DbError::from)
+    }
+
+    /// Reruns the query of materialized view `name` and replaces its rows
+    /// with the result, returning how many there are now. The new rows are
+    /// written the way a schema change rewrites a table, so a failed write
+    /// leaves the old rows in place.
+    pub(super) fn refresh_materialized_view(&mut self, name: &str) -> DbResult<usize> {
+        self.check_view_ddl_outside_tx()?;
+        let query = self
+            .catalog
+            .view(name)
+            .filter(|view| view.materialized)
+            .map(|view| view.query.clone())
+            .ok_or_else(|| DbError::from(format!("Materialized view '{name}' does not exist")))?;
+        let out = self.execute_view_read(query, 0).map_err(DbError::from)?;
+        let (schema, rows) = view_table(name, out).map_err(DbError::from)?;
+        let count = rows.len();
+        let before = (self.catalog.clone(), self.storage.snapshot_table(name));
+        let loaded = self
+            .catalog
+            .set_materialized_schema(name, schema.clone())
+            .and_then(|()| self.storage.load_rows(name, &schema, rows));
+        if let Err(e) = loaded {
+            self.catalog = before.0;
+            self.storage.restore_table(name, before.1);
+            return Err(DbError::from(e));
+        }
+        self.persist_schema_change(Some(name), Some(before))
+            .map_err(DbError::from)?;
+        Ok(count)
+    }
+
+    pub(super) fn drop_view(&mut self, name: &str) -> DbResult<()> {
+        self.check_view_ddl_outside_tx()?;
+        let before = self.catalog.clone();
+        let view = self.catalog.drop_view(name).map_err(DbError::from)?;
+        if let Err(e) = self.save_catalog() {
+            self.catalog = before;
+            return Err(DbError::from(e));
+        }
+        if view.materialized {
+            self.storage.remove_table(name).map_err(DbError::from)?;
+        }
+        Ok(())
+    }
+
+    fn check_view_ddl_outside_tx(&self) -> DbResult<()> {
+        if self.current_tx.is_some() {
+            return Err(DbError::from(
+                "CREATE/DROP VIEW and REFRESH MATERIALIZED VIEW are auto-commit and cannot run inside an active transaction",
+            ));
+        }
+        Ok(())
+    }
+
+    /// `show tables`: every table and view with its kind, sorted by name.
+    /// The table holding a materialized view's rows is listed as the view.
+    /// `with_stats` adds `rows`, `bytes` and `indexes` columns from
+    /// [`Database::table_stats`], null for a plain view.
+    pub(super) fn show_tables(&self, with_stats: bool) -> QueryResult {
+        let column =
+            |name: &str, dtype: types::datatype::DataType, not_null: bool| storage::Column {
+                name: name.to_string(),
+                dtype,
+                primary_key: false,
+                unique: false,
+                not_null,
+                default: None,
+                json_shape: None,
+            };
+        let mut columns = vec![
+            column("name", types::datatype::DataType::Text, true),
+            column("type", types::datatype::DataType::Text, true),
+        ];
+        if with_stats {
+            for name in ["rows", "bytes", "indexes"] {
+                columns.push(column(name, types::datatype::DataType::BigInt, false));
+            }
+        }
+        let schema = Schema::new(columns);
+        let mut entries: Vec<(String, &str)> = self
+            .catalog
+            .table_names()
+            .into_iter()
+            .filter(|name| self.catalog.view(name).is_none())
+            .map(|name| (name, "table"))
+            .chain(self.catalog.view_names().into_iter().map(|name| {
+                let kind = if self.catalog.view(&name).is_some_and(|v| v.materialized) {
+                    "materialized view"
+                } else {
+                    "view"
+                };
+                (name, kind)
+            }))
+            .collect();
+        entries.sort();
+        let rows = entries
+            .into_iter()
+            .map(|(name, kind)| {
+                let stats = (with_stats && kind != "view")
+                    .then(|| self.table_stats(&name).ok())
+                    .flatten();
+                let mut row = vec![
+                    types::value::Value::Text(name),
+                    types::value::Value::Text(kind.to_string()),
+                ];
+                if with_stats {
+                    let counts = stats.map_or([None; 3], |s| {
+                        [
+                            Some(s.rows as u64),
+                            Some(s.bytes),
+                            Some(s.index_count as u64),
+                        ]
+                    });
+                    row.extend(counts.into_iter().map(|n| {
+                        n.map_or(types::value::Value::Null, |n| {
+                            types::value::Value::BigInt(n.into())
+                        })
+                    }));
+                }
+                row
+            })
+            .collect();
+        QueryResult::select(schema, rows)
+    }
+
+    /// Whether `cmd` names a view.
+    pub(super) fn names_view(&self, cmd: &Command) -> bool {
+        restricted::referenced_tables(cmd)
+            .iter()
+            .any(|t| self.catalog.view(t).is_some())
+    }
+
+    /// Runs a statement naming a view. SELECT (without `for update`),
+    /// INTERSECT/EXCEPT, DESCRIBE and SHOW INDEXES read the view's rows, or a materialized
+    /// view's stored rows; anything else is a write and fails.
+    pub(super) fn execute_on_view(&self, cmd: Command) -> Result<QueryResult, String> {
+        let read = match &cmd {
+            Command::Select { for_update, .. } => !for_update,
+            Command::SetOp { .. } | Command::Describe { .. } | Command::ShowIndexes { .. } => true,
+            _ => false,
+        };
+        if !read {
+            let tables = restricted::referenced_tables(&cmd);
+            let view = tables
+                .iter()
+                .find(|t| self.catalog.view(t).is_some())
+                .copied()
+                .unwrap_or_default();
+            // UPDATE and DELETE write only their first table; a view after
+            // it is read by a subquery.
+            let filtered = matches!(cmd, Command::Update { .. } | Command::Delete { .. });
+            if filtered && tables.first() != Some(&view) {
+                return Err(format!(
+                    "A subquery in UPDATE or DELETE cannot read view '{view}'"
+                ));
+            }
+            return Err(format!("Cannot modify view '{view}'"));
+        }
+        self.execute_view_read(cmd, 0)
+    }
+
+    /// The catalog a statement naming a view resolves against, with each
+    /// view it names registered as a table.
+    pub(super) fn view_catalog(&self, cmd: &Command) -> Result<Catalog, String> {
+        Ok(self.view_scope(cmd, 0)?.0)
+    }
+
+    /// A view that is computed on each read: materialized views are read
+    /// like the tables they are stored as.
+    fn plain_view(&self, name: &str) -> Option<&storage::ViewDef> {
+        self.catalog.view(name).filter(|view| !view.materialized)
+    }
+
+    fn execute_view_read(&self, cmd: Command, depth: usize) -> Result<QueryResult, String> {
+        let names_plain_view = restricted::referenced_tables(&cmd)
+            .iter()
+            .any(|t| self.plain_view(t).is_some());
+        if !names_plain_view {
+            return engine::execute_read(
+                self.read_command(cmd, &self.catalog),
+                &self.catalog,
+                &self.storage,
+                self.max_join_rows,
+            );
+        }
+        let (catalog, storage) = self.view_scope(&cmd, depth)?;
+        engine::execute_read(
+            self.read_command(cmd, &catalog),
+            &catalog,
+            &storage,
+            self.max_join_rows,
+        )
+    }
+
+    /// The live catalog and a storage holding the tables `cmd` names, with
+    /// every plain view among them computed into a table.
+    fn view_scope(&self, cmd: &Command, depth: usize) -> Result<(Catalog, DiskStorage), String> {
+        if depth >= MAX_VIEW_DEPTH {
+            return Err(format!("Views nest more than {MAX_VIEW_DEPTH} levels deep"));
+        }
+        let mut catalog = self.catalog.clone();
+        let mut storage = DiskStorage::new(self.path.clone())?;
+        for name in restricted::referenced_tables(cmd) {
+            if let Some(view) = self.plain_view(name) {
+                let out = self.execute_view_read(view.query.clone(), depth + 1)?;
+                let (schema, rows) = view_table(name, out)?;
+                storage.load_rows(name, &schema, rows)?;
+                catalog.insert_schema(name.to_string(), schema);
+            } else if self.catalog.exists(name) {
+                storage.restore_table(name, self.storage.snapshot_table(name));
+            }
+        }
+        Ok((catalog, storage))
+    }
+}
+
+/// The schema and rows of a view's SELECT result, as the table the view is
+/// read as. Its columns carry no constraints.
+fn view_table(name: &str, out: QueryResult) -> Result<(Schema, Vec<Row>), String> {
+    let QueryResult::Select { schema, rows, .. } = out else {
+        return Err(format!("View '{name}' did not produce rows"));
+    };
+    let schema = Schema::new(
+        schema
+            .columns
+            .into_iter()
+            .map(|c| storage::Column {
+                primary_key: false,
+                unique: false,
+                not_null: false,
+                default: None,
+                ..c
+            })
+            .collect(),
+    );
+    Ok((schema, rows))
+}
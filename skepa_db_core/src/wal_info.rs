@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// State of the write-ahead log on a `Database` handle, as `show wal`
+/// reports it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalInfo {
+    /// Current length of `wal.log`.
+    pub bytes: u64,
+    /// Committed transaction groups in the WAL that no checkpoint has
+    /// written to the table files yet.
+    pub pending_transactions: usize,
+}
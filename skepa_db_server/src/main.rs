@@ -5,6 +5,7 @@ use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 use skepa_db_core::Database;
 use skepa_db_core::config::DbConfig;
+use skepa_db_core::error::{DbError, DbErrorKind};
 use skepa_db_core::parser::command::Command;
 use skepa_db_core::parser::parser::parse;
 use skepa_db_core::query_result::QueryResult;
@@ -259,6 +260,8 @@ enum ApiErrorCode {
     DuplicateIndex,
     UnsupportedSyntax,
     Conflict,
+    TableFileMissing,
+    StatementNotPermitted,
     Timeout,
     ExecutionError,
 }
@@ -279,6 +282,7 @@ struct BatchResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 enum ApiQueryResult {
     Select {
         schema: Schema,
@@ -308,7 +312,7 @@ impl From<QueryResult> for ApiQueryResult {
                 rows,
                 stats,
             } => Self::Select {
-                schema,
+                schema: *schema,
                 rows,
                 stats,
             },
@@ -1230,36 +1234,20 @@ fn map_db_error_response(
     message: impl Into<String>,
 ) -> (StatusCode, Json<ErrorResponse>) {
     let message = message.into();
-    let lowercase = message.to_lowercase();
-    let code = if lowercase.contains("unique")
-        || lowercase.contains("primary key")
-        || lowercase.contains("duplicate")
-    {
-        ApiErrorCode::UniqueViolation
-    } else if lowercase.contains("not null") {
-        ApiErrorCode::NotNullViolation
-    } else if lowercase.contains("foreign key") || lowercase.contains("references") {
-        ApiErrorCode::ForeignKeyViolation
-    } else if lowercase.contains("invalid default") {
-        ApiErrorCode::InvalidDefault
-    } else if lowercase.contains("does not exist")
-        && (lowercase.contains("table") || lowercase.contains("storage"))
-    {
-        ApiErrorCode::UnknownTable
-    } else if lowercase.contains("unknown column") {
-        ApiErrorCode::UnknownColumn
-    } else if lowercase.contains("index")
-        && (lowercase.contains("already exists") || lowercase.contains("duplicate"))
-    {
-        ApiErrorCode::DuplicateIndex
-    } else if lowercase.contains("not supported") || lowercase.contains("unsupported") {
-        ApiErrorCode::UnsupportedSyntax
-    } else if lowercase.contains("conflict") {
-        ApiErrorCode::Conflict
-    } else if lowercase.contains("parse") || lowercase.contains("syntax") {
-        ApiErrorCode::SqlParseError
-    } else {
-        ApiErrorCode::ExecutionError
+    let code = match DbError::from(message.clone()).kind() {
+        DbErrorKind::SqlParse => ApiErrorCode::SqlParseError,
+        DbErrorKind::UniqueViolation => ApiErrorCode::UniqueViolation,
+        DbErrorKind::NotNullViolation => ApiErrorCode::NotNullViolation,
+        DbErrorKind::ForeignKeyViolation => ApiErrorCode::ForeignKeyViolation,
+        DbErrorKind::InvalidDefault => ApiErrorCode::InvalidDefault,
+        DbErrorKind::UnknownTable => ApiErrorCode::UnknownTable,
+        DbErrorKind::UnknownColumn => ApiErrorCode::UnknownColumn,
+        DbErrorKind::DuplicateIndex => ApiErrorCode::DuplicateIndex,
+        DbErrorKind::UnsupportedSyntax => ApiErrorCode::UnsupportedSyntax,
+        DbErrorKind::Conflict => ApiErrorCode::Conflict,
+        DbErrorKind::MissingTableFile => ApiErrorCode::TableFileMissing,
+        DbErrorKind::NotPermitted => ApiErrorCode::StatementNotPermitted,
+        DbErrorKind::Execution => ApiErrorCode::ExecutionError,
     };
 
     error_response(request_id, code, message)
@@ -1750,7 +1738,7 @@ mod tests {
         let json: Value = serde_json::from_slice(&body).expect("json body should parse");
         assert_eq!(json["server"], "skepa_db_server");
         assert_eq!(json["api_version"], "v1");
-        assert_eq!(json["storage_format_version"], 1);
+        assert_eq!(json["storage_format_version"], 2);
         assert!(json["version"].as_str().is_some());
     }
 
@@ -2050,6 +2038,51 @@ mod tests {
         assert_eq!(session_commit_response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn session_transaction_is_invisible_to_global_reads_until_commit() {
+        async fn run(app: &Router, uri: &str, sql: &str) -> Value {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .body(Body::from(json!({ "sql": sql }).to_string()))
+                        .expect("request should build"),
+                )
+                .await
+                .expect("request should succeed");
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body should read");
+            serde_json::from_slice(&body).expect("json body should parse")
+        }
+
+        let app = test_app().await;
+        run(&app, "/execute", "create table t (id int)").await;
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/session")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("request should succeed");
+
+        run(&app, "/session/1/execute", "begin").await;
+        run(&app, "/session/1/execute", "insert into t values (1)").await;
+        let before = run(&app, "/execute", "select * from t").await;
+        assert_eq!(before["result"]["rows"], json!([]));
+
+        run(&app, "/session/1/execute", "commit").await;
+        let after = run(&app, "/execute", "select * from t").await;
+        assert_eq!(after["result"]["rows"], json!([[1]]));
+    }
+
     #[tokio::test]
     async fn session_execute_rejects_unknown_session() {
         let app = test_app().await;
@@ -2582,7 +2615,7 @@ mod tests {
             "skepa-db-server-config-test"
         );
         assert_eq!(json["config"]["api_version"], "v1");
-        assert_eq!(json["config"]["storage_format_version"], 1);
+        assert_eq!(json["config"]["storage_format_version"], 2);
         assert!(json["config"].get("auth_token").is_none());
     }
 
@@ -3017,6 +3050,70 @@ mod tests {
         assert_eq!(json["result"]["rows"][0][1], "ram");
     }
 
+    #[tokio::test]
+    async fn import_export_round_trips_table_and_column_comments() {
+        async fn send(app: &Router, method: Method, uri: &str, body: String) -> Value {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .expect("request should build"),
+                )
+                .await
+                .expect("request should succeed");
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("body should read");
+            serde_json::from_slice(&body).expect("json body should parse")
+        }
+
+        let app = test_app().await;
+        send(
+            &app,
+            Method::POST,
+            "/databases",
+            json!({"name": "docs"}).to_string(),
+        )
+        .await;
+        for sql in [
+            "create table users (id int, email text)",
+            r#"comment on table users is "app users""#,
+            r#"comment on column users.email is "login email""#,
+        ] {
+            send(
+                &app,
+                Method::POST,
+                "/databases/docs/execute",
+                json!({ "sql": sql }).to_string(),
+            )
+            .await;
+        }
+
+        let export_json = send(&app, Method::GET, "/databases/docs/export", String::new()).await;
+        send(
+            &app,
+            Method::POST,
+            "/databases/docs_copy/import",
+            json!({ "export": export_json["export"], "overwrite": false }).to_string(),
+        )
+        .await;
+
+        let json = send(
+            &app,
+            Method::POST,
+            "/databases/docs_copy/execute",
+            json!({ "sql": "describe users" }).to_string(),
+        )
+        .await;
+        assert_eq!(json["result"]["rows"][1][7], "login email");
+        assert_eq!(json["result"]["rows"][1][8], "app users");
+    }
+
     #[tokio::test]
     async fn import_export_is_fallback_when_original_database_format_becomes_unsupported() {
         let state = test_state().await;
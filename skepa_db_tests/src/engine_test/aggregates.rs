@@ -1,4 +1,5 @@
 use super::*;
+use skepa_db_core::types::value::{FormatOptions, format_value, value_to_string};
 
 #[test]
 fn test_select_group_by_count_star() {
@@ -239,6 +240,46 @@ fn test_select_aggregate_rejects_star_projection() {
     assert!(err.to_lowercase().contains("cannot be used with group by"));
 }
 
+#[test]
+fn test_select_star_with_group_by_fails_before_reading_rows() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, city text)")
+        .unwrap();
+    for sql in [
+        "select * from t where missing = 1 group by city",
+        "select *, count(*) from t group by city",
+    ] {
+        let err = db.execute_legacy(sql).unwrap_err();
+        assert_eq!(
+            err,
+            "SELECT * cannot be used with GROUP BY; list the grouped columns and aggregates explicitly",
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_select_star_with_aggregate_names_the_aggregate() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, city text)")
+        .unwrap();
+    for (sql, agg) in [
+        ("select *, count(*) from t", "count(*)"),
+        ("select sum(id) as total, * from t", "sum(id) as total"),
+    ] {
+        let err = db.execute_legacy(sql).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "SELECT * cannot be combined with aggregate {agg}; select the aggregates on their own or add GROUP BY with explicit columns"
+            ),
+            "{sql}"
+        );
+        let err = db.describe_statement(sql).unwrap_err().to_string();
+        assert!(err.contains("cannot be combined with aggregate"), "{err}");
+    }
+}
+
 #[test]
 fn test_select_aggregate_rejects_invalid_sum_type() {
     let mut db = test_db();
@@ -367,6 +408,43 @@ fn test_select_group_by_order_by_sum_desc() {
     );
 }
 
+#[test]
+fn test_select_group_by_order_by_aggregate_expression() {
+    let mut db = test_db();
+    db.execute("create table t (id int, city text, v int)")
+        .unwrap();
+    for (id, city, v) in [(1, "ny", 10), (2, "ny", 20), (3, "la", 5), (4, "sf", 7)] {
+        db.execute(&format!(r#"insert into t values ({id}, "{city}", {v})"#))
+            .unwrap();
+    }
+
+    for sql in [
+        "select city, count(id) from t group by city order by COUNT( id ) desc, city limit 2",
+        "select city, count(id) as n from t group by city order by count(id) desc, city limit 2",
+    ] {
+        let out = db.execute_legacy(sql).unwrap();
+        let header = if sql.contains(" as n") {
+            "city\tn"
+        } else {
+            "city\tcount(id)"
+        };
+        assert_eq!(out, format!("{header}\nny\t2\nla\t1"), "{sql}");
+        assert!(db.describe_statement(sql).is_ok(), "{sql}");
+    }
+
+    let err = db
+        .execute_legacy("select city, count(id) from t group by city order by sum(v) desc")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "ORDER BY expression 'sum(v)' is not in the select list of a grouped query; add it to the select list to order by it"
+    );
+    let err = db
+        .execute_legacy("select city, count(distinct id) from t group by city order by count(id)")
+        .unwrap_err();
+    assert!(err.contains("add it to the select list"), "{err}");
+}
+
 #[test]
 fn test_select_projection_alias_headers() {
     let mut db = test_db();
@@ -517,6 +595,38 @@ fn test_select_global_aggregates_on_empty_table_returns_one_row() {
     );
 }
 
+#[test]
+fn test_select_global_aggregate_shapes_on_empty_input_return_one_row() {
+    let mut db = test_db();
+    db.execute("create table t (id int, v int, d decimal(5,2))")
+        .unwrap();
+    db.execute("create table u (id int, t_id int)").unwrap();
+    let out = db
+        .execute("select count(distinct v) as n, sum(distinct v), sum(d), avg(d) from t")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["n", "sum(distinct v)", "sum(d)", "avg(d)"],
+        vec![vec![
+            Value::BigInt(0),
+            Value::Null,
+            Value::Null,
+            Value::Null,
+        ]],
+    );
+    for sql in [
+        "select count(*) from t join u on t.id = u.t_id",
+        "select count(*) from t having count(*) = 0",
+        "select distinct count(*) from t order by count(*) asc limit 1",
+    ] {
+        let out = db.execute(sql).unwrap();
+        assert_select_result(out, &["count(*)"], vec![vec![Value::BigInt(0)]]);
+    }
+    // With GROUP BY there are no groups, so no rows.
+    let out = db.execute("select count(*) from t group by v").unwrap();
+    assert_select_result(out, &["count(*)"], vec![]);
+}
+
 #[test]
 fn test_select_global_aggregate_with_where_no_rows_returns_one_row() {
     let mut db = test_db();
@@ -537,3 +647,205 @@ fn test_select_global_aggregate_with_where_no_rows_returns_one_row() {
         ]],
     );
 }
+
+#[test]
+fn test_group_by_date_part_expression_matches_select_item() {
+    let mut db = test_db();
+    db.execute("create table e (id int, created date, amount int)")
+        .unwrap();
+    db.execute("insert into e values (1, 2025-01-05, 10)")
+        .unwrap();
+    db.execute("insert into e values (2, 2025-01-20, 20)")
+        .unwrap();
+    db.execute("insert into e values (3, 2025-03-01, 5)")
+        .unwrap();
+
+    let out = db
+        .execute("select month(created), count(*) from e group by month(created)")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["month(created)", "count(*)"],
+        vec![
+            vec![Value::Int(1), Value::BigInt(2)],
+            vec![Value::Int(3), Value::BigInt(1)],
+        ],
+    );
+
+    let out = db
+        .execute_legacy(
+            "select MONTH( created ) as m, sum(amount) from e group by month(created) order by m desc",
+        )
+        .unwrap();
+    assert_eq!(out, "m\tsum(amount)\n3\t5\n1\t30");
+}
+
+#[test]
+fn test_group_by_expression_rejects_unmatched_select_items() {
+    let mut db = test_db();
+    db.execute("create table e (id int, created timestamp)")
+        .unwrap();
+    db.execute(r#"insert into e values (1, "2025-01-05 10:00:00")"#)
+        .unwrap();
+
+    let err = db
+        .execute_legacy("select day(created), count(*) from e group by month(created)")
+        .unwrap_err();
+    assert!(err.contains("'day(created)' must appear in GROUP BY"));
+    let err = db
+        .execute_legacy("select created, count(*) from e group by month(created)")
+        .unwrap_err();
+    assert!(err.contains("'created' must appear in GROUP BY"));
+    let err = db
+        .execute_legacy("select month(id), count(*) from e group by month(id)")
+        .unwrap_err();
+    assert!(err.contains("month() requires a date or timestamp column"));
+}
+
+#[test]
+fn test_format_value_rounds_avg_for_display_only() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    let QueryResult::Select { rows, .. } = db.execute("select avg(age) from users").unwrap() else {
+        panic!("expected select result");
+    };
+    let avg = &rows[0][0];
+    let two = FormatOptions {
+        decimal_places: Some(2),
+    };
+    assert_eq!(format_value(avg, &two), "20.00");
+    assert_eq!(format_value(avg, &FormatOptions::default()), "20");
+    assert_eq!(
+        format_value(avg, &FormatOptions::default()),
+        value_to_string(avg)
+    );
+    assert_eq!(format_value(&Value::Int(7), &two), "7");
+
+    db.execute("create table p (v decimal(10,4))").unwrap();
+    db.execute("insert into p values (-2.3457)").unwrap();
+    let QueryResult::Select { rows, .. } = db.execute("select v from p").unwrap() else {
+        panic!("expected select result");
+    };
+    assert_eq!(format_value(&rows[0][0], &two), "-2.35");
+    // The stored value keeps its scale.
+    assert_eq!(db.execute_legacy("select v from p").unwrap(), "v\n-2.3457");
+}
+
+#[test]
+fn test_multi_column_keys_do_not_collide_on_separator_characters() {
+    let mut db = test_db();
+    db.execute("create table t (a text, b text, n int)")
+        .unwrap();
+    // Joined with a separator, both rows used to render the same key.
+    db.execute("insert into t values (\"x\u{1f}y\", \"z\", 1)")
+        .unwrap();
+    db.execute("insert into t values (\"x\", \"y\u{1f}z\", 2)")
+        .unwrap();
+
+    let out = db
+        .execute_legacy("select distinct a, b from t order by a asc")
+        .unwrap();
+    assert_eq!(out, "a\tb\nx\ty\u{1f}z\nx\u{1f}y\tz");
+    assert_select_result(
+        db.execute("select a, sum(n) from t group by a, b order by a asc")
+            .unwrap(),
+        &["a", "sum(n)"],
+        vec![
+            vec![Value::Text("x".to_string()), Value::Int(2)],
+            vec![Value::Text("x\u{1f}y".to_string()), Value::Int(1)],
+        ],
+    );
+}
+
+#[test]
+fn test_value_keys_keep_equal_values_together() {
+    let mut db = test_db();
+    db.execute("create table t (d decimal(6,2), s varchar(5), n bigint)")
+        .unwrap();
+    db.execute(r#"insert into t values (1.1, "a", 7)"#).unwrap();
+    db.execute(r#"insert into t values (1.10, "a", 7)"#)
+        .unwrap();
+    db.execute(r#"insert into t values (2, "b", 8)"#).unwrap();
+    let out = db
+        .execute_legacy("select count(distinct d), count(distinct s), count(distinct n) from t")
+        .unwrap();
+    assert_eq!(
+        out,
+        "count(distinct d)\tcount(distinct s)\tcount(distinct n)\n2\t2\t2"
+    );
+    let out = db
+        .execute_legacy("select d, count(*) from t group by d order by d asc")
+        .unwrap();
+    assert_eq!(out, "d\tcount(*)\n1.10\t2\n2.00\t1");
+    let out = db
+        .execute_legacy("select n from t where n in (7, 9) order by n asc")
+        .unwrap();
+    assert_eq!(out, "n\n7\n7");
+}
+
+#[test]
+fn test_decimal_sum_and_avg_widen_to_precision_38() {
+    let mut db = test_db();
+    db.execute("create table t (g int, v decimal(5,2))")
+        .unwrap();
+    db.execute("insert into t values (1, 999.99)").unwrap();
+    db.execute("insert into t values (1, 999.99)").unwrap();
+    db.execute("insert into t values (2, 1.00)").unwrap();
+    db.execute("insert into t values (2, 2.00)").unwrap();
+    db.execute("insert into t values (2, 2.00)").unwrap();
+
+    let sql = "select g, sum(v), avg(v) from t group by g order by g asc";
+    let plan = db.describe_statement(sql).unwrap();
+    assert_eq!(plan.output[1].dtype, "decimal(38,2)");
+    assert_eq!(plan.output[2].dtype, "decimal(38,6)");
+    // The sum exceeds the source precision of 5; the average is rounded to
+    // the output scale instead of carrying every digit of the division.
+    assert_eq!(
+        db.execute_legacy(sql).unwrap(),
+        "g\tsum(v)\tavg(v)\n1\t1999.98\t999.99\n2\t5.00\t1.666667"
+    );
+}
+
+#[test]
+fn test_decimal_aggregate_results_materialize_into_declared_columns() {
+    let mut db = test_db();
+    db.execute("create table t (v decimal(5,2))").unwrap();
+    for v in ["999.99", "999.99", "0.01"] {
+        db.execute(&format!("insert into t values ({v})")).unwrap();
+    }
+    let QueryResult::Select { rows, .. } = db.execute("select sum(v), avg(v) from t").unwrap()
+    else {
+        panic!("expected select result");
+    };
+    let (sum, avg) = (value_to_string(&rows[0][0]), value_to_string(&rows[0][1]));
+    assert_eq!((sum.as_str(), avg.as_str()), ("1999.99", "666.663333"));
+
+    db.execute("create table totals (s decimal(38,2), a decimal(38,6))")
+        .unwrap();
+    db.execute(&format!("insert into totals values ({sum}, {avg})"))
+        .unwrap();
+    assert_select_result(
+        db.execute("select * from totals").unwrap(),
+        &["s", "a"],
+        vec![rows[0].clone()],
+    );
+
+    db.execute("create table narrow (s decimal(5,2), a decimal(10,2))")
+        .unwrap();
+    let err = db
+        .execute(&format!("insert into narrow values ({sum}, 0)"))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("Decimal precision 6 exceeds allowed precision 5"),
+        "{err}"
+    );
+    let err = db
+        .execute(&format!("insert into narrow values (0, {avg})"))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("Decimal scale 6 exceeds allowed scale 2"),
+        "{err}"
+    );
+}
@@ -85,6 +85,7 @@ fn test_missing_non_default_column_still_errors() {
         .execute_legacy("insert into users values (1)")
         .unwrap_err();
     assert!(err.contains("Missing column 'age' has no DEFAULT"));
+    assert!(err.starts_with("Expected 3 values (id, name, age) but got 1"));
 }
 
 #[test]
@@ -105,6 +106,8 @@ fn test_describe_returns_schema_metadata() {
             "not_null",
             "default",
             "indexes",
+            "comment",
+            "table_comment",
         ],
         vec![
             vec![
@@ -115,6 +118,8 @@ fn test_describe_returns_schema_metadata() {
                 Value::Bool(true),
                 Value::Null,
                 Value::Text("".to_string()),
+                Value::Null,
+                Value::Null,
             ],
             vec![
                 Value::Text("email".to_string()),
@@ -124,6 +129,8 @@ fn test_describe_returns_schema_metadata() {
                 Value::Bool(false),
                 Value::Null,
                 Value::Text("".to_string()),
+                Value::Null,
+                Value::Null,
             ],
             vec![
                 Value::Text("name".to_string()),
@@ -133,11 +140,64 @@ fn test_describe_returns_schema_metadata() {
                 Value::Bool(true),
                 Value::Text("anon".to_string()),
                 Value::Text("name".to_string()),
+                Value::Null,
+                Value::Null,
             ],
         ],
     );
 }
 
+#[test]
+fn test_comments_show_in_describe_and_can_be_removed() {
+    let mut db = test_db();
+    db.execute("create table users (id int, email text)")
+        .unwrap();
+    let out = db
+        .execute(r#"comment on table users is "app users""#)
+        .unwrap();
+    assert_schema_change_result(out, "set comment on table users");
+    db.execute(r#"comment on column users.email is "login email""#)
+        .unwrap();
+
+    let rows = |db: &mut Database| match db.execute("describe users").unwrap() {
+        QueryResult::Select { rows, .. } => rows
+            .into_iter()
+            .map(|r| (r[7].clone(), r[8].clone()))
+            .collect::<Vec<_>>(),
+        other => panic!("expected select, got {other:?}"),
+    };
+    let app = Value::Text("app users".to_string());
+    assert_eq!(
+        rows(&mut db),
+        vec![
+            (Value::Null, app.clone()),
+            (Value::Text("login email".to_string()), app.clone()),
+        ]
+    );
+
+    db.execute("comment on column users.email is null").unwrap();
+    assert_eq!(rows(&mut db)[1], (Value::Null, app));
+}
+
+#[test]
+fn test_comment_errors_on_unknown_targets_and_inside_transaction() {
+    let mut db = test_db();
+    db.execute("create table users (id int)").unwrap();
+    let err = db
+        .execute_legacy(r#"comment on table missing is "x""#)
+        .unwrap_err();
+    assert!(err.contains("Table 'missing' does not exist"));
+    let err = db
+        .execute_legacy(r#"comment on column users.nope is "x""#)
+        .unwrap_err();
+    assert!(err.contains("Unknown column 'nope'"));
+    db.execute("begin").unwrap();
+    let err = db
+        .execute_legacy(r#"comment on table users is "x""#)
+        .unwrap_err();
+    assert!(err.contains("auto-commit"));
+}
+
 #[test]
 fn test_describe_missing_table_errors() {
     let mut db = test_db();
@@ -165,6 +225,33 @@ fn test_create_duplicate_table() {
     assert!(result.unwrap_err().contains("already exists"));
 }
 
+#[test]
+fn test_create_table_if_not_exists_skips_existing_table() {
+    let mut db = test_db();
+    assert_eq!(
+        db.execute_legacy("create table if not exists users (id int, name text)")
+            .unwrap(),
+        "created table users"
+    );
+    db.execute_legacy(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+
+    assert_eq!(
+        db.execute_legacy("create table if not exists users (id int, name text)")
+            .unwrap(),
+        "table users already exists, skipped"
+    );
+    assert_eq!(
+        db.execute_legacy("CREATE TABLE IF NOT EXISTS users (id int)")
+            .unwrap(),
+        "table users already exists, skipped (existing columns differ from this definition)"
+    );
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n1\tram"
+    );
+}
+
 #[test]
 fn test_multiple_tables() {
     let mut db = test_db();
@@ -240,3 +327,60 @@ fn test_large_integers() {
     let result = db.execute_legacy("select * from nums").unwrap();
     assert_eq!(result, "id\tvalue\n1\t999999999");
 }
+
+#[test]
+fn test_quoted_identifiers_allow_reserved_column_names() {
+    let mut db = test_db();
+    db.execute("create table `order` (id int primary key, [select] int, `Group` text)")
+        .unwrap();
+    db.execute(r#"insert into [order] values (1, 30, "a")"#)
+        .unwrap();
+    db.execute(r#"insert into `order` values (2, 10, "b")"#)
+        .unwrap();
+    db.execute(r#"insert into order values (3, 20, "c")"#)
+        .unwrap();
+
+    let result = db
+        .execute("select id, `select` from [order] order by [select]")
+        .unwrap();
+    assert_select_result(
+        result,
+        &["id", "select"],
+        vec![
+            vec![Value::Int(2), Value::Int(10)],
+            vec![Value::Int(3), Value::Int(20)],
+            vec![Value::Int(1), Value::Int(30)],
+        ],
+    );
+
+    let out = db
+        .execute_legacy("create index on `order` ([select])")
+        .unwrap();
+    assert_eq!(out, "created index on order(select)");
+    db.execute("update [order] set `select` = 25 where [select] = 20")
+        .unwrap();
+    let result = db
+        .execute("select [Group] from `order` where `select` = 25")
+        .unwrap();
+    assert_select_result(result, &["Group"], vec![vec![Value::Text("c".to_string())]]);
+
+    db.execute("delete from [order] where [select] > 20")
+        .unwrap();
+    let result = db.execute("select id from order").unwrap();
+    assert_select_result(result, &["id"], vec![vec![Value::Int(2)]]);
+}
+
+#[test]
+fn test_trailing_semicolon_statements_execute() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text);")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "ram");"#)
+        .unwrap();
+    let result = db.execute("select name from users where id = 1;").unwrap();
+    assert_select_result(
+        result,
+        &["name"],
+        vec![vec![Value::Text("ram".to_string())]],
+    );
+}
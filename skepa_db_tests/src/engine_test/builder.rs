@@ -0,0 +1,168 @@
+use super::*;
+use skepa_db_core::parser::command::ForeignKeyAction;
+use skepa_db_core::types::datatype::DataType;
+
+fn build_users(db: &mut Database) {
+    db.create_table("teams")
+        .column("id", DataType::Int)
+        .primary_key(["id"])
+        .execute()
+        .unwrap();
+    let out = db
+        .create_table("users")
+        .column("id", DataType::Int)
+        .column("email", DataType::VarChar(40))
+        .not_null()
+        .column("team_id", DataType::Int)
+        .column("active", DataType::Bool)
+        .default(Value::Bool(true))
+        .primary_key(["id"])
+        .unique(["email"])
+        .foreign_key(["team_id"], "teams", ["id"], ForeignKeyAction::Cascade)
+        .execute()
+        .unwrap();
+    assert_eq!(db.render(&out), "created table users");
+}
+
+fn sql_users(db: &mut Database) {
+    db.execute("create table teams (id int, primary key(id))")
+        .unwrap();
+    db.execute(
+        "create table users (id int, email varchar(40) not null, team_id int, active bool default true, \
+         primary key(id), unique(email), foreign key(team_id) references teams(id) on delete cascade)",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_builder_create_table_matches_sql_definition() {
+    let mut built = test_db();
+    build_users(&mut built);
+    let mut parsed = test_db();
+    sql_users(&mut parsed);
+
+    for table in ["teams", "users"] {
+        let sql = format!("describe {table}");
+        assert_eq!(
+            built.execute_legacy(&sql).unwrap(),
+            parsed.execute_legacy(&sql).unwrap()
+        );
+    }
+    assert_eq!(
+        built.debug_catalog_json().unwrap()["catalog"],
+        parsed.debug_catalog_json().unwrap()["catalog"]
+    );
+}
+
+#[test]
+fn test_builder_create_table_validates_like_sql() {
+    let mut db = test_db();
+    let err = db
+        .create_table("users")
+        .column("id", DataType::Int)
+        .primary_key(["missing"])
+        .execute()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "PRIMARY KEY references unknown column 'missing'"
+    );
+    assert!(db.execute("select * from users").is_err());
+
+    let err = db
+        .create_table("orders")
+        .column("user_id", DataType::Int)
+        .foreign_key(["user_id"], "users", ["id"], ForeignKeyAction::Restrict)
+        .execute()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "FOREIGN KEY references unknown table 'users'"
+    );
+
+    db.create_table("t")
+        .column("a", DataType::Int)
+        .execute()
+        .unwrap();
+    let out = db
+        .create_table("t")
+        .column("a", DataType::Int)
+        .if_not_exists()
+        .execute()
+        .unwrap();
+    assert_eq!(db.render(&out), "table t already exists, skipped");
+}
+
+#[test]
+fn test_builder_insert_binds_values_and_applies_defaults() {
+    let mut db = test_db();
+    build_users(&mut db);
+    db.insert("teams").values([Value::Int(1)]).unwrap();
+    let out = db
+        .insert("users")
+        .values([
+            Value::Int(1),
+            Value::Text(r#"a "quoted" \ name"#.to_string()),
+            Value::Int(1),
+        ])
+        .unwrap();
+    assert_mutation_result(out, "inserted 1 row into users", 1);
+    assert_select_result(
+        db.execute("select email, active from users").unwrap(),
+        &["email", "active"],
+        vec![vec![
+            Value::VarChar(r#"a "quoted" \ name"#.to_string()),
+            Value::Bool(true),
+        ]],
+    );
+
+    let err = db
+        .insert("users")
+        .values([Value::Int(2), Value::Null, Value::Int(1)])
+        .unwrap_err();
+    assert!(err.to_string().contains("NOT NULL"), "{err}");
+    let err = db
+        .insert("users")
+        .values([Value::Int(2), Value::Text("b".to_string()), Value::Int(9)])
+        .unwrap_err();
+    assert!(err.to_string().contains("FOREIGN KEY"), "{err}");
+}
+
+#[test]
+fn test_builder_writes_survive_reopen_and_rollback() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_builder_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.create_table("order")
+            .column("id", DataType::Int)
+            .column("note", DataType::Text)
+            .execute()
+            .unwrap();
+        db.insert("order")
+            .values([Value::Int(1), Value::Text("kept".to_string())])
+            .unwrap();
+        db.execute("begin").unwrap();
+        db.insert("order")
+            .values([Value::Int(2), Value::Text("staged".to_string())])
+            .unwrap();
+        db.execute("rollback").unwrap();
+        db.execute("begin").unwrap();
+        db.insert("order")
+            .values([Value::Int(3), Value::Null])
+            .unwrap();
+        db.execute("commit").unwrap();
+    }
+    let mut db = Database::open_legacy(path.clone());
+    assert_select_result(
+        db.execute("select id, note from `order` order by id asc")
+            .unwrap(),
+        &["id", "note"],
+        vec![
+            vec![Value::Int(1), Value::Text("kept".to_string())],
+            vec![Value::Int(3), Value::Null],
+        ],
+    );
+    let _ = std::fs::remove_dir_all(&path);
+}
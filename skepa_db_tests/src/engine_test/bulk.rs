@@ -0,0 +1,180 @@
+use super::*;
+
+fn user_row(id: i64, name: &str) -> Vec<Value> {
+    vec![Value::Int(id), Value::Text(name.to_string())]
+}
+
+#[test]
+fn test_bulk_load_inserts_rows_and_maintains_indexes() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key, name text)")
+        .unwrap();
+    db.execute("create index on users (name)").unwrap();
+    db.execute(r#"insert into users values (1, "a")"#).unwrap();
+
+    let rows = (2..=100).map(|i| user_row(i, if i % 2 == 0 { "even" } else { "odd" }));
+    assert_mutation_result(
+        db.bulk_load("users", rows).unwrap(),
+        "inserted 99 row(s) into users",
+        99,
+    );
+
+    assert_select_result(
+        db.execute("select id from users where id = 57").unwrap(),
+        &["id"],
+        vec![vec![Value::Int(57)]],
+    );
+    let out = db
+        .execute_legacy(r#"select count(*) from users where name = "odd""#)
+        .unwrap();
+    assert_eq!(out, "count(*)\n49");
+}
+
+#[test]
+fn test_bulk_load_coerces_values_like_insert_literals() {
+    let mut db = test_db();
+    db.execute("create table events (id int, day date, tag varchar(3), doc json)")
+        .unwrap();
+    db.bulk_load(
+        "events",
+        vec![vec![
+            Value::Int(1),
+            Value::Text("2024-02-29".to_string()),
+            Value::Text("a\"b".to_string()),
+            Value::Text("{\"b\":1,\"a\":2}".to_string()),
+        ]],
+    )
+    .unwrap();
+    let out = db.execute_legacy("select * from events").unwrap();
+    assert_eq!(
+        out,
+        "id\tday\ttag\tdoc\n1\t2024-02-29\ta\"b\t{\"a\":2,\"b\":1}"
+    );
+
+    let err = db
+        .bulk_load(
+            "events",
+            vec![vec![
+                Value::Int(2),
+                Value::Text("2024-02-30".to_string()),
+                Value::Null,
+                Value::Null,
+            ]],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Bulk row 1: Expected date"));
+    let err = db
+        .bulk_load("events", vec![vec![Value::Int(2)]])
+        .unwrap_err();
+    assert!(err.to_string().contains("Bulk row 1: Expected 4 values"));
+}
+
+#[test]
+fn test_bulk_load_rejects_whole_batch_on_duplicate_key() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key, name text unique)")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "a")"#).unwrap();
+
+    let err = db
+        .bulk_load(
+            "users",
+            vec![user_row(2, "b"), user_row(3, "c"), user_row(2, "d")],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("PRIMARY KEY constraint violation"));
+    let err = db
+        .bulk_load("users", vec![user_row(2, "b"), user_row(3, "a")])
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("UNIQUE constraint violation on column(s) name")
+    );
+
+    assert_select_result(
+        db.execute("select * from users").unwrap(),
+        &["id", "name"],
+        vec![user_row(1, "a")],
+    );
+}
+
+#[test]
+fn test_bulk_load_checks_foreign_keys_and_not_null() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key)")
+        .unwrap();
+    db.execute("create table orders (id int not null, user_id int, foreign key (user_id) references users(id))")
+        .unwrap();
+    db.execute("insert into users values (1)").unwrap();
+
+    let err = db
+        .bulk_load(
+            "orders",
+            vec![
+                vec![Value::Int(1), Value::Int(1)],
+                vec![Value::Int(2), Value::Int(9)],
+            ],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("FOREIGN KEY violation"));
+    let err = db
+        .bulk_load("orders", vec![vec![Value::Null, Value::Int(1)]])
+        .unwrap_err();
+    assert!(err.to_string().contains("Column 'id' is NOT NULL"));
+
+    db.bulk_load(
+        "orders",
+        vec![
+            vec![Value::Int(1), Value::Int(1)],
+            vec![Value::Int(2), Value::Null],
+        ],
+    )
+    .unwrap();
+    let out = db.execute_legacy("select count(*) from orders").unwrap();
+    assert_eq!(out, "count(*)\n2");
+}
+
+#[test]
+fn test_bulk_load_rejected_inside_transaction() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("begin").unwrap();
+    let err = db.bulk_load("users", vec![user_row(1, "a")]).unwrap_err();
+    assert!(err.to_string().contains("active transaction"));
+}
+
+#[test]
+fn test_bulk_load_persists_across_reopen() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_bulk_reopen_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table users (id int primary key, name text)")
+            .unwrap();
+        db.execute("create index on users (name)").unwrap();
+        db.bulk_load(
+            "users",
+            (1..=100_000).map(|i| user_row(i, &format!("n{}", i % 10))),
+        )
+        .unwrap();
+    }
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let out = db.execute_legacy("select count(*) from users").unwrap();
+        assert_eq!(out, "count(*)\n100000");
+        let out = db
+            .execute_legacy("select name from users where id = 99999")
+            .unwrap();
+        assert_eq!(out, "name\nn9");
+        let err = db
+            .execute(r#"insert into users values (5, "x")"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("PRIMARY KEY constraint violation"));
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -0,0 +1,231 @@
+use super::*;
+use skepa_db_core::change_event::{ChangeEvent, ChangeOp};
+use skepa_db_core::config::{ConflictDetection, DbConfig};
+use skepa_db_core::types::RowId;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+fn record_changes(db: &mut Database) -> Arc<Mutex<Vec<ChangeEvent>>> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    db.on_change(Box::new(move |event, _| {
+        sink.lock().unwrap().push(event.clone())
+    }));
+    events
+}
+
+fn take(events: &Arc<Mutex<Vec<ChangeEvent>>>) -> Vec<ChangeEvent> {
+    events.lock().unwrap().drain(..).collect()
+}
+
+fn event(
+    table: &str,
+    op: ChangeOp,
+    row_id: RowId,
+    old: Option<Vec<Value>>,
+    new: Option<Vec<Value>>,
+) -> ChangeEvent {
+    ChangeEvent {
+        table: table.to_string(),
+        op,
+        row_id,
+        old,
+        new,
+    }
+}
+
+#[test]
+fn test_on_change_reports_autocommit_insert_update_and_delete() {
+    let mut db = test_db();
+    db.execute("create table t (id int, name text)").unwrap();
+    let events = record_changes(&mut db);
+
+    db.execute(r#"insert into t values (1, "a")"#).unwrap();
+    let row = vec![Value::Int(1), Value::Text("a".to_string())];
+    assert_eq!(
+        take(&events),
+        vec![event("t", ChangeOp::Insert, 1, None, Some(row.clone()))]
+    );
+
+    db.execute(r#"update t set name = "b" where id = 1"#)
+        .unwrap();
+    let updated = vec![Value::Int(1), Value::Text("b".to_string())];
+    assert_eq!(
+        take(&events),
+        vec![event(
+            "t",
+            ChangeOp::Update,
+            1,
+            Some(row),
+            Some(updated.clone())
+        )]
+    );
+
+    db.execute("delete from t where id = 1").unwrap();
+    assert_eq!(
+        take(&events),
+        vec![event("t", ChangeOp::Delete, 1, Some(updated), None)]
+    );
+
+    // Reads, failed writes and writes matching nothing report nothing.
+    db.execute("select * from t").unwrap();
+    db.execute("delete from t where id = 1").unwrap();
+    assert!(db.execute("insert into t values (1)").is_err());
+    assert!(take(&events).is_empty());
+}
+
+#[test]
+fn test_on_change_reports_cascades_and_only_committed_transactions() {
+    let mut db = test_db();
+    db.execute("create table p (id int primary key)").unwrap();
+    db.execute(
+        "create table c (id int, pid int, foreign key(pid) references p(id) on delete cascade)",
+    )
+    .unwrap();
+    db.execute("insert into p values (1)").unwrap();
+    db.execute("insert into c values (10, 1)").unwrap();
+    let events = record_changes(&mut db);
+
+    db.execute("begin").unwrap();
+    db.execute("insert into p values (2)").unwrap();
+    db.execute("rollback").unwrap();
+    assert!(take(&events).is_empty());
+
+    db.execute("begin").unwrap();
+    db.execute("insert into p values (2)").unwrap();
+    db.execute("delete from p where id = 1").unwrap();
+    assert!(take(&events).is_empty());
+    db.execute("commit").unwrap();
+    assert_eq!(
+        take(&events),
+        vec![
+            event(
+                "c",
+                ChangeOp::Delete,
+                1,
+                Some(vec![Value::Int(10), Value::Int(1)]),
+                None
+            ),
+            event("p", ChangeOp::Insert, 2, None, Some(vec![Value::Int(2)])),
+            event("p", ChangeOp::Delete, 1, Some(vec![Value::Int(1)]), None),
+        ]
+    );
+}
+
+#[test]
+fn test_on_change_hooks_can_select_the_committed_data() {
+    let mut db = test_db();
+    db.execute("create table t (id int)").unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&seen);
+    db.on_change(Box::new(move |event, view| {
+        let count = match view.query("select count(*) from t").unwrap() {
+            QueryResult::Select { rows, .. } => rows[0][0].clone(),
+            other => panic!("expected rows, got {other:?}"),
+        };
+        let write = view
+            .query("delete from t where id = 1")
+            .unwrap_err()
+            .to_string();
+        sink.lock().unwrap().push((event.row_id, count, write));
+    }));
+
+    let out = db.execute("insert into t values (1)").unwrap();
+    assert_eq!(out.rows_affected(), Some(1));
+    db.execute("begin").unwrap();
+    db.execute("insert into t values (2)").unwrap();
+    db.execute("insert into t values (3)").unwrap();
+    let out = db.execute("commit").unwrap();
+    assert_eq!(out.message(), Some("transaction committed"));
+
+    // Every hook call sees all rows of the statement or transaction it
+    // reports, and its attempted write changed nothing.
+    let write = "DatabaseView::query only runs read statements".to_string();
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            (1, Value::BigInt(1), write.clone()),
+            (2, Value::BigInt(3), write.clone()),
+            (3, Value::BigInt(3), write),
+        ]
+    );
+    assert_eq!(
+        db.execute_legacy("select count(*) from t").unwrap(),
+        "count(*)\n3"
+    );
+}
+
+/// An external "search index": row id to the indexed text column.
+fn index_from_scan(db: &Database) -> BTreeMap<RowId, Value> {
+    db.scan_table("docs")
+        .unwrap()
+        .map(|(id, row)| (id, row[1].clone()))
+        .collect()
+}
+
+fn apply(index: &mut BTreeMap<RowId, Value>, events: Vec<ChangeEvent>) {
+    for e in events.into_iter().filter(|e| e.table == "docs") {
+        match e.op {
+            ChangeOp::Insert | ChangeOp::Update => {
+                index.insert(e.row_id, e.new.unwrap()[1].clone());
+            }
+            ChangeOp::Delete => {
+                assert!(
+                    index.remove(&e.row_id).is_some(),
+                    "unknown row {}",
+                    e.row_id
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_scan_table_and_change_events_keep_an_external_index_in_sync() {
+    let path = test_db().path().clone();
+    let config = DbConfig::new(path.clone()).with_conflict_detection(ConflictDetection::Row);
+    let mut db = Database::open(config).unwrap();
+    db.execute("create table docs (id int primary key, body text)")
+        .unwrap();
+    for (id, body) in [(1, "alpha"), (2, "beta"), (3, "gamma")] {
+        db.execute(&format!(r#"insert into docs values ({id}, "{body}")"#))
+            .unwrap();
+    }
+    assert!(db.scan_table("missing").is_err());
+
+    let mut index = index_from_scan(&db);
+    assert_eq!(index.len(), 3);
+    let events = record_changes(&mut db);
+
+    db.execute(r#"update docs set body = "beta two" where id = 2"#)
+        .unwrap();
+    db.execute("delete from docs where id = 1").unwrap();
+    db.execute(r#"insert into docs values (4, "delta")"#)
+        .unwrap();
+    apply(&mut index, take(&events));
+    assert_eq!(index, index_from_scan(&db));
+
+    // Another handle commits while this one's transaction is open; the
+    // row-level rebase renumbers the transaction's insert, and the event
+    // carries the id the row ends up with.
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into docs values (5, "epsilon")"#)
+        .unwrap();
+    db.execute("delete from docs where id = 3").unwrap();
+    let mut other = Database::open(DbConfig::new(path)).unwrap();
+    other
+        .execute(r#"insert into docs values (6, "zeta")"#)
+        .unwrap();
+    drop(other);
+    db.execute("commit").unwrap();
+    apply(&mut index, take(&events));
+    // Changes committed by the other handle are not reported here; pick
+    // its row up from a scan.
+    let scanned = index_from_scan(&db);
+    let (&zeta_id, _) = scanned
+        .iter()
+        .find(|(_, body)| **body == Value::Text("zeta".to_string()))
+        .unwrap();
+    index.insert(zeta_id, scanned[&zeta_id].clone());
+    assert_eq!(index, scanned);
+}
@@ -81,6 +81,78 @@ fn test_decimal_precision_and_scale_enforced() {
     assert!(e2.to_lowercase().contains("scale"));
 }
 
+#[test]
+fn test_decimal_precision_counts_the_padded_scale() {
+    let mut db = test_db();
+    db.execute("create table t (d decimal(3,2))").unwrap();
+    let err = db.execute_legacy("insert into t values (10)").unwrap_err();
+    assert!(
+        err.contains("Decimal precision 4 exceeds allowed precision 3"),
+        "{err}"
+    );
+    db.execute("insert into t values (9.9)").unwrap();
+    db.execute("insert into t values (-0.5)").unwrap();
+    assert_eq!(
+        db.execute_legacy("select d from t").unwrap(),
+        "d\n9.90\n-0.50"
+    );
+}
+
+#[test]
+fn test_decimal_with_fewer_digits_than_scale_is_padded() {
+    let mut db = test_db();
+    db.execute("create table t (id int, d decimal(8,2))")
+        .unwrap();
+    db.execute("insert into t values (1, 1)").unwrap();
+    db.execute("insert into t values (2, 1.1)").unwrap();
+    db.execute("insert into t values (3, 1.10)").unwrap();
+    assert_eq!(
+        db.execute_legacy("select d from t").unwrap(),
+        "d\n1.00\n1.10\n1.10"
+    );
+    assert_eq!(
+        db.execute_legacy("select id from t where d = 1.1").unwrap(),
+        "id\n2\n3"
+    );
+    assert_eq!(
+        db.execute_legacy("select id from t where d in (1, 1.10)")
+            .unwrap(),
+        "id\n1\n2\n3"
+    );
+    assert_eq!(
+        db.execute_legacy("select id from t where d > 1.05 order by id desc")
+            .unwrap(),
+        "id\n3\n2"
+    );
+    assert_eq!(
+        db.execute_legacy("select distinct d from t").unwrap(),
+        "d\n1.00\n1.10"
+    );
+    let out = db.execute("select id, d from t where id = 2").unwrap();
+    let json = serde_json::to_value(&out).unwrap();
+    assert_eq!(json["Select"]["rows"][0][1], "1.10");
+
+    db.execute("create table u (d decimal(8,2) unique)")
+        .unwrap();
+    db.execute("insert into u values (1.1)").unwrap();
+    let err = db
+        .execute_legacy("insert into u values (1.10)")
+        .unwrap_err();
+    assert!(err.to_lowercase().contains("unique"), "{err}");
+
+    let path = db.path().clone();
+    drop(db);
+    let mut db = Database::open_legacy(path);
+    assert_eq!(
+        db.execute_legacy("select d from t where id = 2").unwrap(),
+        "d\n1.10"
+    );
+    let err = db
+        .execute_legacy("insert into u values (1.10)")
+        .unwrap_err();
+    assert!(err.to_lowercase().contains("unique"), "{err}");
+}
+
 #[test]
 fn test_date_and_timestamp_comparisons() {
     let mut db = test_db();
@@ -100,6 +172,119 @@ fn test_date_and_timestamp_comparisons() {
     assert!(t.contains("2025-01-02"));
 }
 
+#[test]
+fn test_timestamp_offsets_normalize_to_utc() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, ts timestamp)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (1, "2025-01-02T10:00:00+05:30")"#)
+        .unwrap();
+    db.execute_legacy("insert into t values (2, 2025-01-02T04:30:00Z)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (3, "2025-01-01T23:00:00-08:00")"#)
+        .unwrap();
+    let out = db.execute_legacy("select * from t order by id").unwrap();
+    assert_eq!(
+        out,
+        "id\tts\n1\t2025-01-02 04:30:00\n2\t2025-01-02 04:30:00\n3\t2025-01-02 07:00:00"
+    );
+}
+
+#[test]
+fn test_timestamp_comparisons_across_offsets() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, ts timestamp)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (1, "2025-01-02 04:30:00")"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (2, "2025-01-02 06:00:00")"#)
+        .unwrap();
+    let eq = db
+        .execute_legacy("select id from t where ts = 2025-01-02T10:00:00+05:30")
+        .unwrap();
+    assert_eq!(eq, "id\n1");
+    let gt = db
+        .execute_legacy("select id from t where ts > 2025-01-01T22:00:00-07:00")
+        .unwrap();
+    assert_eq!(gt, "id\n2");
+}
+
+#[test]
+fn test_timestamp_rejects_malformed_offsets() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (ts timestamp)").unwrap();
+    for bad in [
+        "2025-01-02T10:00:00+25:00",
+        "2025-01-02T10:00:00+5",
+        "2025-01-02T10:00:00 UTC",
+    ] {
+        let err = db
+            .execute_legacy(&format!(r#"insert into t values ("{bad}")"#))
+            .unwrap_err();
+        assert!(err.contains("Expected timestamp"), "{bad}: {err}");
+    }
+}
+
+#[test]
+fn test_date_accepts_bare_and_quoted_literals() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (dt date)").unwrap();
+    db.execute_legacy("insert into t values (2025-01-02)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values ("2025-01-03")"#)
+        .unwrap();
+    let out = db
+        .execute_legacy(r#"select * from t where dt >= "2025-01-02" order by dt"#)
+        .unwrap();
+    assert_eq!(out, "dt\n2025-01-02\n2025-01-03");
+}
+
+#[test]
+fn test_uuid_rejects_non_canonical_forms() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (u uuid)").unwrap();
+    for bad in [
+        "550e8400e29b41d4a716446655440000",
+        "{550e8400-e29b-41d4-a716-446655440000}",
+        "urn:uuid:550e8400-e29b-41d4-a716-446655440000",
+        "550e840-0e29b-41d4-a716-446655440000",
+        "550e8400-e29b-41d4-a716-44665544000g",
+    ] {
+        let err = db
+            .execute_legacy(&format!(r#"insert into t values ("{bad}")"#))
+            .unwrap_err();
+        assert!(err.contains("Expected uuid"), "{bad}: {err}");
+    }
+}
+
+#[test]
+fn test_uuid_uppercase_input_is_normalized_and_matches() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id uuid primary key, name text)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (550E8400-E29B-41D4-A716-446655440000, "a")"#)
+        .unwrap();
+    let all = db.execute_legacy("select id from t").unwrap();
+    assert_eq!(all, "id\n550e8400-e29b-41d4-a716-446655440000");
+    let hit = db
+        .execute_legacy(r#"select name from t where id = "550E8400-e29b-41D4-A716-446655440000""#)
+        .unwrap();
+    assert_eq!(hit, "name\na");
+    let dup = db
+        .execute_legacy(r#"insert into t values (550e8400-e29b-41d4-a716-446655440000, "b")"#)
+        .unwrap_err();
+    assert!(dup.contains("PRIMARY KEY"));
+}
+
+#[test]
+fn test_gen_uuid_default_requires_uuid_column() {
+    let mut db = test_db();
+    let err = db
+        .execute_legacy("create table t (id text default gen_uuid())")
+        .unwrap_err();
+    assert!(err.contains("gen_uuid() requires a uuid column"));
+}
+
 #[test]
 fn test_bigint_and_decimal_comparisons() {
     let mut db = test_db();
@@ -115,6 +300,131 @@ fn test_bigint_and_decimal_comparisons() {
     assert!(d.contains("2.2") || d.contains("2.20"));
 }
 
+#[test]
+fn test_scientific_notation_for_bigint_and_decimal() {
+    let mut db = test_db();
+    db.execute("create table t (bi bigint, d decimal(8,2))")
+        .unwrap();
+    db.execute("insert into t values (1e3, 1.5e2)").unwrap();
+    db.execute("insert into t values (-2.5E+1, 125e-2)")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from t").unwrap(),
+        "bi\td\n1000\t150.00\n-25\t1.25"
+    );
+    assert_eq!(
+        db.execute_legacy("select d from t where bi = 1e3").unwrap(),
+        "d\n150.00"
+    );
+
+    let err = db.execute("insert into t values (1.5e0, 1)").unwrap_err();
+    assert!(err.to_string().contains("not a whole number"), "{err}");
+    let err = db.execute("insert into t values (1e40, 1)").unwrap_err();
+    assert_eq!(err.to_string(), "Bigint literal '1e40' is out of range");
+    let err = db.execute("insert into t values (1, 1e40)").unwrap_err();
+    assert_eq!(err.to_string(), "Decimal literal '1e40' is out of range");
+    let err = db.execute("insert into t values (1, 1e8)").unwrap_err();
+    assert!(err.to_string().contains("precision"), "{err}");
+    let err = db.execute("insert into t values (1, 1e-3)").unwrap_err();
+    assert!(err.to_string().contains("scale"), "{err}");
+    let err = db.execute("insert into t values (1e999, 1)").unwrap_err();
+    assert_eq!(err.to_string(), "Numeric literal '1e999' is out of range");
+}
+
+#[test]
+fn test_numeric_literals_outside_the_grammar_are_rejected() {
+    let mut db = test_db();
+    db.execute("create table t (i int, bi bigint, d decimal(8,2))")
+        .unwrap();
+    for (values, message) in [
+        (
+            r#"("1,5", 1, 1)"#,
+            "Expected int but got '1,5': write digits without ',', '_' or spaces, with '.' as the decimal point",
+        ),
+        (
+            "(1, 1_000, 1)",
+            "Expected bigint but got '1_000': write digits without ',', '_' or spaces, with '.' as the decimal point",
+        ),
+        (
+            "(1, 1, .5)",
+            "Expected decimal but got '.5': write a digit on each side of '.', as in 0.5 or 5.0",
+        ),
+        (
+            "(1, 1, 5.)",
+            "Expected decimal but got '5.': write a digit on each side of '.', as in 0.5 or 5.0",
+        ),
+        (
+            "(1, 1, +.5)",
+            "Expected decimal but got '+.5': write a digit on each side of '.', as in 0.5 or 5.0",
+        ),
+        (
+            "(1, 1, 5.e2)",
+            "Expected decimal but got '5.e2': write a digit on each side of '.', as in 0.5 or 5.0",
+        ),
+        (
+            "(1, 1, NaN)",
+            "Expected decimal but got 'NaN': NaN and infinity are not numbers",
+        ),
+        (
+            "(1, -inf, 1)",
+            "Expected bigint but got '-inf': NaN and infinity are not numbers",
+        ),
+        ("(1, 1, 1.2.3)", "Expected decimal but got '1.2.3'"),
+        ("(1, 1, 1e)", "Expected decimal but got '1e'"),
+    ] {
+        let err = db
+            .execute(&format!("insert into t values {values}"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), message, "{values}");
+    }
+    assert_eq!(db.execute_legacy("select * from t").unwrap(), "i\tbi\td");
+}
+
+/// Every literal the grammar accepts renders, through `value_to_string`, to
+/// text that parses back to the same value, so stored numbers survive WAL
+/// replay and re-insertion unchanged.
+#[test]
+fn test_accepted_numeric_literals_round_trip_through_their_rendering() {
+    use skepa_db_core::types::datatype::DataType;
+    use skepa_db_core::types::value::{parse_value, value_to_string};
+
+    let types = [
+        DataType::Int,
+        DataType::BigInt,
+        DataType::Decimal {
+            precision: 18,
+            scale: 6,
+        },
+        DataType::Decimal {
+            precision: 6,
+            scale: 0,
+        },
+    ];
+    let mut accepted = 0usize;
+    for sign in ["", "+", "-"] {
+        for whole in ["0", "7", "007", "42", "123456", "9223372036854775807"] {
+            for frac in ["", ".0", ".5", ".25", ".000001", ".1234567"] {
+                for exp in ["", "e0", "e3", "E-2", "e+1", "e-7"] {
+                    let token = format!("{sign}{whole}{frac}{exp}");
+                    for dtype in &types {
+                        let Ok(value) = parse_value(dtype, &token) else {
+                            continue;
+                        };
+                        accepted += 1;
+                        let rendered = value_to_string(&value);
+                        assert_eq!(
+                            parse_value(dtype, &rendered),
+                            Ok(value),
+                            "{token} as {dtype:?} rendered as {rendered}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+    assert!(accepted > 500, "only {accepted} literals accepted");
+}
+
 #[test]
 fn test_primary_key_constraint_insert() {
     let mut db = test_db();
@@ -498,6 +808,127 @@ fn test_alter_set_and_drop_not_null() {
     db.execute_legacy("insert into t values (2, null)").unwrap();
 }
 
+#[test]
+fn test_alter_add_column_places_column_and_rewrites_rows() {
+    let mut db = test_db();
+    db.execute_legacy("create table p (id int primary key)")
+        .unwrap();
+    db.execute_legacy("create table t (id int primary key, name text, pid int, foreign key(pid) references p(id))")
+        .unwrap();
+    db.execute_legacy("create index on t (name)").unwrap();
+    db.execute_legacy("insert into p values (1)").unwrap();
+    db.execute_legacy(r#"insert into t values (1, "a", 1)"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (2, "b", null)"#)
+        .unwrap();
+
+    let out = db
+        .execute_legacy("alter table t add column score int not null default 0 after id")
+        .unwrap();
+    assert_eq!(out, "altered table t: added column score int after id");
+    db.execute_legacy("alter table t add column tag text unique first")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from t order by id asc")
+            .unwrap(),
+        "tag\tid\tscore\tname\tpid\nnull\t1\t0\ta\t1\nnull\t2\t0\tb\tnull"
+    );
+    let out = db.execute_legacy("describe t").unwrap();
+    let names: Vec<&str> = out
+        .lines()
+        .skip(1)
+        .filter_map(|l| l.split('\t').next())
+        .take(5)
+        .collect();
+    assert_eq!(names, vec!["tag", "id", "score", "name", "pid"]);
+
+    // Constraints and indexes follow the columns to their new positions.
+    db.execute_legacy(r#"insert into t values ("x", 3, 5, "c", 1)"#)
+        .unwrap();
+    let err = db
+        .execute_legacy(r#"insert into t values ("y", 3, 5, "d", null)"#)
+        .unwrap_err();
+    assert!(err.contains("PRIMARY KEY"), "{err}");
+    let err = db
+        .execute_legacy(r#"insert into t values ("x", 4, 5, "d", null)"#)
+        .unwrap_err();
+    assert!(err.contains("UNIQUE"), "{err}");
+    let err = db
+        .execute_legacy(r#"insert into t values (null, 4, 5, "d", 9)"#)
+        .unwrap_err();
+    assert!(err.to_lowercase().contains("foreign key"), "{err}");
+    let err = db
+        .execute_legacy(r#"insert into t values (null, 4, null, "d", null)"#)
+        .unwrap_err();
+    assert!(err.contains("NOT NULL"), "{err}");
+    assert_eq!(
+        db.execute_legacy(r#"select id, score from t where name = "c""#)
+            .unwrap(),
+        "id\tscore\n3\t5"
+    );
+}
+
+#[test]
+fn test_alter_add_column_failure_leaves_table_unchanged() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, name text)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (1, "a")"#)
+        .unwrap();
+    let err = db
+        .execute_legacy("alter table t add column x int not null")
+        .unwrap_err();
+    assert!(err.contains("NOT NULL"), "{err}");
+    let err = db
+        .execute_legacy("alter table t add column name text")
+        .unwrap_err();
+    assert!(err.contains("already exists"), "{err}");
+    let err = db
+        .execute_legacy("alter table t add column x int after missing")
+        .unwrap_err();
+    assert!(err.contains("Unknown column 'missing'"), "{err}");
+    let err = db
+        .execute_legacy("alter table t add column x int primary key")
+        .unwrap_err();
+    assert!(err.contains("PRIMARY KEY"), "{err}");
+    assert_eq!(
+        db.execute_legacy("select * from t").unwrap(),
+        "id\tname\n1\ta"
+    );
+}
+
+#[test]
+fn test_alter_add_column_not_null_default_backfills_existing_and_new_rows() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int primary key)")
+        .unwrap();
+    db.execute_legacy("insert into t values (1)").unwrap();
+    db.execute_legacy("insert into t values (2)").unwrap();
+
+    let err = db
+        .execute_legacy("alter table t add column status text not null")
+        .unwrap_err();
+    assert!(err.contains("NOT NULL"), "{err}");
+    assert_eq!(
+        db.execute_legacy("select * from t order by id").unwrap(),
+        "id\n1\n2"
+    );
+
+    db.execute_legacy(r#"alter table t add column status text not null default "new""#)
+        .unwrap();
+    db.execute_legacy("insert into t values (3)").unwrap();
+    db.execute_legacy(r#"insert into t values (4, "done")"#)
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from t order by id").unwrap(),
+        "id\tstatus\n1\tnew\n2\tnew\n3\tnew\n4\tdone"
+    );
+    let err = db
+        .execute_legacy("update t set status = null where id = 1")
+        .unwrap_err();
+    assert!(err.contains("NOT NULL"), "{err}");
+}
+
 #[test]
 fn test_unique_single_column_allows_multiple_nulls() {
     let mut db = test_db();
@@ -524,6 +955,95 @@ fn test_unique_composite_allows_multiple_rows_with_null_member() {
     );
 }
 
+#[test]
+fn test_unique_nulls_not_distinct_rejects_a_second_null() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, email text unique nulls not distinct)")
+        .unwrap();
+    db.execute_legacy("insert into t values (1, null)").unwrap();
+    let err = db
+        .execute_legacy("insert into t values (2, null)")
+        .unwrap_err();
+    assert_eq!(err, "UNIQUE constraint violation on column(s) email");
+    db.execute_legacy(r#"insert into t values (4, "b@x.com")"#)
+        .unwrap();
+
+    let err = db
+        .execute_legacy("update t set email = null where id = 4")
+        .unwrap_err();
+    assert_eq!(err, "UNIQUE constraint violation on column(s) email");
+    db.execute_legacy("update t set email = null where id = 1")
+        .unwrap();
+    db.execute_legacy(r#"update t set email = "a@x.com" where id = 1"#)
+        .unwrap();
+    db.execute_legacy("update t set email = null where id = 4")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select id from t where email is null")
+            .unwrap(),
+        "id\n4"
+    );
+}
+
+#[test]
+fn test_composite_unique_nulls_not_distinct_compares_null_members() {
+    let mut db = test_db();
+    db.execute_legacy(
+        "create table t (a int, b int, c int, unique(a, b) nulls not distinct, unique(b, c))",
+    )
+    .unwrap();
+    db.execute_legacy("insert into t values (1, null, null)")
+        .unwrap();
+    db.execute_legacy("insert into t values (2, null, null)")
+        .unwrap();
+    let err = db
+        .execute_legacy("insert into t values (1, null, 5)")
+        .unwrap_err();
+    assert_eq!(err, "UNIQUE constraint violation on column(s) a,b");
+    let err = db
+        .execute_legacy("update t set a = 1 where a = 2")
+        .unwrap_err();
+    assert_eq!(err, "UNIQUE constraint violation on column(s) a,b");
+    assert_eq!(
+        db.execute_legacy("select count(*) from t").unwrap(),
+        "count(*)\n2"
+    );
+}
+
+#[test]
+fn test_alter_add_unique_nulls_not_distinct_validates_existing_nulls() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, email text)")
+        .unwrap();
+    db.execute_legacy("insert into t values (1, null)").unwrap();
+    db.execute_legacy("insert into t values (2, null)").unwrap();
+
+    let err = db
+        .execute_legacy("alter table t add unique(email) nulls not distinct")
+        .unwrap_err();
+    assert_eq!(err, "UNIQUE constraint violation on column(s) email");
+    // Without the modifier the NULLs are distinct and the constraint fits.
+    db.execute_legacy("insert into t values (3, null)").unwrap();
+
+    db.execute_legacy("delete from t where id > 1").unwrap();
+    assert_eq!(
+        db.execute_legacy("alter table t add unique(email) nulls not distinct")
+            .unwrap(),
+        "altered table t: added unique(email) nulls not distinct"
+    );
+    let err = db
+        .execute_legacy("insert into t values (2, null)")
+        .unwrap_err();
+    assert_eq!(err, "UNIQUE constraint violation on column(s) email");
+
+    // Dropping the constraint drops the modifier with it.
+    db.execute_legacy("alter table t drop unique(email)")
+        .unwrap();
+    db.execute_legacy("alter table t add unique(email)")
+        .unwrap();
+    db.execute_legacy("insert into t values (2, null)").unwrap();
+}
+
 #[test]
 fn test_alter_add_unique_failure_rolls_back_catalog_state() {
     let mut db = test_db();
@@ -572,3 +1092,78 @@ fn test_alter_add_fk_failure_rolls_back_catalog_state() {
         "id\tpid\n1\t999\n2\t888"
     );
 }
+
+#[test]
+fn test_json_shape_checks_on_insert_and_update() {
+    let mut db = test_db();
+    db.execute(
+        "create table t (id int, payload json check_json_object, items json check_json_array)",
+    )
+    .unwrap();
+    db.execute(r#"insert into t values (1, "{\"a\":1}", "[1,2]")"#)
+        .unwrap();
+    db.execute(r#"insert into t values (2, null, null)"#)
+        .unwrap();
+
+    let err = db
+        .execute_legacy(r#"insert into t values (3, "[1]", "[1]")"#)
+        .unwrap_err();
+    assert_eq!(err, "Column 'payload' requires a JSON object");
+    let err = db
+        .execute_legacy(r#"insert into t values (3, "{}", "{\"a\":[1]}")"#)
+        .unwrap_err();
+    assert_eq!(err, "Column 'items' requires a JSON array");
+    let err = db
+        .execute_legacy(r#"update t set payload = "3" where id = 1"#)
+        .unwrap_err();
+    assert_eq!(err, "Column 'payload' requires a JSON object");
+    db.execute(r#"update t set items = "[]" where id = 1"#)
+        .unwrap();
+}
+
+#[test]
+fn test_json_shape_check_requires_json_column_and_valid_default() {
+    let mut db = test_db();
+    let err = db
+        .execute_legacy("create table t (id int check_json_object)")
+        .unwrap_err();
+    assert!(err.contains("requires a json column"));
+    let err = db
+        .execute_legacy(r#"create table t (j json check_json_array default "{}")"#)
+        .unwrap_err();
+    assert!(err.contains("Invalid DEFAULT for column 'j'"));
+    assert!(err.contains("requires a JSON array"));
+}
+
+#[test]
+fn test_json_key_order_is_canonical_for_equality_and_group_by() {
+    let mut db = test_db();
+    db.execute("create table t (id int, doc json)").unwrap();
+    db.execute(
+        r#"insert into t values (1, "{\"b\":1,\"a\":{\"y\":[{\"q\":1,\"p\":2}],\"x\":null}}")"#,
+    )
+    .unwrap();
+    db.execute(
+        r#"insert into t values (2, "{\"a\":{\"x\":null,\"y\":[{\"p\":2,\"q\":1}]},\"b\":1}")"#,
+    )
+    .unwrap();
+
+    let out = db.execute_legacy("select doc from t where id = 2").unwrap();
+    assert_eq!(
+        out,
+        r#"doc
+{"a":{"x":null,"y":[{"p":2,"q":1}]},"b":1}"#
+    );
+    let out = db
+        .execute_legacy(r#"select id from t where doc = "{\"b\":1,\"a\":{\"x\":null,\"y\":[{\"q\":1,\"p\":2}]}}" order by id"#)
+        .unwrap();
+    assert_eq!(out, "id\n1\n2");
+    let out = db
+        .execute_legacy("select doc, count(*) from t group by doc")
+        .unwrap();
+    assert_eq!(
+        out,
+        r#"doc	count(*)
+{"a":{"x":null,"y":[{"p":2,"q":1}]},"b":1}	2"#
+    );
+}
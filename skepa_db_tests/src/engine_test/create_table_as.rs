@@ -0,0 +1,135 @@
+use super::*;
+
+fn seed_orders(db: &mut Database) {
+    seed_users_3(db);
+    db.execute_legacy(
+        "create table orders (id int primary key, user_id int, total bigint, note varchar(8) not null)",
+    )
+    .unwrap();
+    for (id, user_id, total) in [(1, 1, 5), (2, 1, 50), (3, 3, 7)] {
+        db.execute_legacy(&format!(
+            r#"insert into orders values ({id}, {user_id}, {total}, "n{id}")"#
+        ))
+        .unwrap();
+    }
+}
+
+fn column_types(db: &mut Database, table: &str) -> Vec<(Value, Value)> {
+    match db.execute(&format!("describe {table}")).unwrap() {
+        QueryResult::Select { rows, .. } => rows
+            .into_iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect(),
+        other => panic!("expected describe rows, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_create_table_as_copies_a_projection() {
+    let mut db = test_db();
+    seed_orders(&mut db);
+
+    assert_eq!(
+        db.execute_legacy("create table big as select id, total, note from orders where total > 6")
+            .unwrap(),
+        "created table big (2 row(s))"
+    );
+    assert_eq!(
+        db.execute_legacy("select * from big order by id").unwrap(),
+        "id\ttotal\tnote\n2\t50\tn2\n3\t7\tn3"
+    );
+    // Names and types come from the SELECT; constraints do not.
+    let text = |s: &str| Value::Text(s.to_string());
+    assert_eq!(
+        column_types(&mut db, "big"),
+        vec![
+            (text("id"), text("int")),
+            (text("total"), text("bigint")),
+            (text("note"), text("varchar(8)")),
+        ]
+    );
+    db.execute_legacy("insert into big values (2, null, null)")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("create table none as select id from orders where total > 500")
+            .unwrap(),
+        "created table none (0 row(s))"
+    );
+    assert_eq!(db.execute_legacy("select * from none").unwrap(), "id");
+
+    let path = db.path().clone();
+    drop(db);
+    let mut db = Database::open_legacy(path);
+    assert_eq!(
+        db.execute_legacy("select count(*) from big").unwrap(),
+        "count(*)\n3"
+    );
+}
+
+#[test]
+fn test_create_table_as_copies_a_join_result() {
+    let mut db = test_db();
+    seed_orders(&mut db);
+
+    assert_eq!(
+        db.execute_legacy(
+            "create table spend as select users.name, orders.total from users join orders on users.id = orders.user_id"
+        )
+        .unwrap(),
+        "created table spend (3 row(s))"
+    );
+    assert_eq!(
+        db.execute_legacy("select name, total from spend order by total")
+            .unwrap(),
+        "name\ttotal\na\t5\nc\t7\na\t50"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "create table counts as select name, count(*) as n from spend group by name"
+        )
+        .unwrap(),
+        "created table counts (2 row(s))"
+    );
+    assert_eq!(
+        db.execute_legacy("select * from counts order by name")
+            .unwrap(),
+        "name\tn\na\t2\nc\t1"
+    );
+}
+
+#[test]
+fn test_create_table_as_rejects_bad_targets_and_columns() {
+    let mut db = test_db();
+    seed_orders(&mut db);
+
+    let err = db
+        .execute_legacy("create table users as select * from orders")
+        .unwrap_err();
+    assert_eq!(err, "Table 'users' already exists");
+    let err = db
+        .execute_legacy("create table t as select count(*) from orders")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "CREATE TABLE ... AS: output column 'count(*)' is not a column name; give it one with AS"
+    );
+    let err = db
+        .execute_legacy(
+            "create table t as select users.id, orders.id from users join orders on users.id = orders.user_id",
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "CREATE TABLE ... AS: the SELECT returns more than one column named 'id'; rename them with AS"
+    );
+    db.execute_legacy("begin").unwrap();
+    let err = db
+        .execute_legacy("create table t as select * from orders")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "CREATE TABLE ... AS is auto-commit and cannot run inside an active transaction"
+    );
+    db.execute_legacy("rollback").unwrap();
+    assert!(db.execute_legacy("select * from t").is_err());
+}
@@ -79,7 +79,9 @@ fn test_insert_wrong_value_count() {
 
     let result = db.execute_legacy(r#"insert into users values (1, "ram", "extra")"#);
     assert!(result.is_err());
-    assert!(result.unwrap_err().contains("Expected 2 values but got 3"));
+    let err = result.unwrap_err();
+    assert!(err.starts_with("Expected 2 values"));
+    assert!(err.contains("Expected 2 values (id, name) but got 3"));
 }
 
 #[test]
@@ -144,6 +146,77 @@ fn test_update_multiple_columns() {
     assert_eq!(result, "id\tname\tage\n1\travi\t25");
 }
 
+#[test]
+fn test_update_assignments_read_the_row_before_the_update() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, a int, b int, c bigint, name text)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (1, 10, 20, 0, "x")"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (2, 30, 40, 0, "y")"#)
+        .unwrap();
+
+    db.execute_legacy("update t set a = b, b = a, c = a where id = 1")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select id, a, b, c from t order by id asc")
+            .unwrap(),
+        "id\ta\tb\tc\n1\t20\t10\t10\n2\t30\t40\t0"
+    );
+
+    // A double-quoted value is always a literal.
+    db.execute_legacy(r#"update t set name = "a", a = id where id = 2"#)
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select name, a from t where id = 2")
+            .unwrap(),
+        "name\ta\na\t2"
+    );
+
+    let err = db
+        .execute_legacy("update t set a = name where id = 1")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "Cannot assign column 'name' to 'a': Expected int but got 'x'"
+    );
+}
+
+#[test]
+fn test_update_swaps_columns_named_bare() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, a text, b text)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (1, "x", "y")"#)
+        .unwrap();
+
+    db.execute_legacy("update t set a = b, b = a where id = 1")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select a, b from t").unwrap(),
+        "a\tb\ny\tx"
+    );
+
+    db.execute_legacy("update t set a = t.b where id = 1")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select a, b from t").unwrap(),
+        "a\tb\nx\tx"
+    );
+    let err = db
+        .execute_legacy("update t set a = t.zz where id = 1")
+        .unwrap_err();
+    assert_eq!(err, "Unknown column 't.zz' in UPDATE");
+
+    // Quoting keeps the text of a column name as a literal.
+    db.execute_legacy(r#"update t set a = "b" where id = 1"#)
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select a, b from t").unwrap(),
+        "a\tb\nb\tx"
+    );
+}
+
 #[test]
 fn test_update_where_like() {
     let mut db = test_db();
@@ -240,7 +313,24 @@ fn test_where_in_type_mismatch_errors() {
     let err = db
         .execute_legacy(r#"select * from users where id in ("x","y")"#)
         .unwrap_err();
-    assert!(err.contains("Expected int"));
+    assert_eq!(
+        err,
+        "WHERE IN item 1: value 'x' is not valid for column 'id' (int)"
+    );
+}
+
+#[test]
+fn test_where_in_type_mismatch_names_the_item() {
+    let mut db = test_db();
+    db.execute_legacy("create table users (id int, city text)")
+        .unwrap();
+    let err = db
+        .execute_legacy(r#"select * from users where id in (1, "x","y")"#)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "WHERE IN item 2: value 'x' is not valid for column 'id' (int)"
+    );
 }
 
 #[test]
@@ -333,9 +423,10 @@ fn test_update_type_mismatch_errors() {
     db.execute_legacy(r#"insert into users values (1, "ram", 20)"#)
         .unwrap();
 
-    let result = db.execute_legacy(r#"update users set age = "bad" where id = 1"#);
-    assert!(result.is_err());
-    assert!(result.unwrap_err().to_lowercase().contains("expected int"));
+    let err = db
+        .execute_legacy(r#"update users set age = "bad" where id = 1"#)
+        .unwrap_err();
+    assert_eq!(err, "SET: value 'bad' is not valid for column 'age' (int)");
 }
 
 #[test]
@@ -677,3 +768,105 @@ fn test_delete_all_rows_using_like_star_then_insert_again() {
         "id\tname\n3\tc"
     );
 }
+
+#[test]
+fn test_insert_returning_reports_defaulted_columns() {
+    let mut db = test_db();
+    db.execute(
+        r#"create table users (id int, name text default "anon", active bool default true)"#,
+    )
+    .unwrap();
+
+    let out = db
+        .execute("insert into users values (1) returning id, name, active as on")
+        .unwrap();
+    assert_returning_result(
+        out,
+        &["id", "name", "on"],
+        vec![vec![
+            Value::Int(1),
+            Value::Text("anon".to_string()),
+            Value::Bool(true),
+        ]],
+        1,
+    );
+
+    let out = db
+        .execute(r#"insert into users values (2, "ram") returning *"#)
+        .unwrap();
+    assert_returning_result(
+        out,
+        &["id", "name", "active"],
+        vec![vec![
+            Value::Int(2),
+            Value::Text("ram".to_string()),
+            Value::Bool(true),
+        ]],
+        1,
+    );
+}
+
+#[test]
+fn test_update_and_delete_returning_report_new_and_removed_rows() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+
+    let out = db
+        .execute(r#"update users set name = "z" where age < 25 returning id, name"#)
+        .unwrap();
+    assert_returning_result(
+        out,
+        &["id", "name"],
+        vec![
+            vec![Value::Int(2), Value::Text("z".to_string())],
+            vec![Value::Int(3), Value::Text("z".to_string())],
+        ],
+        2,
+    );
+
+    let out = db
+        .execute("delete from users where id = 3 returning name, age")
+        .unwrap();
+    assert_returning_result(
+        out,
+        &["name", "age"],
+        vec![vec![Value::Text("z".to_string()), Value::Int(10)]],
+        1,
+    );
+
+    let out = db
+        .execute("delete from users where id = 99 returning id")
+        .unwrap();
+    assert_returning_result(out, &["id"], Vec::new(), 0);
+}
+
+#[test]
+fn test_returning_unknown_column_leaves_table_unchanged() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+
+    let err = db
+        .execute("delete from users where id = 1 returning nope")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Unknown column 'nope'"), "{err}");
+
+    let result = db.execute("select id from users where id = 1").unwrap();
+    assert_select_result(result, &["id"], vec![vec![Value::Int(1)]]);
+}
+
+#[test]
+fn test_returning_inside_transaction_shows_staged_rows() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("begin").unwrap();
+
+    let out = db
+        .execute("update users set age = 31 where id = 1 returning age")
+        .unwrap();
+    assert_returning_result(out, &["age"], vec![vec![Value::Int(31)]], 1);
+
+    db.execute("rollback").unwrap();
+    let result = db.execute("select age from users where id = 1").unwrap();
+    assert_select_result(result, &["age"], vec![vec![Value::Int(30)]]);
+}
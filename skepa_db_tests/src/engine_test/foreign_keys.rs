@@ -235,6 +235,39 @@ fn test_fk_create_rejects_mismatched_column_count() {
     assert!(err.to_lowercase().contains("column count"));
 }
 
+#[test]
+fn test_fk_rejects_composite_columns_with_swapped_types() {
+    let mut db = test_db();
+    db.execute("create table p (x int, y text, primary key(x,y))")
+        .unwrap();
+    let err = db
+        .execute_legacy("create table c (a int, b int, foreign key(a,b) references p(x,y))")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "FOREIGN KEY column 2 type mismatch: b is int but p.y is text"
+    );
+
+    let err = db
+        .execute_legacy("create table c (a text, b int, foreign key(a,b) references p(x,y))")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "FOREIGN KEY column 1 type mismatch: a is text but p.x is int"
+    );
+
+    db.execute("create table c (a text, b int)").unwrap();
+    let err = db
+        .execute_legacy("alter table c add foreign key(b,a) references p(y,x)")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "FOREIGN KEY column 1 type mismatch: b is int but p.y is text"
+    );
+    db.execute("alter table c add foreign key(b,a) references p(x,y)")
+        .unwrap();
+}
+
 #[test]
 fn test_fk_create_requires_parent_key_or_unique() {
     let mut db = test_db();
@@ -724,3 +757,310 @@ fn test_foreign_key_on_update_cascade_propagates_to_grandchildren() {
         "id\tcid\n100\t2"
     );
 }
+
+fn seed_parent_with_two_way_child(db: &mut Database) {
+    db.execute("create table parent (id int primary key)")
+        .unwrap();
+    db.execute(
+        "create table child (id int primary key, tag text unique, parent_id int, owner_id int, \
+         foreign key(parent_id) references parent(id) on delete cascade on update cascade, \
+         foreign key(owner_id) references parent(id) on delete set null on update set null)",
+    )
+    .unwrap();
+    db.execute("create index on child (owner_id)").unwrap();
+    for id in 1..=3 {
+        db.execute(&format!("insert into parent values ({id})"))
+            .unwrap();
+    }
+    db.execute(r#"insert into child values (10, "a", 1, 2)"#)
+        .unwrap();
+    db.execute(r#"insert into child values (11, "b", 2, 1)"#)
+        .unwrap();
+    db.execute(r#"insert into child values (12, "c", 1, 1)"#)
+        .unwrap();
+    db.execute(r#"insert into child values (13, "d", 3, 1)"#)
+        .unwrap();
+    db.execute(r#"insert into child values (14, "e", 2, 3)"#)
+        .unwrap();
+}
+
+fn assert_child_lookups_match(db: &mut Database, expected: &[(i64, &str)]) {
+    for (id, tag) in expected {
+        assert_select_result(
+            db.execute(&format!("select tag from child where id = {id}"))
+                .unwrap(),
+            &["tag"],
+            vec![vec![Value::Text(tag.to_string())]],
+        );
+        assert_select_result(
+            db.execute(&format!(r#"select id from child where tag = "{tag}""#))
+                .unwrap(),
+            &["id"],
+            vec![vec![Value::Int(*id)]],
+        );
+    }
+}
+
+#[test]
+fn test_cascade_delete_and_set_null_on_same_child_table_keep_indexes_consistent() {
+    let mut db = test_db();
+    seed_parent_with_two_way_child(&mut db);
+
+    // One statement deletes child rows 10 and 12 and nulls owner_id on 11
+    // and 13 in the same child table.
+    db.execute("delete from parent where id = 1").unwrap();
+    db.check_integrity().unwrap();
+
+    assert_select_result(
+        db.execute("select id, parent_id, owner_id from child order by id asc")
+            .unwrap(),
+        &["id", "parent_id", "owner_id"],
+        vec![
+            vec![Value::Int(11), Value::Int(2), Value::Null],
+            vec![Value::Int(13), Value::Int(3), Value::Null],
+            vec![Value::Int(14), Value::Int(2), Value::Int(3)],
+        ],
+    );
+    assert_child_lookups_match(&mut db, &[(11, "b"), (13, "d"), (14, "e")]);
+    assert_select_result(
+        db.execute("select id from child where owner_id = 3")
+            .unwrap(),
+        &["id"],
+        vec![vec![Value::Int(14)]],
+    );
+    db.execute(r#"insert into child values (10, "a", 2, null)"#)
+        .unwrap();
+    db.check_integrity().unwrap();
+}
+
+#[test]
+fn test_cascade_update_and_set_null_then_delete_keep_indexes_consistent() {
+    let mut db = test_db();
+    seed_parent_with_two_way_child(&mut db);
+
+    db.execute("update parent set id = 7 where id = 1").unwrap();
+    db.check_integrity().unwrap();
+    assert_select_result(
+        db.execute("select id, parent_id, owner_id from child order by id asc")
+            .unwrap(),
+        &["id", "parent_id", "owner_id"],
+        vec![
+            vec![Value::Int(10), Value::Int(7), Value::Int(2)],
+            vec![Value::Int(11), Value::Int(2), Value::Null],
+            vec![Value::Int(12), Value::Int(7), Value::Null],
+            vec![Value::Int(13), Value::Int(3), Value::Null],
+            vec![Value::Int(14), Value::Int(2), Value::Int(3)],
+        ],
+    );
+
+    db.execute("delete from parent where id = 2").unwrap();
+    db.check_integrity().unwrap();
+    assert_child_lookups_match(&mut db, &[(10, "a"), (12, "c"), (13, "d")]);
+    assert_select_result(
+        db.execute("select id from child where owner_id = 2")
+            .unwrap(),
+        &["id"],
+        vec![],
+    );
+}
+
+#[test]
+fn test_fk_delete_set_null_with_update_cascade() {
+    let mut db = test_db();
+    db.execute("create table p (id int primary key, code text unique)")
+        .unwrap();
+    db.execute(
+        "create table c (id int primary key, pcode text unique, \
+         foreign key(pcode) references p(code) on delete set null on update cascade)",
+    )
+    .unwrap();
+    for (id, code) in [(1, "a"), (2, "b"), (3, "x")] {
+        db.execute(&format!(r#"insert into p values ({id}, "{code}")"#))
+            .unwrap();
+        db.execute(&format!(r#"insert into c values ({}, "{code}")"#, id + 10))
+            .unwrap();
+    }
+
+    db.execute("delete from p where id = 1").unwrap();
+    db.execute("delete from p where id = 3").unwrap();
+    db.execute(r#"update p set code = "c" where id = 2"#)
+        .unwrap();
+    let expected = vec![
+        vec![Value::Int(11), Value::Null],
+        vec![Value::Int(12), Value::Text("c".to_string())],
+        vec![Value::Int(13), Value::Null],
+    ];
+    assert_select_result(
+        db.execute("select * from c").unwrap(),
+        &["id", "pcode"],
+        expected.clone(),
+    );
+
+    // Children nulled by SET NULL must not follow a parent whose UNIQUE
+    // key is NULL, whether that parent is updated or deleted.
+    db.execute("insert into p values (4, null)").unwrap();
+    db.execute(r#"update p set code = "d" where id = 4"#)
+        .unwrap();
+    db.execute("insert into p values (5, null)").unwrap();
+    db.execute("delete from p where id = 5").unwrap();
+    assert_select_result(
+        db.execute("select * from c").unwrap(),
+        &["id", "pcode"],
+        expected,
+    );
+    db.check_integrity().unwrap();
+}
+
+#[test]
+fn test_fk_null_child_does_not_reference_null_parent_key() {
+    let mut db = test_db();
+    db.execute("create table p (id int primary key, code text unique)")
+        .unwrap();
+    db.execute(
+        "create table c (id int, pcode text, foreign key(pcode) references p(code) on delete cascade)",
+    )
+    .unwrap();
+    db.execute("create table r (id int, pcode text, foreign key(pcode) references p(code))")
+        .unwrap();
+    db.execute("insert into p values (1, null)").unwrap();
+    db.execute("insert into c values (10, null)").unwrap();
+    db.execute("insert into r values (20, null)").unwrap();
+
+    db.execute("delete from p where id = 1").unwrap();
+    assert_select_result(
+        db.execute("select id from c").unwrap(),
+        &["id"],
+        vec![vec![Value::Int(10)]],
+    );
+}
+
+#[test]
+fn test_fk_cascade_counts_are_reported() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key)")
+        .unwrap();
+    db.execute(
+        "create table orders (id int primary key, user_id int, \
+         foreign key(user_id) references users(id) on delete cascade on update cascade)",
+    )
+    .unwrap();
+    db.execute(
+        "create table items (id int, order_id int, \
+         foreign key(order_id) references orders(id) on delete cascade)",
+    )
+    .unwrap();
+    db.execute(
+        "create table notes (id int, user_id int, \
+         foreign key(user_id) references users(id) on delete set null on update set null)",
+    )
+    .unwrap();
+    for sql in [
+        "insert into users values (1)",
+        "insert into users values (2)",
+        "insert into orders values (10, 1)",
+        "insert into orders values (11, 1)",
+        "insert into orders values (12, 2)",
+        "insert into items values (100, 10)",
+        "insert into items values (101, 10)",
+        "insert into items values (102, 11)",
+        "insert into items values (103, 12)",
+        "insert into notes values (1000, 1)",
+        "insert into notes values (1001, 2)",
+    ] {
+        db.execute(sql).unwrap();
+    }
+
+    assert_mutation_result(
+        db.execute("update users set id = 3 where id = 2").unwrap(),
+        "updated 1 row(s) in users (cascaded: 1 notes, 1 orders)",
+        1,
+    );
+    assert_mutation_result(
+        db.execute("delete from users where id = 1").unwrap(),
+        "deleted 1 row(s) from users (cascaded: 1 notes, 2 orders, 3 items)",
+        1,
+    );
+    assert_mutation_result(
+        db.execute("delete from items where id = 103").unwrap(),
+        "deleted 1 row(s) from items",
+        1,
+    );
+}
+
+#[test]
+fn test_fk_references_composite_unique_parent() {
+    let mut db = test_db();
+    db.execute_legacy("create table parent (id int primary key, a int, b text, unique(a,b))")
+        .unwrap();
+    db.execute_legacy(
+        "create table child (id int, a int, b text, foreign key(a,b) references parent(a,b) on delete cascade)",
+    )
+    .unwrap();
+    db.execute_legacy(
+        "create table reversed (id int, b text, a int, foreign key(b,a) references parent(b,a))",
+    )
+    .unwrap();
+    db.execute_legacy(r#"insert into parent values (1, 1, "x")"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into parent values (2, 2, "y")"#)
+        .unwrap();
+
+    db.execute_legacy(r#"insert into child values (1, 1, "x")"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into child values (2, 2, "y")"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into reversed values (1, "y", 2)"#)
+        .unwrap();
+    for bad in [
+        r#"insert into child values (3, 1, "y")"#,
+        r#"insert into child values (3, 3, "x")"#,
+        r#"insert into reversed values (2, "x", 2)"#,
+    ] {
+        let err = db.execute_legacy(bad).unwrap_err();
+        assert!(err.contains("FOREIGN KEY violation"), "{bad}: {err}");
+    }
+
+    db.execute_legacy("delete from parent where id = 1")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from child").unwrap(),
+        "id\ta\tb\n2\t2\ty"
+    );
+
+    let err = db
+        .execute_legacy("create table partial (a int, foreign key(a) references parent(a))")
+        .unwrap_err();
+    assert!(err.contains("must target PRIMARY KEY or UNIQUE columns"));
+}
+
+#[test]
+fn test_fk_alter_add_composite_unique_rejects_orphans() {
+    let mut db = test_db();
+    db.execute_legacy("create table parent (a int, b int, unique(a,b))")
+        .unwrap();
+    db.execute_legacy("create table child (id int, a int, b int)")
+        .unwrap();
+    db.execute_legacy("insert into parent values (1, 2)")
+        .unwrap();
+    db.execute_legacy("insert into child values (1, 1, 2)")
+        .unwrap();
+    db.execute_legacy("insert into child values (2, 2, 1)")
+        .unwrap();
+
+    let err = db
+        .execute_legacy("alter table child add foreign key(a,b) references parent(a,b)")
+        .unwrap_err();
+    assert!(err.contains("FOREIGN KEY violation on (a,b) references parent(a,b)"));
+    // The rejected constraint is not left behind.
+    db.execute_legacy("insert into child values (3, 9, 9)")
+        .unwrap();
+
+    db.execute_legacy("delete from child where id = 2").unwrap();
+    db.execute_legacy("delete from child where id = 3").unwrap();
+    db.execute_legacy("alter table child add foreign key(b,a) references parent(b,a)")
+        .unwrap();
+    let err = db
+        .execute_legacy("insert into child values (4, 2, 1)")
+        .unwrap_err();
+    assert!(err.contains("FOREIGN KEY violation"));
+}
@@ -11,6 +11,69 @@ fn test_create_and_drop_secondary_index() {
     assert_eq!(out, "dropped index on users(email)");
 }
 
+#[test]
+fn test_create_show_and_drop_named_index() {
+    let mut db = test_db();
+    db.execute_legacy("create table users (id int primary key, email text, bio text)")
+        .unwrap();
+    let out = db
+        .execute_legacy("create index idx_email on users (email)")
+        .unwrap();
+    assert_eq!(out, "created index idx_email on users(email)");
+    db.execute_legacy("create index idx_bio on users (bio) using trigram")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("show indexes on users").unwrap(),
+        "kind\tcolumns\tname\nprimary key\tid\tnull\nindex\temail\tidx_email\ntrigram\tbio\tidx_bio"
+    );
+
+    let out = db.execute_legacy("drop index idx_bio").unwrap();
+    assert_eq!(out, "dropped index idx_bio on users(bio) using trigram");
+    // Dropping by columns also forgets the name.
+    db.execute_legacy("drop index on users (email)").unwrap();
+    assert_eq!(
+        db.execute_legacy("show indexes on users").unwrap(),
+        "kind\tcolumns\tname\nprimary key\tid\tnull"
+    );
+    let err = db.execute_legacy("drop index idx_email").unwrap_err();
+    assert_eq!(err, "index idx_email does not exist");
+}
+
+#[test]
+fn test_index_names_are_unique_across_tables() {
+    let mut db = test_db();
+    db.execute_legacy("create table a (x int)").unwrap();
+    db.execute_legacy("create table b (y int)").unwrap();
+    db.execute_legacy("create index idx on a (x)").unwrap();
+    let err = db.execute_legacy("create index idx on b (y)").unwrap_err();
+    assert_eq!(err, "index idx already exists");
+    assert_eq!(db.table_stats("b").unwrap().index_count, 0);
+    let out = db
+        .execute_legacy("create index if not exists idx on b (y)")
+        .unwrap();
+    assert_eq!(out, "index idx on b(y) already exists, skipped");
+    assert_eq!(db.table_stats("b").unwrap().index_count, 0);
+}
+
+#[test]
+fn test_named_index_survives_reopen() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_named_index_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table users (id int, email text)")
+            .unwrap();
+        db.execute("create index idx_email on users (email)")
+            .unwrap();
+    }
+    let mut db = Database::open_legacy(path);
+    let plan = db.describe_statement("drop index idx_email").unwrap();
+    assert_eq!(plan.tables, vec!["users"]);
+    db.execute("drop index idx_email").unwrap();
+    assert_eq!(db.table_stats("users").unwrap().index_count, 0);
+}
+
 #[test]
 fn test_secondary_index_select_update_delete_eq_paths() {
     let mut db = test_db();
@@ -54,7 +117,29 @@ fn test_create_duplicate_secondary_index_errors() {
     let err = db
         .execute_legacy("create index on users (city)")
         .unwrap_err();
-    assert!(err.to_lowercase().contains("already exists"));
+    assert_eq!(err, "index on users(city) already exists");
+    assert_eq!(db.table_stats("users").unwrap().index_count, 1);
+}
+
+#[test]
+fn test_create_index_if_not_exists_skips_existing_index() {
+    let mut db = test_db();
+    db.execute_legacy("create table users (id int, city text)")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("create index if not exists on users (city)")
+            .unwrap(),
+        "created index on users(city)"
+    );
+    assert_eq!(
+        db.execute_legacy("create index if not exists on users (city)")
+            .unwrap(),
+        "index on users(city) already exists, skipped"
+    );
+    let err = db
+        .execute_legacy("create index if not exists on missing (city)")
+        .unwrap_err();
+    assert!(err.contains("does not exist"), "{err}");
 }
 
 #[test]
@@ -63,5 +148,388 @@ fn test_drop_missing_secondary_index_errors() {
     db.execute_legacy("create table users (id int, city text)")
         .unwrap();
     let err = db.execute_legacy("drop index on users (city)").unwrap_err();
-    assert!(err.to_lowercase().contains("does not exist"));
+    assert_eq!(err, "index on users(city) does not exist");
+}
+
+#[test]
+fn test_drop_index_on_constraint_columns_names_the_constraint() {
+    let mut db = test_db();
+    db.execute_legacy("create table users (id int primary key, email text unique)")
+        .unwrap();
+    let err = db.execute_legacy("drop index on users (id)").unwrap_err();
+    assert_eq!(
+        err,
+        "index on users(id) backs the PRIMARY KEY constraint; drop the constraint instead"
+    );
+    let err = db
+        .execute_legacy("drop index on users (email)")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "index on users(email) backs the UNIQUE constraint; drop the constraint instead"
+    );
+}
+
+#[test]
+fn test_show_indexes_lists_implicit_and_declared_indexes() {
+    let mut db = test_db();
+    db.execute_legacy(
+        "create table users (id int primary key, email text unique, city text, age int, bio text, unique(city, age))",
+    )
+    .unwrap();
+    db.execute_legacy("create index on users (age, city)")
+        .unwrap();
+    db.execute_legacy("create index on users (bio) using trigram")
+        .unwrap();
+
+    assert_eq!(
+        db.execute_legacy("show indexes from users").unwrap(),
+        "kind\tcolumns\tname\nprimary key\tid\tnull\nunique\temail\tnull\nunique\tcity,age\tnull\nindex\tage,city\tnull\ntrigram\tbio\tnull"
+    );
+    assert_eq!(db.table_stats("users").unwrap().index_count, 5);
+
+    db.execute_legacy("create table plain (v int)").unwrap();
+    assert_eq!(
+        db.execute_legacy("show indexes from plain").unwrap(),
+        "kind\tcolumns\tname"
+    );
+    let err = db.execute_legacy("show indexes from missing").unwrap_err();
+    assert!(err.contains("Table 'missing' does not exist"), "{err}");
+}
+
+#[test]
+fn test_order_by_matches_with_and_without_secondary_index() {
+    let queries = [
+        "select id, grp from t where age = 30 order by grp",
+        "select id, grp from t where age = 30 order by grp desc",
+        "select id, grp, score from t where age = 30 order by grp, score desc",
+        "select id from t where age = 30",
+    ];
+    let mut outputs: Vec<Vec<String>> = Vec::new();
+    for with_index in [false, true] {
+        let mut db = test_db();
+        db.execute("create table t (id int, age int, grp int, score int)")
+            .unwrap();
+        if with_index {
+            db.execute("create index on t (age)").unwrap();
+        }
+        db.execute("insert into t values (1, 30, null, 5)").unwrap();
+        db.execute("insert into t values (2, 30, 2, null)").unwrap();
+        db.execute("insert into t values (3, 31, 1, 1)").unwrap();
+        db.execute("insert into t values (4, 30, null, null)")
+            .unwrap();
+        db.execute("insert into t values (5, 30, 2, 7)").unwrap();
+        // Move row 1 out of and back into the indexed key.
+        db.execute("update t set age = 31 where id = 1").unwrap();
+        db.execute("update t set age = 30 where id = 1").unwrap();
+        outputs.push(
+            queries
+                .iter()
+                .map(|q| db.execute_legacy(q).unwrap())
+                .collect(),
+        );
+    }
+    assert_eq!(outputs[0], outputs[1]);
+    assert_eq!(outputs[0][0], "id\tgrp\n1\tnull\n4\tnull\n2\t2\n5\t2");
+    assert_eq!(outputs[0][1], "id\tgrp\n2\t2\n5\t2\n1\tnull\n4\tnull");
+    assert_eq!(
+        outputs[0][2],
+        "id\tgrp\tscore\n1\tnull\t5\n4\tnull\tnull\n5\t2\t7\n2\t2\tnull"
+    );
+}
+
+fn select_stats(db: &mut Database, sql: &str) -> (Vec<Vec<Value>>, Option<bool>, Option<usize>) {
+    match db.execute(sql).unwrap() {
+        QueryResult::Select { rows, stats, .. } => (rows, stats.index_used, stats.rows_scanned),
+        other => panic!("expected select result, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_trigram_index_matches_scan_results_for_like() {
+    let mut db = test_db();
+    db.execute("create table logs (id int primary key, body text)")
+        .unwrap();
+    let bodies = [
+        "disk error on sda",
+        "all good",
+        "network ERROR",
+        "héllo wörld ошибка",
+        "error",
+        "errors everywhere",
+        "ab",
+    ];
+    for (i, body) in bodies.iter().enumerate() {
+        db.execute(&format!(r#"insert into logs values ({i}, "{body}")"#))
+            .unwrap();
+    }
+
+    let patterns = [
+        "*error*",
+        "error*",
+        "*rror",
+        "*ошиб*",
+        "*wörld*",
+        "*err?r*",
+        "*",
+        "??",
+        "*ab*",
+        "*zzz*",
+    ];
+    let mut scans = Vec::new();
+    for p in patterns {
+        let sql = format!(r#"select id from logs where body like "{p}" order by id asc"#);
+        scans.push(select_stats(&mut db, &sql).0);
+    }
+
+    let out = db
+        .execute_legacy("create index on logs (body) using trigram")
+        .unwrap();
+    assert_eq!(out, "created index on logs(body) using trigram");
+
+    for (p, expected) in patterns.iter().zip(&scans) {
+        let sql = format!(r#"select id from logs where body like "{p}" order by id asc"#);
+        let (rows, index_used, scanned) = select_stats(&mut db, &sql);
+        assert_eq!(&rows, expected, "pattern {p}");
+        let has_run = p.split(['*', '?']).any(|s| s.chars().count() >= 3);
+        assert_eq!(index_used, Some(has_run), "pattern {p}");
+        if has_run {
+            assert!(scanned.unwrap() < bodies.len(), "pattern {p}");
+        }
+    }
+}
+
+#[test]
+fn test_trigram_index_tracks_mutations_and_reopen() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_trigram_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let query = r#"select id from logs where body like "*timeout*" order by id asc"#;
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table logs (id int, body text)").unwrap();
+        db.execute("create index on logs (body) using trigram")
+            .unwrap();
+        db.execute(r#"insert into logs values (1, "request timeout")"#)
+            .unwrap();
+        db.execute(r#"insert into logs values (2, "ok")"#).unwrap();
+        db.execute(r#"update logs set body = "timeout again" where id = 2"#)
+            .unwrap();
+        db.execute(r#"insert into logs values (3, "timeout later")"#)
+            .unwrap();
+        db.execute("delete from logs where id = 1").unwrap();
+        let (rows, index_used, _) = select_stats(&mut db, query);
+        assert_eq!(rows, vec![vec![Value::Int(2)], vec![Value::Int(3)]]);
+        assert_eq!(index_used, Some(true));
+    }
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let (rows, index_used, _) = select_stats(&mut db, query);
+        assert_eq!(rows, vec![vec![Value::Int(2)], vec![Value::Int(3)]]);
+        assert_eq!(index_used, Some(true));
+        let out = db.execute_legacy("describe logs").unwrap();
+        assert!(out.contains("body using trigram"), "{out}");
+        let out = db
+            .execute_legacy("drop index on logs (body) using trigram")
+            .unwrap();
+        assert_eq!(out, "dropped index on logs(body) using trigram");
+        let (_, index_used, _) = select_stats(&mut db, query);
+        assert_eq!(index_used, Some(false));
+    }
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_trigram_index_rejects_non_text_and_multi_column() {
+    let mut db = test_db();
+    db.execute("create table logs (id int, body text, tag text)")
+        .unwrap();
+    let err = db
+        .execute_legacy("create index on logs (id) using trigram")
+        .unwrap_err();
+    assert!(err.contains("requires a text column"), "{err}");
+    let err = db
+        .execute_legacy("create index on logs (body, tag) using trigram")
+        .unwrap_err();
+    assert!(err.contains("exactly one column"), "{err}");
+    db.execute("create index on logs (body) using trigram")
+        .unwrap();
+    let err = db
+        .execute_legacy("create index on logs (body) using trigram")
+        .unwrap_err();
+    assert!(err.contains("already exists"), "{err}");
+    db.execute("create index if not exists on logs (body) using trigram")
+        .unwrap();
+}
+
+fn seed_events(db: &mut Database, table: &str, partition: &str) {
+    db.execute(&format!(
+        "create table {table} (id int primary key, day int, kind text){partition}"
+    ))
+    .unwrap();
+    let rows = [
+        (1, "1", "open"),
+        (2, "2", "close"),
+        (3, "1", "close"),
+        (4, "3", "open"),
+        (5, "5", "open"),
+        (6, "2", "open"),
+        (7, "4", "close"),
+        (8, "3", "close"),
+    ];
+    for (id, day, kind) in rows {
+        db.execute(&format!(
+            r#"insert into {table} values ({id}, {day}, "{kind}")"#
+        ))
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_partitioned_table_select_matches_unpartitioned() {
+    let mut db = test_db();
+    seed_events(&mut db, "events", " partition by (day)");
+    seed_events(&mut db, "flat", "");
+
+    let pruned = [
+        "day = 2",
+        "day > 2",
+        "day >= 2 and day < 4",
+        "day in (1, 3)",
+        r#"day = 3 and kind = "open""#,
+        "day = 9",
+        "day = 1 or day = 4",
+    ];
+    let scanned = [r#"kind = "open""#, r#"day = 2 or kind = "open""#, "id > 3"];
+    for filter in pruned.iter().chain(&scanned) {
+        let (rows, _, rows_scanned) = select_stats(
+            &mut db,
+            &format!("select * from events where {filter} order by id asc"),
+        );
+        let (expected, _, _) = select_stats(
+            &mut db,
+            &format!("select * from flat where {filter} order by id asc"),
+        );
+        assert_eq!(rows, expected, "filter {filter}");
+        if pruned.contains(filter) {
+            assert!(rows_scanned.unwrap() < 8, "filter {filter}");
+        } else {
+            assert_eq!(rows_scanned, Some(8), "filter {filter}");
+        }
+    }
+
+    // Pruned rows come back in insertion order, as a full scan returns them.
+    let (rows, _, scanned) = select_stats(&mut db, "select id from events where day < 3");
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Int(1)],
+            vec![Value::Int(2)],
+            vec![Value::Int(3)],
+            vec![Value::Int(6)]
+        ]
+    );
+    assert_eq!(scanned, Some(4));
+}
+
+#[test]
+fn test_partitioned_table_update_delete_match_unpartitioned() {
+    let mut db = test_db();
+    seed_events(&mut db, "events", " partition by (day)");
+    seed_events(&mut db, "flat", "");
+    let statements = [
+        r#"update {t} set kind = "late" where day > 2"#,
+        "update {t} set day = 4 where id = 1",
+        "delete from {t} where day = 2",
+        r#"delete from {t} where day in (3, 4) and kind = "late""#,
+        "insert into {t} values (9, 2, \"open\")",
+    ];
+    for sql in statements {
+        let events = db.execute(&sql.replace("{t}", "events")).unwrap();
+        let flat = db.execute(&sql.replace("{t}", "flat")).unwrap();
+        assert_eq!(events.rows_affected(), flat.rows_affected(), "{sql}");
+        let (rows, _, _) = select_stats(&mut db, "select * from events order by id asc");
+        let (expected, _, _) = select_stats(&mut db, "select * from flat order by id asc");
+        assert_eq!(rows, expected, "{sql}");
+    }
+    let (rows, _, _) = select_stats(&mut db, "select id from events where day = 4");
+    assert_eq!(rows, vec![vec![Value::Int(1)]]);
+}
+
+#[test]
+fn test_partitioned_table_segments_survive_reopen() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_partition_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let segment_count = |path: &PathBuf| {
+        std::fs::read_dir(path.join("tables"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                name.starts_with("events.") && name != "events.rows"
+            })
+            .count()
+    };
+    {
+        let mut db = Database::open_legacy(path.clone());
+        seed_events(&mut db, "events", " partition by (day)");
+        db.execute("delete from events where day = 4").unwrap();
+    }
+    // Days 1, 2, 3 and 5 each keep a segment; day 4 was emptied.
+    assert_eq!(segment_count(&path), 4);
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let (rows, _, scanned) = select_stats(
+            &mut db,
+            "select id from events where day = 3 order by id asc",
+        );
+        assert_eq!(rows, vec![vec![Value::Int(4)], vec![Value::Int(8)]]);
+        assert_eq!(scanned, Some(2));
+        let (rows, _, _) = select_stats(&mut db, "select id from events");
+        let ids: Vec<Value> = rows.into_iter().map(|r| r[0].clone()).collect();
+        assert_eq!(ids, [1, 2, 3, 4, 5, 6, 8].map(Value::Int).to_vec());
+    }
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_partition_by_rejects_unknown_column() {
+    let mut db = test_db();
+    let err = db
+        .execute_legacy("create table events (id int, day int) partition by (month)")
+        .unwrap_err();
+    assert!(
+        err.contains("PARTITION BY references unknown column 'month'"),
+        "{err}"
+    );
+    assert!(db.execute_legacy("select * from events").is_err());
+}
+
+#[test]
+fn test_partitioned_table_null_partition_and_errors_match_scan() {
+    let mut db = test_db();
+    for (table, partition) in [("events", " partition by (day)"), ("flat", "")] {
+        db.execute(&format!(
+            "create table {table} (id int, day int){partition}"
+        ))
+        .unwrap();
+        db.execute(&format!("insert into {table} values (1, 1)"))
+            .unwrap();
+        db.execute(&format!("insert into {table} values (2, null)"))
+            .unwrap();
+    }
+    let (rows, _, scanned) = select_stats(&mut db, "select id from events where day is null");
+    assert_eq!(rows, vec![vec![Value::Int(2)]]);
+    assert_eq!(scanned, Some(1));
+    // Ordering against NULL is an error in a scan, and stays one when pruned.
+    for filter in ["day > 0", "day = 1 and day > 0"] {
+        let err = db
+            .execute(&format!("select id from events where {filter}"))
+            .unwrap_err();
+        let expected = db
+            .execute(&format!("select id from flat where {filter}"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), expected.to_string(), "filter {filter}");
+    }
 }
@@ -0,0 +1,190 @@
+use super::*;
+
+fn seed_orders(db: &mut Database) {
+    db.execute_legacy("create table orders (id int primary key, customer text, total int)")
+        .unwrap();
+    for (id, customer, total) in [(1, "ann", 5), (2, "bob", 50), (3, "ann", 7)] {
+        db.execute_legacy(&format!(
+            r#"insert into orders values ({id}, "{customer}", {total})"#
+        ))
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_insert_select_copies_every_selected_row() {
+    let mut db = test_db();
+    seed_orders(&mut db);
+    db.execute_legacy("create table archive (id int primary key, customer text, total int)")
+        .unwrap();
+
+    assert_eq!(
+        db.execute_legacy("insert into archive select * from orders where total > 6")
+            .unwrap(),
+        "inserted 2 row(s) into archive"
+    );
+    assert_eq!(
+        db.execute_legacy("select * from archive order by id")
+            .unwrap(),
+        "id\tcustomer\ttotal\n2\tbob\t50\n3\tann\t7"
+    );
+    assert_eq!(
+        db.execute_legacy("insert into archive select * from orders where total > 500")
+            .unwrap(),
+        "inserted 0 row(s) into archive"
+    );
+
+    // The rows reach the WAL as plain INSERTs and survive a reopen.
+    let path = db.path().clone();
+    drop(db);
+    let mut db = Database::open_legacy(path);
+    assert_eq!(
+        db.execute_legacy("select id from archive order by id")
+            .unwrap(),
+        "id\n2\n3"
+    );
+}
+
+#[test]
+fn test_insert_select_with_column_list_fills_the_rest_from_defaults() {
+    let mut db = test_db();
+    seed_orders(&mut db);
+    db.execute_legacy(
+        r#"create table totals (total bigint, note text default "copied", id int, who varchar(3))"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        db.execute_legacy(
+            "insert into totals (id, total, who) select id, total, customer from orders where customer = \"ann\""
+        )
+        .unwrap(),
+        "inserted 2 row(s) into totals"
+    );
+    db.execute_legacy("insert into totals (total) select max(total) from orders")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from totals").unwrap(),
+        "total\tnote\tid\twho\n5\tcopied\t1\tann\n7\tcopied\t3\tann\n50\tcopied\tnull\tnull"
+    );
+}
+
+#[test]
+fn test_insert_select_logs_the_values_it_stored() {
+    use skepa_db_core::config::{AutoCheckpoint, DbConfig};
+
+    let path = test_db().path().clone();
+    let config = DbConfig::new(path).with_auto_checkpoint(AutoCheckpoint::Manual);
+    let mut db = Database::open(config.clone()).unwrap();
+    db.execute("create table notes (body text, price decimal(6,2))")
+        .unwrap();
+    db.execute(r#"insert into notes values ("say \"hi\", ok\\", 1.5)"#)
+        .unwrap();
+    db.execute("create table copies (id uuid default gen_uuid(), body text, price decimal(6,2))")
+        .unwrap();
+    db.execute("insert into copies (body, price) select body, price from notes")
+        .unwrap();
+    let before = db.execute_legacy("select * from copies").unwrap();
+    assert!(before.ends_with("\tsay \"hi\", ok\\\t1.50"), "{before}");
+    drop(db);
+
+    // Replayed from the WAL: the generated DEFAULT and the converted
+    // values come back as they were stored.
+    let mut db = Database::open(config).unwrap();
+    assert_eq!(db.execute_legacy("select * from copies").unwrap(), before);
+}
+
+#[test]
+fn test_insert_select_rejects_mismatched_columns() {
+    let mut db = test_db();
+    seed_orders(&mut db);
+    db.execute_legacy("create table archive (id int, customer text)")
+        .unwrap();
+    db.execute_legacy("create view big as select id from orders where total > 10")
+        .unwrap();
+
+    let err = db
+        .execute_legacy("insert into archive select customer, id from orders")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "INSERT ... SELECT: column 1 'customer' (text) cannot be inserted into column 'id' (int)"
+    );
+    let err = db
+        .execute_legacy("insert into archive select * from orders")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "INSERT ... SELECT: SELECT returns 3 column(s) but 2 are inserted"
+    );
+    let err = db
+        .execute_legacy("insert into archive (id, id) select id, id from orders")
+        .unwrap_err();
+    assert_eq!(err, "Column 'id' is listed more than once in INSERT");
+    let err = db
+        .execute_legacy("insert into archive (missing) select id from orders")
+        .unwrap_err();
+    assert!(err.contains("missing"), "{err}");
+    let err = db
+        .execute_legacy("insert into big select id from orders")
+        .unwrap_err();
+    assert_eq!(err, "Cannot modify view 'big'");
+
+    // A view is a fine source.
+    db.execute_legacy("insert into archive (id) select id from big")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from archive").unwrap(),
+        "id\tcustomer\n2\tnull"
+    );
+}
+
+#[test]
+fn test_insert_select_is_all_or_nothing() {
+    let mut db = test_db();
+    seed_orders(&mut db);
+    db.execute_legacy("create table archive (id int primary key, total int)")
+        .unwrap();
+    db.execute_legacy("insert into archive values (3, 0)")
+        .unwrap();
+
+    let err = db
+        .execute_legacy("insert into archive select id, total from orders order by id")
+        .unwrap_err();
+    assert!(err.contains("PRIMARY KEY"), "{err}");
+    assert_eq!(
+        db.execute_legacy("select count(*) from archive").unwrap(),
+        "count(*)\n1"
+    );
+
+    db.execute_legacy("begin").unwrap();
+    db.execute_legacy("insert into archive values (9, 9)")
+        .unwrap();
+    db.execute_legacy("insert into archive select id, total from orders order by id")
+        .unwrap_err();
+    assert_eq!(
+        db.execute_legacy("insert into archive select id, total from orders where id < 3")
+            .unwrap(),
+        "inserted 2 row(s) into archive"
+    );
+    db.execute_legacy("commit").unwrap();
+    assert_eq!(
+        db.execute_legacy("select id from archive order by id")
+            .unwrap(),
+        "id\n1\n2\n3\n9"
+    );
+}
+
+#[test]
+fn test_insert_select_reads_the_table_before_writing_it() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (n int)").unwrap();
+    db.execute_legacy("insert into t values (1)").unwrap();
+    db.execute_legacy("insert into t values (2)").unwrap();
+
+    db.execute_legacy("insert into t select n from t").unwrap();
+    assert_eq!(
+        db.execute_legacy("select count(*) from t").unwrap(),
+        "count(*)\n4"
+    );
+}
@@ -1,4 +1,5 @@
 use super::*;
+use skepa_db_core::config::DbConfig;
 
 #[test]
 fn test_select_inner_join_basic() {
@@ -184,6 +185,48 @@ fn test_select_inner_join_type_mismatch_errors() {
     assert!(err.contains("same datatype"));
 }
 
+#[test]
+fn test_select_join_int_to_bigint_matches_equal_values() {
+    let mut db = test_db();
+    db.execute("create table a (id int, name text)").unwrap();
+    db.execute("create table b (a_id bigint, city text)")
+        .unwrap();
+    db.execute(r#"insert into a values (1, "ram")"#).unwrap();
+    db.execute(r#"insert into a values (-7, "avi")"#).unwrap();
+    db.execute(r#"insert into a values (3, "sam")"#).unwrap();
+    db.execute(r#"insert into b values (1, "ny")"#).unwrap();
+    db.execute(r#"insert into b values (-7, "la")"#).unwrap();
+    db.execute(r#"insert into b values (99999999999, "sf")"#)
+        .unwrap();
+
+    let out = db
+        .execute("select a.name, b.city from b join a on b.a_id = a.id order by a.name asc")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["a.name", "b.city"],
+        vec![
+            vec![
+                Value::Text("avi".to_string()),
+                Value::Text("la".to_string()),
+            ],
+            vec![
+                Value::Text("ram".to_string()),
+                Value::Text("ny".to_string()),
+            ],
+        ],
+    );
+
+    let out = db
+        .execute("select a.id, b.a_id from a left join b on a.id = b.a_id where a.id = 3")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["a.id", "b.a_id"],
+        vec![vec![Value::Int(3), Value::Null]],
+    );
+}
+
 #[test]
 fn test_select_inner_join_null_join_key_does_not_match() {
     let mut db = test_db();
@@ -309,6 +352,207 @@ fn test_select_left_join_where_on_right_column_filters_null_rows_out() {
     assert_select_result(out, &["users.id"], vec![vec![Value::Int(1)]]);
 }
 
+#[test]
+fn test_select_left_join_pushes_left_only_where_before_join() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table orders (user_id int, amount int)")
+        .unwrap();
+    for (id, name) in [(1, "ram"), (2, "avi"), (3, "sam"), (4, "ram")] {
+        db.execute(&format!(r#"insert into users values ({id}, "{name}")"#))
+            .unwrap();
+    }
+    for (user_id, amount) in [(1, 10), (1, 20), (2, 5), (2, 6), (2, 7), (3, 1)] {
+        db.execute(&format!("insert into orders values ({user_id}, {amount})"))
+            .unwrap();
+    }
+
+    let join =
+        "select users.id, orders.amount from users left join orders on users.id = orders.user_id";
+    let full = db
+        .execute(&format!("{join} order by users.id asc"))
+        .unwrap();
+    let QueryResult::Select { stats, .. } = full else {
+        panic!("expected select result");
+    };
+    // 4 left rows read plus 7 joined rows.
+    assert_eq!(stats.rows_scanned, Some(11));
+
+    let pushed = db
+        .execute(&format!(
+            r#"{join} where name = "ram" and (users.id = 1 or orders.amount is null) order by users.id asc, orders.amount asc"#
+        ))
+        .unwrap();
+    let QueryResult::Select { rows, stats, .. } = pushed else {
+        panic!("expected select result");
+    };
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Int(1), Value::Int(10)],
+            vec![Value::Int(1), Value::Int(20)],
+            vec![Value::Int(4), Value::Null],
+        ]
+    );
+    // All 4 users are read, but only the two "ram" users reach the join:
+    // 2 matches for id 1, 1 padded row for id 4.
+    assert_eq!(stats.rows_scanned, Some(7));
+
+    // A right-side term stays after the join and still drops padded rows.
+    let out = db
+        .execute(&format!(
+            r#"{join} where users.name = "ram" and orders.amount = 20"#
+        ))
+        .unwrap();
+    assert_select_result(
+        out,
+        &["users.id", "orders.amount"],
+        vec![vec![Value::Int(1), Value::Int(20)]],
+    );
+}
+
+#[test]
+fn test_join_seeds_left_rows_from_index_equality() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key, email text unique, team int, name text)")
+        .unwrap();
+    db.execute("create table orders (user_id int, amount int)")
+        .unwrap();
+    db.execute("create index on users (team)").unwrap();
+    for id in 1..=20 {
+        db.execute(&format!(
+            r#"insert into users values ({id}, "u{id}@x.com", {}, "u{id}")"#,
+            id % 4
+        ))
+        .unwrap();
+        db.execute(&format!("insert into orders values ({id}, {})", id * 10))
+            .unwrap();
+    }
+    db.execute("insert into orders values (5, 7)").unwrap();
+
+    let join = "select users.id, orders.amount from users join orders on users.id = orders.user_id";
+    // Only `=` is index-eligible, so the second form of each pair scans.
+    for (indexed, scanned) in [
+        ("users.id = 5", "users.id >= 5 and users.id <= 5"),
+        (r#"email = "u5@x.com""#, r#"email like "u5@x.com""#),
+        (
+            "team = 1 and orders.amount > 50",
+            "team >= 1 and team <= 1 and orders.amount > 50",
+        ),
+    ] {
+        let QueryResult::Select {
+            rows: seeded_rows,
+            stats: seeded,
+            ..
+        } = db.execute(&format!("{join} where {indexed}")).unwrap()
+        else {
+            panic!("expected select result");
+        };
+        let QueryResult::Select {
+            rows: full_rows,
+            stats: full,
+            ..
+        } = db.execute(&format!("{join} where {scanned}")).unwrap()
+        else {
+            panic!("expected select result");
+        };
+        assert_eq!(seeded_rows, full_rows, "{indexed}");
+        assert_eq!(seeded.index_used, Some(true), "{indexed}");
+        assert_eq!(full.index_used, Some(false), "{scanned}");
+        assert!(seeded.rows_scanned < full.rows_scanned, "{indexed}");
+    }
+
+    let QueryResult::Select { rows, stats, .. } =
+        db.execute(&format!("{join} where users.id = 5")).unwrap()
+    else {
+        panic!("expected select result");
+    };
+    assert_eq!(
+        rows,
+        vec![
+            vec![Value::Int(5), Value::Int(50)],
+            vec![Value::Int(5), Value::Int(7)],
+        ]
+    );
+    // 1 left row from the primary key lookup plus its 2 joined rows.
+    assert_eq!(stats.rows_scanned, Some(3));
+}
+
+#[test]
+fn test_join_order_by_alias_beats_same_named_column_from_other_table() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table orders (user_id int, amount int)")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute(r#"insert into users values (2, "avi")"#)
+        .unwrap();
+    db.execute("insert into orders values (1, 9)").unwrap();
+    db.execute("insert into orders values (2, 5)").unwrap();
+
+    // `id` is both users.id and the alias for orders.amount; the alias wins.
+    let out = db
+        .execute("select users.name, orders.amount as id from users join orders on users.id = orders.user_id order by id desc")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["users.name", "id"],
+        vec![
+            vec![Value::Text("ram".to_string()), Value::Int(9)],
+            vec![Value::Text("avi".to_string()), Value::Int(5)],
+        ],
+    );
+    let out = db
+        .execute("select distinct users.id, orders.amount as id from users join orders on users.id = orders.user_id order by id desc")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["users.id", "id"],
+        vec![
+            vec![Value::Int(1), Value::Int(9)],
+            vec![Value::Int(2), Value::Int(5)],
+        ],
+    );
+
+    // WHERE never sees aliases, so `id` there is users.id.
+    let out = db
+        .execute("select orders.amount as id from users join orders on users.id = orders.user_id where id = 1")
+        .unwrap();
+    assert_select_result(out, &["id"], vec![vec![Value::Int(9)]]);
+}
+
+#[test]
+fn test_duplicate_output_names_are_ambiguous_not_first_wins() {
+    let mut db = test_db();
+    db.execute("create table a (id int, v int)").unwrap();
+    db.execute("create table b (id int, v int)").unwrap();
+    db.execute("insert into a values (1, 10)").unwrap();
+    db.execute("insert into b values (1, 20)").unwrap();
+
+    let err = db
+        .execute("select distinct a.v as x, b.v as x from a join b on a.id = b.id order by x")
+        .unwrap_err();
+    assert!(err.to_string().contains("Ambiguous column 'x' in ORDER BY"));
+}
+
+#[test]
+fn test_dotted_identifiers_rejected_at_create() {
+    let mut db = test_db();
+    let err = db.execute("create table t (a.b int)").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Column name 'a.b' cannot contain '.'")
+    );
+    let err = db.execute("create table s.t (id int)").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Table name 's.t' cannot contain '.'")
+    );
+}
+
 #[test]
 fn test_select_left_join_with_null_left_key_still_included() {
     let mut db = test_db();
@@ -357,3 +601,231 @@ fn test_select_left_join_order_limit() {
         ],
     );
 }
+
+fn seed_users_and_posts(db: &mut Database) {
+    db.execute("create table users (id int primary key, name text)")
+        .unwrap();
+    db.execute("create table posts (id int primary key, user_id int, title text)")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute(r#"insert into users values (2, "avi")"#)
+        .unwrap();
+    db.execute(r#"insert into users values (3, "sam")"#)
+        .unwrap();
+    db.execute(r#"insert into posts values (10, 1, "a")"#)
+        .unwrap();
+    db.execute(r#"insert into posts values (11, 1, "b")"#)
+        .unwrap();
+    db.execute(r#"insert into posts values (12, 2, "c")"#)
+        .unwrap();
+    db.execute("insert into posts values (13, 2, null)")
+        .unwrap();
+}
+
+#[test]
+fn test_join_group_by_counts_posts_per_user() {
+    let mut db = test_db();
+    seed_users_and_posts(&mut db);
+
+    let out = db
+        .execute("select users.id, count(posts.title) from users join posts on users.id = posts.user_id group by users.id order by users.id asc")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["users.id", "count(posts.title)"],
+        vec![
+            vec![Value::Int(1), Value::BigInt(2)],
+            vec![Value::Int(2), Value::BigInt(1)],
+        ],
+    );
+
+    // Unqualified names that exist in only one side resolve through the join.
+    let out = db
+        .execute("select name, count(*) as n, count(title) from users join posts on users.id = posts.user_id group by name order by n desc")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["users.name", "n", "count(title)"],
+        vec![
+            vec![
+                Value::Text("ram".to_string()),
+                Value::BigInt(2),
+                Value::BigInt(2),
+            ],
+            vec![
+                Value::Text("avi".to_string()),
+                Value::BigInt(2),
+                Value::BigInt(1),
+            ],
+        ],
+    );
+
+    let err = db
+        .execute(
+            "select id, count(*) from users join posts on users.id = posts.user_id group by id",
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Ambiguous column 'id'"), "{err}");
+}
+
+#[test]
+fn test_join_aggregate_argument_resolves_unique_and_qualified_columns() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table profiles (user_id int, city text, name text)")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute(r#"insert into users values (2, "avi")"#)
+        .unwrap();
+    db.execute(r#"insert into profiles values (1, "ktm", "home")"#)
+        .unwrap();
+    db.execute(r#"insert into profiles values (1, null, "work")"#)
+        .unwrap();
+    db.execute(r#"insert into profiles values (2, "pkr", "home")"#)
+        .unwrap();
+    db.execute(r#"insert into profiles values (2, "pkr", "away")"#)
+        .unwrap();
+
+    let join = "from users join profiles on users.id = profiles.user_id";
+    for arg in ["city", "profiles.city"] {
+        let sql = format!(
+            "select users.id, count({arg}), count(distinct {arg}) {join} group by users.id order by users.id asc"
+        );
+        let out = db.execute(&sql).unwrap();
+        let count = format!("count({arg})");
+        let distinct = format!("count(distinct {arg})");
+        assert_select_result(
+            out,
+            &["users.id", &count, &distinct],
+            vec![
+                vec![Value::Int(1), Value::BigInt(1), Value::BigInt(1)],
+                vec![Value::Int(2), Value::BigInt(2), Value::BigInt(1)],
+            ],
+        );
+        let plan = db.describe_statement(&sql).unwrap().to_string();
+        assert!(
+            plan.contains(&format!("SELECT {arg} -> columns #3")),
+            "{plan}"
+        );
+    }
+
+    // `name` is a column of both tables, so the bare form must be qualified.
+    for sql in [
+        format!("select users.id, count(name) {join} group by users.id"),
+        format!("select max(name) {join}"),
+    ] {
+        let err = db.execute(&sql).unwrap_err().to_string();
+        assert_eq!(
+            err, "Ambiguous column 'name' in SELECT aggregate. Use qualified name table.column",
+            "{sql}"
+        );
+    }
+    let out = db
+        .execute(&format!(
+            "select max(profiles.name), min(users.name) {join}"
+        ))
+        .unwrap();
+    assert_select_result(
+        out,
+        &["max(profiles.name)", "min(users.name)"],
+        vec![vec![
+            Value::Text("work".to_string()),
+            Value::Text("avi".to_string()),
+        ]],
+    );
+}
+
+#[test]
+fn test_left_join_group_by_counts_unmatched_as_zero() {
+    let mut db = test_db();
+    seed_users_and_posts(&mut db);
+
+    let out = db
+        .execute("select users.id, count(posts.id) as posts from users left join posts on users.id = posts.user_id group by users.id order by users.id asc")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["users.id", "posts"],
+        vec![
+            vec![Value::Int(1), Value::BigInt(2)],
+            vec![Value::Int(2), Value::BigInt(2)],
+            vec![Value::Int(3), Value::BigInt(0)],
+        ],
+    );
+}
+
+#[test]
+fn test_join_hash_index_copies_only_matched_right_rows() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute(
+        "create table wide (user_id int, a text, b text, c text, d text, e text, f text, g text)",
+    )
+    .unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute(r#"insert into users values (2, "avi")"#)
+        .unwrap();
+    let pad = "x".repeat(64);
+    for i in 0..200 {
+        let user_id = if i < 2 { 1 } else { 100 + i };
+        db.execute(&format!(
+            r#"insert into wide values ({user_id}, "{pad}", "{pad}", "{pad}", "{pad}", "{pad}", "{pad}", "{pad}")"#
+        ))
+        .unwrap();
+    }
+
+    let before = skepa_db_core::engine::join_right_row_copies();
+    let out = db
+        .execute("select users.name from users left join wide on users.id = wide.user_id")
+        .unwrap();
+    let copied = skepa_db_core::engine::join_right_row_copies() - before;
+    assert_eq!(copied, 2);
+    assert_select_result(
+        out,
+        &["users.name"],
+        vec![
+            vec![Value::Text("ram".to_string())],
+            vec![Value::Text("ram".to_string())],
+            vec![Value::Text("avi".to_string())],
+        ],
+    );
+}
+
+#[test]
+fn test_max_join_rows_rejects_oversized_join() {
+    let path = test_db().path().clone();
+    let mut db = Database::open(DbConfig::new(path).with_max_join_rows(2)).unwrap();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table posts (user_id int, title text)")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute(r#"insert into posts values (1, "p1")"#).unwrap();
+    db.execute(r#"insert into posts values (1, "p2")"#).unwrap();
+
+    let out = db
+        .execute("select posts.title from users join posts on users.id = posts.user_id")
+        .unwrap();
+    assert_select_result(
+        out,
+        &["posts.title"],
+        vec![
+            vec![Value::Text("p1".to_string())],
+            vec![Value::Text("p2".to_string())],
+        ],
+    );
+
+    db.execute(r#"insert into posts values (1, "p3")"#).unwrap();
+    let err = db
+        .execute("select posts.title from users join posts on users.id = posts.user_id")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("JOIN produced more than 2 rows"));
+}
@@ -1,4 +1,5 @@
 use super::*;
+use skepa_db_core::config::DbConfig;
 
 #[test]
 fn test_pk_eq_select_path_returns_single_row() {
@@ -209,3 +210,309 @@ fn test_alter_drop_one_fk_keeps_other_fk_enforced() {
         .unwrap_err();
     assert!(err.to_lowercase().contains("foreign key"));
 }
+
+#[test]
+fn test_table_stats_reports_rows_bytes_and_indexes() {
+    let mut db = test_db();
+    db.execute_legacy("create table users (id int primary key, email text unique, age int)")
+        .unwrap();
+    db.execute_legacy("create index on users (age)").unwrap();
+
+    let empty = db.table_stats("users").unwrap();
+    assert_eq!(empty.rows, 0);
+    assert_eq!(empty.index_count, 3);
+
+    db.execute_legacy(r#"insert into users values (1, "a@x", 20)"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into users values (2, "b@x", 30)"#)
+        .unwrap();
+    let stats = db.table_stats("users").unwrap();
+    assert_eq!(stats.rows, 2);
+    assert_eq!(stats.index_count, 3);
+    assert!(stats.bytes > empty.bytes);
+
+    let rows_len = std::fs::metadata(db.path().join("tables").join("users.rows"))
+        .unwrap()
+        .len();
+    let idx_len = std::fs::metadata(db.path().join("indexes").join("users.indexes.json"))
+        .unwrap()
+        .len();
+    assert_eq!(stats.bytes, rows_len + idx_len);
+}
+
+#[test]
+fn test_show_tables_with_stats_adds_table_stats_columns() {
+    let mut db = test_db();
+    db.execute_legacy("create table users (id int primary key, age int)")
+        .unwrap();
+    db.execute_legacy("create index on users (age)").unwrap();
+    db.execute_legacy("insert into users values (1, 20)")
+        .unwrap();
+    db.execute_legacy("insert into users values (2, 30)")
+        .unwrap();
+    db.execute("create view adults as select id from users where age >= 21")
+        .unwrap();
+    db.execute("create materialized view ids as select id from users")
+        .unwrap();
+
+    let stats = |db: &Database, table: &str| {
+        let s = db.table_stats(table).unwrap();
+        vec![
+            Value::BigInt(s.rows as i128),
+            Value::BigInt(s.bytes.into()),
+            Value::BigInt(s.index_count as i128),
+        ]
+    };
+    let text = |s: &str| Value::Text(s.to_string());
+    let row = |name: &str, kind: &str, counts: Vec<Value>| {
+        let mut row = vec![text(name), text(kind)];
+        row.extend(counts);
+        row
+    };
+    assert_eq!(stats(&db, "users")[0], Value::BigInt(2));
+    assert_eq!(stats(&db, "users")[2], Value::BigInt(2));
+    assert_select_result(
+        db.execute("show tables with stats").unwrap(),
+        &["name", "type", "rows", "bytes", "indexes"],
+        vec![
+            row("adults", "view", vec![Value::Null; 3]),
+            row("ids", "materialized view", stats(&db, "ids")),
+            row("users", "table", stats(&db, "users")),
+        ],
+    );
+    assert_select_result(
+        db.execute("show tables").unwrap(),
+        &["name", "type"],
+        vec![
+            row("adults", "view", Vec::new()),
+            row("ids", "materialized view", Vec::new()),
+            row("users", "table", Vec::new()),
+        ],
+    );
+}
+
+#[test]
+fn test_table_stats_unknown_table_errors() {
+    let db = test_db();
+    let err = db.table_stats("missing").unwrap_err();
+    assert!(err.to_string().contains("does not exist"));
+}
+
+fn seeded_db(seed: u64) -> Database {
+    let path = test_db().path().clone();
+    Database::open(DbConfig::new(path).with_uuid_seed(seed)).unwrap()
+}
+
+fn select_uuid_column(db: &mut Database, sql: &str) -> Vec<String> {
+    match db.execute(sql).unwrap() {
+        QueryResult::Select { rows, .. } => rows
+            .into_iter()
+            .map(|r| match &r[0] {
+                Value::Uuid(u) => u.to_string(),
+                other => panic!("expected uuid, got {other:?}"),
+            })
+            .collect(),
+        other => panic!("expected select, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_gen_uuid_in_values_and_default_is_seeded_and_v4() {
+    let mut first = Vec::new();
+    for _ in 0..2 {
+        let mut db = seeded_db(42);
+        db.execute("create table s (id uuid, n int default 0)")
+            .unwrap();
+        db.execute("insert into s values (gen_uuid())").unwrap();
+        db.execute("insert into s values (GEN_UUID(), 1)").unwrap();
+        let ids = select_uuid_column(&mut db, "select id from s");
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        for id in &ids {
+            assert_eq!(&id[14..15], "4");
+        }
+        if first.is_empty() {
+            first = ids;
+        } else {
+            assert_eq!(first, ids);
+        }
+    }
+}
+
+#[test]
+fn test_gen_uuid_column_default_fills_omitted_value() {
+    let mut db = seeded_db(7);
+    db.execute("create table t (n int, id uuid not null default gen_uuid())")
+        .unwrap();
+    db.execute("insert into t values (1)").unwrap();
+    db.execute("insert into t values (2)").unwrap();
+    let out = db
+        .execute_legacy("select count(distinct id) from t")
+        .unwrap();
+    assert_eq!(out, "count(distinct id)\n2");
+}
+
+#[test]
+fn test_max_json_bytes_rejects_large_documents() {
+    let path = test_db().path().clone();
+    let mut db = Database::open(DbConfig::new(path).with_max_json_bytes(16)).unwrap();
+    db.execute("create table t (id int, doc json, note text)")
+        .unwrap();
+    db.execute(r#"insert into t values (1, "{\"a\":[1,2,3]}", "long text is not json")"#)
+        .unwrap();
+
+    let err = db
+        .execute(r#"insert into t values (2, "{\"a\":[1,2,3,4,5,6,7]}", "x")"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("JSON value for column 'doc' is 21 bytes, exceeding the 16-byte limit"));
+    let err = db
+        .execute(r#"update t set doc = "[\"aaaaaaaaaaaaaaaa\"]" where id = 1"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("exceeding the 16-byte limit"));
+    db.execute(r#"update t set doc = "[]" where id = 1"#)
+        .unwrap();
+}
+
+#[test]
+fn test_completion_lookups_list_tables_columns_and_keywords() {
+    let mut db = test_db();
+    assert!(db.table_names().is_empty());
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table accounts (id int, owner int)")
+        .unwrap();
+
+    assert_eq!(db.table_names(), vec!["accounts", "users"]);
+    assert_eq!(db.column_names("users").unwrap(), vec!["id", "name"]);
+    let err = db.column_names("nope").unwrap_err().to_string();
+    assert!(err.contains("Table 'nope' does not exist"), "{err}");
+
+    db.execute("alter table users add unique(name)").unwrap();
+    assert_eq!(db.column_names("users").unwrap(), vec!["id", "name"]);
+    assert!(db.keyword_list().contains(&"select"));
+    assert!(db.keyword_list().contains(&"returning"));
+}
+
+#[test]
+fn test_all_identifiers_lists_tables_then_qualified_columns() {
+    let mut db = test_db();
+    assert!(db.all_identifiers().is_empty());
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table accounts (id int)").unwrap();
+
+    assert_eq!(
+        db.all_identifiers(),
+        vec!["accounts", "accounts.id", "users", "users.id", "users.name"]
+    );
+}
+
+#[test]
+fn test_parsed_command_round_trips_through_json_and_executes_the_same() {
+    use skepa_db_core::engine::execute_command;
+    use skepa_db_core::parser::command::Command;
+    use skepa_db_core::parser::parser::parse;
+    use skepa_db_core::storage::{Catalog, DiskStorage};
+    use skepa_db_core::types::uuid_gen::UuidGenerator;
+
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_ast_round_trip_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let mut catalog = Catalog::new();
+    let mut storage = DiskStorage::new(path.clone()).unwrap();
+    let mut uuid_gen = UuidGenerator::from_seed(Some(1));
+    let mut run = |cmd: Command| {
+        execute_command(cmd, &mut catalog, &mut storage, &mut uuid_gen, None, false).unwrap()
+    };
+    for sql in [
+        "create table users (id int primary key, name text, age int)",
+        "create table posts (id int, user_id int, title text)",
+        r#"insert into users values (1, "a", 30)"#,
+        r#"insert into users values (2, "b", 20)"#,
+        r#"insert into users values (3, "c", 10)"#,
+        r#"insert into posts values (1, 1, "x")"#,
+        r#"insert into posts values (2, 1, "y")"#,
+        r#"insert into posts values (3, 2, "z")"#,
+        r#"insert into posts values (4, 3, "w")"#,
+    ] {
+        let json = serde_json::to_string(&parse(sql).unwrap()).unwrap();
+        run(serde_json::from_str(&json).unwrap());
+    }
+
+    let sql = "select name, count(*) from users join posts on users.id = posts.user_id \
+               where users.age >= 20 and posts.title != \"q\" group by name \
+               having count(*) >= 1 order by name asc limit 5 offset 0";
+    let parsed = parse(sql).unwrap();
+    let json = serde_json::to_string(&parsed).unwrap();
+    let restored: Command = serde_json::from_str(&json).unwrap();
+    assert_eq!(format!("{restored:?}"), format!("{parsed:?}"));
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    assert!(json.starts_with(r#"{"Select":{"table":"users""#), "{json}");
+
+    let expected = run(parsed);
+    let expected_json = serde_json::to_value(&expected).unwrap();
+    assert_select_result(
+        expected,
+        &["users.name", "count(*)"],
+        vec![
+            vec![Value::Text("a".to_string()), Value::BigInt(2)],
+            vec![Value::Text("b".to_string()), Value::BigInt(1)],
+        ],
+    );
+    assert_eq!(serde_json::to_value(run(restored)).unwrap(), expected_json);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_execute_read_runs_selects_through_shared_borrows() {
+    use skepa_db_core::engine::{execute_command, execute_read, is_read_command};
+    use skepa_db_core::parser::parser::parse;
+    use skepa_db_core::storage::{Catalog, DiskStorage};
+    use skepa_db_core::types::uuid_gen::UuidGenerator;
+
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_execute_read_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let mut catalog = Catalog::new();
+    let mut storage = DiskStorage::new(path.clone()).unwrap();
+    let mut uuid_gen = UuidGenerator::from_seed(Some(1));
+    for sql in [
+        "create table users (id int primary key, name text)",
+        r#"insert into users values (1, "a")"#,
+    ] {
+        execute_command(
+            parse(sql).unwrap(),
+            &mut catalog,
+            &mut storage,
+            &mut uuid_gen,
+            None,
+            false,
+        )
+        .unwrap();
+    }
+
+    let (catalog, storage) = (&catalog, &storage);
+    let out = execute_read(
+        parse("select name from users").unwrap(),
+        catalog,
+        storage,
+        None,
+    );
+    assert_select_result(
+        out.unwrap(),
+        &["name"],
+        vec![vec![Value::Text("a".to_string())]],
+    );
+    assert!(execute_read(parse("describe users").unwrap(), catalog, storage, None).is_ok());
+
+    let delete = parse("delete from users where id = 1").unwrap();
+    assert!(!is_read_command(&delete));
+    let err = execute_read(delete, catalog, storage, None).unwrap_err();
+    assert_eq!(
+        err,
+        "Only SELECT, DESCRIBE and SHOW INDEXES are read-only statements"
+    );
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -50,6 +50,28 @@ fn assert_select_result(
     }
 }
 
+/// A RETURNING result: select-shaped rows that still report the count.
+fn assert_returning_result(
+    result: QueryResult,
+    expected_columns: &[&str],
+    expected_rows: Vec<Vec<Value>>,
+    expected_rows_affected: usize,
+) {
+    assert_eq!(result.rows_affected(), Some(expected_rows_affected));
+    match result {
+        QueryResult::Select { schema, rows, .. } => {
+            let actual_columns = schema
+                .columns
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(actual_columns, expected_columns);
+            assert_eq!(rows, expected_rows);
+        }
+        other => panic!("expected returning rows, got {other:?}"),
+    }
+}
+
 fn assert_mutation_result(
     result: QueryResult,
     expected_message: &str,
@@ -95,12 +117,25 @@ fn assert_transaction_result(result: QueryResult, expected_message: &str) {
 mod aggregates;
 mod api_json;
 mod basic;
+mod builder;
+mod bulk;
+mod change_events;
 mod constraints;
+mod create_table_as;
 mod dml;
 mod foreign_keys;
 mod indexes;
+mod insert_select;
 mod joins;
 mod misc;
 mod persistence;
+mod plan;
+mod restricted;
 mod select;
+mod set_ops;
+mod settings;
+mod stream;
+mod subqueries;
 mod transactions;
+mod triggers;
+mod views;
@@ -33,6 +33,42 @@ fn test_constraint_persistence_after_reopen() {
     let _ = std::fs::remove_dir_all(&path);
 }
 
+#[test]
+fn test_unique_nulls_not_distinct_persists_after_reopen() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!(
+        "skepa_db_nulls_not_distinct_{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy(
+            "create table t (id int, email text unique nulls not distinct, a int, b int)",
+        )
+        .unwrap();
+        db.execute_legacy("alter table t add unique(a, b) nulls not distinct")
+            .unwrap();
+        db.execute_legacy("insert into t values (1, null, 1, null)")
+            .unwrap();
+    }
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let err = db
+            .execute_legacy("insert into t values (2, null, 2, 2)")
+            .unwrap_err();
+        assert_eq!(err, "UNIQUE constraint violation on column(s) email");
+        let err = db
+            .execute_legacy(r#"insert into t values (2, "x", 1, null)"#)
+            .unwrap_err();
+        assert_eq!(err, "UNIQUE constraint violation on column(s) a,b");
+        db.execute_legacy(r#"insert into t values (2, "x", 2, null)"#)
+            .unwrap();
+    }
+    let _ = std::fs::remove_dir_all(&path);
+}
+
 #[test]
 fn test_persistence_reopen_insert() {
     let mut path: PathBuf = std::env::temp_dir();
@@ -161,6 +197,8 @@ fn test_describe_reflects_schema_changes_after_reopen() {
             "not_null",
             "default",
             "indexes",
+            "comment",
+            "table_comment",
         ],
         vec![
             vec![
@@ -171,6 +209,8 @@ fn test_describe_reflects_schema_changes_after_reopen() {
                 Value::Bool(true),
                 Value::Null,
                 Value::Text("".to_string()),
+                Value::Null,
+                Value::Null,
             ],
             vec![
                 Value::Text("name".to_string()),
@@ -180,12 +220,36 @@ fn test_describe_reflects_schema_changes_after_reopen() {
                 Value::Bool(true),
                 Value::Text("anon".to_string()),
                 Value::Text("".to_string()),
+                Value::Null,
+                Value::Null,
             ],
         ],
     );
     let _ = std::fs::remove_dir_all(&path);
 }
 
+#[test]
+fn test_comments_persist_across_reopen() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_comment_reopen_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table users (id int, email text)")
+            .unwrap();
+        db.execute(r#"comment on table users is "app users""#)
+            .unwrap();
+        db.execute(r#"comment on column users.email is "login email""#)
+            .unwrap();
+    }
+
+    let mut reopened = Database::open_legacy(path.clone());
+    let out = reopened.execute_legacy("describe users").unwrap();
+    assert!(out.contains("email\ttext\tfalse\tfalse\tfalse\tnull\t\tlogin email\tapp users"));
+    let _ = std::fs::remove_dir_all(&path);
+}
+
 #[test]
 fn test_select_stats_after_reopen() {
     let mut path: PathBuf = std::env::temp_dir();
@@ -224,3 +288,32 @@ fn test_select_stats_after_reopen() {
 
     let _ = std::fs::remove_dir_all(&path);
 }
+
+#[test]
+fn test_json_shape_and_nested_documents_persist_across_reopen() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_json_reopen_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table t (id int, doc json check_json_object)")
+            .unwrap();
+        db.execute(
+            r#"insert into t values (1, "{\"z\":{\"k\":[1,{\"b\":2,\"a\":1}]},\"m\":\"s\"}")"#,
+        )
+        .unwrap();
+    }
+
+    let mut reopened = Database::open_legacy(path.clone());
+    assert_eq!(
+        reopened.execute_legacy("select doc from t").unwrap(),
+        r#"doc
+{"m":"s","z":{"k":[1,{"a":1,"b":2}]}}"#
+    );
+    let err = reopened
+        .execute_legacy(r#"insert into t values (2, "[]")"#)
+        .unwrap_err();
+    assert_eq!(err, "Column 'doc' requires a JSON object");
+    let _ = std::fs::remove_dir_all(&path);
+}
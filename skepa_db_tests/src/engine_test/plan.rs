@@ -0,0 +1,241 @@
+use super::*;
+use skepa_db_core::statement_plan::{AccessPath, BindingTarget};
+
+#[test]
+fn test_plan_select_pk_lookup_with_alias() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key, name text, age int)")
+        .unwrap();
+    let plan = db
+        .describe_statement("select name as n, age from users where id = 1 order by n")
+        .unwrap();
+    assert_eq!(plan.statement, "select");
+    assert_eq!(plan.tables, vec!["users"]);
+    assert_eq!(
+        plan.access,
+        Some(AccessPath::PrimaryKey {
+            column: "id".to_string()
+        })
+    );
+    assert_eq!(plan.columns.len(), 3);
+    assert_eq!(plan.columns[2].dtype, "int");
+    assert_eq!(plan.aliases[0].alias, "n");
+    assert_eq!(plan.aliases[0].expr, "name");
+    let order = plan
+        .bindings
+        .iter()
+        .find(|b| b.clause == "ORDER BY")
+        .unwrap();
+    assert_eq!((order.index, order.target), (1, BindingTarget::Input));
+    assert_eq!(plan.output[0].name, "n");
+
+    let text = plan.to_string();
+    assert!(text.contains("access: primary key lookup (id)"));
+    assert!(text.contains("  n = name"));
+    assert!(text.contains("  #0 n text"));
+}
+
+#[test]
+fn test_plan_select_index_paths() {
+    let mut db = test_db();
+    db.execute("create table users (id int, email text unique, age int)")
+        .unwrap();
+    db.execute("create index on users (age)").unwrap();
+    let unique = db
+        .describe_statement(r#"select * from users where email = "a""#)
+        .unwrap();
+    assert_eq!(
+        unique.access,
+        Some(AccessPath::UniqueIndex {
+            column: "email".to_string()
+        })
+    );
+    let secondary = db
+        .describe_statement("delete from users where age = 3")
+        .unwrap();
+    assert_eq!(
+        secondary.access,
+        Some(AccessPath::SecondaryIndex {
+            column: "age".to_string()
+        })
+    );
+    let scan = db
+        .describe_statement("update users set age = 1 where age > 3")
+        .unwrap();
+    assert_eq!(scan.access, Some(AccessPath::FullScan));
+}
+
+#[test]
+fn test_plan_reports_partition_pruning() {
+    let mut db = test_db();
+    db.execute("create table events (id int primary key, day int, kind text) partition by (day)")
+        .unwrap();
+    let pruning = AccessPath::PartitionPruning {
+        column: "day".to_string(),
+    };
+    assert_eq!(pruning.to_string(), "partition pruning (day)");
+    let pruning = Some(pruning);
+    let plan = db
+        .describe_statement(r#"select * from events where day >= 3 and kind = "x""#)
+        .unwrap();
+    assert_eq!(plan.access, pruning);
+    let plan = db
+        .describe_statement("delete from events where day = 3")
+        .unwrap();
+    assert_eq!(plan.access, pruning);
+    let plan = db
+        .describe_statement("update events set kind = \"y\" where id = 1")
+        .unwrap();
+    assert_eq!(
+        plan.access,
+        Some(AccessPath::PrimaryKey {
+            column: "id".to_string()
+        })
+    );
+    let plan = db
+        .describe_statement(r#"select * from events where day = 3 or kind = "x""#)
+        .unwrap();
+    assert_eq!(plan.access, Some(AccessPath::FullScan));
+}
+
+#[test]
+fn test_plan_join_resolves_qualified_columns() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table orders (id int, user_id int)")
+        .unwrap();
+    let plan = db
+        .describe_statement(
+            "select name, orders.id from users join orders on users.id = orders.user_id",
+        )
+        .unwrap();
+    assert_eq!(plan.tables, vec!["users", "orders"]);
+    assert_eq!(
+        plan.access,
+        Some(AccessPath::HashJoin {
+            left: "users.id".to_string(),
+            right: "orders.user_id".to_string()
+        })
+    );
+    let names: Vec<&str> = plan.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["users.id", "users.name", "orders.id", "orders.user_id"]
+    );
+    let select: Vec<(String, usize)> = plan
+        .bindings
+        .iter()
+        .filter(|b| b.clause == "SELECT")
+        .map(|b| (b.name.clone(), b.index))
+        .collect();
+    assert_eq!(
+        select,
+        vec![("name".to_string(), 1), ("orders.id".to_string(), 2)]
+    );
+}
+
+#[test]
+fn test_plan_order_by_prefers_alias_over_joined_column() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table orders (user_id int, amount int)")
+        .unwrap();
+    let plan = db
+        .describe_statement(
+            "select orders.amount as id from users join orders on users.id = orders.user_id order by id",
+        )
+        .unwrap();
+    let order = plan
+        .bindings
+        .iter()
+        .find(|b| b.clause == "ORDER BY")
+        .unwrap();
+    assert_eq!((order.index, order.target), (3, BindingTarget::Input));
+}
+
+#[test]
+fn test_plan_reports_same_errors_as_execution() {
+    let mut db = test_db();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table orders (id int, user_id int)")
+        .unwrap();
+    let sql = "select id from users join orders on users.id = orders.user_id";
+    let plan_err = db.describe_statement(sql).unwrap_err().to_string();
+    let exec_err = db.execute(sql).unwrap_err().to_string();
+    assert_eq!(plan_err, exec_err);
+    assert!(plan_err.contains("Ambiguous column 'id'"));
+
+    let unknown = db
+        .describe_statement("select nope from users")
+        .unwrap_err()
+        .to_string();
+    assert!(unknown.contains("Unknown column 'nope' in SELECT list"));
+}
+
+#[test]
+fn test_plan_grouped_select_binds_having_to_output() {
+    let mut db = test_db();
+    db.execute("create table users (id int, city text)")
+        .unwrap();
+    let plan = db
+        .describe_statement(
+            "select city, count(*) as c from users group by city having c > 1 order by c desc",
+        )
+        .unwrap();
+    let having = plan.bindings.iter().find(|b| b.clause == "HAVING").unwrap();
+    assert_eq!((having.index, having.target), (1, BindingTarget::Output));
+    assert_eq!(plan.output[1].name, "c");
+    assert_eq!(plan.output[1].dtype, "bigint");
+}
+
+#[test]
+fn test_plan_insert_maps_values_and_defaults_without_writing() {
+    let mut db = test_db();
+    db.execute("create table t (id int, name text default \"x\")")
+        .unwrap();
+    let plan = db.describe_statement("insert into t values (1)").unwrap();
+    let clauses: Vec<(&str, &str, usize)> = plan
+        .bindings
+        .iter()
+        .map(|b| (b.clause.as_str(), b.name.as_str(), b.index))
+        .collect();
+    assert_eq!(clauses, vec![("VALUES", "1", 0), ("DEFAULT", "x", 1)]);
+    assert_eq!(db.execute_legacy("select * from t").unwrap(), "id\tname");
+}
+
+#[test]
+fn test_plan_insert_select_binds_select_columns() {
+    let mut db = test_db();
+    db.execute("create table src (a bigint, b text)").unwrap();
+    db.execute("create table t (id bigint, name text default \"x\", note text)")
+        .unwrap();
+    let plan = db
+        .describe_statement("insert into t (id) select a from src")
+        .unwrap();
+    assert_eq!(plan.statement, "insert");
+    assert_eq!(plan.tables, vec!["t", "src"]);
+    let clauses: Vec<(&str, &str, usize)> = plan
+        .bindings
+        .iter()
+        .map(|b| (b.clause.as_str(), b.name.as_str(), b.index))
+        .collect();
+    assert_eq!(
+        clauses,
+        vec![
+            ("SELECT", "a", 0),
+            ("DEFAULT", "x", 1),
+            ("DEFAULT", "null", 2)
+        ]
+    );
+    let err = db
+        .describe_statement("insert into t (id) select b from src")
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("cannot be inserted into column 'id'"),
+        "{err}"
+    );
+}
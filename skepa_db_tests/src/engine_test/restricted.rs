@@ -0,0 +1,156 @@
+use super::*;
+use skepa_db_core::error::DbErrorKind;
+use skepa_db_core::restricted::StatementKind;
+
+const STATEMENTS: [(&str, StatementKind); 17] = [
+    ("begin", StatementKind::Begin),
+    ("commit", StatementKind::Commit),
+    ("rollback", StatementKind::Rollback),
+    ("show transaction", StatementKind::ShowTransaction),
+    ("set max_rows = 5", StatementKind::Set),
+    ("show max_rows", StatementKind::Show),
+    ("create table t2 (id int)", StatementKind::CreateTable),
+    ("create index on t (name)", StatementKind::CreateIndex),
+    ("drop index on t (name)", StatementKind::DropIndex),
+    ("alter table t add unique(name)", StatementKind::Alter),
+    (r#"insert into t values (9, "z")"#, StatementKind::Insert),
+    (
+        r#"update t set name = "y" where id = 1"#,
+        StatementKind::Update,
+    ),
+    ("delete from t where id = 1", StatementKind::Delete),
+    ("describe t", StatementKind::Describe),
+    ("show indexes from t", StatementKind::ShowIndexes),
+    (r#"comment on table t is "x""#, StatementKind::Comment),
+    ("select * from t", StatementKind::Select),
+];
+
+#[test]
+fn test_restricted_rejects_every_kind_outside_the_allow_list() {
+    let mut db = test_db();
+    db.execute("create table t (id int primary key, name text)")
+        .unwrap();
+    db.execute(r#"insert into t values (1, "a")"#).unwrap();
+
+    for (sql, kind) in STATEMENTS {
+        let others: Vec<StatementKind> = STATEMENTS
+            .iter()
+            .map(|(_, k)| *k)
+            .filter(|k| *k != kind)
+            .collect();
+        let err = db.restricted(&others).execute(sql).unwrap_err();
+        assert_eq!(err.kind(), DbErrorKind::NotPermitted, "{sql}: {err}");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Statement '{}' is not permitted on this restricted handle",
+                kind.as_str()
+            )
+        );
+    }
+    // Nothing ran: the row and the schema are unchanged.
+    assert_select_result(
+        db.execute("select * from t").unwrap(),
+        &["id", "name"],
+        vec![vec![Value::Int(1), Value::Text("a".to_string())]],
+    );
+    assert!(db.execute("select * from t2").is_err());
+}
+
+#[test]
+fn test_restricted_runs_allowed_kinds() {
+    let mut db = test_db();
+    db.execute("create table t (id int primary key, name text)")
+        .unwrap();
+    let mut handle = db.restricted(&[StatementKind::Insert, StatementKind::Select]);
+    handle.execute(r#"insert into t values (1, "a")"#).unwrap();
+    assert_select_result(
+        handle.execute("select name from t where id = 1").unwrap(),
+        &["name"],
+        vec![vec![Value::Text("a".to_string())]],
+    );
+}
+
+#[test]
+fn test_restricted_rejects_before_touching_the_catalog() {
+    let mut db = test_db();
+    // The table does not exist, yet the error is the restriction, not an
+    // unknown table.
+    let err = db
+        .restricted(&[StatementKind::Select])
+        .execute("delete from missing where id = 1")
+        .unwrap_err();
+    assert_eq!(err.kind(), DbErrorKind::NotPermitted);
+    let err = db
+        .restricted(&[StatementKind::Select])
+        .with_allowed_tables(["t"])
+        .execute("select * from missing")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Table 'missing' is not permitted on this restricted handle"
+    );
+    // Parse errors are still reported as such.
+    let err = db
+        .restricted(&[StatementKind::Select])
+        .execute("selec * from t")
+        .unwrap_err();
+    assert_ne!(err.kind(), DbErrorKind::NotPermitted);
+}
+
+#[test]
+fn test_restricted_table_filter_requires_both_join_tables() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key, name text)")
+        .unwrap();
+    db.execute("create table orders (id int primary key, user_id int)")
+        .unwrap();
+    db.execute("create table secrets (id int, user_id int)")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "a")"#).unwrap();
+    db.execute("insert into orders values (10, 1)").unwrap();
+
+    let mut handle = db
+        .restricted(&[StatementKind::Select])
+        .with_allowed_tables(["users", "orders"]);
+    assert_select_result(
+        handle
+            .execute("select orders.id from users join orders on users.id = orders.user_id")
+            .unwrap(),
+        &["orders.id"],
+        vec![vec![Value::Int(10)]],
+    );
+    for sql in [
+        "select * from users join secrets on users.id = secrets.user_id",
+        "select * from secrets join users on secrets.user_id = users.id",
+        "select * from secrets",
+    ] {
+        let err = handle.execute(sql).unwrap_err();
+        assert_eq!(err.kind(), DbErrorKind::NotPermitted, "{sql}");
+        assert_eq!(
+            err.to_string(),
+            "Table 'secrets' is not permitted on this restricted handle"
+        );
+    }
+}
+
+#[test]
+fn test_restricted_named_drop_index_checks_its_table() {
+    let mut db = test_db();
+    db.execute("create table t (id int, name text)").unwrap();
+    db.execute("create table secrets (id int, v text)").unwrap();
+    db.execute("create index idx_v on secrets (v)").unwrap();
+    db.execute("create index idx_name on t (name)").unwrap();
+
+    let mut handle = db
+        .restricted(&[StatementKind::DropIndex])
+        .with_allowed_tables(["t"]);
+    let err = handle.execute("drop index idx_v").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Table 'secrets' is not permitted on this restricted handle"
+    );
+    handle.execute("drop index idx_name").unwrap();
+    assert_eq!(db.table_stats("secrets").unwrap().index_count, 1);
+    assert_eq!(db.table_stats("t").unwrap().index_count, 0);
+}
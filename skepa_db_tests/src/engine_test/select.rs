@@ -359,6 +359,64 @@ fn test_select_where_in_numeric_and_text() {
     assert_eq!(out_city, "id\n2\n3");
 }
 
+#[test]
+fn test_empty_in_list_matches_nothing() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+
+    let out = db
+        .execute_legacy("select id from users where id in ()")
+        .unwrap();
+    assert_eq!(out, "id");
+    let out = db
+        .execute_legacy("select id from users where id in () or age = 20")
+        .unwrap();
+    assert_eq!(out, "id\n2");
+
+    assert_mutation_result(
+        db.execute("update users set age = 0 where name in ()")
+            .unwrap(),
+        "updated 0 row(s) in users",
+        0,
+    );
+    assert_mutation_result(
+        db.execute("delete from users where id in ()").unwrap(),
+        "deleted 0 row(s) from users",
+        0,
+    );
+}
+
+#[test]
+fn test_large_in_list_filters_by_set_membership() {
+    let mut db = test_db();
+    db.execute("create table t (id int, tag text)").unwrap();
+    db.bulk_load(
+        "t",
+        (0..2000).map(|i| vec![Value::Int(i), Value::Text(format!("t{}", i % 7))]),
+    )
+    .unwrap();
+
+    let ids: Vec<String> = (0..1000).map(|i| (i * 2 + 1).to_string()).collect();
+    let started = std::time::Instant::now();
+    let out = db
+        .execute(&format!(
+            "select count(*) from t where id in ({})",
+            ids.join(",")
+        ))
+        .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_select_result(out, &["count(*)"], vec![vec![Value::BigInt(1000)]]);
+
+    let err = db
+        .execute("select id from t where id in (1, x)")
+        .unwrap_err();
+    assert!(err.to_string().contains("IN item 2: value 'x'"), "{err}");
+    let out = db
+        .execute_legacy("select id from t where id in (3, null, 5) order by id")
+        .unwrap();
+    assert_eq!(out, "id\n3\n5");
+}
+
 #[test]
 fn test_select_where_and_or() {
     let mut db = test_db();
@@ -533,6 +591,85 @@ fn test_select_order_by_multiple_columns() {
     assert_eq!(out, "id\tcity\n4\tla\n2\tla\n3\tny\n1\tny");
 }
 
+#[test]
+fn test_select_order_by_ties_break_by_ascending_row_id() {
+    let mut db = test_db();
+    db.execute("create table t (id int primary key, age int)")
+        .unwrap();
+    for (id, age) in [(5, 30), (1, 20), (4, 30), (2, 20), (3, 30)] {
+        db.execute(&format!("insert into t values ({id}, {age})"))
+            .unwrap();
+    }
+    // Updating a row keeps its row id; delete + insert gives one above every row.
+    db.execute("update t set age = 30 where id = 5").unwrap();
+    db.execute("delete from t where id = 4").unwrap();
+    db.execute("insert into t values (4, 30)").unwrap();
+
+    for _ in 0..3 {
+        assert_eq!(
+            db.execute_legacy("select id, age from t order by age")
+                .unwrap(),
+            "id\tage\n1\t20\n2\t20\n5\t30\n3\t30\n4\t30"
+        );
+        assert_eq!(
+            db.execute_legacy("select id from t order by age desc")
+                .unwrap(),
+            "id\n5\n3\n4\n1\n2"
+        );
+    }
+    let pages: Vec<String> = (0..5)
+        .map(|off| {
+            db.execute_legacy(&format!(
+                "select id from t order by age limit 1 offset {off}"
+            ))
+            .unwrap()
+        })
+        .collect();
+    assert_eq!(pages, ["id\n1", "id\n2", "id\n5", "id\n3", "id\n4"]);
+}
+
+#[test]
+fn test_select_order_by_ties_use_row_ids_not_storage_order() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_order_ties_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table t (id int primary key, kind text)")
+            .unwrap();
+        for id in 1..=4 {
+            db.execute(&format!(r#"insert into t values ({id}, "x")"#))
+                .unwrap();
+        }
+    }
+    // Rows keep their ids but are loaded in file order, here reversed.
+    let file = path.join("tables").join("t.rows");
+    let content = std::fs::read_to_string(&file).unwrap();
+    let mut lines: Vec<&str> = content.lines().collect();
+    lines.reverse();
+    std::fs::write(&file, lines.join("\n") + "\n").unwrap();
+
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(
+        db.execute_legacy("select id from t").unwrap(),
+        "id\n4\n3\n2\n1"
+    );
+    for query in [
+        "select id from t order by kind",
+        "select id from t order by kind desc",
+        r#"select id from t where kind = "x" order by kind limit 2 offset 1"#,
+    ] {
+        let expected = if query.contains("offset") {
+            "id\n2\n3"
+        } else {
+            "id\n1\n2\n3\n4"
+        };
+        assert_eq!(db.execute_legacy(query).unwrap(), expected, "{query}");
+    }
+    drop(db);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
 #[test]
 fn test_select_order_by_text_asc() {
     let mut db = test_db();
@@ -649,3 +786,289 @@ fn test_select_distinct_keeps_single_null() {
         .unwrap();
     assert_eq!(out, "city\nnull\nny");
 }
+
+#[test]
+fn test_select_distinct_order_by_must_use_select_list() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute(r#"insert into users values (4, "a", 5)"#)
+        .unwrap();
+
+    for sql in [
+        "select distinct name from users order by age",
+        "select distinct name as n from users order by id desc",
+    ] {
+        let err = db.execute_legacy(sql).unwrap_err();
+        let column = if sql.contains("age") { "age" } else { "id" };
+        assert_eq!(
+            err,
+            format!(
+                "ORDER BY column '{column}' must appear in the select list when using DISTINCT"
+            ),
+            "{sql}"
+        );
+        let plan_err = db.describe_statement(sql).unwrap_err();
+        assert_eq!(plan_err.to_string(), err, "{sql}");
+    }
+    let err = db
+        .execute_legacy("select distinct name from users order by missing")
+        .unwrap_err();
+    assert_eq!(err, "Unknown column 'missing' in ORDER BY");
+}
+
+#[test]
+fn test_select_distinct_order_by_alias_or_projected_column() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute(r#"insert into users values (4, "a", 5)"#)
+        .unwrap();
+
+    for order in ["n desc", "name desc"] {
+        let out = db
+            .execute_legacy(&format!(
+                "select distinct name as n from users order by {order} limit 2"
+            ))
+            .unwrap();
+        assert_eq!(
+            out,
+            "n
+c
+b",
+            "order by {order}"
+        );
+    }
+    assert!(
+        db.describe_statement("select distinct name as n from users order by name")
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_where_in_matches_items_with_separator_commas_and_quotes() {
+    let mut db = test_db();
+    db.execute("create table t (id int, s text)").unwrap();
+    db.execute("insert into t values (1, \"a,b\")").unwrap();
+    db.execute("insert into t values (2, \"say \\\"hi\\\"\")")
+        .unwrap();
+    db.execute("insert into t values (3, \"x\u{1F}y\")")
+        .unwrap();
+    db.execute("insert into t values (4, \"x\")").unwrap();
+
+    let out = db
+        .execute_legacy("select id from t where s in (\"x\u{1F}y\") order by id")
+        .unwrap();
+    assert_eq!(out, "id\n3");
+    let out = db
+        .execute_legacy("select id from t where s in (\"a,b\", \"say \\\"hi\\\"\") order by id")
+        .unwrap();
+    assert_eq!(out, "id\n1\n2");
+    let out = db
+        .execute_legacy("select id from t where s in (\"a\", \"b\") order by id")
+        .unwrap();
+    assert_eq!(out, "id");
+    let out = db
+        .execute_legacy("delete from t where s in (\"x\u{1F}y\", \"x\")")
+        .unwrap();
+    assert_eq!(out, "deleted 2 row(s) from t");
+}
+
+#[test]
+fn test_keyword_named_columns_round_trip() {
+    let mut db = test_db();
+    db.execute("create table t (id int, order int, limit int, group text)")
+        .unwrap();
+    db.execute("insert into t values (1, 30, 5, \"a\")")
+        .unwrap();
+    db.execute("insert into t values (2, 10, 7, \"b\")")
+        .unwrap();
+    db.execute("insert into t values (3, 20, 7, \"order\")")
+        .unwrap();
+
+    let out = db
+        .execute_legacy("select id, order from t where order > 10 order by order desc")
+        .unwrap();
+    assert_eq!(out, "id\torder\n1\t30\n3\t20");
+    let out = db
+        .execute_legacy("select id from t where limit = 7 and group = \"order\" limit 1")
+        .unwrap();
+    assert_eq!(out, "id\n3");
+    let out = db
+        .execute_legacy("select limit, count(*) from t group by limit having limit > 5")
+        .unwrap();
+    assert_eq!(out, "limit\tcount(*)\n7\t2");
+
+    db.execute("update t set order = 0 where order = 10")
+        .unwrap();
+    let out = db.execute_legacy("delete from t where order = 0").unwrap();
+    assert_eq!(out, "deleted 1 row(s) from t");
+}
+
+#[test]
+fn test_where_on_select_alias_suggests_full_expression() {
+    let mut db = test_db();
+    db.execute("create table t (id int primary key, created date)")
+        .unwrap();
+    db.execute(r#"insert into t values (1, "2024-03-01")"#)
+        .unwrap();
+
+    let err = db
+        .execute_legacy("select id as uid from t where uid = 1")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "cannot reference select alias 'uid' in WHERE; use the full expression or HAVING"
+    );
+    let err = db
+        .execute_legacy("select year(created) as y from t where id = 1 and y > 2000")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "cannot reference select alias 'y' in WHERE; use the full expression or HAVING"
+    );
+
+    // The underlying column name still works.
+    let out = db.execute("select id as uid from t where id = 1").unwrap();
+    assert_select_result(out, &["uid"], vec![vec![Value::Int(1)]]);
+}
+
+#[test]
+fn test_unknown_where_column_errors_match_across_select_update_delete() {
+    let mut db = test_db();
+    db.execute("create table t (id int primary key, email text unique, age int)")
+        .unwrap();
+    db.execute("create index on t (age)").unwrap();
+    db.execute(r#"insert into t values (1, "a@x", 20)"#)
+        .unwrap();
+
+    let cases = [
+        ("missing = 1", "Unknown column 'missing' in WHERE"),
+        ("ID = 1", "Unknown column 'ID' in WHERE"),
+        ("Email = \"a@x\"", "Unknown column 'Email' in WHERE"),
+        ("missing > 1", "Unknown column 'missing' in WHERE"),
+        (
+            "id = 1 and missing = 2",
+            "Unknown column 'missing' in WHERE",
+        ),
+        ("t.missing = 1", "Unknown column 't.missing' in WHERE"),
+    ];
+    for (filter, expected) in cases {
+        let select = db
+            .execute_legacy(&format!("select * from t where {filter}"))
+            .unwrap_err();
+        let update = db
+            .execute_legacy(&format!("update t set age = 1 where {filter}"))
+            .unwrap_err();
+        let delete = db
+            .execute_legacy(&format!("delete from t where {filter}"))
+            .unwrap_err();
+        assert_eq!(select, expected, "select where {filter}");
+        assert_eq!(update, expected, "update where {filter}");
+        assert_eq!(delete, expected, "delete where {filter}");
+    }
+
+    db.execute("create table u (id int, t_id int)").unwrap();
+    let err = db
+        .execute_legacy("select * from t join u on t.id = u.t_id where u.missing = 1")
+        .unwrap_err();
+    assert_eq!(err, "Unknown column 'u.missing' in WHERE");
+    let err = db
+        .execute_legacy("select * from t join u on t.id = u.t_id where missing = 1")
+        .unwrap_err();
+    assert_eq!(err, "Unknown column 'missing' in WHERE");
+}
+
+#[test]
+fn test_where_literal_type_errors_name_clause_and_column() {
+    let mut db = test_db();
+    db.execute("create table t (id int primary key, name text, age int)")
+        .unwrap();
+    db.execute("create index on t (age)").unwrap();
+
+    // Rejected even with no rows to compare against, and by the index paths.
+    let cases = [
+        (
+            r#"age = "abc""#,
+            "WHERE: value 'abc' is not valid for column 'age' (int)",
+        ),
+        (
+            r#"id = "abc""#,
+            "WHERE: value 'abc' is not valid for column 'id' (int)",
+        ),
+        (
+            r#"name = "a" or age > "old""#,
+            "WHERE: value 'old' is not valid for column 'age' (int)",
+        ),
+        (
+            r#"id = 1 and age in (1, 2, "x")"#,
+            "WHERE IN item 3: value 'x' is not valid for column 'age' (int)",
+        ),
+    ];
+    for (filter, expected) in cases {
+        let select = db
+            .execute_legacy(&format!("select * from t where {filter}"))
+            .unwrap_err();
+        let update = db
+            .execute_legacy(&format!("update t set age = 1 where {filter}"))
+            .unwrap_err();
+        let delete = db
+            .execute_legacy(&format!("delete from t where {filter}"))
+            .unwrap_err();
+        assert_eq!(select, expected, "select where {filter}");
+        assert_eq!(update, expected, "update where {filter}");
+        assert_eq!(delete, expected, "delete where {filter}");
+    }
+
+    db.execute(r#"insert into t values (1, "a", 20)"#).unwrap();
+    let err = db
+        .execute_legacy(r#"select age, count(*) from t group by age having count(*) > "many""#)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "HAVING: value 'many' is not valid for column 'count(*)' (bigint)"
+    );
+
+    db.execute("create table u (id int, label text)").unwrap();
+    let err = db
+        .execute_legacy("select * from t join u on t.id = u.label")
+        .unwrap_err();
+    assert!(
+        err.starts_with("ON: column 't.id' (int) cannot be compared with column 'u.label' (text)"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_select_limit_stops_scan_early() {
+    let mut db = test_db();
+    db.execute("create table events (id int primary key, kind text)")
+        .unwrap();
+    db.execute("create index on events (kind)").unwrap();
+    let rows = (1..=10_000).map(|i| {
+        let kind = if i % 3 == 0 { "c" } else { "ab" };
+        vec![Value::Int(i), Value::Text(kind.to_string())]
+    });
+    db.bulk_load("events", rows).unwrap();
+
+    let select = |db: &mut Database, sql: &str| {
+        let QueryResult::Select { rows, stats, .. } = db.execute(sql).unwrap() else {
+            panic!("expected select result for {sql}");
+        };
+        (rows, stats)
+    };
+    let cases = [
+        ("select * from events", 10, 15),
+        ("select id from events where id > 100", 10, 115),
+        (r#"select * from events where kind = "c""#, 10, 15),
+    ];
+    for (base, expected_len, expected_scanned) in cases {
+        let (all, _) = select(&mut db, base);
+        let (limited, stats) = select(&mut db, &format!("{base} limit 10 offset 5"));
+        assert_eq!(limited.len(), expected_len, "{base}");
+        assert_eq!(limited, all[5..15].to_vec(), "{base}");
+        assert_eq!(stats.rows_scanned, Some(expected_scanned), "{base}");
+    }
+
+    // Ordering still needs every row before the limit applies.
+    let (_, stats) = select(&mut db, "select * from events order by id desc limit 10");
+    assert_eq!(stats.rows_scanned, Some(10_000));
+}
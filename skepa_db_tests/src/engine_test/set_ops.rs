@@ -0,0 +1,156 @@
+use super::*;
+
+fn seed_left_right(db: &mut Database) {
+    db.execute("create table l (id int, tag text)").unwrap();
+    db.execute("create table r (id bigint, tag varchar(10))")
+        .unwrap();
+    for (id, tag) in [(1, "a"), (2, "b"), (2, "b"), (2, "b"), (3, "c")] {
+        db.execute(&format!(r#"insert into l values ({id}, "{tag}")"#))
+            .unwrap();
+    }
+    db.execute("insert into l values (4, null)").unwrap();
+    for (id, tag) in [(2, "b"), (3, "x")] {
+        db.execute(&format!(r#"insert into r values ({id}, "{tag}")"#))
+            .unwrap();
+    }
+    db.execute("insert into r values (4, null)").unwrap();
+}
+
+#[test]
+fn test_intersect_keeps_distinct_left_rows_found_on_the_right() {
+    let mut db = test_db();
+    seed_left_right(&mut db);
+
+    // int and bigint match on value, text and varchar on content, and
+    // NULL matches NULL.
+    assert_select_result(
+        db.execute("select id, tag from l intersect select id, tag from r")
+            .unwrap(),
+        &["id", "tag"],
+        vec![
+            vec![Value::Int(2), Value::Text("b".to_string())],
+            vec![Value::Int(4), Value::Null],
+        ],
+    );
+    assert_eq!(
+        db.execute_legacy("select id from l intersect select id from r")
+            .unwrap(),
+        "id\n2\n3\n4"
+    );
+}
+
+#[test]
+fn test_except_keeps_distinct_left_rows_missing_on_the_right() {
+    let mut db = test_db();
+    seed_left_right(&mut db);
+
+    assert_eq!(
+        db.execute_legacy("select id, tag from l except select id, tag from r")
+            .unwrap(),
+        "id\ttag\n1\ta\n3\tc"
+    );
+    // The left SELECT's own ORDER BY decides the result order.
+    assert_eq!(
+        db.execute_legacy("select id from l order by id desc except select id from r")
+            .unwrap(),
+        "id\n1"
+    );
+}
+
+#[test]
+fn test_all_forms_use_multiset_semantics() {
+    let mut db = test_db();
+    seed_left_right(&mut db);
+    db.execute(r#"insert into r values (2, "b")"#).unwrap();
+
+    // l has (2, b) three times and r twice.
+    assert_eq!(
+        db.execute_legacy("select id from l where id = 2 intersect all select id from r")
+            .unwrap(),
+        "id\n2\n2"
+    );
+    assert_eq!(
+        db.execute_legacy("select id, tag from l except all select id, tag from r")
+            .unwrap(),
+        "id\ttag\n1\ta\n2\tb\n3\tc"
+    );
+    assert_eq!(
+        db.execute_legacy("select tag from l intersect all select tag from l")
+            .unwrap(),
+        "tag\na\nb\nb\nb\nc\nnull"
+    );
+}
+
+#[test]
+fn test_set_op_chains_group_left() {
+    let mut db = test_db();
+    seed_left_right(&mut db);
+
+    // (l except r) intersect l-with-id-below-3
+    assert_eq!(
+        db.execute_legacy(
+            "select id from l except select id from r intersect select id from l where id < 3"
+        )
+        .unwrap(),
+        "id\n1"
+    );
+}
+
+#[test]
+fn test_set_op_rejects_incompatible_selects() {
+    let mut db = test_db();
+    seed_left_right(&mut db);
+
+    let err = db
+        .execute("select id, tag from l intersect select id from r")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("INTERSECT: left SELECT returns 2 column(s) but right SELECT returns 1"),
+        "{err}"
+    );
+    let err = db
+        .execute("select tag from l except select id from r")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("EXCEPT: column 1 'tag' (text) cannot be combined with column 'id' (bigint)"),
+        "{err}"
+    );
+    let err = db
+        .describe_statement("select id from l except select tag from r")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(
+            "EXCEPT: column 1 'id' (int) cannot be combined with column 'tag' (varchar(10))"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_set_op_reads_views_and_is_read_only() {
+    let mut db = test_db();
+    seed_left_right(&mut db);
+    db.execute("create view low as select id from l where id < 3")
+        .unwrap();
+
+    assert_eq!(
+        db.execute_legacy("select id from l except select id from low")
+            .unwrap(),
+        "id\n3\n4"
+    );
+    db.execute("create view both_ids as select id from l intersect select id from r")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select id from both_ids").unwrap(),
+        "id\n2\n3\n4"
+    );
+
+    let plan = db
+        .describe_statement("select id from l intersect all select id from r")
+        .unwrap();
+    assert_eq!(plan.statement, "intersect all");
+    assert_eq!(plan.tables, vec!["l".to_string(), "r".to_string()]);
+}
@@ -0,0 +1,267 @@
+use super::*;
+
+#[test]
+fn test_show_reports_defaults_and_set_round_trips() {
+    let mut db = test_db();
+    assert_transaction_result(
+        db.execute("show output_format").unwrap(),
+        "output_format = tabs",
+    );
+    assert_transaction_result(
+        db.execute("show defer_constraints").unwrap(),
+        "defer_constraints = off",
+    );
+    assert_transaction_result(db.execute("show max_rows").unwrap(), "max_rows = none");
+
+    assert_transaction_result(db.execute("set MAX_ROWS = 5").unwrap(), "set max_rows = 5");
+    assert_transaction_result(db.execute("show max_rows").unwrap(), "max_rows = 5");
+    assert_eq!(db.settings().max_rows, Some(5));
+}
+
+#[test]
+fn test_set_rejects_unknown_names_and_bad_values() {
+    let mut db = test_db();
+    let err = db.execute_legacy("set page_size = 10").unwrap_err();
+    assert!(
+        err.contains("Unknown setting 'page_size'; known settings: output_format, defer_constraints, max_rows"),
+        "{err}"
+    );
+    let err = db.execute_legacy("show page_size").unwrap_err();
+    assert!(err.contains("known settings"), "{err}");
+
+    for (sql, expected) in [
+        ("set output_format = csv", "expected tabs or json"),
+        ("set defer_constraints = yes", "expected on or off"),
+        (
+            "set max_rows = -1",
+            "expected a non-negative integer or none",
+        ),
+    ] {
+        let err = db.execute_legacy(sql).unwrap_err();
+        assert!(err.contains(expected), "{sql}: {err}");
+    }
+    assert_transaction_result(db.execute("show max_rows").unwrap(), "max_rows = none");
+}
+
+#[test]
+fn test_settings_reset_on_new_database_handle() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_settings_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("set output_format = json").unwrap();
+        db.execute("set max_rows = 1").unwrap();
+    }
+    let db = Database::open_legacy(path.clone());
+    assert_eq!(*db.settings(), Default::default());
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_output_format_json_changes_rendering() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    let sql = "select id, name from users where id = 1";
+    assert_eq!(db.execute_legacy(sql).unwrap(), "id\tname\n1\ta");
+
+    db.execute("set output_format = json").unwrap();
+    assert_eq!(
+        db.execute_legacy(sql).unwrap(),
+        r#"{"columns":["id","name"],"rows":[[1,"a"]]}"#
+    );
+    assert_eq!(
+        db.execute_legacy("delete from users where id = 1").unwrap(),
+        r#"{"message":"deleted 1 row(s) from users"}"#
+    );
+}
+
+#[test]
+fn test_defer_constraints_checks_foreign_keys_at_commit() {
+    let mut db = test_db();
+    db.execute("create table p (id int primary key)").unwrap();
+    db.execute("create table c (id int, pid int, foreign key(pid) references p(id))")
+        .unwrap();
+
+    db.execute("begin").unwrap();
+    let err = db
+        .execute_legacy("insert into c values (1, 10)")
+        .unwrap_err();
+    assert!(err.contains("FOREIGN KEY violation"), "{err}");
+    db.execute("rollback").unwrap();
+
+    db.execute("set defer_constraints = on").unwrap();
+    db.execute("begin").unwrap();
+    db.execute("insert into c values (1, 10)").unwrap();
+    db.execute("insert into p values (10)").unwrap();
+    assert_transaction_result(db.execute("commit").unwrap(), "transaction committed");
+    assert_eq!(
+        db.execute_legacy("select id, pid from c").unwrap(),
+        "id\tpid\n1\t10"
+    );
+
+    db.execute("begin").unwrap();
+    db.execute("insert into c values (2, 20)").unwrap();
+    db.execute("update c set pid = 30 where id = 1").unwrap();
+    let err = db.execute_legacy("commit").unwrap_err();
+    assert!(
+        err.contains("Deferred constraint check failed at commit: FOREIGN KEY violation"),
+        "{err}"
+    );
+    assert!(!db.has_active_transaction());
+    assert_eq!(
+        db.execute_legacy("select id, pid from c").unwrap(),
+        "id\tpid\n1\t10"
+    );
+
+    // Outside a transaction each statement is still checked on its own.
+    let err = db
+        .execute_legacy("insert into c values (3, 30)")
+        .unwrap_err();
+    assert!(err.contains("FOREIGN KEY violation"), "{err}");
+}
+
+#[test]
+fn test_max_rows_caps_select_and_stream_output() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("set max_rows = 2").unwrap();
+
+    match db.execute("select id from users order by id asc").unwrap() {
+        QueryResult::Select { rows, stats, .. } => {
+            assert_eq!(rows, vec![vec![Value::Int(1)], vec![Value::Int(2)]]);
+            assert_eq!(stats.rows_returned, Some(2));
+        }
+        other => panic!("expected select result, got {other:?}"),
+    }
+    assert_eq!(
+        db.execute_legacy("select id from users order by id asc limit 1")
+            .unwrap(),
+        "id\n1"
+    );
+
+    let mut streamed = 0;
+    db.query_stream("select * from users", &mut |_, _| {
+        streamed += 1;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(streamed, 2);
+
+    // The scan stops at the cap: the row after it, whose null age cannot
+    // be compared, is never read.
+    db.execute(r#"insert into users values (4, "d", null)"#)
+        .unwrap();
+    db.execute("set max_rows = 3").unwrap();
+    let mut streamed = 0;
+    db.query_stream("select * from users where age > 5", &mut |_, _| {
+        streamed += 1;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(streamed, 3);
+    db.execute("delete from users where id = 4").unwrap();
+
+    db.execute("set max_rows = none").unwrap();
+    assert_eq!(
+        db.execute_legacy("select id from users order by id asc")
+            .unwrap(),
+        "id\n1\n2\n3"
+    );
+}
+
+#[test]
+fn test_strict_types_on_rejects_mismatched_where_literals() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    assert_transaction_result(
+        db.execute("show strict_types").unwrap(),
+        "strict_types = on",
+    );
+    let err = db
+        .execute_legacy(r#"select name from users where age > "old" or id = 1"#)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "WHERE: value 'old' is not valid for column 'age' (int)"
+    );
+    let err = db
+        .execute_legacy(r#"select name from users where id in (1, "x")"#)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "WHERE IN item 2: value 'x' is not valid for column 'id' (int)"
+    );
+}
+
+#[test]
+fn test_strict_types_off_treats_mismatched_where_literals_as_no_match() {
+    let mut db = test_db();
+    db.execute("create table users (id int primary key, name text, age int)")
+        .unwrap();
+    for (id, name, age) in [(1, "a", 30), (2, "b", 20), (3, "c", 10)] {
+        db.execute(&format!(
+            r#"insert into users values ({id}, "{name}", {age})"#
+        ))
+        .unwrap();
+    }
+    db.execute("create table orders (id int, user_id int)")
+        .unwrap();
+    db.execute("insert into orders values (1, 2)").unwrap();
+    assert_transaction_result(
+        db.execute("set strict_types = off").unwrap(),
+        "set strict_types = off",
+    );
+
+    for (sql, expected) in [
+        (r#"select name from users where age = "abc""#, "name"),
+        (r#"select name from users where age != "abc""#, "name"),
+        (r#"select name from users where id = "abc""#, "name"),
+        (
+            r#"select name from users where age > "old" or id = 1"#,
+            "name\na",
+        ),
+        (
+            r#"select name from users where id in (1, "x", 3) order by id"#,
+            "name\na\nc",
+        ),
+        (
+            r#"select users.name from users join orders on users.id = orders.user_id where orders.id = "x" or users.age = 20"#,
+            "users.name\nb",
+        ),
+        (
+            r#"select name from users u where exists (select 1 from orders o where o.user_id = u.id and o.id < "x")"#,
+            "name",
+        ),
+        (
+            r#"select id from users where age = "x" intersect select id from users"#,
+            "id",
+        ),
+    ] {
+        assert_eq!(db.execute_legacy(sql).unwrap(), expected, "{sql}");
+    }
+
+    // Operators a column type does not support are still errors, and
+    // UPDATE and DELETE stay strict.
+    let err = db
+        .execute_legacy(r#"select name from users where age like "1*""#)
+        .unwrap_err();
+    assert_eq!(err, "Operator 'like' is only valid for text columns");
+    let err = db
+        .execute_legacy(r#"delete from users where age = "abc""#)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "WHERE: value 'abc' is not valid for column 'age' (int)"
+    );
+    assert_eq!(
+        db.execute_legacy("select count(*) from users").unwrap(),
+        "count(*)\n3"
+    );
+
+    db.execute("set strict_types = on").unwrap();
+    assert!(
+        db.execute_legacy(r#"select name from users where age = "abc""#)
+            .is_err()
+    );
+}
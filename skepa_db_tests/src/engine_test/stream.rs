@@ -0,0 +1,142 @@
+use super::*;
+use skepa_db_core::storage::Schema;
+use skepa_db_core::types::Row;
+
+fn collect_stream(db: &mut Database, sql: &str) -> (Schema, Vec<Row>) {
+    let mut rows: Vec<Row> = Vec::new();
+    let schema = db
+        .query_stream(sql, &mut |_, row| {
+            rows.push(row.clone());
+            Ok(())
+        })
+        .unwrap();
+    (schema, rows)
+}
+
+fn column_names(schema: &Schema) -> Vec<&str> {
+    schema.columns.iter().map(|c| c.name.as_str()).collect()
+}
+
+#[test]
+fn test_query_stream_matches_materialized_select() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("create table orders (id int, user_id int)")
+        .unwrap();
+    db.execute("insert into orders values (10, 1)").unwrap();
+    db.execute("insert into orders values (11, 3)").unwrap();
+
+    for sql in [
+        "select * from users",
+        "select name as n, age from users where age >= 20",
+        "select id from users limit 1 offset 1",
+        "select * from users where id = 9",
+        "select * from users order by age",
+        "select distinct name from users",
+        "select age, count(*) from users group by age",
+        "select users.name, orders.id from users join orders on users.id = orders.user_id",
+    ] {
+        let QueryResult::Select { schema, rows, .. } = db.execute(sql).unwrap() else {
+            panic!("expected select result for {sql}");
+        };
+        let (streamed_schema, streamed_rows) = collect_stream(&mut db, sql);
+        assert_eq!(
+            column_names(&streamed_schema),
+            column_names(&schema),
+            "{sql}"
+        );
+        assert_eq!(streamed_rows, rows, "{sql}");
+    }
+}
+
+#[test]
+fn test_query_stream_reads_views_and_snapshots() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("create view adults as select name, age from users where age >= 18")
+        .unwrap();
+    db.execute("create materialized view names as select name from users")
+        .unwrap();
+    db.execute("create snapshot before").unwrap();
+    db.execute("delete from users where id = 1").unwrap();
+
+    for sql in [
+        "select * from adults",
+        "select name from names",
+        "select * from users as of before",
+        "select name from users intersect select name from adults",
+    ] {
+        let QueryResult::Select { schema, rows, .. } = db.execute(sql).unwrap() else {
+            panic!("expected select result for {sql}");
+        };
+        let (streamed_schema, streamed_rows) = collect_stream(&mut db, sql);
+        assert_eq!(
+            column_names(&streamed_schema),
+            column_names(&schema),
+            "{sql}"
+        );
+        assert_eq!(streamed_rows, rows, "{sql}");
+    }
+
+    db.execute("set max_rows = 1").unwrap();
+    let (_, rows) = collect_stream(&mut db, "select * from users as of before");
+    assert_eq!(rows.len(), 1);
+    let err = db
+        .query_stream("select * from adults for update", &mut |_, _| Ok(()))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "FOR UPDATE is not supported when streaming"
+    );
+}
+
+#[test]
+fn test_query_stream_returns_schema_for_empty_result() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    let (schema, rows) = collect_stream(&mut db, "select name from users where age > 100");
+    assert!(rows.is_empty());
+    assert_eq!(column_names(&schema), vec!["name"]);
+}
+
+#[test]
+fn test_query_stream_sink_error_aborts() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (4, "d", 5)"#)
+        .unwrap();
+
+    let mut calls = 0;
+    let err = db
+        .query_stream("select * from users", &mut |_, _| {
+            calls += 1;
+            if calls == 2 {
+                return Err("sink full".to_string());
+            }
+            Ok(())
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "sink full");
+    assert_eq!(calls, 2);
+
+    assert!(db.has_active_transaction());
+    db.execute("commit").unwrap();
+    let (_, rows) = collect_stream(&mut db, "select id from users");
+    assert_eq!(
+        rows,
+        (1..=4).map(|i| vec![Value::Int(i)]).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_query_stream_rejects_non_select() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    let err = db
+        .query_stream("delete from users where id = 1", &mut |_, _| Ok(()))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Only SELECT statements can be streamed");
+    let (_, rows) = collect_stream(&mut db, "select * from users");
+    assert_eq!(rows.len(), 3);
+}
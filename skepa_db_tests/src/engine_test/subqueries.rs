@@ -0,0 +1,284 @@
+use super::*;
+
+fn seed_users_orders(db: &mut Database) {
+    seed_users_3(db);
+    db.execute_legacy("insert into users values (null, \"d\", 40)")
+        .unwrap();
+    db.execute_legacy("create table orders (id int, user_id bigint, total int)")
+        .unwrap();
+    for (id, user_id, total) in [(1, "1", 5), (2, "1", 50), (3, "3", 7), (4, "null", 9)] {
+        db.execute_legacy(&format!(
+            "insert into orders values ({id}, {user_id}, {total})"
+        ))
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_exists_keeps_outer_rows_with_a_correlated_match() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+
+    assert_eq!(
+        db.execute_legacy(
+            "select name from users u where exists (select 1 from orders o where o.user_id = u.id)"
+        )
+        .unwrap(),
+        "name\na\nc"
+    );
+    // The outer column may come first, and the outer table name qualifies
+    // it when the outer query has no alias.
+    assert_eq!(
+        db.execute_legacy(
+            "select name from users where exists (select * from orders o where users.id = o.user_id and total > 6)"
+        )
+        .unwrap(),
+        "name\na\nc"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "select u.name from users u where u.age > 15 and exists (select id from orders where user_id = u.id and total < 10)"
+        )
+        .unwrap(),
+        "name\na"
+    );
+}
+
+#[test]
+fn test_not_exists_keeps_outer_rows_without_a_match() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+
+    // A NULL id matches no order, not even the one with a NULL user_id.
+    assert_eq!(
+        db.execute_legacy(
+            "select name from users u where not exists (select 1 from orders o where o.user_id = u.id) order by name"
+        )
+        .unwrap(),
+        "name\nb\nd"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "select name from users u where not exists (select 1 from orders o where o.user_id = u.id and o.total > 10) or u.age = 30"
+        )
+        .unwrap(),
+        "name\na\nb\nc\nd"
+    );
+}
+
+#[test]
+fn test_uncorrelated_exists_tests_the_subquery_once() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+
+    assert_eq!(
+        db.execute_legacy(
+            "select id from users where exists (select 1 from orders where total > 40)"
+        )
+        .unwrap(),
+        "id\n1\n2\n3\nnull"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "select id from users where exists (select 1 from orders where total > 400)"
+        )
+        .unwrap(),
+        "id"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "select id from users where id = 2 or not exists (select 1 from orders where total > 400)"
+        )
+        .unwrap(),
+        "id\n1\n2\n3\nnull"
+    );
+}
+
+#[test]
+fn test_exists_reads_subquery_rows_through_views_and_transactions() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+    db.execute_legacy(
+        "create view buyers as select name from users u where exists (select 1 from orders o where o.user_id = u.id)",
+    )
+    .unwrap();
+
+    db.execute_legacy("begin").unwrap();
+    db.execute_legacy("insert into orders values (5, 2, 1)")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy(
+            "select name from users u where exists (select 1 from orders o where o.user_id = u.id)"
+        )
+        .unwrap(),
+        "name\na\nb\nc"
+    );
+    db.execute_legacy("rollback").unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from buyers").unwrap(),
+        "name\na\nc"
+    );
+}
+
+#[test]
+fn test_exists_reports_bad_subqueries() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+    db.execute_legacy("create table tags (user_id text)")
+        .unwrap();
+
+    let err = db
+        .execute_legacy(
+            "select * from users u where exists (select 1 from orders o where o.user_id > u.id)",
+        )
+        .unwrap_err();
+    assert!(
+        err.contains("can only reference the outer statement in '<column> = u.<column>' terms"),
+        "{err}"
+    );
+    let err = db
+        .execute_legacy(
+            "select * from users u where exists (select 1 from tags t where t.user_id = u.id)",
+        )
+        .unwrap_err();
+    assert!(
+        err.contains("EXISTS cannot compare 'tags.user_id' (text) with 'id' (int)"),
+        "{err}"
+    );
+    let err = db
+        .execute_legacy(
+            "select * from users u where exists (select 1 from orders o where o.missing = u.id)",
+        )
+        .unwrap_err();
+    assert!(err.contains("missing"), "{err}");
+    let err = db
+        .execute_legacy(
+            "select * from users u where exists (select 1 from nope n where n.id = u.id)",
+        )
+        .unwrap_err();
+    assert!(err.contains("nope"), "{err}");
+    assert_eq!(
+        db.execute_legacy("select count(*) from users").unwrap(),
+        "count(*)\n4"
+    );
+}
+
+#[test]
+fn test_in_subquery_selects_rows_by_the_subquery_column() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+
+    assert_eq!(
+        db.execute_legacy(
+            "select name from users where id in (select user_id from orders where total < 8)"
+        )
+        .unwrap(),
+        "name\na\nc"
+    );
+    let err = db
+        .execute_legacy("select name from users where id in (select id, user_id from orders)")
+        .unwrap_err();
+    assert_eq!(err, "IN subquery must select exactly one column");
+}
+
+#[test]
+fn test_delete_with_subquery_removes_selected_rows() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+    db.execute_legacy("create table banned (user_id int)")
+        .unwrap();
+    db.execute_legacy("insert into banned values (3)").unwrap();
+
+    assert_eq!(
+        db.execute_legacy("delete from orders where user_id in (select user_id from banned)")
+            .unwrap(),
+        "deleted 1 row(s) from orders"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "delete from users where not exists (select 1 from orders o where o.user_id = users.id)"
+        )
+        .unwrap(),
+        "deleted 3 row(s) from users"
+    );
+    assert_eq!(
+        db.execute_legacy("select name from users").unwrap(),
+        "name\na"
+    );
+    // An empty subquery matches nothing.
+    assert_eq!(
+        db.execute_legacy(
+            "delete from orders where user_id in (select user_id from banned where user_id = 1)"
+        )
+        .unwrap(),
+        "deleted 0 row(s) from orders"
+    );
+    assert_eq!(
+        db.execute_legacy("select count(*) from orders").unwrap(),
+        "count(*)\n3"
+    );
+}
+
+#[test]
+fn test_update_with_subquery_reads_rows_before_the_update() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+
+    assert_eq!(
+        db.execute_legacy(
+            "update users set age = 0 where exists (select 1 from orders o where o.user_id = users.id and o.total > 6)"
+        )
+        .unwrap(),
+        "updated 2 row(s) in users"
+    );
+    assert_eq!(
+        db.execute_legacy("select name from users where age = 0")
+            .unwrap(),
+        "name\na\nc"
+    );
+    // The subquery sees the table as it was before the statement, so
+    // rows the update changes do not feed back into it.
+    assert_eq!(
+        db.execute_legacy(
+            "update users set age = 5 where age in (select age from users where age < 25)"
+        )
+        .unwrap(),
+        "updated 3 row(s) in users"
+    );
+    assert_eq!(
+        db.execute_legacy("select name, age from users order by name")
+            .unwrap(),
+        "name\tage\na\t5\nb\t5\nc\t5\nd\t40"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "update users set age = 1 where id in (select user_id from orders where total > 999)"
+        )
+        .unwrap(),
+        "updated 0 row(s) in users"
+    );
+
+    db.execute_legacy("create view big as select user_id from orders where total > 10")
+        .unwrap();
+    let err = db
+        .execute_legacy("update users set age = 2 where id in (select user_id from big)")
+        .unwrap_err();
+    assert_eq!(err, "A subquery in UPDATE or DELETE cannot read view 'big'");
+}
+
+#[test]
+fn test_trigger_into_a_view_is_not_reported_as_a_subquery() {
+    let mut db = test_db();
+    seed_users_orders(&mut db);
+    db.execute_legacy(
+        "create materialized view big as select user_id from orders where total > 10",
+    )
+    .unwrap();
+
+    let err = db
+        .execute_legacy(
+            "create trigger t after insert on users begin insert into big values (new.id) end",
+        )
+        .unwrap_err();
+    assert_eq!(err, "Cannot modify view 'big'");
+}
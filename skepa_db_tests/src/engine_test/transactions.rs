@@ -1,4 +1,5 @@
 use super::*;
+use skepa_db_core::config::{ConflictDetection, DbConfig};
 
 #[test]
 fn test_transaction_commit_persists_changes() {
@@ -181,7 +182,6 @@ fn test_transaction_commit_conflict_when_table_changed_externally() {
     tx_db
         .execute_legacy("update t set v = 11 where id = 1")
         .unwrap();
-    std::thread::sleep(Duration::from_millis(5));
     other_db
         .execute_legacy("insert into t values (2, 20)")
         .unwrap();
@@ -198,6 +198,215 @@ fn test_transaction_commit_conflict_when_table_changed_externally() {
     let _ = std::fs::remove_dir_all(&path);
 }
 
+#[test]
+fn test_transaction_commit_fails_when_table_created_externally() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_schema_created_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut setup = Database::open_legacy(path.clone());
+        setup
+            .execute_legacy("create table a (id int, v int)")
+            .unwrap();
+        setup
+            .execute_legacy("insert into a values (1, 10)")
+            .unwrap();
+    }
+
+    let mut tx_db = Database::open_legacy(path.clone());
+    let mut other_db = Database::open_legacy(path.clone());
+
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db
+        .execute_legacy("update a set v = 11 where id = 1")
+        .unwrap();
+    other_db.execute_legacy("create table c (id int)").unwrap();
+
+    let err = tx_db.execute_legacy("commit").unwrap_err();
+    assert_eq!(
+        err,
+        "Transaction conflict: schema changed during transaction (created: c)"
+    );
+    assert!(!tx_db.has_active_transaction());
+
+    // The staged update is discarded and the new table is visible.
+    assert_eq!(
+        tx_db.execute_legacy("select * from a").unwrap(),
+        "id\tv\n1\t10"
+    );
+    assert_eq!(tx_db.execute_legacy("select * from c").unwrap(), "id");
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_transaction_commit_fails_when_schema_altered_externally() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_schema_altered_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut setup = Database::open_legacy(path.clone());
+        setup
+            .execute_legacy("create table a (id int, v int)")
+            .unwrap();
+        setup
+            .execute_legacy("create table b (id int, v int)")
+            .unwrap();
+    }
+
+    let mut tx_db = Database::open_legacy(path.clone());
+    let mut other_db = Database::open_legacy(path.clone());
+
+    // A read-only transaction has nothing to apply and still commits.
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db.execute_legacy("select * from a").unwrap();
+    other_db
+        .execute_legacy("alter table b add unique(v)")
+        .unwrap();
+    assert_eq!(
+        tx_db.execute_legacy("commit").unwrap(),
+        "transaction committed"
+    );
+
+    // The schema change hit a table this transaction never wrote.
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db
+        .execute_legacy("insert into a values (1, 10)")
+        .unwrap();
+    other_db
+        .execute_legacy("alter table b alter column v set not null")
+        .unwrap();
+    let err = tx_db.execute_legacy("commit").unwrap_err();
+    assert_eq!(
+        err,
+        "Transaction conflict: schema changed during transaction"
+    );
+    assert_eq!(tx_db.execute_legacy("select * from a").unwrap(), "id\tv");
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_other_handle_reads_only_committed_rows() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_snapshot_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut writer = Database::open_legacy(path.clone());
+    writer
+        .execute_legacy("create table t (id int, v int)")
+        .unwrap();
+    let mut reader = Database::open_legacy(path.clone());
+
+    writer.execute_legacy("begin").unwrap();
+    writer
+        .execute_legacy("insert into t values (1, 10)")
+        .unwrap();
+    assert_eq!(reader.execute_legacy("select * from t").unwrap(), "id\tv");
+
+    writer.execute_legacy("commit").unwrap();
+    assert_eq!(
+        reader.execute_legacy("select * from t").unwrap(),
+        "id\tv\n1\t10"
+    );
+
+    // A transaction keeps reading the state it began with.
+    reader.execute_legacy("begin").unwrap();
+    writer
+        .execute_legacy("insert into t values (2, 20)")
+        .unwrap();
+    assert_eq!(
+        reader.execute_legacy("select * from t").unwrap(),
+        "id\tv\n1\t10"
+    );
+    reader.execute_legacy("rollback").unwrap();
+    assert_eq!(
+        reader
+            .execute_legacy("select * from t order by id asc")
+            .unwrap(),
+        "id\tv\n1\t10\n2\t20"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_autocommit_write_does_not_overwrite_other_handles_commit() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_stale_write_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut a = Database::open_legacy(path.clone());
+    a.execute_legacy("create table t (id int, v int)").unwrap();
+    let mut b = Database::open_legacy(path.clone());
+
+    a.execute_legacy("insert into t values (1, 10)").unwrap();
+    b.execute_legacy("insert into t values (2, 20)").unwrap();
+
+    let mut check = Database::open_legacy(path.clone());
+    assert_eq!(
+        check
+            .execute_legacy("select * from t order by id asc")
+            .unwrap(),
+        "id\tv\n1\t10\n2\t20"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_table_write_counters_move_only_with_their_table() {
+    use skepa_db_core::storage::generations::Generations;
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_generations_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut a = Database::open_legacy(path.clone());
+    a.execute_legacy("create table t (id int)").unwrap();
+    a.execute_legacy("create table u (id int)").unwrap();
+    let before = Generations::load(&path).unwrap();
+    a.execute_legacy("insert into t values (1)").unwrap();
+    let after = Generations::load(&path).unwrap();
+    assert!(after.table("t") > before.table("t"));
+    assert_eq!(after.table("u"), before.table("u"));
+    assert!(after.generation > before.generation);
+
+    // Opening rewrites nothing that is already on disk, so a transaction
+    // begun before it commits cleanly.
+    a.execute_legacy("begin").unwrap();
+    a.execute_legacy("insert into t values (2)").unwrap();
+    drop(Database::open_legacy(path.clone()));
+    assert_eq!(Generations::load(&path).unwrap().tables, after.tables);
+    assert_eq!(a.execute_legacy("commit").unwrap(), "transaction committed");
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_same_size_rewrites_are_seen_by_other_handles_at_once() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_same_size_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut a = Database::open_legacy(path.clone());
+    a.execute_legacy("create table t (id int, v int)").unwrap();
+    a.execute_legacy("insert into t values (1, 10)").unwrap();
+    let mut b = Database::open_legacy(path.clone());
+    // Each write keeps the file the same length, well inside one mtime tick.
+    for v in 11..30 {
+        a.execute_legacy(&format!("update t set v = {v} where id = 1"))
+            .unwrap();
+        assert_eq!(
+            b.execute_legacy("select v from t").unwrap(),
+            format!("v\n{v}")
+        );
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
 #[test]
 fn test_transaction_commit_no_conflict_when_other_table_changes() {
     let mut path: PathBuf = std::env::temp_dir();
@@ -227,7 +436,6 @@ fn test_transaction_commit_no_conflict_when_other_table_changes() {
     tx_db
         .execute_legacy("update a set v = 11 where id = 1")
         .unwrap();
-    std::thread::sleep(Duration::from_millis(5));
     other_db
         .execute_legacy("update b set v = 101 where id = 1")
         .unwrap();
@@ -276,7 +484,6 @@ fn test_transaction_conflict_detection_is_scoped_to_touched_tables() {
         vec![vec![Value::Int(1), Value::Int(10)]],
     );
 
-    std::thread::sleep(Duration::from_millis(5));
     other_db
         .execute_legacy("update b set v = 101 where id = 1")
         .unwrap();
@@ -795,3 +1002,545 @@ fn test_alter_fk_on_delete_no_action_commit_succeeds_when_fixed_in_tx() {
     assert_eq!(db.execute_legacy("select * from p").unwrap(), "id");
     assert_eq!(db.execute_legacy("select * from c").unwrap(), "id\tpid");
 }
+
+#[test]
+fn test_tx_second_update_on_indexed_column_sees_first_update() {
+    let mut db = test_db();
+    db.execute("create table users (id int, city text)")
+        .unwrap();
+    db.execute("create index on users (city)").unwrap();
+    db.execute(r#"insert into users values (1, "ny")"#).unwrap();
+    db.execute(r#"insert into users values (2, "la")"#).unwrap();
+    db.execute(r#"insert into users values (3, "ny")"#).unwrap();
+
+    db.execute("begin").unwrap();
+    assert_eq!(
+        db.execute_legacy(r#"update users set city = "sf" where city = "ny""#)
+            .unwrap(),
+        "updated 2 row(s) in users"
+    );
+    assert_eq!(
+        db.execute_legacy(r#"update users set city = "ny" where city = "la""#)
+            .unwrap(),
+        "updated 1 row(s) in users"
+    );
+    assert_eq!(
+        db.execute_legacy(r#"update users set city = "tx" where city = "sf""#)
+            .unwrap(),
+        "updated 2 row(s) in users"
+    );
+    assert_eq!(
+        db.execute_legacy(r#"select id from users where city = "ny""#)
+            .unwrap(),
+        "id\n2"
+    );
+    db.execute("commit").unwrap();
+    assert_eq!(
+        db.execute_legacy("select id, city from users order by id")
+            .unwrap(),
+        "id\tcity\n1\ttx\n2\tny\n3\ttx"
+    );
+}
+
+#[test]
+fn test_annotate_staged_prefixes_mutation_messages_in_tx() {
+    let path = test_db().path().clone();
+    let mut db = Database::open(DbConfig::new(path).with_annotate_staged(true)).unwrap();
+    db.execute("create table t (id int, v int)").unwrap();
+    assert_eq!(
+        db.execute_legacy("insert into t values (1, 10)").unwrap(),
+        "inserted 1 row into t"
+    );
+
+    db.execute("begin").unwrap();
+    assert_eq!(
+        db.execute_legacy("insert into t values (2, 20)").unwrap(),
+        "staged: inserted 1 row into t"
+    );
+    let out = db.execute("update t set v = 0 where id = 1").unwrap();
+    assert_eq!(out.message(), Some("staged: updated 1 row(s) in t"));
+    assert_eq!(out.rows_affected(), Some(1));
+    assert_eq!(
+        db.execute_legacy("delete from t where id = 2").unwrap(),
+        "staged: deleted 1 row(s) from t"
+    );
+    assert_eq!(db.execute_legacy("select * from t").unwrap(), "id\tv\n1\t0");
+    assert_eq!(
+        db.execute_legacy("commit").unwrap(),
+        "transaction committed"
+    );
+    assert_eq!(
+        db.execute_legacy("delete from t where id = 1").unwrap(),
+        "deleted 1 row(s) from t"
+    );
+}
+
+#[test]
+fn test_staged_messages_are_not_annotated_by_default() {
+    let mut db = test_db();
+    db.execute("create table t (id int)").unwrap();
+    db.execute("begin").unwrap();
+    assert_eq!(
+        db.execute_legacy("insert into t values (1)").unwrap(),
+        "inserted 1 row into t"
+    );
+    db.execute("rollback").unwrap();
+}
+
+#[test]
+fn test_begin_rollback_only_copies_tables_the_transaction_writes() {
+    let mut db = test_db();
+    db.execute("create table huge (id int primary key, v text)")
+        .unwrap();
+    db.execute("create table tiny (id int primary key, v text)")
+        .unwrap();
+    let rows = (0..20_000).map(|i| vec![Value::Int(i), Value::Text(format!("v{i}"))]);
+    db.bulk_load("huge", rows).unwrap();
+    db.execute(r#"insert into tiny values (1, "a")"#).unwrap();
+    db.execute(r#"insert into tiny values (2, "b")"#).unwrap();
+    let copied = |db: &Database| {
+        db.debug_storage_json().unwrap()["tx_snapshot_rows"]
+            .as_u64()
+            .unwrap()
+    };
+
+    db.execute("begin").unwrap();
+    assert_eq!(copied(&db), 0);
+    db.execute(r#"insert into tiny values (3, "c")"#).unwrap();
+    db.execute("delete from tiny where id = 1").unwrap();
+    db.execute("rollback").unwrap();
+    assert_eq!(copied(&db), 2);
+
+    assert_select_result(
+        db.execute("select id from tiny order by id asc").unwrap(),
+        &["id"],
+        vec![vec![Value::Int(1)], vec![Value::Int(2)]],
+    );
+    assert_eq!(db.table_stats("huge").unwrap().rows, 20_000);
+}
+
+#[test]
+fn test_rollback_restores_child_tables_reached_through_cascade() {
+    let mut db = test_db();
+    db.execute("create table parent (id int primary key)")
+        .unwrap();
+    db.execute("create table child (id int primary key, pid int, foreign key (pid) references parent(id) on delete cascade)")
+        .unwrap();
+    db.execute("insert into parent values (1)").unwrap();
+    db.execute("insert into child values (10, 1)").unwrap();
+
+    db.execute("begin").unwrap();
+    db.execute("delete from parent where id = 1").unwrap();
+    assert_select_result(db.execute("select id from child").unwrap(), &["id"], vec![]);
+    db.execute("rollback").unwrap();
+
+    assert_select_result(
+        db.execute("select id, pid from child").unwrap(),
+        &["id", "pid"],
+        vec![vec![Value::Int(10), Value::Int(1)]],
+    );
+    assert_eq!(
+        db.debug_storage_json().unwrap()["tx_snapshot_rows"],
+        serde_json::json!(2)
+    );
+}
+
+#[test]
+fn test_show_transaction_reports_staged_work() {
+    let mut db = test_db();
+    db.execute("create table p (id int primary key)").unwrap();
+    db.execute(
+        "create table c (id int, pid int, foreign key(pid) references p(id) on update no action)",
+    )
+    .unwrap();
+    db.execute("insert into p values (1)").unwrap();
+    db.execute("insert into c values (1, 1)").unwrap();
+
+    assert_eq!(db.transaction_info(), None);
+    assert_eq!(
+        db.execute_legacy("show transaction").unwrap(),
+        "no active transaction"
+    );
+
+    db.execute("begin").unwrap();
+    let info = db.transaction_info().unwrap();
+    assert_eq!(info.staged_statements, 0);
+    assert!(info.touched_tables.is_empty());
+    let begin_txid = info.txid;
+
+    db.execute("insert into c values (2, 1)").unwrap();
+    db.execute("update p set id = 2 where id = 1").unwrap();
+    db.execute("select * from p").unwrap();
+    let info = db.transaction_info().unwrap();
+    assert_eq!(info.txid, begin_txid);
+    assert_eq!(info.staged_statements, 2);
+    let staged_bytes =
+        "insert into c values (2, 1)".len() + "update p set id = 2 where id = 1".len();
+    assert_eq!(info.staged_bytes, staged_bytes);
+    assert_eq!(info.touched_tables, vec!["c".to_string(), "p".to_string()]);
+    let violation = info
+        .no_action_violation
+        .expect("pending NO ACTION violation");
+    assert!(
+        violation.to_lowercase().contains("no action"),
+        "{violation}"
+    );
+    let shown = db.execute_legacy("show transaction").unwrap();
+    assert!(
+        shown.starts_with(&format!(
+            "transaction {begin_txid}: 2 staged statement(s) ({staged_bytes} bytes); touched tables: c, p; deferred NO ACTION constraints: unresolved ("
+        )),
+        "{shown}"
+    );
+
+    db.execute("update p set id = 1 where id = 2").unwrap();
+    assert_eq!(db.transaction_info().unwrap().no_action_violation, None);
+    assert!(
+        db.execute_legacy("show transaction")
+            .unwrap()
+            .ends_with("deferred NO ACTION constraints: none unresolved")
+    );
+    db.execute("commit").unwrap();
+    assert_eq!(db.transaction_info(), None);
+}
+
+#[test]
+fn test_staging_limits_reject_oversized_transactions_and_allow_rollback() {
+    let path = test_db().path().clone();
+    let config = DbConfig::new(path)
+        .with_tx_max_staged_statements(2)
+        .with_tx_max_staged_bytes(60);
+    let mut db = Database::open(config).unwrap();
+    db.execute("create table t (id int)").unwrap();
+
+    db.execute("begin").unwrap();
+    db.execute("insert into t values (1)").unwrap();
+    db.execute("insert into t values (2)").unwrap();
+    let err = db
+        .execute("insert into t values (3)")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Transaction too large"), "{err}");
+    assert!(err.contains("tx_max_staged_statements"), "{err}");
+    let info = db.transaction_info().unwrap();
+    assert_eq!(info.staged_statements, 2);
+    db.execute("rollback").unwrap();
+    assert_eq!(db.execute_legacy("select * from t").unwrap(), "id");
+
+    db.execute("begin").unwrap();
+    db.execute("insert into t values (1)").unwrap();
+    let err = db
+        .execute("insert into t values (1000000000000000000000000000000)")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("tx_max_staged_bytes"), "{err}");
+    assert_eq!(db.transaction_info().unwrap().staged_statements, 1);
+    db.execute("rollback").unwrap();
+    assert_eq!(db.execute_legacy("select * from t").unwrap(), "id");
+
+    // Autocommit statements are not staged and ignore the caps.
+    for id in 0..3 {
+        db.execute(&format!("insert into t values ({id})")).unwrap();
+    }
+}
+
+#[test]
+fn test_select_for_update_conflicts_only_on_locked_rows() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_for_update_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut setup = Database::open_legacy(path.clone());
+        setup
+            .execute_legacy("create table accounts (id int primary key, balance int)")
+            .unwrap();
+        setup
+            .execute_legacy("create table audit (note text)")
+            .unwrap();
+        setup
+            .execute_legacy("insert into accounts values (1, 100)")
+            .unwrap();
+        setup
+            .execute_legacy("insert into accounts values (2, 200)")
+            .unwrap();
+    }
+
+    let mut tx_db = Database::open_legacy(path.clone());
+    let mut other_db = Database::open_legacy(path.clone());
+
+    // A change to a row that was not locked commits fine.
+    tx_db.execute_legacy("begin").unwrap();
+    assert_eq!(
+        tx_db
+            .execute_legacy("select balance from accounts where id = 1 for update")
+            .unwrap(),
+        "balance\n100"
+    );
+    tx_db
+        .execute_legacy(r#"insert into audit values ("checked 1")"#)
+        .unwrap();
+    other_db
+        .execute_legacy("update accounts set balance = 250 where id = 2")
+        .unwrap();
+    assert_eq!(
+        tx_db.execute_legacy("commit").unwrap(),
+        "transaction committed"
+    );
+
+    // A change to the locked row does not.
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db
+        .execute_legacy("select * from accounts where id = 1 for update")
+        .unwrap();
+    tx_db
+        .execute_legacy(r#"insert into audit values ("checked 1 again")"#)
+        .unwrap();
+    other_db
+        .execute_legacy("update accounts set balance = 90 where id = 1")
+        .unwrap();
+    let err = tx_db.execute_legacy("commit").unwrap_err();
+    assert_eq!(
+        err,
+        "Transaction conflict on table 'accounts': a row locked with FOR UPDATE changed outside this transaction"
+    );
+    assert_eq!(
+        tx_db.execute_legacy("select * from audit").unwrap(),
+        "note\nchecked 1"
+    );
+    assert_eq!(
+        tx_db
+            .execute_legacy("select balance from accounts where id = 1")
+            .unwrap(),
+        "balance\n90"
+    );
+}
+
+/// Opens two handles on a fresh `accounts` table holding ids 1 and 2: the
+/// first commits with `conflict`, the second with the default.
+fn conflict_mode_handles(name: &str, conflict: ConflictDetection) -> (Database, Database, PathBuf) {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    {
+        let mut setup = Database::open_legacy(path.clone());
+        setup
+            .execute_legacy("create table accounts (id int primary key, balance int)")
+            .unwrap();
+        setup
+            .execute_legacy("insert into accounts values (1, 100)")
+            .unwrap();
+        setup
+            .execute_legacy("insert into accounts values (2, 200)")
+            .unwrap();
+    }
+    let tx_db =
+        Database::open(DbConfig::new(path.clone()).with_conflict_detection(conflict)).unwrap();
+    let other_db = Database::open_legacy(path.clone());
+    (tx_db, other_db, path)
+}
+
+#[test]
+fn test_conflict_detection_modes_on_disjoint_and_overlapping_rows() {
+    for conflict in [ConflictDetection::Table, ConflictDetection::Row] {
+        let (mut tx_db, mut other_db, path) =
+            conflict_mode_handles(&format!("conflict_{conflict:?}"), conflict);
+
+        // Disjoint rows: only table-level detection refuses the commit.
+        tx_db.execute_legacy("begin").unwrap();
+        tx_db
+            .execute_legacy("update accounts set balance = 110 where id = 1")
+            .unwrap();
+        tx_db
+            .execute_legacy("insert into accounts values (3, 300)")
+            .unwrap();
+        other_db
+            .execute_legacy("update accounts set balance = 250 where id = 2")
+            .unwrap();
+        other_db
+            .execute_legacy("insert into accounts values (4, 400)")
+            .unwrap();
+        let out = tx_db.execute_legacy("commit");
+        let expected = match conflict {
+            ConflictDetection::Table => {
+                assert_eq!(
+                    out.unwrap_err(),
+                    "Transaction conflict on table 'accounts': data changed outside this transaction"
+                );
+                "id\tbalance\n1\t100\n2\t250\n4\t400"
+            }
+            ConflictDetection::Row => {
+                assert_eq!(out.unwrap(), "transaction committed");
+                "id\tbalance\n1\t110\n2\t250\n3\t300\n4\t400"
+            }
+        };
+        let all = "select * from accounts order by id asc";
+        assert_eq!(tx_db.execute_legacy(all).unwrap(), expected);
+        assert_eq!(other_db.execute_legacy(all).unwrap(), expected);
+        assert_eq!(
+            Database::open_legacy(path.clone())
+                .execute_legacy(all)
+                .unwrap(),
+            expected
+        );
+
+        // The same row: both modes refuse the commit.
+        tx_db.execute_legacy("begin").unwrap();
+        tx_db
+            .execute_legacy("delete from accounts where id = 2")
+            .unwrap();
+        other_db
+            .execute_legacy("update accounts set balance = 260 where id = 2")
+            .unwrap();
+        let err = tx_db.execute_legacy("commit").unwrap_err();
+        assert!(
+            err.starts_with("Transaction conflict on table 'accounts'"),
+            "{err}"
+        );
+        assert_eq!(
+            tx_db
+                .execute_legacy("select balance from accounts where id = 2")
+                .unwrap(),
+            "balance\n260"
+        );
+        let _ = std::fs::remove_dir_all(&path);
+    }
+}
+
+#[test]
+fn test_row_conflict_detection_commits_what_wal_replay_would_apply() {
+    let (mut tx_db, mut other_db, path) =
+        conflict_mode_handles("row_conflict_rerun", ConflictDetection::Row);
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db
+        .execute_legacy("update accounts set balance = 0 where balance >= 200")
+        .unwrap();
+    other_db
+        .execute_legacy("insert into accounts values (3, 300)")
+        .unwrap();
+    assert_eq!(
+        tx_db.execute_legacy("commit").unwrap(),
+        "transaction committed"
+    );
+
+    // The logged UPDATE also matches the row inserted outside the
+    // transaction, so the commit applies it there too.
+    let expected = "id\tbalance\n1\t100\n2\t0\n3\t0";
+    let all = "select * from accounts order by id asc";
+    assert_eq!(tx_db.execute_legacy(all).unwrap(), expected);
+    assert_eq!(other_db.execute_legacy(all).unwrap(), expected);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_row_conflict_detection_rechecks_keys_after_merge() {
+    let (mut tx_db, mut other_db, path) =
+        conflict_mode_handles("row_conflict_keys", ConflictDetection::Row);
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db
+        .execute_legacy("insert into accounts values (3, 300)")
+        .unwrap();
+    other_db
+        .execute_legacy("insert into accounts values (3, 333)")
+        .unwrap();
+    let err = tx_db.execute_legacy("commit").unwrap_err();
+    assert_eq!(
+        err,
+        "Transaction conflict on table 'accounts': PRIMARY KEY constraint violation on column(s) id"
+    );
+    assert_eq!(
+        tx_db
+            .execute_legacy("select * from accounts where id = 3")
+            .unwrap(),
+        "id\tbalance\n3\t333"
+    );
+    assert!(!tx_db.has_active_transaction());
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_select_for_update_rejects_grouped_and_joined_reads() {
+    let mut db = test_db();
+    db.execute_legacy("create table t (id int, g int)").unwrap();
+    db.execute_legacy("create table u (id int)").unwrap();
+    db.execute_legacy("begin").unwrap();
+    for sql in [
+        "select count(*) from t for update",
+        "select g from t group by g for update",
+        "select distinct g from t for update",
+        "select * from t join u on t.id = u.id for update",
+    ] {
+        let err = db.execute_legacy(sql).unwrap_err();
+        assert!(err.contains("FOR UPDATE is not supported"), "{sql}: {err}");
+    }
+    // Outside that, it reads like a plain select, in or out of a transaction.
+    assert_eq!(
+        db.execute_legacy("select * from t where id = 1 for update")
+            .unwrap(),
+        "id\tg"
+    );
+    db.execute_legacy("commit").unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from t for update").unwrap(),
+        "id\tg"
+    );
+}
+
+#[test]
+fn test_subquery_tables_join_the_commit_conflict_check() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_tx_subquery_reads_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut setup = Database::open_legacy(path.clone());
+        setup
+            .execute_legacy("create table users (id int primary key, banned bool)")
+            .unwrap();
+        setup
+            .execute_legacy("create table bans (user_id int)")
+            .unwrap();
+        setup
+            .execute_legacy("insert into users values (1, false)")
+            .unwrap();
+        setup
+            .execute_legacy("insert into users values (2, false)")
+            .unwrap();
+    }
+
+    let mut tx_db = Database::open_legacy(path.clone());
+    let mut other_db = Database::open_legacy(path.clone());
+
+    // WAL replay would run the subquery against bans as changed by the
+    // other handle, so the commit must not go through.
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db
+        .execute_legacy("update users set banned = true where id in (select user_id from bans)")
+        .unwrap();
+    other_db
+        .execute_legacy("insert into bans values (2)")
+        .unwrap();
+    let err = tx_db.execute_legacy("commit").unwrap_err();
+    assert_eq!(
+        err,
+        "Transaction conflict on table 'bans': a table read by a subquery changed outside this transaction"
+    );
+
+    // Without an outside change the same statement commits.
+    tx_db.execute_legacy("begin").unwrap();
+    tx_db
+        .execute_legacy("update users set banned = true where id in (select user_id from bans)")
+        .unwrap();
+    assert_eq!(
+        tx_db.execute_legacy("commit").unwrap(),
+        "transaction committed"
+    );
+    assert_eq!(
+        tx_db
+            .execute_legacy("select id, banned from users order by id asc")
+            .unwrap(),
+        "id\tbanned\n1\tfalse\n2\ttrue"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+fn seed_audited_users(db: &mut Database) {
+    db.execute("create table users (id int primary key, name text)")
+        .unwrap();
+    db.execute("create table audit (user_id int unique, note text)")
+        .unwrap();
+    db.execute(
+        r#"create trigger log_ins after insert on users begin insert into audit values (new.id, "created") end"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_after_insert_trigger_inserts_into_target_table() {
+    let mut db = test_db();
+    seed_audited_users(&mut db);
+
+    assert_eq!(
+        db.execute_legacy(r#"insert into users values (1, "a")"#)
+            .unwrap(),
+        "inserted 1 row into users"
+    );
+    db.execute(r#"insert into users values (2, "b")"#).unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from audit order by user_id")
+            .unwrap(),
+        "user_id\tnote\n1\tcreated\n2\tcreated"
+    );
+
+    db.execute("drop trigger log_ins").unwrap();
+    db.execute(r#"insert into users values (3, "c")"#).unwrap();
+    assert_eq!(
+        db.execute_legacy("select count(*) from audit").unwrap(),
+        "count(*)\n2"
+    );
+}
+
+#[test]
+fn test_failing_trigger_undoes_the_insert() {
+    let mut db = test_db();
+    seed_audited_users(&mut db);
+    db.execute(r#"insert into audit values (1, "taken")"#)
+        .unwrap();
+
+    let err = db
+        .execute_legacy(r#"insert into users values (1, "a")"#)
+        .unwrap_err();
+    assert!(err.contains("Trigger 'log_ins' failed"), "{err}");
+    assert!(err.contains("UNIQUE"), "{err}");
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname"
+    );
+    // The undone row's key is free again.
+    db.execute("delete from audit where user_id = 1").unwrap();
+    db.execute(r#"insert into users values (1, "a")"#).unwrap();
+
+    // A trigger that keeps firing itself is stopped and undone whole.
+    db.execute("create table log (n int)").unwrap();
+    db.execute("create trigger again after insert on log begin insert into log values (new.n) end")
+        .unwrap();
+    let err = db.execute_legacy("insert into log values (1)").unwrap_err();
+    assert!(err.contains("nest more than"), "{err}");
+    assert_eq!(db.execute_legacy("select * from log").unwrap(), "n");
+}
+
+#[test]
+fn test_failing_trigger_undoes_its_sibling_triggers_writes() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_trigger_siblings_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table t (id int)").unwrap();
+        db.execute("create table a (id int)").unwrap();
+        db.execute("create table b (id int unique)").unwrap();
+        db.execute("create trigger to_a after insert on t begin insert into a values (new.id) end")
+            .unwrap();
+        db.execute("create trigger to_b after insert on t begin insert into b values (new.id) end")
+            .unwrap();
+        db.execute("insert into b values (1)").unwrap();
+
+        let err = db.execute_legacy("insert into t values (1)").unwrap_err();
+        assert!(err.contains("Trigger 'to_b' failed"), "{err}");
+        assert_eq!(db.execute_legacy("select * from a").unwrap(), "id");
+
+        db.execute("begin").unwrap();
+        db.execute("insert into t values (2)").unwrap();
+        let err = db.execute_legacy("insert into t values (1)").unwrap_err();
+        assert!(err.contains("Trigger 'to_b' failed"), "{err}");
+        db.execute("commit").unwrap();
+        assert_eq!(db.execute_legacy("select * from a").unwrap(), "id\n2");
+        assert_eq!(db.execute_legacy("select * from t").unwrap(), "id\n2");
+    }
+
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(db.execute_legacy("select * from a").unwrap(), "id\n2");
+    assert_eq!(
+        db.execute_legacy("select * from b order by id").unwrap(),
+        "id\n1\n2"
+    );
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_trigger_writes_follow_the_transaction() {
+    let mut db = test_db();
+    seed_audited_users(&mut db);
+
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (1, "a")"#).unwrap();
+    assert_eq!(
+        db.execute_legacy("select user_id from audit").unwrap(),
+        "user_id\n1"
+    );
+    let err = db.execute_legacy("drop trigger log_ins").unwrap_err();
+    assert!(err.contains("auto-commit"), "{err}");
+    db.execute("rollback").unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from audit").unwrap(),
+        "user_id\tnote"
+    );
+
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (2, "b")"#).unwrap();
+    db.execute("commit").unwrap();
+    assert_eq!(
+        db.execute_legacy("select user_id from audit").unwrap(),
+        "user_id\n2"
+    );
+}
+
+#[test]
+fn test_create_trigger_validates_tables_columns_and_names() {
+    let mut db = test_db();
+    seed_audited_users(&mut db);
+
+    for (sql, expected) in [
+        (
+            "create trigger t after insert on missing begin insert into audit values (1) end",
+            "does not exist",
+        ),
+        (
+            "create trigger t after insert on users begin insert into missing values (1) end",
+            "does not exist",
+        ),
+        (
+            "create trigger t after insert on users begin insert into audit values (new.age) end",
+            "Unknown column 'new.age'",
+        ),
+        (
+            "create trigger t after insert on users begin insert into audit values (1, 2, 3) end",
+            "has 2 column(s)",
+        ),
+        (
+            "create trigger log_ins after insert on users begin insert into audit values (1) end",
+            "already exists",
+        ),
+    ] {
+        let err = db.execute_legacy(sql).unwrap_err();
+        assert!(err.contains(expected), "{sql}: {err}");
+    }
+    let err = db.execute_legacy("drop trigger missing").unwrap_err();
+    assert!(err.contains("does not exist"), "{err}");
+}
@@ -0,0 +1,281 @@
+use super::*;
+
+#[test]
+fn test_filtered_view_reads_matching_rows() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+
+    assert_schema_change_result(
+        db.execute("create view adults as select * from users where age >= 18")
+            .unwrap(),
+        "created view adults",
+    );
+    assert_eq!(
+        db.execute_legacy("select name from adults order by name")
+            .unwrap(),
+        "name\na\nb"
+    );
+
+    // The view is read afresh each time.
+    db.execute(r#"insert into users values (4, "d", 50)"#)
+        .unwrap();
+    db.execute("update users set age = 5 where id = 1").unwrap();
+    assert_eq!(
+        db.execute_legacy("select name from adults order by name")
+            .unwrap(),
+        "name\nb\nd"
+    );
+
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (5, "e", 60)"#)
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select count(*) from adults").unwrap(),
+        "count(*)\n3"
+    );
+    db.execute("rollback").unwrap();
+}
+
+#[test]
+fn test_projected_view_exposes_only_its_columns() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("create view names as select id, name from users")
+        .unwrap();
+
+    assert_select_result(
+        db.execute("select * from names order by id").unwrap(),
+        &["id", "name"],
+        vec![
+            vec![Value::Int(1), Value::Text("a".to_string())],
+            vec![Value::Int(2), Value::Text("b".to_string())],
+            vec![Value::Int(3), Value::Text("c".to_string())],
+        ],
+    );
+    let err = db.execute_legacy("select age from names").unwrap_err();
+    assert!(err.contains("age"), "{err}");
+    let describe = db.execute_legacy("describe names").unwrap();
+    assert!(describe.contains("\nid\tint\t"), "{describe}");
+    assert!(describe.contains("\nname\ttext\t"), "{describe}");
+    assert!(!describe.contains("age"), "{describe}");
+}
+
+#[test]
+fn test_view_queried_with_extra_where_and_nested_view() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("create view adults as select * from users where age >= 18")
+        .unwrap();
+
+    assert_eq!(
+        db.execute_legacy("select id from adults where age < 25")
+            .unwrap(),
+        "id\n2"
+    );
+    assert_eq!(
+        db.execute_legacy("select count(*) from adults").unwrap(),
+        "count(*)\n2"
+    );
+
+    db.execute("create view young_adults as select name from adults where age < 25")
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from young_adults").unwrap(),
+        "name\nb"
+    );
+}
+
+#[test]
+fn test_writes_to_views_are_rejected() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("create view adults as select * from users where age >= 18")
+        .unwrap();
+
+    for sql in [
+        r#"insert into adults values (9, "z", 40)"#,
+        "update adults set age = 1 where id = 1",
+        "delete from adults where id = 1",
+        "select * from adults for update",
+    ] {
+        let err = db.execute_legacy(sql).unwrap_err();
+        assert!(err.contains("Cannot modify view 'adults'"), "{sql}: {err}");
+    }
+    let err = db
+        .execute_legacy("create table adults (id int)")
+        .unwrap_err();
+    assert!(err.contains("View 'adults' already exists"), "{err}");
+    let err = db
+        .execute_legacy("create view adults as select * from users")
+        .unwrap_err();
+    assert!(err.contains("already exists"), "{err}");
+    let err = db
+        .execute_legacy("create view bad as select * from missing")
+        .unwrap_err();
+    assert!(err.contains("missing"), "{err}");
+
+    db.execute("begin").unwrap();
+    let err = db.execute_legacy("drop view adults").unwrap_err();
+    assert!(err.contains("auto-commit"), "{err}");
+    db.execute("rollback").unwrap();
+
+    assert_eq!(
+        db.execute_legacy("select count(*) from users").unwrap(),
+        "count(*)\n3"
+    );
+    db.execute("drop view adults").unwrap();
+    let err = db.execute_legacy("select * from adults").unwrap_err();
+    assert!(err.contains("does not exist"), "{err}");
+}
+
+#[test]
+fn test_views_persist_and_show_in_show_tables() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_views_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        seed_users_3(&mut db);
+        db.execute("create view adults as select name from users where age >= 18")
+            .unwrap();
+    }
+    {
+        let mut db = Database::open_legacy(path.clone());
+        assert_eq!(
+            db.execute_legacy("select * from adults order by name")
+                .unwrap(),
+            "name\na\nb"
+        );
+        assert_select_result(
+            db.execute("show tables").unwrap(),
+            &["name", "type"],
+            vec![
+                vec![
+                    Value::Text("adults".to_string()),
+                    Value::Text("view".to_string()),
+                ],
+                vec![
+                    Value::Text("users".to_string()),
+                    Value::Text("table".to_string()),
+                ],
+            ],
+        );
+        assert_eq!(db.table_names(), vec!["users".to_string()]);
+    }
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_materialized_view_changes_only_on_refresh() {
+    let mut db = test_db();
+    seed_users_3(&mut db);
+    db.execute("create view live_adults as select name from users where age >= 18")
+        .unwrap();
+    assert_schema_change_result(
+        db.execute("create materialized view adults as select name from users where age >= 18")
+            .unwrap(),
+        "created materialized view adults",
+    );
+    assert_eq!(
+        db.execute_legacy("select * from adults order by name")
+            .unwrap(),
+        "name\na\nb"
+    );
+
+    db.execute(r#"insert into users values (4, "d", 50)"#)
+        .unwrap();
+    db.execute("delete from users where id = 1").unwrap();
+    // The plain view follows the base table; the materialized one does not.
+    assert_eq!(
+        db.execute_legacy("select * from live_adults order by name")
+            .unwrap(),
+        "name\nb\nd"
+    );
+    assert_eq!(
+        db.execute_legacy("select * from adults order by name")
+            .unwrap(),
+        "name\na\nb"
+    );
+
+    assert_schema_change_result(
+        db.execute("refresh materialized view adults").unwrap(),
+        "refreshed materialized view adults (2 row(s))",
+    );
+    assert_eq!(
+        db.execute_legacy("select * from adults order by name")
+            .unwrap(),
+        "name\nb\nd"
+    );
+
+    let err = db
+        .execute_legacy("refresh materialized view live_adults")
+        .unwrap_err();
+    assert!(
+        err.contains("Materialized view 'live_adults' does not exist"),
+        "{err}"
+    );
+    db.execute("begin").unwrap();
+    let err = db
+        .execute_legacy("refresh materialized view adults")
+        .unwrap_err();
+    assert!(err.contains("auto-commit"), "{err}");
+    db.execute("rollback").unwrap();
+}
+
+#[test]
+fn test_materialized_view_is_read_only_and_persists() {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(format!("skepa_db_matviews_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        seed_users_3(&mut db);
+        db.execute("create materialized view totals as select count(*), sum(age) from users")
+            .unwrap();
+        for sql in [
+            "insert into totals values (1, 2)",
+            "delete from totals where n = 1",
+            "create index on totals (n)",
+        ] {
+            let err = db.execute_legacy(sql).unwrap_err();
+            assert!(err.contains("Cannot modify view 'totals'"), "{sql}: {err}");
+        }
+        db.execute(r#"insert into users values (4, "d", 40)"#)
+            .unwrap();
+    }
+    {
+        let mut db = Database::open_legacy(path.clone());
+        assert_eq!(
+            db.execute_legacy("select * from totals").unwrap(),
+            "count(*)\tsum(age)\n3\t60"
+        );
+        assert_select_result(
+            db.execute("show tables").unwrap(),
+            &["name", "type"],
+            vec![
+                vec![
+                    Value::Text("totals".to_string()),
+                    Value::Text("materialized view".to_string()),
+                ],
+                vec![
+                    Value::Text("users".to_string()),
+                    Value::Text("table".to_string()),
+                ],
+            ],
+        );
+        db.execute("refresh materialized view totals").unwrap();
+        assert_eq!(
+            db.execute_legacy("select * from totals").unwrap(),
+            "count(*)\tsum(age)\n4\t100"
+        );
+
+        db.execute("drop materialized view totals").unwrap();
+        assert!(!path.join("tables").join("totals.rows").exists());
+        let err = db.execute_legacy("select * from totals").unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+        db.execute("create table totals (n int)").unwrap();
+    }
+    let _ = std::fs::remove_dir_all(&path);
+}
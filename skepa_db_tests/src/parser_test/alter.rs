@@ -174,3 +174,66 @@ fn parse_alter_column_bad_shape_shows_supported_forms() {
     assert!(err.to_lowercase().contains("set not null"));
     assert!(err.to_lowercase().contains("drop not null"));
 }
+
+#[test]
+fn parse_alter_add_column_with_placement() {
+    use skepa_db_core::parser::command::{AlterAction, ColumnPosition};
+    let position = |sql: &str| match parse(sql).unwrap() {
+        Command::Alter {
+            action: AlterAction::AddColumn { column, position },
+            ..
+        } => (column, position),
+        _ => panic!("Expected add column action"),
+    };
+    let (column, pos) = position("alter table t add column x int not null default 0 after id");
+    assert_eq!(column.name, "x");
+    assert!(column.not_null);
+    assert_eq!(column.default.as_deref(), Some("0"));
+    assert_eq!(pos, ColumnPosition::After("id".to_string()));
+    let (_, pos) = position("alter table t add column x decimal(5,2) first");
+    assert_eq!(pos, ColumnPosition::First);
+    let (column, pos) = position(r#"alter table t add column x text default "first""#);
+    assert_eq!(column.default.as_deref(), Some("first"));
+    assert_eq!(pos, ColumnPosition::Last);
+}
+
+#[test]
+fn parse_alter_add_column_bad_shape_shows_usage() {
+    for sql in [
+        "alter table t add column x",
+        "alter table t add column x int after",
+        "alter table t add column x int last",
+    ] {
+        let err = parse(sql).unwrap_err();
+        assert!(
+            err.to_lowercase().contains("add column") || err.contains("Unknown column constraint"),
+            "{sql}: {err}"
+        );
+    }
+}
+
+#[test]
+fn parse_alter_add_unique_nulls_not_distinct() {
+    use skepa_db_core::parser::command::AlterAction;
+    match parse("alter table t add unique(a, b) nulls not distinct").unwrap() {
+        Command::Alter {
+            action:
+                AlterAction::AddUnique {
+                    columns,
+                    nulls_not_distinct,
+                },
+            ..
+        } => {
+            assert_eq!(columns, vec!["a".to_string(), "b".to_string()]);
+            assert!(nulls_not_distinct);
+        }
+        _ => panic!("Expected add unique action"),
+    }
+    let err = parse("alter table t add unique(a) nulls").unwrap_err();
+    assert!(err.contains("nulls not distinct"), "{err}");
+    let err = parse("alter table t add column x int unique nulls not distinct").unwrap_err();
+    assert!(
+        err.starts_with("ALTER TABLE ADD COLUMN does not support NULLS NOT DISTINCT"),
+        "{err}"
+    );
+}
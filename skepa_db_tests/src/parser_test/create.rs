@@ -17,6 +17,25 @@ fn parse_create_basic() {
     }
 }
 
+#[test]
+fn parse_create_table_as_select() {
+    match parse("create table recent as select * from orders where total > 5;").unwrap() {
+        Command::CreateTableAs { table, query } => {
+            assert_eq!(table, "recent");
+            assert!(matches!(*query, Command::Select { ref table, .. } if table == "orders"));
+        }
+        _ => panic!("Expected CreateTableAs command"),
+    }
+    assert_eq!(
+        parse("create table t as select * from orders for update").unwrap_err(),
+        "CREATE TABLE ... AS cannot select FOR UPDATE"
+    );
+    assert_eq!(
+        parse("create table t as orders").unwrap_err(),
+        "Usage: create table <table> as select ..."
+    );
+}
+
 #[test]
 fn create_rejects_unknown_datatype() {
     let err = parse("create table users (id integer, name text)").unwrap_err();
@@ -301,6 +320,46 @@ fn parse_create_table_constraint_unique_single_col() {
     }
 }
 
+#[test]
+fn parse_create_unique_nulls_not_distinct() {
+    use skepa_db_core::parser::command::TableConstraintDef;
+    let constraints = |sql: &str| match parse(sql).unwrap() {
+        Command::Create {
+            table_constraints, ..
+        } => table_constraints,
+        _ => panic!("Expected Create command"),
+    };
+    let unique = |cols: &[&str], nulls_not_distinct| TableConstraintDef::Unique {
+        columns: cols.iter().map(|c| c.to_string()).collect(),
+        nulls_not_distinct,
+    };
+    assert_eq!(
+        constraints("create table t (a int, b int, unique(a, b) nulls not distinct)"),
+        vec![unique(&["a", "b"], true)]
+    );
+    assert_eq!(
+        constraints("create table t (a int, unique(a) nulls distinct)"),
+        vec![unique(&["a"], false)]
+    );
+    // The column-level form becomes the one-column table constraint.
+    match parse("create table t (email text unique nulls not distinct not null, n int)").unwrap() {
+        Command::Create {
+            columns,
+            table_constraints,
+            ..
+        } => {
+            assert!(columns[0].unique && columns[0].not_null);
+            assert_eq!(table_constraints, vec![unique(&["email"], true)]);
+        }
+        _ => panic!("Expected Create command"),
+    }
+    let err = parse("create table t (a int, unique(a) nulls not)").unwrap_err();
+    assert_eq!(
+        err,
+        "Bad UNIQUE modifier. Use 'nulls distinct' or 'nulls not distinct'"
+    );
+}
+
 #[test]
 fn parse_create_with_foreign_key_constraint() {
     let cmd = parse(
@@ -593,12 +652,91 @@ fn parse_create_rejects_unknown_table_constraint_token() {
 fn parse_create_index_basic() {
     let cmd = parse("create index on users (email)").unwrap();
     match cmd {
-        Command::CreateIndex { table, columns } => {
+        Command::CreateIndex {
+            table,
+            columns,
+            if_not_exists,
+            kind,
+            name,
+        } => {
+            assert!(!if_not_exists);
+            assert_eq!(kind, IndexKind::Standard);
+            assert_eq!(name, None);
+            assert_eq!(table, "users");
+            assert_eq!(columns, vec!["email"]);
+        }
+        _ => panic!("Expected CreateIndex command"),
+    }
+}
+
+#[test]
+fn parse_create_and_drop_trigram_index() {
+    match parse("create index if not exists on logs (body) using trigram").unwrap() {
+        Command::CreateIndex {
+            table,
+            columns,
+            if_not_exists,
+            kind,
+            ..
+        } => {
+            assert!(if_not_exists);
+            assert_eq!(kind, IndexKind::Trigram);
+            assert_eq!(table, "logs");
+            assert_eq!(columns, vec!["body"]);
+        }
+        _ => panic!("Expected CreateIndex command"),
+    }
+    match parse("drop index on logs (body) using trigram").unwrap() {
+        Command::DropIndex { kind, .. } => assert_eq!(kind, IndexKind::Trigram),
+        _ => panic!("Expected DropIndex command"),
+    }
+}
+
+#[test]
+fn parse_create_and_drop_named_index() {
+    match parse("create index if not exists idx_email on users (email)").unwrap() {
+        Command::CreateIndex {
+            table,
+            columns,
+            if_not_exists,
+            name,
+            ..
+        } => {
+            assert!(if_not_exists);
+            assert_eq!(name.as_deref(), Some("idx_email"));
             assert_eq!(table, "users");
             assert_eq!(columns, vec!["email"]);
         }
         _ => panic!("Expected CreateIndex command"),
     }
+    match parse("create index `order` on logs (body) using trigram").unwrap() {
+        Command::CreateIndex { name, kind, .. } => {
+            assert_eq!(name.as_deref(), Some("order"));
+            assert_eq!(kind, IndexKind::Trigram);
+        }
+        _ => panic!("Expected CreateIndex command"),
+    }
+    match parse("drop index idx_email").unwrap() {
+        Command::DropIndex {
+            table,
+            columns,
+            name,
+            ..
+        } => {
+            assert_eq!(name.as_deref(), Some("idx_email"));
+            assert!(table.is_empty() && columns.is_empty());
+        }
+        _ => panic!("Expected DropIndex command"),
+    }
+    let err = parse("create index idx_email users (email)").unwrap_err();
+    assert!(err.contains("create index <name> on <table>"), "{err}");
+    assert!(parse("drop index idx_email on users").is_err());
+}
+
+#[test]
+fn parse_create_index_unknown_method_errors() {
+    let err = parse("create index on logs (body) using hash").unwrap_err();
+    assert!(err.contains("Unknown index method 'hash'"));
 }
 
 #[test]
@@ -614,3 +752,264 @@ fn parse_create_unknown_table_constraint_lists_supported_forms() {
     assert!(e.contains("bad foreign key constraint"));
     assert!(e.contains("references"));
 }
+
+#[test]
+fn parse_create_default_gen_uuid_call() {
+    let cmd = parse("create table t (id uuid default gen_uuid(), name text)").unwrap();
+    match cmd {
+        Command::Create { columns, .. } => {
+            assert_eq!(columns[0].default.as_deref(), Some("gen_uuid()"));
+            assert_eq!(columns[1].name, "name");
+        }
+        _ => panic!("Expected Create command"),
+    }
+}
+
+#[test]
+fn parse_create_keyword_column_names() {
+    let cmd = parse(
+        "create table t (order int, limit int, group text, unique int, primary int, key int)",
+    )
+    .unwrap();
+    match cmd {
+        Command::Create {
+            columns,
+            table_constraints,
+            ..
+        } => {
+            let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(
+                names,
+                vec!["order", "limit", "group", "unique", "primary", "key"]
+            );
+            assert!(table_constraints.is_empty());
+        }
+        _ => panic!("Expected Create command"),
+    }
+}
+
+#[test]
+fn parse_create_keyword_column_alongside_table_constraints() {
+    let cmd =
+        parse("create table t (primary int, unique int, primary key (primary), unique (unique))")
+            .unwrap();
+    match cmd {
+        Command::Create {
+            columns,
+            table_constraints,
+            ..
+        } => {
+            assert_eq!(columns.len(), 2);
+            assert_eq!(table_constraints.len(), 2);
+        }
+        _ => panic!("Expected Create command"),
+    }
+}
+
+#[test]
+fn parse_create_json_shape_checks() {
+    let cmd = parse(
+        "create table t (a json check_json_object not null, b json check_json_array, c json)",
+    )
+    .unwrap();
+    match cmd {
+        Command::Create { columns, .. } => {
+            assert_eq!(columns[0].json_shape, Some(JsonShape::Object));
+            assert!(columns[0].not_null);
+            assert_eq!(columns[1].json_shape, Some(JsonShape::Array));
+            assert_eq!(columns[2].json_shape, None);
+        }
+        _ => panic!("Expected Create command"),
+    }
+    let err = parse("create table t (a json check_json_object check_json_array)").unwrap_err();
+    assert!(err.contains("more than once"));
+}
+
+#[test]
+fn parse_create_if_not_exists_sets_flag() {
+    match parse("create table if not exists users (id int)").unwrap() {
+        Command::Create {
+            table,
+            columns,
+            if_not_exists,
+            ..
+        } => {
+            assert_eq!(table, "users");
+            assert_eq!(columns.len(), 1);
+            assert!(if_not_exists);
+        }
+        _ => panic!("Expected Create command"),
+    }
+    match parse("create table users (id int)").unwrap() {
+        Command::Create { if_not_exists, .. } => assert!(!if_not_exists),
+        _ => panic!("Expected Create command"),
+    }
+    match parse("CREATE INDEX IF NOT EXISTS ON users (email, city)").unwrap() {
+        Command::CreateIndex {
+            table,
+            columns,
+            if_not_exists,
+            ..
+        } => {
+            assert_eq!(table, "users");
+            assert_eq!(columns, vec!["email", "city"]);
+            assert!(if_not_exists);
+        }
+        _ => panic!("Expected CreateIndex command"),
+    }
+    assert!(parse("create table if not users (id int)").is_err());
+}
+
+#[test]
+fn parse_create_partition_by() {
+    let cmd = parse("create table events (id int, day date) partition by (day)").unwrap();
+    match cmd {
+        Command::Create {
+            columns,
+            partition_by,
+            ..
+        } => {
+            assert_eq!(columns.len(), 2);
+            assert_eq!(partition_by.as_deref(), Some("day"));
+        }
+        _ => panic!("Expected Create command"),
+    }
+    match parse("create table t (partition int)").unwrap() {
+        Command::Create { partition_by, .. } => assert_eq!(partition_by, None),
+        _ => panic!("Expected Create command"),
+    }
+    let err = parse("create table t (a int, b int) partition by (a, b)").unwrap_err();
+    assert!(err.contains("exactly one column"), "{err}");
+}
+
+#[test]
+fn parse_create_and_drop_trigger() {
+    let cmd = parse(
+        r#"create trigger log_ins after insert on users begin insert into audit values (new.id, "new.name", 1) end"#,
+    )
+    .unwrap();
+    match cmd {
+        Command::CreateTrigger {
+            name,
+            table,
+            target,
+            values,
+        } => {
+            assert_eq!(name, "log_ins");
+            assert_eq!(table, "users");
+            assert_eq!(target, "audit");
+            assert_eq!(
+                values,
+                vec![
+                    TriggerValue::New("id".to_string()),
+                    TriggerValue::Literal("new.name".to_string()),
+                    TriggerValue::Literal("1".to_string()),
+                ]
+            );
+        }
+        _ => panic!("Expected CreateTrigger command"),
+    }
+    match parse("drop trigger log_ins").unwrap() {
+        Command::DropTrigger { name } => assert_eq!(name, "log_ins"),
+        _ => panic!("Expected DropTrigger command"),
+    }
+
+    for (sql, expected) in [
+        (
+            "create trigger t before insert on users begin insert into audit values (1) end",
+            "Only AFTER INSERT",
+        ),
+        (
+            "create trigger t after delete on users begin insert into audit values (1) end",
+            "Only AFTER INSERT",
+        ),
+        (
+            "create trigger t after insert on users begin delete from audit where id = 1 end",
+            "single INSERT",
+        ),
+        (
+            "create trigger t after insert on users begin insert into audit values (old.id) end",
+            "OLD",
+        ),
+        (
+            "create trigger t after insert on users insert into audit values (1)",
+            "Usage: create trigger",
+        ),
+        ("drop trigger", "Usage: drop trigger"),
+    ] {
+        let err = parse(sql).unwrap_err();
+        assert!(err.contains(expected), "{sql}: {err}");
+    }
+}
+
+#[test]
+fn parse_create_and_drop_view() {
+    match parse("create view adults as select name from users where age >= 18;").unwrap() {
+        Command::CreateView {
+            name,
+            materialized,
+            query,
+            sql,
+        } => {
+            assert_eq!(name, "adults");
+            assert!(!materialized);
+            assert_eq!(sql, "select name from users where age >= 18");
+            assert!(matches!(*query, Command::Select { ref table, .. } if table == "users"));
+        }
+        _ => panic!("Expected CreateView command"),
+    }
+    match parse("drop view adults").unwrap() {
+        Command::DropView { name } => assert_eq!(name, "adults"),
+        _ => panic!("Expected DropView command"),
+    }
+    assert!(matches!(
+        parse("show tables").unwrap(),
+        Command::ShowTables { with_stats: false }
+    ));
+    assert!(matches!(
+        parse("SHOW TABLES WITH STATS").unwrap(),
+        Command::ShowTables { with_stats: true }
+    ));
+    assert!(parse("show tables with").is_err());
+    match parse("create materialized view totals as select count(*) from users").unwrap() {
+        Command::CreateView {
+            name,
+            materialized,
+            sql,
+            ..
+        } => {
+            assert_eq!(name, "totals");
+            assert!(materialized);
+            assert_eq!(sql, "select count(*) from users");
+        }
+        _ => panic!("Expected CreateView command"),
+    }
+    match parse("refresh materialized view totals").unwrap() {
+        Command::RefreshMaterializedView { name } => assert_eq!(name, "totals"),
+        _ => panic!("Expected RefreshMaterializedView command"),
+    }
+    match parse("drop materialized view totals").unwrap() {
+        Command::DropView { name } => assert_eq!(name, "totals"),
+        _ => panic!("Expected DropView command"),
+    }
+
+    for (sql, expected) in [
+        (
+            "create view adults select * from users",
+            "Usage: create [materialized] view",
+        ),
+        (
+            "create view adults as users",
+            "Usage: create [materialized] view",
+        ),
+        ("drop view", "Usage: drop [materialized] view"),
+        (
+            "create materialized adults as select * from users",
+            "Usage: create [materialized] view",
+        ),
+        ("refresh view totals", "Usage: refresh materialized view"),
+    ] {
+        let err = parse(sql).unwrap_err();
+        assert!(err.contains(expected), "{sql}: {err}");
+    }
+}
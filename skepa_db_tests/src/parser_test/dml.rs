@@ -5,14 +5,54 @@ fn parse_insert_with_quotes() {
     let cmd = parse(r#"insert into users values (1, "ram kumar")"#).unwrap();
 
     match cmd {
-        Command::Insert { table, values } => {
+        Command::Insert {
+            table,
+            values,
+            returning,
+        } => {
             assert_eq!(table, "users");
             assert_eq!(values, vec!["1".to_string(), "ram kumar".to_string()]);
+            assert_eq!(returning, None);
         }
         _ => panic!("Expected Insert command"),
     }
 }
 
+#[test]
+fn parse_insert_select_with_and_without_column_list() {
+    match parse("insert into archive select * from orders where total > 5;").unwrap() {
+        Command::InsertSelect {
+            table,
+            columns,
+            query,
+        } => {
+            assert_eq!(table, "archive");
+            assert_eq!(columns, None);
+            assert!(matches!(*query, Command::Select { ref table, .. } if table == "orders"));
+        }
+        _ => panic!("Expected InsertSelect command"),
+    }
+    match parse("insert into `order` (id, `select`) select id, total from orders").unwrap() {
+        Command::InsertSelect { table, columns, .. } => {
+            assert_eq!(table, "order");
+            assert_eq!(columns, Some(vec!["id".to_string(), "select".to_string()]));
+        }
+        _ => panic!("Expected InsertSelect command"),
+    }
+    assert!(matches!(
+        parse("insert into a select id from b intersect select id from c").unwrap(),
+        Command::InsertSelect { .. }
+    ));
+    assert_eq!(
+        parse("insert into a select * from b for update").unwrap_err(),
+        "INSERT ... SELECT cannot select FOR UPDATE"
+    );
+    assert_eq!(
+        parse("insert into a () select * from b").unwrap_err(),
+        "Constraint column list cannot be empty"
+    );
+}
+
 #[test]
 fn parse_update_basic() {
     let cmd = parse(r#"update users set name = "ravi" where id = 1"#).unwrap();
@@ -22,8 +62,10 @@ fn parse_update_basic() {
             table,
             assignments,
             filter,
+            returning,
         } => {
             assert_eq!(table, "users");
+            assert_eq!(returning, None);
             assert_eq!(assignments.len(), 1);
             assert_eq!(assignments[0].column, "name");
             assert_eq!(assignments[0].value, "ravi");
@@ -47,6 +89,8 @@ fn parse_update_multiple_assignments() {
             assert_eq!(assignments[0].value, "ravi");
             assert_eq!(assignments[1].column, "age");
             assert_eq!(assignments[1].value, "30");
+            assert!(assignments[0].quoted);
+            assert!(!assignments[1].quoted);
         }
         _ => panic!("Expected Update command"),
     }
@@ -57,8 +101,13 @@ fn parse_delete_basic() {
     let cmd = parse(r#"delete from users where id = 1"#).unwrap();
 
     match cmd {
-        Command::Delete { table, filter } => {
+        Command::Delete {
+            table,
+            filter,
+            returning,
+        } => {
             assert_eq!(table, "users");
+            assert_eq!(returning, None);
             let p = pred(&filter);
             assert_eq!(p.column, "id");
             assert_eq!(p.op, CompareOp::Eq);
@@ -73,7 +122,7 @@ fn parse_insert_empty_string_value_allowed() {
     let cmd = parse(r#"insert into users values (1, "")"#).unwrap();
 
     match cmd {
-        Command::Insert { table, values } => {
+        Command::Insert { table, values, .. } => {
             assert_eq!(table, "users");
             assert_eq!(values, vec!["1".to_string(), "".to_string()]);
         }
@@ -133,7 +182,7 @@ fn insert_requires_commas_between_values() {
 fn insert_allows_no_spaces_around_commas() {
     let cmd = parse(r#"insert into users values(1,"ram")"#).unwrap();
     match cmd {
-        Command::Insert { table, values } => {
+        Command::Insert { table, values, .. } => {
             assert_eq!(table, "users");
             assert_eq!(values, vec!["1".to_string(), "ram".to_string()]);
         }
@@ -388,7 +437,7 @@ fn parse_update_with_empty_set_errors() {
 fn parse_delete_with_parenthesized_predicate() {
     let cmd = parse("delete from users where (id = 1)").unwrap();
     match cmd {
-        Command::Delete { table, filter } => {
+        Command::Delete { table, filter, .. } => {
             assert_eq!(table, "users");
             let p = pred(&filter);
             assert_eq!(p.column, "id");
@@ -407,3 +456,119 @@ fn parse_insert_handles_escaped_quote_text() {
         _ => panic!("expected insert"),
     }
 }
+
+#[test]
+fn parse_insert_folds_gen_uuid_call_into_one_value() {
+    let cmd = parse(r#"insert into t values (gen_uuid(), "ram")"#).unwrap();
+    match cmd {
+        Command::Insert { values, .. } => assert_eq!(values, vec!["gen_uuid()", "ram"]),
+        _ => panic!("Expected Insert command"),
+    }
+}
+
+#[test]
+fn parse_returning_clause_on_insert_update_and_delete() {
+    match parse(r#"insert into users values (1, "ram") returning id, name as who"#).unwrap() {
+        Command::Insert {
+            values, returning, ..
+        } => {
+            assert_eq!(values, vec!["1", "ram"]);
+            assert_eq!(
+                returning,
+                Some(vec!["id".to_string(), "name as who".to_string()])
+            );
+        }
+        _ => panic!("Expected Insert command"),
+    }
+    match parse(r#"update users set name = "x" where id = 1 returning *"#).unwrap() {
+        Command::Update {
+            filter, returning, ..
+        } => {
+            assert_eq!(pred(&filter).value, "1");
+            assert_eq!(returning, Some(Vec::new()));
+        }
+        _ => panic!("Expected Update command"),
+    }
+    match parse("delete from users where id in (1, 2) returning id").unwrap() {
+        Command::Delete { returning, .. } => assert_eq!(returning, Some(vec!["id".to_string()])),
+        _ => panic!("Expected Delete command"),
+    }
+}
+
+#[test]
+fn parse_returning_keyword_as_quoted_value_is_not_a_clause() {
+    match parse(r#"insert into notes values (1, "returning")"#).unwrap() {
+        Command::Insert {
+            values, returning, ..
+        } => {
+            assert_eq!(values, vec!["1", "returning"]);
+            assert_eq!(returning, None);
+        }
+        _ => panic!("Expected Insert command"),
+    }
+    match parse(r#"delete from notes where body = "returning" returning id"#).unwrap() {
+        Command::Delete {
+            filter, returning, ..
+        } => {
+            assert_eq!(pred(&filter).value, "returning");
+            assert_eq!(returning, Some(vec!["id".to_string()]));
+        }
+        _ => panic!("Expected Delete command"),
+    }
+}
+
+#[test]
+fn parse_returning_without_columns_errors() {
+    let err = parse("delete from users where id = 1 returning").unwrap_err();
+    assert!(err.contains("RETURNING requires"), "{err}");
+}
+
+#[test]
+fn parse_delete_and_update_with_subqueries() {
+    match parse(
+        "delete from orders where user_id in (select id from users u where u.banned = true)",
+    )
+    .unwrap()
+    {
+        Command::Delete {
+            filter: WhereClause::Exists { subquery, negated },
+            ..
+        } => {
+            assert!(!negated);
+            assert_eq!(subquery.table, "users");
+            assert_eq!(
+                subquery.correlation,
+                vec![("id".to_string(), "user_id".to_string())]
+            );
+            assert_eq!(pred(subquery.filter.as_deref().unwrap()).column, "banned");
+        }
+        other => panic!("Expected Delete with IN subquery, got {other:?}"),
+    }
+    // The target table's name qualifies its columns inside the subquery.
+    match parse(
+        "update users set age = 1 where exists (select 1 from orders o where o.user_id = users.id)",
+    )
+    .unwrap()
+    {
+        Command::Update {
+            filter: WhereClause::Exists { subquery, .. },
+            ..
+        } => assert_eq!(
+            subquery.correlation,
+            vec![("user_id".to_string(), "id".to_string())]
+        ),
+        other => panic!("Expected Update with EXISTS, got {other:?}"),
+    }
+    assert_eq!(
+        parse("delete from t where id in (select * from u)").unwrap_err(),
+        "IN subquery must select exactly one column"
+    );
+    assert_eq!(
+        parse("delete from t where id in (select count(id) from u)").unwrap_err(),
+        "IN subquery must select a plain column"
+    );
+    assert_eq!(
+        parse("delete from t where id in (select id from u").unwrap_err(),
+        "Malformed IN subquery. Missing closing ')'"
+    );
+}
@@ -54,7 +54,7 @@ fn parse_keyword_case_insensitive_commands() {
                 assert_eq!(table, "t");
                 assert_eq!(columns.len(), 1);
             }
-            ("insert", Command::Insert { table, values }) => {
+            ("insert", Command::Insert { table, values, .. }) => {
                 assert_eq!(table, "t");
                 assert_eq!(values, vec!["1"]);
             }
@@ -83,11 +83,52 @@ fn parse_describe_usage_errors() {
     assert!(err.to_lowercase().contains("usage: describe"));
 }
 
+#[test]
+fn parse_comment_on_table_and_column() {
+    match parse(r#"comment on table users is "app users""#).unwrap() {
+        Command::Comment { target, text } => {
+            assert_eq!(target, CommentTarget::Table("users".to_string()));
+            assert_eq!(text.as_deref(), Some("app users"));
+        }
+        _ => panic!("Expected Comment command"),
+    }
+    match parse("COMMENT ON COLUMN users.email IS null").unwrap() {
+        Command::Comment { target, text } => {
+            assert_eq!(
+                target,
+                CommentTarget::Column {
+                    table: "users".to_string(),
+                    column: "email".to_string()
+                }
+            );
+            assert_eq!(text, None);
+        }
+        _ => panic!("Expected Comment command"),
+    }
+}
+
+#[test]
+fn parse_comment_usage_errors() {
+    let err = parse(r#"comment on table users "x""#).unwrap_err();
+    assert!(err.to_lowercase().contains("usage: comment on"));
+    let err = parse(r#"comment on column email is "x""#).unwrap_err();
+    assert!(err.contains("Use <table>.<column>"));
+    let err = parse(r#"comment on index users is "x""#).unwrap_err();
+    assert!(err.to_lowercase().contains("usage: comment on"));
+}
+
 #[test]
 fn parse_drop_index_basic() {
     let cmd = parse("drop index on users (email)").unwrap();
     match cmd {
-        Command::DropIndex { table, columns } => {
+        Command::DropIndex {
+            table,
+            columns,
+            kind,
+            name,
+        } => {
+            assert_eq!(kind, IndexKind::Standard);
+            assert_eq!(name, None);
             assert_eq!(table, "users");
             assert_eq!(columns, vec!["email"]);
         }
@@ -104,3 +145,19 @@ fn parse_drop_index_missing_parens_errors() {
 fn parse_drop_index_rejects_extra_tokens() {
     assert!(parse("drop index on users (id) now").is_err());
 }
+
+#[test]
+fn keywords_cover_every_statement_and_stay_sorted_lowercase() {
+    use skepa_db_core::parser::parser::KEYWORDS;
+    for leader in [
+        "begin", "commit", "rollback", "show", "set", "create", "drop", "alter", "comment",
+        "insert", "update", "delete", "describe", "select", "list",
+    ] {
+        assert!(KEYWORDS.contains(&leader), "missing {leader}");
+    }
+    let mut sorted = KEYWORDS.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(sorted, KEYWORDS);
+    assert!(KEYWORDS.iter().all(|k| *k == k.to_lowercase()));
+}
@@ -1,4 +1,7 @@
-use skepa_db_core::parser::command::{Command, CompareOp, JoinType, WhereClause};
+use skepa_db_core::parser::command::{
+    Command, CommentTarget, CompareOp, IndexKind, JoinType, JsonShape, SetOpKind, TriggerValue,
+    WhereClause,
+};
 use skepa_db_core::parser::parser::parse;
 use skepa_db_core::types::datatype::DataType;
 
@@ -320,6 +320,47 @@ fn parse_select_with_limit_only() {
     }
 }
 
+#[test]
+fn parse_select_limit_all_means_no_limit() {
+    for (sql, expected_offset) in [
+        ("select * from users limit all", None),
+        (
+            "select * from users where age > 1 order by id LIMIT ALL",
+            None,
+        ),
+        ("select * from users limit all offset 5", Some(5)),
+        ("select * from users offset 5 limit all", Some(5)),
+    ] {
+        match parse(sql).unwrap() {
+            Command::Select { limit, offset, .. } => {
+                assert_eq!(limit, None, "{sql}");
+                assert_eq!(offset, expected_offset, "{sql}");
+            }
+            _ => panic!("Expected Select command"),
+        }
+    }
+
+    match parse("select * from users limit 0").unwrap() {
+        Command::Select { limit, .. } => assert_eq!(limit, Some(0)),
+        _ => panic!("Expected Select command"),
+    }
+    let err = parse("select * from users limit all limit 3").unwrap_err();
+    assert!(err.contains("LIMIT specified more than once"), "{err}");
+    let err = parse("select * from users limit -1").unwrap_err();
+    assert!(
+        err.contains("LIMIT must be a non-negative integer"),
+        "{err}"
+    );
+}
+
+#[test]
+fn parse_select_rejects_bound_parameter_in_limit_and_offset() {
+    let err = parse("select * from users limit ?").unwrap_err();
+    assert!(err.contains("LIMIT ? is not supported yet"), "{err}");
+    let err = parse("select * from users limit 5 offset ?").unwrap_err();
+    assert!(err.contains("OFFSET ? is not supported yet"), "{err}");
+}
+
 #[test]
 fn parse_select_where_is_null() {
     let cmd = parse("select * from users where city is null").unwrap();
@@ -360,14 +401,42 @@ fn parse_select_where_in_list() {
     }
 }
 
+#[test]
+fn parse_select_where_in_list_keeps_items_separate() {
+    let cmd =
+        parse("select * from t where s in (\"a,b\", \"say \\\"hi\\\"\", \"x\u{1F}y\")").unwrap();
+    match cmd {
+        Command::Select { filter, .. } => {
+            let f = filter.expect("where");
+            let pf = pred(&f);
+            assert_eq!(pf.op, CompareOp::In);
+            assert_eq!(pf.value, "");
+            assert_eq!(pf.values, vec!["a,b", "say \"hi\"", "x\u{1F}y"]);
+        }
+        _ => panic!("Expected Select command"),
+    }
+}
+
+#[test]
+fn parse_select_where_empty_in_list() {
+    let cmd = parse("select * from users where id in ()").unwrap();
+    match cmd {
+        Command::Select { filter, .. } => match filter.expect("where") {
+            WhereClause::Predicate(p) => {
+                assert_eq!(p.op, CompareOp::In);
+                assert!(p.values.is_empty());
+            }
+            _ => panic!("expected predicate"),
+        },
+        _ => panic!("Expected Select command"),
+    }
+}
+
 #[test]
 fn parse_select_where_in_invalid_syntax_errors() {
     let err = parse("select * from users where id in 1,2").unwrap_err();
     assert!(err.to_lowercase().contains("malformed in list"));
 
-    let err = parse("select * from users where id in ()").unwrap_err();
-    assert!(err.to_lowercase().contains("in list cannot be empty"));
-
     let err = parse("select * from users where id in (1,)").unwrap_err();
     assert!(err.to_lowercase().contains("trailing comma"));
 }
@@ -716,6 +785,12 @@ fn parse_select_with_aliases() {
     }
 }
 
+#[test]
+fn parse_select_rejects_dotted_alias() {
+    let err = parse("select a.id as users.id from a").unwrap_err();
+    assert!(err.contains("Aliases cannot contain '.'"));
+}
+
 #[test]
 fn parse_select_order_by_alias() {
     let cmd = parse("select city,count(*) as c from users group by city order by c desc").unwrap();
@@ -852,3 +927,293 @@ fn parse_select_having_without_group_with_order_and_limit() {
         _ => panic!("expected select"),
     }
 }
+
+#[test]
+fn parse_select_where_keyword_column_names() {
+    let cmd = parse(
+        "select order from t where order = 1 and limit > 2 or group = \"order\" order by order desc limit 3",
+    )
+    .unwrap();
+    match cmd {
+        Command::Select {
+            filter,
+            order_by,
+            limit,
+            ..
+        } => {
+            let filter = format!("{:?}", filter.unwrap());
+            assert!(filter.contains("column: \"order\""));
+            assert!(filter.contains("column: \"limit\""));
+            assert!(filter.contains("column: \"group\""));
+            assert!(filter.contains("value: \"order\""));
+            let ob = order_by.unwrap();
+            assert_eq!(ob.column, "order");
+            assert!(!ob.asc);
+            assert_eq!(limit, Some(3));
+        }
+        _ => panic!("expected select"),
+    }
+}
+
+#[test]
+fn parse_select_having_keyword_column_name() {
+    let cmd = parse("select group, count(*) from t group by group having group = 1 order by group")
+        .unwrap();
+    match cmd {
+        Command::Select {
+            group_by,
+            having,
+            order_by,
+            ..
+        } => {
+            assert_eq!(group_by.unwrap(), vec!["group".to_string()]);
+            assert!(format!("{:?}", having.unwrap()).contains("column: \"group\""));
+            assert_eq!(order_by.unwrap().column, "group");
+        }
+        _ => panic!("expected select"),
+    }
+}
+
+#[test]
+fn parse_select_group_by_function_expression() {
+    let cmd =
+        parse("select month(created), count(*) from e group by year(created), month(created)")
+            .unwrap();
+    match cmd {
+        Command::Select { group_by, .. } => {
+            assert_eq!(
+                group_by.expect("group by"),
+                vec!["year(created)", "month(created)"]
+            );
+        }
+        _ => panic!("expected select"),
+    }
+    let err = parse("select count(*) from e group by month(created").unwrap_err();
+    assert!(err.contains("Bad GROUP BY expression"));
+}
+
+#[test]
+fn parse_select_for_update_suffix() {
+    match parse("select * from t where id = 1 order by id limit 1 FOR UPDATE").unwrap() {
+        Command::Select {
+            for_update, limit, ..
+        } => {
+            assert!(for_update);
+            assert_eq!(limit, Some(1));
+        }
+        _ => panic!("Expected Select command"),
+    }
+    match parse("select * from t").unwrap() {
+        Command::Select { for_update, .. } => assert!(!for_update),
+        _ => panic!("Expected Select command"),
+    }
+    assert!(parse("select * from t for").is_err());
+    assert!(parse("select * from t for update where id = 1").is_err());
+}
+
+#[test]
+fn parse_select_as_of_snapshot() {
+    match parse(r#"select * from users as of "before_migration" where id = 1 limit 2"#).unwrap() {
+        Command::Select {
+            table,
+            as_of,
+            filter,
+            limit,
+            ..
+        } => {
+            assert_eq!(table, "users");
+            assert_eq!(as_of.as_deref(), Some("before_migration"));
+            assert_eq!(pred(filter.as_ref().unwrap()).column, "id");
+            assert_eq!(limit, Some(2));
+        }
+        _ => panic!("Expected Select command"),
+    }
+    match parse("select * from users").unwrap() {
+        Command::Select { as_of, .. } => assert_eq!(as_of, None),
+        _ => panic!("Expected Select command"),
+    }
+    let err = parse("select * from users as of").unwrap_err();
+    assert!(err.contains("Bad AS OF syntax"), "{err}");
+    for write in [
+        "update users as of s set a = 1",
+        "delete from users as of s where id = 1",
+        "insert into users as of s values (1)",
+    ] {
+        let err = parse(write).unwrap_err();
+        assert_eq!(
+            err,
+            "Snapshots are read-only: AS OF is only allowed in SELECT"
+        );
+    }
+}
+
+#[test]
+fn parse_intersect_and_except_group_left() {
+    let cmd = parse(
+        r#"select id from a where name = "except" intersect all select id from b except select id from c order by id"#,
+    )
+    .unwrap();
+    let Command::SetOp {
+        kind: SetOpKind::Except,
+        left,
+        right,
+        all: false,
+    } = cmd
+    else {
+        panic!("Expected EXCEPT, got {cmd:?}");
+    };
+    match *right {
+        Command::Select {
+            table, order_by, ..
+        } => {
+            assert_eq!(table, "c");
+            assert_eq!(order_by.unwrap().column, "id");
+        }
+        other => panic!("Expected Select, got {other:?}"),
+    }
+    match *left {
+        Command::SetOp {
+            kind: SetOpKind::Intersect,
+            left,
+            right,
+            all: true,
+        } => {
+            assert!(matches!(*left, Command::Select { ref table, .. } if table == "a"));
+            assert!(matches!(*right, Command::Select { ref table, .. } if table == "b"));
+        }
+        other => panic!("Expected INTERSECT ALL, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_set_op_errors() {
+    for sql in [
+        "select id from a intersect",
+        "select id from a except all",
+        "select id from a intersect from b",
+    ] {
+        let err = parse(sql).unwrap_err();
+        assert!(
+            err.starts_with("Usage: select ... intersect"),
+            "{sql}: {err}"
+        );
+    }
+    assert_eq!(
+        parse("select id from a except select id from b for update").unwrap_err(),
+        "FOR UPDATE and AS OF cannot be used with INTERSECT or EXCEPT"
+    );
+    assert_eq!(
+        parse("select id from a as of s1 intersect select id from b").unwrap_err(),
+        "FOR UPDATE and AS OF cannot be used with INTERSECT or EXCEPT"
+    );
+}
+
+#[test]
+fn parse_select_table_alias_strips_qualifier() {
+    match parse("select u.name, count(u.id) as n from users u where u.age > 1 group by u.name order by u.name").unwrap() {
+        Command::Select {
+            table,
+            columns,
+            filter,
+            group_by,
+            order_by,
+            ..
+        } => {
+            assert_eq!(table, "users");
+            assert_eq!(
+                columns,
+                Some(vec!["name".to_string(), "count(id) as n".to_string()])
+            );
+            assert_eq!(pred(filter.as_ref().unwrap()).column, "age");
+            assert_eq!(group_by, Some(vec!["name".to_string()]));
+            assert_eq!(order_by.unwrap().column, "name");
+        }
+        other => panic!("Expected Select, got {other:?}"),
+    }
+    assert_eq!(
+        parse("select * from users u join orders on users.id = orders.user_id").unwrap_err(),
+        "Table aliases are not supported with JOIN"
+    );
+}
+
+#[test]
+fn parse_exists_subquery_binds_correlation() {
+    let cmd = parse(
+        "select * from users u where u.age > 1 and not exists (select 1 from orders o where o.total > 5 and u.id = o.user_id)",
+    )
+    .unwrap();
+    let Command::Select {
+        filter: Some(WhereClause::Binary { left, right, .. }),
+        ..
+    } = cmd
+    else {
+        panic!("Expected Select with AND filter");
+    };
+    assert_eq!(pred(&left).column, "age");
+    match *right {
+        WhereClause::Exists { subquery, negated } => {
+            assert!(negated);
+            assert_eq!(subquery.table, "orders");
+            assert_eq!(subquery.alias.as_deref(), Some("o"));
+            assert_eq!(
+                subquery.correlation,
+                vec![("user_id".to_string(), "id".to_string())]
+            );
+            assert_eq!(pred(subquery.filter.as_deref().unwrap()).column, "total");
+        }
+        other => panic!("Expected EXISTS, got {other:?}"),
+    }
+
+    // Without an alias the outer table name qualifies outer columns.
+    match parse("select * from users where exists (select * from orders where user_id = users.id)")
+        .unwrap()
+    {
+        Command::Select {
+            filter: Some(WhereClause::Exists { subquery, negated }),
+            ..
+        } => {
+            assert!(!negated);
+            assert_eq!(subquery.filter, None);
+            assert_eq!(
+                subquery.correlation,
+                vec![("user_id".to_string(), "id".to_string())]
+            );
+        }
+        other => panic!("Expected EXISTS, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_exists_subquery_errors() {
+    for sql in [
+        "select * from users u where exists (select 1 from orders limit 1)",
+        "select * from users u where exists (select 1 from orders group by id)",
+        "select * from users u where exists (update orders set id = 1)",
+    ] {
+        let err = parse(sql).unwrap_err();
+        assert!(
+            err.starts_with("Usage: [not] exists (select"),
+            "{sql}: {err}"
+        );
+    }
+    assert_eq!(
+        parse("select * from users u where exists (select 1 from orders o where o.user_id = u.id")
+            .unwrap_err(),
+        "Malformed EXISTS. Missing closing ')'"
+    );
+    assert_eq!(
+        parse("select * from a where exists (select 1 from b where exists (select 1 from c))")
+            .unwrap_err(),
+        "Subqueries cannot be nested"
+    );
+    assert_eq!(
+        parse("select * from users u where exists (select 1 from orders o where o.id = 1 or o.user_id = u.id)")
+            .unwrap_err(),
+        "A subquery can only reference the outer statement in '<column> = u.<column>' terms joined by AND"
+    );
+    assert_eq!(
+        parse("select * from users u where exists (select 1 from orders u where u.id = 1)")
+            .unwrap_err(),
+        "Subquery alias 'u' must differ from the outer statement's"
+    );
+}
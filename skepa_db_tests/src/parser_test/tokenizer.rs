@@ -65,3 +65,118 @@ fn tokenizer_handles_parentheses_without_spaces() {
         _ => panic!("Expected Create command"),
     }
 }
+
+#[test]
+fn tokenize_quoted_identifiers_are_not_keywords() {
+    let cmd =
+        parse("select `select`, [order] from `from` where [where] = 1 order by `order`").unwrap();
+
+    match cmd {
+        Command::Select {
+            table,
+            columns,
+            filter,
+            order_by,
+            ..
+        } => {
+            assert_eq!(table, "from");
+            assert_eq!(
+                columns,
+                Some(vec!["select".to_string(), "order".to_string()])
+            );
+            assert_eq!(pred(filter.as_ref().unwrap()).column, "where");
+            assert_eq!(order_by.unwrap().column, "order");
+        }
+        _ => panic!("Expected Select command"),
+    }
+}
+
+#[test]
+fn tokenize_quoted_identifier_after_table_qualifier() {
+    let cmd = parse("select [t].[select], t.`order` from t").unwrap();
+
+    match cmd {
+        Command::Select { columns, .. } => {
+            assert_eq!(
+                columns,
+                Some(vec!["t.select".to_string(), "t.order".to_string()])
+            );
+        }
+        _ => panic!("Expected Select command"),
+    }
+}
+
+#[test]
+fn tokenize_quoted_identifiers_keep_case_in_create() {
+    let cmd = parse("create table t (`select` int, [Order] text)").unwrap();
+
+    match cmd {
+        Command::Create { columns, .. } => {
+            assert_eq!(columns[0].name, "select");
+            assert_eq!(columns[1].name, "Order");
+        }
+        _ => panic!("Expected Create command"),
+    }
+}
+
+#[test]
+fn tokenize_rejects_bad_quoted_identifiers() {
+    let err = parse("select `a from t").unwrap_err();
+    assert!(err.contains("Unclosed quoted identifier"));
+
+    let err = parse("select [] from t").unwrap_err();
+    assert!(err.contains("cannot be empty"));
+
+    let err = parse("select [first name] from t").unwrap_err();
+    assert!(err.contains("cannot contain ' '"));
+
+    let err = parse("select a[b] from t").unwrap_err();
+    assert!(err.contains("middle of a token"));
+}
+
+#[test]
+fn trailing_semicolon_is_ignored_across_statements() {
+    match parse("create table users (id int, name text);").unwrap() {
+        Command::Create { table, columns, .. } => {
+            assert_eq!(table, "users");
+            assert_eq!(columns.len(), 2);
+        }
+        _ => panic!("Expected Create command"),
+    }
+    match parse(r#"insert into users values (1, "ram") ;  "#).unwrap() {
+        Command::Insert { values, .. } => assert_eq!(values, vec!["1", "ram"]),
+        _ => panic!("Expected Insert command"),
+    }
+    match parse("select * from users;").unwrap() {
+        Command::Select { table, .. } => assert_eq!(table, "users"),
+        _ => panic!("Expected Select command"),
+    }
+    match parse(r#"select * from users where name = "ram";"#).unwrap() {
+        Command::Select { filter, .. } => assert_eq!(pred(&filter.unwrap()).value, "ram"),
+        _ => panic!("Expected Select command"),
+    }
+}
+
+#[test]
+fn semicolon_inside_quotes_stays_part_of_the_value() {
+    match parse(r#"insert into users values (1, ";")"#).unwrap() {
+        Command::Insert { values, .. } => assert_eq!(values, vec!["1", ";"]),
+        _ => panic!("Expected Insert command"),
+    }
+    match parse(r#"insert into users values (1, "a;b");"#).unwrap() {
+        Command::Insert { values, .. } => assert_eq!(values, vec!["1", "a;b"]),
+        _ => panic!("Expected Insert command"),
+    }
+}
+
+#[test]
+fn semicolon_before_more_input_errors() {
+    for sql in [
+        "select * from users; select * from users",
+        "select * from users;;",
+        "select * from users; where id = 1",
+    ] {
+        let err = parse(sql).unwrap_err();
+        assert!(err.contains("only a single trailing ';'"), "{sql}: {err}");
+    }
+}
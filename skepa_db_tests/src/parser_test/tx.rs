@@ -46,3 +46,119 @@ fn parse_rollback_with_leading_trailing_spaces() {
         Command::Rollback
     ));
 }
+
+#[test]
+fn parse_show_transaction() {
+    assert!(matches!(
+        parse("show transaction").unwrap(),
+        Command::ShowTransaction
+    ));
+    assert!(matches!(
+        parse("SHOW Transaction").unwrap(),
+        Command::ShowTransaction
+    ));
+    for bad in ["show", "show transaction now"] {
+        assert!(
+            parse(bad)
+                .unwrap_err()
+                .to_lowercase()
+                .contains("usage: show transaction"),
+            "{bad}"
+        );
+    }
+}
+
+#[test]
+fn parse_set_and_show_setting() {
+    match parse("set max_rows = 10").unwrap() {
+        Command::Set { name, value } => {
+            assert_eq!(name, "max_rows");
+            assert_eq!(value, "10");
+        }
+        other => panic!("Expected Set command, got {other:?}"),
+    }
+    match parse("show output_format").unwrap() {
+        Command::Show { name } => assert_eq!(name, "output_format"),
+        other => panic!("Expected Show command, got {other:?}"),
+    }
+    for bad in [
+        "set",
+        "set max_rows",
+        "set max_rows 10",
+        "set max_rows = 10 20",
+    ] {
+        assert!(
+            parse(bad)
+                .unwrap_err()
+                .to_lowercase()
+                .contains("usage: set <setting> = <value>"),
+            "{bad}"
+        );
+    }
+}
+
+#[test]
+fn parse_show_indexes_from_table() {
+    match parse("SHOW INDEXES FROM users").unwrap() {
+        Command::ShowIndexes { table } => assert_eq!(table, "users"),
+        other => panic!("Expected ShowIndexes command, got {other:?}"),
+    }
+    match parse("show indexes on users").unwrap() {
+        Command::ShowIndexes { table } => assert_eq!(table, "users"),
+        other => panic!("Expected ShowIndexes command, got {other:?}"),
+    }
+    let err = parse("show indexes from").unwrap_err();
+    assert!(err.contains("show indexes from|on <table>"), "{err}");
+}
+
+#[test]
+fn parse_snapshot_statements() {
+    match parse("create snapshot before_migration").unwrap() {
+        Command::CreateSnapshot { name } => assert_eq!(name, "before_migration"),
+        other => panic!("Expected CreateSnapshot command, got {other:?}"),
+    }
+    match parse("DROP SNAPSHOT `nightly`").unwrap() {
+        Command::DropSnapshot { name } => assert_eq!(name, "nightly"),
+        other => panic!("Expected DropSnapshot command, got {other:?}"),
+    }
+    assert!(matches!(
+        parse("list snapshots").unwrap(),
+        Command::ListSnapshots
+    ));
+    assert_eq!(parse("list").unwrap_err(), "Usage: list snapshots");
+    assert_eq!(
+        parse("create snapshot a b").unwrap_err(),
+        "Usage: create snapshot <name>"
+    );
+    assert_eq!(
+        parse("drop snapshot").unwrap_err(),
+        "Usage: drop snapshot <name>"
+    );
+}
+
+#[test]
+fn parse_attach_and_detach() {
+    match parse(r#"attach "/data/other db" as otherdb"#).unwrap() {
+        Command::Attach { path, alias } => {
+            assert_eq!(path, "/data/other db");
+            assert_eq!(alias, "otherdb");
+        }
+        other => panic!("Expected Attach command, got {other:?}"),
+    }
+    match parse("DETACH otherdb").unwrap() {
+        Command::Detach { alias } => assert_eq!(alias, "otherdb"),
+        other => panic!("Expected Detach command, got {other:?}"),
+    }
+    assert_eq!(
+        parse(r#"attach "/data/other""#).unwrap_err(),
+        "Usage: attach <path> as <alias>"
+    );
+    assert_eq!(parse("detach").unwrap_err(), "Usage: detach <alias>");
+}
+
+#[test]
+fn parse_checkpoint_and_show_wal() {
+    assert!(matches!(parse("CHECKPOINT").unwrap(), Command::Checkpoint));
+    assert!(matches!(parse("show wal").unwrap(), Command::ShowWal));
+    assert_eq!(parse("checkpoint now").unwrap_err(), "Usage: checkpoint");
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+#[test]
+fn attached_database_joins_with_local_tables() {
+    let main_path = temp_dir("attach_main");
+    let other_path = temp_dir("attach_other");
+    {
+        let mut other = Database::open_legacy(other_path.clone());
+        other
+            .execute_legacy("create table users (id int primary key, name text)")
+            .unwrap();
+        other
+            .execute_legacy(r#"insert into users values (1, "ann")"#)
+            .unwrap();
+        other
+            .execute_legacy(r#"insert into users values (2, "bob")"#)
+            .unwrap();
+    }
+    let mut db = Database::open_legacy(main_path.clone());
+    db.execute_legacy("create table orders (id int, user_id int)")
+        .unwrap();
+    db.execute_legacy("insert into orders values (10, 2)")
+        .unwrap();
+    db.execute_legacy("insert into orders values (11, 1)")
+        .unwrap();
+
+    let attach = format!(r#"attach "{}" as otherdb"#, other_path.display());
+    assert_eq!(db.execute_legacy(&attach).unwrap(), "attached otherdb");
+    assert_eq!(
+        db.execute_legacy("select * from otherdb.users where id = 2")
+            .unwrap(),
+        "id\tname\n2\tbob"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            "select orders.id, otherdb.users.name from orders join otherdb.users on orders.user_id = otherdb.users.id order by orders.id asc"
+        )
+        .unwrap(),
+        "orders.id\totherdb.users.name\n10\tbob\n11\tann"
+    );
+    let mut streamed = Vec::new();
+    db.query_stream("select name from otherdb.users", &mut |_, row| {
+        streamed.push(row.clone());
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(streamed.len(), 2);
+    let plan = db
+        .describe_statement("select name from otherdb.users")
+        .unwrap();
+    assert_eq!(plan.output.len(), 1);
+    assert!(
+        db.execute_legacy("describe otherdb.users")
+            .unwrap()
+            .contains("name")
+    );
+
+    // Each statement rereads the attached directory.
+    {
+        let mut other = Database::open_legacy(other_path.clone());
+        other
+            .execute_legacy(r#"insert into users values (3, "cy")"#)
+            .unwrap();
+    }
+    assert_eq!(
+        db.execute_legacy("select name from otherdb.users where id = 3")
+            .unwrap(),
+        "name\ncy"
+    );
+
+    assert_eq!(
+        db.execute_legacy("detach otherdb").unwrap(),
+        "detached otherdb"
+    );
+    let err = db
+        .execute_legacy("select * from otherdb.users")
+        .unwrap_err();
+    assert_eq!(err, "Table 'otherdb.users' does not exist");
+    let _ = std::fs::remove_dir_all(&main_path);
+    let _ = std::fs::remove_dir_all(&other_path);
+}
+
+#[test]
+fn attached_database_rejects_writes_and_misuse() {
+    let main_path = temp_dir("attach_ro_main");
+    let other_path = temp_dir("attach_ro_other");
+    {
+        let mut other = Database::open_legacy(other_path.clone());
+        other.execute_legacy("create table users (id int)").unwrap();
+        other
+            .execute_legacy("insert into users values (1)")
+            .unwrap();
+    }
+    let mut db = Database::open_legacy(main_path.clone());
+    db.attach(other_path.clone(), "otherdb").unwrap();
+
+    for write in [
+        "insert into otherdb.users values (2)",
+        "update otherdb.users set id = 5 where id = 1",
+        "delete from otherdb.users where id = 1",
+        "create index on otherdb.users (id)",
+        "alter table otherdb.users add column name text",
+        "select * from otherdb.users for update",
+    ] {
+        let err = db.execute_legacy(write).unwrap_err();
+        assert_eq!(err, "Attached database 'otherdb' is read-only", "{write}");
+    }
+    assert_eq!(
+        db.execute_legacy("select * from otherdb.users").unwrap(),
+        "id\n1"
+    );
+
+    let err = db.attach(other_path.clone(), "otherdb").unwrap_err();
+    assert_eq!(err.to_string(), "Database 'otherdb' is already attached");
+    let err = db.attach(other_path.clone(), "other.db").unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Invalid attachment alias 'other.db'"),
+        "{err}"
+    );
+    let missing = main_path.join("nowhere");
+    let err = db.attach(missing.clone(), "gone").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("No database found at '{}'", missing.display())
+    );
+    let err = db
+        .execute_legacy("select * from otherdb.missing")
+        .unwrap_err();
+    assert_eq!(err, "Table 'otherdb.missing' does not exist");
+    assert_eq!(
+        db.attached_databases(),
+        vec![("otherdb".to_string(), other_path.clone())]
+    );
+    let err = db.execute_legacy("detach nope").unwrap_err();
+    assert_eq!(err, "Database 'nope' is not attached");
+    let _ = std::fs::remove_dir_all(&main_path);
+    let _ = std::fs::remove_dir_all(&other_path);
+}
@@ -1,5 +1,6 @@
 use super::*;
 use skepa_db_core::config::DbConfig;
+use skepa_db_core::error::DbErrorKind;
 
 #[test]
 fn bootstrap_malformed_row_count_errors() {
@@ -15,6 +16,7 @@ fn bootstrap_malformed_row_count_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "name".to_string(),
@@ -23,6 +25,7 @@ fn bootstrap_malformed_row_count_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
     ]);
 
@@ -45,6 +48,7 @@ fn bootstrap_bad_type_prefix_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "name".to_string(),
@@ -53,6 +57,7 @@ fn bootstrap_bad_type_prefix_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
     ]);
 
@@ -75,6 +80,7 @@ fn bootstrap_dangling_escape_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "name".to_string(),
@@ -83,6 +89,7 @@ fn bootstrap_dangling_escape_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
     ]);
 
@@ -105,6 +112,7 @@ fn bootstrap_unsupported_escape_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "name".to_string(),
@@ -113,6 +121,7 @@ fn bootstrap_unsupported_escape_errors() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
     ]);
 
@@ -134,6 +143,7 @@ fn bootstrap_row_id_prefix_only_without_payload_errors() {
         unique: false,
         not_null: false,
         default: None,
+        json_shape: None,
     }]);
 
     let mut storage = DiskStorage::new(root).unwrap();
@@ -198,3 +208,177 @@ fn newer_catalog_format_is_rejected_on_open() {
     let err = Database::open(DbConfig::new(path)).unwrap_err();
     assert!(err.to_string().contains("newer than supported version"));
 }
+
+#[test]
+fn version_1_directory_opens_in_place_and_is_saved_as_current() {
+    let path = temp_dir("version_1_open");
+    {
+        let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+        db.execute_legacy("create table users (id int, name text)")
+            .unwrap();
+        db.execute_legacy(r#"insert into users values (1, "ram")"#)
+            .unwrap();
+    }
+    let catalog_path = path.join("catalog.json");
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&catalog_path).unwrap()).unwrap();
+    json["format_version"] = serde_json::json!(1);
+    std::fs::write(&catalog_path, json.to_string()).unwrap();
+
+    let plan = skepa_db_core::storage::migrations::plan_catalog_migration(1).unwrap();
+    assert!(!plan.requires_import_export);
+    let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n1\tram"
+    );
+    db.execute_legacy("create table posts (id int)").unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&catalog_path).unwrap()).unwrap();
+    assert_eq!(
+        json["format_version"],
+        serde_json::json!(skepa_db_core::STORAGE_FORMAT_VERSION)
+    );
+}
+
+fn users_db_with_rows_file_removed(prefix: &str) -> PathBuf {
+    let path = temp_dir(prefix);
+    {
+        let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+        db.execute("create table users (id int, name text)")
+            .unwrap();
+        db.execute(r#"insert into users values (1, "ram")"#)
+            .unwrap();
+    }
+    std::fs::remove_file(path.join("tables").join("users.rows")).unwrap();
+    path
+}
+
+#[test]
+fn open_with_missing_table_file_reports_friendly_error() {
+    let path = users_db_with_rows_file_removed("missing_rows_file");
+    let err = Database::open(DbConfig::new(path.clone())).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.starts_with("Table file for 'users' is missing: "),
+        "{message}"
+    );
+    assert!(!message.contains("os error"), "{message}");
+    assert_eq!(err.kind(), DbErrorKind::MissingTableFile);
+    assert!(!path.join("tables").join("users.rows").exists());
+}
+
+#[test]
+fn open_can_recreate_missing_table_file_as_empty() {
+    let path = users_db_with_rows_file_removed("recreate_rows_file");
+    let mut db =
+        Database::open(DbConfig::new(path.clone()).with_recreate_missing_table_files(true))
+            .unwrap();
+    assert!(path.join("tables").join("users.rows").exists());
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname"
+    );
+    db.execute(r#"insert into users values (2, "sam")"#)
+        .unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n2\tsam"
+    );
+}
+
+#[test]
+fn commit_with_missing_table_file_reports_friendly_error() {
+    let path = temp_dir("missing_rows_file_commit");
+    let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    std::fs::remove_file(path.join("tables").join("users.rows")).unwrap();
+    let err = db.execute("commit").unwrap_err();
+    assert_eq!(err.kind(), DbErrorKind::MissingTableFile, "{err}");
+}
+
+#[test]
+fn commit_with_missing_table_file_rolls_back_and_stays_usable() {
+    let path = temp_dir("missing_rows_file_rollback");
+    let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (2, "sam")"#)
+        .unwrap();
+    let rows_file = path.join("tables").join("users.rows");
+    std::fs::remove_file(&rows_file).unwrap();
+
+    let err = db.execute("commit").unwrap_err();
+    assert_eq!(err.kind(), DbErrorKind::MissingTableFile, "{err}");
+    assert_eq!(db.transaction_info(), None);
+    // The committed rows are written back; the staged insert is gone.
+    assert!(rows_file.exists());
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n1\tram"
+    );
+    db.execute(r#"insert into users values (3, "hari")"#)
+        .unwrap();
+    drop(db);
+
+    let mut db = Database::open(DbConfig::new(path)).unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n1\tram\n3\thari"
+    );
+}
+
+#[test]
+fn commit_after_tables_directory_removed_rolls_back_and_stays_usable() {
+    let path = temp_dir("missing_tables_dir_rollback");
+    let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table logs (msg text)").unwrap();
+    db.execute(r#"insert into logs values ("boot")"#).unwrap();
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    std::fs::remove_dir_all(path.join("tables")).unwrap();
+
+    let err = db.execute("commit").unwrap_err();
+    assert_eq!(err.kind(), DbErrorKind::MissingTableFile, "{err}");
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname"
+    );
+    assert_eq!(
+        db.execute_legacy("select * from logs").unwrap(),
+        "msg\nboot"
+    );
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (2, "sam")"#)
+        .unwrap();
+    db.execute("commit").unwrap();
+    drop(db);
+
+    let mut db = Database::open(DbConfig::new(path)).unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n2\tsam"
+    );
+}
+
+#[test]
+fn persist_table_recreates_missing_tables_directory() {
+    let root = temp_dir("persist_recreates_dir");
+    let mut storage = DiskStorage::new(root.clone()).unwrap();
+    storage.create_table("users").unwrap();
+    storage.insert_row("users", vec![Value::Int(1)]).unwrap();
+    std::fs::remove_dir_all(root.join("tables")).unwrap();
+    storage.persist_table("users").unwrap();
+    let content = std::fs::read_to_string(root.join("tables").join("users.rows")).unwrap();
+    assert_eq!(content.lines().count(), 1);
+}
@@ -14,6 +14,7 @@ fn catalog_save_load_roundtrip() {
                     unique: false,
                     not_null: false,
                     default: None,
+                    json_shape: None,
                 },
                 ColumnDef {
                     name: "name".to_string(),
@@ -22,6 +23,7 @@ fn catalog_save_load_roundtrip() {
                     unique: false,
                     not_null: false,
                     default: None,
+                    json_shape: None,
                 },
             ],
             vec![],
@@ -71,6 +73,7 @@ fn catalog_save_load_roundtrip_with_constraints() {
                     unique: false,
                     not_null: true,
                     default: None,
+                    json_shape: None,
                 },
                 ColumnDef {
                     name: "email".to_string(),
@@ -79,6 +82,7 @@ fn catalog_save_load_roundtrip_with_constraints() {
                     unique: true,
                     not_null: false,
                     default: None,
+                    json_shape: None,
                 },
             ],
             vec![],
@@ -95,6 +99,7 @@ fn catalog_save_load_roundtrip_with_constraints() {
                     unique: false,
                     not_null: true,
                     default: None,
+                    json_shape: None,
                 },
                 ColumnDef {
                     name: "user_id".to_string(),
@@ -103,13 +108,14 @@ fn catalog_save_load_roundtrip_with_constraints() {
                     unique: false,
                     not_null: false,
                     default: None,
+                    json_shape: None,
                 },
             ],
             vec![],
         )
         .unwrap();
     catalog
-        .add_unique_constraint("posts", vec!["user_id".to_string()])
+        .add_unique_constraint("posts", vec!["user_id".to_string()], false)
         .unwrap();
     catalog
         .add_secondary_index("posts", vec!["user_id".to_string()])
@@ -155,6 +161,7 @@ fn catalog_save_replaces_file_without_temp_artifacts() {
                 unique: false,
                 not_null: true,
                 default: None,
+                json_shape: None,
             }],
             vec![],
         )
@@ -190,6 +197,7 @@ fn catalog_save_includes_storage_format_version() {
                 unique: false,
                 not_null: true,
                 default: None,
+                json_shape: None,
             }],
             vec![],
         )
@@ -274,3 +282,168 @@ fn catalog_rejects_newer_unsupported_format_version() {
     assert!(err.contains("newer than supported version"));
     assert!(err.contains(&unsupported_version.to_string()));
 }
+
+fn id_column() -> Vec<ColumnDef> {
+    vec![ColumnDef {
+        name: "id".to_string(),
+        dtype: DataType::Int,
+        primary_key: false,
+        unique: false,
+        not_null: false,
+        default: None,
+        json_shape: None,
+    }]
+}
+
+#[test]
+fn catalog_index_names_roundtrip_and_follow_drops() {
+    let mut catalog = Catalog::new();
+    catalog
+        .create_table("users".to_string(), id_column(), vec![])
+        .unwrap();
+    catalog
+        .add_secondary_index("users", vec!["id".to_string()])
+        .unwrap();
+    catalog
+        .name_index(
+            "users",
+            "idx_id",
+            IndexKind::Standard,
+            vec!["id".to_string()],
+        )
+        .unwrap();
+    let err = catalog
+        .name_index(
+            "users",
+            "idx_id",
+            IndexKind::Standard,
+            vec!["id".to_string()],
+        )
+        .unwrap_err();
+    assert_eq!(err, "index idx_id already exists");
+
+    let path = temp_dir("catalog_index_names");
+    std::fs::create_dir_all(&path).unwrap();
+    let catalog_path = path.join("catalog.json");
+    catalog.save_to_path(&catalog_path).unwrap();
+    let mut loaded = Catalog::load_from_path(&catalog_path).unwrap();
+    let (table, index) = loaded.named_index("idx_id").unwrap();
+    assert_eq!(table, "users");
+    assert_eq!(index.columns, vec!["id"]);
+    assert_eq!(
+        loaded.indexes("users").unwrap()[0].name.as_deref(),
+        Some("idx_id")
+    );
+
+    loaded
+        .drop_secondary_index("users", &["id".to_string()])
+        .unwrap();
+    assert!(loaded.named_index("idx_id").is_none());
+}
+
+#[test]
+fn catalog_rejects_reserved_table_names() {
+    let mut catalog = Catalog::new();
+    for name in ["catalog", "wal", "PRAGMA", "information_schema", "system"] {
+        let err = catalog
+            .create_table(name.to_string(), id_column(), vec![])
+            .unwrap_err();
+        assert!(err.contains("reserved table name"), "{name}: {err}");
+    }
+    catalog
+        .create_table("wal_entries".to_string(), id_column(), vec![])
+        .unwrap();
+    assert!(catalog.exists("wal_entries"));
+}
+
+#[test]
+fn catalog_reserved_table_names_are_configurable() {
+    let mut catalog = Catalog::new();
+    catalog.set_reserved_table_names(vec!["audit".to_string()]);
+    let err = catalog
+        .create_table("Audit".to_string(), id_column(), vec![])
+        .unwrap_err();
+    assert!(err.contains("reserved table name"), "{err}");
+    catalog
+        .create_table("wal".to_string(), id_column(), vec![])
+        .unwrap();
+}
+
+#[test]
+fn database_applies_configured_reserved_table_names() {
+    let path = temp_dir("reserved_table_names");
+    let mut db = Database::open(
+        skepa_db_core::config::DbConfig::new(path).with_reserved_table_names(["audit"]),
+    )
+    .unwrap();
+    let err = db.execute("create table audit (id int)").unwrap_err();
+    assert!(err.to_string().contains("reserved table name"), "{err}");
+    db.execute("create table catalog (id int)").unwrap();
+    db.execute("insert into catalog values (1)").unwrap();
+}
+
+#[test]
+fn snapshot_tables_follow_creation_order_across_reopen() {
+    let path = temp_dir("catalog_table_order");
+    let created = ["zeta", "alpha", "mid", "beta", "omega", "gamma"];
+    let mut db = Database::open_legacy(path.clone());
+    for table in created {
+        db.execute_legacy(&format!("create table {table} (id int)"))
+            .unwrap();
+    }
+    drop(db);
+
+    let catalog = Catalog::load_from_path(&path.join("catalog.json")).unwrap();
+    let names: Vec<String> = catalog
+        .snapshot_tables()
+        .into_iter()
+        .map(|(t, _)| t)
+        .collect();
+    assert_eq!(names, created);
+
+    // A catalog written without `table_order` loads its tables by name.
+    let legacy = temp_dir("catalog_table_order_legacy");
+    std::fs::create_dir_all(&legacy).unwrap();
+    let raw = std::fs::read_to_string(path.join("catalog.json")).unwrap();
+    let mut json: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    json.as_object_mut().unwrap().remove("table_order");
+    std::fs::write(legacy.join("catalog.json"), json.to_string()).unwrap();
+    let loaded = Catalog::load_from_path(&legacy.join("catalog.json")).unwrap();
+    let names: Vec<String> = loaded
+        .snapshot_tables()
+        .into_iter()
+        .map(|(t, _)| t)
+        .collect();
+    assert_eq!(names, ["alpha", "beta", "gamma", "mid", "omega", "zeta"]);
+    let _ = std::fs::remove_dir_all(&path);
+    let _ = std::fs::remove_dir_all(&legacy);
+}
+
+#[test]
+fn triggers_persist_in_catalog_across_reopen() {
+    let path = temp_dir("catalog_triggers");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table users (id int, name text)")
+        .unwrap();
+    db.execute_legacy("create table audit (user_id int, name text)")
+        .unwrap();
+    db.execute_legacy(
+        "create trigger copy after insert on users begin insert into audit values (new.id, new.name) end",
+    )
+    .unwrap();
+    drop(db);
+
+    let raw = std::fs::read_to_string(path.join("catalog.json")).unwrap();
+    assert!(raw.contains("\"triggers\""), "{raw}");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    drop(db);
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(
+        db.execute_legacy("select * from audit").unwrap(),
+        "user_id\tname\n1\tram"
+    );
+    drop(db);
+    let _ = std::fs::remove_dir_all(&path);
+}
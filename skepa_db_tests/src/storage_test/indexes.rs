@@ -351,6 +351,32 @@ fn secondary_index_persists_across_reopen() {
     }
 }
 
+#[test]
+fn added_column_order_and_indexes_persist_across_reopen() {
+    let path = temp_dir("add_column_reopen");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy("create table users (id int primary key, city text)")
+            .unwrap();
+        db.execute_legacy("create index on users (city)").unwrap();
+        db.execute_legacy(r#"insert into users values (1, "ny")"#)
+            .unwrap();
+        db.execute_legacy(r#"alter table users add column tier text default "basic" after id"#)
+            .unwrap();
+    }
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let out = db
+            .execute_legacy(r#"select * from users where city = "ny""#)
+            .unwrap();
+        assert_eq!(out, "id\ttier\tcity\n1\tbasic\tny");
+        let err = db
+            .execute_legacy(r#"insert into users values (1, "pro", "la")"#)
+            .unwrap_err();
+        assert!(err.contains("PRIMARY KEY"), "{err}");
+    }
+}
+
 #[test]
 fn reopen_select_index_lookup_multiple_values() {
     let path = temp_dir("reopen_select");
@@ -553,3 +579,144 @@ fn pk_index_snapshot_col_idxs_mismatch_self_heals() {
         assert_eq!(out, "id\tname\n1\ta");
     }
 }
+
+#[test]
+fn indexes_report_stale_until_rebuilt_after_row_mutation() {
+    let root = temp_dir("index_staleness");
+    let mut storage = DiskStorage::new(root).unwrap();
+    let mut schema = Schema::new(vec![Column {
+        name: "city".to_string(),
+        dtype: DataType::Text,
+        primary_key: false,
+        unique: false,
+        not_null: false,
+        default: None,
+        json_shape: None,
+    }]);
+    schema.secondary_indexes = vec![vec!["city".to_string()]];
+    storage.create_table("users").unwrap();
+    assert!(storage.indexes_current("users"));
+
+    storage
+        .insert_row("users", vec![Value::Text("ny".to_string())])
+        .unwrap();
+    assert!(!storage.indexes_current("users"));
+    storage.rebuild_indexes("users", &schema).unwrap();
+    assert!(storage.indexes_current("users"));
+
+    storage.scan_mut("users").unwrap()[0] = vec![Value::Text("sf".to_string())];
+    assert!(!storage.indexes_current("users"));
+    // A stale index still maps "ny" to the row; callers must not trust it.
+    assert_eq!(
+        storage
+            .lookup_secondary_row_indices("users", &schema, "city", "ny")
+            .unwrap(),
+        Some(vec![0])
+    );
+    storage.rebuild_indexes("users", &schema).unwrap();
+    assert_eq!(
+        storage
+            .lookup_secondary_row_indices("users", &schema, "city", "ny")
+            .unwrap(),
+        Some(Vec::new())
+    );
+}
+
+#[test]
+fn open_rebuilds_index_defined_in_catalog_without_index_or_rows_state() {
+    let path = temp_dir("index_reconcile_no_state");
+    std::fs::create_dir_all(&path).unwrap();
+    std::fs::write(
+        path.join("catalog.json"),
+        r#"{
+  "format_version": 1,
+  "tables": {"users": [{"name": "id", "dtype": "int"}, {"name": "age", "dtype": "int"}]},
+  "table_constraints": {"users": {"secondary_indexes": [["age"]]}}
+}"#,
+    )
+    .unwrap();
+
+    {
+        let mut db = Database::open(
+            skepa_db_core::config::DbConfig::new(path.clone())
+                .with_recreate_missing_table_files(true),
+        )
+        .unwrap();
+        assert_eq!(
+            db.recovery_report().index_repairs,
+            vec![IndexRepair {
+                table: "users".to_string(),
+                index: "index (age)".to_string(),
+                action: IndexRepairAction::Rebuilt,
+            }]
+        );
+        db.execute("insert into users values (1, 30)").unwrap();
+        assert_eq!(
+            db.execute_legacy("select id from users where age = 30")
+                .unwrap(),
+            "id\n1"
+        );
+    }
+    let content = std::fs::read_to_string(path.join("indexes").join("users.indexes.json")).unwrap();
+    assert!(content.contains("\"age\""), "{content}");
+
+    let db = Database::open_legacy(path.clone());
+    assert!(db.recovery_report().index_repairs.is_empty());
+}
+
+#[test]
+fn open_reconciles_index_file_left_behind_by_index_ddl() {
+    let path = temp_dir("index_reconcile_ddl");
+    let idx_path = path.join("indexes").join("users.indexes.json");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table users (id int primary key, age int, city text)")
+            .unwrap();
+        db.execute("create index on users (city)").unwrap();
+        db.execute(r#"insert into users values (1, 30, "ny")"#)
+            .unwrap();
+        db.execute(r#"insert into users values (2, 40, "sf")"#)
+            .unwrap();
+        db.checkpoint().unwrap();
+        // As if the process stopped after saving the catalog of both
+        // statements but before writing their index data.
+        let before = std::fs::read(&idx_path).unwrap();
+        db.execute("create index on users (age)").unwrap();
+        db.execute("drop index on users (city)").unwrap();
+        std::fs::write(&idx_path, before).unwrap();
+    }
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let mut repairs = db.recovery_report().index_repairs.clone();
+        repairs.sort_by(|a, b| a.index.cmp(&b.index));
+        assert_eq!(
+            repairs,
+            vec![
+                IndexRepair {
+                    table: "users".to_string(),
+                    index: "index (age)".to_string(),
+                    action: IndexRepairAction::Rebuilt,
+                },
+                IndexRepair {
+                    table: "users".to_string(),
+                    index: "index (city)".to_string(),
+                    action: IndexRepairAction::Dropped,
+                },
+            ]
+        );
+        assert_eq!(
+            db.execute_legacy("select id from users where age = 40")
+                .unwrap(),
+            "id\n2"
+        );
+    }
+    let content = std::fs::read_to_string(&idx_path).unwrap();
+    assert!(!content.contains("\"city\""), "{content}");
+    assert!(
+        Database::open_legacy(path.clone())
+            .recovery_report()
+            .index_repairs
+            .is_empty()
+    );
+}
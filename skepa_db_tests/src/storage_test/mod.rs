@@ -1,5 +1,6 @@
 use skepa_db_core::Database;
-use skepa_db_core::parser::command::ColumnDef;
+use skepa_db_core::parser::command::{ColumnDef, IndexKind};
+use skepa_db_core::recovery_report::{IndexRepair, IndexRepairAction};
 use skepa_db_core::storage::{Catalog, Column, DiskStorage, Schema, StorageEngine};
 use skepa_db_core::types::datatype::DataType;
 use skepa_db_core::types::value::Value;
@@ -20,9 +21,11 @@ fn temp_dir(prefix: &str) -> PathBuf {
     path
 }
 
+mod attachments;
 mod bootstrap;
 mod catalog;
 mod indexes;
 mod persistence;
 mod row_ids;
+mod snapshots;
 mod wal_recovery;
@@ -1,4 +1,5 @@
 use super::*;
+use skepa_db_core::query_result::QueryResult;
 
 #[test]
 fn diskstorage_persist_bootstrap_roundtrip() {
@@ -18,6 +19,7 @@ fn diskstorage_persist_bootstrap_roundtrip() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "name".to_string(),
@@ -26,6 +28,7 @@ fn diskstorage_persist_bootstrap_roundtrip() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
     ]);
 
@@ -61,6 +64,7 @@ fn diskstorage_text_escape_roundtrip() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "name".to_string(),
@@ -69,6 +73,7 @@ fn diskstorage_text_escape_roundtrip() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
     ]);
 
@@ -104,6 +109,92 @@ fn checkpoint_writes_table_files() {
     assert!(!products_rows.trim().is_empty());
 }
 
+fn table_file_mtime(path: &std::path::Path, table: &str) -> std::time::SystemTime {
+    std::fs::metadata(path.join("tables").join(format!("{table}.rows")))
+        .unwrap()
+        .modified()
+        .unwrap()
+}
+
+#[test]
+fn autocommit_write_checkpoints_only_the_written_table() {
+    let path = temp_dir("checkpoint_written_only");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table users (id int primary key, name text)")
+        .unwrap();
+    db.execute_legacy("create table products (id int primary key)")
+        .unwrap();
+    db.execute_legacy("insert into products values (1)")
+        .unwrap();
+    let products_file = path.join("tables").join("products.rows");
+    let products_before = std::fs::read_to_string(&products_file).unwrap();
+    let products_mtime = table_file_mtime(&path, "products");
+    let users_mtime = table_file_mtime(&path, "users");
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    db.execute_legacy(r#"insert into users values (1, "a")"#)
+        .unwrap();
+    db.execute_legacy(r#"update users set name = "b" where id = 1"#)
+        .unwrap();
+    db.execute_legacy("begin").unwrap();
+    db.execute_legacy(r#"insert into users values (2, "c")"#)
+        .unwrap();
+    db.execute_legacy("commit").unwrap();
+
+    assert_eq!(table_file_mtime(&path, "products"), products_mtime);
+    assert_eq!(
+        std::fs::read_to_string(&products_file).unwrap(),
+        products_before
+    );
+    assert_ne!(table_file_mtime(&path, "users"), users_mtime);
+    assert_eq!(std::fs::read_to_string(path.join("wal.log")).unwrap(), "");
+    drop(db);
+
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n1\tb\n2\tc"
+    );
+}
+
+#[test]
+fn checkpoint_includes_tables_written_by_cascades() {
+    let path = temp_dir("checkpoint_cascade");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table users (id int primary key)")
+        .unwrap();
+    db.execute_legacy(
+        "create table posts (id int primary key, user_id int, foreign key(user_id) references users(id) on delete cascade)",
+    )
+    .unwrap();
+    db.execute_legacy("create table other (id int)").unwrap();
+    for sql in [
+        "insert into users values (1)",
+        "insert into users values (2)",
+        "insert into posts values (10, 1)",
+        "insert into posts values (20, 2)",
+        "insert into other values (1)",
+    ] {
+        db.execute_legacy(sql).unwrap();
+    }
+    let other_mtime = table_file_mtime(&path, "other");
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    db.execute_legacy("delete from users where id = 1").unwrap();
+    db.execute_legacy("begin").unwrap();
+    db.execute_legacy("delete from users where id = 2").unwrap();
+    db.execute_legacy("commit").unwrap();
+    assert_eq!(table_file_mtime(&path, "other"), other_mtime);
+    drop(db);
+
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(
+        db.execute_legacy("select * from posts").unwrap(),
+        "id\tuser_id"
+    );
+    assert_eq!(db.execute_legacy("select * from other").unwrap(), "id\n1");
+}
+
 #[test]
 fn reopen_is_idempotent_no_duplicate_rows() {
     let path = temp_dir("reopen_idempotent");
@@ -154,6 +245,27 @@ fn persistence_roundtrip_extended_types() {
     }
 }
 
+#[test]
+fn persistence_roundtrip_offset_timestamps_as_utc() {
+    let path = temp_dir("persist_ts_offset");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy("create table t (id int, ts timestamp)")
+            .unwrap();
+        db.execute_legacy(r#"insert into t values (1, "2025-01-02T10:00:00+05:30")"#)
+            .unwrap();
+    }
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let out = db.execute_legacy("select * from t").unwrap();
+        assert_eq!(out, "id\tts\n1\t2025-01-02 04:30:00");
+        let hit = db
+            .execute_legacy("select id from t where ts = 2025-01-02T04:30:00Z")
+            .unwrap();
+        assert_eq!(hit, "id\n1");
+    }
+}
+
 #[test]
 fn diskstorage_persists_null_values_roundtrip() {
     let root = temp_dir("null_roundtrip");
@@ -172,6 +284,121 @@ fn diskstorage_persists_null_values_roundtrip() {
     }
 }
 
+#[test]
+fn null_roundtrips_for_every_column_type() {
+    let root = temp_dir("null_every_type");
+    let create = "create table t (id int, b bool, g bigint, m decimal(8,2), v varchar(10), s text, d date, ts timestamp, u uuid, j json, x blob)";
+    let non_null = r#"insert into t values (2, true, 7, 1.5, "v", "s", 2025-01-02, "2025-01-02 03:04:05", "550e8400-e29b-41d4-a716-446655440000", "{}", 0xAB)"#;
+    {
+        let mut db = Database::open_legacy(root.clone());
+        db.execute(create).unwrap();
+        db.execute(
+            "insert into t values (1, null, null, null, null, null, null, null, null, null, null)",
+        )
+        .unwrap();
+        db.execute(non_null).unwrap();
+        let file = std::fs::read_to_string(root.join("tables").join("t.rows")).unwrap();
+        let null_line = file.lines().next().unwrap();
+        assert_eq!(null_line.matches("\tn:").count(), 10, "{null_line}");
+    }
+    {
+        let mut db = Database::open_legacy(root.clone());
+        let QueryResult::Select { rows, .. } = db.execute("select * from t").unwrap() else {
+            panic!("expected select result");
+        };
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], Value::Int(1));
+        assert!(
+            rows[0][1..].iter().all(|v| *v == Value::Null),
+            "{:?}",
+            rows[0]
+        );
+        assert!(rows[1].iter().all(|v| *v != Value::Null), "{:?}", rows[1]);
+        assert_eq!(
+            db.execute_legacy("select * from t where id = 1").unwrap(),
+            "id\tb\tg\tm\tv\ts\td\tts\tu\tj\tx\n1\tnull\tnull\tnull\tnull\tnull\tnull\tnull\tnull\tnull\tnull"
+        );
+        for col in ["b", "g", "m", "v", "s", "d", "ts", "u", "j", "x"] {
+            assert_eq!(
+                db.execute_legacy(&format!("select id from t where {col} is null"))
+                    .unwrap(),
+                "id\n1",
+                "{col}"
+            );
+        }
+    }
+}
+
+#[test]
+fn failed_add_column_table_write_restores_old_schema_and_rows() {
+    let path = temp_dir("add_column_write_failure");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy("create table users (id int, name text)")
+            .unwrap();
+        db.execute_legacy(r#"insert into users values (1, "ram")"#)
+            .unwrap();
+        std::fs::write(path.join(".simulate_fail_schema_table_write"), "1").unwrap();
+        let err = db
+            .execute_legacy(r#"alter table users add column status text not null default "new""#)
+            .unwrap_err();
+        assert!(err.contains("Simulated table write failure"), "{err}");
+        assert_eq!(
+            db.execute_legacy("select * from users").unwrap(),
+            "id\tname\n1\tram"
+        );
+    }
+
+    std::fs::remove_file(path.join(".simulate_fail_schema_table_write")).unwrap();
+
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n1\tram"
+    );
+    db.execute_legacy(r#"alter table users add column status text not null default "new""#)
+        .unwrap();
+    drop(db);
+    let mut db = Database::open_legacy(path);
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\tstatus\n1\tram\tnew"
+    );
+}
+
+#[test]
+fn failed_create_table_as_write_leaves_no_table_behind() {
+    let path = temp_dir("create_table_as_write_failure");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy("create table users (id int, name text)")
+            .unwrap();
+        db.execute_legacy(r#"insert into users values (1, "ram")"#)
+            .unwrap();
+        std::fs::write(path.join(".simulate_fail_schema_table_write"), "1").unwrap();
+        let err = db
+            .execute_legacy("create table copy as select * from users")
+            .unwrap_err();
+        assert!(err.contains("Simulated table write failure"), "{err}");
+        let err = db.execute_legacy("select * from copy").unwrap_err();
+        assert!(err.contains("does not exist"), "{err}");
+    }
+
+    std::fs::remove_file(path.join(".simulate_fail_schema_table_write")).unwrap();
+
+    let mut db = Database::open_legacy(path.clone());
+    let err = db.execute_legacy("select * from copy").unwrap_err();
+    assert!(err.contains("does not exist"), "{err}");
+    db.execute_legacy("create table copy as select * from users")
+        .unwrap();
+    drop(db);
+    let mut db = Database::open_legacy(path);
+    assert_eq!(
+        db.execute_legacy("select * from copy").unwrap(),
+        "id\tname\n1\tram"
+    );
+}
+
 #[test]
 fn interrupted_checkpoint_after_update_recovers_deterministically() {
     let path = temp_dir("interrupt_checkpoint_update");
@@ -206,3 +433,223 @@ fn interrupted_checkpoint_after_update_recovers_deterministically() {
         );
     }
 }
+
+fn pending_journals(path: &std::path::Path) -> Vec<std::path::PathBuf> {
+    match std::fs::read_dir(path.join("journal")) {
+        Ok(entries) => entries
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.is_dir())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn segment_files(path: &std::path::Path, table: &str) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(path.join("tables"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|n| n.starts_with(&format!("{table}.")) && n != &format!("{table}.rows"))
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn interrupted_segment_write_lands_whole_on_recovery() {
+    let path = temp_dir("interrupt_segment_journal");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table t (id int, region text) partition by (region)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (1, "a")"#)
+        .unwrap();
+    let before = segment_files(&path, "t");
+    assert_eq!(before.len(), 1);
+
+    // The commit record is written, but no segment has been touched yet.
+    std::fs::write(path.join(".simulate_interrupt_journal_after_commit"), "1").unwrap();
+    let err = db
+        .execute_legacy(r#"update t set region = "b" where id = 1"#)
+        .unwrap_err();
+    assert!(
+        err.contains("Simulated interruption after journal commit"),
+        "{err}"
+    );
+    drop(db);
+    std::fs::remove_file(path.join(".simulate_interrupt_journal_after_commit")).unwrap();
+    assert_eq!(segment_files(&path, "t"), before);
+    let pending = pending_journals(&path);
+    assert_eq!(pending.len(), 1);
+    assert!(pending[0].join("commit").exists());
+
+    skepa_db_core::storage::persistence::recover_journal(&path).unwrap();
+    let after = segment_files(&path, "t");
+    assert_eq!(after.len(), 1);
+    assert_ne!(after, before);
+    assert!(pending_journals(&path).is_empty());
+
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(
+        db.execute_legacy("select * from t").unwrap(),
+        "id\tregion\n1\tb"
+    );
+}
+
+#[test]
+fn segment_write_interrupted_before_commit_is_discarded() {
+    let path = temp_dir("interrupt_segment_journal_staged");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table t (id int, region text) partition by (region)")
+        .unwrap();
+    db.execute_legacy(r#"insert into t values (1, "a")"#)
+        .unwrap();
+    let before = segment_files(&path, "t");
+
+    std::fs::write(path.join(".simulate_interrupt_journal_before_commit"), "1").unwrap();
+    let err = db
+        .execute_legacy(r#"insert into t values (2, "b")"#)
+        .unwrap_err();
+    assert!(
+        err.contains("Simulated interruption before journal commit"),
+        "{err}"
+    );
+    drop(db);
+    std::fs::remove_file(path.join(".simulate_interrupt_journal_before_commit")).unwrap();
+    assert_eq!(segment_files(&path, "t"), before);
+
+    // Opening drops the staged files; the WAL still replays the insert.
+    let mut db = Database::open_legacy(path.clone());
+    assert!(pending_journals(&path).is_empty());
+    assert_eq!(
+        db.execute_legacy("select * from t order by id asc")
+            .unwrap(),
+        "id\tregion\n1\ta\n2\tb"
+    );
+}
+
+#[test]
+fn schema_change_journals_catalog_and_rows_together() {
+    let path = temp_dir("schema_change_journal");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table users (id int, name text)")
+        .unwrap();
+    db.execute_legacy(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+
+    std::fs::write(path.join(".simulate_interrupt_journal_after_commit"), "1").unwrap();
+    let err = db
+        .execute_legacy(r#"alter table users add column status text not null default "new""#)
+        .unwrap_err();
+    assert!(
+        err.contains("Simulated interruption after journal commit"),
+        "{err}"
+    );
+    drop(db);
+    std::fs::remove_file(path.join(".simulate_interrupt_journal_after_commit")).unwrap();
+    let pending = pending_journals(&path);
+    assert_eq!(pending.len(), 1);
+    let commit = std::fs::read_to_string(pending[0].join("commit")).unwrap();
+    assert!(commit.contains(" catalog.json"), "{commit}");
+    assert!(commit.contains(" tables/users.rows"), "{commit}");
+
+    // The failed statement rolled back, so the committed journal holds the
+    // old catalog and rows; both land on open.
+    let mut db = Database::open_legacy(path.clone());
+    assert!(pending_journals(&path).is_empty());
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\n1\tram"
+    );
+    db.execute_legacy(r#"alter table users add column status text not null default "new""#)
+        .unwrap();
+    drop(db);
+    let mut db = Database::open_legacy(path);
+    assert_eq!(
+        db.execute_legacy("select * from users").unwrap(),
+        "id\tname\tstatus\n1\tram\tnew"
+    );
+}
+
+#[test]
+fn handles_sharing_a_directory_never_tear_each_others_journal() {
+    let path = temp_dir("shared_journal");
+    let mut db = Database::open_legacy(path.clone());
+    for table in ["t1", "t2"] {
+        db.execute_legacy(&format!(
+            "create table {table} (id int, region text) partition by (region)"
+        ))
+        .unwrap();
+        db.execute_legacy(&format!(r#"insert into {table} values (1, "r0")"#))
+            .unwrap();
+    }
+    drop(db);
+
+    std::thread::scope(|s| {
+        for table in ["t1", "t2"] {
+            let path = path.clone();
+            s.spawn(move || {
+                let mut db = Database::open_legacy(path);
+                for i in 1..=20 {
+                    db.execute_legacy(&format!(
+                        r#"update {table} set region = "r{i}" where id = 1"#
+                    ))
+                    .unwrap();
+                }
+            });
+        }
+        let path = path.clone();
+        s.spawn(move || {
+            // What opening runs first; it must leave live writers' journals
+            // alone.
+            for _ in 0..40 {
+                skepa_db_core::storage::persistence::recover_journal(&path).unwrap();
+            }
+        });
+    });
+
+    assert!(pending_journals(&path).is_empty());
+    let mut db = Database::open_legacy(path.clone());
+    for table in ["t1", "t2"] {
+        assert_eq!(
+            db.execute_legacy(&format!("select * from {table}"))
+                .unwrap(),
+            "id\tregion\n1\tr20"
+        );
+        assert_eq!(segment_files(&path, table).len(), 1);
+    }
+}
+
+#[test]
+fn concurrent_schema_change_and_row_writes_keep_catalog_and_rows_in_step() {
+    let path = temp_dir("concurrent_ddl_dml");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table t (id int)").unwrap();
+    db.execute_legacy("insert into t values (0)").unwrap();
+    drop(db);
+
+    std::thread::scope(|s| {
+        let ddl_path = path.clone();
+        s.spawn(move || {
+            let mut db = Database::open_legacy(ddl_path);
+            for i in 0..10 {
+                db.execute_legacy(&format!("alter table t add column c{i} int default {i}"))
+                    .unwrap();
+            }
+        });
+        let dml_path = path.clone();
+        s.spawn(move || {
+            let mut db = Database::open_legacy(dml_path);
+            for i in 1..=40 {
+                db.execute_legacy(&format!("update t set id = {i} where id = {}", i - 1))
+                    .unwrap();
+            }
+        });
+    });
+
+    let mut db = Database::open_legacy(path);
+    let described = db.execute_legacy("describe t").unwrap();
+    assert!(described.contains("c9"), "{described}");
+    assert_eq!(
+        db.execute_legacy("select * from t").unwrap(),
+        "id\tc0\tc1\tc2\tc3\tc4\tc5\tc6\tc7\tc8\tc9\n40\t0\t1\t2\t3\t4\t5\t6\t7\t8\t9"
+    );
+}
@@ -95,6 +95,7 @@ fn row_id_format_backward_compat_without_prefix() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
         Column {
             name: "name".to_string(),
@@ -103,6 +104,7 @@ fn row_id_format_backward_compat_without_prefix() {
             unique: false,
             not_null: false,
             default: None,
+            json_shape: None,
         },
     ]);
     storage.bootstrap_table("users", &schema).unwrap();
@@ -110,3 +112,46 @@ fn row_id_format_backward_compat_without_prefix() {
     let rows = std::fs::read_to_string(root.join("tables").join("users.rows")).unwrap();
     assert!(rows.lines().all(|l| l.starts_with('@')));
 }
+
+#[test]
+fn replace_rows_with_alignment_rejects_misaligned_input() {
+    let mut storage = DiskStorage::new(temp_dir("row_alignment_invariants")).unwrap();
+    storage.create_table("t").unwrap();
+    for i in 0..3 {
+        storage.insert_row("t", vec![Value::Int(i)]).unwrap();
+    }
+    let rows = |n: i64| (0..n).map(|i| vec![Value::Int(i)]).collect::<Vec<_>>();
+
+    let err = storage
+        .replace_rows_with_alignment("t", rows(2), vec![0])
+        .unwrap_err();
+    assert!(err.contains("2 row(s) but 1 source index(es)"), "{err}");
+    let err = storage
+        .replace_rows_with_alignment("t", rows(2), vec![2, 1])
+        .unwrap_err();
+    assert!(err.contains("not strictly increasing"), "{err}");
+    let err = storage
+        .replace_rows_with_alignment("t", rows(2), vec![1, 1])
+        .unwrap_err();
+    assert!(err.contains("not strictly increasing"), "{err}");
+    let err = storage
+        .replace_rows_with_alignment("t", rows(2), vec![0, 3])
+        .unwrap_err();
+    assert!(err.contains("out of range for 3 row(s)"), "{err}");
+    assert_eq!(storage.scan("t").unwrap().len(), 3);
+
+    storage
+        .replace_rows_with_alignment("t", rows(2), vec![0, 2])
+        .unwrap();
+    assert_eq!(storage.scan("t").unwrap().len(), 2);
+    let schema = Schema::new(vec![Column {
+        name: "id".to_string(),
+        dtype: DataType::Int,
+        primary_key: false,
+        unique: false,
+        not_null: false,
+        default: None,
+        json_shape: None,
+    }]);
+    storage.verify_table("t", &schema).unwrap();
+}
@@ -0,0 +1,109 @@
+use super::*;
+
+#[test]
+fn select_as_of_reads_the_pre_migration_state() {
+    let path = temp_dir("snapshot_as_of");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table users (id int primary key, name text)")
+        .unwrap();
+    db.execute_legacy("create table posts (id int, user_id int)")
+        .unwrap();
+    db.execute_legacy(r#"insert into users values (1, "a")"#)
+        .unwrap();
+    db.execute_legacy(r#"insert into users values (2, "b")"#)
+        .unwrap();
+    db.execute_legacy("insert into posts values (10, 1)")
+        .unwrap();
+    db.create_snapshot("before_migration").unwrap();
+
+    db.execute_legacy("alter table users add column age int default 7")
+        .unwrap();
+    db.execute_legacy(r#"update users set name = "z" where id = 1"#)
+        .unwrap();
+    db.execute_legacy("delete from users where id = 2").unwrap();
+    db.execute_legacy(r#"insert into users values (3, "c", 9)"#)
+        .unwrap();
+    db.checkpoint().unwrap();
+
+    let before = "select * from users as of before_migration order by id asc";
+    assert_eq!(db.execute_legacy(before).unwrap(), "id\tname\n1\ta\n2\tb");
+    assert_eq!(
+        db.execute_legacy("select * from users order by id asc")
+            .unwrap(),
+        "id\tname\tage\n1\tz\t7\n3\tc\t9"
+    );
+    assert_eq!(
+        db.execute_legacy(
+            r#"select users.name, posts.id from users as of "before_migration" join posts on users.id = posts.user_id"#
+        )
+        .unwrap(),
+        "users.name\tposts.id\na\t10"
+    );
+    let plan = db.describe_statement(before).unwrap();
+    assert_eq!(plan.columns.len(), 2);
+    assert_eq!(
+        db.execute_legacy("list snapshots").unwrap(),
+        "name\nbefore_migration"
+    );
+
+    // The copy survives reopening and further checkpoints.
+    drop(db);
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("delete from users where id = 1").unwrap();
+    db.checkpoint().unwrap();
+    assert_eq!(db.execute_legacy(before).unwrap(), "id\tname\n1\ta\n2\tb");
+
+    assert_eq!(
+        db.execute_legacy("drop snapshot before_migration").unwrap(),
+        "dropped snapshot before_migration"
+    );
+    assert_eq!(db.list_snapshots().unwrap(), Vec::<String>::new());
+    let err = db.execute_legacy(before).unwrap_err();
+    assert_eq!(err, "Snapshot 'before_migration' does not exist");
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn snapshot_statements_reject_misuse() {
+    let path = temp_dir("snapshot_misuse");
+    let mut db = Database::open_legacy(path.clone());
+    db.execute_legacy("create table users (id int)").unwrap();
+    assert_eq!(
+        db.execute_legacy("create snapshot s1").unwrap(),
+        "created snapshot s1"
+    );
+    db.execute_legacy("create table later (id int)").unwrap();
+
+    let err = db.execute_legacy("create snapshot s1").unwrap_err();
+    assert_eq!(err, "Snapshot 's1' already exists");
+    let err = db.create_snapshot("../escape").unwrap_err().to_string();
+    assert!(
+        err.starts_with("Invalid snapshot name '../escape'"),
+        "{err}"
+    );
+    let err = db
+        .execute_legacy("select * from later as of s1")
+        .unwrap_err();
+    assert!(err.contains("Table 'later' does not exist"), "{err}");
+    let err = db
+        .execute_legacy("select * from users as of s1 for update")
+        .unwrap_err();
+    assert_eq!(err, "FOR UPDATE cannot be combined with AS OF");
+    let err = db.execute_legacy("delete from users as of s1").unwrap_err();
+    assert_eq!(
+        err,
+        "Snapshots are read-only: AS OF is only allowed in SELECT"
+    );
+
+    db.execute_legacy("begin").unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from users as of s1").unwrap(),
+        "id"
+    );
+    let err = db.execute_legacy("create snapshot s2").unwrap_err();
+    assert_eq!(err, "Cannot create a snapshot inside an active transaction");
+    db.execute_legacy("rollback").unwrap();
+    let err = db.execute_legacy("drop snapshot s2").unwrap_err();
+    assert_eq!(err, "Snapshot 's2' does not exist");
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -1,4 +1,6 @@
 use super::*;
+use skepa_db_core::config::{AutoCheckpoint, DbConfig};
+use skepa_db_core::query_result::QueryResult;
 use skepa_db_core::types::value::Value;
 
 #[test]
@@ -15,6 +17,158 @@ fn wal_is_truncated_after_write() {
     assert_eq!(wal, "");
 }
 
+fn commit_three_ops_with_interrupted_checkpoint(
+    path: &std::path::Path,
+    config: DbConfig,
+) -> String {
+    let mut db = Database::open(config).unwrap();
+    db.execute("create table users (id int primary key, name text)")
+        .unwrap();
+    std::fs::write(
+        path.join(".simulate_interrupt_checkpoint_after_tables"),
+        "1",
+    )
+    .unwrap();
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute(r#"insert into users values (2, "sam")"#)
+        .unwrap();
+    db.execute(r#"update users set name = "hari" where id = 1"#)
+        .unwrap();
+    let err = db.execute("commit").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Simulated checkpoint interruption")
+    );
+    std::fs::remove_file(path.join(".simulate_interrupt_checkpoint_after_tables")).unwrap();
+    std::fs::read_to_string(path.join("wal.log")).unwrap()
+}
+
+fn assert_single_contiguous_group(wal: &str) {
+    let lines: Vec<&str> = wal.lines().collect();
+    assert!(wal.ends_with('\n'));
+    assert_eq!(lines.len(), 5, "{wal}");
+    let txid = lines[0].strip_prefix("BEGIN ").unwrap();
+    for (line, stmt) in lines[1..4].iter().zip([
+        r#"insert into users values (1, "ram")"#,
+        r#"insert into users values (2, "sam")"#,
+        r#"update users set name = "hari" where id = 1"#,
+    ]) {
+        let payload = line
+            .strip_prefix(&format!("OP {txid} crc="))
+            .unwrap_or_else(|| panic!("{line}"));
+        assert_eq!(payload.split_once(' ').unwrap().1, stmt);
+    }
+    assert_eq!(lines[4], format!("COMMIT {txid}"));
+}
+
+/// Commits one insert of `note` with the checkpoint interrupted and
+/// returns the WAL it left behind.
+fn wal_for_note_insert(note: &str) -> String {
+    let path = temp_dir("wal_note_source");
+    let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+    db.execute("create table notes (id int primary key, body text)")
+        .unwrap();
+    std::fs::write(
+        path.join(".simulate_interrupt_checkpoint_after_tables"),
+        "1",
+    )
+    .unwrap();
+    db.execute(&format!(r#"insert into notes values (1, "{note}")"#))
+        .unwrap_err();
+    std::fs::read_to_string(path.join("wal.log")).unwrap()
+}
+
+fn replay_notes_wal(name: &str, wal: &str) -> Database {
+    let path = temp_dir(name);
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute("create table notes (id int primary key, body text)")
+            .unwrap();
+    }
+    std::fs::write(path.join("wal.log"), wal).unwrap();
+    Database::open_legacy(path)
+}
+
+#[test]
+fn wal_op_with_embedded_newline_replays_intact() {
+    // The SQL literal spells the backslash as `\\`; the WAL escapes both.
+    let wal = wal_for_note_insert("line one\nline two \\\\ end");
+    assert_eq!(wal.lines().count(), 3, "{wal}");
+    assert!(
+        wal.lines()
+            .nth(1)
+            .unwrap()
+            .contains(r"line one\nline two \\\\ end")
+    );
+
+    let mut db = replay_notes_wal("wal_newline_replay", &wal);
+    match db.execute("select body from notes").unwrap() {
+        QueryResult::Select { rows, .. } => {
+            assert_eq!(
+                rows,
+                vec![vec![Value::Text("line one\nline two \\ end".to_string())]]
+            )
+        }
+        other => panic!("expected select result, got {other:?}"),
+    }
+}
+
+#[test]
+fn wal_op_failing_checksum_skips_its_transaction() {
+    let wal = wal_for_note_insert("intact");
+    let tampered = wal.replace("intact", "edited");
+    assert_ne!(wal, tampered);
+
+    let mut db = replay_notes_wal("wal_checksum_mismatch", &tampered);
+    assert_eq!(
+        db.execute_legacy("select * from notes").unwrap(),
+        "id\tbody"
+    );
+    assert_eq!(
+        std::fs::read_to_string(db.path().join("wal.log")).unwrap(),
+        ""
+    );
+
+    let bad_escape = wal.replace("intact", r"in\tact");
+    let mut db = replay_notes_wal("wal_bad_escape", &bad_escape);
+    assert_eq!(
+        db.execute_legacy("select * from notes").unwrap(),
+        "id\tbody"
+    );
+}
+
+#[test]
+fn wal_transaction_commit_is_written_as_one_contiguous_group() {
+    let path = temp_dir("wal_single_group");
+    let wal = commit_three_ops_with_interrupted_checkpoint(&path, DbConfig::new(path.clone()));
+    assert_single_contiguous_group(&wal);
+}
+
+#[test]
+fn wal_kept_open_writes_one_group_and_truncates_in_place() {
+    let path = temp_dir("wal_kept_open");
+    let config = DbConfig::new(path.clone()).with_keep_wal_open(true);
+    let wal = commit_three_ops_with_interrupted_checkpoint(&path, config.clone());
+    assert_single_contiguous_group(&wal);
+
+    {
+        let mut db = Database::open(config).unwrap();
+        db.execute(r#"insert into users values (3, "avi")"#)
+            .unwrap();
+        db.execute("delete from users where id = 2").unwrap();
+        assert_eq!(std::fs::read_to_string(path.join("wal.log")).unwrap(), "");
+    }
+
+    let mut db = Database::open_legacy(path.clone());
+    assert_eq!(
+        db.execute_legacy("select * from users order by id asc")
+            .unwrap(),
+        "id\tname\n1\thari\n3\tavi"
+    );
+}
+
 #[test]
 fn recovery_ignores_uncommitted_wal_transaction() {
     let path = temp_dir("wal_uncommitted_ignored");
@@ -61,6 +215,54 @@ fn recovery_replays_committed_wal_transaction() {
     }
 }
 
+#[test]
+fn recovery_replays_offset_timestamp_as_utc() {
+    let path = temp_dir("wal_ts_offset_replayed");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy("create table t (id int, ts timestamp)")
+            .unwrap();
+    }
+
+    std::fs::write(
+        path.join("wal.log"),
+        "BEGIN 7\nOP 7 insert into t values (1, \"2025-01-02T10:00:00+05:30\")\nCOMMIT 7\n",
+    )
+    .unwrap();
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let out = db.execute_legacy("select * from t").unwrap();
+        assert_eq!(out, "id\tts\n1\t2025-01-02 04:30:00");
+    }
+}
+
+#[test]
+fn recovery_replays_delete_with_in_list() {
+    let path = temp_dir("wal_in_list_replayed");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy("create table t (id int, s text)")
+            .unwrap();
+        db.execute_legacy(r#"insert into t values (1, "a,b")"#)
+            .unwrap();
+        db.execute_legacy(r#"insert into t values (2, "c")"#)
+            .unwrap();
+    }
+
+    std::fs::write(
+        path.join("wal.log"),
+        "BEGIN 9\nOP 9 delete from t where s in (\"a,b\", \"z\")\nCOMMIT 9\n",
+    )
+    .unwrap();
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let out = db.execute_legacy("select * from t").unwrap();
+        assert_eq!(out, "id\ts\n2\tc");
+    }
+}
+
 #[test]
 fn recovery_replays_insert_that_uses_default_values() {
     let path = temp_dir("wal_defaults_replayed");
@@ -115,6 +317,43 @@ fn recovery_replays_only_committed_when_wal_has_mixed_transactions() {
     }
 }
 
+#[test]
+fn recovery_replays_interleaved_transactions_in_commit_order() {
+    let path = temp_dir("wal_commit_order_recovery");
+    {
+        let mut db = Database::open_legacy(path.clone());
+        db.execute_legacy("create table users (id int primary key, name text)")
+            .unwrap();
+        db.execute_legacy(r#"insert into users values (1, "start")"#)
+            .unwrap();
+    }
+
+    // tx 1 begins first but commits after tx 2, so its update wins; tx 3
+    // deletes the row tx 1 inserted, which only works in commit order.
+    std::fs::write(
+        path.join("wal.log"),
+        concat!(
+            "BEGIN 1\n",
+            "BEGIN 2\n",
+            "OP 2 update users set name = \"from tx2\" where id = 1\n",
+            "COMMIT 2\n",
+            "OP 1 update users set name = \"from tx1\" where id = 1\n",
+            "OP 1 insert into users values (2, \"b\")\n",
+            "BEGIN 3\n",
+            "COMMIT 1\n",
+            "OP 3 delete from users where id = 2\n",
+            "COMMIT 3\n"
+        ),
+    )
+    .unwrap();
+
+    {
+        let mut db = Database::open_legacy(path.clone());
+        let out = db.execute_legacy("select * from users").unwrap();
+        assert_eq!(out, "id\tname\n1\tfrom tx1");
+    }
+}
+
 #[test]
 fn recovery_ignores_explicitly_rolled_back_transaction() {
     let path = temp_dir("wal_rolled_back_ignored");
@@ -490,3 +729,248 @@ fn recovery_ignores_truncated_wal_tail_record() {
         vec![vec![Value::Int(1), Value::Text("ram".to_string())]]
     );
 }
+
+fn wal_status(db: &mut Database) -> (u64, usize) {
+    let info = db.wal_info().unwrap();
+    match db.execute("show wal").unwrap() {
+        QueryResult::Select { schema, rows, .. } => {
+            let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(names, vec!["bytes", "pending_transactions"]);
+            assert_eq!(
+                rows,
+                vec![vec![
+                    Value::BigInt(i128::from(info.bytes)),
+                    Value::BigInt(info.pending_transactions as i128)
+                ]]
+            );
+        }
+        other => panic!("expected select result, got {other:?}"),
+    }
+    (info.bytes, info.pending_transactions)
+}
+
+#[test]
+fn manual_auto_checkpoint_grows_wal_until_checkpoint_statement() {
+    let path = temp_dir("wal_manual_checkpoint");
+    let config = DbConfig::new(path.clone()).with_auto_checkpoint(AutoCheckpoint::Manual);
+    let mut db = Database::open(config.clone()).unwrap();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+    db.execute("create table logs (id int)").unwrap();
+    assert_eq!(wal_status(&mut db), (0, 0));
+
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    db.execute("insert into logs values (1)").unwrap();
+    let (one_group, _) = wal_status(&mut db);
+    db.execute("begin").unwrap();
+    db.execute(r#"insert into users values (2, "sita")"#)
+        .unwrap();
+    db.execute(r#"update users set name = "hari" where id = 1"#)
+        .unwrap();
+    db.execute("commit").unwrap();
+    let (bytes, pending) = wal_status(&mut db);
+    assert!(bytes > one_group);
+    assert_eq!(pending, 3);
+    assert_eq!(
+        std::fs::metadata(path.join("wal.log")).unwrap().len(),
+        bytes
+    );
+
+    match db.execute("checkpoint").unwrap() {
+        QueryResult::Transaction { message, .. } => assert_eq!(
+            message,
+            format!("checkpointed 2 table(s), truncated {bytes} B of WAL")
+        ),
+        other => panic!("expected transaction result, got {other:?}"),
+    }
+    assert_eq!(wal_status(&mut db), (0, 0));
+    match db.execute("checkpoint").unwrap() {
+        QueryResult::Transaction { message, .. } => {
+            assert_eq!(message, "checkpointed 0 table(s), truncated 0 B of WAL")
+        }
+        other => panic!("expected transaction result, got {other:?}"),
+    }
+    drop(db);
+
+    let mut db = Database::open(config).unwrap();
+    assert_eq!(
+        db.execute_legacy("select * from users order by id")
+            .unwrap(),
+        "id\tname\n1\thari\n2\tsita"
+    );
+}
+
+#[test]
+fn uncheckpointed_commits_are_replayed_on_open() {
+    let path = temp_dir("wal_manual_replay");
+    let config = DbConfig::new(path.clone()).with_auto_checkpoint(AutoCheckpoint::Manual);
+    {
+        let mut db = Database::open(config.clone()).unwrap();
+        db.execute("create table users (id int, name text)")
+            .unwrap();
+        for i in 0..40 {
+            db.execute(&format!(
+                r#"insert into users values ({i}, "user number {i}")"#
+            ))
+            .unwrap();
+        }
+        let (bytes, pending) = wal_status(&mut db);
+        assert!(bytes > 1024);
+        assert_eq!(pending, 40);
+        // A schema change checkpoints first, so the rows it rewrites are
+        // not replayed on top of themselves.
+        db.execute("create index on users (name)").unwrap();
+        assert_eq!(wal_status(&mut db), (0, 0));
+        db.execute(r#"insert into users values (40, "last")"#)
+            .unwrap();
+        assert_eq!(wal_status(&mut db).1, 1);
+    }
+
+    let mut db = Database::open(config).unwrap();
+    assert_eq!(wal_status(&mut db), (0, 0));
+    assert_eq!(
+        db.execute_legacy("select count(*) from users").unwrap(),
+        "count(*)\n41"
+    );
+}
+
+#[test]
+fn deferred_checkpoints_need_the_directory_to_themselves() {
+    let path = temp_dir("wal_manual_lock");
+    let manual = DbConfig::new(path.clone()).with_auto_checkpoint(AutoCheckpoint::Manual);
+
+    let shared = Database::open(DbConfig::new(path.clone())).unwrap();
+    let err = Database::open(manual.clone()).unwrap_err().to_string();
+    assert!(err.contains("is already open"), "{err}");
+    drop(shared);
+
+    let mut db = Database::open(manual.clone()).unwrap();
+    let err = Database::open(DbConfig::new(path.clone()))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("open by a handle with deferred checkpoints"),
+        "{err}"
+    );
+    let err = Database::open(manual.clone()).unwrap_err().to_string();
+    assert!(err.contains("is already open"), "{err}");
+
+    db.execute("create table users (id int)").unwrap();
+    db.execute("insert into users values (1)").unwrap();
+    drop(db);
+    let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+    assert_eq!(db.execute_legacy("select * from users").unwrap(), "id\n1");
+}
+
+#[test]
+fn reloading_from_disk_keeps_uncheckpointed_commits() {
+    let path = temp_dir("wal_manual_reload");
+    let config = DbConfig::new(path.clone()).with_auto_checkpoint(AutoCheckpoint::Manual);
+    let mut db = Database::open(config).unwrap();
+    db.execute("create table users (id int)").unwrap();
+    db.execute("insert into users values (1)").unwrap();
+    db.execute("insert into users values (2)").unwrap();
+    assert_eq!(wal_status(&mut db).1, 2);
+
+    // A changed catalog file makes the next statement reload from disk.
+    let catalog = std::fs::File::options()
+        .write(true)
+        .open(path.join("catalog.json"))
+        .unwrap();
+    catalog
+        .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+        .unwrap();
+    drop(catalog);
+
+    assert_eq!(
+        db.execute_legacy("select * from users order by id")
+            .unwrap(),
+        "id\n1\n2"
+    );
+    db.execute("checkpoint").unwrap();
+    assert_eq!(
+        db.execute_legacy("select count(*) from users").unwrap(),
+        "count(*)\n2"
+    );
+}
+
+#[test]
+fn replayed_inserts_keep_their_generated_uuids() {
+    let path = temp_dir("wal_gen_uuid_replay");
+    let config = DbConfig::new(path.clone()).with_auto_checkpoint(AutoCheckpoint::Manual);
+    let read_ids = |db: &mut Database| db.execute_legacy("select id from ids order by n").unwrap();
+    let before = {
+        let mut db = Database::open(config.clone()).unwrap();
+        db.execute("create table ids (n int, id uuid default gen_uuid())")
+            .unwrap();
+        db.execute("create table items (n int)").unwrap();
+        db.execute(
+            "create trigger log_item after insert on items begin insert into ids values (new.n) end",
+        )
+        .unwrap();
+        db.execute("insert into ids values (1, gen_uuid())")
+            .unwrap();
+        db.execute("insert into ids values (2)").unwrap();
+        db.execute("begin").unwrap();
+        db.execute("insert into items values (3)").unwrap();
+        db.execute("commit").unwrap();
+        assert_eq!(wal_status(&mut db).1, 3);
+        read_ids(&mut db)
+    };
+
+    let mut db = Database::open(config).unwrap();
+    assert_eq!(read_ids(&mut db), before);
+    assert_eq!(
+        db.execute_legacy("select count(distinct id) from ids")
+            .unwrap(),
+        "count(distinct id)\n3"
+    );
+}
+
+#[test]
+fn wal_bytes_auto_checkpoint_truncates_once_the_wal_is_large_enough() {
+    let path = temp_dir("wal_bytes_checkpoint");
+    let config = DbConfig::new(path.clone()).with_auto_checkpoint(AutoCheckpoint::WalBytes(300));
+    let mut db = Database::open(config).unwrap();
+    db.execute("create table users (id int, name text)")
+        .unwrap();
+
+    db.execute(r#"insert into users values (1, "ram")"#)
+        .unwrap();
+    let (bytes, pending) = wal_status(&mut db);
+    assert!(bytes > 0 && bytes < 300, "{bytes}");
+    assert_eq!(pending, 1);
+
+    let mut checkpointed = false;
+    for i in 2..20 {
+        db.execute(&format!(r#"insert into users values ({i}, "ram")"#))
+            .unwrap();
+        let (bytes, pending) = wal_status(&mut db);
+        assert!(bytes < 300, "{bytes}");
+        if pending == 0 {
+            checkpointed = true;
+            break;
+        }
+    }
+    assert!(checkpointed);
+}
+
+#[test]
+fn checkpoint_statement_is_rejected_inside_a_transaction() {
+    let path = temp_dir("wal_checkpoint_in_tx");
+    let mut db = Database::open(DbConfig::new(path.clone())).unwrap();
+    db.execute("begin").unwrap();
+    let err = db.execute("checkpoint").unwrap_err().to_string();
+    assert!(
+        err.contains("CHECKPOINT cannot run inside an active transaction"),
+        "{err}"
+    );
+    db.execute("rollback").unwrap();
+    match db.execute("checkpoint").unwrap() {
+        QueryResult::Transaction { message, .. } => {
+            assert_eq!(message, "checkpointed 0 table(s), truncated 0 B of WAL")
+        }
+        other => panic!("expected transaction result, got {other:?}"),
+    }
+}